@@ -0,0 +1,214 @@
+use std::{
+    collections::BTreeMap,
+    io::Cursor,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::Context;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Html,
+    routing::{get, post},
+    Json, Router,
+};
+use cfn_guard::{
+    commands::{validate::OutputFormatType, Executable},
+    utils::{
+        reader::{ReadBuffer, Reader},
+        writer::{WriteBuffer, Writer},
+    },
+    CommandBuilder, CompiledRules, ParseTreeBuilder, ValidateInput,
+};
+use serde::{Deserialize, Serialize};
+
+const INDEX: &str = include_str!("../static/index.html");
+
+/// the names the rules passed with a validation and the document are reported under
+const INLINE_RULES: &str = "rules";
+const DATA: &str = "data";
+
+/// a rule set registered with the playground, compiled once when it gets registered and
+/// evaluated from the compiled rules by every validation referring to it
+#[derive(Clone, Serialize)]
+pub struct RuleSet {
+    pub rules: String,
+    pub parse_tree: serde_json::Value,
+    #[serde(skip)]
+    pub compiled: Arc<CompiledRules>,
+}
+
+/// the registered rule sets, keyed by name
+type RuleSets = Arc<RwLock<BTreeMap<String, RuleSet>>>;
+
+type ApiError = (StatusCode, String);
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateRequest {
+    /// rules to evaluate, takes precedence over `rule_set`
+    pub rules: Option<String>,
+    /// name of a registered rule set to evaluate
+    pub rule_set: Option<String>,
+    /// JSON or YAML document the rules are evaluated against
+    pub data: String,
+    /// one of json (default), yaml, junit or sarif
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidateResponse {
+    pub exit_code: i32,
+    pub report: String,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let addr: SocketAddr = std::env::var("PLAYGROUND_ADDR")
+        .unwrap_or_else(|_| String::from("127.0.0.1:3000"))
+        .parse()
+        .context("PLAYGROUND_ADDR is not a valid socket address")?;
+
+    println!("cfn-guard playground listening on http://{addr}");
+    axum::Server::bind(&addr)
+        .serve(app().into_make_service())
+        .await
+        .context("playground server failed")
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/", get(|| async { Html(INDEX) }))
+        .route("/rules", get(list_rule_sets))
+        .route("/rules/:name", get(get_rule_set).put(register_rule_set))
+        .route("/validate", post(validate))
+        .with_state(RuleSets::default())
+}
+
+async fn list_rule_sets(State(rule_sets): State<RuleSets>) -> Json<Vec<String>> {
+    Json(rule_sets.read().unwrap().keys().cloned().collect())
+}
+
+async fn get_rule_set(
+    State(rule_sets): State<RuleSets>,
+    Path(name): Path<String>,
+) -> Result<Json<RuleSet>, ApiError> {
+    rule_sets
+        .read()
+        .unwrap()
+        .get(&name)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no rule set named {name}")))
+}
+
+async fn register_rule_set(
+    State(rule_sets): State<RuleSets>,
+    Path(name): Path<String>,
+    rules: String,
+) -> Result<Json<RuleSet>, ApiError> {
+    let compiled = Arc::new(compile(&name, &rules).map_err(bad_request)?);
+    let parse_tree = parse_tree(&rules).map_err(bad_request)?;
+    let rule_set = RuleSet {
+        rules,
+        parse_tree,
+        compiled,
+    };
+
+    rule_sets.write().unwrap().insert(name, rule_set.clone());
+
+    Ok(Json(rule_set))
+}
+
+async fn validate(
+    State(rule_sets): State<RuleSets>,
+    Json(request): Json<ValidateRequest>,
+) -> Result<Json<ValidateResponse>, ApiError> {
+    let compiled = match (request.rules, request.rule_set) {
+        // parse errors are surfaced to the caller instead of ending up in the report
+        (Some(rules), _) => Arc::new(compile(INLINE_RULES, &rules).map_err(bad_request)?),
+        (None, Some(name)) => rule_sets
+            .read()
+            .unwrap()
+            .get(&name)
+            .map(|rule_set| Arc::clone(&rule_set.compiled))
+            .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no rule set named {name}")))?,
+        (None, None) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                String::from("either rules or rule_set must be provided"),
+            ))
+        }
+    };
+
+    let format = match request.format.as_deref() {
+        None | Some("json") => OutputFormatType::JSON,
+        Some("yaml") => OutputFormatType::YAML,
+        Some("junit") => OutputFormatType::Junit,
+        Some("sarif") => OutputFormatType::Sarif,
+        Some(format) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("unsupported format {format}, expected json, yaml, junit or sarif"),
+            ))
+        }
+    };
+
+    evaluate(&compiled, &request.data, format)
+        .map(Json)
+        .map_err(bad_request)
+}
+
+fn bad_request(error: anyhow::Error) -> ApiError {
+    (StatusCode::BAD_REQUEST, format!("{error:#}"))
+}
+
+fn reader(content: &str) -> Reader {
+    Reader::new(ReadBuffer::Cursor(Cursor::new(Vec::from(
+        content.as_bytes(),
+    ))))
+}
+
+/// parses the rules the same way `cfn-guard parse-tree` does, returning the parse tree as JSON
+fn parse_tree(rules: &str) -> anyhow::Result<serde_json::Value> {
+    let mut writer = Writer::new(WriteBuffer::Vec(vec![]))?;
+
+    ParseTreeBuilder::default()
+        .print_json(true)
+        .try_build()
+        .context("failed to build parse-tree command")?
+        .execute(&mut writer, &mut reader(rules))
+        .context("failed to parse rules")?;
+
+    let content = writer.stripped().context("failed to read from writer")?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// parses the rules once, to be evaluated against any number of documents
+fn compile(name: &str, rules: &str) -> anyhow::Result<CompiledRules> {
+    CompiledRules::compile(ValidateInput {
+        content: rules,
+        file_name: name,
+    })
+    .context("failed to parse rules")
+}
+
+/// evaluates the compiled rules against the data, producing the structured report in the given
+/// format
+fn evaluate(
+    rules: &CompiledRules,
+    data: &str,
+    format: OutputFormatType,
+) -> anyhow::Result<ValidateResponse> {
+    let (exit_code, report) = rules
+        .validate_structured(
+            ValidateInput {
+                content: data,
+                file_name: DATA,
+            },
+            format,
+        )
+        .context("failed to evaluate rules")?;
+
+    Ok(ValidateResponse { exit_code, report })
+}
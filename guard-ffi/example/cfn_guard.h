@@ -1,6 +1,9 @@
 #ifndef CFN_GUARD_H
 #define CFN_GUARD_H
 
+#include <stddef.h>
+#include <stdint.h>
+
 typedef struct {
   int32_t code;
   char *message;
@@ -11,7 +14,25 @@ typedef struct {
   char *file_name;
 } validate_input_t;
 
+typedef struct {
+  uint8_t output_format;
+  uint8_t fail_on;
+  uint8_t show_summary;
+  _Bool verbose;
+} guard_options_t;
+
 char* cfn_guard_run_checks(validate_input_t template, validate_input_t rules, _Bool verbose, extern_err_t * err);
+char* cfn_guard_validate(validate_input_t template, validate_input_t rules, guard_options_t options, int32_t *exit_code, extern_err_t * err);
+char* cfn_guard_run_tests(const char *rules_file, const char *test_data_file, guard_options_t options, int32_t *exit_code, extern_err_t * err);
+char* cfn_guard_parse_rules(validate_input_t rules, extern_err_t * err);
+
+typedef struct guard_compiled_rules guard_compiled_rules_t;
+guard_compiled_rules_t* cfn_guard_compile_rules(validate_input_t rules, extern_err_t * err);
+char* cfn_guard_run_compiled_checks(const guard_compiled_rules_t *rules, validate_input_t template, _Bool verbose, extern_err_t * err);
+void cfn_guard_free_compiled_rules(guard_compiled_rules_t *rules);
+
+typedef int32_t (*guard_result_callback_t)(void *context, const char *rules_file, const char *data_file, const char *result, const char *error);
+int32_t cfn_guard_run_checks_streaming(const validate_input_t *templates, size_t templates_len, const validate_input_t *rules, size_t rules_len, _Bool verbose, guard_result_callback_t callback, void *context, extern_err_t * err);
 void cfn_guard_free_string(char *);
 
 #endif
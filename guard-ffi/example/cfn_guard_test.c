@@ -2,6 +2,17 @@
 #include <stdlib.h>
 #include "cfn_guard.h"
 
+int32_t print_result(void *context, const char *rules_file, const char *data_file, const char *result, const char *error) {
+  int *count = (int *)context;
+  *count += 1;
+  if (result != NULL) {
+    printf("%s against %s:\n%s\n", rules_file, data_file, result);
+  } else {
+    printf("%s against %s failed: %s\n", rules_file, data_file, error);
+  }
+  return 0;
+}
+
 int main() {
   extern_err_t err;
   validate_input_t data, rules;
@@ -19,5 +30,62 @@ int main() {
     cfn_guard_free_string(err.message);
     cfn_guard_free_string(result);
   }
+
+  guard_options_t options = {0};
+  options.output_format = 1;
+  int32_t exit_code = 0;
+  result = cfn_guard_validate(data, rules, options, &exit_code, &err);
+  if (err.code == 0) {
+    printf("exit code %i\n%s", exit_code, result);
+    cfn_guard_free_string(result);
+    cfn_guard_free_string(err.message);
+  } else {
+    printf("error: %i (%s)\n", err.code, err.message);
+    cfn_guard_free_string(err.message);
+    cfn_guard_free_string(result);
+  }
+
+  result = cfn_guard_parse_rules(rules, &err);
+  if (err.code == 0) {
+    printf("%s", result);
+    cfn_guard_free_string(result);
+    cfn_guard_free_string(err.message);
+  } else {
+    printf("error: %i (%s)\n", err.code, err.message);
+    cfn_guard_free_string(err.message);
+    cfn_guard_free_string(result);
+  }
+
+  guard_compiled_rules_t *compiled = cfn_guard_compile_rules(rules, &err);
+  if (err.code == 0) {
+    for (int i = 0; i < 2; i++) {
+      result = cfn_guard_run_compiled_checks(compiled, data, 0, &err);
+      if (err.code == 0) {
+        printf("%s", result);
+      } else {
+        printf("error: %i (%s)\n", err.code, err.message);
+      }
+      cfn_guard_free_string(result);
+      cfn_guard_free_string(err.message);
+    }
+    cfn_guard_free_compiled_rules(compiled);
+  } else {
+    printf("error: %i (%s)\n", err.code, err.message);
+    cfn_guard_free_string(err.message);
+  }
+
+  int count = 0;
+  validate_input_t templates[] = { data, data };
+  int32_t evaluated = cfn_guard_run_checks_streaming(templates, 2, &rules, 1, 0, print_result, &count, &err);
+  if (err.code == 0) {
+    printf("evaluated %i pairs, %i results\n", evaluated, count);
+  } else {
+    printf("error: %i (%s)\n", err.code, err.message);
+  }
+  cfn_guard_free_string(err.message);
+
+  cfn_guard_run_checks_streaming(templates, 2, &rules, 1, 0, NULL, &count, &err);
+  printf("NULL callback: error: %i (%s)\n", err.code, err.message);
+  cfn_guard_free_string(err.message);
   return 0;
 }
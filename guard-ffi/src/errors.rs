@@ -15,7 +15,7 @@ fn get_code(e: &Error) -> ErrorCode {
         Error::YamlError(_err) => 2,
         Error::FormatError(_fmt) => 3,
         Error::IoError(_io) => 4,
-        Error::ParseError(_err) => 5,
+        Error::ParseError(_err) | Error::RulesParseError { .. } => 5,
         Error::RegexError(_err) => 6,
         Error::MissingProperty(_err) => 7,
         Error::MissingVariable(_err) => 8,
@@ -32,6 +32,7 @@ fn get_code(e: &Error) -> ErrorCode {
         //NOTE: skipping 19 since we already use that for something and dont want to confuse users
         //that use both the regular cli, and the ffi
         Error::XMLError(_) => 20,
+        Error::SignatureError(_) => 21,
         Error::InternalError(_) => unreachable!(),
     };
     ErrorCode::new(code)
@@ -1,12 +1,20 @@
-use cfn_guard::run_checks;
-use ffi_support::ExternError;
-use std::os::raw::c_char;
+use cfn_guard::commands::Executable;
+use cfn_guard::utils::reader::{ReadBuffer, Reader};
+use cfn_guard::utils::writer::{WriteBuffer, Writer};
+use cfn_guard::{
+    run_checks, CommandBuilder, CompiledRules, ParseTreeBuilder, TestBuilder, ValidateBuilder,
+};
+use ffi_support::{ExternError, FfiStr};
+use std::ffi::CString;
+use std::io::Cursor;
+use std::os::raw::{c_char, c_void};
+use std::panic::AssertUnwindSafe;
 
 mod errors;
 mod types;
 
 use errors::FfiError;
-use types::FfiValidateInput;
+use types::{FfiCompiledRules, FfiOptions, FfiValidateInput};
 
 /**
  * C prototype for this function:
@@ -44,4 +52,264 @@ pub extern "C" fn cfn_guard_run_checks<'a>(
     })
 }
 
+/**
+ * C prototype for this function:
+ * typedef struct {
+ *   uint8_t output_format; // 0 single-line-summary, 1 json, 2 yaml, 3 junit, 4 sarif, 5 csv, 6 tsv
+ *   uint8_t fail_on;       // 0 violations, 1 errors, 2 warnings, 3 none
+ *   uint8_t show_summary;  // 0 fail, 1 all, 2 pass, 3 skip, 4 none
+ *   _Bool verbose;
+ * } guard_options_t;
+ *
+ * char* cfn_guard_validate(validate_input_t template, validate_input_t rules, guard_options_t options, int32_t *exit_code, extern_err_t *err);
+ *
+ * validates the template against the rules like the validate command, and returns its output. A
+ * zeroed `guard_options_t` holds the defaults of the CLI. `exit_code` is set to the exit code of
+ * the command. The result and `err` are released as for `cfn_guard_run_checks`.
+ */
+#[no_mangle]
+pub extern "C" fn cfn_guard_validate<'a>(
+    data: FfiValidateInput<'a>,
+    rules: FfiValidateInput<'a>,
+    options: FfiOptions,
+    exit_code: &mut i32,
+    err: &mut ExternError,
+) -> *mut c_char {
+    ffi_support::call_with_result(
+        err,
+        AssertUnwindSafe(|| -> Result<String, FfiError> {
+            let payload = serde_json::json!({
+                "rules": [rules.payload_document()],
+                "data": [data.payload_document()],
+            });
+            let validate = ValidateBuilder::default()
+                .payload(true)
+                .output_format(options.output_format()?)
+                .fail_on(options.fail_on()?)
+                .show_summary(vec![options.show_summary()?])
+                .verbose(options.verbose())
+                .try_build()?;
+
+            execute(validate, payload.to_string(), exit_code)
+        }),
+    )
+}
+
+/**
+ * C prototype for this function:
+ * char* cfn_guard_run_tests(const char *rules_file, const char *test_data_file, guard_options_t options, int32_t *exit_code, extern_err_t *err);
+ *
+ * runs the test cases of the test data file against the rules file like the test command, and
+ * returns its output. `fail_on` and `show_summary` of the options do not apply to tests.
+ * `exit_code` is set to the exit code of the command. The result and `err` are released as for
+ * `cfn_guard_run_checks`.
+ */
+#[no_mangle]
+pub extern "C" fn cfn_guard_run_tests(
+    rules_file: FfiStr<'_>,
+    test_data_file: FfiStr<'_>,
+    options: FfiOptions,
+    exit_code: &mut i32,
+    err: &mut ExternError,
+) -> *mut c_char {
+    ffi_support::call_with_result(
+        err,
+        AssertUnwindSafe(|| -> Result<String, FfiError> {
+            let test = TestBuilder::default()
+                .rules(rules_file.into_opt_string())
+                .test_data(test_data_file.into_opt_string())
+                .output_format(options.output_format()?)
+                .verbose(options.verbose())
+                .try_build()?;
+
+            execute(test, String::new(), exit_code)
+        }),
+    )
+}
+
+/**
+ * C prototype for this function:
+ * char* cfn_guard_parse_rules(validate_input_t rules, extern_err_t *err);
+ *
+ * returns the parse tree of the rules as json. The result and `err` are released as for
+ * `cfn_guard_run_checks`.
+ */
+#[no_mangle]
+pub extern "C" fn cfn_guard_parse_rules(
+    rules: FfiValidateInput<'_>,
+    err: &mut ExternError,
+) -> *mut c_char {
+    ffi_support::call_with_result(err, || -> Result<String, FfiError> {
+        let parse_tree = ParseTreeBuilder::default().print_json(true).try_build()?;
+
+        execute(parse_tree, rules.data.into_string(), &mut 0)
+    })
+}
+
+/**
+ * C prototype for this function:
+ * typedef struct guard_compiled_rules guard_compiled_rules_t;
+ *
+ * guard_compiled_rules_t* cfn_guard_compile_rules(validate_input_t rules, extern_err_t *err);
+ * char* cfn_guard_run_compiled_checks(const guard_compiled_rules_t *rules, validate_input_t template, _Bool verbose, extern_err_t *err);
+ * void cfn_guard_free_compiled_rules(guard_compiled_rules_t *rules);
+ *
+ * parses the rules once, into a handle that `cfn_guard_run_compiled_checks` evaluates against
+ * any number of templates, from any number of threads at the same time. The handle is NULL when
+ * the rules fail to parse, with the reason in `err`, and must be passed to
+ * `cfn_guard_free_compiled_rules` once no thread uses it anymore.
+ */
+#[no_mangle]
+pub extern "C" fn cfn_guard_compile_rules(
+    rules: FfiValidateInput<'_>,
+    err: &mut ExternError,
+) -> *mut FfiCompiledRules {
+    ffi_support::call_with_result(err, || -> Result<FfiCompiledRules, FfiError> {
+        Ok(FfiCompiledRules(CompiledRules::compile(rules.into())?))
+    })
+}
+
+/**
+ * evaluates the compiled rules against the template, the result is the one of
+ * `cfn_guard_run_checks` and is released the same way.
+ *
+ * # Safety
+ *
+ * `rules` must be a handle returned by `cfn_guard_compile_rules` that was not freed yet.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn cfn_guard_run_compiled_checks(
+    rules: *const FfiCompiledRules,
+    data: FfiValidateInput<'_>,
+    verbose: c_char,
+    err: &mut ExternError,
+) -> *mut c_char {
+    let rules = rules.as_ref();
+    ffi_support::call_with_result(
+        err,
+        AssertUnwindSafe(|| -> Result<String, FfiError> {
+            match rules {
+                Some(FfiCompiledRules(rules)) => Ok(rules.validate(data.into(), verbose == 1)?),
+                None => Err(FfiError(cfn_guard::Error::IllegalArguments(String::from(
+                    "the compiled rules handle is NULL",
+                )))),
+            }
+        }),
+    )
+}
+
+ffi_support::define_box_destructor!(FfiCompiledRules, cfn_guard_free_compiled_rules);
+
+/// called with the result of each rules file evaluated against each data file, see
+/// `cfn_guard_run_checks_streaming`
+pub type FfiResultCallback = extern "C" fn(
+    context: *mut c_void,
+    rules_file: *const c_char,
+    data_file: *const c_char,
+    result: *const c_char,
+    error: *const c_char,
+) -> i32;
+
+/**
+ * C prototype for this function:
+ * typedef int32_t (*guard_result_callback_t)(void *context, const char *rules_file, const char *data_file, const char *result, const char *error);
+ *
+ * int32_t cfn_guard_run_checks_streaming(const validate_input_t *templates, size_t templates_len, const validate_input_t *rules, size_t rules_len, _Bool verbose, guard_result_callback_t callback, void *context, extern_err_t *err);
+ *
+ * evaluates every rules file against every template, and calls `callback` with the result of each
+ * pair as soon as it is evaluated, so that the results of large runs do not have to be held in
+ * memory at once. `result` is the json string `cfn_guard_run_checks` would return, or NULL when
+ * the pair could not be evaluated and `error` holds why. The strings are owned by rust and only
+ * valid until the callback returns. `context` is passed through to the callback untouched.
+ *
+ * The callback returns 0 to continue with the next pair, any other value stops the run. Returns
+ * the number of pairs evaluated. A NULL `callback` is an error, reported in `err` before any pair
+ * is evaluated.
+ *
+ * # Safety
+ *
+ * `templates` and `rules` must point to `templates_len` and `rules_len` valid inputs
+ * respectively, or be NULL when their length is 0.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn cfn_guard_run_checks_streaming(
+    data: *const FfiValidateInput<'_>,
+    data_len: usize,
+    rules: *const FfiValidateInput<'_>,
+    rules_len: usize,
+    verbose: c_char,
+    callback: Option<FfiResultCallback>,
+    context: *mut c_void,
+    err: &mut ExternError,
+) -> i32 {
+    let data = inputs(data, data_len);
+    let rules = inputs(rules, rules_len);
+
+    ffi_support::call_with_result(
+        err,
+        AssertUnwindSafe(|| -> Result<i32, FfiError> {
+            let callback = callback.ok_or_else(|| {
+                FfiError(cfn_guard::Error::IllegalArguments(String::from(
+                    "the result callback is NULL",
+                )))
+            })?;
+            let mut evaluated = 0;
+            for rules in rules {
+                for data in data {
+                    let (result, error) = match run_checks(data.into(), rules.into(), verbose == 1)
+                    {
+                        Ok(result) => (Some(c_string(result)), None),
+                        Err(e) => (None, Some(c_string(e.to_string()))),
+                    };
+                    let rules_file = c_string(rules.file_name.as_str().to_string());
+                    let data_file = c_string(data.file_name.as_str().to_string());
+                    evaluated += 1;
+
+                    let status = callback(
+                        context,
+                        rules_file.as_ptr(),
+                        data_file.as_ptr(),
+                        result.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+                        error.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+                    );
+                    if status != 0 {
+                        return Ok(evaluated);
+                    }
+                }
+            }
+
+            Ok(evaluated)
+        }),
+    )
+}
+
+unsafe fn inputs<'a>(
+    inputs: *const FfiValidateInput<'a>,
+    len: usize,
+) -> &'a [FfiValidateInput<'a>] {
+    match inputs.is_null() {
+        true => &[],
+        false => std::slice::from_raw_parts(inputs, len),
+    }
+}
+
+/// strings handed to the host cannot hold a nul byte, which only a malformed result could have
+fn c_string(s: String) -> CString {
+    CString::new(s.replace('\0', "")).unwrap_or_default()
+}
+
+/// runs the command with the input to read from, and returns what it wrote
+fn execute<T: Executable>(
+    command: T,
+    input: String,
+    exit_code: &mut i32,
+) -> Result<String, FfiError> {
+    let mut reader = Reader::new(ReadBuffer::Cursor(Cursor::new(input.into_bytes())));
+    let mut writer = Writer::new(WriteBuffer::Vec(vec![]))?;
+
+    *exit_code = command.execute(&mut writer, &mut reader)?;
+
+    Ok(writer.into_string()?)
+}
+
 ffi_support::define_string_destructor!(cfn_guard_free_string);
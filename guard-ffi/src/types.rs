@@ -1,5 +1,7 @@
-use cfn_guard::ValidateInput;
+use cfn_guard::commands::validate::{FailOn, OutputFormatType, ShowSummaryType};
+use cfn_guard::{CompiledRules, Error, ValidateInput};
 use ffi_support::FfiStr;
+use std::os::raw::c_char;
 
 #[repr(C)]
 pub struct FfiValidateInput<'a> {
@@ -15,3 +17,85 @@ impl<'a> From<FfiValidateInput<'a>> for ValidateInput<'a> {
         }
     }
 }
+
+impl<'a> From<&FfiValidateInput<'a>> for ValidateInput<'a> {
+    fn from(input: &FfiValidateInput<'a>) -> Self {
+        ValidateInput {
+            content: input.data.as_str(),
+            file_name: input.file_name.as_str(),
+        }
+    }
+}
+
+impl<'a> FfiValidateInput<'a> {
+    /// the named document of a validate payload
+    pub fn payload_document(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.file_name.as_str(),
+            "content": self.data.as_str(),
+        })
+    }
+}
+
+/// the opaque handle to rules compiled by `cfn_guard_compile_rules`
+pub struct FfiCompiledRules(pub CompiledRules);
+
+ffi_support::implement_into_ffi_by_pointer!(FfiCompiledRules);
+
+/// the options of the validate and test commands, a zeroed struct holds the defaults of the CLI
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FfiOptions {
+    /// 0 single-line-summary, 1 json, 2 yaml, 3 junit, 4 sarif, 5 csv, 6 tsv
+    pub output_format: u8,
+    /// which outcomes of validate give a non-zero exit code: 0 violations, 1 errors, 2 warnings,
+    /// 3 none
+    pub fail_on: u8,
+    /// the summary validate prints: 0 fail, 1 all, 2 pass, 3 skip, 4 none
+    pub show_summary: u8,
+    pub verbose: c_char,
+}
+
+impl FfiOptions {
+    pub fn output_format(&self) -> Result<OutputFormatType, Error> {
+        match self.output_format {
+            0 => Ok(OutputFormatType::SingleLineSummary),
+            1 => Ok(OutputFormatType::JSON),
+            2 => Ok(OutputFormatType::YAML),
+            3 => Ok(OutputFormatType::Junit),
+            4 => Ok(OutputFormatType::Sarif),
+            5 => Ok(OutputFormatType::Csv),
+            6 => Ok(OutputFormatType::Tsv),
+            other => Err(illegal_option("output_format", other)),
+        }
+    }
+
+    pub fn fail_on(&self) -> Result<FailOn, Error> {
+        match self.fail_on {
+            0 => Ok(FailOn::Violations),
+            1 => Ok(FailOn::Errors),
+            2 => Ok(FailOn::Warnings),
+            3 => Ok(FailOn::None),
+            other => Err(illegal_option("fail_on", other)),
+        }
+    }
+
+    pub fn show_summary(&self) -> Result<ShowSummaryType, Error> {
+        match self.show_summary {
+            0 => Ok(ShowSummaryType::Fail),
+            1 => Ok(ShowSummaryType::All),
+            2 => Ok(ShowSummaryType::Pass),
+            3 => Ok(ShowSummaryType::Skip),
+            4 => Ok(ShowSummaryType::None),
+            other => Err(illegal_option("show_summary", other)),
+        }
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose == 1
+    }
+}
+
+fn illegal_option(option: &str, value: u8) -> Error {
+    Error::IllegalArguments(format!("{value} is not a valid value for {option}"))
+}
@@ -0,0 +1,11 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// any input must parse into rules or fail with an error, never panic or overflow the stack
+fuzz_target!(|data: &[u8]| {
+    let _ = cfn_guard::parse_rules(data);
+});
@@ -0,0 +1,37 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+
+use cfn_guard::{run_checks, ValidateInput};
+use libfuzzer_sys::fuzz_target;
+
+const DATA: &str = r#"{
+    "Resources": {
+        "Bucket": {
+            "Type": "AWS::S3::Bucket",
+            "Properties": {
+                "BucketName": "logs",
+                "Tags": [{ "Key": "owner", "Value": "infra" }],
+                "VersioningConfiguration": { "Status": "Enabled" }
+            }
+        }
+    }
+}"#;
+
+// rules that parse are evaluated against a small template, the evaluation must not panic either
+fuzz_target!(|data: &[u8]| {
+    if let Ok(rules) = std::str::from_utf8(data) {
+        let _ = run_checks(
+            ValidateInput {
+                content: DATA,
+                file_name: "template.json",
+            },
+            ValidateInput {
+                content: rules,
+                file_name: "fuzz.guard",
+            },
+            false,
+        );
+    }
+});
@@ -0,0 +1,34 @@
+package software.amazon.cfnguard;
+
+/**
+ * Bindings of the cfn-guard library, loaded from the cfn_guard_jni native library. Every method
+ * throws {@link CfnGuardException} when the rules or data cannot be evaluated.
+ */
+public final class CfnGuard {
+    static {
+        System.loadLibrary("cfn_guard_jni");
+    }
+
+    private CfnGuard() {}
+
+    /** Evaluates the rules against the data, and returns the json report. */
+    public static native String runChecks(
+            String data, String dataFileName, String rules, String rulesFileName, boolean verbose);
+
+    /** Evaluates the rules against the data like {@code validate --structured --output-format json}. */
+    public static native String validate(
+            String data, String dataFileName, String rules, String rulesFileName);
+
+    /**
+     * Parses the rules once, into a handle {@link #runCompiledChecks} evaluates against any number
+     * of data files from any number of threads. The handle must be passed to {@link
+     * #freeCompiledRules} once no thread uses it anymore.
+     */
+    public static native long compileRules(String rules, String rulesFileName);
+
+    /** Evaluates compiled rules against the data, and returns the report {@link #runChecks} would. */
+    public static native String runCompiledChecks(
+            long handle, String data, String dataFileName, boolean verbose);
+
+    public static native void freeCompiledRules(long handle);
+}
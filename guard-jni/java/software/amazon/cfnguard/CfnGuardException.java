@@ -0,0 +1,8 @@
+package software.amazon.cfnguard;
+
+/** Thrown by the native methods of {@link CfnGuard} when the rules or data cannot be evaluated. */
+public class CfnGuardException extends RuntimeException {
+    public CfnGuardException(String message) {
+        super(message);
+    }
+}
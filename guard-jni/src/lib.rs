@@ -0,0 +1,217 @@
+use std::any::Any;
+use std::io::Cursor;
+use std::panic::AssertUnwindSafe;
+
+use cfn_guard::commands::validate::{OutputFormatType, ShowSummaryType};
+use cfn_guard::commands::Executable;
+use cfn_guard::utils::reader::{ReadBuffer, Reader};
+use cfn_guard::utils::writer::{WriteBuffer, Writer};
+use cfn_guard::{run_checks, CommandBuilder, CompiledRules, ValidateBuilder, ValidateInput};
+use jni::objects::{JClass, JString};
+use jni::sys::{jboolean, jlong, jstring, JNI_TRUE};
+use jni::JNIEnv;
+
+/// thrown for every error, and for panics, so that none of them crosses into the JVM
+const EXCEPTION_CLASS: &str = "software/amazon/cfnguard/CfnGuardException";
+
+/**
+ * Java signature:
+ * static native String runChecks(String data, String dataFileName, String rules, String rulesFileName, boolean verbose);
+ *
+ * evaluates the rules against the data, and returns the json report `run_checks` returns.
+ */
+#[no_mangle]
+pub extern "system" fn Java_software_amazon_cfnguard_CfnGuard_runChecks<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    data: JString<'local>,
+    data_file_name: JString<'local>,
+    rules: JString<'local>,
+    rules_file_name: JString<'local>,
+    verbose: jboolean,
+) -> jstring {
+    call(&mut env, std::ptr::null_mut(), |env| {
+        let (data, data_file_name) = (string(env, &data)?, string(env, &data_file_name)?);
+        let (rules, rules_file_name) = (string(env, &rules)?, string(env, &rules_file_name)?);
+        let report = run_checks(
+            ValidateInput {
+                content: &data,
+                file_name: &data_file_name,
+            },
+            ValidateInput {
+                content: &rules,
+                file_name: &rules_file_name,
+            },
+            verbose == JNI_TRUE,
+        )
+        .map_err(|e| e.to_string())?;
+
+        new_string(env, report)
+    })
+}
+
+/**
+ * Java signature:
+ * static native String validate(String data, String dataFileName, String rules, String rulesFileName);
+ *
+ * evaluates the rules against the data like `validate --structured --output-format json`, and
+ * returns its output.
+ */
+#[no_mangle]
+pub extern "system" fn Java_software_amazon_cfnguard_CfnGuard_validate<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    data: JString<'local>,
+    data_file_name: JString<'local>,
+    rules: JString<'local>,
+    rules_file_name: JString<'local>,
+) -> jstring {
+    call(&mut env, std::ptr::null_mut(), |env| {
+        let payload = serde_json::json!({
+            "rules": [{ "name": string(env, &rules_file_name)?, "content": string(env, &rules)? }],
+            "data": [{ "name": string(env, &data_file_name)?, "content": string(env, &data)? }],
+        });
+        let validate = ValidateBuilder::default()
+            .payload(true)
+            .structured(true)
+            .output_format(OutputFormatType::JSON)
+            .show_summary(vec![ShowSummaryType::None])
+            .try_build()
+            .map_err(|e| e.to_string())?;
+
+        let mut reader = Reader::new(ReadBuffer::Cursor(Cursor::new(
+            payload.to_string().into_bytes(),
+        )));
+        let mut writer = Writer::new(WriteBuffer::Vec(vec![])).map_err(|e| e.to_string())?;
+        validate
+            .execute(&mut writer, &mut reader)
+            .map_err(|e| e.to_string())?;
+
+        new_string(env, writer.into_string().map_err(|e| e.to_string())?)
+    })
+}
+
+/**
+ * Java signature:
+ * static native long compileRules(String rules, String rulesFileName);
+ *
+ * parses the rules once, into a handle `runCompiledChecks` evaluates against any number of data
+ * files, from any number of threads at the same time. The handle must be passed to
+ * `freeCompiledRules` once no thread uses it anymore.
+ */
+#[no_mangle]
+pub extern "system" fn Java_software_amazon_cfnguard_CfnGuard_compileRules<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    rules: JString<'local>,
+    rules_file_name: JString<'local>,
+) -> jlong {
+    call(&mut env, 0, |env| {
+        let (rules, rules_file_name) = (string(env, &rules)?, string(env, &rules_file_name)?);
+        let compiled = CompiledRules::compile(ValidateInput {
+            content: &rules,
+            file_name: &rules_file_name,
+        })
+        .map_err(|e| e.to_string())?;
+
+        Ok(Box::into_raw(Box::new(compiled)) as jlong)
+    })
+}
+
+/**
+ * Java signature:
+ * static native String runCompiledChecks(long handle, String data, String dataFileName, boolean verbose);
+ *
+ * evaluates the compiled rules against the data, and returns the report `runChecks` would.
+ */
+#[no_mangle]
+pub extern "system" fn Java_software_amazon_cfnguard_CfnGuard_runCompiledChecks<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+    data: JString<'local>,
+    data_file_name: JString<'local>,
+    verbose: jboolean,
+) -> jstring {
+    call(&mut env, std::ptr::null_mut(), |env| {
+        // SAFETY: the handle was returned by compileRules, and the Java side does not free it
+        // while it is in use
+        let compiled = match unsafe { (handle as *const CompiledRules).as_ref() } {
+            Some(compiled) => compiled,
+            None => return Err(String::from("the compiled rules handle is not valid")),
+        };
+        let (data, data_file_name) = (string(env, &data)?, string(env, &data_file_name)?);
+        let report = compiled
+            .validate(
+                ValidateInput {
+                    content: &data,
+                    file_name: &data_file_name,
+                },
+                verbose == JNI_TRUE,
+            )
+            .map_err(|e| e.to_string())?;
+
+        new_string(env, report)
+    })
+}
+
+/**
+ * Java signature:
+ * static native void freeCompiledRules(long handle);
+ */
+#[no_mangle]
+pub extern "system" fn Java_software_amazon_cfnguard_CfnGuard_freeCompiledRules<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle: jlong,
+) {
+    if handle != 0 {
+        // SAFETY: the handle was returned by compileRules, and is freed once
+        drop(unsafe { Box::from_raw(handle as *mut CompiledRules) });
+    }
+}
+
+/// runs the body of a native method, and throws a `CfnGuardException` with the error or the
+/// panic it ended with, returning the default value to the JVM instead
+fn call<'local, T>(
+    env: &mut JNIEnv<'local>,
+    default: T,
+    body: impl FnOnce(&mut JNIEnv<'local>) -> Result<T, String>,
+) -> T {
+    let message = match std::panic::catch_unwind(AssertUnwindSafe(|| body(env))) {
+        Ok(Ok(value)) => return value,
+        Ok(Err(message)) => message,
+        Err(panic) => panic_message(panic),
+    };
+
+    // an exception the JVM already raised, e.g. reading an argument, is left to propagate
+    if !env.exception_check().unwrap_or(false) {
+        let _ = env.throw_new(EXCEPTION_CLASS, message);
+    }
+    default
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => String::from("cfn-guard panicked"),
+        },
+    }
+}
+
+fn string(env: &mut JNIEnv<'_>, value: &JString<'_>) -> Result<String, String> {
+    if value.is_null() {
+        return Err(String::from("arguments cannot be null"));
+    }
+    env.get_string(value)
+        .map(String::from)
+        .map_err(|e| e.to_string())
+}
+
+fn new_string(env: &mut JNIEnv<'_>, value: String) -> Result<jstring, String> {
+    env.new_string(value)
+        .map(|value| value.into_raw())
+        .map_err(|e| e.to_string())
+}
@@ -1,11 +1,47 @@
 // Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use cfn_guard::{run_checks, ValidateInput};
+use std::sync::{Arc, Mutex};
+
+use aws_sdk_s3::Client as S3Client;
+use cfn_guard::{run_checks, CompiledRules, ValidateInput};
 use lambda_runtime::{handler_fn, Context, Error};
 use log::{self, info, LevelFilter};
 use serde_derive::{Deserialize, Serialize};
 use simple_logger::SimpleLogger;
+use tokio::sync::OnceCell;
+
+/// created on cold start, and reused by the warm invocations
+static S3_CLIENT: OnceCell<S3Client> = OnceCell::const_new();
+
+/// the rules files downloaded for each `rulesS3Uri`, the most recently used last
+static S3_RULES: Mutex<Vec<(String, Arc<S3Rules>)>> = Mutex::new(Vec::new());
+
+/// how many `rulesS3Uri` the rules files are cached for, the least recently used are dropped first
+const MAX_CACHED_S3_URIS: usize = 16;
+
+/// the environment variable with the Ed25519 public key in PEM the rules packs must be signed for
+/// with `cfn-guard sign`. When set, only signed rules packs from S3 are evaluated
+const PUBLIC_KEY_VARIABLE: &str = "CFN_GUARD_PUBLIC_KEY";
+
+/// the rules files downloaded for a `rulesS3Uri`, parsed once and cached as they were parsed along
+/// with the ones of the rules packs built by `cfn-guard build`. The rules files that fail to parse
+/// are cached with their error, which is reported on every invocation. They are downloaded again
+/// once the key or the ETag of any of the objects changes
+#[derive(Default)]
+struct S3Rules {
+    /// the key and the ETag of each object the rules files were downloaded from
+    versions: Vec<(String, String)>,
+    compiled: Vec<CompiledRules>,
+    errors: Vec<(String, String)>,
+}
+
+/// a rules file to evaluate the data against
+enum Rules<'r> {
+    Source(&'r str),
+    Compiled(&'r CompiledRules),
+    Invalid(&'r str),
+}
 
 fn default_as_true() -> bool {
     true
@@ -15,15 +51,83 @@ fn default_as_true() -> bool {
 pub struct CustomEvent {
     #[serde(rename = "data")]
     pub data: String,
-    #[serde(rename = "rules")]
+    #[serde(rename = "rules", default)]
     pub rules: Vec<String>,
+    /// `s3://bucket/key` of a rules file, or `s3://bucket/prefix/` of the rules files under it
+    #[serde(
+        rename = "rulesS3Uri",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rules_s3_uri: Option<String>,
     #[serde(rename = "verbose", default = "default_as_true")] // for backward compatibility
     pub verbose: bool,
+    /// the version of the structured response the caller reads, `SCHEMA_VERSION`. Without it, the
+    /// report of each rules file is returned as it was before the structured response, which is
+    /// the only one `verbose` applies to
+    #[serde(
+        rename = "schemaVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub schema_version: Option<String>,
+}
+
+/// the version of the structured response, bumped on any change that is not backward compatible
+pub const SCHEMA_VERSION: &str = "1.0";
+
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum CustomOutput {
+    Structured(StructuredOutput),
+    Compatibility { message: Vec<serde_json::Value> },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredOutput {
+    pub schema_version: String,
+    /// `ERROR` when a rules file could not be evaluated, `FAIL` when a rule failed, `PASS` when
+    /// a rule passed and `SKIP` otherwise
+    pub status: String,
+    pub summary: Summary,
+    pub rules: Vec<RuleResult>,
+    pub errors: Vec<RulesFileError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub pass: usize,
+    pub fail: usize,
+    pub skip: usize,
+    pub error: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleResult {
+    pub rules_file: String,
+    pub name: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// the clause that failed
+    pub check: String,
+    /// the path of the value the clause failed on, empty when there is none
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
-#[derive(Serialize)]
-pub struct CustomOutput {
-    pub message: Vec<serde_json::Value>,
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesFileError {
+    pub rules_file: String,
+    pub message: String,
 }
 
 #[tokio::main]
@@ -41,28 +145,364 @@ async fn main() -> Result<(), Error> {
 pub async fn call_cfn_guard(e: CustomEvent, _c: Context) -> Result<CustomOutput, Error> {
     info!("Template is: [{}]", &e.data);
     info!("Rules are: [{:?}]", &e.rules);
-    let mut results_vec = Vec::new();
-    for rule in e.rules.iter() {
-        let result = match run_checks(
-            ValidateInput {
-                content: &e.data,
-                file_name: "lambda-payload",
-            },
+    if let Some(version) = e
+        .schema_version
+        .as_deref()
+        .filter(|version| *version != SCHEMA_VERSION)
+    {
+        return Err(format!(
+            "schemaVersion {version} is not supported, the supported version is {SCHEMA_VERSION}"
+        )
+        .into());
+    }
+    let public_key = std::env::var(PUBLIC_KEY_VARIABLE).ok();
+    if public_key.is_some() && !e.rules.is_empty() {
+        return Err(format!(
+            "rules are refused when {PUBLIC_KEY_VARIABLE} is set, pass signed rules packs with rulesS3Uri"
+        )
+        .into());
+    }
+    let s3_rules = match &e.rules_s3_uri {
+        Some(uri) => {
+            info!("Rules S3 URI is: [{}]", uri);
+            s3_rules(uri, public_key.as_deref()).await?
+        }
+        None => Arc::default(),
+    };
+
+    let rules = e
+        .rules
+        .iter()
+        .map(|rule| ("lambda-rule", Rules::Source(rule)))
+        .chain(
+            s3_rules
+                .compiled
+                .iter()
+                .map(|rules| (rules.file_name(), Rules::Compiled(rules))),
+        )
+        .chain(
+            s3_rules
+                .errors
+                .iter()
+                .map(|(key, error)| (key.as_str(), Rules::Invalid(error))),
+        );
+
+    if e.schema_version.is_none() {
+        let mut results_vec = Vec::new();
+        for (file_name, rule) in rules {
+            let result = match check(&e.data, file_name, rule, e.verbose) {
+                Ok(t) => t,
+                Err(e) => e,
+            };
+            let json_value: serde_json::Value = serde_json::from_str(&result)?;
+            results_vec.push(json_value)
+        }
+        return Ok(CustomOutput::Compatibility {
+            message: results_vec,
+        });
+    }
+
+    let mut output = StructuredOutput {
+        schema_version: SCHEMA_VERSION.to_string(),
+        ..Default::default()
+    };
+    for (file_name, rule) in rules {
+        match check(&e.data, file_name, rule, false) {
+            Ok(report) if report.is_empty() => {}
+            Ok(report) => output.add_report(file_name, &serde_json::from_str(&report)?),
+            Err(error) => output.errors.push(RulesFileError {
+                rules_file: file_name.to_string(),
+                message: error,
+            }),
+        }
+    }
+    output.summarize();
+
+    Ok(CustomOutput::Structured(output))
+}
+
+fn check(data: &str, rules_file: &str, rules: Rules<'_>, verbose: bool) -> Result<String, String> {
+    let data = ValidateInput {
+        content: data,
+        file_name: "lambda-payload",
+    };
+    match rules {
+        Rules::Source(rules) => run_checks(
+            data,
             ValidateInput {
-                content: rule,
-                file_name: "lambda-rule",
+                content: rules,
+                file_name: rules_file,
             },
-            e.verbose,
-        ) {
-            Ok(t) => t,
-            Err(e) => e.to_string(),
+            verbose,
+        ),
+        Rules::Compiled(rules) => rules.validate(data, verbose),
+        Rules::Invalid(error) => return Err(error.to_string()),
+    }
+    .map_err(|e| e.to_string())
+}
+
+impl StructuredOutput {
+    /// adds the rules of the report of a rules file, which lists the names of the rules that
+    /// passed or were skipped, and the failures of the others
+    pub fn add_report(&mut self, rules_file: &str, report: &serde_json::Value) {
+        for (key, status) in [("compliant", "PASS"), ("not_applicable", "SKIP")] {
+            let names = report.get(key).and_then(serde_json::Value::as_array);
+            for name in names
+                .into_iter()
+                .flatten()
+                .filter_map(serde_json::Value::as_str)
+            {
+                self.rules.push(RuleResult {
+                    rules_file: rules_file.to_string(),
+                    name: name.to_string(),
+                    status: status.to_string(),
+                    violations: vec![],
+                });
+            }
+        }
+
+        let failures = report
+            .get("not_compliant")
+            .and_then(serde_json::Value::as_array);
+        for failure in failures.into_iter().flatten() {
+            let rule = match failure.get("Rule") {
+                Some(rule) => rule,
+                None => continue,
+            };
+            let mut violations = Vec::new();
+            for check in rule
+                .get("checks")
+                .and_then(serde_json::Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                collect_violations(check, &mut violations);
+            }
+            self.rules.push(RuleResult {
+                rules_file: rules_file.to_string(),
+                name: string_at(rule, "/name"),
+                status: String::from("FAIL"),
+                violations,
+            });
+        }
+    }
+
+    fn summarize(&mut self) {
+        let count = |status: &str| self.rules.iter().filter(|r| r.status == status).count();
+        self.summary = Summary {
+            pass: count("PASS"),
+            fail: count("FAIL"),
+            skip: count("SKIP"),
+            error: self.errors.len(),
         };
-        let json_value: serde_json::Value = serde_json::from_str(&result)?;
-        results_vec.push(json_value)
+        self.status = String::from(match &self.summary {
+            Summary { error, .. } if *error > 0 => "ERROR",
+            Summary { fail, .. } if *fail > 0 => "FAIL",
+            Summary { pass, .. } if *pass > 0 => "PASS",
+            _ => "SKIP",
+        });
+    }
+}
+
+/// walks a failed check, `{"Block": ...}`, `{"Disjunctions": ...}`, `{"Clause": ...}` or a
+/// nested `{"Rule": ...}`, down to the clauses that failed
+fn collect_violations(check: &serde_json::Value, violations: &mut Vec<Violation>) {
+    let (kind, report) = match check.as_object().and_then(|check| check.iter().next()) {
+        Some(entry) => entry,
+        None => return,
+    };
+
+    let report = match kind.as_str() {
+        "Rule" | "Disjunctions" => {
+            for check in report
+                .get("checks")
+                .and_then(serde_json::Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                collect_violations(check, violations);
+            }
+            return;
+        }
+        "Block" => report,
+        "Clause" => match report.as_object().and_then(|clause| clause.values().next()) {
+            Some(report) => report,
+            None => return,
+        },
+        _ => return,
+    };
+
+    let check = report
+        .get("query")
+        .or_else(|| report.get("context"))
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    let path = [
+        "/check/Resolved/from/path",
+        "/check/Resolved/value/path",
+        "/check/InResolved/from/path",
+        "/check/UnResolved/value/traversed_to/path",
+        "/unresolved/traversed_to/path",
+    ]
+    .iter()
+    .find_map(|pointer| report.pointer(pointer).and_then(serde_json::Value::as_str))
+    .unwrap_or_default();
+    let message = ["/messages/custom_message", "/messages/error_message"]
+        .iter()
+        .find_map(|pointer| report.pointer(pointer).and_then(serde_json::Value::as_str))
+        .map(|message| message.trim().to_string());
+
+    violations.push(Violation {
+        check: check.trim().to_string(),
+        path: path.to_string(),
+        message,
+    });
+}
+
+fn string_at(value: &serde_json::Value, pointer: &str) -> String {
+    value
+        .pointer(pointer)
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// splits `s3://bucket/key` into the bucket and the key, which is empty or ends with `/` for a
+/// prefix
+pub fn parse_s3_uri(uri: &str) -> Result<(String, String), Error> {
+    let path = uri.strip_prefix("s3://").unwrap_or_default();
+    let (bucket, key) = path.split_once('/').unwrap_or((path, ""));
+    match bucket.is_empty() {
+        true => Err(format!(
+            "rulesS3Uri must be s3://bucket/key or s3://bucket/prefix/, got {uri}"
+        )
+        .into()),
+        false => Ok((bucket.to_string(), key.to_string())),
+    }
+}
+
+/// the rules files at the URI, downloaded on the first invocation asking for them and whenever
+/// the objects at the URI changed since. Given the public key, only rules packs are accepted and
+/// the signature next to each, `<key>.sig`, is checked
+async fn s3_rules(uri: &str, public_key: Option<&str>) -> Result<Arc<S3Rules>, Error> {
+    let (bucket, key) = parse_s3_uri(uri)?;
+    let client = S3_CLIENT
+        .get_or_init(|| async { S3Client::new(&aws_config::load_from_env().await) })
+        .await;
+
+    let versions = match key.is_empty() || key.ends_with('/') {
+        true => {
+            let mut versions = Vec::new();
+            let mut pages = client
+                .list_objects_v2()
+                .bucket(&bucket)
+                .prefix(&key)
+                .into_paginator()
+                .send();
+            while let Some(page) = pages.next().await {
+                versions.extend(
+                    page?
+                        .contents()
+                        .iter()
+                        .filter_map(|object| {
+                            let e_tag = object.e_tag().unwrap_or_default();
+                            Some((object.key()?.to_string(), e_tag.to_string()))
+                        })
+                        .filter(|(key, _)| {
+                            key.ends_with(".guard")
+                                || key.ends_with(".ruleset")
+                                || key.ends_with(".pack")
+                        }),
+                );
+            }
+            versions.sort();
+            versions
+        }
+        false => {
+            let object = client
+                .head_object()
+                .bucket(&bucket)
+                .key(&key)
+                .send()
+                .await?;
+            let e_tag = object.e_tag().unwrap_or_default().to_string();
+            vec![(key, e_tag)]
+        }
+    };
+
+    let cached = {
+        let mut cache = S3_RULES.lock().map_err(|e| e.to_string())?;
+        match cache.iter().position(|(cached, _)| cached == uri) {
+            Some(index) => {
+                let entry = cache.remove(index);
+                let rules = Arc::clone(&entry.1);
+                cache.push(entry);
+                Some(rules).filter(|rules| rules.versions == versions)
+            }
+            None => None,
+        }
+    };
+    if let Some(rules) = cached {
+        return Ok(rules);
+    }
+
+    let mut rules = S3Rules::default();
+    for (key, _) in &versions {
+        let key = key.clone();
+        info!("Downloading rules s3://{}/{}", bucket, key);
+        let object = client.get_object().bucket(&bucket).key(&key).send().await?;
+        let content = object.body.collect().await?.into_bytes().to_vec();
+        match (key.ends_with(".pack"), public_key) {
+            (true, None) => rules
+                .compiled
+                .extend(CompiledRules::from_pack(&key, &content).map_err(|e| e.to_string())?),
+            (true, Some(public_key)) => {
+                let signature_key = format!("{key}.sig");
+                let signature = client
+                    .get_object()
+                    .bucket(&bucket)
+                    .key(&signature_key)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        format!("unable to download the signature s3://{bucket}/{signature_key} of {key}, {e}")
+                    })?;
+                let signature =
+                    String::from_utf8(signature.body.collect().await?.into_bytes().to_vec())?;
+                rules.compiled.extend(
+                    CompiledRules::from_signed_pack(&key, &content, &signature, public_key)
+                        .map_err(|e| e.to_string())?,
+                )
+            }
+            (false, None) => {
+                let content = String::from_utf8(content)?;
+                match CompiledRules::compile(ValidateInput {
+                    content: &content,
+                    file_name: &key,
+                }) {
+                    Ok(compiled) => rules.compiled.push(compiled),
+                    Err(error) => rules.errors.push((key, error.to_string())),
+                }
+            }
+            (false, Some(_)) => {
+                return Err(format!(
+                    "{key} is not a rules pack, only signed rules packs are evaluated when {PUBLIC_KEY_VARIABLE} is set"
+                )
+                .into())
+            }
+        }
+    }
+
+    rules.versions = versions;
+    let rules = Arc::new(rules);
+    let mut cache = S3_RULES.lock().map_err(|e| e.to_string())?;
+    cache.retain(|(cached, _)| cached != uri);
+    cache.push((uri.to_string(), Arc::clone(&rules)));
+    if cache.len() > MAX_CACHED_S3_URIS {
+        let evicted = cache.len() - MAX_CACHED_S3_URIS;
+        cache.drain(..evicted);
     }
-    Ok(CustomOutput {
-        message: results_vec,
-    })
+    Ok(rules)
 }
 
 impl std::fmt::Display for CustomEvent {
@@ -74,15 +514,24 @@ impl std::fmt::Display for CustomEvent {
 
 impl std::fmt::Display for CustomOutput {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        for message in &self.message {
-            write!(
+        match self {
+            CustomOutput::Structured(output) => write!(
                 f,
                 "{}",
-                match serde_json::to_string_pretty(message) {
-                    Ok(message) => message,
-                    Err(_) => unreachable!(),
+                serde_json::to_string_pretty(output).map_err(|_| std::fmt::Error)?
+            )?,
+            CustomOutput::Compatibility { message } => {
+                for message in message {
+                    write!(
+                        f,
+                        "{}",
+                        match serde_json::to_string_pretty(message) {
+                            Ok(message) => message,
+                            Err(_) => unreachable!(),
+                        }
+                    )?;
                 }
-            )?;
+            }
         }
         Ok(())
     }
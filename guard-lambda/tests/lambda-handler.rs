@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use cfn_guard_lambda::main::{call_cfn_guard, CustomEvent, CustomOutput};
+    use cfn_guard_lambda::main::{
+        call_cfn_guard, parse_s3_uri, CustomEvent, CustomOutput, Summary, SCHEMA_VERSION,
+    };
     use lambda_runtime::Context;
 
     const NON_COMPLIANT_DATA: &str = "{\"Resources\":{\"NewVolume\":{\"Type\":\"AWS::EC2::Volume\",\"Properties\":{\"Size\":500,\"Encrypted\":false,\"AvailabilityZone\":\"us-west-2b\"}},\"NewVolume2\":{\"Type\":\"AWS::EC2::Volume\",\"Properties\":{\"Size\":50,\"Encrypted\":true,\"AvailabilityZone\":\"us-west-2c\"}}}}";
@@ -14,7 +16,9 @@ mod tests {
         let request = CustomEvent {
             data: NON_COMPLIANT_DATA.parse().unwrap(),
             rules: vec![RULE.parse().unwrap()],
+            rules_s3_uri: None,
             verbose: false,
+            schema_version: None,
         };
         println!("Request:\n{}", request);
 
@@ -31,7 +35,9 @@ mod tests {
         let request = CustomEvent {
             data: NON_COMPLIANT_DATA.parse().unwrap(),
             rules: vec![RULE.parse().unwrap()],
+            rules_s3_uri: None,
             verbose: true,
+            schema_version: None,
         };
         println!("Request:\n{}", request);
 
@@ -40,4 +46,132 @@ mod tests {
             .expect(FAILURE_MESSAGE);
         println!("Response:\n{}", response);
     }
+
+    #[test]
+    fn test_parse_s3_uri() {
+        assert_eq!(
+            ("rules-bucket".to_string(), "packs/s3.guard".to_string()),
+            parse_s3_uri("s3://rules-bucket/packs/s3.guard").unwrap()
+        );
+        assert_eq!(
+            ("rules-bucket".to_string(), "packs/".to_string()),
+            parse_s3_uri("s3://rules-bucket/packs/").unwrap()
+        );
+        assert_eq!(
+            ("rules-bucket".to_string(), String::new()),
+            parse_s3_uri("s3://rules-bucket").unwrap()
+        );
+        assert!(parse_s3_uri("rules-bucket/packs/s3.guard").is_err());
+        assert!(parse_s3_uri("s3:///packs/s3.guard").is_err());
+    }
+
+    #[test]
+    fn test_event_with_rules_s3_uri() {
+        let request: CustomEvent = serde_json::from_str(
+            r#"{"data": "{}", "rulesS3Uri": "s3://rules-bucket/packs/", "verbose": false}"#,
+        )
+        .unwrap();
+        assert!(request.rules.is_empty());
+        assert_eq!(
+            Some("s3://rules-bucket/packs/"),
+            request.rules_s3_uri.as_deref()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guard_lambda_handler_without_schema_version() {
+        let request: CustomEvent = serde_json::from_value(serde_json::json!({
+            "data": NON_COMPLIANT_DATA,
+            "rules": [RULE],
+            "verbose": false,
+        }))
+        .unwrap();
+        assert_eq!(None, request.schema_version);
+
+        let message = match call_cfn_guard(request, Context::default())
+            .await
+            .expect(FAILURE_MESSAGE)
+        {
+            CustomOutput::Compatibility { message } => message,
+            unexpected => panic!("unexpected {unexpected:?}"),
+        };
+        assert_eq!(1, message.len());
+        assert!(message[0].get("not_compliant").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_guard_lambda_handler_unsupported_schema_version() {
+        let request = CustomEvent {
+            data: NON_COMPLIANT_DATA.parse().unwrap(),
+            rules: vec![RULE.parse().unwrap()],
+            rules_s3_uri: None,
+            verbose: false,
+            schema_version: Some(String::from("0.1")),
+        };
+
+        assert!(call_cfn_guard(request, Context::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_guard_lambda_handler_invalid_rules_s3_uri() {
+        let request = CustomEvent {
+            data: NON_COMPLIANT_DATA.parse().unwrap(),
+            rules: vec![],
+            rules_s3_uri: Some(String::from("rules-bucket/packs/")),
+            verbose: false,
+            schema_version: Some(SCHEMA_VERSION.to_string()),
+        };
+
+        assert!(call_cfn_guard(request, Context::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_guard_lambda_handler_structured() {
+        let request = CustomEvent {
+            data: NON_COMPLIANT_DATA.parse().unwrap(),
+            rules: vec![RULE.parse().unwrap(), String::from("rule broken {")],
+            rules_s3_uri: None,
+            verbose: true,
+            schema_version: Some(SCHEMA_VERSION.to_string()),
+        };
+
+        let output = match call_cfn_guard(request, Context::default())
+            .await
+            .expect(FAILURE_MESSAGE)
+        {
+            CustomOutput::Structured(output) => output,
+            unexpected => panic!("unexpected {unexpected:?}"),
+        };
+
+        assert_eq!(SCHEMA_VERSION, output.schema_version);
+        assert_eq!("ERROR", output.status);
+        assert_eq!(
+            Summary {
+                pass: 0,
+                fail: 1,
+                skip: 0,
+                error: 1,
+            },
+            output.summary
+        );
+        assert_eq!(1, output.errors.len());
+        assert_eq!("lambda-rule", output.errors[0].rules_file);
+
+        let rule = match &output.rules[..] {
+            [rule] => rule,
+            unexpected => panic!("unexpected {unexpected:?}"),
+        };
+        assert_eq!("EC2_ENCRYPTION_BY_DEFAULT", rule.name);
+        assert_eq!("FAIL", rule.status);
+        let violation = match &rule.violations[..] {
+            [violation] => violation,
+            unexpected => panic!("unexpected {unexpected:?}"),
+        };
+        assert_eq!("/Resources/NewVolume/Properties/Encrypted", violation.path);
+        assert!(violation
+            .message
+            .as_deref()
+            .unwrap_or_default()
+            .contains("All EBS Volumes should be encrypted"));
+    }
 }
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+
+use crate::commands::validate::DataFile;
+use crate::rules::errors::Error;
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::values::{read_all_from, read_from};
+
+/// An input format the data files are adapted from before rules are evaluated against them,
+/// for applications embedding Guard to evaluate formats of their own, e.g. an internal deployment
+/// manifest. Adapters are registered with [`register_adapter`], and selected either by name with
+/// `--type` or for the data files they detect.
+pub trait InputAdapter: Send + Sync {
+    /// whether the data file is of this format, checked when no type is given
+    fn detect(&self, document: &serde_json::Value) -> bool;
+
+    /// the documents the rules are evaluated against in place of the data file, each of them is
+    /// reported as a data file of its own
+    fn adapt(&self, document: serde_json::Value) -> Result<Vec<serde_json::Value>, Error>;
+}
+
+lazy_static! {
+    static ref ADAPTERS: RwLock<BTreeMap<String, Arc<dyn InputAdapter>>> =
+        RwLock::new(BTreeMap::new());
+}
+
+/// Makes the adapter selectable by the given name with `--type`, and detected for the data files
+/// read afterwards ahead of the built-in types, replacing the adapter registered by that name
+/// before.
+///
+/// This function will return an error if the name is empty, or the name of a built-in type
+pub fn register_adapter(
+    name: &str,
+    adapter: impl InputAdapter + 'static,
+) -> crate::rules::Result<()> {
+    if name.trim().is_empty() {
+        return Err(Error::IllegalArguments(String::from(
+            "the name of an input adapter cannot be empty",
+        )));
+    }
+
+    if TemplateType::built_in(name).is_some() {
+        return Err(Error::IllegalArguments(format!(
+            "'{name}' is a built-in type and cannot be replaced"
+        )));
+    }
+
+    ADAPTERS
+        .write()
+        .map_err(|_| Error::IllegalArguments(String::from("input adapters are unavailable")))?
+        .insert(name.to_string(), Arc::new(adapter));
+
+    Ok(())
+}
+
+/// the formats Guard adapts data files from on its own
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum TemplateType {
+    /// the template is extracted from the `TemplateBody` of `aws cloudformation get-template`
+    /// output, expanding the short form intrinsic functions of a YAML body
+    CloudFormation,
+    /// every document of a multi-document file, and every item of a `List`, is evaluated on its
+    /// own
+    Kubernetes,
+    /// plans and state files are evaluated as they are
+    Terraform,
+    /// the output of `aws cloudformation describe-change-set --include-property-values` is
+    /// evaluated as a template of the resources the change set adds, modifies or imports, with
+    /// the properties they will have once it is executed
+    ChangeSet,
+    /// the template of the resources a change set modifies or removes, with the properties they
+    /// have before it is executed, to compare with the violations of [`TemplateType::ChangeSet`]
+    ChangeSetBefore,
+    Generic,
+}
+
+impl TemplateType {
+    /// the type `--type` names, `CFNTemplate` is kept for the command lines written before
+    /// the other types existed
+    pub(crate) fn built_in(name: &str) -> Option<TemplateType> {
+        match name {
+            "cfn" | "CFNTemplate" => Some(TemplateType::CloudFormation),
+            "k8s" => Some(TemplateType::Kubernetes),
+            "tf" => Some(TemplateType::Terraform),
+            "changeset" => Some(TemplateType::ChangeSet),
+            "changeset-before" => Some(TemplateType::ChangeSetBefore),
+            "generic" => Some(TemplateType::Generic),
+            _ => None,
+        }
+    }
+
+    /// the type the document looks like, generic when it looks like none of them
+    pub(crate) fn detect(document: &PathAwareValue) -> TemplateType {
+        let has = |key: &str| map_entry(document, key).is_some();
+
+        let is_resource = |resource: &PathAwareValue| {
+            matches!(
+                map_entry(resource, "Type"),
+                Some(PathAwareValue::String((_, type_name))) if type_name.contains("::")
+            )
+        };
+        let has_resources = match map_entry(document, "Resources") {
+            Some(PathAwareValue::Map((_, resources))) => {
+                !resources.values.is_empty() && resources.values.values().all(is_resource)
+            }
+            _ => false,
+        };
+
+        if has("Changes") && (has("ChangeSetId") || has("ChangeSetName")) {
+            TemplateType::ChangeSet
+        } else if has("AWSTemplateFormatVersion") || has("TemplateBody") || has_resources {
+            TemplateType::CloudFormation
+        } else if has("apiVersion") && has("kind") {
+            TemplateType::Kubernetes
+        } else if has("terraform_version")
+            || (has("format_version") && (has("planned_values") || has("resource_changes")))
+        {
+            TemplateType::Terraform
+        } else {
+            TemplateType::Generic
+        }
+    }
+
+    /// the documents of the data file, `None` when it is evaluated as it is
+    fn adapt(self, data_file: &DataFile) -> crate::rules::Result<Option<Vec<PathAwareValue>>> {
+        match self {
+            TemplateType::CloudFormation => {
+                match map_entry(&data_file.path_value, "TemplateBody") {
+                    Some(PathAwareValue::String((_, body))) => {
+                        Ok(Some(vec![PathAwareValue::try_from(read_from(body)?)?]))
+                    }
+                    Some(body @ PathAwareValue::Map(_)) => Ok(Some(vec![body.clone()])),
+                    _ => Ok(None),
+                }
+            }
+            TemplateType::Kubernetes => {
+                let mut documents = vec![];
+                for document in read_all_from(&data_file.content)? {
+                    match PathAwareValue::try_from(document)? {
+                        // the empty documents of a stream, e.g. after a trailing `---`
+                        PathAwareValue::Null(_) => {}
+                        document => {
+                            match (map_entry(&document, "kind"), map_entry(&document, "items")) {
+                                (
+                                    Some(PathAwareValue::String((_, kind))),
+                                    Some(PathAwareValue::List((_, items))),
+                                ) if kind == "List" => documents.extend(items.iter().cloned()),
+                                _ => documents.push(document),
+                            }
+                        }
+                    }
+                }
+                Ok(Some(documents))
+            }
+            TemplateType::ChangeSet | TemplateType::ChangeSetBefore => {
+                let (_, change_set): (String, serde_json::Value) =
+                    (&data_file.path_value).try_into()?;
+                let template = change_set_template(
+                    &data_file.name,
+                    &change_set,
+                    self == TemplateType::ChangeSetBefore,
+                )?;
+                Ok(Some(vec![PathAwareValue::try_from(template)?]))
+            }
+            TemplateType::Terraform | TemplateType::Generic => Ok(None),
+        }
+    }
+}
+
+/// the template of the resources of a change set as they are after it is executed, or before
+/// it is, built from the `AfterContext` or `BeforeContext` of their changes. Resources the change
+/// does not affect in that state are left out, e.g. removed resources afterwards
+///
+/// This function will return an error if the change set was described without property values
+fn change_set_template(
+    name: &str,
+    change_set: &serde_json::Value,
+    before: bool,
+) -> crate::rules::Result<serde_json::Value> {
+    let (actions, context): (&[&str], &str) = match before {
+        true => (&["Modify", "Remove", "Dynamic"], "BeforeContext"),
+        false => (&["Add", "Modify", "Import", "Dynamic"], "AfterContext"),
+    };
+
+    let mut resources = serde_json::Map::new();
+    let changes = change_set["Changes"].as_array().into_iter().flatten();
+    for change in changes.filter(|change| change["Type"] == "Resource") {
+        let change = &change["ResourceChange"];
+        let action = change["Action"].as_str().unwrap_or_default();
+        if !actions.contains(&action) {
+            continue;
+        }
+
+        let logical_id = change["LogicalResourceId"].as_str().unwrap_or_default();
+        let mut resource = serde_json::Map::new();
+        resource.insert(String::from("Type"), change["ResourceType"].clone());
+        let state = change[context].as_str().ok_or_else(|| {
+            Error::IncompatibleError(format!(
+                "the change of {logical_id} in {name} has no {context}, describe the change set with aws cloudformation describe-change-set --include-property-values"
+            ))
+        })?;
+        resource.extend(serde_json::from_str::<serde_json::Map<_, _>>(state)?);
+        resources.insert(logical_id.to_string(), serde_json::Value::Object(resource));
+    }
+
+    Ok(serde_json::json!({ "Resources": resources }))
+}
+
+/// how the data files are adapted, through a built-in type or a registered adapter
+#[derive(Clone)]
+pub(crate) enum Adapter {
+    BuiltIn(TemplateType),
+    Custom(Arc<dyn InputAdapter>),
+}
+
+impl Adapter {
+    /// the adapter `--type` names, either a built-in type or a registered adapter
+    ///
+    /// This function will return an error if no type or adapter has the name
+    pub(crate) fn named(name: &str) -> crate::rules::Result<Adapter> {
+        if let Some(template_type) = TemplateType::built_in(name) {
+            return Ok(Adapter::BuiltIn(template_type));
+        }
+
+        registered()?
+            .into_iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, adapter)| Adapter::Custom(adapter))
+            .ok_or_else(|| {
+                Error::IllegalArguments(format!(
+                    "'{name}' is not a type, expecting cfn, k8s, tf, changeset, changeset-before, generic or the name of a registered adapter"
+                ))
+            })
+    }
+
+    /// the first registered adapter detecting the document, or else the built-in type it looks
+    /// like
+    pub(crate) fn detect(document: &PathAwareValue) -> crate::rules::Result<Adapter> {
+        let adapters = registered()?;
+        if !adapters.is_empty() {
+            let (_, json): (String, serde_json::Value) = document.try_into()?;
+            if let Some((_, adapter)) = adapters.into_iter().find(|(_, a)| a.detect(&json)) {
+                return Ok(Adapter::Custom(adapter));
+            }
+        }
+
+        Ok(Adapter::BuiltIn(TemplateType::detect(document)))
+    }
+
+    /// the data files the rules are evaluated against in place of the data file, named after it
+    /// with the position of each document when there is more than one
+    pub(crate) fn adapt(&self, data_file: DataFile) -> crate::rules::Result<Vec<DataFile>> {
+        let mut documents = match self {
+            Adapter::BuiltIn(template_type) => match template_type.adapt(&data_file)? {
+                Some(documents) => documents,
+                None => return Ok(vec![data_file]),
+            },
+            Adapter::Custom(adapter) => {
+                let (_, json): (String, serde_json::Value) = (&data_file.path_value).try_into()?;
+                adapter
+                    .adapt(json)?
+                    .into_iter()
+                    .map(PathAwareValue::try_from)
+                    .collect::<crate::rules::Result<Vec<_>>>()?
+            }
+        };
+
+        if documents.len() == 1 {
+            return Ok(vec![DataFile {
+                path_value: documents.remove(0),
+                ..data_file
+            }]);
+        }
+
+        Ok(documents
+            .into_iter()
+            .enumerate()
+            .map(|(index, path_value)| DataFile {
+                name: format!("{}[{}]", data_file.name, index + 1),
+                path_value,
+                ..data_file.clone()
+            })
+            .collect())
+    }
+}
+
+/// adapts every data file, through the type `--type` names or else the adapter detected for
+/// each of them
+pub(crate) fn adapt_data_files(
+    data_files: Vec<DataFile>,
+    template_type: Option<&str>,
+) -> crate::rules::Result<Vec<DataFile>> {
+    let named = template_type.map(Adapter::named).transpose()?;
+
+    let mut adapted = vec![];
+    for data_file in data_files {
+        let adapter = match &named {
+            Some(adapter) => adapter.clone(),
+            None => Adapter::detect(&data_file.path_value)?,
+        };
+        adapted.extend(adapter.adapt(data_file)?);
+    }
+
+    Ok(adapted)
+}
+
+fn registered() -> crate::rules::Result<Vec<(String, Arc<dyn InputAdapter>)>> {
+    Ok(ADAPTERS
+        .read()
+        .map_err(|_| Error::IllegalArguments(String::from("input adapters are unavailable")))?
+        .iter()
+        .map(|(name, adapter)| (name.clone(), adapter.clone()))
+        .collect())
+}
+
+fn map_entry<'value>(value: &'value PathAwareValue, key: &str) -> Option<&'value PathAwareValue> {
+    match value {
+        PathAwareValue::Map((_, map)) => map.values.get(key),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "adapters_tests.rs"]
+mod adapters_tests;
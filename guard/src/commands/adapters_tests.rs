@@ -0,0 +1,255 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::commands::validate::build_data_file;
+
+fn data_file(content: &str) -> DataFile {
+    build_data_file(content.to_string(), String::from("data.yaml")).unwrap()
+}
+
+fn kinds(data_files: &[DataFile]) -> Vec<(String, String)> {
+    data_files
+        .iter()
+        .map(|data_file| match map_entry(&data_file.path_value, "kind") {
+            Some(PathAwareValue::String((_, kind))) => (data_file.name.clone(), kind.clone()),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_detect_template_type() {
+    for (content, expected) in [
+        (
+            "AWSTemplateFormatVersion: 2010-09-09\nResources: {}",
+            TemplateType::CloudFormation,
+        ),
+        (
+            "Resources:\n  bucket:\n    Type: AWS::S3::Bucket",
+            TemplateType::CloudFormation,
+        ),
+        ("apiVersion: v1\nkind: Pod", TemplateType::Kubernetes),
+        (
+            r#"{"format_version": "1.2", "planned_values": {}}"#,
+            TemplateType::Terraform,
+        ),
+        (
+            "Resources:\n  bucket:\n    Name: logs",
+            TemplateType::Generic,
+        ),
+        ("[1, 2]", TemplateType::Generic),
+    ] {
+        assert_eq!(
+            expected,
+            TemplateType::detect(&data_file(content).path_value)
+        );
+    }
+}
+
+#[test]
+fn test_kubernetes_documents() -> crate::rules::Result<()> {
+    let content = r#"
+apiVersion: v1
+kind: Pod
+---
+apiVersion: v1
+kind: List
+items:
+  - apiVersion: v1
+    kind: Service
+  - apiVersion: apps/v1
+    kind: Deployment
+---
+"#;
+
+    let adapted = adapt_data_files(vec![data_file(content)], None)?;
+    assert_eq!(
+        vec![
+            (String::from("data.yaml[1]"), String::from("Pod")),
+            (String::from("data.yaml[2]"), String::from("Service")),
+            (String::from("data.yaml[3]"), String::from("Deployment")),
+        ],
+        kinds(&adapted)
+    );
+
+    // a single document keeps the name of the data file
+    let adapted = adapt_data_files(vec![data_file("apiVersion: v1\nkind: Pod")], None)?;
+    assert_eq!(
+        vec![(String::from("data.yaml"), String::from("Pod"))],
+        kinds(&adapted)
+    );
+
+    // only the first document is evaluated as generic data
+    let adapted = adapt_data_files(vec![data_file(content)], Some("generic"))?;
+    assert_eq!(
+        vec![(String::from("data.yaml"), String::from("Pod"))],
+        kinds(&adapted)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cloudformation_template_body() -> crate::rules::Result<()> {
+    let content = r#"{"TemplateBody": "Resources:\n  bucket:\n    Type: AWS::S3::Bucket\n    Properties:\n      BucketName: !Ref Name\n"}"#;
+
+    let adapted = adapt_data_files(vec![data_file(content)], Some("cfn"))?;
+    assert_eq!(1, adapted.len());
+
+    let (_, template): (String, serde_json::Value) = (&adapted[0].path_value).try_into()?;
+    assert_eq!(
+        serde_json::json!({
+            "Resources": {
+                "bucket": {
+                    "Type": "AWS::S3::Bucket",
+                    "Properties": { "BucketName": { "Ref": "Name" } }
+                }
+            }
+        }),
+        template
+    );
+
+    Ok(())
+}
+
+const CHANGE_SET: &str = r#"{
+  "ChangeSetName": "add-logs",
+  "ChangeSetId": "arn:aws:cloudformation:us-east-1:123456789012:changeSet/add-logs/1a2b3c",
+  "StackName": "network",
+  "Changes": [
+    {
+      "Type": "Resource",
+      "ResourceChange": {
+        "Action": "Add",
+        "LogicalResourceId": "logs",
+        "ResourceType": "AWS::S3::Bucket",
+        "AfterContext": "{\"Properties\":{\"BucketName\":\"logs\"}}"
+      }
+    },
+    {
+      "Type": "Resource",
+      "ResourceChange": {
+        "Action": "Modify",
+        "LogicalResourceId": "data",
+        "ResourceType": "AWS::S3::Bucket",
+        "BeforeContext": "{\"Properties\":{\"VersioningConfiguration\":{\"Status\":\"Enabled\"}}}",
+        "AfterContext": "{\"Properties\":{\"VersioningConfiguration\":{\"Status\":\"Suspended\"}}}"
+      }
+    },
+    {
+      "Type": "Resource",
+      "ResourceChange": {
+        "Action": "Remove",
+        "LogicalResourceId": "queue",
+        "ResourceType": "AWS::SQS::Queue",
+        "BeforeContext": "{\"Properties\":{}}"
+      }
+    }
+  ]
+}"#;
+
+#[test]
+fn test_change_set_resources() -> crate::rules::Result<()> {
+    assert_eq!(
+        TemplateType::ChangeSet,
+        TemplateType::detect(&data_file(CHANGE_SET).path_value)
+    );
+
+    let adapted = adapt_data_files(vec![data_file(CHANGE_SET)], None)?;
+    let (_, template): (String, serde_json::Value) = (&adapted[0].path_value).try_into()?;
+    assert_eq!(
+        serde_json::json!({
+            "Resources": {
+                "logs": {
+                    "Type": "AWS::S3::Bucket",
+                    "Properties": { "BucketName": "logs" }
+                },
+                "data": {
+                    "Type": "AWS::S3::Bucket",
+                    "Properties": { "VersioningConfiguration": { "Status": "Suspended" } }
+                }
+            }
+        }),
+        template
+    );
+
+    let adapted = adapt_data_files(vec![data_file(CHANGE_SET)], Some("changeset-before"))?;
+    let (_, template): (String, serde_json::Value) = (&adapted[0].path_value).try_into()?;
+    assert_eq!(
+        serde_json::json!({
+            "Resources": {
+                "data": {
+                    "Type": "AWS::S3::Bucket",
+                    "Properties": { "VersioningConfiguration": { "Status": "Enabled" } }
+                },
+                "queue": { "Type": "AWS::SQS::Queue", "Properties": {} }
+            }
+        }),
+        template
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_change_set_without_property_values() {
+    let content = CHANGE_SET.replace("AfterContext", "Details");
+    let error = adapt_data_files(vec![data_file(&content)], Some("changeset")).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("the change of logs in data.yaml has no AfterContext"));
+}
+
+struct Manifest;
+
+impl InputAdapter for Manifest {
+    fn detect(&self, document: &serde_json::Value) -> bool {
+        document.get("adapters_tests_manifest").is_some()
+    }
+
+    fn adapt(&self, document: serde_json::Value) -> Result<Vec<serde_json::Value>, Error> {
+        Ok(document["adapters_tests_manifest"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[test]
+fn test_custom_adapter() -> crate::rules::Result<()> {
+    register_adapter("adapters_tests_manifest", Manifest)?;
+
+    let content = r#"{"adapters_tests_manifest": [{"kind": "Queue"}, {"kind": "Topic"}]}"#;
+    for template_type in [None, Some("adapters_tests_manifest")] {
+        let adapted = adapt_data_files(vec![data_file(content)], template_type)?;
+        assert_eq!(
+            vec![
+                (String::from("data.yaml[1]"), String::from("Queue")),
+                (String::from("data.yaml[2]"), String::from("Topic")),
+            ],
+            kinds(&adapted)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_adapter_names() {
+    assert!(matches!(
+        register_adapter("k8s", Manifest),
+        Err(Error::IllegalArguments(_))
+    ));
+    assert!(matches!(
+        register_adapter(" ", Manifest),
+        Err(Error::IllegalArguments(_))
+    ));
+    assert!(matches!(
+        Adapter::named("adapters_tests_unknown"),
+        Err(Error::IllegalArguments(_))
+    ));
+    assert!(matches!(
+        Adapter::named("CFNTemplate"),
+        Ok(Adapter::BuiltIn(TemplateType::CloudFormation))
+    ));
+}
@@ -0,0 +1,84 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::files::read_file_content;
+use crate::commands::results_diff::{clause_violations, Violation};
+use crate::rules::errors::Error;
+use crate::rules::eval_context::FileReport;
+use crate::rules::{Result, Status};
+
+/// the violations an existing codebase already had when the rules were introduced. Validate
+/// reports the failures made up only of these as baselined, and does not fail on them
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Baseline {
+    pub(crate) violations: BTreeSet<Violation>,
+}
+
+impl Baseline {
+    pub(crate) fn read(path: &str) -> Result<Baseline> {
+        let content = read_file_content(File::open(path)?)?;
+        serde_json::from_str(&content).map_err(|e| {
+            Error::ParseError(format!("Unable to read the baseline in {path}, Error {e}"))
+        })
+    }
+
+    pub(crate) fn write(&self, path: &str) -> Result<()> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{}", serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// adds every violation in the report of the data file to the baseline
+    pub(crate) fn extend(&mut self, data_file: &str, report: &FileReport<'_>) -> Result<()> {
+        let file = baseline_file_name(data_file);
+        for failure in &report.not_compliant {
+            self.violations
+                .extend(clause_violations(&file, &serde_json::to_value(failure)?));
+        }
+
+        Ok(())
+    }
+
+    /// moves the failures of the report whose violations are all in the baseline to its
+    /// `baselined` list, the file passes when no other failure of a rule that is not a warning is left
+    pub(crate) fn apply(&self, data_file: &str, report: &mut FileReport<'_>) -> Result<()> {
+        let file = baseline_file_name(data_file);
+        for failure in std::mem::take(&mut report.not_compliant) {
+            let violations = clause_violations(&file, &serde_json::to_value(&failure)?);
+            match violations.is_subset(&self.violations) {
+                true => report.baselined.push(failure),
+                false => report.not_compliant.push(failure),
+            }
+        }
+
+        if report.status == Status::FAIL
+            && !report
+                .not_compliant
+                .iter()
+                .any(|failure| failure.severity().is_error())
+        {
+            report.status = Status::PASS;
+        }
+
+        Ok(())
+    }
+}
+
+/// data files are named relative to the current directory in the baseline, so that it keeps
+/// matching when the repository is checked out somewhere else
+fn baseline_file_name(data_file: &str) -> String {
+    std::env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .ok()
+        .and_then(|dir| {
+            Path::new(data_file)
+                .strip_prefix(dir)
+                .ok()
+                .map(|path| path.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| data_file.to_string())
+}
@@ -0,0 +1,75 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use clap::Args;
+
+use crate::commands::rules_diff::read_rule_pack;
+use crate::commands::validate::parse_rules;
+use crate::commands::{Executable, ERROR_STATUS_CODE, RULES, SUCCESS_STATUS_CODE};
+use crate::rules::pack::{write_pack, PackedRulesFile};
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+const ABOUT: &str = r#"Parses rules files ahead of time into a rules pack, a single file that validate, test and the
+Lambda function load without parsing the rules again. A pack can only be loaded by the version of
+cfn-guard that built it."#;
+const RULES_HELP: &str = "Provide a rules file or a directory of rules files. Supports passing multiple values by using this option repeatedly";
+const OUTPUT_HELP: &str = "Write the rules pack to this file, e.g. rules.pack";
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Build command parses rules files into a rules pack
+pub struct Build {
+    // the paths to the rules files, or directories of rules files, to pack
+    #[arg(short=RULES.1, long=RULES.0, help=RULES_HELP, required=true)]
+    pub(crate) rules: Vec<String>,
+    // the path the rules pack is written to
+    #[arg(short, long, help=OUTPUT_HELP, required=true)]
+    pub(crate) output: String,
+}
+
+impl Executable for Build {
+    /// .
+    /// parses every rules file and writes them to the rules pack. Nothing is written when a rules
+    /// file fails to parse, the parse errors are reported on stderr
+    ///
+    /// This function will return an error if
+    /// - any of the specified paths do not exist
+    /// - the rules pack could not be written
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let mut sources = vec![];
+        for path in &self.rules {
+            sources.extend(read_rule_pack(path)?);
+        }
+
+        let mut files = vec![];
+        let mut exit_code = SUCCESS_STATUS_CODE;
+        for (name, content) in &sources {
+            match parse_rules(content, name) {
+                Ok(Some(rules)) => files.push(PackedRulesFile {
+                    name: name.clone(),
+                    rules,
+                }),
+                Ok(None) => {}
+                Err(e) => {
+                    writer.write_err(format!(
+                        "Parsing error handling rule file = {name}, Error = {e}\n---"
+                    ))?;
+                    exit_code = ERROR_STATUS_CODE;
+                }
+            }
+        }
+        if exit_code != SUCCESS_STATUS_CODE {
+            return Ok(exit_code);
+        }
+
+        let mut output = BufWriter::new(File::create(&self.output)?);
+        write_pack(&files, &mut output)?;
+        output.flush()?;
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
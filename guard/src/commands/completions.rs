@@ -1,7 +1,12 @@
-use crate::{commands::CfnGuard, rules};
+use crate::{
+    commands::{CfnGuard, APP_NAME},
+    rules,
+    utils::writer::Writer,
+};
 use clap::{Args, CommandFactory, ValueEnum};
+use clap_complete::Generator;
 
-#[derive(Copy, Clone, Debug, ValueEnum)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
 pub enum Shell {
     Bash,
     Zsh,
@@ -19,27 +24,41 @@ impl From<String> for Shell {
     }
 }
 
+impl Shell {
+    fn generator(self) -> clap_complete::Shell {
+        match self {
+            Shell::Bash => clap_complete::Shell::Bash,
+            Shell::Zsh => clap_complete::Shell::Zsh,
+            Shell::Fish => clap_complete::Shell::Fish,
+        }
+    }
+
+    /// the name the shell looks the completions script up by, e.g. _cfn-guard for zsh
+    pub(crate) fn file_name(self) -> String {
+        self.generator().file_name(APP_NAME)
+    }
+}
+
 #[derive(Debug, Args)]
 #[clap(arg_required_else_help = true)]
-#[clap(about = "Generate auto-completions for all the sub-commands in shell.")]
+#[clap(
+    about = "Generate auto-completions for all the sub-commands in shell. Same as generate completions."
+)]
 pub struct Completions {
     #[arg(short, long, value_enum)]
     shell: Shell,
 }
 
 impl Completions {
-    pub fn execute(&self) -> rules::Result<i32> {
-        let mut app = CfnGuard::command();
-        match &self.shell {
-            Shell::Bash => generate(clap_complete::shells::Bash, &mut app),
-            Shell::Zsh => generate(clap_complete::shells::Zsh, &mut app),
-            Shell::Fish => generate(clap_complete::shells::Fish, &mut app),
-        }
+    pub fn execute(&self, writer: &mut Writer) -> rules::Result<i32> {
+        completions(self.shell, &mut CfnGuard::command(), writer);
 
         Ok(0)
     }
 }
 
-fn generate<G: clap_complete::Generator>(gen: G, cmd: &mut clap::Command) {
-    clap_complete::generate(gen, cmd, cmd.get_name().to_string(), &mut std::io::stdout());
+/// writes the completions script of the command for the shell
+pub(crate) fn completions(shell: Shell, cmd: &mut clap::Command, buf: &mut dyn std::io::Write) {
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell.generator(), cmd, name, buf);
 }
@@ -0,0 +1,121 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::commands::files::read_file_content;
+use crate::commands::remote::is_remote;
+use crate::commands::validate::STDIN_DATA;
+use crate::rules::errors::Error;
+use crate::rules::Result;
+
+/// the names of the config file of a project, looked up in this order in the current directory
+/// and then in each of its parents up to the root of the repository
+pub(crate) const CONFIG_FILES: [&str; 2] = ["cfn-guard.toml", ".guardrc"];
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+/// the defaults of a project for the commands, flags passed on the command line take precedence
+pub(crate) struct GuardConfig {
+    #[serde(default)]
+    pub(crate) validate: ValidateConfig,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct ValidateConfig {
+    /// rules files or directories, relative to the directory of the config file
+    pub(crate) rules: Vec<String>,
+    /// data files, directories or glob patterns, relative to the directory of the config file
+    pub(crate) data: Vec<String>,
+    /// the output formats, each as `format` or `format=path`
+    pub(crate) output_format: OneOrMany,
+    /// the file of the violations accepted as they are, relative to the directory of the config
+    /// file
+    pub(crate) baseline: Option<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) exclude_tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+/// a setting given either as a single value or as a list of them
+pub(crate) enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Default for OneOrMany {
+    fn default() -> Self {
+        OneOrMany::Many(vec![])
+    }
+}
+
+impl OneOrMany {
+    pub(crate) fn values(&self) -> &[String] {
+        match self {
+            OneOrMany::One(value) => std::slice::from_ref(value),
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+impl GuardConfig {
+    /// reads the config file, resolving the paths in it against the directory of the file
+    ///
+    /// This function will return an error if the file does not exist or is not valid TOML
+    pub(crate) fn read(path: &Path) -> Result<GuardConfig> {
+        if !path.is_file() {
+            return Err(Error::FileNotFoundError(path.display().to_string()));
+        }
+
+        let content = read_file_content(File::open(path)?)?;
+        let mut config: GuardConfig = toml::from_str(&content).map_err(|e| {
+            Error::ParseError(format!("unable to read config {}, {e}", path.display()))
+        })?;
+
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        let validate = &mut config.validate;
+        for path in validate.rules.iter_mut().chain(validate.data.iter_mut()) {
+            *path = relative_to(base, path);
+        }
+        if let Some(baseline) = validate.baseline.as_mut() {
+            *baseline = relative_to(base, baseline);
+        }
+
+        Ok(config)
+    }
+}
+
+/// the config file of the project the directory belongs to, the first of [`CONFIG_FILES`] found
+/// in the directory or one of its parents, not looking past the root of a git repository
+pub(crate) fn find_config(dir: &Path) -> Option<PathBuf> {
+    for dir in dir.ancestors() {
+        if let Some(file) = CONFIG_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|file| file.is_file())
+        {
+            return Some(file);
+        }
+
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    None
+}
+
+/// the path joined to the directory of the config file, absolute paths, URLs and `-` for stdin
+/// are kept as they are
+fn relative_to(base: &Path, path: &str) -> String {
+    match path == STDIN_DATA || is_remote(path) || Path::new(path).is_absolute() {
+        true => path.to_string(),
+        false => base.join(path).display().to_string(),
+    }
+}
+
+#[cfg(test)]
+#[path = "config_tests.rs"]
+mod config_tests;
@@ -0,0 +1,159 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//
+// Embedded mapping table from well-known AWS Config managed rules to the resource type and
+// clauses that approximate them for a CloudFormation template. The clauses are relative to the
+// `Properties` section of each matching resource. These are only meant as a starting point when
+// migrating conformance packs to pre-deployment checks, they do not try to replicate every
+// parameter the managed rules accept.
+//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ConfigRuleMapping {
+    pub(crate) identifier: &'static str,
+    pub(crate) resource_type: &'static str,
+    pub(crate) clauses: &'static [&'static str],
+    pub(crate) message: &'static str,
+}
+
+pub(crate) const CONFIG_RULE_MAPPINGS: &[ConfigRuleMapping] = &[
+    ConfigRuleMapping {
+        identifier: "s3-bucket-server-side-encryption-enabled",
+        resource_type: "AWS::S3::Bucket",
+        clauses: &[
+            r#"BucketEncryption.ServerSideEncryptionConfiguration[*].ServerSideEncryptionByDefault.SSEAlgorithm IN ["AES256", "aws:kms", "aws:kms:dsse"]"#,
+        ],
+        message: "S3 buckets must have default server side encryption enabled",
+    },
+    ConfigRuleMapping {
+        identifier: "s3-bucket-public-read-prohibited",
+        resource_type: "AWS::S3::Bucket",
+        clauses: &[
+            "PublicAccessBlockConfiguration.BlockPublicAcls == true",
+            "PublicAccessBlockConfiguration.IgnorePublicAcls == true",
+        ],
+        message: "S3 buckets must not allow public read access",
+    },
+    ConfigRuleMapping {
+        identifier: "s3-bucket-public-write-prohibited",
+        resource_type: "AWS::S3::Bucket",
+        clauses: &[
+            "PublicAccessBlockConfiguration.BlockPublicAcls == true",
+            "PublicAccessBlockConfiguration.BlockPublicPolicy == true",
+        ],
+        message: "S3 buckets must not allow public write access",
+    },
+    ConfigRuleMapping {
+        identifier: "s3-bucket-level-public-access-prohibited",
+        resource_type: "AWS::S3::Bucket",
+        clauses: &[
+            "PublicAccessBlockConfiguration.BlockPublicAcls == true",
+            "PublicAccessBlockConfiguration.BlockPublicPolicy == true",
+            "PublicAccessBlockConfiguration.IgnorePublicAcls == true",
+            "PublicAccessBlockConfiguration.RestrictPublicBuckets == true",
+        ],
+        message: "S3 buckets must block all public access",
+    },
+    ConfigRuleMapping {
+        identifier: "s3-bucket-versioning-enabled",
+        resource_type: "AWS::S3::Bucket",
+        clauses: &[r#"VersioningConfiguration.Status == "Enabled""#],
+        message: "S3 buckets must have versioning enabled",
+    },
+    ConfigRuleMapping {
+        identifier: "s3-bucket-logging-enabled",
+        resource_type: "AWS::S3::Bucket",
+        clauses: &["LoggingConfiguration exists"],
+        message: "S3 buckets must have server access logging enabled",
+    },
+    ConfigRuleMapping {
+        identifier: "encrypted-volumes",
+        resource_type: "AWS::EC2::Volume",
+        clauses: &["Encrypted == true"],
+        message: "EBS volumes must be encrypted",
+    },
+    ConfigRuleMapping {
+        identifier: "rds-storage-encrypted",
+        resource_type: "AWS::RDS::DBInstance",
+        clauses: &["StorageEncrypted == true"],
+        message: "RDS DB instances must have storage encryption enabled",
+    },
+    ConfigRuleMapping {
+        identifier: "rds-instance-public-access-check",
+        resource_type: "AWS::RDS::DBInstance",
+        clauses: &["PubliclyAccessible == false"],
+        message: "RDS DB instances must not be publicly accessible",
+    },
+    ConfigRuleMapping {
+        identifier: "rds-multi-az-support",
+        resource_type: "AWS::RDS::DBInstance",
+        clauses: &["MultiAZ == true"],
+        message: "RDS DB instances must be deployed across multiple availability zones",
+    },
+    ConfigRuleMapping {
+        identifier: "dynamodb-pitr-enabled",
+        resource_type: "AWS::DynamoDB::Table",
+        clauses: &["PointInTimeRecoverySpecification.PointInTimeRecoveryEnabled == true"],
+        message: "DynamoDB tables must have point in time recovery enabled",
+    },
+    ConfigRuleMapping {
+        identifier: "cloud-trail-encryption-enabled",
+        resource_type: "AWS::CloudTrail::Trail",
+        clauses: &["KMSKeyId exists"],
+        message: "CloudTrail trails must be encrypted with a KMS key",
+    },
+    ConfigRuleMapping {
+        identifier: "cloud-trail-log-file-validation-enabled",
+        resource_type: "AWS::CloudTrail::Trail",
+        clauses: &["EnableLogFileValidation == true"],
+        message: "CloudTrail trails must have log file validation enabled",
+    },
+    ConfigRuleMapping {
+        identifier: "efs-encrypted-check",
+        resource_type: "AWS::EFS::FileSystem",
+        clauses: &["Encrypted == true"],
+        message: "EFS file systems must be encrypted",
+    },
+    ConfigRuleMapping {
+        identifier: "elasticsearch-encrypted-at-rest",
+        resource_type: "AWS::Elasticsearch::Domain",
+        clauses: &["EncryptionAtRestOptions.Enabled == true"],
+        message: "Elasticsearch domains must have encryption at rest enabled",
+    },
+    ConfigRuleMapping {
+        identifier: "kinesis-stream-encrypted",
+        resource_type: "AWS::Kinesis::Stream",
+        clauses: &["StreamEncryption exists"],
+        message: "Kinesis streams must be encrypted",
+    },
+    ConfigRuleMapping {
+        identifier: "redshift-cluster-public-access-check",
+        resource_type: "AWS::Redshift::Cluster",
+        clauses: &["PubliclyAccessible == false"],
+        message: "Redshift clusters must not be publicly accessible",
+    },
+    ConfigRuleMapping {
+        identifier: "sns-encrypted-kms",
+        resource_type: "AWS::SNS::Topic",
+        clauses: &["KmsMasterKeyId exists"],
+        message: "SNS topics must be encrypted with a KMS key",
+    },
+];
+
+/// looks up a managed rule by its identifier, accepts both the kebab case identifier
+/// (s3-bucket-versioning-enabled) and the upper snake case form used by conformance
+/// packs (S3_BUCKET_VERSIONING_ENABLED)
+pub(crate) fn find_config_rule(identifier: &str) -> Option<&'static ConfigRuleMapping> {
+    let identifier = identifier.trim().to_lowercase().replace('_', "-");
+
+    CONFIG_RULE_MAPPINGS
+        .iter()
+        .find(|mapping| mapping.identifier == identifier)
+}
+
+pub(crate) fn supported_config_rules() -> impl Iterator<Item = &'static str> {
+    CONFIG_RULE_MAPPINGS
+        .iter()
+        .map(|mapping| mapping.identifier)
+}
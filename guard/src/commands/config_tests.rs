@@ -0,0 +1,79 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+#[test]
+fn test_validate_config() {
+    let config: GuardConfig = toml::from_str(
+        r#"
+        [validate]
+        rules = ["rules/"]
+        data = ["templates/**/*.yaml"]
+        output-format = "json"
+        baseline = "guard-baseline.json"
+        exclude-tags = ["experimental"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        ValidateConfig {
+            rules: vec![String::from("rules/")],
+            data: vec![String::from("templates/**/*.yaml")],
+            output_format: OneOrMany::One(String::from("json")),
+            baseline: Some(String::from("guard-baseline.json")),
+            tags: vec![],
+            exclude_tags: vec![String::from("experimental")],
+        },
+        config.validate
+    );
+    assert_eq!(
+        &[String::from("json")],
+        config.validate.output_format.values()
+    );
+
+    let config: GuardConfig = toml::from_str("").unwrap();
+    assert_eq!(GuardConfig::default(), config);
+    assert!(config.validate.output_format.values().is_empty());
+
+    assert!(toml::from_str::<GuardConfig>("[validate]\nrule = [\"rules/\"]").is_err());
+}
+
+#[test]
+fn test_read_config() -> crate::rules::Result<()> {
+    let path = find_config(Path::new("resources/validate/config/rules")).unwrap();
+    assert_eq!(Path::new("resources/validate/config/cfn-guard.toml"), path);
+
+    let base = Path::new("resources/validate/config");
+    let config = GuardConfig::read(&path)?.validate;
+    assert_eq!(vec![base.join("rules").display().to_string()], config.rules);
+    assert_eq!(
+        vec![base
+            .join("../data-dir/s3-public-read-prohibited-template-non-compliant.yaml")
+            .display()
+            .to_string()],
+        config.data
+    );
+    assert_eq!(vec![String::from("cis-2.1.1")], config.tags);
+
+    assert!(matches!(
+        GuardConfig::read(Path::new("resources/validate/config/dne.toml")),
+        Err(Error::FileNotFoundError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_relative_to() {
+    let base = Path::new("project");
+    assert_eq!(
+        Path::new("project").join("rules").display().to_string(),
+        relative_to(base, "rules")
+    );
+    assert_eq!("-", relative_to(base, "-"));
+    assert_eq!(
+        "https://example.com/rules.guard",
+        relative_to(base, "https://example.com/rules.guard")
+    );
+}
@@ -0,0 +1,292 @@
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Write;
+use std::process::{Command as Process, Stdio};
+use std::rc::Rc;
+
+use clap::Args;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::files::read_file_content;
+use crate::commands::validate::build_data_file;
+use crate::commands::{Executable, SUCCESS_STATUS_CODE, TEST_FAILURE_STATUS_CODE};
+use crate::rules::errors::Error;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::{root_scope, EventRecord};
+use crate::rules::exprs::RulesFile;
+use crate::rules::{NamedStatus, RecordType, Result, Status};
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+/// the conformance cases bundled with cfn-guard
+pub(crate) const BUNDLED_CASES: &str = include_str!("../../resources/conformance/cases.yaml");
+
+const ABOUT: &str = r#"Runs the conformance cases of the Guard rules language against an implementation.
+Each case is piped to the implementation command as {"name", "rules", "data"} JSON on its
+standard input, and the command must print a JSON object mapping each rule name to PASS, FAIL
+or SKIP. Without --impl-cmd the cases are run against cfn-guard itself."#;
+const IMPL_CMD_HELP: &str =
+    "The command running the implementation under test, executed through the shell once per case";
+const CASES_HELP: &str = "A file of conformance cases to run instead of the bundled ones";
+const EXPORT_HELP: &str = "Print the conformance cases as JSON instead of running them";
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+/// .
+/// The Conformance command runs the conformance cases of the rules language against an
+/// implementation and reports where its rule statuses diverge from the expected ones
+pub struct Conformance {
+    // the command running the implementation under test
+    // default None, the cases are run against cfn-guard itself
+    #[arg(long, help=IMPL_CMD_HELP, conflicts_with="export")]
+    pub(crate) impl_cmd: Option<String>,
+    // the path to a file of conformance cases
+    // default None, the bundled cases are used
+    #[arg(long, help=CASES_HELP)]
+    pub(crate) cases: Option<String>,
+    // print the cases as JSON
+    // default false
+    #[arg(long, help=EXPORT_HELP)]
+    pub(crate) export: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ConformanceCases {
+    pub(crate) cases: Vec<ConformanceCase>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ConformanceCase {
+    pub(crate) name: String,
+    pub(crate) rules: String,
+    pub(crate) data: String,
+    pub(crate) expected: IndexMap<String, Status>,
+}
+
+/// what an implementation is handed on its standard input for each case
+#[derive(Debug, Serialize)]
+struct CaseInput<'a> {
+    name: &'a str,
+    rules: &'a str,
+    data: &'a str,
+}
+
+/// a way an implementation departed from the expected outcome of a case
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Divergence {
+    /// the implementation could not be run or its output could not be read
+    Failed(String),
+    /// a rule did not end up with the expected status, `actual` is `None` when the rule is
+    /// missing from the output
+    Status {
+        rule: String,
+        expected: Status,
+        actual: Option<Status>,
+    },
+    /// the output carries a rule the case does not expect
+    Unexpected { rule: String, actual: Status },
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Divergence::Failed(reason) => write!(f, "{reason}"),
+            Divergence::Status {
+                rule,
+                expected,
+                actual: Some(actual),
+            } => write!(f, "rule {rule}: expected {expected:?}, got {actual:?}"),
+            Divergence::Status {
+                rule,
+                expected,
+                actual: None,
+            } => write!(
+                f,
+                "rule {rule}: expected {expected:?}, missing from the output"
+            ),
+            Divergence::Unexpected { rule, actual } => {
+                write!(f, "rule {rule}: not expected, got {actual:?}")
+            }
+        }
+    }
+}
+
+impl Executable for Conformance {
+    /// .
+    /// runs every conformance case against the implementation
+    ///
+    /// This function will return an error if
+    /// - the cases file does not exist or cannot be parsed
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let cases = match &self.cases {
+            Some(file) => parse_cases(&read_file_content(File::open(file)?)?)?,
+            None => parse_cases(BUNDLED_CASES)?,
+        };
+
+        if self.export {
+            writeln!(writer, "{}", serde_json::to_string_pretty(&cases)?)?;
+            return Ok(SUCCESS_STATUS_CODE);
+        }
+
+        let mut conforming = 0;
+        for case in &cases.cases {
+            let divergences = match &self.impl_cmd {
+                Some(command) => run_implementation(command, case),
+                None => evaluate_case(case).map_err(|e| e.to_string()),
+            }
+            .map_or_else(
+                |reason| vec![Divergence::Failed(reason)],
+                |actual| compare(&case.expected, &actual),
+            );
+
+            match divergences.is_empty() {
+                true => {
+                    conforming += 1;
+                    writeln!(writer, "PASS {}", case.name)?;
+                }
+                false => {
+                    writeln!(writer, "FAIL {}", case.name)?;
+                    for divergence in divergences {
+                        writeln!(writer, "  {divergence}")?;
+                    }
+                }
+            }
+        }
+
+        writeln!(
+            writer,
+            "{conforming} of {} cases conform",
+            cases.cases.len()
+        )?;
+
+        match conforming == cases.cases.len() {
+            true => Ok(SUCCESS_STATUS_CODE),
+            false => Ok(TEST_FAILURE_STATUS_CODE),
+        }
+    }
+}
+
+pub(crate) fn parse_cases(content: &str) -> Result<ConformanceCases> {
+    serde_yaml::from_str(content)
+        .map_err(|e| Error::ParseError(format!("Unable to parse conformance cases: {e}")))
+}
+
+/// evaluates a case with cfn-guard's own evaluator, returning the status of every named rule
+pub(crate) fn evaluate_case(case: &ConformanceCase) -> Result<IndexMap<String, Status>> {
+    let rules_file = RulesFile::try_from(case.rules.as_str())?;
+    let data_file = build_data_file(case.data.clone(), case.name.clone())?;
+
+    let mut scope = root_scope(&rules_file, Rc::new(data_file.path_value));
+    eval_rules_file(&rules_file, &mut scope, Some(&case.name))?;
+    let root_record = scope.reset_recorder().extract();
+
+    Ok(rule_statuses(&root_record))
+}
+
+fn rule_statuses(root_record: &EventRecord<'_>) -> IndexMap<String, Status> {
+    root_record
+        .children
+        .iter()
+        .filter_map(|each| match &each.container {
+            Some(RecordType::RuleCheck(NamedStatus { name, status, .. })) => {
+                Some((name.to_string(), *status))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// runs the implementation command for a single case through the shell, handing the case over
+/// on its standard input and reading the rule statuses from its standard output
+fn run_implementation(
+    command: &str,
+    case: &ConformanceCase,
+) -> std::result::Result<IndexMap<String, Status>, String> {
+    let input = serde_json::to_string(&CaseInput {
+        name: &case.name,
+        rules: &case.rules,
+        data: &case.data,
+    })
+    .map_err(|e| e.to_string())?;
+
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("unable to run {command}: {e}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // an implementation exiting without reading its input is reported through its output
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("unable to run {command}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "implementation exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        format!(
+            "unable to read the rule statuses from the output, {e}: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        )
+    })
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Process {
+    let mut process = Process::new("sh");
+    process.arg("-c").arg(command);
+    process
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Process {
+    let mut process = Process::new("cmd");
+    process.arg("/C").arg(command);
+    process
+}
+
+/// the divergences of the statuses an implementation reported from the expected ones, in the
+/// order the case lists its rules
+pub(crate) fn compare(
+    expected: &IndexMap<String, Status>,
+    actual: &IndexMap<String, Status>,
+) -> Vec<Divergence> {
+    let mut divergences = expected
+        .iter()
+        .filter(|(rule, status)| actual.get(*rule) != Some(*status))
+        .map(|(rule, status)| Divergence::Status {
+            rule: rule.clone(),
+            expected: *status,
+            actual: actual.get(rule).copied(),
+        })
+        .collect::<Vec<_>>();
+
+    divergences.extend(
+        actual
+            .iter()
+            .filter(|(rule, _)| !expected.contains_key(*rule))
+            .map(|(rule, status)| Divergence::Unexpected {
+                rule: rule.clone(),
+                actual: *status,
+            }),
+    );
+
+    divergences
+}
+
+#[cfg(test)]
+#[path = "conformance_tests.rs"]
+mod conformance_tests;
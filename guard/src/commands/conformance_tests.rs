@@ -0,0 +1,91 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_bundled_cases_conform() {
+    let cases = parse_cases(BUNDLED_CASES).unwrap();
+    assert!(!cases.cases.is_empty());
+
+    for case in &cases.cases {
+        let actual = evaluate_case(case).unwrap();
+        assert_eq!(
+            Vec::<Divergence>::new(),
+            compare(&case.expected, &actual),
+            "case {}",
+            case.name
+        );
+    }
+}
+
+#[test]
+fn test_bundled_case_names_are_unique() {
+    let cases = parse_cases(BUNDLED_CASES).unwrap();
+    let mut names = cases
+        .cases
+        .iter()
+        .map(|case| case.name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(cases.cases.len(), names.len());
+}
+
+#[test]
+fn test_compare() {
+    let expected = IndexMap::from([
+        (String::from("first"), Status::PASS),
+        (String::from("second"), Status::FAIL),
+        (String::from("third"), Status::SKIP),
+    ]);
+    let actual = IndexMap::from([
+        (String::from("first"), Status::PASS),
+        (String::from("second"), Status::PASS),
+        (String::from("fourth"), Status::SKIP),
+    ]);
+
+    let divergences = compare(&expected, &actual);
+    assert_eq!(
+        vec![
+            Divergence::Status {
+                rule: String::from("second"),
+                expected: Status::FAIL,
+                actual: Some(Status::PASS),
+            },
+            Divergence::Status {
+                rule: String::from("third"),
+                expected: Status::SKIP,
+                actual: None,
+            },
+            Divergence::Unexpected {
+                rule: String::from("fourth"),
+                actual: Status::SKIP,
+            },
+        ],
+        divergences
+    );
+    assert_eq!(
+        vec![
+            "rule second: expected FAIL, got PASS",
+            "rule third: expected SKIP, missing from the output",
+            "rule fourth: not expected, got SKIP",
+        ],
+        divergences
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+    assert!(compare(&expected, &expected).is_empty());
+}
+
+#[test]
+fn test_parse_cases_rejects_unknown_status() {
+    let content = r#"
+cases:
+  - name: unknown
+    rules: "rule r { a exists }"
+    data: "a: 1"
+    expected:
+      r: PASSED
+"#;
+    assert!(parse_cases(content).is_err());
+}
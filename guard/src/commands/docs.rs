@@ -0,0 +1,661 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+
+use clap::{Args, ValueEnum};
+
+use crate::commands::rules_diff::read_rule_pack;
+use crate::commands::validate::parse_rules;
+use crate::commands::{Executable, RULES, SUCCESS_STATUS_CODE};
+use crate::rules::display::{
+    conjunctions_source, let_value_source, query_source, when_clause_source,
+};
+use crate::rules::errors::Error;
+use crate::rules::exprs::{
+    Block, GuardAccessClause, GuardClause, GuardNamedRuleClause, LetExpr, LetValue,
+    ParameterizedNamedRuleClause, Rule, RuleClause, RulesFile, WhenConditions,
+};
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::values::CmpOperator;
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+const ABOUT: &str = r#"Generates policy documentation from rules files. Every named rule is documented with
+the comments written right above it, its tags, severity and on-missing annotations, its clauses
+in plain language and the custom messages attached to them."#;
+const RULES_HELP: &str = "Provide a rules file or a directory of rules files. Supports passing multiple values by using this option repeatedly";
+const OUTPUT_HELP: &str = "Write the documentation to a file instead of stdout";
+const FORMAT_HELP: &str = "The format of the documentation";
+
+const TITLE: &str = "Policy documentation";
+
+#[derive(Copy, Eq, Clone, Debug, PartialEq, ValueEnum, Default)]
+pub enum DocsFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Docs command generates policy documentation from rules files
+pub struct Docs {
+    // the paths to the rules files, or directories of rules files, to document
+    #[arg(short=RULES.1, long=RULES.0, help=RULES_HELP, required=true)]
+    pub(crate) rules: Vec<String>,
+    // the path to a file the documentation is written to
+    // default None, the documentation is written to stdout
+    #[arg(short, long, help=OUTPUT_HELP)]
+    pub(crate) output: Option<String>,
+    // the format of the documentation
+    // default markdown
+    #[arg(long, help=FORMAT_HELP, value_enum, default_value_t=DocsFormat::Markdown)]
+    pub(crate) format: DocsFormat,
+}
+
+impl Executable for Docs {
+    /// .
+    /// documents the named rules of every rules file
+    ///
+    /// This function will return an error if
+    /// - any of the specified paths do not exist
+    /// - parse errors occur in any of the rule files
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let mut files = vec![];
+        for path in &self.rules {
+            files.extend(read_rule_pack(path)?);
+        }
+
+        let mut docs = vec![];
+        for (name, content) in &files {
+            match parse_rules(content, name) {
+                Ok(Some(rules_file)) => docs.push(file_doc(name, content, &rules_file)),
+                Ok(None) => {}
+                Err(e) => {
+                    return Err(Error::ParseError(format!(
+                        "Parsing error handling rule file = {name}, Error = {e}"
+                    )))
+                }
+            }
+        }
+
+        let text = match self.format {
+            DocsFormat::Markdown => markdown(&docs),
+            DocsFormat::Html => html(&docs),
+        };
+
+        match &self.output {
+            Some(path) => fs::write(path, text)?,
+            None => write!(writer, "{text}")?,
+        }
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
+
+/// a run of documentation text, `Code` is guard syntax rendered verbatim
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Span {
+    Text(String),
+    Code(String),
+}
+
+fn text(text: &str) -> Span {
+    Span::Text(text.to_string())
+}
+
+/// a line of the description of a rule, lines one level deeper than the line before them only
+/// apply within it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Line {
+    pub(crate) depth: usize,
+    pub(crate) spans: Vec<Span>,
+    pub(crate) message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RuleDoc {
+    pub(crate) name: String,
+    pub(crate) parameters: Vec<String>,
+    pub(crate) description: Vec<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) severity: String,
+    pub(crate) on_missing: Option<String>,
+    pub(crate) lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileDoc {
+    pub(crate) name: String,
+    pub(crate) variables: Vec<Line>,
+    pub(crate) rules: Vec<RuleDoc>,
+}
+
+pub(crate) fn file_doc(name: &str, content: &str, rules_file: &RulesFile<'_>) -> FileDoc {
+    let comments = rule_comments(content);
+    let mut variables = Describer::default();
+    for assignment in &rules_file.assignments {
+        variables.assignment(assignment);
+    }
+
+    let rules = rules_file
+        .guard_rules
+        .iter()
+        .map(|rule| (rule, vec![]))
+        .chain(rules_file.parameterized_rules.iter().map(|parameterized| {
+            (
+                &parameterized.rule,
+                parameterized.parameter_names.iter().cloned().collect(),
+            )
+        }))
+        .map(|(rule, parameters)| RuleDoc {
+            name: rule.rule_name.clone(),
+            parameters,
+            description: comments.get(&rule.rule_name).cloned().unwrap_or_default(),
+            tags: rule.annotations.tags.clone(),
+            severity: rule.annotations.severity.unwrap_or_default().to_string(),
+            on_missing: rule
+                .annotations
+                .on_missing
+                .map(|on_missing| on_missing.to_string()),
+            lines: rule_lines(rule),
+        })
+        .collect();
+
+    FileDoc {
+        name: name.to_string(),
+        variables: variables.lines,
+        rules,
+    }
+}
+
+/// the comments written right above each `rule` of a rules file, keyed by rule name. Annotation
+/// lines between the comments and the rule are skipped, a blank line detaches the comments from
+/// the rule. When a rule is defined more than once, the comments of its first definition are kept
+pub(crate) fn rule_comments(content: &str) -> HashMap<String, Vec<String>> {
+    let mut comments = HashMap::new();
+    let mut pending = vec![];
+    for line in content.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.strip_prefix(' ').unwrap_or(comment);
+            pending.push(comment.trim_end().to_string());
+            continue;
+        }
+        match declared_rule(line) {
+            Some(name) if !pending.is_empty() => {
+                comments
+                    .entry(name.to_string())
+                    .or_insert_with(|| std::mem::take(&mut pending));
+            }
+            // annotations written on their own lines, between the comments and the rule
+            None if line.starts_with('@') => continue,
+            _ => {}
+        }
+        pending.clear();
+    }
+    comments
+}
+
+/// the name of the rule a line declares, annotations may come before the `rule` keyword
+fn declared_rule(line: &str) -> Option<&str> {
+    let mut rest = line;
+    while rest.starts_with('@') {
+        rest = rest[rest.find(')')? + 1..].trim_start();
+    }
+    let rest = rest.strip_prefix("rule")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    match end {
+        0 => None,
+        _ => Some(&rest[..end]),
+    }
+}
+
+pub(crate) fn rule_lines(rule: &Rule<'_>) -> Vec<Line> {
+    let mut describer = Describer::default();
+    match &rule.conditions {
+        Some(conditions) => describer.nested(when(conditions), |this| {
+            this.block(&rule.block, Describer::rule_clause)
+        }),
+        None => describer.block(&rule.block, Describer::rule_clause),
+    }
+    describer.lines
+}
+
+fn when(conditions: &WhenConditions<'_>) -> Vec<Span> {
+    vec![
+        text("When "),
+        Span::Code(conjunctions_source(conditions, when_clause_source)),
+    ]
+}
+
+#[derive(Default)]
+struct Describer {
+    lines: Vec<Line>,
+    depth: usize,
+}
+
+impl Describer {
+    fn push(&mut self, spans: Vec<Span>, message: Option<&String>) {
+        self.lines.push(Line {
+            depth: self.depth,
+            spans,
+            message: message.map(String::as_str).map(message_text),
+        });
+    }
+
+    fn nested(&mut self, mut spans: Vec<Span>, describe: impl FnOnce(&mut Self)) {
+        spans.push(text(":"));
+        self.push(spans, None);
+        self.depth += 1;
+        describe(self);
+        self.depth -= 1;
+    }
+
+    fn assignment(&mut self, assignment: &LetExpr<'_>) {
+        self.push(
+            vec![
+                Span::Code(format!("%{}", assignment.var)),
+                text(" is "),
+                Span::Code(let_value_source(&assignment.value)),
+            ],
+            None,
+        )
+    }
+
+    fn block<T>(&mut self, block: &Block<'_, T>, describe: fn(&mut Self, &T)) {
+        for assignment in &block.assignments {
+            self.assignment(assignment);
+        }
+        for disjunctions in &block.conjunctions {
+            match disjunctions.as_slice() {
+                [single] => describe(self, single),
+                _ => self.nested(vec![text("At least one of")], |this| {
+                    for each in disjunctions {
+                        describe(this, each);
+                    }
+                }),
+            }
+        }
+    }
+
+    fn rule_clause(&mut self, clause: &RuleClause<'_>) {
+        match clause {
+            RuleClause::Clause(clause) => self.guard_clause(clause),
+            RuleClause::WhenBlock(conditions, block, otherwise) => {
+                self.when_block(conditions, block, otherwise.as_ref())
+            }
+            RuleClause::TypeBlock(type_block) => {
+                let mut spans = vec![
+                    text("For each "),
+                    Span::Code(type_block.type_name.clone()),
+                    text(" resource"),
+                ];
+                if let Some(conditions) = &type_block.conditions {
+                    spans.push(text(" when "));
+                    spans.push(Span::Code(conjunctions_source(
+                        conditions,
+                        when_clause_source,
+                    )));
+                }
+                self.nested(spans, |this| {
+                    this.block(&type_block.block, Self::guard_clause)
+                })
+            }
+        }
+    }
+
+    fn guard_clause(&mut self, clause: &GuardClause<'_>) {
+        match clause {
+            GuardClause::Clause(clause) => self.push(
+                requirement(clause),
+                clause.access_clause.custom_message.as_ref(),
+            ),
+            GuardClause::NamedRule(named) => self.push(
+                named_rule(named, named.dependent_rule.clone()),
+                named.custom_message.as_ref(),
+            ),
+            GuardClause::ParameterizedNamedRule(parameterized) => self.push(
+                parameterized_rule(parameterized),
+                parameterized.named_rule.custom_message.as_ref(),
+            ),
+            GuardClause::BlockClause(block_clause) => {
+                let query = Span::Code(query_source(&block_clause.query.query));
+                let spans = match (block_clause.negation, block_clause.query.match_all) {
+                    (false, _) => vec![text("Within "), query],
+                    (true, true) => vec![text("Not every value of "), query, text(" may satisfy")],
+                    (true, false) => vec![text("No value of "), query, text(" may satisfy")],
+                };
+                self.nested(spans, |this| {
+                    this.block(&block_clause.block, Self::guard_clause)
+                })
+            }
+            GuardClause::WhenBlock(conditions, block, otherwise) => {
+                self.when_block(conditions, block, otherwise.as_ref())
+            }
+        }
+    }
+
+    fn when_block(
+        &mut self,
+        conditions: &WhenConditions<'_>,
+        block: &Block<'_, GuardClause<'_>>,
+        otherwise: Option<&Block<'_, GuardClause<'_>>>,
+    ) {
+        self.nested(when(conditions), |this| {
+            this.block(block, Self::guard_clause)
+        });
+        if let Some(otherwise) = otherwise {
+            self.nested(vec![text("Otherwise")], |this| {
+                this.block(otherwise, Self::guard_clause)
+            });
+        }
+    }
+}
+
+/// a clause in plain language, e.g. "`Properties.Encrypted` must equal `true`"
+fn requirement(clause: &GuardAccessClause<'_>) -> Vec<Span> {
+    let access = &clause.access_clause;
+    let (cmp, not) = access.comparator;
+    let regex = matches!(
+        access.compare_with,
+        Some(LetValue::Value(PathAwareValue::Regex(_)))
+    );
+
+    let mut spans = match access.query.match_all {
+        true => vec![],
+        false => vec![text("At least one value of ")],
+    };
+    spans.push(Span::Code(query_source(&access.query.query)));
+    spans.push(Span::Text(format!(
+        " must {}",
+        predicate(cmp, not != clause.negation, regex)
+    )));
+    if let Some(value) = &access.compare_with {
+        spans.push(text(" "));
+        spans.push(Span::Code(let_value_source(value)));
+    }
+    spans
+}
+
+fn predicate(cmp: CmpOperator, not: bool, regex: bool) -> String {
+    let predicate = match cmp {
+        CmpOperator::Eq if regex => "match",
+        CmpOperator::Eq => "equal",
+        CmpOperator::UnorderedEq => "equal, in any order,",
+        CmpOperator::In => "be one of",
+        CmpOperator::Like => "match",
+        CmpOperator::Gt => "be greater than",
+        CmpOperator::Lt => "be less than",
+        CmpOperator::Ge => "be greater than or equal to",
+        CmpOperator::Le => "be less than or equal to",
+        CmpOperator::Exists => "exist",
+        CmpOperator::Empty => "be empty",
+        CmpOperator::IsString => "be a string",
+        CmpOperator::IsBool => "be a boolean",
+        CmpOperator::IsInt => "be an integer",
+        CmpOperator::IsList => "be a list",
+        CmpOperator::IsMap => "be a struct",
+        CmpOperator::IsNull => "be null",
+        CmpOperator::IsFloat => "be a float",
+    };
+    match not {
+        true => format!("not {predicate}"),
+        false => predicate.to_string(),
+    }
+}
+
+fn named_rule(named: &GuardNamedRuleClause<'_>, call: String) -> Vec<Span> {
+    vec![
+        text("Rule "),
+        Span::Code(call),
+        text(match named.negation {
+            true => " must not pass",
+            false => " must pass",
+        }),
+    ]
+}
+
+fn parameterized_rule(parameterized: &ParameterizedNamedRuleClause<'_>) -> Vec<Span> {
+    let call = format!(
+        "{}({})",
+        parameterized.named_rule.dependent_rule,
+        parameterized
+            .parameters
+            .iter()
+            .map(let_value_source)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    named_rule(&parameterized.named_rule, call)
+}
+
+/// custom messages are usually spread over several indented lines, they are joined into one
+fn message_text(message: &str) -> String {
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub(crate) fn markdown(docs: &[FileDoc]) -> String {
+    let mut out = format!("# {TITLE}\n");
+    for file in docs {
+        out.push_str(&format!("\n## {}\n", markdown_escape(&file.name)));
+        if !file.variables.is_empty() {
+            out.push_str("\nVariables shared by the rules of this file:\n\n");
+            markdown_lines(&file.variables, &mut out);
+        }
+
+        for rule in &file.rules {
+            out.push_str(&format!("\n### {}\n", markdown_escape(&rule.name)));
+            if !rule.description.is_empty() {
+                out.push('\n');
+                for line in &rule.description {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+
+            out.push('\n');
+            if !rule.parameters.is_empty() {
+                out.push_str(&format!(
+                    "- **Parameters:** {}\n",
+                    markdown_codes(&rule.parameters)
+                ));
+            }
+            if !rule.tags.is_empty() {
+                out.push_str(&format!("- **Tags:** {}\n", markdown_codes(&rule.tags)));
+            }
+            out.push_str(&format!("- **Severity:** {}\n", rule.severity));
+            if let Some(on_missing) = &rule.on_missing {
+                out.push_str(&format!("- **On missing:** {on_missing}\n"));
+            }
+
+            out.push_str("\n#### Clauses\n\n");
+            markdown_lines(&rule.lines, &mut out);
+        }
+    }
+    out
+}
+
+fn markdown_lines(lines: &[Line], out: &mut String) {
+    for line in lines {
+        let indent = "  ".repeat(line.depth);
+        out.push_str(&format!("{indent}- {}\n", markdown_spans(&line.spans)));
+        if let Some(message) = &line.message {
+            out.push_str(&format!(
+                "{indent}  - Message: *{}*\n",
+                markdown_escape(message)
+            ));
+        }
+    }
+}
+
+fn markdown_spans(spans: &[Span]) -> String {
+    spans
+        .iter()
+        .map(|span| match span {
+            Span::Text(text) => markdown_escape(text),
+            Span::Code(code) => markdown_code(code),
+        })
+        .collect()
+}
+
+fn markdown_codes(codes: &[String]) -> String {
+    codes
+        .iter()
+        .map(String::as_str)
+        .map(markdown_code)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// a code span long enough to hold the backticks of the code
+fn markdown_code(code: &str) -> String {
+    let mut fence = String::from("`");
+    while code.contains(fence.as_str()) {
+        fence.push('`');
+    }
+    match fence.len() {
+        1 => format!("`{code}`"),
+        _ => format!("{fence} {code} {fence}"),
+    }
+}
+
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub(crate) fn html(docs: &[FileDoc]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{TITLE}</title>\n</head>\n<body>\n<h1>{TITLE}</h1>\n"
+    );
+    for file in docs {
+        out.push_str(&format!("<h2>{}</h2>\n", html_escape(&file.name)));
+        if !file.variables.is_empty() {
+            out.push_str("<p>Variables shared by the rules of this file:</p>\n");
+            html_lines(&file.variables, &mut out);
+        }
+
+        for rule in &file.rules {
+            out.push_str(&format!("<h3>{}</h3>\n", html_escape(&rule.name)));
+            for paragraph in rule
+                .description
+                .split(|line| line.is_empty())
+                .filter(|paragraph| !paragraph.is_empty())
+            {
+                out.push_str(&format!("<p>{}</p>\n", html_escape(&paragraph.join("\n"))));
+            }
+
+            out.push_str("<ul>\n");
+            if !rule.parameters.is_empty() {
+                out.push_str(&format!(
+                    "<li><strong>Parameters:</strong> {}</li>\n",
+                    html_codes(&rule.parameters)
+                ));
+            }
+            if !rule.tags.is_empty() {
+                out.push_str(&format!(
+                    "<li><strong>Tags:</strong> {}</li>\n",
+                    html_codes(&rule.tags)
+                ));
+            }
+            out.push_str(&format!(
+                "<li><strong>Severity:</strong> {}</li>\n",
+                rule.severity
+            ));
+            if let Some(on_missing) = &rule.on_missing {
+                out.push_str(&format!(
+                    "<li><strong>On missing:</strong> {on_missing}</li>\n"
+                ));
+            }
+            out.push_str("</ul>\n<h4>Clauses</h4>\n");
+            html_lines(&rule.lines, &mut out);
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// nested lists following the depth of the lines, every line is an item of the list of its depth
+fn html_lines(lines: &[Line], out: &mut String) {
+    let mut open = 0;
+    for line in lines {
+        let depth = line.depth + 1;
+        match depth > open {
+            true => {
+                for _ in open..depth {
+                    out.push_str("<ul>\n");
+                }
+            }
+            false => {
+                for _ in depth..open {
+                    out.push_str("</li>\n</ul>\n");
+                }
+                out.push_str("</li>\n");
+            }
+        }
+        open = depth;
+
+        out.push_str(&format!("<li>{}", html_spans(&line.spans)));
+        if let Some(message) = &line.message {
+            out.push_str(&format!("<br>Message: <em>{}</em>", html_escape(message)));
+        }
+        out.push('\n');
+    }
+    for _ in 0..open {
+        out.push_str("</li>\n</ul>\n");
+    }
+}
+
+fn html_spans(spans: &[Span]) -> String {
+    spans
+        .iter()
+        .map(|span| match span {
+            Span::Text(text) => html_escape(text),
+            Span::Code(code) => format!("<code>{}</code>", html_escape(code)),
+        })
+        .collect()
+}
+
+fn html_codes(codes: &[String]) -> String {
+    codes
+        .iter()
+        .map(|code| format!("<code>{}</code>", html_escape(code)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[path = "docs_tests.rs"]
+mod docs_tests;
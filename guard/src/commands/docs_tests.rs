@@ -0,0 +1,176 @@
+use super::*;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+const RULES: &str = r#"
+let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+
+# Volumes must be encrypted.
+#
+# See the encryption guide.
+@severity(warning)
+rule volumes @tags(ec2, encryption) when Resources exists {
+    AWS::EC2::Volume {
+        Properties.Size <= 100
+        Properties.Encrypted == true or
+        Properties.KmsKeyId exists
+        <<
+            volumes must be
+            encrypted
+        >>
+    }
+    s3_buckets
+}
+
+rule s3_buckets {
+    %buckets.Properties.BucketName == /^app-/
+}
+"#;
+
+fn docs(content: &str) -> Vec<FileDoc> {
+    let rules_file = parse_rules(content, "rules.guard").unwrap().unwrap();
+    vec![file_doc("rules.guard", content, &rules_file)]
+}
+
+#[test]
+fn test_rule_comments() {
+    let content = r#"
+    # file header, detached by the blank line
+
+    # first line
+    #second line
+    @tags(s3)
+    @severity(warning) rule documented {
+        # a comment inside the rule
+        Resources exists
+    }
+
+    rule undocumented { Resources exists }
+    # dangling comment
+    let x = 1
+    rule not_a_rule_name { Resources exists }
+    "#;
+
+    let comments = rule_comments(content);
+    assert_eq!(1, comments.len());
+    assert_eq!(
+        vec!["first line", "second line"],
+        comments["documented"]
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_declared_rule() {
+    assert_eq!(Some("s3_rule"), declared_rule("rule s3_rule {"));
+    assert_eq!(
+        Some("s3"),
+        declared_rule("rule s3 @tags(s3) when %x exists {")
+    );
+    assert_eq!(
+        Some("s3"),
+        declared_rule("@tags(s3, cis) @severity(warning) rule s3")
+    );
+    assert_eq!(None, declared_rule("rules_count exists"));
+    assert_eq!(None, declared_rule("@tags(s3)"));
+}
+
+#[test]
+fn test_markdown() {
+    assert_eq!(
+        indoc! {r#"
+            # Policy documentation
+
+            ## rules.guard
+
+            Variables shared by the rules of this file:
+
+            - `%buckets` is `Resources.*[ Type == "AWS::S3::Bucket" ]`
+
+            ### volumes
+
+            Volumes must be encrypted.
+
+            See the encryption guide.
+
+            - **Tags:** `ec2`, `encryption`
+            - **Severity:** warning
+
+            #### Clauses
+
+            - When `Resources exists`:
+              - For each `AWS::EC2::Volume` resource:
+                - `Properties.Size` must be less than or equal to `100`
+                - At least one of:
+                  - `Properties.Encrypted` must equal `true`
+                  - `Properties.KmsKeyId` must exist
+                    - Message: *volumes must be encrypted*
+              - Rule `s3_buckets` must pass
+
+            ### s3\_buckets
+
+            - **Severity:** error
+
+            #### Clauses
+
+            - `%buckets.Properties.BucketName` must match `/^app-/`
+        "#},
+        markdown(&docs(RULES))
+    );
+}
+
+#[test]
+fn test_predicates() {
+    let content = r#"
+    @on-missing(skip)
+    rule predicates {
+        some Properties.Tags[*].Key == 'App'
+        not Properties.Public exists
+        Properties.Type != 'gp2'
+        Properties.Size in r[1, 100]
+        Properties.Name is_string
+    }
+    "#;
+
+    let rule = &docs(content)[0].rules[0];
+    assert_eq!(Some(String::from("skip")), rule.on_missing);
+    assert_eq!(
+        vec![
+            "At least one value of `Properties.Tags[*].Key` must equal `\"App\"`",
+            "`Properties.Public` must not exist",
+            "`Properties.Type` must not equal `\"gp2\"`",
+            "`Properties.Size` must be one of `[1,100]`",
+            "`Properties.Name` must be a string",
+        ],
+        rule.lines
+            .iter()
+            .map(|line| markdown_spans(&line.spans))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_html() {
+    let html = html(&docs(RULES));
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(html.contains(
+        "<h3>volumes</h3>\n<p>Volumes must be encrypted.</p>\n<p>See the encryption guide.</p>\n"
+    ));
+    assert!(html.contains(
+        "<li>At least one of:\n<ul>\n<li><code>Properties.Encrypted</code> must equal <code>true</code>\n</li>\n<li><code>Properties.KmsKeyId</code> must exist<br>Message: <em>volumes must be encrypted</em>\n</li>\n</ul>\n</li>\n</ul>\n</li>\n<li>Rule <code>s3_buckets</code> must pass\n</li>\n</ul>\n</li>\n</ul>\n"
+    ));
+    assert!(html.contains("<code>Resources.*[ Type == &quot;AWS::S3::Bucket&quot; ]</code>"));
+    assert!(html.ends_with("</body>\n</html>\n"));
+}
+
+#[test]
+fn test_markdown_code() {
+    assert_eq!("`a == b`", markdown_code("a == b"));
+    assert_eq!("`` a`b ``", markdown_code("a`b"));
+    assert_eq!(
+        r"Use \<\<message\>\> \*always\*",
+        markdown_escape("Use <<message>> *always*")
+    );
+}
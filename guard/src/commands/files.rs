@@ -1,11 +1,31 @@
 use std::cmp::{self, Ordering};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, Read};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+
+use globset::GlobBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
 
 use crate::rules::errors::Error;
 use walkdir::WalkDir;
 
+/// name of the files listing the paths to leave out when discovering rules and data files
+pub(crate) const GUARD_IGNORE_FILE: &str = ".guardignore";
+
+/// how directories are traversed when discovering rules and data files. Entries are visited depth
+/// first, in the order of the comparator within each directory, so a walk is deterministic
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct WalkOptions {
+    /// how deep below the walked path to look for files, 1 only looks at the files directly in it,
+    /// unlimited when None
+    pub(crate) max_depth: Option<usize>,
+    /// descend into symlinked directories. Links back to one of their ancestors are skipped, and
+    /// files reachable through several links are only yielded once
+    pub(crate) follow_symlinks: bool,
+}
+
 pub(crate) fn read_file_content(file: File) -> crate::rules::Result<String> {
     let mut file_content = String::new();
     let mut buf_reader = BufReader::new(file);
@@ -23,9 +43,12 @@ where
     F: Fn(&walkdir::DirEntry) -> bool,
 {
     let walker = WalkDir::new(file).sort_by(sort).into_iter();
+    let mut ignores = GuardIgnore::default();
 
     let selected = walker
-        .filter_entry(|entry| entry.path().is_dir() || filter(entry))
+        .filter_entry(|entry| {
+            (entry.path().is_dir() || filter(entry)) && !ignores.is_ignored(entry)
+        })
         .flatten()
         .filter(|entry| entry.path().is_file())
         .map(|entry| entry.into_path())
@@ -83,18 +106,22 @@ pub(crate) fn alphabetical(first: &walkdir::DirEntry, second: &walkdir::DirEntry
     first.file_name().cmp(second.file_name())
 }
 
+/// orders by last modified time, files modified at the same time, or whose time is unknown, are
+/// ordered alphabetically
 pub(crate) fn last_modified(first: &walkdir::DirEntry, second: &walkdir::DirEntry) -> Ordering {
-    if let Ok(first) = first.metadata() {
-        if let Ok(second) = second.metadata() {
-            if let Ok(first) = first.modified() {
-                if let Ok(second) = second.modified() {
-                    return first.cmp(&second);
+    if let Ok(first_metadata) = first.metadata() {
+        if let Ok(second_metadata) = second.metadata() {
+            if let Ok(first_modified) = first_metadata.modified() {
+                if let Ok(second_modified) = second_metadata.modified() {
+                    return first_modified
+                        .cmp(&second_modified)
+                        .then_with(|| alphabetical(first, second));
                 }
             }
         }
     }
 
-    Ordering::Equal
+    alphabetical(first, second)
 }
 
 pub(crate) fn regular_ordering(
@@ -107,9 +134,120 @@ pub(crate) fn regular_ordering(
 pub(crate) fn walk_dir(
     base: PathBuf,
     cmp: fn(&walkdir::DirEntry, &walkdir::DirEntry) -> cmp::Ordering,
-) -> std::iter::Flatten<walkdir::IntoIter> {
-    walkdir::WalkDir::new(base)
+    options: WalkOptions,
+) -> impl Iterator<Item = walkdir::DirEntry> {
+    let mut ignores = GuardIgnore::default();
+    let mut walker = walkdir::WalkDir::new(base)
         .sort_by(cmp)
+        .follow_links(options.follow_symlinks);
+    if let Some(max_depth) = options.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+
+    let mut visited = HashSet::new();
+    walker
         .into_iter()
+        .filter_entry(move |entry| !ignores.is_ignored(entry))
         .flatten()
+        .filter(move |entry| {
+            !options.follow_symlinks
+                || !entry.file_type().is_file()
+                || visited.insert(
+                    entry
+                        .path()
+                        .canonicalize()
+                        .unwrap_or_else(|_| entry.path().to_path_buf()),
+                )
+        })
+}
+
+/// whether a rules or data argument is a glob pattern, e.g. `stacks/**/prod-*.yaml`, rather than a path
+pub(crate) fn is_glob(file_or_dir: &str) -> bool {
+    file_or_dir.contains(['*', '?', '[', '{']) && !Path::new(file_or_dir).exists()
+}
+
+/// walks the directory the pattern starts with, i.e. the components before the first one with a
+/// wildcard, yielding the files matching the pattern. `*` does not match across directories, `**` does
+pub(crate) fn walk_glob(
+    pattern: &str,
+    cmp: fn(&walkdir::DirEntry, &walkdir::DirEntry) -> cmp::Ordering,
+    options: WalkOptions,
+) -> Result<impl Iterator<Item = walkdir::DirEntry>, Error> {
+    let matcher = GlobBuilder::new(pattern.strip_prefix("./").unwrap_or(pattern))
+        .literal_separator(true)
+        .build()
+        .map_err(|e| Error::IllegalArguments(format!("invalid glob pattern {pattern}: {e}")))?
+        .compile_matcher();
+
+    let base = Path::new(pattern)
+        .components()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '[', '{'])
+        })
+        .collect::<PathBuf>();
+    let base = match base.components().next() {
+        Some(_) => base,
+        None => PathBuf::from(Component::CurDir.as_os_str()),
+    };
+
+    Ok(walk_dir(base, cmp, options).filter(move |entry| {
+        let path = entry.path();
+        entry.file_type().is_file() && matcher.is_match(path.strip_prefix(".").unwrap_or(path))
+    }))
+}
+
+/// the `.guardignore` files that apply to the entries of a walk. These follow gitignore semantics,
+/// a file applies to the directory it is in and everything below it, and patterns from files deeper
+/// in the tree take precedence. The files in the directories above the walked path, as it was given,
+/// apply as well. The path the walk started from is never ignored
+#[derive(Default)]
+struct GuardIgnore {
+    matchers: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl GuardIgnore {
+    fn is_ignored(&mut self, entry: &walkdir::DirEntry) -> bool {
+        if entry.depth() == 0 {
+            return false;
+        }
+
+        let path = entry.path();
+        let is_dir = entry.file_type().is_dir();
+        for dir in path.ancestors().skip(1) {
+            let dir = match dir.as_os_str().is_empty() {
+                true => Path::new(Component::CurDir.as_os_str()),
+                false => dir,
+            };
+
+            let matcher = self
+                .matchers
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| guard_ignore_in(dir));
+            match matcher
+                .as_ref()
+                .map(|matcher| matcher.matched(path, is_dir))
+            {
+                Some(Match::Ignore(_)) => return true,
+                Some(Match::Whitelist(_)) => return false,
+                _ => {}
+            }
+        }
+
+        false
+    }
+}
+
+/// the patterns of the `.guardignore` file in a directory, patterns that are not valid are skipped
+fn guard_ignore_in(dir: &Path) -> Option<Gitignore> {
+    let file = dir.join(GUARD_IGNORE_FILE);
+    if !file.is_file() {
+        return None;
+    }
+
+    let mut builder = GitignoreBuilder::new(dir);
+    builder.add(file);
+    builder.build().ok()
 }
@@ -0,0 +1,202 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use clap::{Args, CommandFactory, ValueEnum};
+use serde::Serialize;
+
+use crate::commands::completions::{completions, Shell};
+use crate::commands::{CfnGuard, Executable, SUCCESS_STATUS_CODE};
+use crate::rules::errors::Error;
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+const ABOUT: &str = r#"Generates shell completions, man pages or a JSON description of every command and flag
+of cfn-guard, for distribution packages and documentation sites to stay in sync with the flags
+of the installed version."#;
+const TARGET_HELP: &str = "What to generate: completions for a shell, man pages in roff, or a JSON spec of the commands, their flags and their help";
+const SHELL_HELP: &str = "The shell to generate completions for";
+const OUTPUT_DIR_HELP: &str = "Write the files to the given directory instead of stdout. Man pages are written one per command, e.g. cfn-guard.1 and cfn-guard-validate.1, instead of the page of cfn-guard alone";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum GenerateTarget {
+    Completions,
+    Man,
+    Spec,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Generate command writes shell completions, man pages, or a JSON spec of the command line
+pub struct Generate {
+    // what to generate
+    #[arg(value_enum, help=TARGET_HELP)]
+    pub(crate) target: GenerateTarget,
+    // the shell completions are generated for, required for completions
+    #[arg(short, long, value_enum, help=SHELL_HELP)]
+    pub(crate) shell: Option<Shell>,
+    // the directory the files are written to
+    // default None, written to stdout
+    #[arg(long, help=OUTPUT_DIR_HELP)]
+    pub(crate) output_dir: Option<String>,
+}
+
+impl Executable for Generate {
+    /// .
+    /// generates the completions, man pages or spec of the cfn-guard command line
+    ///
+    /// This function will return an error if
+    /// - completions are generated without a shell
+    /// - the output directory does not exist, or a file cannot be written to it
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let mut cmd = CfnGuard::command();
+        let output_dir = self.output_dir.as_deref().map(Path::new);
+        if let Some(dir) = output_dir {
+            if !dir.is_dir() {
+                return Err(Error::FileNotFoundError(format!(
+                    "{} is not a directory",
+                    dir.display()
+                )));
+            }
+        }
+
+        match self.target {
+            GenerateTarget::Completions => {
+                let shell = self.shell.ok_or_else(|| {
+                    Error::IllegalArguments(String::from(
+                        "the shell to generate completions for is missing, e.g. --shell zsh",
+                    ))
+                })?;
+                match output_dir {
+                    Some(dir) => {
+                        let mut file = fs::File::create(dir.join(shell.file_name()))?;
+                        completions(shell, &mut cmd, &mut file);
+                    }
+                    None => completions(shell, &mut cmd, writer),
+                }
+            }
+            GenerateTarget::Man => match output_dir {
+                Some(dir) => {
+                    for (name, page) in man_pages(cmd)? {
+                        fs::write(dir.join(format!("{name}.1")), page)?;
+                    }
+                }
+                None => clap_mangen::Man::new(cmd).render(writer)?,
+            },
+            GenerateTarget::Spec => {
+                let spec = serde_json::to_string_pretty(&CommandSpec::from(&cmd))?;
+                match output_dir {
+                    Some(dir) => fs::write(dir.join(format!("{}.json", cmd.get_name())), spec)?,
+                    None => writeln!(writer, "{spec}")?,
+                }
+            }
+        }
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
+
+/// the man page of the command and of each of its subcommands, named after the command followed
+/// by the subcommand, the way man looks them up, e.g. cfn-guard-validate
+fn man_pages(cmd: clap::Command) -> Result<Vec<(String, Vec<u8>)>> {
+    let name = cmd.get_name().to_string();
+    let mut pages = vec![];
+    for subcommand in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        let subcommand = subcommand
+            .clone()
+            .name(format!("{name}-{}", subcommand.get_name()));
+        pages.extend(man_pages(subcommand)?);
+    }
+
+    let mut page = vec![];
+    clap_mangen::Man::new(cmd).render(&mut page)?;
+    pages.insert(0, (name, page));
+
+    Ok(pages)
+}
+
+/// a command of the spec, with its flags and subcommands
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub(crate) struct CommandSpec {
+    pub(crate) name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) version: Option<String>,
+    pub(crate) about: Option<String>,
+    pub(crate) args: Vec<ArgSpec>,
+    pub(crate) subcommands: Vec<CommandSpec>,
+}
+
+/// a flag or positional argument of the spec
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub(crate) struct ArgSpec {
+    pub(crate) name: String,
+    pub(crate) long: Option<String>,
+    pub(crate) short: Option<char>,
+    pub(crate) help: Option<String>,
+    pub(crate) required: bool,
+    /// whether the argument takes a value, flags do not
+    pub(crate) takes_value: bool,
+    /// whether the argument can be passed more than once, or takes several values
+    pub(crate) multiple: bool,
+    /// whether the flag is accepted by every subcommand too
+    pub(crate) global: bool,
+    pub(crate) possible_values: Vec<String>,
+    pub(crate) default_values: Vec<String>,
+}
+
+impl From<&clap::Command> for CommandSpec {
+    fn from(cmd: &clap::Command) -> Self {
+        CommandSpec {
+            name: cmd.get_name().to_string(),
+            version: cmd.get_version().map(String::from),
+            about: cmd
+                .get_about()
+                .map(|about| about.to_string().trim().to_string()),
+            args: cmd
+                .get_arguments()
+                .filter(|arg| !arg.is_hide_set())
+                .map(ArgSpec::from)
+                .collect(),
+            subcommands: cmd
+                .get_subcommands()
+                .filter(|sub| !sub.is_hide_set())
+                .map(CommandSpec::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<&clap::Arg> for ArgSpec {
+    fn from(arg: &clap::Arg) -> Self {
+        let num_args = arg.get_num_args();
+        ArgSpec {
+            name: arg.get_id().to_string(),
+            long: arg.get_long().map(String::from),
+            short: arg.get_short(),
+            help: arg.get_help().map(ToString::to_string),
+            required: arg.is_required_set(),
+            takes_value: arg.get_action().takes_values(),
+            multiple: matches!(arg.get_action(), clap::ArgAction::Append)
+                || num_args.map_or(false, |range| range.max_values() > 1),
+            global: arg.is_global_set(),
+            possible_values: arg
+                .get_possible_values()
+                .iter()
+                .filter(|value| !value.is_hide_set())
+                .map(|value| value.get_name().to_string())
+                .collect(),
+            default_values: arg
+                .get_default_values()
+                .iter()
+                .map(|value| value.to_string_lossy().to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "generate_tests.rs"]
+mod generate_tests;
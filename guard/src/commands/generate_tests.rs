@@ -0,0 +1,68 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_command_spec() {
+    let spec = CommandSpec::from(&CfnGuard::command());
+    assert_eq!("cfn-guard", spec.name);
+    assert_eq!(Some(env!("CARGO_PKG_VERSION")), spec.version.as_deref());
+
+    let quiet = spec.args.iter().find(|arg| arg.name == "quiet").unwrap();
+    assert!(quiet.global);
+    assert!(!quiet.takes_value);
+
+    let validate = spec
+        .subcommands
+        .iter()
+        .find(|subcommand| subcommand.name == "validate")
+        .unwrap();
+    let rules = validate
+        .args
+        .iter()
+        .find(|arg| arg.name == "rules")
+        .unwrap();
+    assert_eq!(Some("rules"), rules.long.as_deref());
+    assert_eq!(Some('r'), rules.short);
+    assert!(rules.takes_value);
+    assert!(rules.multiple);
+
+    let fail_on = validate
+        .args
+        .iter()
+        .find(|arg| arg.name == "fail-on")
+        .unwrap();
+    assert_eq!(
+        vec!["violations", "errors", "warnings", "none"],
+        fail_on.possible_values
+    );
+    assert_eq!(vec!["violations"], fail_on.default_values);
+
+    let rules_command = spec
+        .subcommands
+        .iter()
+        .find(|subcommand| subcommand.name == "rules")
+        .unwrap();
+    assert!(!rules_command.subcommands.is_empty());
+}
+
+#[test]
+fn test_man_pages() {
+    let pages = man_pages(CfnGuard::command()).unwrap();
+    let names = pages
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!("cfn-guard", names[0]);
+    assert!(names.contains(&"cfn-guard-validate"), "{names:?}");
+    assert!(names.contains(&"cfn-guard-rules-diff"), "{names:?}");
+
+    let validate = pages
+        .iter()
+        .find(|(name, _)| name == "cfn-guard-validate")
+        .map(|(_, page)| String::from_utf8_lossy(page))
+        .unwrap();
+    assert!(validate.contains(".TH"), "{validate}");
+    assert!(validate.contains("validate"), "{validate}");
+    assert!(validate.contains("rules"), "{validate}");
+}
@@ -3,17 +3,31 @@
 
 use enumflags2::BitFlags;
 
+use crate::commands::progress::Progress;
 use crate::commands::reporters::validate::generic_summary::GenericSummary;
-use crate::commands::validate::{DataFile, OutputFormatType, Reporter};
+use crate::commands::reporters::validate::structured::StructuredEvaluator;
+use crate::commands::summary::Summary;
+use crate::commands::validate::{DataFile, GroupBy, OutputFormatType, OutputVersion, Reporter};
+use crate::commands::SUCCESS_STATUS_CODE;
 use crate::rules::errors::Error;
 use crate::rules::eval::eval_rules_file;
 use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::{RuleSelection, RulesFile};
+use crate::rules::functions::custom::FunctionRegistry;
+use crate::rules::limits::InputLimits;
+use crate::rules::messages::MessageCatalog;
+use crate::rules::observer::EvaluationObserver;
+use crate::rules::pack::{read_pack, verify_pack};
 use crate::rules::path_value::traversal::Traversal;
 use crate::rules::path_value::PathAwareValue;
 use crate::rules::Result;
+use crate::utils::writer::{WriteBuffer, Writer};
 use std::convert::TryFrom;
 use std::io::BufWriter;
+#[cfg(feature = "async")]
+use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
 
 #[allow(dead_code)]
 pub struct ValidateInput<'a> {
@@ -21,12 +35,310 @@ pub struct ValidateInput<'a> {
     pub file_name: &'a str,
 }
 
+/// async variant of [`validate_and_return_json`], the evaluation runs on the blocking thread pool
+/// of the tokio runtime
+#[cfg(feature = "async")]
+pub async fn validate_and_return_json_async(
+    data: ValidateInput<'_>,
+    rules: ValidateInput<'_>,
+    verbose: bool,
+) -> Result<String> {
+    let (data_content, data_file_name) = (data.content.to_string(), data.file_name.to_string());
+    let (rules_content, rules_file_name) = (rules.content.to_string(), rules.file_name.to_string());
+
+    tokio::task::spawn_blocking(move || {
+        validate_and_return_json(
+            ValidateInput {
+                content: &data_content,
+                file_name: &data_file_name,
+            },
+            ValidateInput {
+                content: &rules_content,
+                file_name: &rules_file_name,
+            },
+            verbose,
+        )
+    })
+    .await
+    .map_err(std::io::Error::from)?
+}
+
+/// reads the data and rules files without blocking, then evaluates them like
+/// [`validate_and_return_json_async`]
+#[cfg(feature = "async")]
+pub async fn validate_files_and_return_json_async(
+    data_file: impl AsRef<Path>,
+    rules_file: impl AsRef<Path>,
+    verbose: bool,
+) -> Result<String> {
+    let (data_file, rules_file) = (data_file.as_ref(), rules_file.as_ref());
+    let data = tokio::fs::read_to_string(data_file).await?;
+    let rules = tokio::fs::read_to_string(rules_file).await?;
+
+    validate_and_return_json_async(
+        ValidateInput {
+            content: &data,
+            file_name: &data_file.to_string_lossy(),
+        },
+        ValidateInput {
+            content: &rules,
+            file_name: &rules_file.to_string_lossy(),
+        },
+        verbose,
+    )
+    .await
+}
+
 #[allow(dead_code)]
 pub fn validate_and_return_json(
     data: ValidateInput,
     rules: ValidateInput,
     verbose: bool,
 ) -> Result<String> {
+    let path_value = parse_data(&data, &InputLimits::default())?;
+
+    let span = crate::rules::parser::Span::new_extra(rules.content, rules.file_name);
+    match crate::rules::parser::rules_file(span) {
+        Ok(Some(rules_file)) => evaluate_and_return_json(
+            &rules_file,
+            rules.file_name,
+            data,
+            path_value,
+            verbose,
+            &InputLimits::default(),
+            None,
+            None,
+        ),
+        Ok(None) => Ok(String::default()),
+        Err(e) => Err(Error::ParseError(e.to_string())),
+    }
+}
+
+/// parses rules from bytes that need not be UTF-8, or rules at all, e.g. the input of a fuzzer,
+/// returning the number of rules parsed. Malformed input is an error, never a panic
+///
+/// This function will return an error if the bytes are not UTF-8, or the rules fail to parse
+pub fn parse_rules(content: &[u8]) -> Result<usize> {
+    let content = std::str::from_utf8(content)
+        .map_err(|e| Error::ParseError(format!("rules are not valid UTF-8, {e}")))?;
+    let span = crate::rules::parser::Span::new_extra(content, "");
+
+    Ok(crate::rules::parser::rules_file(span)?.map_or(0, |rules| {
+        rules.guard_rules.len() + rules.parameterized_rules.len()
+    }))
+}
+
+/// rules parsed once, to be evaluated against any number of data files from any number of
+/// threads, for callers that would otherwise spend most of their time parsing the same rules
+pub struct CompiledRules {
+    // declared first so that it is dropped before the content and the file name it borrows
+    rules: Option<RulesFile<'static>>,
+    _content: Box<str>,
+    file_name: Box<str>,
+    functions: Option<Arc<FunctionRegistry>>,
+}
+
+impl CompiledRules {
+    /// parses the rules
+    ///
+    /// This function will return an error if the rules fail to parse
+    pub fn compile(rules: ValidateInput) -> Result<CompiledRules> {
+        let content = Box::<str>::from(rules.content);
+        let file_name = Box::<str>::from(rules.file_name);
+
+        // SAFETY: the parsed rules borrow from the content and the file name, which stay at the
+        // same place on the heap, unchanged, for as long as the rules
+        let (content_ref, file_name_ref) = unsafe {
+            (
+                &*(content.as_ref() as *const str),
+                &*(file_name.as_ref() as *const str),
+            )
+        };
+        let span = crate::rules::parser::Span::new_extra(content_ref, file_name_ref);
+        let rules =
+            crate::rules::parser::rules_file(span).map_err(|e| Error::ParseError(e.to_string()))?;
+
+        Ok(CompiledRules {
+            rules,
+            _content: content,
+            file_name,
+            functions: None,
+        })
+    }
+
+    /// the rules files of a rules pack built by `cfn-guard build`, which are already parsed
+    ///
+    /// This function will return an error if the content is not a rules pack, or one built by
+    /// another version of cfn-guard
+    pub fn from_pack(pack_name: &str, content: &[u8]) -> Result<Vec<CompiledRules>> {
+        Ok(read_pack(pack_name, content)?
+            .into_iter()
+            .map(|file| CompiledRules {
+                rules: Some(file.rules),
+                _content: Box::default(),
+                file_name: file.name.into_boxed_str(),
+                functions: None,
+            })
+            .collect())
+    }
+
+    /// the rules files of a rules pack, after checking the signature written for it by
+    /// `cfn-guard sign` with the Ed25519 public key in PEM
+    ///
+    /// This function will return an error if the signature does not match the key and the
+    /// content, or the content is not a rules pack built by this version of cfn-guard
+    pub fn from_signed_pack(
+        pack_name: &str,
+        content: &[u8],
+        signature: &str,
+        public_key_pem: &str,
+    ) -> Result<Vec<CompiledRules>> {
+        verify_pack(pack_name, content, signature, public_key_pem)?;
+        Self::from_pack(pack_name, content)
+    }
+
+    /// rules built with the builders of [`crate::ast`], or otherwise not parsed from text, to be
+    /// evaluated like the rules of a file with the name
+    pub fn from_rules_file(file_name: &str, rules: RulesFile<'static>) -> CompiledRules {
+        CompiledRules {
+            rules: Some(rules),
+            _content: Box::default(),
+            file_name: Box::from(file_name),
+            functions: None,
+        }
+    }
+
+    /// the custom functions the rules may call besides the built-in ones, calling a function
+    /// that is neither is an error
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> CompiledRules {
+        self.functions = Some(Arc::new(functions));
+        self
+    }
+
+    /// the name of the rules file the rules were parsed from
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// evaluates the rules against the data, the result is the same as the one of
+    /// [`validate_and_return_json`]
+    pub fn validate(&self, data: ValidateInput, verbose: bool) -> Result<String> {
+        self.validate_with_limits(data, verbose, &InputLimits::default())
+    }
+
+    /// evaluates the rules against the data like [`CompiledRules::validate`], for data that
+    /// cannot be trusted to be reasonably sized
+    ///
+    /// This function will return an error if the data is larger or nests deeper than the limits
+    /// allow, or a query of the rules resolves to more values than they allow
+    pub fn validate_with_limits(
+        &self,
+        data: ValidateInput,
+        verbose: bool,
+        limits: &InputLimits,
+    ) -> Result<String> {
+        self.evaluate(data, verbose, limits, None)
+    }
+
+    /// evaluates the rules against the data like [`CompiledRules::validate`], reporting each
+    /// rule, clause and block to the observer as it is evaluated
+    pub fn validate_with_observer(
+        &self,
+        data: ValidateInput,
+        verbose: bool,
+        observer: &mut dyn EvaluationObserver,
+    ) -> Result<String> {
+        self.evaluate(data, verbose, &InputLimits::default(), Some(observer))
+    }
+
+    /// evaluates the rules against the data, returning the report `cfn-guard validate --structured`
+    /// writes in the format along with the exit code it returns
+    ///
+    /// This function will return an error if the format is not a structured one, or the data
+    /// fails to parse
+    pub fn validate_structured(
+        &self,
+        data: ValidateInput,
+        format: OutputFormatType,
+    ) -> Result<(i32, String)> {
+        if !format.is_structured() {
+            return Err(Error::IncompatibleError(String::from(
+                "the single-line-summary format is not a structured one",
+            )));
+        }
+
+        let limits = InputLimits::default();
+        let path_value = parse_data(&data, &limits)?;
+        // the rules of packs are cloned the same way when validate evaluates them
+        let rules = self
+            .rules
+            .iter()
+            .map(|rules| (rules.clone(), self.file_name()))
+            .collect::<Vec<_>>();
+        let mut writer = Writer::new(WriteBuffer::Vec(vec![]))?;
+
+        let exit_code = StructuredEvaluator {
+            rules: &rules,
+            global_rules: &[],
+            rule_errors: &[],
+            input_params: None,
+            data: vec![DataFile {
+                content: String::default(),
+                path_value,
+                name: data.file_name.to_owned(),
+                parameters_file: None,
+                parameter_values: None,
+                vars: None,
+                stack_resources: None,
+            }],
+            outputs: vec![(format, &mut writer)],
+            selection: RuleSelection::default(),
+            exit_code: SUCCESS_STATUS_CODE,
+            summary: &mut Summary::default(),
+            baseline: None,
+            update_baseline: false,
+            strict_types: false,
+            limits,
+            functions: self.functions.clone(),
+            messages: &MessageCatalog::default(),
+            progress: &mut Progress::new(0, false),
+            group_by: GroupBy::File,
+            max_findings_per_rule: None,
+            top_offenders: None,
+            output_version: OutputVersion::V1,
+            console: None,
+        }
+        .evaluate()?;
+
+        Ok((exit_code, writer.stripped()?))
+    }
+
+    fn evaluate(
+        &self,
+        data: ValidateInput,
+        verbose: bool,
+        limits: &InputLimits,
+        observer: Option<&mut dyn EvaluationObserver>,
+    ) -> Result<String> {
+        let path_value = parse_data(&data, limits)?;
+        match &self.rules {
+            Some(rules) => evaluate_and_return_json(
+                rules,
+                &self.file_name,
+                data,
+                path_value,
+                verbose,
+                limits,
+                self.functions.clone(),
+                observer,
+            ),
+            None => Ok(String::default()),
+        }
+    }
+}
+
+fn parse_data(data: &ValidateInput, limits: &InputLimits) -> Result<PathAwareValue> {
+    limits.check_file_size(data.file_name, data.content.len())?;
     let path_value = match serde_json::from_str::<serde_json::Value>(data.content) {
         Ok(value) => PathAwareValue::try_from(value),
         Err(_) => {
@@ -40,48 +352,65 @@ pub fn validate_and_return_json(
             data.file_name,
         ))
     })?;
+    // the parsers stop at a depth of their own, the limit is usually lower
+    limits.check_nesting_depth(data.file_name, &path_value)?;
 
+    Ok(path_value)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn evaluate_and_return_json(
+    rules: &RulesFile<'_>,
+    rules_file_name: &str,
+    data: ValidateInput,
+    path_value: PathAwareValue,
+    verbose: bool,
+    limits: &InputLimits,
+    functions: Option<Arc<FunctionRegistry>>,
+    observer: Option<&mut dyn EvaluationObserver>,
+) -> Result<String> {
     let input_data = DataFile {
         content: "".to_string(), // not used later
         path_value,
         name: data.file_name.to_owned(),
+        parameters_file: None,
+        parameter_values: None,
+        vars: None,
+        stack_resources: None,
     };
 
-    let span = crate::rules::parser::Span::new_extra(rules.content, rules.file_name);
+    let mut write_output = BufWriter::new(Vec::new());
+    let root = input_data.path_value;
+    let traversal = Traversal::from(&root);
+    let mut root_scope = root_scope(rules, Rc::new(root.clone()))
+        .with_limits(*limits)
+        .with_functions(functions);
+    if let Some(observer) = observer {
+        root_scope = root_scope.with_observer(Box::new(observer));
+    }
+    let status = eval_rules_file(rules, &mut root_scope, Some(&input_data.name))?;
+    let root_record = root_scope.reset_recorder().extract();
 
-    let rules_file_name = rules.file_name;
-    return match crate::rules::parser::rules_file(span) {
-        Ok(Some(rules)) => {
-            let mut write_output = BufWriter::new(Vec::new());
-            let root = input_data.path_value;
-            let traversal = Traversal::from(&root);
-            let mut root_scope = root_scope(&rules, Rc::new(root.clone()));
-            let status = eval_rules_file(&rules, &mut root_scope, Some(&input_data.name))?;
-            let root_record = root_scope.reset_recorder().extract();
-
-            if verbose {
-                return Ok(serde_json::to_string_pretty(&root_record)?);
-            }
-
-            let reporter = &GenericSummary::new(BitFlags::empty()) as &dyn Reporter;
-
-            reporter.report_eval(
-                &mut write_output,
-                status,
-                &root_record,
-                rules_file_name,
-                data.file_name,
-                data.content,
-                &traversal,
-                OutputFormatType::JSON,
-            )?;
-
-            match String::from_utf8(write_output.buffer().to_vec()) {
-                Ok(val) => Ok(val),
-                Err(e) => Err(Error::ParseError(e.to_string())),
-            }
-        }
-        Ok(None) => Ok(String::default()),
+    if verbose {
+        return Ok(serde_json::to_string_pretty(&root_record)?);
+    }
+
+    let reporter =
+        &GenericSummary::new(BitFlags::empty(), &MessageCatalog::default()) as &dyn Reporter;
+
+    reporter.report_eval(
+        &mut write_output,
+        status,
+        &root_record,
+        rules_file_name,
+        data.file_name,
+        data.content,
+        &traversal,
+        OutputFormatType::JSON,
+    )?;
+
+    match String::from_utf8(write_output.buffer().to_vec()) {
+        Ok(val) => Ok(val),
         Err(e) => Err(Error::ParseError(e.to_string())),
-    };
+    }
 }
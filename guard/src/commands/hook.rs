@@ -0,0 +1,258 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+
+use clap::Args;
+use serde::Deserialize;
+
+use crate::commands::files::read_file_content;
+use crate::commands::rules_diff::read_rule_pack;
+use crate::commands::validate::{build_data_file, glob_match, parse_rule_files, RuleFileInfo};
+use crate::commands::{
+    Executable, CONFIG, ERROR_STATUS_CODE, FAILURE_STATUS_CODE, SUCCESS_STATUS_CODE,
+};
+use crate::rules::errors::Error;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::{root_scope, simplified_json_from_root, ClauseReport, RuleReport};
+use crate::rules::exprs::{RulesFile, Severity};
+use crate::rules::messages::MessageCatalog;
+use crate::rules::{Result, Status};
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+/// the config file looked up in the directory the hook is run from
+pub(crate) const DEFAULT_CONFIG_FILE: &str = ".cfn-guard-hook.yaml";
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+/// .
+/// The Hook command evaluates the files handed over by the pre-commit framework against the rule
+/// sets configured for them
+pub struct Hook {
+    #[arg(help=FILES_HELP)]
+    /// the files pre-commit passes to the hook, files no rule set matches are ignored
+    pub(crate) files: Vec<String>,
+    #[arg(short=CONFIG.1, long=CONFIG.0, help=CONFIG_HELP, default_value=DEFAULT_CONFIG_FILE)]
+    /// path to the config mapping files to rule sets
+    /// default is .cfn-guard-hook.yaml
+    pub(crate) config: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+/// the rule sets of a project and the files each of them applies to
+pub(crate) struct HookConfig {
+    pub(crate) rule_sets: Vec<RuleSet>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RuleSet {
+    pub(crate) name: String,
+    /// patterns the files are matched against, `*` matches any sequence of characters and `?` any single one
+    pub(crate) files: Vec<String>,
+    /// rules files, or directories of rules files, relative to the directory of the config file
+    pub(crate) rules: Vec<String>,
+}
+
+impl RuleSet {
+    fn matches(&self, file: &str) -> bool {
+        let file = file.replace('\\', "/");
+        self.files.iter().any(|pattern| glob_match(pattern, &file))
+    }
+}
+
+impl Executable for Hook {
+    /// .
+    /// evaluates each file against the rule sets matching it, printing a line per file and rule set
+    ///
+    /// This function will return an error if
+    /// - the config file does not exist or is not valid
+    /// - the rules of a rule set that matched a file do not exist
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let config_path = Path::new(&self.config);
+        if !config_path.is_file() {
+            return Err(Error::FileNotFoundError(self.config.clone()));
+        }
+        let config: HookConfig =
+            serde_yaml::from_str(&read_file_content(File::open(config_path)?)?)?;
+        let base = config_path.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut exit_code = SUCCESS_STATUS_CODE;
+        let mut matched = BTreeSet::new();
+        let mut failed = BTreeSet::new();
+
+        for rule_set in &config.rule_sets {
+            let files = self
+                .files
+                .iter()
+                .filter(|file| rule_set.matches(file))
+                .collect::<Vec<_>>();
+            if files.is_empty() {
+                continue;
+            }
+            matched.extend(files.iter().copied());
+
+            let mut rule_info = vec![];
+            for rules in &rule_set.rules {
+                let path = base.join(rules);
+                for (file_name, content) in read_rule_pack(&path.display().to_string())? {
+                    // report rules files by the path they have in the config
+                    let file_name = match path.is_file() {
+                        true => rules.clone(),
+                        false => Path::new(rules).join(file_name).display().to_string(),
+                    };
+                    rule_info.push(RuleFileInfo { content, file_name });
+                }
+            }
+
+            let parsed = parse_rule_files(&rule_info);
+            for each in &parsed.errors {
+                writer.write_err(format!(
+                    "[{}] unable to parse rules file {}: {}",
+                    rule_set.name, each.file_name, each.error
+                ))?;
+            }
+            if !parsed.errors.is_empty() {
+                exit_code = ERROR_STATUS_CODE;
+                continue;
+            }
+
+            for file in files {
+                match check_file(file, &parsed.rules)? {
+                    Outcome::Error(e) => {
+                        // only the first line, errors of data files quote their content
+                        let error = e.to_string();
+                        let error = error.lines().next().unwrap_or_default();
+                        writeln!(writer, "{file}: ERROR [{}]\n  {error}", rule_set.name)?;
+                        failed.insert(file);
+                        exit_code = ERROR_STATUS_CODE;
+                    }
+                    Outcome::Checked { status, failures } => {
+                        let label = match status {
+                            Status::FAIL => "FAIL",
+                            _ if !failures.is_empty() => "WARN",
+                            Status::SKIP => "SKIP",
+                            _ => "PASS",
+                        };
+                        writeln!(writer, "{file}: {label} [{}]", rule_set.name)?;
+                        for failure in failures {
+                            writeln!(writer, "  {failure}")?;
+                        }
+
+                        if status == Status::FAIL {
+                            failed.insert(file);
+                            if exit_code == SUCCESS_STATUS_CODE {
+                                exit_code = FAILURE_STATUS_CODE;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        match matched.len() {
+            0 => writeln!(writer, "No files matched the rule sets in {}", self.config)?,
+            n => writeln!(
+                writer,
+                "{n} file(s) matched the rule sets, {} failed",
+                failed.len()
+            )?,
+        }
+
+        Ok(exit_code)
+    }
+}
+
+enum Outcome {
+    Error(Error),
+    Checked {
+        status: Status,
+        failures: Vec<String>,
+    },
+}
+
+/// evaluates a single file against every rules file of a rule set, a file that cannot be read
+/// or parsed is reported as an error of that file rather than failing the whole hook
+fn check_file(file: &str, rules: &[(RulesFile<'_>, &str)]) -> Result<Outcome> {
+    let data_file = match File::open(file)
+        .map_err(Error::from)
+        .and_then(read_file_content)
+        .and_then(|content| build_data_file(content, file.to_string()))
+    {
+        Ok(data_file) => data_file,
+        Err(e) => return Ok(Outcome::Error(e)),
+    };
+
+    let mut overall = Status::SKIP;
+    let mut failures = vec![];
+    for (rules_file, rules_file_name) in rules {
+        let mut scope = root_scope(rules_file, Rc::new(data_file.path_value.clone()));
+        let status = eval_rules_file(rules_file, &mut scope, Some(&data_file.name))?;
+        overall = overall.and(status);
+
+        let root_record = scope.reset_recorder().extract();
+        for report in
+            simplified_json_from_root(&root_record, &MessageCatalog::default())?.not_compliant
+        {
+            failures.push(describe_failure(&report, rules_file_name));
+        }
+    }
+
+    Ok(Outcome::Checked {
+        status: overall,
+        failures,
+    })
+}
+
+/// a single line for a failed rule, naming the rule and the first line of its first message
+fn describe_failure(report: &ClauseReport<'_>, rules_file_name: &str) -> String {
+    let name = match report {
+        ClauseReport::Rule(RuleReport { name, .. }) => *name,
+        _ => "<unnamed>",
+    };
+    let severity = match report.severity() {
+        Severity::Warning => " (warning)",
+        Severity::Error => "",
+    };
+    let message = report.get_message().into_iter().find_map(|messages| {
+        messages
+            .custom_message
+            .or(messages.error_message)
+            .and_then(|message| {
+                message
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .map(String::from)
+            })
+    });
+
+    // the default rule of a rules file without named rules is already named after the file
+    let name = match name.starts_with(rules_file_name) {
+        true => name.to_string(),
+        false => format!("{rules_file_name}/{name}"),
+    };
+    match message {
+        Some(message) => format!("{name}{severity}: {message}"),
+        None => format!("{name}{severity}"),
+    }
+}
+
+const ABOUT: &str = r#"Evaluates the files passed by the pre-commit framework against the rule sets configured for them.
+The config file maps patterns of file names to rules files, e.g.
+
+rule_sets:
+  - name: s3
+    files: ["templates/*.yaml"]
+    rules: ["rules/s3"]
+
+Files no rule set matches are ignored. Exits with 19 when a file violates a rule and 5 when a file or rules file could not be parsed."#;
+const FILES_HELP: &str = "The files to evaluate, as passed by pre-commit";
+const CONFIG_HELP: &str = "Path to the config file mapping files to rule sets. Rules paths in it are relative to the directory of the config file";
+
+#[cfg(test)]
+#[path = "hook_tests.rs"]
+mod hook_tests;
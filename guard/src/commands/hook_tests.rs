@@ -0,0 +1,93 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_deserialize_config() {
+    let config = r#"
+rule_sets:
+  - name: s3
+    files: ["templates/*.yaml", "templates/*.json"]
+    rules: ["rules/s3"]
+"#;
+    let config: HookConfig = serde_yaml::from_str(config).unwrap();
+
+    assert_eq!(
+        HookConfig {
+            rule_sets: vec![RuleSet {
+                name: String::from("s3"),
+                files: vec![
+                    String::from("templates/*.yaml"),
+                    String::from("templates/*.json")
+                ],
+                rules: vec![String::from("rules/s3")],
+            }]
+        },
+        config
+    );
+}
+
+#[test]
+fn test_deserialize_config_rejects_unknown_fields() {
+    let config = r#"
+rule_sets:
+  - name: s3
+    file: ["templates/*.yaml"]
+    rules: ["rules/s3"]
+"#;
+
+    assert!(serde_yaml::from_str::<HookConfig>(config).is_err());
+}
+
+#[test]
+fn test_rule_set_matches() {
+    let rule_set = RuleSet {
+        name: String::from("s3"),
+        files: vec![String::from("templates/*.yaml")],
+        rules: vec![],
+    };
+
+    assert!(rule_set.matches("templates/bucket.yaml"));
+    assert!(rule_set.matches("templates/nested/bucket.yaml"));
+    assert!(rule_set.matches(r#"templates\bucket.yaml"#));
+    assert!(!rule_set.matches("templates/bucket.json"));
+    assert!(!rule_set.matches("other/templates/bucket.yaml"));
+}
+
+#[test]
+fn test_check_file_describes_failed_rules() {
+    let rules = r#"
+rule s3_logging {
+    Resources.*[ Type == 'AWS::S3::Bucket' ].Properties.LoggingConfiguration exists
+    <<
+        Violation: logging must be configured
+        Fix: set LoggingConfiguration
+    >>
+}
+
+@severity(warning)
+rule s3_public_access_block {
+    Resources.*[ Type == 'AWS::S3::Bucket' ].Properties.PublicAccessBlockConfiguration exists
+}
+"#;
+    let rules_file = crate::commands::validate::parse_rules(rules, "s3.guard")
+        .unwrap()
+        .unwrap();
+    let file = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml"
+    );
+
+    match check_file(file, &[(rules_file, "s3.guard")]).unwrap() {
+        Outcome::Checked { status, failures } => {
+            assert_eq!(Status::FAIL, status);
+            assert_eq!(
+                vec![
+                    "s3.guard/s3_logging: Violation: logging must be configured",
+                    "s3.guard/s3_public_access_block (warning)"
+                ],
+                failures
+            );
+        }
+        Outcome::Error(e) => panic!("unexpected error {}", e),
+    }
+}
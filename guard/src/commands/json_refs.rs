@@ -0,0 +1,76 @@
+use crate::commands::template_parameters::relocate;
+use crate::rules::path_value::{MapValue, PathAwareValue};
+
+const REF: &str = "$ref";
+
+/// replaces every `{"$ref": "#/json/pointer"}` of the document with a copy of the value the
+/// pointer refers to, so rules see e.g. the schema an OpenAPI operation refers to rather than the
+/// reference. Keys next to `$ref` are dropped along with it. Refs to other documents, to values
+/// that do not exist, and refs that end up referring to themselves are kept as they are
+pub(crate) fn resolve_json_refs(document: PathAwareValue) -> PathAwareValue {
+    let root = document.clone();
+    resolve(document, &root, &mut vec![])
+}
+
+fn resolve(
+    value: PathAwareValue,
+    root: &PathAwareValue,
+    resolving: &mut Vec<String>,
+) -> PathAwareValue {
+    match value {
+        PathAwareValue::Map((path, map)) => {
+            if let Some(PathAwareValue::String((_, reference))) = map.values.get(REF) {
+                if !resolving.contains(reference) {
+                    if let Some(target) = local_target(root, reference) {
+                        resolving.push(reference.clone());
+                        let resolved = resolve(target.clone(), root, resolving);
+                        resolving.pop();
+                        return relocate(&resolved, &path);
+                    }
+                }
+            }
+
+            let MapValue { keys, values } = map;
+            let values = values
+                .into_iter()
+                .map(|(key, value)| (key, resolve(value, root, resolving)))
+                .collect();
+            PathAwareValue::Map((path, MapValue { keys, values }))
+        }
+
+        PathAwareValue::List((path, list)) => PathAwareValue::List((
+            path,
+            list.into_iter()
+                .map(|each| resolve(each, root, resolving))
+                .collect(),
+        )),
+
+        other => other,
+    }
+}
+
+/// the value a reference within the document points to, `#` followed by a JSON pointer whose
+/// tokens may be percent-encoded, with `~1` standing for `/` and `~0` for `~`
+pub(crate) fn local_target<'value>(
+    root: &'value PathAwareValue,
+    reference: &str,
+) -> Option<&'value PathAwareValue> {
+    let pointer = urlencoding::decode(reference.strip_prefix('#')?).ok()?;
+    if pointer.is_empty() {
+        return Some(root);
+    }
+
+    pointer
+        .strip_prefix('/')?
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .try_fold(root, |value, token| match value {
+            PathAwareValue::Map((_, map)) => map.values.get(&token),
+            PathAwareValue::List((_, list)) => list.get(token.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+#[path = "json_refs_tests.rs"]
+mod json_refs_tests;
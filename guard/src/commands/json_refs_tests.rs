@@ -0,0 +1,104 @@
+use super::*;
+use pretty_assertions::assert_eq;
+use std::convert::{TryFrom, TryInto};
+
+fn value(yaml: &str) -> PathAwareValue {
+    PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(yaml).unwrap()).unwrap()
+}
+
+fn json(value: &PathAwareValue) -> serde_json::Value {
+    let (_, json): (String, serde_json::Value) = value.try_into().unwrap();
+    json
+}
+
+const SPEC: &str = r##"
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Pets" }
+components:
+  schemas:
+    Pets:
+      type: array
+      items: { $ref: "#/components/schemas/Pet" }
+    Pet:
+      type: object
+      properties:
+        id: { type: integer }
+        parent: { $ref: "#/components/schemas/Pet" }
+        remote: { $ref: "https://example.com/pet.json" }
+        missing: { $ref: "#/components/schemas/Missing" }
+    "a/b~c":
+      type: string
+"##;
+
+#[test]
+fn test_resolve_json_refs() {
+    let resolved = resolve_json_refs(value(SPEC));
+    let schema = json(&resolved)
+        .pointer("/paths/~1pets/get/responses/200/content/application~1json/schema")
+        .cloned()
+        .unwrap();
+
+    assert_eq!(
+        serde_json::json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer" },
+                    "parent": { "$ref": "#/components/schemas/Pet" },
+                    "remote": { "$ref": "https://example.com/pet.json" },
+                    "missing": { "$ref": "#/components/schemas/Missing" },
+                }
+            }
+        }),
+        schema
+    );
+}
+
+#[test]
+fn test_resolved_values_are_at_the_path_of_the_ref() {
+    let resolved = resolve_json_refs(value(SPEC));
+    let items = local_target(&resolved, "#/components/schemas/Pets/items").unwrap();
+
+    assert_eq!("/components/schemas/Pets/items", items.self_path().0);
+    assert_eq!(
+        "/components/schemas/Pets/items/properties/id/type",
+        local_target(
+            &resolved,
+            "#/components/schemas/Pets/items/properties/id/type"
+        )
+        .unwrap()
+        .self_path()
+        .0
+    );
+}
+
+#[test]
+fn test_local_target() {
+    let spec = value(SPEC);
+    for (reference, expected) in [
+        (
+            "#/components/schemas/a~1b~0c/type",
+            Some(serde_json::json!("string")),
+        ),
+        (
+            "#/components/schemas/a%7E1b~0c/type",
+            Some(serde_json::json!("string")),
+        ),
+        (
+            "#/paths/~1pets/get/responses/200/content/application~1json/schema/$ref",
+            Some(serde_json::json!("#/components/schemas/Pets")),
+        ),
+        ("#/components/schemas/Missing", None),
+        ("components/schemas/Pet", None),
+    ] {
+        assert_eq!(expected, local_target(&spec, reference).map(json));
+    }
+    assert!(local_target(&spec, "#").is_some());
+}
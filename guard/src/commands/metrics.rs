@@ -0,0 +1,254 @@
+use std::net::UdpSocket;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+use crate::commands::summary::Summary;
+use crate::rules::errors::Error;
+use crate::rules::Result;
+
+/// where `--metrics statsd` sends to when `--metrics-endpoint` is not set
+pub(crate) const DEFAULT_STATSD_ENDPOINT: &str = "127.0.0.1:8125";
+/// where `--metrics otlp` posts to when `--metrics-endpoint` is not set, the default of the
+/// OTLP/HTTP receiver of the OpenTelemetry collector
+pub(crate) const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318/v1/metrics";
+/// prefix of the name of every metric
+const PREFIX: &str = "cfn_guard";
+
+/// the protocol the metrics of a run are exported with, see `--metrics`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub(crate) enum MetricsSink {
+    /// datagrams of the StatsD line protocol, with the DogStatsD `#tags` extension for the name
+    /// of the rule
+    Statsd,
+    /// the OTLP/HTTP JSON encoding, posted to an OpenTelemetry collector
+    Otlp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MetricValue {
+    Count(usize),
+    Duration(Duration),
+}
+
+/// a counter or a latency of the run, with the attributes it is broken down by
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Metric {
+    name: String,
+    value: MetricValue,
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl Metric {
+    fn count(name: &str, count: usize) -> Metric {
+        Metric {
+            name: format!("{PREFIX}.{name}"),
+            value: MetricValue::Count(count),
+            attributes: vec![],
+        }
+    }
+
+    fn duration(name: &str, duration: Duration) -> Metric {
+        Metric {
+            name: format!("{PREFIX}.{name}"),
+            value: MetricValue::Duration(duration),
+            attributes: vec![],
+        }
+    }
+
+    fn with(mut self, key: &'static str, value: &str) -> Metric {
+        self.attributes.push((key, value.to_string()));
+        self
+    }
+}
+
+/// the metrics of the run: how many data files and rules were evaluated, how many rules failed,
+/// how many times each rule failed, and how long the run and each rule took
+pub(crate) fn metrics(summary: &Summary) -> Vec<Metric> {
+    let rules_evaluated =
+        summary.rules_passed + summary.rules_failed + summary.rules_warned + summary.rules_skipped;
+
+    let mut metrics = vec![
+        Metric::count("files_evaluated", summary.files_evaluated),
+        Metric::count("rules_evaluated", rules_evaluated),
+        Metric::count("rules_failed", summary.rules_failed),
+        Metric::count("rules_warned", summary.rules_warned),
+        Metric::count("rules_skipped", summary.rules_skipped),
+        Metric::duration("duration", summary.duration),
+    ];
+    metrics.extend(
+        summary
+            .failures_by_rule
+            .iter()
+            .map(|(rule, failures)| Metric::count("rule_failures", *failures).with("rule", rule)),
+    );
+    metrics.extend(
+        summary
+            .rule_durations
+            .iter()
+            .flat_map(|(rules_file, rules)| {
+                rules.iter().map(move |(rule, duration)| {
+                    Metric::duration("rule_duration", *duration)
+                        .with("rule", rule)
+                        .with("rules_file", rules_file)
+                })
+            }),
+    );
+
+    metrics
+}
+
+/// sends the metrics of the run to the endpoint, or to the default one of the sink
+///
+/// This function will return an error if the endpoint cannot be reached, or does not accept
+/// the metrics
+pub(crate) fn export(sink: MetricsSink, endpoint: Option<&str>, summary: &Summary) -> Result<()> {
+    let metrics = metrics(summary);
+    match sink {
+        MetricsSink::Statsd => {
+            let endpoint = endpoint.unwrap_or(DEFAULT_STATSD_ENDPOINT);
+            let socket = UdpSocket::bind("0.0.0.0:0")?;
+            for metric in &metrics {
+                socket.send_to(statsd_line(metric).as_bytes(), endpoint)?;
+            }
+            Ok(())
+        }
+        MetricsSink::Otlp => {
+            let end = SystemTime::now();
+            let start = end.checked_sub(summary.duration).unwrap_or(end);
+            post(
+                endpoint.unwrap_or(DEFAULT_OTLP_ENDPOINT),
+                &otlp_payload(&metrics, start, end),
+            )
+        }
+    }
+}
+
+/// the metric in the StatsD line protocol, e.g. `cfn_guard.rule_failures:2|c|#rule:s3_encrypted`
+pub(crate) fn statsd_line(metric: &Metric) -> String {
+    let mut line = match metric.value {
+        MetricValue::Count(count) => format!("{}:{count}|c", metric.name),
+        MetricValue::Duration(duration) => format!("{}:{}|ms", metric.name, millis(duration)),
+    };
+    if !metric.attributes.is_empty() {
+        let tags = metric
+            .attributes
+            .iter()
+            .map(|(key, value)| format!("{key}:{}", statsd_tag_value(value)))
+            .collect::<Vec<_>>();
+        line.push_str(&format!("|#{}", tags.join(",")));
+    }
+
+    line
+}
+
+/// the value with the characters that delimit the parts of a StatsD line replaced
+fn statsd_tag_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '|' | ',' | '#' | '@' => '_',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1_000_000.0
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+/// the metrics in the OTLP/HTTP JSON encoding, counts as delta sums over the run and durations
+/// as gauges in milliseconds
+pub(crate) fn otlp_payload(metrics: &[Metric], start: SystemTime, end: SystemTime) -> Value {
+    let (start, end) = (unix_nanos(start), unix_nanos(end));
+    let metrics = metrics
+        .iter()
+        .map(|metric| {
+            let attributes = metric
+                .attributes
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+                .collect::<Vec<_>>();
+            match metric.value {
+                MetricValue::Count(count) => json!({
+                    "name": metric.name,
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": 1,
+                        "isMonotonic": true,
+                        "dataPoints": [{
+                            "attributes": attributes,
+                            "startTimeUnixNano": start,
+                            "timeUnixNano": end,
+                            "asInt": count.to_string(),
+                        }],
+                    },
+                }),
+                MetricValue::Duration(duration) => json!({
+                    "name": metric.name,
+                    "unit": "ms",
+                    "gauge": {
+                        "dataPoints": [{
+                            "attributes": attributes,
+                            "timeUnixNano": end,
+                            "asDouble": millis(duration),
+                        }],
+                    },
+                }),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "resourceMetrics": [{
+            "resource": {
+                "attributes": [{ "key": "service.name", "value": { "stringValue": "cfn-guard" } }],
+            },
+            "scopeMetrics": [{
+                "scope": { "name": "cfn-guard", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": metrics,
+            }],
+        }],
+    })
+}
+
+#[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+fn post(endpoint: &str, payload: &Value) -> Result<()> {
+    ureq::post(endpoint)
+        .set("Content-Type", "application/json")
+        .send_string(&payload.to_string())
+        .map_err(|e| {
+            Error::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("unable to send metrics to {endpoint}, {e}"),
+            ))
+        })?;
+    Ok(())
+}
+
+#[cfg(all(not(feature = "remote"), not(target_arch = "wasm32")))]
+fn post(endpoint: &str, _payload: &Value) -> Result<()> {
+    Err(Error::IllegalArguments(format!(
+        "unable to send metrics to {endpoint}, the OTLP sink requires cfn-guard to be built with the remote feature, e.g. cargo install cfn-guard --features remote"
+    )))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn post(endpoint: &str, _payload: &Value) -> Result<()> {
+    Err(Error::UnsupportedOperationError(format!(
+        "unable to send metrics to {endpoint}, OTLP is not supported in this environment"
+    )))
+}
+
+#[cfg(test)]
+#[path = "metrics_tests.rs"]
+mod metrics_tests;
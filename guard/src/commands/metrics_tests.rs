@@ -0,0 +1,119 @@
+use super::*;
+use pretty_assertions::assert_eq;
+use std::collections::BTreeMap;
+
+fn summary() -> Summary {
+    Summary {
+        files_evaluated: 2,
+        rules_passed: 3,
+        rules_failed: 2,
+        rules_warned: 1,
+        rules_skipped: 1,
+        failures_by_rule: BTreeMap::from([
+            (String::from("s3_encrypted"), 2),
+            (String::from("s3_tagged"), 1),
+        ]),
+        duration: Duration::from_millis(1500),
+        rule_durations: BTreeMap::from([(
+            String::from("rules/s3 rules.guard"),
+            BTreeMap::from([(String::from("s3_encrypted"), Duration::from_millis(12))]),
+        )]),
+        ..Summary::default()
+    }
+}
+
+#[test]
+fn test_statsd_lines() {
+    let lines = metrics(&summary())
+        .iter()
+        .map(statsd_line)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            "cfn_guard.files_evaluated:2|c",
+            "cfn_guard.rules_evaluated:7|c",
+            "cfn_guard.rules_failed:2|c",
+            "cfn_guard.rules_warned:1|c",
+            "cfn_guard.rules_skipped:1|c",
+            "cfn_guard.duration:1500|ms",
+            "cfn_guard.rule_failures:2|c|#rule:s3_encrypted",
+            "cfn_guard.rule_failures:1|c|#rule:s3_tagged",
+            "cfn_guard.rule_duration:12|ms|#rule:s3_encrypted,rules_file:rules/s3_rules.guard",
+        ],
+        lines
+    );
+}
+
+#[test]
+fn test_otlp_payload() {
+    let end = UNIX_EPOCH + Duration::from_secs(10);
+    let start = UNIX_EPOCH + Duration::from_secs(8);
+    let payload = otlp_payload(&metrics(&summary()), start, end);
+
+    let scope = &payload["resourceMetrics"][0]["scopeMetrics"][0];
+    assert_eq!("cfn-guard", scope["scope"]["name"]);
+    let metrics = scope["metrics"].as_array().unwrap();
+    assert_eq!(9, metrics.len());
+
+    assert_eq!(
+        json!({
+            "name": "cfn_guard.rule_failures",
+            "unit": "1",
+            "sum": {
+                "aggregationTemporality": 1,
+                "isMonotonic": true,
+                "dataPoints": [{
+                    "attributes": [{ "key": "rule", "value": { "stringValue": "s3_encrypted" } }],
+                    "startTimeUnixNano": "8000000000",
+                    "timeUnixNano": "10000000000",
+                    "asInt": "2",
+                }],
+            },
+        }),
+        metrics[6]
+    );
+    assert_eq!("ms", metrics[5]["unit"]);
+    assert_eq!(1500.0, metrics[5]["gauge"]["dataPoints"][0]["asDouble"]);
+}
+
+#[test]
+fn test_export_statsd() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    server
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let endpoint = server.local_addr().unwrap().to_string();
+
+    export(MetricsSink::Statsd, Some(&endpoint), &summary()).unwrap();
+
+    let mut buffer = [0; 512];
+    let size = server.recv(&mut buffer).unwrap();
+    assert_eq!(
+        "cfn_guard.files_evaluated:2|c",
+        String::from_utf8_lossy(&buffer[..size])
+    );
+}
+
+#[test]
+fn test_export_otlp_unreachable() {
+    assert!(export(
+        MetricsSink::Otlp,
+        Some("http://127.0.0.1:1/v1/metrics"),
+        &summary()
+    )
+    .is_err());
+}
+
+#[cfg(not(feature = "remote"))]
+#[test]
+fn test_export_otlp_without_remote_feature() {
+    let err = export(
+        MetricsSink::Otlp,
+        Some("http://127.0.0.1:1/v1/metrics"),
+        &summary(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::IllegalArguments(_)));
+    assert!(err.to_string().contains("the remote feature"));
+}
@@ -1,24 +1,56 @@
+use std::io::IsTerminal;
+
 use clap::{Parser, Subcommand};
 
 use crate::{
     commands::{
-        completions::Completions, parse_tree::ParseTree, rulegen::Rulegen, test::Test,
-        validate::Validate,
+        build::Build, completions::Completions, conformance::Conformance, docs::Docs,
+        generate::Generate, hook::Hook, parse_tree::ParseTree, profile::Profile,
+        results_diff::ResultsDiff, rulegen::Rulegen, rules::Rules, sign::Sign, suggest::Suggest,
+        test::Test, validate::Validate,
     },
     utils::{reader::Reader, writer::Writer},
 };
 
+pub(crate) mod adapters;
+pub(crate) mod baseline;
+pub mod build;
+pub mod conformance;
+pub mod docs;
 pub(crate) mod files;
+pub mod generate;
 pub(crate) mod helper;
+pub mod hook;
 pub mod parse_tree;
+pub mod profile;
+pub mod registry;
+pub mod results_diff;
 pub mod rulegen;
+pub mod rules;
+pub mod rules_diff;
+pub mod sign;
+pub mod suggest;
+pub mod summary;
 pub mod test;
 pub mod validate;
 
 mod aws_meta_appender;
 mod common_test_helpers;
 pub mod completions;
+mod config;
+mod config_rules;
+mod json_refs;
+mod metrics;
+mod progress;
+mod remote;
 pub mod reporters;
+mod stack;
+mod template_conditions;
+mod template_parameters;
+mod test_manifest;
+mod test_mutation;
+mod test_skeleton;
+mod test_spec;
 mod tracker;
 
 //
@@ -36,11 +68,13 @@ pub const LAST_MODIFIED: (&str, char) = ("last-modified", 'm');
 pub const OUTPUT_FORMAT: (&str, char) = ("output-format", 'o');
 #[allow(dead_code)]
 pub const INPUT_PARAMETERS: (&str, char) = ("input-parameters", 'i');
+pub const OUTPUT_FILE: (&str, char) = ("output-file", 'O');
 pub const PAYLOAD: (&str, char) = ("payload", 'P');
 pub const PRINT_JSON: (&str, char) = ("print-json", 'p');
 pub const SHOW_SUMMARY: (&str, char) = ("show-summary", 'S');
 pub const TYPE: (&str, char) = ("type", 't');
 pub const VERBOSE: (&str, char) = ("verbose", 'v');
+pub const FAIL_ON: (&str, char) = ("fail-on", 'F');
 // Arguments for validate, parse tree
 pub const RULES: (&str, char) = ("rules", 'r');
 // Arguments for parse-tree, rulegen
@@ -52,11 +86,18 @@ pub const PRINT_YAML: (&str, char) = ("print-yaml", 'y');
 pub const RULES_FILE: (&str, char) = ("rules-file", 'r');
 pub const TEST_DATA: (&str, char) = ("test-data", 't');
 pub const DIRECTORY: (&str, char) = ("dir", 'd');
+pub const INIT: (&str, char) = ("init", 'i');
 // Arguments for rulegen
-#[allow(dead_code)]
 pub const TEMPLATE: (&str, char) = ("template", 't');
+pub const FROM_CONFIG_RULE: (&str, char) = ("from-config-rule", 'c');
+// Arguments for hook
+pub const CONFIG: (&str, char) = ("config", 'c');
+// suggest
+pub const GOOD: (&str, char) = ("good", 'g');
 // Arg group for validate
 pub(crate) const REQUIRED_FLAGS: &str = "required_flags";
+// Arg group for rulegen
+pub(crate) const RULEGEN_SOURCE: &str = "rulegen_source";
 // Arg group for test
 pub const RULES_AND_TEST_FILE: &str = "rules-and-test-file";
 pub const DIRECTORY_ONLY: &str = "directory-only";
@@ -65,12 +106,53 @@ pub const STRUCTURED: (&str, char) = ("structured", 'z');
 pub(crate) const DATA_FILE_SUPPORTED_EXTENSIONS: [&str; 5] =
     [".yaml", ".yml", ".json", ".jsn", ".template"];
 pub(crate) const RULE_FILE_SUPPORTED_EXTENSIONS: [&str; 2] = [".guard", ".ruleset"];
+/// the extension of the rules packs built by `cfn-guard build`
+pub(crate) const RULE_PACK_EXTENSION: &str = ".pack";
+
+pub const FAILURE_STATUS_CODE: i32 = ExitCode::Failure.code();
+pub const SUCCESS_STATUS_CODE: i32 = ExitCode::Success.code();
+pub const ERROR_STATUS_CODE: i32 = ExitCode::Error.code();
+pub const TEST_ERROR_STATUS_CODE: i32 = ExitCode::TestError.code();
+pub const TEST_FAILURE_STATUS_CODE: i32 = ExitCode::TestFailure.code();
 
-pub const FAILURE_STATUS_CODE: i32 = 19;
-pub const SUCCESS_STATUS_CODE: i32 = 0;
-pub const ERROR_STATUS_CODE: i32 = 5;
-pub const TEST_ERROR_STATUS_CODE: i32 = 1;
-pub const TEST_FAILURE_STATUS_CODE: i32 = 7;
+/// .
+/// The exit codes returned by cfn-guard. These are stable across releases, wrappers should match
+/// on them rather than hard-coding the numbers
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExitCode {
+    /// every rule passed or was skipped, or every test case met its expectations
+    Success,
+    /// `test` was not able to evaluate some of the test cases
+    TestError,
+    /// `validate` was not able to evaluate some of the rules, e.g. a rules file failed to parse
+    Error,
+    /// `test` found test cases whose expectations were not met
+    TestFailure,
+    /// `validate` found rules that were violated
+    Failure,
+    /// the command was not able to run, e.g. because of conflicting arguments or unreadable files
+    InternalError,
+}
+
+impl ExitCode {
+    /// the process exit code
+    pub const fn code(self) -> i32 {
+        match self {
+            ExitCode::Success => 0,
+            ExitCode::TestError => 1,
+            ExitCode::Error => 5,
+            ExitCode::TestFailure => 7,
+            ExitCode::Failure => 19,
+            ExitCode::InternalError => -1,
+        }
+    }
+}
+
+impl From<ExitCode> for i32 {
+    fn from(exit_code: ExitCode) -> Self {
+        exit_code.code()
+    }
+}
 
 const ABOUT: &str = r#"
 Guard is a general-purpose tool that provides a simple declarative syntax to define
@@ -87,35 +169,101 @@ that equally works for any JSON- and YAML- data."#;
 pub struct CfnGuard {
     #[command(subcommand)]
     pub(crate) command: Commands,
+    /// print no colors or other escape codes, also the case when the NO_COLOR environment variable
+    /// is set or stdout is not a terminal
+    #[arg(long, global=true, help=NO_COLOR_HELP)]
+    pub(crate) no_color: bool,
+    /// print nothing but errors to the console, relying on the exit code and the files written
+    #[arg(long, global=true, help=QUIET_HELP)]
+    pub(crate) quiet: bool,
 }
 
+const NO_COLOR_HELP: &str = "Print no colors or other escape codes to the console, which is also the case when the NO_COLOR environment variable is set or stdout is not a terminal";
+const QUIET_HELP: &str = "Print nothing to the console but errors, relying on the exit code and any output files written";
+
 impl CfnGuard {
     pub fn execute(&self, writer: &mut Writer, reader: &mut Reader) -> crate::rules::Result<i32> {
+        if self.no_color || no_color_env() || !std::io::stdout().is_terminal() {
+            colored::control::set_override(false);
+        }
+        if self.quiet {
+            writer.set_quiet(true);
+        }
         self.command.execute(writer, reader)
     }
 }
 
+/// whether the NO_COLOR environment variable asks for no colors, see https://no-color.org
+fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty())
+}
+
 #[derive(Debug, Subcommand)]
 pub(crate) enum Commands {
     Validate(Validate),
     Test(Test),
     ParseTree(ParseTree),
     Rulegen(Rulegen),
+    Suggest(Suggest),
+    Rules(Rules),
+    Hook(Hook),
     Completions(Completions),
+    Generate(Generate),
+    Conformance(Conformance),
+    Docs(Docs),
+    Diff(ResultsDiff),
+    Profile(Profile),
+    Build(Build),
+    Sign(Sign),
 }
 
 pub trait Executable {
     fn execute(&self, writer: &mut Writer, reader: &mut Reader) -> crate::rules::Result<i32>;
 }
 
+/// runs a command on the blocking thread pool of the tokio runtime, so that services embedding
+/// Guard can await it in a request handler without holding up a worker thread. The writer is
+/// handed back with the exit code to read the output of the command from
+#[cfg(feature = "async")]
+pub trait AsyncExecutable: Executable + Clone + Send + 'static {
+    fn execute_async(
+        &self,
+        mut writer: Writer,
+        mut reader: Reader,
+    ) -> impl std::future::Future<Output = crate::rules::Result<(i32, Writer)>> + Send {
+        let command = self.clone();
+        async move {
+            tokio::task::spawn_blocking(move || {
+                let exit_code = command.execute(&mut writer, &mut reader)?;
+                Ok((exit_code, writer))
+            })
+            .await
+            .map_err(std::io::Error::from)?
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: Executable + Clone + Send + 'static> AsyncExecutable for T {}
+
 impl Executable for Commands {
     fn execute(&self, writer: &mut Writer, reader: &mut Reader) -> crate::rules::Result<i32> {
         match self {
-            Commands::Validate(cmd) => cmd.execute(writer, reader),
+            Commands::Validate(cmd) => cmd.with_config()?.execute(writer, reader),
             Commands::Test(cmd) => cmd.execute(writer, reader),
             Commands::ParseTree(cmd) => cmd.execute(writer, reader),
             Commands::Rulegen(cmd) => cmd.execute(writer, reader),
-            Commands::Completions(cmd) => cmd.execute(),
+            Commands::Suggest(cmd) => cmd.execute(writer, reader),
+            Commands::Rules(cmd) => cmd.execute(writer, reader),
+            Commands::Hook(cmd) => cmd.execute(writer, reader),
+            Commands::Completions(cmd) => cmd.execute(writer),
+            Commands::Generate(cmd) => cmd.execute(writer, reader),
+            Commands::Conformance(cmd) => cmd.execute(writer, reader),
+            Commands::Docs(cmd) => cmd.execute(writer, reader),
+            Commands::Diff(cmd) => cmd.execute(writer, reader),
+            Commands::Profile(cmd) => cmd.execute(writer, reader),
+            Commands::Build(cmd) => cmd.execute(writer, reader),
+            Commands::Sign(cmd) => cmd.execute(writer, reader),
         }
     }
 }
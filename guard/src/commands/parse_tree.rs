@@ -1,15 +1,35 @@
 use crate::commands::{Executable, PRINT_JSON, PRINT_YAML, SUCCESS_STATUS_CODE};
+use crate::rules::display::{
+    guard_clause_source, let_value_source, query_source, when_clause_source,
+};
+use crate::rules::errors::Error;
+use crate::rules::exprs::{
+    Block, Conjunctions, GuardClause, LetExpr, Rule, RuleClause, RulesFile, WhenConditions,
+    WhenGuardClause,
+};
 use crate::rules::Result;
 use crate::utils::reader::Reader;
 use crate::utils::writer::Writer;
-use clap::Args;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
 use std::fs::File;
+use std::io::Write;
 
 const ABOUT: &str = "Prints out the parse tree for the rules defined in the file.";
 const OUTPUT_HELP: &str = "Write to output file";
 const PRINT_JSON_HELP: &str = "Print output in JSON format. Use -p as the short flag";
 const PRINT_YAML_HELP: &str = "Print output in YAML format";
 const RULES_HELP: &str = "Provide a rules file";
+const QUERY_HELP: &str =
+    "Only print the tree of the rule with the given name, e.g. --query s3_encrypted";
+const FORMAT_HELP: &str = "The format of the tree, dot prints a Graphviz digraph of the rules and the nesting of their clauses, e.g. cfn-guard parse-tree -r rules.guard --format dot | dot -Tsvg > rules.svg. Defaults to yaml";
+
+#[derive(Copy, Eq, Clone, Debug, PartialEq, ValueEnum)]
+pub enum ParseTreeFormat {
+    Yaml,
+    Json,
+    Dot,
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Args)]
 #[clap(about=ABOUT)]
@@ -34,6 +54,14 @@ pub struct ParseTree {
     // default true
     #[arg(short=PRINT_YAML.1, long=PRINT_YAML.0, help=PRINT_YAML_HELP)]
     pub(crate) print_yaml: bool,
+    // the name of the rule whose tree is printed
+    // default None, the tree of the whole rules file is printed
+    #[arg(long, help=QUERY_HELP)]
+    pub(crate) query: Option<String>,
+    // the format of the tree, print_json and print_yaml are shorthands for it
+    // default None, yaml unless print_json is set
+    #[arg(long, help=FORMAT_HELP, value_enum, conflicts_with_all=["print_json", "print_yaml"])]
+    pub(crate) format: Option<ParseTreeFormat>,
 }
 
 impl Executable for ParseTree {
@@ -54,12 +82,255 @@ impl Executable for ParseTree {
         let span = crate::rules::parser::Span::new_extra(&content, "");
 
         let rules = crate::rules::parser::rules_file(span)?;
+        let format = match (self.format, self.print_json) {
+            (Some(format), _) => format,
+            (None, true) => ParseTreeFormat::Json,
+            (None, false) => ParseTreeFormat::Yaml,
+        };
+
+        let name = match &self.query {
+            Some(name) => name,
+            None => {
+                match format {
+                    ParseTreeFormat::Dot => {
+                        let mut dot = Dot::default();
+                        if let Some(rules) = &rules {
+                            dot.rules_file(rules);
+                        }
+                        write!(writer, "{}", dot.digraph())?;
+                    }
+                    _ => print(&rules, format, writer)?,
+                }
+                return Ok(SUCCESS_STATUS_CODE);
+            }
+        };
+
+        let rules = rules.as_ref();
+        let rule = rules.and_then(|rules| {
+            rules
+                .guard_rules
+                .iter()
+                .find(|rule| &rule.rule_name == name)
+        });
+        let parameterized = rules.and_then(|rules| {
+            rules
+                .parameterized_rules
+                .iter()
+                .find(|parameterized| &parameterized.rule.rule_name == name)
+        });
 
-        match self.print_json {
-            true => serde_json::to_writer_pretty(writer, &rules)?,
-            false => serde_yaml::to_writer(writer, &rules)?,
+        match (rule, parameterized, format) {
+            (Some(rule), _, ParseTreeFormat::Dot) => {
+                let mut dot = Dot::default();
+                dot.rule(None, rule, &[]);
+                write!(writer, "{}", dot.digraph())?;
+            }
+            (None, Some(parameterized), ParseTreeFormat::Dot) => {
+                let mut dot = Dot::default();
+                let parameters = parameterized.parameter_names.iter().collect::<Vec<_>>();
+                dot.rule(None, &parameterized.rule, &parameters);
+                write!(writer, "{}", dot.digraph())?;
+            }
+            (Some(rule), _, _) => print(rule, format, writer)?,
+            (None, Some(parameterized), _) => print(parameterized, format, writer)?,
+            (None, None, _) => {
+                return Err(Error::IllegalArguments(format!(
+                    "no rule named {name} in the rules file"
+                )))
+            }
         }
 
         Ok(SUCCESS_STATUS_CODE)
     }
 }
+
+fn print<T: Serialize>(tree: &T, format: ParseTreeFormat, writer: &mut Writer) -> Result<()> {
+    match format {
+        ParseTreeFormat::Json => serde_json::to_writer_pretty(writer, tree)?,
+        _ => serde_yaml::with::singleton_map_recursive::serialize(
+            tree,
+            &mut serde_yaml::Serializer::new(writer),
+        )?,
+    }
+
+    Ok(())
+}
+
+/// a Graphviz digraph of rules, every rule a box with its clauses below it. Conjunctions and
+/// disjunctions of more than one clause get an `and` or `or` node, and `when` conditions a diamond
+/// with the clauses they guard below its `then` node, so that the nesting the parser saw can be
+/// compared with the one intended
+#[derive(Debug, Default)]
+pub(crate) struct Dot {
+    lines: Vec<String>,
+    nodes: usize,
+}
+
+impl Dot {
+    pub(crate) fn digraph(&self) -> String {
+        format!(
+            "digraph rules {{\n  node [fontname=\"monospace\", shape=plaintext];\n{}}}\n",
+            self.lines
+                .iter()
+                .map(|line| format!("  {line}\n"))
+                .collect::<String>()
+        )
+    }
+
+    fn child(&mut self, parent: Option<usize>, label: &str, attributes: &str) -> usize {
+        let id = self.nodes;
+        self.nodes += 1;
+        let label = label
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        self.lines
+            .push(format!("n{id} [label=\"{label}\"{attributes}];"));
+        if let Some(parent) = parent {
+            self.lines.push(format!("n{parent} -> n{id};"));
+        }
+        id
+    }
+
+    pub(crate) fn rules_file(&mut self, rules: &RulesFile<'_>) {
+        let root = Some(self.child(None, "rules file", ", shape=folder"));
+        self.assignments(root, &rules.assignments);
+        for rule in &rules.guard_rules {
+            self.rule(root, rule, &[]);
+        }
+        for parameterized in &rules.parameterized_rules {
+            let parameters = parameterized.parameter_names.iter().collect::<Vec<_>>();
+            self.rule(root, &parameterized.rule, &parameters);
+        }
+    }
+
+    pub(crate) fn rule(&mut self, parent: Option<usize>, rule: &Rule<'_>, parameters: &[&String]) {
+        let label = match parameters.is_empty() {
+            true => format!("rule {}", rule.rule_name),
+            false => format!(
+                "rule {}({})",
+                rule.rule_name,
+                parameters
+                    .iter()
+                    .map(|parameter| parameter.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let id = Some(self.child(parent, &label, ", shape=box, style=bold"));
+        match &rule.conditions {
+            Some(conditions) => {
+                let then = self.when(id, conditions);
+                self.block(then, &rule.block, Self::rule_clause);
+            }
+            None => self.block(id, &rule.block, Self::rule_clause),
+        }
+    }
+
+    fn assignments(&mut self, parent: Option<usize>, assignments: &[LetExpr<'_>]) {
+        for assignment in assignments {
+            self.child(
+                parent,
+                &format!(
+                    "let {} = {}",
+                    assignment.var,
+                    let_value_source(&assignment.value)
+                ),
+                ", shape=note",
+            );
+        }
+    }
+
+    /// the diamond of the conditions, returns the `then` node the clauses they guard go below
+    fn when(
+        &mut self,
+        parent: Option<usize>,
+        conditions: &WhenConditions<'_>,
+        block: &Block<'_, GuardClause<'_>>,
+        otherwise: Option<&Block<'_, GuardClause<'_>>>,
+    ) {
+        let when = Some(self.child(parent, "when", ", shape=diamond"));
+        self.conjunctions(when, conditions, Self::when_clause);
+        let then = Some(self.child(when, "then", ", shape=ellipse"));
+        self.block(then, block, Self::guard_clause);
+        if let Some(otherwise) = otherwise {
+            let otherwise_id = Some(self.child(when, "else", ", shape=ellipse"));
+            self.block(otherwise_id, otherwise, Self::guard_clause);
+        }
+    }
+
+    fn block<T>(
+        &mut self,
+        parent: Option<usize>,
+        block: &Block<'_, T>,
+        render: fn(&mut Self, Option<usize>, &T),
+    ) {
+        self.assignments(parent, &block.assignments);
+        self.conjunctions(parent, &block.conjunctions, render);
+    }
+
+    fn conjunctions<T>(
+        &mut self,
+        parent: Option<usize>,
+        conjunctions: &Conjunctions<T>,
+        render: fn(&mut Self, Option<usize>, &T),
+    ) {
+        let and = match conjunctions.len() > 1 {
+            true => Some(self.child(parent, "and", ", shape=ellipse")),
+            false => parent,
+        };
+        for disjunctions in conjunctions {
+            let or = match disjunctions.len() > 1 {
+                true => Some(self.child(and, "or", ", shape=ellipse")),
+                false => and,
+            };
+            for clause in disjunctions {
+                render(self, or, clause);
+            }
+        }
+    }
+
+    fn rule_clause(&mut self, parent: Option<usize>, clause: &RuleClause<'_>) {
+        match clause {
+            RuleClause::Clause(clause) => self.guard_clause(parent, clause),
+            RuleClause::WhenBlock(conditions, block, otherwise) => {
+                self.when(parent, conditions, block, otherwise.as_ref())
+            }
+            RuleClause::TypeBlock(type_block) => {
+                let id = Some(self.child(parent, &type_block.type_name, ", shape=box"));
+                match &type_block.conditions {
+                    Some(conditions) => self.when(id, conditions, &type_block.block, None),
+                    None => self.block(id, &type_block.block, Self::guard_clause),
+                }
+            }
+        }
+    }
+
+    fn guard_clause(&mut self, parent: Option<usize>, clause: &GuardClause<'_>) {
+        match clause {
+            GuardClause::BlockClause(block_clause) => {
+                let label = format!(
+                    "{}{}",
+                    if block_clause.negation { "not " } else { "" },
+                    query_source(&block_clause.query.query)
+                );
+                let id = Some(self.child(parent, &label, ", shape=box"));
+                self.block(id, &block_clause.block, Self::guard_clause);
+            }
+            GuardClause::WhenBlock(conditions, block, otherwise) => {
+                self.when(parent, conditions, block, otherwise.as_ref())
+            }
+            clause => {
+                self.child(parent, &guard_clause_source(clause), "");
+            }
+        }
+    }
+
+    fn when_clause(&mut self, parent: Option<usize>, clause: &WhenGuardClause<'_>) {
+        self.child(parent, &when_clause_source(clause), "");
+    }
+}
+
+#[cfg(test)]
+#[path = "parse_tree_tests.rs"]
+mod parse_tree_tests;
@@ -0,0 +1,86 @@
+use super::*;
+use crate::rules::parser::{rules_file, Span};
+use pretty_assertions::assert_eq;
+
+const RULES: &str = r#"
+let buckets = Resources.*[ Type == /S3::Bucket/ ]
+
+rule s3_encrypted when Properties exists {
+    Properties.BucketEncryption exists
+    Properties.Size > 10 or Properties.KmsKeyId exists
+}
+
+rule s3_tagged(tags) {
+    %buckets.Properties.Tags[*] {
+        Key in %tags
+    }
+}
+"#;
+
+#[test]
+fn test_dot_rule() {
+    let rules = rules_file(Span::new_extra(RULES, "")).unwrap().unwrap();
+    let mut dot = Dot::default();
+    dot.rule(None, &rules.guard_rules[0], &[]);
+
+    assert_eq!(
+        r#"digraph rules {
+  node [fontname="monospace", shape=plaintext];
+  n0 [label="rule s3_encrypted", shape=box, style=bold];
+  n1 [label="when", shape=diamond];
+  n0 -> n1;
+  n2 [label="Properties exists"];
+  n1 -> n2;
+  n3 [label="then", shape=ellipse];
+  n1 -> n3;
+  n4 [label="and", shape=ellipse];
+  n3 -> n4;
+  n5 [label="Properties.BucketEncryption exists"];
+  n4 -> n5;
+  n6 [label="or", shape=ellipse];
+  n4 -> n6;
+  n7 [label="Properties.Size > 10"];
+  n6 -> n7;
+  n8 [label="Properties.KmsKeyId exists"];
+  n6 -> n8;
+}
+"#,
+        dot.digraph()
+    );
+}
+
+#[test]
+fn test_dot_rules_file() {
+    let rules = rules_file(Span::new_extra(RULES, "")).unwrap().unwrap();
+    let mut dot = Dot::default();
+    dot.rules_file(&rules);
+    let digraph = dot.digraph();
+
+    assert!(
+        digraph.contains("n0 [label=\"rules file\", shape=folder];"),
+        "{digraph}"
+    );
+    assert!(
+        digraph.contains(
+            "n1 [label=\"let buckets = Resources.*[ Type == /S3::Bucket/ ]\", shape=note];"
+        ),
+        "{digraph}"
+    );
+    assert!(
+        digraph.contains("[label=\"rule s3_tagged(tags)\", shape=box, style=bold];"),
+        "{digraph}"
+    );
+    assert!(
+        digraph.contains("[label=\"%buckets.Properties.Tags[*]\", shape=box];"),
+        "{digraph}"
+    );
+    assert!(digraph.contains("[label=\"Key in %tags\"];"), "{digraph}");
+}
+
+#[test]
+fn test_dot_label_escaping() {
+    let mut dot = Dot::default();
+    dot.child(None, "Name == \"a\\b\"\nnext", "");
+
+    assert_eq!(vec![r#"n0 [label="Name == \"a\\b\"\nnext"];"#], dot.lines);
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Duration;
+
+use clap::{Args, ValueEnum};
+
+use crate::commands::adapters::adapt_data_files;
+use crate::commands::files::read_file_content;
+use crate::commands::validate::{
+    argument_files, build_data_file, get_rule_info, parse_rule_files, DataFile,
+};
+use crate::commands::{
+    Executable, DATA_FILE_SUPPORTED_EXTENSIONS, ERROR_STATUS_CODE, RULES,
+    RULE_FILE_SUPPORTED_EXTENSIONS, SUCCESS_STATUS_CODE,
+};
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::root_scope;
+use crate::rules::profile::{FrameStats, Profiler};
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+const ABOUT: &str = "Evaluates rules against data files while recording the time spent in each rule, block, clause and let, \
+and how many values their queries stepped through, to find the ones that slow the evaluation down";
+const RULES_HELP: &str = "Provide a rules file or a directory of rules files. Supports passing multiple values by using this option repeatedly";
+const DATA_HELP: &str = "Provide a data file or a directory of data files in JSON or YAML. Supports passing multiple values by using this option repeatedly";
+const OUTPUT_FORMAT_HELP: &str = "sorted (default) lists the frames by the time spent in them, leaving out the frames evaluated within them, slowest first. \
+folded prints one line per stack of frames with that time in microseconds, the input of flame graph tools such as inferno-flamegraph or speedscope";
+const TOP_HELP: &str =
+    "The number of frames the sorted report lists. Defaults to 25, 0 lists all of them";
+
+/// how the profile is printed
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum ProfileFormat {
+    #[default]
+    Sorted,
+    Folded,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Profile command evaluates rules against data files and reports where the evaluation spent
+/// its time
+pub struct Profile {
+    #[arg(short=RULES.1, long=RULES.0, help=RULES_HELP, required=true)]
+    /// paths to rules files, or directories of rules files
+    pub(crate) rules: Vec<String>,
+    #[arg(short, long, help=DATA_HELP, required=true)]
+    /// paths to data files, or directories of data files
+    pub(crate) data: Vec<String>,
+    #[arg(short, long, help=OUTPUT_FORMAT_HELP, value_enum, default_value_t=ProfileFormat::Sorted)]
+    /// sorted or folded
+    /// default is sorted
+    pub(crate) output_format: ProfileFormat,
+    #[arg(long, help=TOP_HELP, default_value_t=25)]
+    /// the number of frames the sorted report lists, all of them when 0
+    /// default is 25
+    pub(crate) top: usize,
+}
+
+impl Executable for Profile {
+    /// .
+    /// evaluates every rules file against every data file with a profiler, and prints what it
+    /// recorded. Rules files that fail to parse and evaluations that fail are reported on stderr,
+    /// the rest are still profiled
+    ///
+    /// This function will return an error if
+    /// - any of the specified paths do not exist
+    /// - a data file is not valid JSON or YAML
+    fn execute(&self, writer: &mut Writer, _: &mut Reader) -> Result<i32> {
+        let rule_info = get_rule_info(
+            &argument_files(&self.rules, &RULE_FILE_SUPPORTED_EXTENSIONS)?,
+            writer,
+        )?;
+        let parsed = parse_rule_files(&rule_info);
+        let mut exit_code = match parsed.errors.is_empty() {
+            true => SUCCESS_STATUS_CODE,
+            false => ERROR_STATUS_CODE,
+        };
+        for error in &parsed.errors {
+            writer.write_err(format!("{}: {}", error.file_name, error.error))?;
+        }
+
+        let data_files = self.data_files()?;
+        let mut profiler = Profiler::default();
+        for data_file in &data_files {
+            for (rules, file_name) in &parsed.rules {
+                let mut root_scope =
+                    root_scope(rules, Rc::new(data_file.path_value.clone())).with_profiler();
+                if let Err(e) = eval_rules_file(rules, &mut root_scope, Some(&data_file.name)) {
+                    writer.write_err(format!(
+                        "{file_name} could not be evaluated against {}: {e}",
+                        data_file.name
+                    ))?;
+                    exit_code = ERROR_STATUS_CODE;
+                }
+                if let Some(profiled) = root_scope.take_profiler() {
+                    profiler.merge(file_name, profiled);
+                }
+            }
+        }
+
+        match self.output_format {
+            ProfileFormat::Sorted => write!(writer, "{}", sorted_report(&profiler, self.top))?,
+            ProfileFormat::Folded => write!(writer, "{}", folded_report(&profiler))?,
+        }
+
+        Ok(exit_code)
+    }
+}
+
+impl Profile {
+    fn data_files(&self) -> Result<Vec<DataFile>> {
+        let mut data_files = vec![];
+        for file in argument_files(&self.data, &DATA_FILE_SUPPORTED_EXTENSIONS)? {
+            let content = read_file_content(File::open(&file)?)?;
+            data_files.push(build_data_file(content, file.display().to_string())?);
+        }
+
+        adapt_data_files(data_files, None)
+    }
+}
+
+/// the frames with the most time spent in them, leaving out the frames evaluated within them,
+/// as a table
+pub(crate) fn sorted_report(profiler: &Profiler, top: usize) -> String {
+    let mut frames = profiler.frames().into_iter().collect::<Vec<_>>();
+    frames.sort_by(|(left, left_stats), (right, right_stats)| {
+        right_stats
+            .own
+            .cmp(&left_stats.own)
+            .then_with(|| left.cmp(right))
+    });
+    if top > 0 {
+        frames.truncate(top);
+    }
+
+    let rows = std::iter::once(
+        [
+            "Own time",
+            "Total time",
+            "Calls",
+            "Resolutions",
+            "Rules file",
+            "Frame",
+        ]
+        .iter()
+        .map(|header| header.to_string())
+        .collect::<Vec<_>>(),
+    )
+    .chain(frames.into_iter().map(
+        |(
+            (rules_file, frame),
+            FrameStats {
+                calls,
+                total,
+                own,
+                resolutions,
+            },
+        )| {
+            vec![
+                duration(own),
+                duration(total),
+                calls.to_string(),
+                resolutions.to_string(),
+                rules_file.to_string(),
+                frame.to_string(),
+            ]
+        },
+    ))
+    .collect::<Vec<_>>();
+
+    let widths = (0..rows[0].len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+/// one `rules file;frame;frame microseconds` line per stack of frames, the time spent in the
+/// innermost frame itself
+pub(crate) fn folded_report(profiler: &Profiler) -> String {
+    let mut lines = BTreeMap::new();
+    for (stack, stats) in profiler.stacks() {
+        let micros = stats.own.as_micros();
+        if micros > 0 {
+            // `;` separates the frames of a stack
+            let stack = stack
+                .iter()
+                .map(|frame| frame.replace(';', ","))
+                .collect::<Vec<_>>()
+                .join(";");
+            *lines.entry(stack).or_insert(0) += micros;
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(stack, micros)| format!("{stack} {micros}\n"))
+        .collect()
+}
+
+fn duration(duration: Duration) -> String {
+    format!("{duration:.3?}")
+}
+
+#[cfg(test)]
+#[path = "profile_tests.rs"]
+mod profile_tests;
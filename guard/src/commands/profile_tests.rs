@@ -0,0 +1,122 @@
+use std::convert::TryFrom;
+
+use super::*;
+use crate::rules::exprs::RulesFile;
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::profile::Frame;
+use pretty_assertions::assert_eq;
+
+const RULES: &str = r#"let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+
+rule s3_encrypted when %buckets not empty {
+    %buckets.Properties.BucketEncryption exists
+}
+
+rule roles_have_a_path {
+    AWS::IAM::Role {
+        Properties {
+            Path exists
+        }
+    }
+}
+"#;
+
+const DATA: &str = r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketEncryption: {}
+  Role:
+    Type: AWS::IAM::Role
+    Properties:
+      Path: /
+"#;
+
+fn profiled() -> Result<Profiler> {
+    let rules = RulesFile::try_from(RULES)?;
+    let data = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(DATA)?)?;
+    let mut root_scope = root_scope(&rules, Rc::new(data)).with_profiler();
+    eval_rules_file(&rules, &mut root_scope, None)?;
+
+    let mut profiler = Profiler::default();
+    profiler.merge("s3.guard", root_scope.take_profiler().unwrap());
+    Ok(profiler)
+}
+
+#[test]
+fn test_sorted_report() -> Result<()> {
+    let report = sorted_report(&profiled()?, 0);
+    let mut lines = report.lines();
+    assert_eq!(
+        vec![
+            "Own",
+            "time",
+            "Total",
+            "time",
+            "Calls",
+            "Resolutions",
+            "Rules",
+            "file",
+            "Frame"
+        ],
+        lines.next().unwrap().split_whitespace().collect::<Vec<_>>()
+    );
+
+    // calls and frame of each row, the times vary from run to run
+    let mut frames = lines
+        .map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            assert_eq!("s3.guard", columns[4]);
+            (columns[2].to_string(), columns[5..].join(" "))
+        })
+        .collect::<Vec<_>>();
+    frames.sort();
+    assert_eq!(
+        vec![
+            (
+                String::from("1"),
+                String::from("%buckets not empty at line 3")
+            ),
+            (
+                String::from("1"),
+                String::from("%buckets.Properties.BucketEncryption exists at line 4")
+            ),
+            (String::from("1"), String::from("AWS::IAM::Role {}")),
+            (String::from("1"), String::from("Path exists at line 10")),
+            (String::from("1"), String::from("Properties {} at line 9")),
+            (String::from("1"), String::from("let buckets")),
+            (String::from("1"), String::from("rule roles_have_a_path")),
+            (String::from("1"), String::from("rule s3_encrypted")),
+        ],
+        frames
+    );
+
+    assert_eq!(3, sorted_report(&profiled()?, 2).lines().count());
+
+    Ok(())
+}
+
+#[test]
+fn test_folded_report() {
+    let mut profiled = Profiler::default();
+    profiled.enter(Frame::Rule("s3_encrypted"));
+    profiled.enter(Frame::Variable("buckets;logs"));
+    std::thread::sleep(Duration::from_millis(2));
+    profiled.exit();
+    profiled.exit();
+
+    let mut profiler = Profiler::default();
+    profiler.merge("s3.guard", profiled);
+
+    // stacks that took less than a microsecond themselves are left out, as the rule may have
+    let report = folded_report(&profiler);
+    let lines = report
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap())
+        .filter(|(stack, _)| stack.ends_with("let buckets,logs"))
+        .collect::<Vec<_>>();
+    assert_eq!(1, lines.len());
+    assert_eq!("s3.guard;rule s3_encrypted;let buckets,logs", lines[0].0);
+    assert!(lines[0].1.parse::<u128>().unwrap() >= 2000);
+}
@@ -0,0 +1,62 @@
+use std::io::{IsTerminal, Write};
+
+/// width of the bar, in characters
+const WIDTH: usize = 30;
+
+/// how many evaluations of a rules file against a data file are done out of all of them, drawn as
+/// a bar on stderr as they complete. Nothing is drawn when stderr is not a terminal, so the
+/// output of a redirected or piped run is left as it is
+#[derive(Debug)]
+pub(crate) struct Progress {
+    total: usize,
+    done: usize,
+    visible: bool,
+}
+
+impl Progress {
+    pub(crate) fn new(total: usize, show: bool) -> Progress {
+        Progress {
+            total,
+            done: 0,
+            visible: show && total > 0 && std::io::stderr().is_terminal(),
+        }
+    }
+
+    /// counts an evaluation against the data file as done
+    pub(crate) fn tick(&mut self, data_file: &str) {
+        self.done += 1;
+        if self.visible {
+            let mut stderr = std::io::stderr();
+            let _ = write!(stderr, "\r\x1b[2K{}", bar(self.done, self.total, data_file));
+            let _ = stderr.flush();
+        }
+    }
+
+    /// clears the bar so the output that follows starts on an empty line
+    pub(crate) fn finish(&mut self) {
+        if self.visible {
+            let mut stderr = std::io::stderr();
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+            self.visible = false;
+        }
+    }
+}
+
+/// `[=====     ] 12/40 template.yaml`, the bar filled in proportion to the evaluations done
+fn bar(done: usize, total: usize, data_file: &str) -> String {
+    let filled = match total {
+        0 => WIDTH,
+        total => WIDTH * done.min(total) / total,
+    };
+
+    format!(
+        "[{}{}] {done}/{total} {data_file}",
+        "=".repeat(filled),
+        " ".repeat(WIDTH - filled)
+    )
+}
+
+#[cfg(test)]
+#[path = "progress_tests.rs"]
+mod progress_tests;
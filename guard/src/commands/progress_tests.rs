@@ -0,0 +1,33 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_bar() {
+    assert_eq!(
+        format!("[{}] 0/4 a.yaml", " ".repeat(WIDTH)),
+        bar(0, 4, "a.yaml")
+    );
+    assert_eq!(
+        format!(
+            "[{}{}] 2/4 b.yaml",
+            "=".repeat(WIDTH / 2),
+            " ".repeat(WIDTH / 2)
+        ),
+        bar(2, 4, "b.yaml")
+    );
+    assert_eq!(
+        format!("[{}] 4/4 c.yaml", "=".repeat(WIDTH)),
+        bar(4, 4, "c.yaml")
+    );
+}
+
+#[test]
+fn test_hidden_progress_counts() {
+    let mut progress = Progress::new(2, false);
+    progress.tick("a.yaml");
+    progress.tick("b.yaml");
+    progress.finish();
+
+    assert_eq!(2, progress.done);
+    assert!(!progress.visible);
+}
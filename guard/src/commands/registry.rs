@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::commands::remote::{fetch, file_name, is_remote, Header};
+use crate::commands::{Executable, SUCCESS_STATUS_CODE};
+use crate::rules::errors::Error;
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+/// the index of a registry of rule packs, a JSON or YAML file listing every published version of
+/// each pack with the files it is made of
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct RegistryIndex {
+    #[serde(default)]
+    pub(crate) packs: BTreeMap<String, PackEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct PackEntry {
+    #[serde(default)]
+    pub(crate) description: String,
+    #[serde(default)]
+    pub(crate) versions: BTreeMap<String, PackVersion>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct PackVersion {
+    pub(crate) files: Vec<PackFile>,
+}
+
+/// a file of a published pack, the URL is resolved from the location of the index when relative
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct PackFile {
+    pub(crate) url: String,
+    pub(crate) sha256: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=LIST_ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The List command prints the rule packs published in a registry and their versions
+pub struct List {
+    #[arg(long, help=REGISTRY_HELP, required=true)]
+    /// path or https:// URL of the registry index
+    pub(crate) registry: String,
+    #[arg(name="header", long="header", help=HEADER_HELP)]
+    /// headers sent along with the requests for the index given as an https:// URL
+    pub(crate) headers: Vec<Header>,
+}
+
+impl Executable for List {
+    /// .
+    /// prints every pack of the registry, with its versions from the oldest to the newest
+    ///
+    /// This function will return an error if the index cannot be read, or is not a registry index
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let index = read_index(&self.registry, &self.headers)?;
+        write!(writer, "{}", list_packs(&index))?;
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=PULL_ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Pull command downloads a version of a rule pack published in a registry
+pub struct Pull {
+    #[arg(help=PACK_HELP)]
+    /// the pack and the version to pull, as pack@version
+    pub(crate) pack: String,
+    #[arg(long, help=REGISTRY_HELP, required=true)]
+    /// path or https:// URL of the registry index
+    pub(crate) registry: String,
+    #[arg(short, long, help=OUTPUT_DIR_HELP, default_value=DEFAULT_OUTPUT_DIR)]
+    /// the directory the pack is written into, under pack/version
+    /// default is guard-rules
+    pub(crate) output_dir: String,
+    #[arg(name="header", long="header", help=HEADER_HELP)]
+    /// headers sent along with the requests for the index and files given as https:// URLs
+    pub(crate) headers: Vec<Header>,
+}
+
+impl Executable for Pull {
+    /// .
+    /// downloads every file of the version of the pack and checks them against the checksums of
+    /// the index, before writing any of them to `<output-dir>/<pack>/<version>`
+    ///
+    /// This function will return an error if
+    /// - the pack is not given as pack@version
+    /// - the index cannot be read, or is not a registry index
+    /// - the registry does not publish the version of the pack
+    /// - a file cannot be downloaded, or does not match its checksum
+    /// - the files cannot be written to the output directory
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let (name, version) = parse_pack(&self.pack)?;
+        let index = read_index(&self.registry, &self.headers)?;
+        let files = pack_files(&index, name, version)?;
+
+        let mut contents = vec![];
+        for file in files {
+            let location = resolve(&self.registry, &file.url);
+            let content = read_location(&location, &self.headers)?;
+            verify_checksum(&location, &content, &file.sha256)?;
+            contents.push((location_file_name(&location), content));
+        }
+
+        let dir = PathBuf::from(&self.output_dir).join(name).join(version);
+        std::fs::create_dir_all(&dir)?;
+        for (file_name, content) in &contents {
+            std::fs::write(dir.join(file_name), content)?;
+        }
+
+        writeln!(
+            writer,
+            "Pulled {name}@{version} into {}, {} file(s)",
+            dir.display(),
+            contents.len()
+        )?;
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
+
+/// splits `pack@version`, the version is required so a pull always gets the same files
+pub(crate) fn parse_pack(pack: &str) -> Result<(&str, &str)> {
+    match pack.split_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => Ok((name, version)),
+        _ => Err(Error::IllegalArguments(format!(
+            "'{pack}' is not a valid pack, expecting pack@version, e.g. cis-aws@1.0.0"
+        ))),
+    }
+}
+
+pub(crate) fn read_index(registry: &str, headers: &[Header]) -> Result<RegistryIndex> {
+    let content = read_location(registry, headers)?;
+
+    Ok(serde_yaml::from_slice(&content)?)
+}
+
+/// the files of the version of the pack
+///
+/// This function will return an error listing the packs, or the versions of the pack, published
+/// in the registry when it does not publish the one asked for
+pub(crate) fn pack_files<'index>(
+    index: &'index RegistryIndex,
+    name: &str,
+    version: &str,
+) -> Result<&'index [PackFile]> {
+    let pack = index.packs.get(name).ok_or_else(|| {
+        Error::IllegalArguments(format!(
+            "The registry has no pack named {name}, the packs are {}",
+            index.packs.keys().cloned().collect::<Vec<_>>().join(", ")
+        ))
+    })?;
+
+    let files = pack.versions.get(version).ok_or_else(|| {
+        Error::IllegalArguments(format!(
+            "The registry has no version {version} of {name}, the versions are {}",
+            sorted_versions(pack).join(", ")
+        ))
+    })?;
+
+    Ok(&files.files)
+}
+
+/// one line per pack with its versions, followed by its description
+pub(crate) fn list_packs(index: &RegistryIndex) -> String {
+    let mut list = String::new();
+    for (name, pack) in &index.packs {
+        list.push_str(&format!("{name} {}\n", sorted_versions(pack).join(", ")));
+        if !pack.description.trim().is_empty() {
+            list.push_str(&format!("    {}\n", pack.description.trim()));
+        }
+    }
+    list
+}
+
+/// the versions of the pack from the oldest to the newest, comparing the numbers of dotted versions
+/// as numbers so 1.10.0 comes after 1.9.0
+fn sorted_versions(pack: &PackEntry) -> Vec<&str> {
+    let mut versions = pack.versions.keys().map(String::as_str).collect::<Vec<_>>();
+    versions.sort_by(|left, right| {
+        let segments = |version: &str| {
+            version
+                .split('.')
+                .map(|segment| segment.parse::<u64>().map_err(|_| segment.to_string()))
+                .collect::<Vec<_>>()
+        };
+        segments(left)
+            .cmp(&segments(right))
+            .then_with(|| left.cmp(right))
+    });
+    versions
+}
+
+/// the location of a file of the index, relative URLs and paths are resolved from the directory
+/// of the index
+pub(crate) fn resolve(registry: &str, url: &str) -> String {
+    if is_remote(url) || Path::new(url).is_absolute() {
+        return url.to_string();
+    }
+
+    if is_remote(registry) {
+        let base = registry
+            .split(|c| c == '?' || c == '#')
+            .next()
+            .unwrap_or_default();
+        return match base.rsplit_once('/') {
+            Some((base, _)) => format!("{base}/{url}"),
+            None => url.to_string(),
+        };
+    }
+
+    Path::new(registry)
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(url)
+        .display()
+        .to_string()
+}
+
+fn read_location(location: &str, headers: &[Header]) -> Result<Vec<u8>> {
+    if is_remote(location) {
+        return fetch(location, headers);
+    }
+
+    std::fs::read(location).map_err(|_| Error::FileNotFoundError(location.to_string()))
+}
+
+fn location_file_name(location: &str) -> String {
+    match is_remote(location) {
+        true => file_name(location).to_string(),
+        false => Path::new(location)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| location.to_string()),
+    }
+}
+
+/// checks the SHA-256 of the content against the hex checksum of the index
+///
+/// This function will return an error if they do not match, the file was modified after it was
+/// published or the index is wrong
+pub(crate) fn verify_checksum(location: &str, content: &[u8], expected: &str) -> Result<()> {
+    let actual = Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    match actual.eq_ignore_ascii_case(expected.trim()) {
+        true => Ok(()),
+        false => Err(Error::IllegalArguments(format!(
+            "The checksum of {location} is {actual}, the registry expects {}, nothing was written",
+            expected.trim()
+        ))),
+    }
+}
+
+const DEFAULT_OUTPUT_DIR: &str = "guard-rules";
+const LIST_ABOUT: &str = "Lists the rule packs published in a registry, and their versions";
+const PULL_ABOUT: &str = r#"Downloads a version of a rule pack published in a registry into <output-dir>/<pack>/<version>.
+Every file is checked against the SHA-256 checksum of the registry index before any of them is written."#;
+const PACK_HELP: &str = "The pack and the version to pull, e.g. cis-aws@1.0.0";
+const REGISTRY_HELP: &str = "Path or https:// URL of the registry index, a JSON or YAML file listing the versions of each pack and the URL and SHA-256 checksum of their files";
+const OUTPUT_DIR_HELP: &str =
+    "The directory the pack is written into, under <pack>/<version>. Defaults to guard-rules";
+const HEADER_HELP: &str = "A header sent along with the requests for files given as https:// URLs, e.g. --header 'Authorization: Bearer <token>'. Can be repeated. URLs require cfn-guard to be built with the remote feature";
+
+#[cfg(test)]
+#[path = "registry_tests.rs"]
+mod registry_tests;
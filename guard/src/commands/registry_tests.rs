@@ -0,0 +1,85 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+fn index(versions: &[&str]) -> RegistryIndex {
+    RegistryIndex {
+        packs: BTreeMap::from([(
+            String::from("s3-baseline"),
+            PackEntry {
+                description: String::from("S3 checks"),
+                versions: versions
+                    .iter()
+                    .map(|version| (version.to_string(), PackVersion::default()))
+                    .collect(),
+            },
+        )]),
+    }
+}
+
+#[test]
+fn test_parse_pack() {
+    assert_eq!(
+        ("s3-baseline", "1.0.0"),
+        parse_pack("s3-baseline@1.0.0").unwrap()
+    );
+    assert!(parse_pack("s3-baseline").is_err());
+    assert!(parse_pack("s3-baseline@").is_err());
+    assert!(parse_pack("@1.0.0").is_err());
+}
+
+#[test]
+fn test_list_packs_sorts_versions_numerically() {
+    assert_eq!(
+        "s3-baseline 1.2.0, 1.9.0, 1.10.0, 2.0.0\n    S3 checks\n",
+        list_packs(&index(&["1.10.0", "2.0.0", "1.9.0", "1.2.0"]))
+    );
+}
+
+#[test]
+fn test_pack_files_unknown_version() {
+    let index = index(&["1.0.0", "1.1.0"]);
+    assert!(pack_files(&index, "s3-baseline", "1.0.0").is_ok());
+
+    let error = pack_files(&index, "s3-baseline", "2.0.0").unwrap_err();
+    assert_eq!(
+        "The registry has no version 2.0.0 of s3-baseline, the versions are 1.0.0, 1.1.0",
+        error.to_string()
+    );
+    assert!(pack_files(&index, "iam", "1.0.0").is_err());
+}
+
+#[test]
+fn test_resolve() {
+    for (registry, url, expected) in [
+        (
+            "https://example.com/registry/index.yaml?v=1",
+            "packs/s3.guard",
+            "https://example.com/registry/packs/s3.guard",
+        ),
+        (
+            "https://example.com/registry/index.yaml",
+            "https://cdn.example.com/s3.guard",
+            "https://cdn.example.com/s3.guard",
+        ),
+        (
+            "registry/index.yaml",
+            "packs/s3.guard",
+            "registry/packs/s3.guard",
+        ),
+        ("index.yaml", "packs/s3.guard", "packs/s3.guard"),
+    ] {
+        assert_eq!(
+            Path::new(expected),
+            Path::new(&resolve(registry, url)),
+            "{registry} {url}"
+        );
+    }
+}
+
+#[test]
+fn test_verify_checksum() {
+    let sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    assert!(verify_checksum("hello.guard", b"hello", sha256).is_ok());
+    assert!(verify_checksum("hello.guard", b"hello", &sha256.to_uppercase()).is_ok());
+    assert!(verify_checksum("hello.guard", b"hello!", sha256).is_err());
+}
@@ -0,0 +1,173 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::rules::errors::Error;
+use crate::rules::Result;
+
+const REMOTE_PREFIX: &str = "https://";
+
+/// whether a `--rules` or `--data` argument is a URL to download rather than a local path
+pub(crate) fn is_remote(file_or_dir: &str) -> bool {
+    file_or_dir.starts_with(REMOTE_PREFIX)
+}
+
+/// a `Name: value` header sent along with every request for a remote file, e.g. for authorization
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Header {
+    pub(crate) name: String,
+    pub(crate) value: String,
+}
+
+impl FromStr for Header {
+    type Err = String;
+
+    fn from_str(header: &str) -> std::result::Result<Self, Self::Err> {
+        match header.split_once(':') {
+            Some((name, value)) if !name.trim().is_empty() => Ok(Header {
+                name: name.trim().to_string(),
+                value: value.trim().to_string(),
+            }),
+            _ => Err(format!(
+                "'{header}' is not a valid header, expecting Name: value"
+            )),
+        }
+    }
+}
+
+/// the files downloaded during a run, each URL is fetched once however many times it is referred
+/// to. They are written to a directory only the current user can read, which is deleted with
+/// everything in it when the downloads are dropped at the end of the run
+#[derive(Debug, Default)]
+pub(crate) struct Downloads {
+    dir: Option<PathBuf>,
+    files: HashMap<String, PathBuf>,
+}
+
+impl Downloads {
+    /// downloads the file at the URL, keeping the name of the file in the URL so rules files are
+    /// reported by it, and returns where it was written
+    pub(crate) fn download(&mut self, url: &str, headers: &[Header]) -> Result<PathBuf> {
+        if let Some(path) = self.files.get(url) {
+            return Ok(path.clone());
+        }
+
+        let content = fetch(url, headers)?;
+        self.write(url, &content)
+    }
+
+    fn write(&mut self, url: &str, content: &[u8]) -> Result<PathBuf> {
+        let dir = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => self.dir.insert(private_temp_dir()?).clone(),
+        };
+
+        // every file has a directory of its own, different URLs may end with the same name
+        let file_dir = dir.join(self.files.len().to_string());
+        private_dir_builder().create(&file_dir)?;
+        let path = file_dir.join(file_name(url));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(&path)?.write_all(content)?;
+
+        self.files.insert(url.to_string(), path.clone());
+        Ok(path)
+    }
+}
+
+impl Drop for Downloads {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            // nothing is left to report the error to at the end of the run
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// creates a directory with a random name in the temporary directory, failing rather than
+/// following whatever already exists by that name
+fn private_temp_dir() -> Result<PathBuf> {
+    for _ in 0..8 {
+        let dir = std::env::temp_dir().join(format!(
+            "cfn-guard-{}-{:016x}",
+            std::process::id(),
+            RandomState::new().build_hasher().finish()
+        ));
+        match private_dir_builder().create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(Error::IoError(std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        "unable to create a temporary directory for the remote files",
+    )))
+}
+
+/// builds directories only the current user can read
+fn private_dir_builder() -> std::fs::DirBuilder {
+    #[allow(unused_mut)]
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+}
+
+/// the last segment of the path of the URL, without its query or fragment
+pub(crate) fn file_name(url: &str) -> &str {
+    let path = url
+        .trim_start_matches(REMOTE_PREFIX)
+        .split(|c| c == '?' || c == '#')
+        .next()
+        .unwrap_or_default();
+
+    match path.rsplit_once('/') {
+        Some((_, name)) if !name.is_empty() => name,
+        _ => "index",
+    }
+}
+
+/// the content of the file at the URL
+#[cfg(all(feature = "remote", not(target_arch = "wasm32")))]
+pub(crate) fn fetch(url: &str, headers: &[Header]) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let request = headers.iter().fold(ureq::get(url), |request, header| {
+        request.set(&header.name, &header.value)
+    });
+    let response = request.call().map_err(|e| {
+        Error::IoError(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("unable to download {url}, {e}"),
+        ))
+    })?;
+
+    let mut content = vec![];
+    response.into_reader().read_to_end(&mut content)?;
+    Ok(content)
+}
+
+#[cfg(all(not(feature = "remote"), not(target_arch = "wasm32")))]
+pub(crate) fn fetch(url: &str, _headers: &[Header]) -> Result<Vec<u8>> {
+    Err(Error::IllegalArguments(format!(
+        "unable to download {url}, remote files require cfn-guard to be built with the remote feature, e.g. cargo install cfn-guard --features remote"
+    )))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn fetch(url: &str, _headers: &[Header]) -> Result<Vec<u8>> {
+    Err(Error::UnsupportedOperationError(format!(
+        "unable to download {url}, remote files are not supported in this environment"
+    )))
+}
+
+#[cfg(test)]
+#[path = "remote_tests.rs"]
+mod remote_tests;
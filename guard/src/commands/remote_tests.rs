@@ -0,0 +1,64 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_is_remote() {
+    assert!(is_remote("https://example.com/rules/s3.guard"));
+    assert!(!is_remote("rules/s3.guard"));
+    assert!(!is_remote("/tmp/https://example.com"));
+}
+
+#[test]
+fn test_header_from_str() {
+    assert_eq!(
+        Ok(Header {
+            name: String::from("Authorization"),
+            value: String::from("Bearer abc:def"),
+        }),
+        "Authorization: Bearer abc:def".parse::<Header>()
+    );
+    assert!("Authorization".parse::<Header>().is_err());
+    assert!(": value".parse::<Header>().is_err());
+}
+
+#[test]
+fn test_file_name() {
+    for (url, expected) in [
+        ("https://example.com/templates/app.yaml", "app.yaml"),
+        (
+            "https://example.com/templates/app.yaml?version=3#top",
+            "app.yaml",
+        ),
+        ("https://example.com/rules/", "index"),
+        ("https://example.com", "index"),
+    ] {
+        assert_eq!(expected, file_name(url));
+    }
+}
+
+#[test]
+fn test_downloads_removed_when_dropped() -> Result<()> {
+    let mut downloads = Downloads::default();
+    let rules = downloads.write("https://example.com/rules/s3.guard", b"rule s3 {}")?;
+    let other = downloads.write("https://example.com/other/s3.guard", b"rule other {}")?;
+
+    assert_eq!("s3.guard", rules.file_name().unwrap());
+    assert_eq!("rule s3 {}", std::fs::read_to_string(&rules)?);
+    assert_eq!("rule other {}", std::fs::read_to_string(&other)?);
+
+    let dir = downloads.dir.clone().unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(0o700, std::fs::metadata(&dir)?.permissions().mode() & 0o777);
+        assert_eq!(
+            0o600,
+            std::fs::metadata(&rules)?.permissions().mode() & 0o777
+        );
+    }
+
+    drop(downloads);
+    assert!(!dir.exists());
+
+    Ok(())
+}
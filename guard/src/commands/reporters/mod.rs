@@ -1,7 +1,8 @@
+pub mod schema;
 pub mod test;
 pub mod validate;
 
-use std::{fmt::Display, rc::Rc, time::Instant};
+use std::fmt::Display;
 
 use quick_xml::{
     events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event},
@@ -10,15 +11,10 @@ use quick_xml::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    commands::{
-        reporters::test::structured::TestResult, validate::DataFile, ERROR_STATUS_CODE,
-        FAILURE_STATUS_CODE,
-    },
+    commands::reporters::test::structured::TestResult,
     rules::{
         self,
-        eval::eval_rules_file,
-        eval_context::{root_scope, simplified_json_from_root, Messages},
-        exprs::RulesFile,
+        eval_context::{FileReport, Messages},
         Status,
     },
 };
@@ -63,10 +59,7 @@ impl<'report> From<&'report Vec<TestResult>> for JunitReport<'report> {
 }
 
 impl<'report> JunitReport<'report> {
-    pub fn serialize(
-        &self,
-        writer: &'report mut crate::utils::writer::Writer,
-    ) -> crate::rules::Result<()> {
+    pub fn serialize(&self, writer: &mut crate::utils::writer::Writer) -> crate::rules::Result<()> {
         let mut writer = quick_xml::Writer::new_with_indent(writer, b' ', 4);
         let decl = BytesDecl::new("1.0", Some("UTF-8"), None);
 
@@ -85,57 +78,43 @@ impl<'report> JunitReport<'report> {
     }
 }
 
-struct JunitReporter<'reporter> {
-    rules: Vec<(RulesFile<'reporter>, &'reporter str)>,
-    data: Vec<DataFile>,
-    writer: &'reporter mut crate::utils::writer::Writer,
-    exit_code: i32,
-}
-
-impl<'reporter> JunitReporter<'reporter> {
-    /// Update exit code only if code takes more precedence than current exit code
-    fn update_exit_code(&mut self, code: i32) {
-        if code == ERROR_STATUS_CODE
-            || code == FAILURE_STATUS_CODE && self.exit_code != ERROR_STATUS_CODE
-        {
-            self.exit_code = code;
-        }
-    }
-}
-
+/// builds the junit test case of a rule out of its evaluation, `report` being the simplified
+/// report of the evaluated record. Failing warnings are left out of the failure messages, and a
+/// rules file that only failed warnings is reported as skipped
 fn get_test_case<'rule>(
-    data: &DataFile,
-    rule: &RulesFile<'_>,
     name: &'rule str,
-) -> crate::rules::Result<TestCase<'rule>> {
-    let now = Instant::now();
-    let mut root_scope = root_scope(rule, Rc::new(data.path_value.clone()));
-    let status = eval_rules_file(rule, &mut root_scope, Some(&data.name))?;
-    let root_record = root_scope.reset_recorder().extract();
-    let time = now.elapsed().as_millis();
-
-    let tc = match simplified_json_from_root(&root_record) {
+    status: Status,
+    time: u128,
+    report: &crate::rules::Result<FileReport<'_>>,
+) -> TestCase<'rule> {
+    match report {
         Ok(report) => match status {
             Status::FAIL => {
-                let status = report.not_compliant.iter().fold(
-                    FailingTestCase {
-                        name: None,
-                        messages: vec![],
-                    },
-                    |mut test_case, failure| {
-                        failure.get_message().into_iter().for_each(|e| {
-                            if let rules::eval_context::ClauseReport::Rule(rule) = failure {
-                                let name = match rule.name.contains(".guard/") {
-                                    true => rule.name.split(".guard/").collect::<Vec<&str>>()[1],
-                                    false => rule.name,
+                let status = report
+                    .not_compliant
+                    .iter()
+                    .filter(|failure| failure.severity().is_error())
+                    .fold(
+                        FailingTestCase {
+                            name: None,
+                            messages: vec![],
+                        },
+                        |mut test_case, failure| {
+                            failure.get_message().into_iter().for_each(|e| {
+                                if let rules::eval_context::ClauseReport::Rule(rule) = failure {
+                                    let name = match rule.name.contains(".guard/") {
+                                        true => {
+                                            rule.name.split(".guard/").collect::<Vec<&str>>()[1]
+                                        }
+                                        false => rule.name,
+                                    };
+                                    test_case.name = Some(String::from(name));
                                 };
-                                test_case.name = Some(String::from(name));
-                            };
-                            test_case.messages.push(e);
-                        });
-                        test_case
-                    },
-                );
+                                test_case.messages.push(e);
+                            });
+                            test_case
+                        },
+                    );
 
                 TestCase {
                     id: None,
@@ -149,6 +128,7 @@ fn get_test_case<'rule>(
                 name,
                 time,
                 status: match status {
+                    Status::PASS if !report.not_compliant.is_empty() => TestCaseStatus::Skip,
                     Status::PASS => TestCaseStatus::Pass,
                     Status::SKIP => TestCaseStatus::Skip,
                     _ => unreachable!(),
@@ -164,9 +144,7 @@ fn get_test_case<'rule>(
                 error: error.to_string(),
             },
         },
-    };
-
-    Ok(tc)
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
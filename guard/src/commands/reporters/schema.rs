@@ -0,0 +1,40 @@
+use serde::Serialize;
+
+/// the version of the schemas of the json and yaml output of validate and test, emitted as the
+/// `schema_version` of each document. The minor version is bumped when fields are added, the
+/// major version when fields are removed or change their meaning
+pub const OUTPUT_SCHEMA_VERSION: &str = "1.0";
+/// the version of the schema of the json and yaml output of validate with `--output-version v2`
+pub const OUTPUT_SCHEMA_VERSION_V2: &str = "2.0";
+
+/// the JSON Schema of the structured json and yaml output of validate
+pub const VALIDATE_OUTPUT_SCHEMA: &str =
+    include_str!("../../../resources/schema/validate-output.schema.json");
+/// the JSON Schema of the structured json and yaml output of validate with `--output-version v2`
+pub const VALIDATE_OUTPUT_SCHEMA_V2: &str =
+    include_str!("../../../resources/schema/validate-output-v2.schema.json");
+/// the JSON Schema of the json and yaml output of test
+pub const TEST_OUTPUT_SCHEMA: &str =
+    include_str!("../../../resources/schema/test-output.schema.json");
+
+/// a document of the json and yaml output, with the version of the schema it follows ahead of
+/// its fields
+#[derive(Debug, Serialize)]
+pub(crate) struct Versioned<T> {
+    schema_version: &'static str,
+    #[serde(flatten)]
+    document: T,
+}
+
+impl<T> Versioned<T> {
+    pub(crate) fn new(schema_version: &'static str, document: T) -> Self {
+        Versioned {
+            schema_version,
+            document,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "schema_tests.rs"]
+mod schema_tests;
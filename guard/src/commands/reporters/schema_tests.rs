@@ -0,0 +1,48 @@
+use super::*;
+use pretty_assertions::assert_eq;
+use serde_json::{json, Value};
+
+#[test]
+fn test_schemas_have_the_output_version() {
+    for (schema, version) in [
+        (VALIDATE_OUTPUT_SCHEMA, OUTPUT_SCHEMA_VERSION),
+        (VALIDATE_OUTPUT_SCHEMA_V2, OUTPUT_SCHEMA_VERSION_V2),
+        (TEST_OUTPUT_SCHEMA, OUTPUT_SCHEMA_VERSION),
+    ] {
+        let schema = serde_json::from_str::<Value>(schema).unwrap();
+        assert_eq!(version, schema["$defs"]["SchemaVersion"]["const"]);
+        assert!(schema["$id"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("/{version}")));
+    }
+}
+
+#[test]
+fn test_versioned() {
+    #[derive(Serialize)]
+    struct Report {
+        name: &'static str,
+    }
+
+    assert_eq!(
+        json!({ "schema_version": OUTPUT_SCHEMA_VERSION, "name": "template.yaml" }),
+        serde_json::to_value(Versioned::new(
+            OUTPUT_SCHEMA_VERSION,
+            Report {
+                name: "template.yaml"
+            }
+        ))
+        .unwrap()
+    );
+    assert_eq!(
+        format!("schema_version: '{OUTPUT_SCHEMA_VERSION}'\nname: template.yaml\n"),
+        serde_yaml::to_string(&Versioned::new(
+            OUTPUT_SCHEMA_VERSION,
+            Report {
+                name: "template.yaml"
+            }
+        ))
+        .unwrap()
+    );
+}
@@ -5,10 +5,14 @@ use crate::{
         files::iterate_over,
         reporters::test::{get_by_rules, get_status_result},
         test::TestSpec,
+        test_spec::read_test_specs,
         validate, SUCCESS_STATUS_CODE, TEST_ERROR_STATUS_CODE, TEST_FAILURE_STATUS_CODE,
     },
     rules::{
-        errors::Error, eval::eval_rules_file, exprs::RulesFile, path_value::PathAwareValue, Status,
+        eval::eval_rules_file,
+        exprs::{RuleSelection, RulesFile},
+        path_value::PathAwareValue,
+        Status,
     },
 };
 use std::io::Write;
@@ -17,6 +21,7 @@ pub struct GenericReporter<'report> {
     pub(crate) test_data: &'report [PathBuf],
     pub(crate) verbose: bool,
     pub(crate) rules: RulesFile<'report>,
+    pub(crate) selection: &'report RuleSelection,
     pub(crate) writer: &'report mut crate::utils::writer::Writer,
 }
 
@@ -25,19 +30,7 @@ impl<'report> GenericReporter<'report> {
         let mut exit_code = SUCCESS_STATUS_CODE;
         let mut test_counter = 1;
 
-        for specs in iterate_over(self.test_data, |data, path| {
-            match serde_yaml::from_str::<Vec<TestSpec>>(&data) {
-                Ok(spec) => Ok(spec),
-                Err(_) => match serde_json::from_str::<Vec<TestSpec>>(&data) {
-                    Ok(specs) => Ok(specs),
-                    Err(e) => Err(Error::ParseError(format!(
-                        "Unable to process data in file {}, Error {},",
-                        path.display(),
-                        e
-                    ))),
-                },
-            }
-        }) {
+        for specs in iterate_over(self.test_data, |data, path| read_test_specs(&data, path)) {
             match specs {
                 Err(e) => {
                     writeln!(&mut self.writer, "Error processing {e}")?;
@@ -74,7 +67,8 @@ impl<'report> GenericReporter<'report> {
         let mut by_result = HashMap::new();
 
         let root = PathAwareValue::try_from(spec.input)?;
-        let mut root_scope = crate::rules::eval_context::root_scope(&self.rules, Rc::new(root));
+        let mut root_scope = crate::rules::eval_context::root_scope(&self.rules, Rc::new(root))
+            .with_selection(self.selection);
         eval_rules_file(&self.rules, &mut root_scope, None)?;
         let top = root_scope.reset_recorder().extract();
 
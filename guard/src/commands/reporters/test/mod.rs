@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::rules::{NamedStatus, RecordType, Status};
 
@@ -7,8 +7,8 @@ pub mod structured;
 
 pub(crate) fn get_by_rules<'top>(
     top: &'top crate::rules::eval_context::EventRecord<'_>,
-) -> HashMap<&'top str, Vec<&'top Option<RecordType<'top>>>> {
-    top.children.iter().fold(HashMap::new(), |mut acc, rule| {
+) -> BTreeMap<&'top str, Vec<&'top Option<RecordType<'top>>>> {
+    top.children.iter().fold(BTreeMap::new(), |mut acc, rule| {
         if let Some(RecordType::RuleCheck(NamedStatus { name, .. })) = rule.container {
             acc.entry(name).or_default().push(&rule.container)
         }
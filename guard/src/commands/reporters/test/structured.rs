@@ -11,10 +11,16 @@ use crate::rules::eval_context::Messages;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    commands::{files::iterate_over, test::TestSpec, validate::OutputFormatType},
+    commands::{
+        files::iterate_over, test::TestSpec, test_spec::read_test_specs, validate::OutputFormatType,
+    },
     rules::{
-        errors::Error, eval::eval_rules_file, eval_context, exprs::RulesFile,
-        path_value::PathAwareValue, Status,
+        errors::ParseErrorLocation,
+        eval::eval_rules_file,
+        eval_context,
+        exprs::{RuleSelection, RulesFile},
+        path_value::PathAwareValue,
+        Status,
     },
 };
 
@@ -28,6 +34,7 @@ pub struct StructuredTestReporter<'reporter> {
     pub data_test_files: &'reporter [PathBuf],
     pub output: OutputFormatType,
     pub rules: ContextAwareRule<'reporter>,
+    pub(crate) selection: &'reporter RuleSelection,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,6 +49,9 @@ pub struct Ok {
 pub struct Err {
     pub rule_file: String,
     pub error: String,
+    /// where the rules file failed to parse, when it did
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub location: Option<ParseErrorLocation>,
     #[serde(skip_serializing)] // NOTE: Only using this for junit
     pub time: u128,
 }
@@ -72,6 +82,7 @@ impl TestResult {
                 rule_file,
                 error,
                 time: test_result_time,
+                ..
             }) => TestSuite::new(
                 rule_file.to_string(),
                 vec![JunitTestCase {
@@ -106,6 +117,17 @@ impl TestResult {
         }
     }
 
+    /// the result with the rules file named after the mapping of the test manifest it was tested
+    /// in, as the same rules file can be tested in several mappings
+    pub(crate) fn in_mapping(mut self, mapping: &str) -> Self {
+        match &mut self {
+            TestResult::Ok(Ok { rule_file, .. }) | TestResult::Err(Err { rule_file, .. }) => {
+                *rule_file = format!("{mapping}: {rule_file}");
+            }
+        }
+        self
+    }
+
     fn insert_test_case(&mut self, tc: TestCase) {
         match self {
             TestResult::Err(Err { .. }) => unreachable!(),
@@ -171,6 +193,7 @@ impl TestCase {
                                     acc
                                 })
                         )),
+                        message_id: None,
                     }],
                 }),
                 name: &test_case.name,
@@ -217,25 +240,15 @@ impl<'reporter> StructuredTestReporter<'reporter> {
             time: 0,
         });
 
-        for specs in iterate_over(
-            self.data_test_files,
-            |data, path| match serde_yaml::from_str::<Vec<TestSpec>>(&data) {
-                Ok(spec) => Ok(spec),
-                Err(..) => match serde_json::from_str::<Vec<TestSpec>>(&data) {
-                    Ok(spec) => Ok(spec),
-                    Err(e) => Err(Error::ParseError(format!(
-                        "Unable to process data in file {}, Error {}",
-                        path.display(),
-                        e
-                    ))),
-                },
-            },
-        ) {
+        for specs in iterate_over(self.data_test_files, |data, path| {
+            read_test_specs(&data, path)
+        }) {
             match specs {
                 Err(e) => {
                     return Ok(TestResult::Err(Err {
                         rule_file: file.to_owned(),
                         error: e.to_string(),
+                        location: None,
                         time: now.elapsed().as_millis(),
                     }))
                 }
@@ -245,7 +258,8 @@ impl<'reporter> StructuredTestReporter<'reporter> {
                     for each in &test_data {
                         let now = Instant::now();
                         let mut root_scope =
-                            eval_context::root_scope(rule, Rc::clone(&each.path_value));
+                            eval_context::root_scope(rule, Rc::clone(&each.path_value))
+                                .with_selection(self.selection);
 
                         eval_rules_file(rule, &mut root_scope, None)?;
 
@@ -265,6 +279,7 @@ impl<'reporter> StructuredTestReporter<'reporter> {
                                         return Ok(TestResult::Err(Err {
                                             rule_file: file.to_owned(),
                                             error: e.to_string(),
+                                            location: None,
                                             time: now.elapsed().as_millis(),
                                         }))
                                     }
@@ -310,6 +325,7 @@ fn get_test_data(specs: Vec<TestSpec>) -> crate::rules::Result<Vec<TestData>> {
              name,
              input,
              expectations,
+             ..
          }| {
             let root = PathAwareValue::try_from(input)?;
             acc.push(TestData {
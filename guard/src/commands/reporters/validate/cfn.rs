@@ -1,7 +1,7 @@
 use fancy_regex::Regex;
 use std::{
     cmp::max,
-    collections::{BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     io::Write,
     rc::Rc,
 };
@@ -25,6 +25,7 @@ use crate::{
             simplified_json_from_root, BinaryComparison, ClauseReport, EventRecord, FileReport,
             InComparison, RuleReport, UnaryComparison,
         },
+        messages::MessageCatalog,
         path_value::{
             traversal::{Node, Traversal, TraversalResult},
             PathAwareValue,
@@ -43,11 +44,18 @@ lazy_static! {
 #[derive(Debug)]
 pub(crate) struct CfnAware<'reporter> {
     next: Option<&'reporter dyn Reporter>,
+    messages: &'reporter MessageCatalog,
 }
 
 impl<'reporter> CfnAware<'reporter> {
-    pub(crate) fn new_with(next: &'reporter dyn Reporter) -> CfnAware {
-        CfnAware { next: Some(next) }
+    pub(crate) fn new_with(
+        next: &'reporter dyn Reporter,
+        messages: &'reporter MessageCatalog,
+    ) -> CfnAware<'reporter> {
+        CfnAware {
+            next: Some(next),
+            messages,
+        }
     }
 }
 
@@ -81,7 +89,7 @@ impl<'reporter> Reporter for CfnAware<'reporter> {
         let root = data.root().unwrap();
 
         if data.at("/Resources", root).is_ok() {
-            let failure_report = simplified_json_from_root(root_record)?;
+            let failure_report = simplified_json_from_root(root_record, self.messages)?;
             match output_type {
                 OutputFormatType::YAML => serde_yaml::to_writer(write, &failure_report)?,
                 OutputFormatType::JSON => serde_json::to_writer_pretty(write, &failure_report)?,
@@ -114,6 +122,7 @@ impl<'reporter> Reporter for CfnAware<'reporter> {
                 }
                 OutputFormatType::Junit => unreachable!(),
                 OutputFormatType::Sarif => unreachable!(),
+                OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
             };
 
             Ok(())
@@ -176,7 +185,7 @@ fn single_line(
     }
 
     let root = data.root().unwrap();
-    let mut by_resources = HashMap::new();
+    let mut by_resources = BTreeMap::new();
     for (key, value) in path_tree.range(String::from("/Resources")..) {
         let matches = key.matches('/').count();
         let mut count = 1;
@@ -397,7 +406,9 @@ fn single_line(
                     ) -> rules::Result<()> {
                         writeln!(writer, "{prefix}Code:", prefix = prefix)?;
                         let new_prefix = format!("{}  ", prefix);
-                        if let Some((num, line)) = self.code_segment.seek_line(max(1, line - 2)) {
+                        if let Some((num, line)) =
+                            self.code_segment.seek_line(max(1, line.saturating_sub(2)))
+                        {
                             let line =
                                 format!("{num:>5}.{line}", num = num, line = line).bright_green();
                             writeln!(writer, "{prefix}{line}", prefix = new_prefix, line = line)?;
@@ -457,7 +468,7 @@ fn handle_resource_aggr<'record, 'value: 'record>(
     data: &'value Traversal<'_>,
     root: &'value Node<'_>,
     name: String,
-    by_resources: &mut HashMap<String, LocalResourceAggr<'record, 'value>>,
+    by_resources: &mut BTreeMap<String, LocalResourceAggr<'record, 'value>>,
     value: &[Rc<crate::commands::reporters::validate::common::Node<'record, 'value>>],
 ) -> Option<()> {
     let path = format!("/Resources/{}", name);
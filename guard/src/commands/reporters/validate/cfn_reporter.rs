@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Debug;
 use std::io::Write;
 
@@ -50,9 +50,10 @@ impl Reporter for CfnReporter {
                     as Box<dyn GenericReporter>,
                 OutputFormatType::Junit => unreachable!(),
                 OutputFormatType::Sarif => unreachable!(),
+                OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
             };
         let failed = if !failed_rules.is_empty() {
-            let mut by_resource_name = HashMap::new();
+            let mut by_resource_name = BTreeMap::new();
             for (idx, each_failed_rule) in failed_rules.iter().enumerate() {
                 let failed = find_all_failing_clauses(each_failed_rule);
                 for (clause_idx, each_failing_clause) in failed.iter().enumerate() {
@@ -102,7 +103,7 @@ impl Reporter for CfnReporter {
             }
             by_resource_name
         } else {
-            HashMap::new()
+            BTreeMap::new()
         };
         let as_vec = passed_or_skipped.to_vec();
         let (skipped, passed): (Vec<&StatusContext>, Vec<&StatusContext>) =
@@ -114,11 +115,11 @@ impl Reporter for CfnReporter {
         let skipped = skipped
             .iter()
             .map(|s| s.context.clone())
-            .collect::<HashSet<String>>();
+            .collect::<BTreeSet<String>>();
         let passed = passed
             .iter()
             .map(|s| s.context.clone())
-            .collect::<HashSet<String>>();
+            .collect::<BTreeSet<String>>();
         renderer.report(
             writer,
             rules_file,
@@ -153,6 +154,7 @@ impl Reporter for CfnReporter {
                     as Box<dyn GenericReporter>,
                 OutputFormatType::Junit => unreachable!(),
                 OutputFormatType::Sarif => unreachable!(),
+                OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
             };
         super::common::report_from_events(
             _root_record,
@@ -173,9 +175,9 @@ impl super::common::GenericReporter for SingleLineReporter {
         writer: &mut dyn Write,
         rules_file_name: &str,
         data_file_name: &str,
-        by_resource_name: HashMap<String, Vec<NameInfo<'_>>>,
-        passed: HashSet<String>,
-        skipped: HashSet<String>,
+        by_resource_name: BTreeMap<String, Vec<NameInfo<'_>>>,
+        passed: BTreeSet<String>,
+        skipped: BTreeSet<String>,
         longest_rule_len: usize,
     ) -> crate::rules::Result<()> {
         writeln!(
@@ -13,7 +13,7 @@ use crate::rules::{
 };
 use fancy_regex::Regex;
 use lazy_static::*;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::convert::TryInto;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
@@ -66,9 +66,9 @@ pub(super) trait GenericReporter: Debug {
         writer: &mut dyn Write,
         rules_file_name: &str,
         data_file_name: &str,
-        failed: HashMap<String, Vec<NameInfo<'_>>>,
-        passed: HashSet<String>,
-        skipped: HashSet<String>,
+        failed: BTreeMap<String, Vec<NameInfo<'_>>>,
+        passed: BTreeSet<String>,
+        skipped: BTreeSet<String>,
         longest_rule_len: usize,
     ) -> crate::rules::Result<()>;
 }
@@ -95,9 +95,9 @@ impl StructuredSummary {
 struct DataOutput<'a> {
     data_from: &'a str,
     rules_from: &'a str,
-    not_compliant: HashMap<String, Vec<NameInfo<'a>>>,
-    not_applicable: HashSet<String>,
-    compliant: HashSet<String>,
+    not_compliant: BTreeMap<String, Vec<NameInfo<'a>>>,
+    not_applicable: BTreeSet<String>,
+    compliant: BTreeSet<String>,
 }
 
 impl GenericReporter for StructuredSummary {
@@ -106,9 +106,9 @@ impl GenericReporter for StructuredSummary {
         writer: &mut dyn Write,
         rules_file_name: &str,
         data_file_name: &str,
-        failed: HashMap<String, Vec<NameInfo<'_>>>,
-        passed: HashSet<String>,
-        skipped: HashSet<String>,
+        failed: BTreeMap<String, Vec<NameInfo<'_>>>,
+        passed: BTreeSet<String>,
+        skipped: BTreeSet<String>,
         _: usize,
     ) -> crate::rules::Result<()> {
         let value = DataOutput {
@@ -338,9 +338,9 @@ pub(super) fn report_from_events(
     renderer: &dyn GenericReporter,
 ) -> crate::rules::Result<()> {
     let mut longest_rule_length = 0;
-    let mut failed = HashMap::new();
-    let mut skipped = HashSet::new();
-    let mut success = HashSet::new();
+    let mut failed = BTreeMap::new();
+    let mut skipped = BTreeSet::new();
+    let mut success = BTreeSet::new();
     for each_rule in &root_record.children {
         if let Some(RecordType::RuleCheck(NamedStatus { status, name, .. })) = &each_rule.container
         {
@@ -481,8 +481,8 @@ pub(super) fn find_all_failing_clauses(context: &StatusContext) -> Vec<&StatusCo
 
 pub(super) fn print_compliant_skipped_info(
     writer: &mut dyn Write,
-    passed: &HashSet<String>,
-    skipped: &HashSet<String>,
+    passed: &BTreeSet<String>,
+    skipped: &BTreeSet<String>,
     _: &str,
     data_file_name: &str,
 ) -> crate::rules::Result<()> {
@@ -4,8 +4,8 @@ use crate::rules::eval_context::EventRecord;
 use crate::rules::path_value::traversal::Traversal;
 use crate::rules::values::CmpOperator;
 use crate::rules::{
-    BlockCheck, ClauseCheck, ComparisonClauseCheck, NamedStatus, QueryResult, RecordType, Status,
-    TypeBlockCheck, UnaryValueCheck, ValueCheck,
+    BlockCheck, ClauseCheck, ComparisonClauseCheck, GuardClauseCheck, NamedStatus, QueryResult,
+    RecordType, Status, TypeBlockCheck, UnaryValueCheck, ValueCheck,
 };
 use std::io::Write;
 use std::rc::Rc;
@@ -31,8 +31,12 @@ fn pprint_failed_sub_tree(
             status: Status::FAIL,
             ..
         }))
-        | Some(RecordType::GuardClauseBlockCheck(BlockCheck {
-            status: Status::FAIL,
+        | Some(RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+            block:
+                BlockCheck {
+                    status: Status::FAIL,
+                    ..
+                },
             ..
         }))
         | Some(RecordType::TypeCheck(TypeBlockCheck {
@@ -263,11 +267,13 @@ fn pprint_failed_sub_tree(
                                 to=to_result.map_or("NULL".to_string(), |t| format!("{}", t)),
                                 op_msg=match cmp {
                                     CmpOperator::Eq => if *not { "equal to" } else { "not equal to" },
+                                    CmpOperator::UnorderedEq => if *not { "equal in any order to" } else { "not equal in any order to" },
                                     CmpOperator::Le => if *not { "less than equal to" } else { "not less than equal to" },
                                     CmpOperator::Lt => if *not { "less than" } else { "not less than" },
                                     CmpOperator::Ge => if *not { "greater than equal to" } else { "not greater than equal" },
                                     CmpOperator::Gt => if *not { "greater than" } else { "not greater than" },
                                     CmpOperator::In => if *not { "in" } else { "not in" },
+                                    CmpOperator::Like => if *not { "like" } else { "not like" },
                                     _ => unreachable!()
                                 },
                                 err=error_message,
@@ -0,0 +1,188 @@
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::commands::validate::RuleFileError;
+use crate::rules::eval_context::{ClauseReport, FileReport, Messages, ValueComparisons};
+use crate::rules::path_value::{Location, Path, PathAwareValue};
+use crate::rules::Result;
+
+/// a row of the csv and tsv reports, one per rule and data file, and one per failed check of the
+/// rules that failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Row {
+    pub(crate) data_file: String,
+    pub(crate) rule: String,
+    /// the logical id of the resource of a CloudFormation template the check failed on, otherwise
+    /// the path of the value
+    pub(crate) resource: String,
+    pub(crate) status: &'static str,
+    pub(crate) message: String,
+}
+
+/// the rows of every data file, after a row for each rules file that failed to parse
+pub(crate) fn rows(reports: &[FileReport<'_>], rule_errors: &[RuleFileError<'_>]) -> Vec<Row> {
+    let mut rows = rule_errors
+        .iter()
+        .map(|rule_file_error| Row {
+            data_file: String::new(),
+            rule: rule_file_error.file_name.to_string(),
+            resource: String::new(),
+            status: "ERROR",
+            message: rule_file_error.error.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    for report in reports {
+        let row = |rule: &str, resource: String, status, message: String| Row {
+            data_file: report.name.to_string(),
+            rule: rule.to_string(),
+            resource,
+            status,
+            message,
+        };
+
+        for (failures, status) in [
+            (&report.not_compliant, "FAIL"),
+            (&report.baselined, "BASELINED"),
+        ] {
+            for failure in failures {
+                let mut checks = vec![];
+                failed_checks(failure, &mut checks);
+                let rule = match failure {
+                    ClauseReport::Rule(rule) => rule.name,
+                    _ => "",
+                };
+                rows.extend(
+                    checks
+                        .into_iter()
+                        .map(|check| row(rule, check.resource, status, check.message)),
+                );
+            }
+        }
+
+        rows.extend(report.suppressed.iter().map(|suppressed| {
+            row(
+                suppressed.rule,
+                suppressed.suppression.resource.clone(),
+                "SUPPRESSED",
+                suppressed
+                    .suppression
+                    .justification
+                    .clone()
+                    .unwrap_or_default(),
+            )
+        }));
+        rows.extend(
+            report
+                .compliant
+                .iter()
+                .map(|rule| row(rule, String::new(), "PASS", String::new())),
+        );
+        rows.extend(
+            report
+                .not_applicable
+                .iter()
+                .map(|rule| row(rule, String::new(), "SKIP", String::new())),
+        );
+    }
+
+    rows
+}
+
+/// writes the rows with a header, comma separated for csv and tab separated for tsv
+pub(crate) fn write_rows(rows: &[Row], delimiter: u8, writer: &mut dyn Write) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(writer);
+    for row in rows {
+        writer.serialize(row).map_err(std::io::Error::from)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// a check that failed under a clause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FailedCheck {
+    /// the logical id of the resource of a CloudFormation template the check failed on, otherwise
+    /// the path of the value
+    pub(crate) resource: String,
+    /// the path of the value the check failed on, empty when there is none
+    pub(crate) path: String,
+    /// where the value is in the data file, with lines and columns starting at 0
+    pub(crate) location: Option<Location>,
+    pub(crate) message: String,
+}
+
+impl FailedCheck {
+    fn new(value: Option<std::rc::Rc<PathAwareValue>>, message: String) -> Self {
+        let (path, location) = match value {
+            Some(value) => {
+                let Path(path, location) = value.self_path();
+                (path.clone(), Some(*location))
+            }
+            None => (String::new(), None),
+        };
+        FailedCheck {
+            resource: resource(&path),
+            path,
+            location,
+            message,
+        }
+    }
+}
+
+/// each check that failed under the clause
+pub(crate) fn failed_checks(clause: &ClauseReport<'_>, checks: &mut Vec<FailedCheck>) {
+    match clause {
+        ClauseReport::Rule(rule) if !rule.checks.is_empty() => {
+            for check in &rule.checks {
+                failed_checks(check, checks);
+            }
+        }
+        // a rule can fail without any check to show for it, e.g. a failed dependency
+        ClauseReport::Rule(rule) => checks.push(FailedCheck::new(None, message(&rule.messages))),
+        ClauseReport::Disjunctions(disjunctions) => {
+            for check in &disjunctions.checks {
+                failed_checks(check, checks);
+            }
+        }
+        ClauseReport::Block(block) => checks.push(FailedCheck::new(
+            block.value_from(),
+            message(&block.messages),
+        )),
+        ClauseReport::Clause(check) => checks.push(FailedCheck::new(
+            check.value_from(),
+            clause
+                .get_message()
+                .iter()
+                .map(message)
+                .collect::<Vec<_>>()
+                .join(" "),
+        )),
+    }
+}
+
+fn resource(path: &str) -> String {
+    match path.strip_prefix("/Resources/") {
+        Some(rest) => rest.split('/').next().unwrap_or_default().to_string(),
+        None => path.to_string(),
+    }
+}
+
+fn message(messages: &Messages) -> String {
+    messages
+        .custom_message
+        .iter()
+        .chain(messages.error_message.iter())
+        .map(|message| message.trim())
+        .filter(|message| !message.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+#[path = "delimited_tests.rs"]
+mod delimited_tests;
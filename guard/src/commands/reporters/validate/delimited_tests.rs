@@ -0,0 +1,157 @@
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use super::*;
+use crate::rules::errors::Error;
+use crate::rules::eval_context::{RuleReport, SuppressedReport};
+use crate::rules::path_value::{Location, Path};
+use crate::rules::suppressions::Suppression;
+use pretty_assertions::assert_eq;
+
+fn row(data_file: &str, rule: &str, resource: &str, status: &'static str, message: &str) -> Row {
+    Row {
+        data_file: data_file.to_string(),
+        rule: rule.to_string(),
+        resource: resource.to_string(),
+        status,
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn test_resource() {
+    assert_eq!(
+        "Bucket",
+        resource("/Resources/Bucket/Properties/BucketName")
+    );
+    assert_eq!("/spec/containers/0", resource("/spec/containers/0"));
+    assert_eq!("", resource(""));
+}
+
+#[test]
+fn test_failed_check() {
+    let value = Rc::new(PathAwareValue::String((
+        Path::new(
+            String::from("/Resources/Bucket/Properties/BucketName"),
+            4,
+            6,
+        ),
+        String::from("value"),
+    )));
+
+    let check = FailedCheck::new(Some(value), String::from("Check was not compliant"));
+    assert_eq!("Bucket", check.resource);
+    assert_eq!("/Resources/Bucket/Properties/BucketName", check.path);
+    assert_eq!(Some(Location::new(4, 6)), check.location);
+
+    let check = FailedCheck::new(None, String::new());
+    assert_eq!(("", ""), (check.resource.as_str(), check.path.as_str()));
+    assert_eq!(None, check.location);
+}
+
+#[test]
+fn test_message() {
+    let messages = Messages {
+        custom_message: Some(String::from(" Buckets must be encrypted\n")),
+        error_message: Some(String::from("Check was not compliant")),
+        message_id: None,
+        location: None,
+    };
+    assert_eq!(
+        "Buckets must be encrypted Check was not compliant",
+        message(&messages)
+    );
+    assert_eq!("", message(&Messages::default()));
+}
+
+#[test]
+fn test_rows() {
+    let report = FileReport {
+        name: "template.yaml",
+        not_compliant: vec![ClauseReport::Rule(RuleReport {
+            name: "s3_encrypted",
+            messages: Messages {
+                custom_message: Some(String::from("depends on s3_exists")),
+                ..Default::default()
+            },
+            ..Default::default()
+        })],
+        compliant: BTreeSet::from([String::from("s3_exists")]),
+        not_applicable: BTreeSet::from([String::from("iam_no_wildcards")]),
+        suppressed: vec![SuppressedReport {
+            rule: "s3_logging",
+            suppression: Suppression {
+                resource: String::from("LogBucket"),
+                justification: Some(String::from("the logs bucket itself")),
+            },
+        }],
+        ..Default::default()
+    };
+    let rule_errors = [RuleFileError {
+        file_name: "broken.guard",
+        error: Error::ParseError(String::from("unexpected end of input")),
+        following_errors: vec![],
+    }];
+
+    assert_eq!(
+        vec![
+            row(
+                "",
+                "broken.guard",
+                "",
+                "ERROR",
+                &Error::ParseError(String::from("unexpected end of input")).to_string()
+            ),
+            row(
+                "template.yaml",
+                "s3_encrypted",
+                "",
+                "FAIL",
+                "depends on s3_exists"
+            ),
+            row(
+                "template.yaml",
+                "s3_logging",
+                "LogBucket",
+                "SUPPRESSED",
+                "the logs bucket itself"
+            ),
+            row("template.yaml", "s3_exists", "", "PASS", ""),
+            row("template.yaml", "iam_no_wildcards", "", "SKIP", ""),
+        ],
+        rows(&[report], &rule_errors)
+    );
+}
+
+#[test]
+fn test_write_rows() -> Result<()> {
+    let rows = [
+        row(
+            "template.yaml",
+            "s3_encrypted",
+            "Bucket",
+            "FAIL",
+            "expected \"aws:kms\", found AES256",
+        ),
+        row("template.yaml", "s3_exists", "", "PASS", ""),
+    ];
+
+    let mut csv = vec![];
+    write_rows(&rows, b',', &mut csv)?;
+    assert_eq!(
+        "data_file,rule,resource,status,message\n\
+         template.yaml,s3_encrypted,Bucket,FAIL,\"expected \"\"aws:kms\"\", found AES256\"\n\
+         template.yaml,s3_exists,,PASS,\n",
+        String::from_utf8(csv).unwrap()
+    );
+
+    let mut tsv = vec![];
+    write_rows(&rows[1..], b'\t', &mut tsv)?;
+    assert_eq!(
+        "data_file\trule\tresource\tstatus\tmessage\n\
+         template.yaml\ts3_exists\t\tPASS\t\n",
+        String::from_utf8(tsv).unwrap()
+    );
+
+    Ok(())
+}
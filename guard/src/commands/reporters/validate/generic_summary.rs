@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Debug;
 use std::io::Write;
 
@@ -11,21 +11,29 @@ use crate::rules::{EvaluationType, Status};
 use super::common::*;
 use super::summary_table::SummaryType;
 use crate::rules::eval_context::{simplified_json_from_root, EventRecord};
+use crate::rules::messages::MessageCatalog;
 use crate::rules::path_value::traversal::Traversal;
 use crate::rules::values::CmpOperator;
 
 #[derive(Debug)]
-pub(crate) struct GenericSummary {
+pub(crate) struct GenericSummary<'reporter> {
     summary_table: BitFlags<SummaryType>,
+    messages: &'reporter MessageCatalog,
 }
 
-impl GenericSummary {
-    pub(crate) fn new(summary_table: BitFlags<SummaryType>) -> Self {
-        GenericSummary { summary_table }
+impl<'reporter> GenericSummary<'reporter> {
+    pub(crate) fn new(
+        summary_table: BitFlags<SummaryType>,
+        messages: &'reporter MessageCatalog,
+    ) -> Self {
+        GenericSummary {
+            summary_table,
+            messages,
+        }
     }
 }
 
-impl Reporter for GenericSummary {
+impl<'reporter> Reporter for GenericSummary<'reporter> {
     fn report(
         &self,
         writer: &mut dyn Write,
@@ -50,9 +58,10 @@ impl Reporter for GenericSummary {
             }
             OutputFormatType::Junit => unreachable!(),
             OutputFormatType::Sarif => unreachable!(),
+            OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
         };
         let failed = if !failed_rules.is_empty() {
-            let mut by_rule = HashMap::with_capacity(failed_rules.len());
+            let mut by_rule = BTreeMap::new();
             for each_failed_rule in failed_rules {
                 for each_failed_clause in find_all_failing_clauses(each_failed_rule) {
                     match each_failed_clause.eval_type {
@@ -85,7 +94,7 @@ impl Reporter for GenericSummary {
             }
             by_rule
         } else {
-            HashMap::new()
+            BTreeMap::new()
         };
 
         let as_vec = passed_or_skipped.to_vec();
@@ -98,11 +107,11 @@ impl Reporter for GenericSummary {
         let skipped = skipped
             .iter()
             .map(|s| s.context.clone())
-            .collect::<HashSet<String>>();
+            .collect::<BTreeSet<String>>();
         let passed = passed
             .iter()
             .map(|s| s.context.clone())
-            .collect::<HashSet<String>>();
+            .collect::<BTreeSet<String>>();
         renderer.report(
             writer,
             rules_file,
@@ -126,7 +135,7 @@ impl Reporter for GenericSummary {
         _data: &Traversal<'value>,
         output_type: OutputFormatType,
     ) -> crate::rules::Result<()> {
-        let failure_repord = simplified_json_from_root(root_record)?;
+        let failure_repord = simplified_json_from_root(root_record, self.messages)?;
 
         match output_type {
             OutputFormatType::JSON => serde_json::to_writer_pretty(writer, &failure_repord)?,
@@ -141,6 +150,7 @@ impl Reporter for GenericSummary {
                 }),
             )?,
             OutputFormatType::Sarif => unreachable!(),
+            OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
             OutputFormatType::Junit => unreachable!(),
         };
 
@@ -156,9 +166,9 @@ struct SingleLineSummary {
 impl SingleLineSummary {
     fn is_reportable(
         &self,
-        failed: &HashMap<String, Vec<NameInfo<'_>>>,
-        passed: &HashSet<String>,
-        skipped: &HashSet<String>,
+        failed: &BTreeMap<String, Vec<NameInfo<'_>>>,
+        passed: &BTreeSet<String>,
+        skipped: &BTreeSet<String>,
     ) -> bool {
         if self.summary_table.is_empty() {
             return false;
@@ -242,7 +252,7 @@ fn binary_error_message(
 
 fn print_rules_output(
     writer: &mut dyn Write,
-    rules: HashSet<String>,
+    rules: BTreeSet<String>,
     descriptor: &str,
     data_file_name: &str,
 ) -> crate::rules::Result<()> {
@@ -265,9 +275,9 @@ impl GenericReporter for SingleLineSummary {
         writer: &mut dyn Write,
         rules_file_name: &str,
         data_file_name: &str,
-        failed: HashMap<String, Vec<NameInfo<'_>>>,
-        passed: HashSet<String>,
-        skipped: HashSet<String>,
+        failed: BTreeMap<String, Vec<NameInfo<'_>>>,
+        passed: BTreeSet<String>,
+        skipped: BTreeSet<String>,
         longest_rule_len: usize,
     ) -> crate::rules::Result<()> {
         if !self.is_reportable(&failed, &passed, &skipped) {
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::commands::reporters::validate::delimited::failed_checks;
+use crate::commands::summary::{ERROR_SEVERITY, WARNING_SEVERITY};
+use crate::commands::validate::GroupBy;
+use crate::rules::eval_context::{ClauseReport, FileReport};
+use crate::rules::exprs::Severity;
+use crate::rules::path_value::{Location, PathAwareValue};
+use crate::rules::Result;
+
+/// the group of the checks that are not about a resource of a CloudFormation template, when
+/// grouping by resource type
+pub(crate) const NO_RESOURCE_TYPE: &str = "(none)";
+
+/// a failed check of a rule against a data file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Finding {
+    pub(crate) data_file: String,
+    pub(crate) rule: String,
+    /// the logical id of the resource of a CloudFormation template the check failed on, otherwise
+    /// the path of the value
+    pub(crate) resource: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) resource_type: Option<String>,
+    /// the path of the value the check failed on, empty when there is none
+    pub(crate) path: String,
+    /// where the value the check failed on is in the data file, only in the v2 layout of the
+    /// output, see `--output-version`
+    #[serde(skip)]
+    pub(crate) location: Option<Location>,
+    pub(crate) severity: &'static str,
+    pub(crate) message: String,
+}
+
+/// the findings of a rule, a resource type or a severity, see `--group-by`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Group {
+    pub(crate) group: String,
+    pub(crate) findings: Vec<Finding>,
+}
+
+/// the failed checks of the report of a data file, with the type of the resource each failed on
+/// looked up in the data file
+pub(crate) fn findings(report: &FileReport<'_>, data: &PathAwareValue) -> Vec<Finding> {
+    failure_findings(report.name, &report.not_compliant, data)
+}
+
+/// the failed checks of the failures of a rule against the data file
+pub(crate) fn failure_findings(
+    data_file: &str,
+    failures: &[ClauseReport<'_>],
+    data: &PathAwareValue,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+    for failure in failures {
+        let rule = match failure {
+            ClauseReport::Rule(rule) => rule.name,
+            _ => "",
+        };
+        let severity = match failure.severity() {
+            Severity::Error => ERROR_SEVERITY,
+            Severity::Warning => WARNING_SEVERITY,
+        };
+
+        let mut checks = vec![];
+        failed_checks(failure, &mut checks);
+        findings.extend(checks.into_iter().map(|check| Finding {
+            data_file: data_file.to_string(),
+            rule: rule.to_string(),
+            resource_type: resource_type(data, &check.resource),
+            resource: check.resource,
+            path: check.path,
+            location: check.location,
+            severity,
+            message: check.message,
+        }));
+    }
+
+    findings
+}
+
+/// the `Type` of the resource with the logical id in the template
+fn resource_type(template: &PathAwareValue, logical_id: &str) -> Option<String> {
+    let resources = match template {
+        PathAwareValue::Map((_, map)) => match map.values.get("Resources") {
+            Some(PathAwareValue::Map((_, resources))) => resources,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match resources.values.get(logical_id) {
+        Some(PathAwareValue::Map((_, resource))) => match resource.values.get("Type") {
+            Some(PathAwareValue::String((_, resource_type))) => Some(resource_type.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// the findings grouped by the rule, the resource type or the severity, the groups with the most
+/// findings first, each keeping the findings in the order of the data files. Grouping by file
+/// keeps a group per data file
+pub(crate) fn group(findings: Vec<Finding>, group_by: GroupBy) -> Vec<Group> {
+    let mut groups = BTreeMap::<String, Vec<Finding>>::new();
+    for finding in findings {
+        let key = match group_by {
+            GroupBy::File => finding.data_file.clone(),
+            GroupBy::Rule => finding.rule.clone(),
+            GroupBy::ResourceType => finding
+                .resource_type
+                .clone()
+                .unwrap_or_else(|| NO_RESOURCE_TYPE.to_string()),
+            GroupBy::Severity => finding.severity.to_string(),
+        };
+        groups.entry(key).or_default().push(finding);
+    }
+
+    let mut groups = groups
+        .into_iter()
+        .map(|(group, findings)| Group { group, findings })
+        .collect::<Vec<_>>();
+    // stable, the groups with as many findings stay sorted by name
+    groups.sort_by(|left, right| right.findings.len().cmp(&left.findings.len()));
+    groups
+}
+
+/// the same violation repeated across the data files, see `--top-offenders`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Offender {
+    pub(crate) rule: String,
+    /// the shape of the paths of the values the rule failed on, see [`path_shape`]
+    pub(crate) path: String,
+    pub(crate) failures: usize,
+    /// the data files with the violation, in the order they were evaluated
+    pub(crate) data_files: Vec<String>,
+}
+
+/// the path with the logical id of the resource of a CloudFormation template and the indices of
+/// lists replaced with `*`, so the failures of a rule on the same property of different resources
+/// or files have the same shape, e.g. `/Resources/*/Properties/Tags/*/Key`
+pub(crate) fn path_shape(path: &str) -> String {
+    let mut previous = "";
+    path.split('/')
+        .enumerate()
+        .map(|(index, segment)| {
+            let shape =
+                if (index == 2 && previous == "Resources") || segment.parse::<usize>().is_ok() {
+                    "*"
+                } else {
+                    segment
+                };
+            previous = segment;
+            shape
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// the findings of the same rule on paths of the same shape aggregated across the data files,
+/// the most repeated first
+pub(crate) fn offenders(findings: &[Finding]) -> Vec<Offender> {
+    let mut offenders = BTreeMap::<(&str, String), Offender>::new();
+    for finding in findings {
+        let path = path_shape(&finding.path);
+        let offender = offenders
+            .entry((finding.rule.as_str(), path.clone()))
+            .or_insert_with(|| Offender {
+                rule: finding.rule.clone(),
+                path,
+                failures: 0,
+                data_files: vec![],
+            });
+        offender.failures += 1;
+        if !offender.data_files.contains(&finding.data_file) {
+            offender.data_files.push(finding.data_file.clone());
+        }
+    }
+
+    let mut offenders = offenders.into_values().collect::<Vec<_>>();
+    // stable, the violations repeated as often stay sorted by rule and path
+    offenders.sort_by(|left, right| right.failures.cmp(&left.failures));
+    offenders
+}
+
+/// writes the groups for the console, a line for each group followed by a line for each of its
+/// findings, leaving out what the findings are grouped by. At most `max_findings_per_rule`
+/// findings of each rule are listed across the groups, the rest are counted
+pub(crate) fn write_groups(
+    groups: &[Group],
+    group_by: GroupBy,
+    max_findings_per_rule: Option<usize>,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let label = match group_by {
+        GroupBy::File => "Data file",
+        GroupBy::Rule => "Rule",
+        GroupBy::ResourceType => "Resource type",
+        GroupBy::Severity => "Severity",
+    };
+
+    let mut listed = BTreeMap::<&str, usize>::new();
+    for group in groups {
+        let count = group.findings.len();
+        writeln!(
+            writer,
+            "{label} {}: {count} {}",
+            group.group,
+            failures(count)
+        )?;
+
+        let mut omitted = 0;
+        for finding in &group.findings {
+            let rule_listed = listed.entry(finding.rule.as_str()).or_default();
+            if max_findings_per_rule.map_or(false, |max| *rule_listed >= max) {
+                omitted += 1;
+                continue;
+            }
+            *rule_listed += 1;
+
+            let mut columns = vec![];
+            if group_by != GroupBy::File {
+                columns.push(finding.data_file.as_str());
+            }
+            if group_by != GroupBy::Rule {
+                columns.push(finding.rule.as_str());
+            }
+            if !finding.resource.is_empty() {
+                columns.push(finding.resource.as_str());
+            }
+            writeln!(
+                writer,
+                "    {}: {}",
+                columns.join(" "),
+                finding.message.replace('\n', " ")
+            )?;
+        }
+
+        if omitted > 0 {
+            writeln!(
+                writer,
+                "    ... {omitted} more {} not shown, see --max-findings-per-rule",
+                failures(omitted)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// writes the `top` most repeated violations for the console, a line for each with the data files
+/// it is in below it
+pub(crate) fn write_offenders(
+    offenders: &[Offender],
+    top: usize,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    if offenders.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "Top offenders:")?;
+    for offender in offenders.iter().take(top) {
+        let files = offender.data_files.len();
+        writeln!(
+            writer,
+            "    {}{}: {} {} in {files} data {}",
+            offender.rule,
+            if offender.path.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", offender.path)
+            },
+            offender.failures,
+            failures(offender.failures),
+            if files == 1 { "file" } else { "files" }
+        )?;
+        writeln!(writer, "        {}", offender.data_files.join(", "))?;
+    }
+
+    Ok(())
+}
+
+fn failures(count: usize) -> &'static str {
+    if count == 1 {
+        "failure"
+    } else {
+        "failures"
+    }
+}
+
+#[cfg(test)]
+#[path = "grouped_tests.rs"]
+mod grouped_tests;
@@ -0,0 +1,247 @@
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+use super::*;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::{root_scope, simplified_json_from_root};
+use crate::rules::exprs::RulesFile;
+use crate::rules::messages::MessageCatalog;
+use pretty_assertions::assert_eq;
+
+fn finding(
+    data_file: &str,
+    rule: &str,
+    resource: &str,
+    resource_type: Option<&str>,
+    severity: &'static str,
+) -> Finding {
+    Finding {
+        data_file: data_file.to_string(),
+        rule: rule.to_string(),
+        resource: resource.to_string(),
+        resource_type: resource_type.map(String::from),
+        path: format!("/Resources/{resource}/Properties/BucketEncryption"),
+        location: None,
+        severity,
+        message: String::from("Check was not compliant"),
+    }
+}
+
+#[test]
+fn test_findings() -> Result<()> {
+    let rules = RulesFile::try_from(
+        r#"
+rule s3_encrypted {
+    Resources.*[ Type == 'AWS::S3::Bucket' ].Properties.BucketEncryption exists
+}
+
+@severity(warning)
+rule has_description {
+    Description exists
+}
+"#,
+    )?;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(
+        r#"
+Resources:
+  bucket:
+    Type: AWS::S3::Bucket
+    Properties: {}
+"#,
+    )?)?;
+
+    let mut root = root_scope(&rules, Rc::new(value.clone()));
+    eval_rules_file(&rules, &mut root, Some("template.yaml"))?;
+    let top = root.reset_recorder().extract();
+    let report = simplified_json_from_root(&top, &MessageCatalog::default())?;
+
+    let findings = findings(&report, &value);
+    assert_eq!(2, findings.len());
+    assert_eq!("template.yaml", findings[0].data_file);
+    assert_eq!("bucket", findings[0].resource);
+    assert_eq!(
+        (
+            "s3_encrypted",
+            Some(String::from("AWS::S3::Bucket")),
+            ERROR_SEVERITY
+        ),
+        (
+            findings[0].rule.as_str(),
+            findings[0].resource_type.clone(),
+            findings[0].severity
+        )
+    );
+    assert_eq!(
+        ("has_description", None, WARNING_SEVERITY),
+        (
+            findings[1].rule.as_str(),
+            findings[1].resource_type.clone(),
+            findings[1].severity
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_group() {
+    let findings = vec![
+        finding(
+            "a.yaml",
+            "s3_encrypted",
+            "bucket",
+            Some("AWS::S3::Bucket"),
+            ERROR_SEVERITY,
+        ),
+        finding("a.yaml", "has_description", "", None, WARNING_SEVERITY),
+        finding(
+            "b.yaml",
+            "s3_encrypted",
+            "logs",
+            Some("AWS::S3::Bucket"),
+            ERROR_SEVERITY,
+        ),
+        finding(
+            "b.yaml",
+            "iam_no_wildcards",
+            "role",
+            Some("AWS::IAM::Role"),
+            ERROR_SEVERITY,
+        ),
+    ];
+
+    let by_rule = group(findings.clone(), GroupBy::Rule)
+        .into_iter()
+        .map(|g| (g.group, g.findings.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (String::from("s3_encrypted"), 2),
+            (String::from("has_description"), 1),
+            (String::from("iam_no_wildcards"), 1),
+        ],
+        by_rule
+    );
+
+    let by_type = group(findings.clone(), GroupBy::ResourceType);
+    assert_eq!(
+        vec!["AWS::S3::Bucket", NO_RESOURCE_TYPE, "AWS::IAM::Role"],
+        by_type.iter().map(|g| g.group.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["bucket", "logs"],
+        by_type[0]
+            .findings
+            .iter()
+            .map(|f| f.resource.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    let by_severity = group(findings, GroupBy::Severity);
+    assert_eq!(
+        vec![ERROR_SEVERITY, WARNING_SEVERITY],
+        by_severity
+            .iter()
+            .map(|g| g.group.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_write_groups() -> Result<()> {
+    let groups = group(
+        vec![
+            finding(
+                "a.yaml",
+                "s3_encrypted",
+                "bucket",
+                Some("AWS::S3::Bucket"),
+                ERROR_SEVERITY,
+            ),
+            finding(
+                "b.yaml",
+                "s3_encrypted",
+                "logs",
+                Some("AWS::S3::Bucket"),
+                ERROR_SEVERITY,
+            ),
+            finding("a.yaml", "has_description", "", None, WARNING_SEVERITY),
+        ],
+        GroupBy::Rule,
+    );
+
+    let mut output = vec![];
+    write_groups(&groups, GroupBy::Rule, None, &mut output)?;
+    assert_eq!(
+        "Rule s3_encrypted: 2 failures\n    a.yaml bucket: Check was not compliant\n    b.yaml logs: Check was not compliant\nRule has_description: 1 failure\n    a.yaml: Check was not compliant\n",
+        String::from_utf8_lossy(&output)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_write_groups_with_max_findings_per_rule() -> Result<()> {
+    let groups = group(
+        vec![
+            finding("a.yaml", "s3_encrypted", "bucket", None, ERROR_SEVERITY),
+            finding("a.yaml", "s3_encrypted", "logs", None, ERROR_SEVERITY),
+            finding("b.yaml", "s3_encrypted", "bucket", None, ERROR_SEVERITY),
+            finding("b.yaml", "has_description", "", None, WARNING_SEVERITY),
+        ],
+        GroupBy::File,
+    );
+
+    let mut output = vec![];
+    write_groups(&groups, GroupBy::File, Some(1), &mut output)?;
+    assert_eq!(
+        "Data file a.yaml: 2 failures\n    s3_encrypted bucket: Check was not compliant\n    ... 1 more failure not shown, see --max-findings-per-rule\nData file b.yaml: 2 failures\n    has_description: Check was not compliant\n    ... 1 more failure not shown, see --max-findings-per-rule\n",
+        String::from_utf8_lossy(&output)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_path_shape() {
+    assert_eq!(
+        "/Resources/*/Properties/Tags/*/Key",
+        path_shape("/Resources/bucket/Properties/Tags/0/Key")
+    );
+    assert_eq!(
+        "/spec/containers/*/image",
+        path_shape("/spec/containers/1/image")
+    );
+    assert_eq!("", path_shape(""));
+}
+
+#[test]
+fn test_offenders() -> Result<()> {
+    let findings = vec![
+        finding("a.yaml", "s3_encrypted", "bucket", None, ERROR_SEVERITY),
+        finding("a.yaml", "has_description", "", None, WARNING_SEVERITY),
+        finding("a.yaml", "s3_encrypted", "logs", None, ERROR_SEVERITY),
+        finding("b.yaml", "s3_encrypted", "bucket", None, ERROR_SEVERITY),
+    ];
+
+    let offenders = offenders(&findings);
+    assert_eq!(
+        Offender {
+            rule: String::from("s3_encrypted"),
+            path: String::from("/Resources/*/Properties/BucketEncryption"),
+            failures: 3,
+            data_files: vec![String::from("a.yaml"), String::from("b.yaml")],
+        },
+        offenders[0]
+    );
+    assert_eq!(2, offenders.len());
+
+    let mut output = vec![];
+    write_offenders(&offenders, 1, &mut output)?;
+    assert_eq!(
+        "Top offenders:\n    s3_encrypted /Resources/*/Properties/BucketEncryption: 3 failures in 2 data files\n        a.yaml, b.yaml\n",
+        String::from_utf8_lossy(&output)
+    );
+
+    Ok(())
+}
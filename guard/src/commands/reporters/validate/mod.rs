@@ -2,9 +2,12 @@ pub mod cfn;
 pub mod cfn_reporter;
 pub mod common;
 pub mod console_reporter;
+pub mod delimited;
 pub mod generic_summary;
+pub mod grouped;
 pub mod sarif;
 pub mod structured;
 pub mod summary_table;
 pub mod tf;
+pub mod v2;
 pub mod xml;
@@ -32,7 +32,9 @@ impl From<&[FileReport<'_>]> for SarifRun {
 
         value
             .iter()
-            .filter(|report| matches!(report.status, Status::FAIL))
+            .filter(|report| {
+                matches!(report.status, Status::FAIL) || !report.not_compliant.is_empty()
+            })
             .fold(SarifRun::default(), |mut runs, report| {
                 if !sarif_unique_artifacts.contains(report.name) && !report.name.is_empty() {
                     sarif_unique_artifacts.insert(report.name);
@@ -150,7 +152,7 @@ impl From<(&ClauseReport<'_>, &str)> for SarifResults {
                 results.push(SarifResult {
                     rule_id,
                     message,
-                    level: String::from("error"),
+                    level: failure.severity().to_string(),
                     locations,
                 });
 
@@ -1,53 +1,114 @@
+use std::io::Write;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
 
+use crate::commands::baseline::Baseline;
+use crate::commands::progress::Progress;
+use crate::commands::reporters::schema::Versioned;
+use crate::commands::reporters::validate::delimited;
+use crate::commands::reporters::validate::grouped::{self, Group};
 use crate::commands::reporters::validate::sarif::SarifReport;
-use crate::commands::reporters::JunitReporter;
-use crate::commands::validate::{parse_rules, DataFile, OutputFormatType, RuleFileInfo};
+use crate::commands::reporters::validate::v2::{FileReportV2, GroupV2};
+use crate::commands::reporters::validate::xml;
+use crate::commands::reporters::{get_test_case, TestCase, TestCaseStatus, TestSuite};
+use crate::commands::summary::Summary;
+use crate::commands::validate::{
+    global_data_file, Console, DataFile, GroupBy, OutputFormatType, OutputVersion, RuleFileError,
+};
 use crate::commands::{ERROR_STATUS_CODE, FAILURE_STATUS_CODE};
 use crate::rules;
+use crate::rules::errors::ParseErrorLocation;
 use crate::rules::eval::eval_rules_file;
 use crate::rules::eval_context::{root_scope, simplified_json_from_root, FileReport};
-use crate::rules::exprs::RulesFile;
+use crate::rules::exprs::{RuleSelection, RulesFile};
+use crate::rules::functions::custom::FunctionRegistry;
+use crate::rules::limits::InputLimits;
+use crate::rules::messages::MessageCatalog;
 use crate::rules::path_value::PathAwareValue;
-use crate::rules::Status;
-use crate::utils::writer::Writer;
-use colored::Colorize;
+use crate::utils::writer::{WriteBuffer, Writer};
+use serde::Serialize;
 
-pub(crate) trait StructuredReporter {
-    fn report(&mut self) -> rules::Result<i32>;
+/// how the regular console output is written along with the reports
+pub(crate) enum ConsoleOutput<'eval> {
+    /// the failures across every data file, grouped with `group_by`
+    Grouped(&'eval mut Writer),
+    /// the evaluation of each rules file against each data file, as written without a report
+    Each(Console<'eval>, &'eval mut Writer),
+}
+
+/// a rules file that could not be parsed, listed ahead of the data file reports
+#[derive(Debug, Serialize)]
+struct RuleFileErrorReport<'report> {
+    name: &'report str,
+    status: &'static str,
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<&'report ParseErrorLocation>,
+    /// the syntax errors further down the rules file
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    following_errors: Vec<SyntaxErrorReport<'report>>,
+}
+
+#[derive(Debug, Serialize)]
+struct SyntaxErrorReport<'report> {
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<&'report ParseErrorLocation>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum Record<'report> {
+    RuleFileError(RuleFileErrorReport<'report>),
+    File(&'report FileReport<'report>),
+    Group(&'report Group),
+    FileV2(FileReportV2<'report>),
+    GroupV2(GroupV2),
 }
 
 pub struct StructuredEvaluator<'eval> {
-    pub(crate) rule_info: &'eval [RuleFileInfo],
+    pub(crate) rules: &'eval [(RulesFile<'eval>, &'eval str)],
+    /// rules files evaluated once against every data file together, reported as the GLOBAL
+    /// data file
+    pub(crate) global_rules: &'eval [(RulesFile<'eval>, &'eval str)],
+    /// rules files that failed to parse, reported as errors for every data file
+    pub(crate) rule_errors: &'eval [RuleFileError<'eval>],
     pub(crate) input_params: Option<PathAwareValue>,
     pub(crate) data: Vec<DataFile>,
-    pub(crate) output: OutputFormatType,
-    pub(crate) writer: &'eval mut Writer,
+    /// every requested output paired with the writer it is written to
+    pub(crate) outputs: Vec<(OutputFormatType, &'eval mut Writer)>,
+    pub(crate) selection: RuleSelection,
     pub(crate) exit_code: i32,
+    pub(crate) summary: &'eval mut Summary,
+    /// failures made up only of violations in the baseline do not fail the data file
+    pub(crate) baseline: Option<&'eval mut Baseline>,
+    /// add every violation found to the baseline before applying it
+    pub(crate) update_baseline: bool,
+    /// report the comparisons between values of different types, see `--strict-types`
+    pub(crate) strict_types: bool,
+    /// the most values a query may resolve to, see `--max-query-results`
+    pub(crate) limits: InputLimits,
+    /// the custom functions the rules may call, see `ValidateBuilder::functions`
+    pub(crate) functions: Option<Arc<FunctionRegistry>>,
+    /// the wording of the failure messages, see `--message-catalog`
+    pub(crate) messages: &'eval MessageCatalog,
+    pub(crate) progress: &'eval mut Progress,
+    /// how the json and yaml output, and the console output, are organized, see `--group-by`
+    pub(crate) group_by: GroupBy,
+    /// how many failures of each rule the console output lists, see `--max-findings-per-rule`
+    pub(crate) max_findings_per_rule: Option<usize>,
+    /// how many of the most repeated violations the console output lists, see `--top-offenders`
+    pub(crate) top_offenders: Option<usize>,
+    /// the layout of the json and yaml output, see `--output-version`
+    pub(crate) output_version: OutputVersion,
+    /// writes the console output of each evaluation from the records the reports are made of,
+    /// in the order the rules files are evaluated in without a report
+    pub(crate) console: Option<(Console<'eval>, &'eval mut Writer)>,
 }
 
 impl<'eval> StructuredEvaluator<'eval> {
     pub(crate) fn evaluate(&mut self) -> rules::Result<i32> {
-        let rules = self.rule_info.iter().try_fold(
-            vec![],
-            |mut rules,
-             RuleFileInfo { file_name, content }|
-             -> rules::Result<Vec<(RulesFile, &str)>> {
-                match parse_rules(content, file_name) {
-                    Err(e) => {
-                        self.writer.write_err(format!(
-                            "Parsing error handling rule file = {}, Error = {e}\n---",
-                            file_name.underline()
-                        ))?;
-                        self.exit_code = ERROR_STATUS_CODE;
-                    }
-                    Ok(Some(rule)) => rules.push((rule, file_name)),
-                    Ok(None) => {}
-                }
-                Ok(rules)
-            },
-        )?;
-
         let merged_data = self.data.iter().fold(vec![], |mut res, file| {
             let each = match &self.input_params {
                 Some(data) => data.clone().merge(file.path_value.clone()).unwrap(),
@@ -57,78 +118,267 @@ impl<'eval> StructuredEvaluator<'eval> {
             let merged_file_data = DataFile {
                 path_value: each,
                 name: file.name.to_owned(),
-                content: String::default(),
+                // only the console output shows the lines of code that failed
+                content: match self.console {
+                    Some(_) => file.content.clone(),
+                    None => String::default(),
+                },
+                parameters_file: file.parameters_file.clone(),
+                parameter_values: file.parameter_values.clone(),
+                vars: file.vars.clone(),
+                stack_resources: file.stack_resources.clone(),
             };
 
             res.push(merged_file_data);
             res
         });
 
-        let mut reporter = match self.output {
-            OutputFormatType::Junit => Box::new(JunitReporter {
-                data: merged_data,
-                rules,
-                writer: self.writer,
-                exit_code: self.exit_code,
-            }) as Box<dyn StructuredReporter>,
-            OutputFormatType::JSON | OutputFormatType::YAML | OutputFormatType::Sarif => {
-                Box::new(CommonStructuredReporter {
-                    rules,
-                    data: merged_data,
-                    writer: self.writer,
-                    exit_code: self.exit_code,
-                    output: self.output,
-                }) as Box<dyn StructuredReporter>
-            }
-            OutputFormatType::SingleLineSummary => unreachable!(),
+        let global = match self.global_rules.is_empty() {
+            true => vec![],
+            false => vec![global_data_file(&self.data)?],
         };
+        let (rules, global_rules) = (self.rules, self.global_rules);
+        let evaluations = merged_data
+            .iter()
+            .map(|file| (file, rules))
+            .chain(global.iter().map(|file| (file, global_rules)))
+            .collect::<Vec<_>>();
 
-        reporter.report()
+        self.report(&evaluations)
     }
-}
 
-struct CommonStructuredReporter<'reporter> {
-    rules: Vec<(RulesFile<'reporter>, &'reporter str)>,
-    data: Vec<DataFile>,
-    writer: &'reporter mut crate::utils::writer::Writer,
-    exit_code: i32,
-    output: OutputFormatType,
-}
+    /// evaluates every rule file against the data file it is paired with once, fanning the
+    /// records out to each of the requested outputs
+    #[allow(clippy::type_complexity)]
+    fn report(
+        &mut self,
+        evaluations: &[(&DataFile, &[(RulesFile<'_>, &str)])],
+    ) -> rules::Result<i32> {
+        let now = Instant::now();
+        let needs_records = self
+            .outputs
+            .iter()
+            .any(|(output, _)| !matches!(output, OutputFormatType::Junit));
 
-impl<'reporter> StructuredReporter for CommonStructuredReporter<'reporter> {
-    fn report(&mut self) -> rules::Result<i32> {
         let mut records = vec![];
-        for each in &self.data {
-            let mut file_report: FileReport = FileReport {
+        let mut suites = vec![];
+        let mut console_output = vec![];
+        for (position, (each, rules)) in evaluations.iter().enumerate() {
+            let mut file_report = FileReport {
                 name: &each.name,
                 ..Default::default()
             };
+            let mut test_cases = vec![];
 
-            for (rule, _) in &self.rules {
-                let mut root_scope = root_scope(rule, Rc::new(each.path_value.clone()));
+            for (index, (rule, name)) in rules.iter().enumerate() {
+                let start = Instant::now();
+                let mut root_scope = root_scope(rule, Rc::new(each.path_value.clone()))
+                    .with_selection(&self.selection)
+                    .with_parameters_file(each.parameters_file.clone())
+                    .with_parameter_values(each.parameter_values.clone())
+                    .with_vars(each.vars.clone())
+                    .with_stack_resources(each.stack_resources.clone())
+                    .with_strict_types(self.strict_types)
+                    .with_limits(self.limits)
+                    .with_functions(self.functions.clone());
+                let mut status = eval_rules_file(rule, &mut root_scope, Some(&each.name))?;
+                self.summary.record_durations(
+                    name,
+                    &each.name,
+                    start.elapsed(),
+                    root_scope.durations(),
+                );
+                let root_record = root_scope.reset_recorder().extract();
+                self.summary.record(&root_record)?;
+                self.progress.tick(&each.name);
+                let time = start.elapsed().as_millis();
 
-                if let Status::FAIL = eval_rules_file(rule, &mut root_scope, Some(&each.name))? {
-                    self.exit_code = FAILURE_STATUS_CODE;
+                if let Some((console, _)) = &self.console {
+                    let mut output =
+                        Writer::new_with_err(WriteBuffer::Vec(vec![]), WriteBuffer::Vec(vec![]))?;
+                    console.write(
+                        &mut output,
+                        status,
+                        &root_record,
+                        name,
+                        each,
+                        &each.path_value,
+                    )?;
+                    // each rules file against every data file, the global rules files last
+                    let global = !std::ptr::eq(*rules, self.rules);
+                    console_output.push(((global, index, position), output.into_string()?));
                 }
 
-                let root_record = root_scope.reset_recorder().extract();
-                let report = simplified_json_from_root(&root_record)?;
-                file_report.combine(report);
+                let mut report = simplified_json_from_root(&root_record, self.messages);
+                if let (Some(baseline), Ok(report)) = (self.baseline.as_deref_mut(), &mut report) {
+                    if self.update_baseline {
+                        baseline.extend(&each.name, report)?;
+                    }
+                    baseline.apply(&each.name, report)?;
+                    status = report.status;
+                }
+                let test_case = get_test_case(name, status, time, &report);
+                match &test_case.status {
+                    TestCaseStatus::Fail(_) => self.update_exit_code(FAILURE_STATUS_CODE),
+                    TestCaseStatus::Error { .. } => self.update_exit_code(ERROR_STATUS_CODE),
+                    _ => {}
+                }
+                test_cases.push(test_case);
+
+                if needs_records {
+                    file_report.combine(report?);
+                }
             }
 
+            // rules files that failed to parse could not be evaluated against the data file
+            test_cases.extend(self.rule_errors.iter().map(|rule_file_error| {
+                TestCase {
+                    id: None,
+                    name: rule_file_error.file_name,
+                    time: 0,
+                    status: TestCaseStatus::Error {
+                        error: rule_file_error
+                            .all_errors()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    },
+                }
+            }));
+
+            let (failures, errors) =
+                test_cases
+                    .iter()
+                    .fold((0, 0), |(failures, errors), test_case| {
+                        match test_case.status {
+                            TestCaseStatus::Fail(_) => (failures + 1, errors),
+                            TestCaseStatus::Error { .. } => (failures, errors + 1),
+                            _ => (failures, errors),
+                        }
+                    });
+
+            suites.push(TestSuite {
+                name: each.name.to_string(),
+                test_cases,
+                time: now.elapsed().as_millis(),
+                errors,
+                failures,
+            });
             records.push(file_report);
         }
 
-        match self.output {
-            OutputFormatType::YAML => serde_yaml::to_writer(&mut self.writer, &records)?,
-            OutputFormatType::JSON => serde_json::to_writer_pretty(&mut self.writer, &records)?,
-            OutputFormatType::Sarif => {
-                let report = SarifReport::new(&records);
-                serde_json::to_writer_pretty(&mut self.writer, &report)?
+        if let Some((_, writer)) = self.console.as_mut() {
+            console_output.sort_by_key(|(order, _)| *order);
+            for (_, output) in console_output {
+                write!(writer, "{output}")?;
             }
-            _ => unreachable!(),
+        }
+
+        // the console output is grouped by file as well, to list a bounded number of failures
+        let console = self
+            .outputs
+            .iter()
+            .any(|(output, _)| matches!(output, OutputFormatType::SingleLineSummary));
+        let findings = match (self.group_by, console) {
+            (GroupBy::File, false) => vec![],
+            _ => records
+                .iter()
+                .zip(evaluations)
+                .flat_map(|(report, (data, _))| grouped::findings(report, &data.path_value))
+                .collect(),
         };
+        let groups = grouped::group(findings.clone(), self.group_by);
+        let junit = xml::validate_report(suites, now.elapsed().as_millis());
+        let structured = self
+            .rule_errors
+            .iter()
+            .map(
+                |RuleFileError {
+                     file_name,
+                     error,
+                     following_errors,
+                 }| {
+                    Record::RuleFileError(RuleFileErrorReport {
+                        name: file_name,
+                        status: "ERROR",
+                        error: error.to_string(),
+                        location: error.parse_error_location(),
+                        following_errors: following_errors
+                            .iter()
+                            .map(|error| SyntaxErrorReport {
+                                error: error.to_string(),
+                                location: error.parse_error_location(),
+                            })
+                            .collect(),
+                    })
+                },
+            )
+            .chain(match (self.group_by, self.output_version) {
+                (GroupBy::File, OutputVersion::V1) => {
+                    records.iter().map(Record::File).collect::<Vec<_>>()
+                }
+                (GroupBy::File, OutputVersion::V2) => records
+                    .iter()
+                    .zip(evaluations)
+                    .map(|(report, (data, _))| {
+                        Record::FileV2(FileReportV2::new(report, &data.path_value))
+                    })
+                    .collect(),
+                (_, OutputVersion::V1) => groups.iter().map(Record::Group).collect(),
+                (_, OutputVersion::V2) => groups
+                    .iter()
+                    .map(|group| Record::GroupV2(GroupV2::from(group)))
+                    .collect(),
+            })
+            .map(|record| Versioned::new(self.output_version.schema_version(), record))
+            .collect::<Vec<_>>();
+        for (output, writer) in self.outputs.iter_mut() {
+            match output {
+                OutputFormatType::Junit => junit.serialize(writer)?,
+                OutputFormatType::YAML => serde_yaml::to_writer(&mut **writer, &structured)?,
+                OutputFormatType::JSON => serde_json::to_writer_pretty(&mut **writer, &structured)?,
+                OutputFormatType::Sarif => {
+                    let report = SarifReport::new(&records);
+                    serde_json::to_writer_pretty(&mut **writer, &report)?
+                }
+                OutputFormatType::Csv => delimited::write_rows(
+                    &delimited::rows(&records, self.rule_errors),
+                    b',',
+                    &mut **writer,
+                )?,
+                OutputFormatType::Tsv => delimited::write_rows(
+                    &delimited::rows(&records, self.rule_errors),
+                    b'\t',
+                    &mut **writer,
+                )?,
+                // only asked for to write the console output grouped
+                OutputFormatType::SingleLineSummary => {
+                    grouped::write_groups(
+                        &groups,
+                        self.group_by,
+                        self.max_findings_per_rule,
+                        &mut **writer,
+                    )?;
+                    if let Some(top) = self.top_offenders {
+                        grouped::write_offenders(
+                            &grouped::offenders(&findings),
+                            top,
+                            &mut **writer,
+                        )?;
+                    }
+                }
+            };
+        }
 
         Ok(self.exit_code)
     }
+
+    /// update exit code only if code takes more precedence than current exit code
+    fn update_exit_code(&mut self, code: i32) {
+        if code == ERROR_STATUS_CODE
+            || code == FAILURE_STATUS_CODE && self.exit_code != ERROR_STATUS_CODE
+        {
+            self.exit_code = code;
+        }
+    }
 }
@@ -62,18 +62,35 @@ fn print_summary(
     rules: &indexmap::IndexMap<&str, Status>,
 ) -> crate::rules::Result<()> {
     for (rule_name, status) in rules.iter() {
-        writeln!(
+        print_summary_line(
             writer,
-            "{filename}/{context:<0$}{status}",
-            longest + 4,
-            filename = rules_file_name,
-            context = get_rule_name(rules_file_name, rule_name),
-            status = super::common::colored_string(Some(*status))
+            rules_file_name,
+            rule_name,
+            longest,
+            super::common::colored_string(Some(*status)),
         )?;
     }
     Ok(())
 }
 
+fn print_summary_line(
+    writer: &mut dyn Write,
+    rules_file_name: &str,
+    rule_name: &str,
+    longest: usize,
+    status: ColoredString,
+) -> crate::rules::Result<()> {
+    writeln!(
+        writer,
+        "{filename}/{context:<0$}{status}",
+        longest + 4,
+        filename = rules_file_name,
+        context = get_rule_name(rules_file_name, rule_name),
+        status = status
+    )?;
+    Ok(())
+}
+
 impl<'r> Reporter for SummaryTable<'r> {
     fn report(
         &self,
@@ -162,15 +179,32 @@ impl<'r> Reporter for SummaryTable<'r> {
         let mut passed = indexmap::IndexMap::with_capacity(_root_record.children.len());
         let mut skipped = indexmap::IndexMap::with_capacity(_root_record.children.len());
         let mut failed = indexmap::IndexMap::with_capacity(_root_record.children.len());
+        let mut warned = indexmap::IndexSet::with_capacity(_root_record.children.len());
+        let mut suppressed = vec![];
         let mut longest = 0;
         for each_rule in &_root_record.children {
-            if let Some(RecordType::RuleCheck(NamedStatus { status, name, .. })) =
-                &each_rule.container
+            if let Some(RecordType::RuleCheck(NamedStatus {
+                status,
+                name,
+                severity,
+                suppressed: suppressions,
+                ..
+            })) = &each_rule.container
             {
+                suppressed.extend(suppressions.iter().map(|suppression| (*name, suppression)));
                 match status {
-                    Status::PASS => passed.insert(*name, *status),
-                    Status::FAIL => failed.insert(*name, *status),
-                    Status::SKIP => skipped.insert(*name, *status),
+                    Status::PASS => {
+                        passed.insert(*name, *status);
+                    }
+                    Status::FAIL if !severity.is_error() => {
+                        warned.insert(*name);
+                    }
+                    Status::FAIL => {
+                        failed.insert(*name, *status);
+                    }
+                    Status::SKIP => {
+                        skipped.insert(*name, *status);
+                    }
                 };
                 let child_rule_name_length = get_rule_name(_rules_file, name).len(); //get_rule_name(_rules_file, name).len();
                 if longest < child_rule_name_length {
@@ -179,7 +213,9 @@ impl<'r> Reporter for SummaryTable<'r> {
             }
         }
 
-        skipped.retain(|key, _| !(passed.contains_key(key) || failed.contains_key(key)));
+        skipped.retain(|key, _| {
+            !(passed.contains_key(key) || failed.contains_key(key) || warned.contains(key))
+        });
 
         let mut wrote_header_line = false;
         if self.summary_type.contains(SummaryType::SKIP) && !skipped.is_empty() {
@@ -222,6 +258,46 @@ impl<'r> Reporter for SummaryTable<'r> {
             print_summary(_write, _rules_file, longest, &failed)?;
         }
 
+        // warnings are shown along with the failures, they never change the status of the file
+        if self.summary_type.contains(SummaryType::FAIL) && !warned.is_empty() {
+            if !wrote_header_line {
+                wrote_header_line = true;
+                writeln!(
+                    _write,
+                    "{} Status = {}",
+                    _data_file,
+                    colored_string(Some(_status))
+                )?;
+            }
+            writeln!(_write, "{}", "WARNING rules".bold())?;
+            for rule_name in &warned {
+                print_summary_line(_write, _rules_file, rule_name, longest, "WARN".yellow())?;
+            }
+        }
+
+        // resources opting out of a rule are shown along with the failures, with the justification
+        if self.summary_type.contains(SummaryType::FAIL) && !suppressed.is_empty() {
+            if !wrote_header_line {
+                wrote_header_line = true;
+                writeln!(
+                    _write,
+                    "{} Status = {}",
+                    _data_file,
+                    colored_string(Some(_status))
+                )?;
+            }
+            writeln!(_write, "{}", "SUPPRESSED rules".bold())?;
+            for (rule_name, suppression) in &suppressed {
+                let status = match &suppression.justification {
+                    Some(justification) => {
+                        format!("SUPPRESSED for {} ({justification})", suppression.resource)
+                    }
+                    None => format!("SUPPRESSED for {}", suppression.resource),
+                };
+                print_summary_line(_write, _rules_file, rule_name, longest, status.cyan())?;
+            }
+        }
+
         if wrote_header_line {
             writeln!(_write, "---")?;
         }
@@ -4,22 +4,30 @@ use crate::rules::eval_context::{
     simplified_json_from_root, BinaryComparison, ClauseReport, EventRecord, FileReport,
     InComparison, RuleReport, UnaryComparison,
 };
+use crate::rules::messages::MessageCatalog;
 use crate::rules::path_value::traversal::{Node, Traversal, TraversalResult};
 use crate::rules::Status;
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::io::Write;
 use std::rc::Rc;
 
 #[derive(Debug)]
 pub(crate) struct TfAware<'reporter> {
     next: Option<&'reporter dyn Reporter>,
+    messages: &'reporter MessageCatalog,
 }
 
 impl<'reporter> TfAware<'reporter> {
-    pub(crate) fn new_with(next: &'reporter dyn Reporter) -> TfAware {
-        TfAware { next: Some(next) }
+    pub(crate) fn new_with(
+        next: &'reporter dyn Reporter,
+        messages: &'reporter MessageCatalog,
+    ) -> TfAware<'reporter> {
+        TfAware {
+            next: Some(next),
+            messages,
+        }
     }
 }
 
@@ -52,7 +60,7 @@ impl<'reporter> Reporter for TfAware<'reporter> {
     ) -> crate::rules::Result<()> {
         let root = data.root().unwrap();
         if data.at("/resource_changes", root).is_ok() {
-            let failure_report = simplified_json_from_root(root_record)?;
+            let failure_report = simplified_json_from_root(root_record, self.messages)?;
             match output_type {
                 OutputFormatType::YAML => serde_yaml::to_writer(write, &failure_report)?,
                 OutputFormatType::JSON => serde_json::to_writer_pretty(write, &failure_report)?,
@@ -61,6 +69,7 @@ impl<'reporter> Reporter for TfAware<'reporter> {
                 }
                 OutputFormatType::Junit => unreachable!(),
                 OutputFormatType::Sarif => unreachable!(),
+                OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
             };
 
             Ok(())
@@ -117,7 +126,7 @@ fn single_line(
         populate_hierarchy_path_trees(each_rule, root_node.clone(), &mut path_tree, &mut hierarchy);
     }
 
-    let mut by_resources = HashMap::new();
+    let mut by_resources = BTreeMap::new();
     for (key, value) in path_tree.range(String::from("/resource_changes/")..) {
         let resource_ptr = match RESOURCE_CHANGE_EXTRACTION.captures(key) {
             Ok(Some(cap)) => cap.name("index_or_name").unwrap().as_str(),
@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::commands::reporters::validate::grouped::{self, Finding, Group};
+use crate::rules::eval_context::{ClauseReport, FileReport, Metadata, SuppressedReport};
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::Status;
+
+/// a failed check of a rule against a data file in the v2 layout, with the line and column of
+/// the value it failed on when there is one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Violation {
+    #[serde(flatten)]
+    finding: Finding,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+}
+
+impl From<Finding> for Violation {
+    fn from(finding: Finding) -> Self {
+        // lines and columns start at 0 as they are read, at 1 as editors show them
+        let location = finding.location;
+        Violation {
+            finding,
+            line: location.map(|location| location.line + 1),
+            column: location.map(|location| location.col + 1),
+        }
+    }
+}
+
+/// the report of a data file in the v2 layout: the failures flattened to a list of violations,
+/// with the baselined and suppressed ones always listed
+#[derive(Debug, Serialize)]
+pub(crate) struct FileReportV2<'report> {
+    name: &'report str,
+    metadata: &'report Metadata,
+    status: Status,
+    violations: Vec<Violation>,
+    baselined: Vec<Violation>,
+    suppressed: &'report [SuppressedReport<'report>],
+    not_applicable: &'report BTreeSet<String>,
+    compliant: &'report BTreeSet<String>,
+}
+
+impl<'report> FileReportV2<'report> {
+    pub(crate) fn new(report: &'report FileReport<'report>, data: &PathAwareValue) -> Self {
+        FileReportV2 {
+            name: report.name,
+            metadata: &report.metadata,
+            status: report.status,
+            violations: violations(report.name, &report.not_compliant, data),
+            baselined: violations(report.name, &report.baselined, data),
+            suppressed: &report.suppressed,
+            not_applicable: &report.not_applicable,
+            compliant: &report.compliant,
+        }
+    }
+}
+
+fn violations(
+    data_file: &str,
+    failures: &[ClauseReport<'_>],
+    data: &PathAwareValue,
+) -> Vec<Violation> {
+    grouped::failure_findings(data_file, failures, data)
+        .into_iter()
+        .map(Violation::from)
+        .collect()
+}
+
+/// a group of `--group-by` in the v2 layout, its findings with their line and column
+#[derive(Debug, Serialize)]
+pub(crate) struct GroupV2 {
+    group: String,
+    findings: Vec<Violation>,
+}
+
+impl From<&Group> for GroupV2 {
+    fn from(group: &Group) -> Self {
+        GroupV2 {
+            group: group.group.clone(),
+            findings: group
+                .findings
+                .iter()
+                .cloned()
+                .map(Violation::from)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "v2_tests.rs"]
+mod v2_tests;
@@ -0,0 +1,86 @@
+use super::*;
+use crate::commands::summary::ERROR_SEVERITY;
+use crate::rules::path_value::{Location, Path};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn finding(location: Option<Location>) -> Finding {
+    Finding {
+        data_file: String::from("template.yaml"),
+        rule: String::from("s3_encrypted"),
+        resource: String::from("bucket"),
+        resource_type: Some(String::from("AWS::S3::Bucket")),
+        path: String::from("/Resources/bucket/Properties"),
+        location,
+        severity: ERROR_SEVERITY,
+        message: String::from("Check was not compliant"),
+    }
+}
+
+#[test]
+fn test_violation() {
+    assert_eq!(
+        json!({
+            "data_file": "template.yaml",
+            "rule": "s3_encrypted",
+            "resource": "bucket",
+            "resource_type": "AWS::S3::Bucket",
+            "path": "/Resources/bucket/Properties",
+            "severity": "error",
+            "message": "Check was not compliant",
+            "line": 5,
+            "column": 7,
+        }),
+        serde_json::to_value(Violation::from(finding(Some(Location::new(4, 6))))).unwrap()
+    );
+
+    let violation = serde_json::to_value(Violation::from(finding(None))).unwrap();
+    assert_eq!(None, violation.get("line"));
+    assert_eq!(None, violation.get("column"));
+}
+
+#[test]
+fn test_file_report() {
+    let report = FileReport {
+        name: "template.yaml",
+        status: Status::PASS,
+        compliant: BTreeSet::from([String::from("s3_encrypted")]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        json!({
+            "name": "template.yaml",
+            "metadata": {},
+            "status": "PASS",
+            "violations": [],
+            "baselined": [],
+            "suppressed": [],
+            "not_applicable": [],
+            "compliant": ["s3_encrypted"],
+        }),
+        serde_json::to_value(FileReportV2::new(
+            &report,
+            &PathAwareValue::Null(Path::root())
+        ))
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_group() {
+    let group = Group {
+        group: String::from("s3_encrypted"),
+        findings: vec![finding(Some(Location::new(0, 0)))],
+    };
+
+    let group = serde_json::to_value(GroupV2::from(&group)).unwrap();
+    assert_eq!("s3_encrypted", group["group"]);
+    assert_eq!(
+        (1, 1),
+        (
+            group["findings"][0]["line"].as_u64().unwrap(),
+            group["findings"][0]["column"].as_u64().unwrap()
+        )
+    );
+}
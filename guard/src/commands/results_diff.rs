@@ -0,0 +1,269 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Write;
+
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::files::read_file_content;
+use crate::commands::{Executable, FAILURE_STATUS_CODE, PRINT_JSON, SUCCESS_STATUS_CODE};
+use crate::rules::errors::Error;
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+const ABOUT: &str = r#"Compares the structured output of two validate runs, as written by
+validate --structured --output-format json (or yaml), and lists the violations the second run
+introduced, the ones it fixed and the ones left unchanged. Exits with a failure status code when
+there are new violations, so pull requests only get blocked on what they introduced."#;
+const BEFORE_HELP: &str =
+    "The structured output of the validate run to compare against, e.g. from the base branch";
+const AFTER_HELP: &str = "The structured output of the validate run to check for new violations";
+const PRINT_JSON_HELP: &str = "Print the new, fixed and unchanged violations in a json format";
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Diff command compares the violations reported by two validate runs
+pub struct ResultsDiff {
+    // path to the structured output of the earlier run
+    #[arg(long, help=BEFORE_HELP)]
+    pub(crate) before: String,
+    // path to the structured output of the later run
+    #[arg(long, help=AFTER_HELP)]
+    pub(crate) after: String,
+    // print the differences in a json format
+    // default is false
+    #[arg(short=PRINT_JSON.1, long=PRINT_JSON.0, help=PRINT_JSON_HELP)]
+    pub(crate) print_json: bool,
+}
+
+/// a failed check of a rule against a data file, identified by what was checked and where
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) struct Violation {
+    pub(crate) file: String,
+    pub(crate) rule: String,
+    pub(crate) check: String,
+    /// the path of the value the check failed on, empty when the report does not carry one
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) path: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.file, self.rule, self.check)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ViolationsDiff {
+    pub(crate) new: Vec<Violation>,
+    pub(crate) fixed: Vec<Violation>,
+    pub(crate) unchanged: Vec<Violation>,
+}
+
+impl Executable for ResultsDiff {
+    /// .
+    /// compares the violations of both runs
+    ///
+    /// This function will return an error if
+    /// - any of the specified paths do not exist
+    /// - any of the files is not the structured output of validate
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let before = read_violations(&self.before)?;
+        let after = read_violations(&self.after)?;
+        let diff = diff_violations(&before, &after);
+
+        match self.print_json {
+            true => writeln!(writer, "{}", serde_json::to_string_pretty(&diff)?)?,
+            false => print_diff(&diff, writer)?,
+        }
+
+        match diff.new.is_empty() {
+            true => Ok(SUCCESS_STATUS_CODE),
+            false => Ok(FAILURE_STATUS_CODE),
+        }
+    }
+}
+
+fn read_violations(path: &str) -> Result<BTreeSet<Violation>> {
+    let content = read_file_content(File::open(path)?)?;
+    let report = match serde_json::from_str::<Value>(&content) {
+        Ok(report) => report,
+        Err(_) => serde_yaml::from_str::<Value>(&content).map_err(|e| {
+            Error::ParseError(format!(
+                "Unable to read the validate results in {path}, Error {e}"
+            ))
+        })?,
+    };
+
+    violations(&report).map_err(|reason| {
+        Error::ParseError(format!(
+            "{path} is not the structured output of validate, {reason}"
+        ))
+    })
+}
+
+/// the violations in the structured output of validate, which is a list of file reports, or a
+/// single one. Rules files that failed to parse are listed along with them and are ignored
+pub(crate) fn violations(report: &Value) -> std::result::Result<BTreeSet<Violation>, String> {
+    let files = match report {
+        Value::Array(files) => files.iter().collect::<Vec<_>>(),
+        Value::Object(_) => vec![report],
+        _ => return Err(String::from("expecting a list of file reports")),
+    };
+
+    let mut violations = BTreeSet::new();
+    for file in files {
+        let not_compliant = match file.get("not_compliant") {
+            Some(Value::Array(not_compliant)) => not_compliant,
+            Some(_) => return Err(String::from("expecting not_compliant to be a list")),
+            None if file.get("status").and_then(Value::as_str) == Some("ERROR") => continue,
+            None => return Err(String::from("expecting not_compliant in every file report")),
+        };
+        let name = file.get("name").and_then(Value::as_str).unwrap_or_default();
+
+        for clause in not_compliant {
+            violations.extend(clause_violations(name, clause));
+        }
+    }
+
+    Ok(violations)
+}
+
+/// the violations of a single failure in the `not_compliant` list of a file report
+pub(crate) fn clause_violations(file: &str, clause: &Value) -> BTreeSet<Violation> {
+    let mut violations = BTreeSet::new();
+    collect_violations(file, "", clause, &mut violations);
+    violations
+}
+
+/// walks a clause report, `{"Rule": ...}`, `{"Block": ...}`, `{"Disjunctions": ...}` or
+/// `{"Clause": ...}`, down to the checks that failed
+fn collect_violations(
+    file: &str,
+    rule: &str,
+    clause: &Value,
+    violations: &mut BTreeSet<Violation>,
+) {
+    let (kind, report) = match clause.as_object().and_then(|clause| clause.iter().next()) {
+        Some(entry) => entry,
+        None => return,
+    };
+
+    match kind.as_str() {
+        "Rule" => {
+            let rule = report.get("name").and_then(Value::as_str).unwrap_or(rule);
+            let checks = report.get("checks").and_then(Value::as_array);
+            match checks {
+                Some(checks) if !checks.is_empty() => {
+                    for check in checks {
+                        collect_violations(file, rule, check, violations);
+                    }
+                }
+                // a rule can fail without any check to show for it, e.g. a failed dependency
+                _ => {
+                    violations.insert(Violation {
+                        file: file.to_string(),
+                        rule: rule.to_string(),
+                        check: String::new(),
+                        path: String::new(),
+                    });
+                }
+            }
+        }
+        "Disjunctions" => {
+            for check in report
+                .get("checks")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                collect_violations(file, rule, check, violations);
+            }
+        }
+        "Block" => {
+            violations.insert(Violation {
+                file: file.to_string(),
+                rule: rule.to_string(),
+                check: check_text(report),
+                path: value_path(report, &["/unresolved/traversed_to/path"]),
+            });
+        }
+        "Clause" => {
+            if let Some((_, report)) = report.as_object().and_then(|clause| clause.iter().next()) {
+                violations.insert(Violation {
+                    file: file.to_string(),
+                    rule: rule.to_string(),
+                    check: check_text(report),
+                    path: value_path(
+                        report,
+                        &[
+                            "/check/Resolved/from/path",
+                            "/check/Resolved/value/path",
+                            "/check/InResolved/from/path",
+                            "/check/UnResolved/value/traversed_to/path",
+                        ],
+                    ),
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+/// the clause as written in the rules file when the report has it, its context otherwise
+fn check_text(report: &Value) -> String {
+    report
+        .get("query")
+        .or_else(|| report.get("context"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+fn value_path(report: &Value, pointers: &[&str]) -> String {
+    pointers
+        .iter()
+        .find_map(|pointer| report.pointer(pointer).and_then(Value::as_str))
+        .unwrap_or_default()
+        .to_string()
+}
+
+pub(crate) fn diff_violations(
+    before: &BTreeSet<Violation>,
+    after: &BTreeSet<Violation>,
+) -> ViolationsDiff {
+    ViolationsDiff {
+        new: after.difference(before).cloned().collect(),
+        fixed: before.difference(after).cloned().collect(),
+        unchanged: after.intersection(before).cloned().collect(),
+    }
+}
+
+fn print_diff(diff: &ViolationsDiff, writer: &mut Writer) -> Result<()> {
+    for (heading, violations) in [
+        ("New violations", &diff.new),
+        ("Fixed violations", &diff.fixed),
+        ("Unchanged violations", &diff.unchanged),
+    ] {
+        writeln!(writer, "{heading} ({}):", violations.len())?;
+        for violation in violations {
+            writeln!(writer, "  {violation}")?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "results_diff_tests.rs"]
+mod results_diff_tests;
@@ -0,0 +1,141 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+fn violation(file: &str, rule: &str, check: &str, path: &str) -> Violation {
+    Violation {
+        file: file.to_string(),
+        rule: rule.to_string(),
+        check: check.to_string(),
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn test_violations() {
+    let report = serde_json::json!([
+        {
+            "name": "rules.guard",
+            "status": "ERROR",
+            "error": "Parser Error when parsing",
+            "location": "rules.guard:2:5"
+        },
+        {
+            "name": "template.yaml",
+            "metadata": {},
+            "status": "FAIL",
+            "not_compliant": [
+                {
+                    "Rule": {
+                        "name": "s3_encrypted",
+                        "metadata": {},
+                        "messages": {},
+                        "checks": [
+                            {
+                                "Clause": {
+                                    "Binary": {
+                                        "context": " Properties.Encrypted EQUALS true",
+                                        "query": "Properties.Encrypted == true",
+                                        "messages": {},
+                                        "check": {
+                                            "Resolved": {
+                                                "from": { "path": "/Resources/a/Properties/Encrypted", "value": false },
+                                                "to": { "path": "", "value": true },
+                                                "comparison": ["Eq", false]
+                                            }
+                                        }
+                                    }
+                                }
+                            },
+                            {
+                                "Disjunctions": {
+                                    "checks": [
+                                        {
+                                            "Clause": {
+                                                "Unary": {
+                                                    "context": " Properties.KmsKeyId EXISTS",
+                                                    "messages": {},
+                                                    "check": {
+                                                        "UnResolved": {
+                                                            "value": {
+                                                                "traversed_to": { "path": "/Resources/b/Properties", "value": {} },
+                                                                "remaining_query": "KmsKeyId"
+                                                            },
+                                                            "comparison": ["Exists", false]
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    ]
+                                }
+                            }
+                        ]
+                    }
+                },
+                {
+                    "Rule": {
+                        "name": "depends_on_s3",
+                        "metadata": {},
+                        "messages": {},
+                        "checks": []
+                    }
+                }
+            ],
+            "not_applicable": [],
+            "compliant": []
+        }
+    ]);
+
+    let violations = violations(&report).unwrap();
+    assert_eq!(
+        vec![
+            violation("template.yaml", "depends_on_s3", "", ""),
+            violation(
+                "template.yaml",
+                "s3_encrypted",
+                "Properties.Encrypted == true",
+                "/Resources/a/Properties/Encrypted"
+            ),
+            violation(
+                "template.yaml",
+                "s3_encrypted",
+                "Properties.KmsKeyId EXISTS",
+                "/Resources/b/Properties"
+            ),
+        ],
+        violations.into_iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_violations_not_a_report() {
+    assert!(violations(&serde_json::json!("FAIL")).is_err());
+    assert!(violations(&serde_json::json!([{ "name": "template.yaml" }])).is_err());
+}
+
+#[test]
+fn test_diff_violations() {
+    let fixed = violation("t.yaml", "r", "a == 1", "/a");
+    let unchanged = violation("t.yaml", "r", "b == 1", "/b");
+    let new = violation("t.yaml", "r", "a == 1", "/c");
+
+    let before = BTreeSet::from([fixed.clone(), unchanged.clone()]);
+    let after = BTreeSet::from([unchanged.clone(), new.clone()]);
+    assert_eq!(
+        ViolationsDiff {
+            new: vec![new],
+            fixed: vec![fixed],
+            unchanged: vec![unchanged],
+        },
+        diff_violations(&before, &after)
+    );
+}
+
+#[test]
+fn test_violation_display() {
+    assert_eq!(
+        "t.yaml: r: a == 1 at /a",
+        violation("t.yaml", "r", "a == 1", "/a").to_string()
+    );
+    assert_eq!("t.yaml: r: ", violation("t.yaml", "r", "", "").to_string());
+}
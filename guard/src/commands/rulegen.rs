@@ -1,25 +1,31 @@
 use std::fs;
 use std::process;
 
-use crate::commands::Executable;
-use crate::commands::SUCCESS_STATUS_CODE;
+use crate::commands::config_rules::{find_config_rule, supported_config_rules, ConfigRuleMapping};
+use crate::commands::{
+    Executable, FROM_CONFIG_RULE, OUTPUT_FILE, RULEGEN_SOURCE, SUCCESS_STATUS_CODE, TEMPLATE,
+};
+use crate::rules::errors::Error;
 use crate::rules::Result;
 use crate::utils::reader::Reader;
 use crate::utils::writer::Writer;
 use clap::Args;
 use itertools::Itertools;
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::Write;
 use string_builder::Builder;
 
 const ABOUT: &str = "Autogenerate rules from an existing JSON- or YAML- formatted data. (Currently works with only CloudFormation templates)";
 const TEMPLATE_HELP: &str = "Provide path to a CloudFormation template file in JSON or YAML";
 const OUTPUT_HELP: &str = "Write to output file";
+const FROM_CONFIG_RULE_HELP: &str = "Generate a rule skeleton approximating a well-known AWS Config managed rule, \
+                                     e.g. s3-bucket-server-side-encryption-enabled. Conflicts with template";
 
 #[derive(Debug, Clone, Eq, PartialEq, Args)]
 #[clap(arg_required_else_help = true)]
 #[clap(about=ABOUT)]
+#[clap(group=clap::ArgGroup::new(RULEGEN_SOURCE).args([TEMPLATE.0, FROM_CONFIG_RULE.0]).required(true))]
 /// .
 /// The Rulegen command auto generates rules from an existing CloudFormation template
 /// Please note this currently only works on CloudFormation templates
@@ -27,11 +33,16 @@ pub struct Rulegen {
     /// the path to the file which the generated rules will be outputted to
     /// default None
     /// if set to None rules will be outputted to the stdout
-    #[arg(short, long, help=OUTPUT_HELP)]
+    #[arg(short, long, visible_alias=OUTPUT_FILE.0, help=OUTPUT_HELP)]
     pub(crate) output: Option<String>,
     /// the path to the CloudFormation template
-    #[arg(short, long, help=TEMPLATE_HELP)]
-    pub(crate) template: String,
+    /// conflicts with from_config_rule
+    #[arg(short, long, help=TEMPLATE_HELP, conflicts_with=FROM_CONFIG_RULE.0)]
+    pub(crate) template: Option<String>,
+    /// the identifier of an AWS Config managed rule to generate a rule skeleton for
+    /// conflicts with template
+    #[arg(name=FROM_CONFIG_RULE.0, short=FROM_CONFIG_RULE.1, long, help=FROM_CONFIG_RULE_HELP, conflicts_with=TEMPLATE.0)]
+    pub(crate) from_config_rule: Option<String>,
 }
 
 impl Executable for Rulegen {
@@ -41,11 +52,31 @@ impl Executable for Rulegen {
     /// This function will return an error if
     /// - any of the specified paths do not exist
     /// - illegal json or yaml syntax present in any of the data/input parameter files
+    /// - the config rule requested is not one of the supported managed rules
     fn execute(&self, writer: &mut Writer, _: &mut Reader) -> Result<i32> {
-        let template_contents = fs::read_to_string(&self.template)?;
+        match (&self.template, &self.from_config_rule) {
+            (Some(template), None) => {
+                let template_contents = fs::read_to_string(template)?;
 
-        let result = parse_template_and_call_gen(&template_contents, writer);
-        print_rules(result, writer)?;
+                let result = parse_template_and_call_gen(&template_contents, writer);
+                print_rules(result, writer)?;
+            }
+            (None, Some(identifier)) => {
+                let mapping = find_config_rule(identifier).ok_or_else(|| {
+                    Error::IllegalArguments(format!(
+                        "no mapping exists for the config rule {identifier}, supported rules are: {}",
+                        supported_config_rules().join(", ")
+                    ))
+                })?;
+
+                print_config_rule(mapping, writer)?;
+            }
+            _ => {
+                return Err(Error::IllegalArguments(String::from(
+                    "exactly one of template or from-config-rule must be provided",
+                )))
+            }
+        }
 
         Ok(SUCCESS_STATUS_CODE)
     }
@@ -54,7 +85,7 @@ impl Executable for Rulegen {
 pub fn parse_template_and_call_gen(
     template_contents: &str,
     writer: &mut Writer,
-) -> HashMap<String, HashMap<String, HashSet<String>>> {
+) -> BTreeMap<String, BTreeMap<String, BTreeSet<String>>> {
     let cfn_template: HashMap<String, Value> = match serde_yaml::from_str(template_contents) {
         Ok(s) => s,
         Err(e) => {
@@ -93,7 +124,7 @@ pub fn parse_template_and_call_gen(
 #[allow(clippy::map_entry)]
 fn gen_rules(
     cfn_resources: HashMap<String, Value>,
-) -> HashMap<String, HashMap<String, HashSet<String>>> {
+) -> BTreeMap<String, BTreeMap<String, BTreeSet<String>>> {
     // Create hashmap of resource name, property name and property values
     // For example, the following template:
     //
@@ -121,12 +152,12 @@ fn gen_rules(
     //
     // The data structure would contain:
     // <AWS::EC2::Volume> <Encrypted> <false>
-    //                    <Size> <500, 50>
-    //                    <AvailabilityZone> <us-west-2c, us-west-2b>
+    //                    <Size> <50, 500>
+    //                    <AvailabilityZone> <us-west-2b, us-west-2c>
     //
     //
     //
-    let mut rule_map: HashMap<String, HashMap<String, HashSet<String>>> = HashMap::new();
+    let mut rule_map: BTreeMap<String, BTreeMap<String, BTreeSet<String>>> = BTreeMap::new();
     for (_name, cfn_resource) in cfn_resources {
         let props: HashMap<String, Value> =
             match serde_json::from_value(cfn_resource["Properties"].clone()) {
@@ -150,17 +181,17 @@ fn gen_rules(
             let resource_name = (&cfn_resource["Type"].as_str().unwrap()).to_string();
 
             if !rule_map.contains_key(&resource_name) {
-                let value_set: HashSet<String> =
+                let value_set: BTreeSet<String> =
                     vec![no_newline_stripped_val].into_iter().collect();
 
-                let mut property_map = HashMap::new();
+                let mut property_map = BTreeMap::new();
                 property_map.insert(prop_name, value_set);
                 rule_map.insert(resource_name, property_map);
             } else {
                 let property_map = rule_map.get_mut(&resource_name).unwrap();
 
                 if !property_map.contains_key(&prop_name) {
-                    let value_set: HashSet<String> =
+                    let value_set: BTreeSet<String> =
                         vec![no_newline_stripped_val].into_iter().collect();
                     property_map.insert(prop_name, value_set);
                 } else {
@@ -174,60 +205,147 @@ fn gen_rules(
     rule_map
 }
 
-// Prints the generated rules data structure to stdout. If there are properties mapping to
-// multiple values in the template, the rules are put in one statement using the IN keyword so that
-// the generated rules are interpreted as ALL by default.
+// Prints the generated rules data structure to stdout, as one named rule per resource type that
+// wraps a type block for the resource type. If there are properties mapping to multiple values in
+// the template, the values are put in one clause using the IN keyword so that the generated rules
+// are interpreted as ALL by default. When all the observed values are numbers, a range spanning
+// the smallest and largest value is used instead of listing them.
 // Using the same example in the comment above, the rules printed for the template will be:
-//     let aws_ec2_volume_resources = Resources.*[ Type == 'AWS::EC2::Volume' ]
-//     rule aws_ec2_volume when %aws_ec2_volume_resources !empty {
-//          %aws_ec2_volume_resources.Properties.Size IN [500, 50]
-//          %aws_ec2_volume_resources.Properties.AvailabilityZone IN ["us-west-2b", "us-west-2c"]
-//          %aws_ec2_volume_resources.Properties.Encrypted == false
+//     rule aws_ec2_volume {
+//       AWS::EC2::Volume {
+//         Properties.AvailabilityZone IN ["us-west-2b", "us-west-2c"]
+//         Properties.Encrypted == false
+//         Properties.Size IN r[50, 500]
+//       }
 //     }
 fn print_rules(
-    rule_map: HashMap<String, HashMap<String, HashSet<String>>>,
+    rule_map: BTreeMap<String, BTreeMap<String, BTreeSet<String>>>,
     writer: &mut Writer,
 ) -> Result<()> {
     let mut str = Builder::default();
 
-    for (resource, properties) in &rule_map {
-        let resource_name_underscore = resource.replace("::", "_").to_lowercase();
-        let variable_name = format!("{}_resources", resource_name_underscore);
+    for (index, (resource, properties)) in rule_map.iter().enumerate() {
+        if index > 0 {
+            str.append("\n");
+        }
 
         str.append(format!(
-            "let {} = Resources.*[ Type == '{}' ]\n",
-            variable_name, resource
-        ));
-        str.append(format!(
-            "rule {} when %{} !empty {{\n",
-            resource_name_underscore, variable_name
+            "rule {} {{\n",
+            resource.replace("::", "_").to_lowercase()
         ));
+        str.append(format!("  {} {{\n", resource));
 
         for (property, values) in properties {
             if values.len() > 1 {
-                str.append(format!(
-                    "  %{}.Properties.{} IN [{}]\n",
-                    variable_name,
-                    property,
-                    values.iter().join(", ")
-                ));
+                let values = match numeric_range(values) {
+                    Some(range) => range,
+                    None => format!("[{}]", values.iter().join(", ")),
+                };
+                str.append(format!("    Properties.{} IN {}\n", property, values));
             } else {
                 str.append(format!(
-                    "  %{}.Properties.{} == {}\n",
-                    variable_name,
+                    "    Properties.{} == {}\n",
                     property,
                     values.iter().next().unwrap()
                 ));
             }
         }
 
+        str.append("  }\n");
         str.append("}\n");
     }
 
     // validate rules generated
     let generated_rules = str.string().unwrap();
 
-    let span = crate::rules::parser::Span::new_extra(&generated_rules, "");
+    write_generated_rules(&generated_rules, writer)
+}
+
+// Infers an inclusive range from the observed values when every one of them is an integer, or
+// every one of them is a floating point number, as integers do not compare against float ranges.
+// Falls back to None otherwise, or when the bounds can not be expressed as a range literal
+// (negative floating point numbers and exponents are not accepted inside ranges)
+pub(crate) fn numeric_range(values: &BTreeSet<String>) -> Option<String> {
+    if let Ok(numbers) = values
+        .iter()
+        .map(|value| value.parse::<i64>())
+        .collect::<std::result::Result<Vec<i64>, _>>()
+    {
+        let lower = numbers.iter().min()?;
+        let upper = numbers.iter().max()?;
+        return Some(format!("r[{}, {}]", lower, upper));
+    }
+
+    if values.iter().any(|value| value.parse::<i64>().is_ok()) {
+        return None;
+    }
+
+    let numbers = values
+        .iter()
+        .map(|value| value.parse::<f64>())
+        .collect::<std::result::Result<Vec<f64>, _>>()
+        .ok()?;
+    if numbers.iter().any(|number| !number.is_finite()) {
+        return None;
+    }
+
+    let lower = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+    let upper = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let (lower, upper) = (format!("{:?}", lower), format!("{:?}", upper));
+
+    match lower.starts_with('-') || lower.contains('e') || upper.contains('e') {
+        true => None,
+        false => Some(format!("r[{}, {}]", lower, upper)),
+    }
+}
+
+// Prints a rule skeleton that approximates an AWS Config managed rule. Using the
+// s3-bucket-versioning-enabled rule as an example, the rules printed will be:
+//     #
+//     # Approximation of the AWS Config managed rule s3-bucket-versioning-enabled
+//     # Review the generated clauses before using them to gate deployments
+//     #
+//     rule S3_BUCKET_VERSIONING_ENABLED {
+//       AWS::S3::Bucket {
+//         Properties.VersioningConfiguration.Status == "Enabled"
+//         <<
+//           Violation: S3 buckets must have versioning enabled
+//         >>
+//       }
+//     }
+fn print_config_rule(mapping: &ConfigRuleMapping, writer: &mut Writer) -> Result<()> {
+    let mut str = Builder::default();
+
+    let rule_name = mapping.identifier.replace('-', "_").to_uppercase();
+
+    str.append("#\n");
+    str.append(format!(
+        "# Approximation of the AWS Config managed rule {}\n",
+        mapping.identifier
+    ));
+    str.append("# Review the generated clauses before using them to gate deployments\n");
+    str.append("#\n");
+    str.append(format!("rule {} {{\n", rule_name));
+    str.append(format!("  {} {{\n", mapping.resource_type));
+
+    for clause in mapping.clauses {
+        str.append(format!("    Properties.{}\n", clause));
+        str.append(format!(
+            "    <<\n      Violation: {}\n    >>\n",
+            mapping.message
+        ));
+    }
+
+    str.append("  }\n");
+    str.append("}\n");
+
+    let generated_rules = str.string().unwrap();
+
+    write_generated_rules(&generated_rules, writer)
+}
+
+pub(crate) fn write_generated_rules(generated_rules: &str, writer: &mut Writer) -> Result<()> {
+    let span = crate::rules::parser::Span::new_extra(generated_rules, "");
     match crate::rules::parser::rules_file(span) {
         Ok(_rules) => {
             //
@@ -43,6 +43,75 @@ fn test_rulegen() {
     assert!(property_map.contains_key("AvailabilityZone"));
 }
 
+#[test]
+fn test_rulegen_print_rules_uses_type_blocks_and_ranges() {
+    use crate::utils::writer::WriteBuffer::Vec as WBVec;
+
+    let data = String::from(
+        r#"
+        {
+            "Resources": {
+                "NewVolume" : {
+                    "Type" : "AWS::EC2::Volume",
+                    "Properties" : {
+                        "Size" : 500,
+                        "Encrypted": false,
+                        "AvailabilityZone" : "us-west-2b"
+                    }
+                },
+                "NewVolume2" : {
+                    "Type" : "AWS::EC2::Volume",
+                    "Properties" : {
+                        "Size" : 50,
+                        "Encrypted": false,
+                        "AvailabilityZone" : "us-west-2c"
+                    }
+                }
+            }
+        }
+        "#,
+    );
+
+    let mut writer = Writer::new(WBVec(vec![])).unwrap();
+    let generated_rules = rulegen::parse_template_and_call_gen(&data, &mut writer);
+    rulegen::print_rules(generated_rules, &mut writer).unwrap();
+
+    let expected = r#"rule aws_ec2_volume {
+  AWS::EC2::Volume {
+    Properties.AvailabilityZone IN ["us-west-2b", "us-west-2c"]
+    Properties.Encrypted == false
+    Properties.Size IN r[50, 500]
+  }
+}
+"#;
+    assert_eq!(expected, writer.stripped().unwrap());
+}
+
+#[test]
+fn test_rulegen_numeric_range() {
+    let values = |values: &[&str]| {
+        values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<std::collections::BTreeSet<String>>()
+    };
+
+    assert_eq!(
+        Some(String::from("r[-10, 500]")),
+        rulegen::numeric_range(&values(&["500", "-10", "50"]))
+    );
+    assert_eq!(
+        Some(String::from("r[0.5, 2.25]")),
+        rulegen::numeric_range(&values(&["2.25", "0.5"]))
+    );
+    assert_eq!(None, rulegen::numeric_range(&values(&["1.5", "3"])));
+    assert_eq!(None, rulegen::numeric_range(&values(&["-1.5", "3.5"])));
+    assert_eq!(
+        None,
+        rulegen::numeric_range(&values(&["50", "\"us-west-2b\""]))
+    );
+}
+
 #[test]
 fn test_rulegen_no_properties() {
     let data = String::from(
@@ -65,3 +134,35 @@ fn test_rulegen_no_properties() {
 
     assert_eq!(0, generated_rules.len());
 }
+
+#[test]
+fn test_rulegen_from_config_rule_generates_valid_rules() {
+    use crate::commands::config_rules::CONFIG_RULE_MAPPINGS;
+    use crate::utils::writer::WriteBuffer::Vec as WBVec;
+
+    for mapping in CONFIG_RULE_MAPPINGS {
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        rulegen::print_config_rule(mapping, &mut writer).unwrap();
+
+        let rule_name = mapping.identifier.replace('-', "_").to_uppercase();
+        let err = writer.err_to_stripped().unwrap();
+        assert!(
+            err.is_empty(),
+            "{} generated invalid rules: {err}",
+            rule_name
+        );
+    }
+}
+
+#[test]
+fn test_find_config_rule() {
+    use crate::commands::config_rules::find_config_rule;
+
+    let mapping = find_config_rule("s3-bucket-versioning-enabled").unwrap();
+    assert_eq!("AWS::S3::Bucket", mapping.resource_type);
+    assert_eq!(
+        Some(mapping),
+        find_config_rule("S3_BUCKET_VERSIONING_ENABLED")
+    );
+    assert!(find_config_rule("not-a-managed-rule").is_none());
+}
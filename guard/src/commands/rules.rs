@@ -0,0 +1,37 @@
+use clap::{Args, Subcommand};
+
+use crate::commands::registry::{List, Pull};
+use crate::commands::rules_diff::Diff;
+use crate::commands::Executable;
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+const ABOUT: &str = "Operations on rule packs, i.e. a rules file or a directory of rules files, and on the registries publishing them";
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Rules command groups operations that work on rule packs
+pub struct Rules {
+    #[command(subcommand)]
+    pub(crate) command: RulesCommands,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Subcommand)]
+pub(crate) enum RulesCommands {
+    Diff(Diff),
+    List(List),
+    Pull(Pull),
+}
+
+impl Executable for Rules {
+    fn execute(&self, writer: &mut Writer, reader: &mut Reader) -> Result<i32> {
+        match &self.command {
+            RulesCommands::Diff(cmd) => cmd.execute(writer, reader),
+            RulesCommands::List(cmd) => cmd.execute(writer, reader),
+            RulesCommands::Pull(cmd) => cmd.execute(writer, reader),
+        }
+    }
+}
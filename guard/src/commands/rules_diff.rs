@@ -0,0 +1,728 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::commands::files::{alphabetical, get_files_with_filter, read_file_content};
+use crate::commands::validate::parse_rules;
+use crate::commands::{
+    Executable, PRINT_JSON, RULE_FILE_SUPPORTED_EXTENSIONS, SUCCESS_STATUS_CODE,
+};
+use crate::rules::display::{slice_source, ValueOnlyDisplay};
+use crate::rules::errors::Error;
+use crate::rules::exprs::{
+    display_comparator, AccessClause, Block, Conjunctions, GuardAccessClause, GuardClause,
+    GuardNamedRuleClause, LetExpr, LetValue, ParameterizedNamedRuleClause, QueryPart, Rule,
+    RuleClause, RulesFile, WhenGuardClause,
+};
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=DIFF_ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Diff command reports the rules and clauses that changed between two versions of a rule pack
+pub struct Diff {
+    #[arg(help=OLD_RULES_HELP)]
+    /// path to a rules file, or a directory of rules files, of the previous version of the rule pack
+    pub(crate) old: String,
+    #[arg(help=NEW_RULES_HELP)]
+    /// path to a rules file, or a directory of rules files, of the new version of the rule pack
+    pub(crate) new: String,
+    #[arg(short=PRINT_JSON.1, long=PRINT_JSON.0, help=PRINT_JSON_HELP)]
+    /// print the differences in a json format
+    /// default is false
+    pub(crate) print_json: bool,
+}
+
+impl Executable for Diff {
+    /// .
+    /// compares the rules of both rule packs by rule name
+    ///
+    /// This function will return an error if
+    /// - any of the specified paths do not exist
+    /// - parse errors occur in any of the rule files
+    fn execute(&self, writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let old = read_rule_pack(&self.old)?;
+        let new = read_rule_pack(&self.new)?;
+
+        let diff = diff_rule_packs(&parse_rule_pack(&old)?, &parse_rule_pack(&new)?);
+
+        match self.print_json {
+            true => writeln!(writer, "{}", serde_json::to_string_pretty(&diff)?)?,
+            false => print_diff(&diff, writer)?,
+        }
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct RulePackDiff {
+    pub(crate) added_rules: Vec<String>,
+    pub(crate) removed_rules: Vec<String>,
+    pub(crate) modified_rules: Vec<RuleDiff>,
+    pub(crate) added_variables: Vec<String>,
+    pub(crate) removed_variables: Vec<String>,
+    pub(crate) changed_variables: Vec<ClauseChange>,
+}
+
+impl RulePackDiff {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added_rules.is_empty()
+            && self.removed_rules.is_empty()
+            && self.modified_rules.is_empty()
+            && self.added_variables.is_empty()
+            && self.removed_variables.is_empty()
+            && self.changed_variables.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct RuleDiff {
+    pub(crate) name: String,
+    pub(crate) added_clauses: Vec<String>,
+    pub(crate) removed_clauses: Vec<String>,
+    pub(crate) changed_clauses: Vec<ClauseChange>,
+    pub(crate) changed_messages: Vec<MessageChange>,
+}
+
+/// a clause that checks the same query in both versions, but with a different operator or value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ClauseChange {
+    pub(crate) target: String,
+    pub(crate) old: String,
+    pub(crate) new: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct MessageChange {
+    pub(crate) clause: String,
+    pub(crate) old: Option<String>,
+    pub(crate) new: Option<String>,
+}
+
+/// the rules and file level assignments of all the rules files in a rule pack
+#[derive(Debug, Default)]
+struct RulePack<'r, 'loc> {
+    rules: BTreeMap<&'r str, Vec<&'r Rule<'loc>>>,
+    assignments: BTreeMap<&'r str, &'r LetExpr<'loc>>,
+}
+
+pub(crate) fn read_rule_pack(path: &str) -> Result<Vec<(String, String)>> {
+    let base = Path::new(path);
+    if !base.exists() {
+        return Err(Error::FileNotFoundError(path.to_string()));
+    }
+
+    let files = match base.is_file() {
+        true => vec![base.to_path_buf()],
+        false => get_files_with_filter(path, alphabetical, |entry| {
+            entry.file_name().to_str().map_or(false, |name| {
+                RULE_FILE_SUPPORTED_EXTENSIONS
+                    .iter()
+                    .any(|extension| name.ends_with(extension))
+            })
+        })?,
+    };
+
+    files
+        .iter()
+        .map(|file| {
+            let name = file
+                .strip_prefix(base)
+                .ok()
+                .filter(|relative| !relative.as_os_str().is_empty())
+                .unwrap_or(file)
+                .display()
+                .to_string();
+            Ok((name, read_file_content(File::open(file)?)?))
+        })
+        .collect()
+}
+
+fn parse_rule_pack(files: &[(String, String)]) -> Result<Vec<RulesFile<'_>>> {
+    let mut parsed = Vec::with_capacity(files.len());
+    for (name, content) in files {
+        match parse_rules(content, name) {
+            Ok(Some(rules_file)) => parsed.push(rules_file),
+            Ok(None) => {}
+            Err(e) => {
+                return Err(Error::ParseError(format!(
+                    "Parsing error handling rule file = {name}, Error = {e}"
+                )))
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+fn rule_pack<'r, 'loc>(rules_files: &'r [RulesFile<'loc>]) -> RulePack<'r, 'loc> {
+    let mut pack = RulePack::default();
+    for rules_file in rules_files {
+        for rule in &rules_file.guard_rules {
+            pack.rules
+                .entry(rule.rule_name.as_str())
+                .or_default()
+                .push(rule);
+        }
+        for parameterized in &rules_file.parameterized_rules {
+            pack.rules
+                .entry(parameterized.rule.rule_name.as_str())
+                .or_default()
+                .push(&parameterized.rule);
+        }
+        for assignment in &rules_file.assignments {
+            pack.assignments.insert(assignment.var.as_str(), assignment);
+        }
+    }
+    pack
+}
+
+fn diff_rule_packs(old: &[RulesFile<'_>], new: &[RulesFile<'_>]) -> RulePackDiff {
+    let (old, new) = (rule_pack(old), rule_pack(new));
+    let mut diff = RulePackDiff::default();
+
+    for (name, rules) in &old.rules {
+        match new.rules.get(name) {
+            None => diff.removed_rules.push(name.to_string()),
+            Some(new_rules) => {
+                let rule_diff = diff_entries(name, &rule_entries(rules), &rule_entries(new_rules));
+                if !rule_diff.is_unchanged() {
+                    diff.modified_rules.push(rule_diff);
+                }
+            }
+        }
+    }
+    diff.added_rules = new
+        .rules
+        .keys()
+        .filter(|name| !old.rules.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect();
+
+    for (name, assignment) in &old.assignments {
+        match new.assignments.get(name) {
+            None => diff.removed_variables.push(name.to_string()),
+            Some(new_assignment) => {
+                let (old_value, new_value) = (
+                    let_value(&assignment.value),
+                    let_value(&new_assignment.value),
+                );
+                if old_value != new_value {
+                    diff.changed_variables.push(ClauseChange {
+                        target: name.to_string(),
+                        old: old_value,
+                        new: new_value,
+                    });
+                }
+            }
+        }
+    }
+    diff.added_variables = new
+        .assignments
+        .keys()
+        .filter(|name| !old.assignments.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect();
+
+    diff
+}
+
+impl RuleDiff {
+    fn is_unchanged(&self) -> bool {
+        self.added_clauses.is_empty()
+            && self.removed_clauses.is_empty()
+            && self.changed_clauses.is_empty()
+            && self.changed_messages.is_empty()
+    }
+}
+
+//
+// Every clause of a rule is flattened into an entry, where the target is the query (prefixed by
+// the blocks the clause is nested in) and the check is the operator and value. Entries with the
+// same target in both versions are compared by their checks, which is how operator and value
+// changes are told apart from clauses that were added or removed.
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    target: String,
+    check: String,
+    message: Option<String>,
+    depth: usize,
+}
+
+impl Entry {
+    fn text(&self) -> String {
+        let clause = match self.check.is_empty() {
+            true => self.target.clone(),
+            false => format!("{} {}", self.target, self.check),
+        };
+        format!("{clause}{}", " }".repeat(self.depth))
+    }
+}
+
+#[derive(Default)]
+struct EntryCollector {
+    context: Vec<String>,
+    entries: Vec<Entry>,
+}
+
+impl EntryCollector {
+    fn push(&mut self, target: String, check: String, message: Option<&String>) {
+        let prefix = self
+            .context
+            .iter()
+            .map(|each| format!("{each} {{ "))
+            .collect::<String>();
+        self.entries.push(Entry {
+            target: format!("{prefix}{target}"),
+            check,
+            message: message.map(|msg| msg.trim().to_string()),
+            depth: self.context.len(),
+        });
+    }
+
+    fn nested<F: FnOnce(&mut Self)>(&mut self, context: String, collect: F) {
+        self.context.push(context);
+        collect(self);
+        self.context.pop();
+    }
+
+    fn block<T, F: Fn(&mut Self, &T)>(&mut self, block: &Block<'_, T>, collect: F)
+    where
+        T: Clause,
+    {
+        for assignment in &block.assignments {
+            self.push(
+                format!("let {}", assignment.var),
+                format!("= {}", let_value(&assignment.value)),
+                None,
+            );
+        }
+        for disjunctions in &block.conjunctions {
+            match disjunctions.as_slice() {
+                [single] => collect(self, single),
+                _ => self.push(
+                    disjunctions
+                        .iter()
+                        .map(Clause::text)
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                    String::new(),
+                    None,
+                ),
+            }
+        }
+    }
+
+    fn rule_clause(&mut self, clause: &RuleClause<'_>) {
+        match clause {
+            RuleClause::Clause(clause) => self.guard_clause(clause),
+            RuleClause::WhenBlock(conditions, block, otherwise) => {
+                self.when_block(conditions, block, otherwise.as_ref())
+            }
+            RuleClause::TypeBlock(type_block) => {
+                let context = match &type_block.conditions {
+                    Some(conditions) => format!(
+                        "{} when {}",
+                        type_block.type_name,
+                        when_conditions(conditions)
+                    ),
+                    None => type_block.type_name.clone(),
+                };
+                self.nested(context, |this| {
+                    this.block(&type_block.block, Self::guard_clause)
+                })
+            }
+        }
+    }
+
+    fn guard_clause(&mut self, clause: &GuardClause<'_>) {
+        match clause {
+            GuardClause::Clause(GuardAccessClause {
+                access_clause,
+                negation,
+            }) => self.push(
+                query(&access_clause.query.query),
+                format!(
+                    "{}{}",
+                    if *negation { "not " } else { "" },
+                    comparison(access_clause)
+                ),
+                access_clause.custom_message.as_ref(),
+            ),
+            GuardClause::NamedRule(named) => self.push(
+                named_rule(named),
+                String::new(),
+                named.custom_message.as_ref(),
+            ),
+            GuardClause::ParameterizedNamedRule(parameterized) => self.push(
+                parameterized_rule(parameterized),
+                String::new(),
+                parameterized.named_rule.custom_message.as_ref(),
+            ),
+            GuardClause::BlockClause(block_clause) => self.nested(
+                format!(
+                    "{}{}",
+                    if block_clause.negation { "not " } else { "" },
+                    query(&block_clause.query.query)
+                ),
+                |this| this.block(&block_clause.block, Self::guard_clause),
+            ),
+            GuardClause::WhenBlock(conditions, block, otherwise) => {
+                self.when_block(conditions, block, otherwise.as_ref())
+            }
+        }
+    }
+
+    /// the clauses of the else block are nested under their own context, so moving a clause
+    /// between the blocks is a change
+    fn when_block(
+        &mut self,
+        conditions: &Conjunctions<WhenGuardClause<'_>>,
+        block: &Block<'_, GuardClause<'_>>,
+        otherwise: Option<&Block<'_, GuardClause<'_>>>,
+    ) {
+        let conditions = when_conditions(conditions);
+        self.nested(format!("when {conditions}"), |this| {
+            this.block(block, Self::guard_clause)
+        });
+        if let Some(otherwise) = otherwise {
+            self.nested(format!("when {conditions} else"), |this| {
+                this.block(otherwise, Self::guard_clause)
+            });
+        }
+    }
+}
+
+fn rule_entries(rules: &[&Rule<'_>]) -> Vec<Entry> {
+    let mut collector = EntryCollector::default();
+    for rule in rules {
+        match &rule.conditions {
+            Some(conditions) => collector
+                .nested(format!("when {}", when_conditions(conditions)), |this| {
+                    this.block(&rule.block, EntryCollector::rule_clause)
+                }),
+            None => collector.block(&rule.block, EntryCollector::rule_clause),
+        }
+    }
+    collector.entries
+}
+
+fn diff_entries(name: &str, old: &[Entry], new: &[Entry]) -> RuleDiff {
+    let mut rule_diff = RuleDiff {
+        name: name.to_string(),
+        ..Default::default()
+    };
+
+    let mut remaining_new = new.iter().collect::<Vec<_>>();
+    let mut remaining_old = vec![];
+    for each in old {
+        match remaining_new
+            .iter()
+            .position(|other| other.target == each.target && other.check == each.check)
+        {
+            Some(idx) => {
+                let other = remaining_new.remove(idx);
+                if other.message != each.message {
+                    rule_diff.changed_messages.push(MessageChange {
+                        clause: each.text(),
+                        old: each.message.clone(),
+                        new: other.message.clone(),
+                    });
+                }
+            }
+            None => remaining_old.push(each),
+        }
+    }
+
+    // a single clause on the same target in both versions is reported as a change of operator or value
+    let mut targets = BTreeMap::<&str, (Vec<&Entry>, Vec<&Entry>)>::new();
+    for each in &remaining_old {
+        targets
+            .entry(each.target.as_str())
+            .or_default()
+            .0
+            .push(each);
+    }
+    for each in &remaining_new {
+        targets
+            .entry(each.target.as_str())
+            .or_default()
+            .1
+            .push(each);
+    }
+
+    for each in &remaining_old {
+        match targets.get(each.target.as_str()) {
+            Some((old, new)) if old.len() == 1 && new.len() == 1 => {
+                rule_diff.changed_clauses.push(ClauseChange {
+                    target: format!("{}{}", each.target, " }".repeat(each.depth)),
+                    old: each.check.clone(),
+                    new: new[0].check.clone(),
+                })
+            }
+            _ => rule_diff.removed_clauses.push(each.text()),
+        }
+    }
+    for each in &remaining_new {
+        match targets.get(each.target.as_str()) {
+            Some((old, new)) if old.len() == 1 && new.len() == 1 => {}
+            _ => rule_diff.added_clauses.push(each.text()),
+        }
+    }
+
+    rule_diff
+}
+
+trait Clause {
+    fn text(&self) -> String;
+}
+
+impl<'loc> Clause for GuardClause<'loc> {
+    fn text(&self) -> String {
+        let mut collector = EntryCollector::default();
+        collector.guard_clause(self);
+        collector
+            .entries
+            .iter()
+            .map(Entry::text)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<'loc> Clause for RuleClause<'loc> {
+    fn text(&self) -> String {
+        let mut collector = EntryCollector::default();
+        collector.rule_clause(self);
+        collector
+            .entries
+            .iter()
+            .map(Entry::text)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<'loc> Clause for WhenGuardClause<'loc> {
+    fn text(&self) -> String {
+        match self {
+            WhenGuardClause::Clause(GuardAccessClause {
+                access_clause,
+                negation,
+            }) => format!(
+                "{}{} {}",
+                if *negation { "not " } else { "" },
+                query(&access_clause.query.query),
+                comparison(access_clause)
+            ),
+            WhenGuardClause::NamedRule(named) => named_rule(named),
+            WhenGuardClause::ParameterizedNamedRule(parameterized) => {
+                parameterized_rule(parameterized)
+            }
+        }
+    }
+}
+
+fn conjunctions<T: Clause>(conjunctions: &Conjunctions<T>) -> String {
+    conjunctions
+        .iter()
+        .map(|disjunctions| {
+            disjunctions
+                .iter()
+                .map(Clause::text)
+                .collect::<Vec<_>>()
+                .join(" or ")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn when_conditions(conditions: &Conjunctions<WhenGuardClause<'_>>) -> String {
+    conjunctions(conditions)
+}
+
+fn named_rule(named: &GuardNamedRuleClause<'_>) -> String {
+    format!(
+        "{}{}",
+        if named.negation { "not " } else { "" },
+        named.dependent_rule
+    )
+}
+
+fn parameterized_rule(parameterized: &ParameterizedNamedRuleClause<'_>) -> String {
+    format!(
+        "{}({})",
+        named_rule(&parameterized.named_rule),
+        parameterized
+            .parameters
+            .iter()
+            .map(let_value)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn comparison(access_clause: &AccessClause<'_>) -> String {
+    let comparator = display_comparator(access_clause.comparator);
+    match &access_clause.compare_with {
+        Some(value) => format!("{comparator}{}", let_value(value)),
+        None => comparator.trim_end().to_string(),
+    }
+}
+
+fn let_value(value: &LetValue<'_>) -> String {
+    match value {
+        LetValue::Value(PathAwareValue::Regex((_, regex))) => format!("/{regex}/"),
+        LetValue::Value(value) => ValueOnlyDisplay(Rc::new(value.clone())).to_string(),
+        LetValue::AccessClause(access) => query(&access.query),
+        LetValue::FunctionCall(function) => format!(
+            "{}({})",
+            function.name,
+            function
+                .parameters
+                .iter()
+                .map(let_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn query(parts: &[QueryPart<'_>]) -> String {
+    let mut text = String::new();
+    let mut previous_was_variable = false;
+    for part in parts {
+        let segment = match part {
+            // variables always get an implicit [*] when parsed, which is not part of what was written
+            QueryPart::AllIndices(None) if previous_was_variable => String::new(),
+            QueryPart::AllIndices(_) => String::from("[*]"),
+            QueryPart::Index(idx) => format!("[{idx}]"),
+            QueryPart::Slice(start, end) => slice_source(*start, *end),
+            QueryPart::Filter(name, clauses) => format!(
+                "[ {}{} ]",
+                name.as_ref()
+                    .map_or(String::new(), |name| format!("{name} | ")),
+                conjunctions(clauses)
+            ),
+            QueryPart::MapKeyFilter(name, clause) => format!(
+                "[ {}keys {}{} ]",
+                name.as_ref()
+                    .map_or(String::new(), |name| format!("{name} | ")),
+                display_comparator(clause.comparator),
+                let_value(&clause.compare_with)
+            ),
+            QueryPart::Key(key) if !text.is_empty() => format!(".{key}"),
+            QueryPart::Key(key) => key.clone(),
+            QueryPart::AllValues(_) if !text.is_empty() => String::from(".*"),
+            QueryPart::AllValues(_) => String::from("*"),
+            QueryPart::This => String::from("this"),
+            QueryPart::Resolve => String::from(".resolve()"),
+            QueryPart::Descendants(key) => format!(".**.{key}"),
+        };
+        previous_was_variable = part.is_variable();
+        text.push_str(&segment);
+    }
+    text
+}
+
+fn print_diff(diff: &RulePackDiff, writer: &mut Writer) -> Result<()> {
+    if diff.is_empty() {
+        writeln!(writer, "No differences found")?;
+        return Ok(());
+    }
+
+    if !diff.added_rules.is_empty() {
+        writeln!(writer, "Added rules:")?;
+        for name in &diff.added_rules {
+            writeln!(writer, "  + {name}")?;
+        }
+    }
+
+    if !diff.removed_rules.is_empty() {
+        writeln!(writer, "Removed rules:")?;
+        for name in &diff.removed_rules {
+            writeln!(writer, "  - {name}")?;
+        }
+    }
+
+    if !diff.modified_rules.is_empty() {
+        writeln!(writer, "Modified rules:")?;
+        for rule in &diff.modified_rules {
+            writeln!(writer, "  ~ {}", rule.name)?;
+            for clause in &rule.removed_clauses {
+                writeln!(writer, "      - {clause}")?;
+            }
+            for clause in &rule.added_clauses {
+                writeln!(writer, "      + {clause}")?;
+            }
+            for change in &rule.changed_clauses {
+                writeln!(
+                    writer,
+                    "      ~ {}: {} -> {}",
+                    change.target, change.old, change.new
+                )?;
+            }
+            for change in &rule.changed_messages {
+                writeln!(
+                    writer,
+                    "      ~ message of {}: {} -> {}",
+                    change.clause,
+                    message(&change.old),
+                    message(&change.new)
+                )?;
+            }
+        }
+    }
+
+    let variables_changed = !diff.added_variables.is_empty()
+        || !diff.removed_variables.is_empty()
+        || !diff.changed_variables.is_empty();
+    if variables_changed {
+        writeln!(writer, "Modified variables:")?;
+        for name in &diff.removed_variables {
+            writeln!(writer, "  - {name}")?;
+        }
+        for name in &diff.added_variables {
+            writeln!(writer, "  + {name}")?;
+        }
+        for change in &diff.changed_variables {
+            writeln!(
+                writer,
+                "  ~ {}: {} -> {}",
+                change.target, change.old, change.new
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn message(message: &Option<String>) -> String {
+    message
+        .as_ref()
+        .map_or(String::from("<none>"), |msg| format!("<<{msg}>>"))
+}
+
+const DIFF_ABOUT: &str = r#"Reports the differences between two versions of a rule pack.
+Rules are matched by name and reported as added, removed or modified. For modified rules the
+clauses that were added or removed are listed, as well as clauses checking the same query with
+a different operator or value, and changes to custom messages."#;
+const OLD_RULES_HELP: &str =
+    "Path to a rules file, or a directory of rules files, of the previous version of the rule pack";
+const NEW_RULES_HELP: &str =
+    "Path to a rules file, or a directory of rules files, of the new version of the rule pack";
+const PRINT_JSON_HELP: &str = "Print the differences in a json format";
+
+#[cfg(test)]
+#[path = "rules_diff_tests.rs"]
+mod rules_diff_tests;
@@ -0,0 +1,160 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+fn diff(old: &str, new: &str) -> RulePackDiff {
+    let old = parse_rules(old, "old.guard").unwrap().unwrap();
+    let new = parse_rules(new, "new.guard").unwrap().unwrap();
+    diff_rule_packs(&[old], &[new])
+}
+
+#[test]
+fn test_no_differences_ignores_formatting() {
+    let old = r#"
+    rule s3_encryption {
+        Resources.*[ Type == 'AWS::S3::Bucket' ].Properties.BucketEncryption exists
+    }
+    "#;
+    let new = r#"
+
+    # same rule, different layout
+    rule s3_encryption
+    {
+      Resources.*[Type=="AWS::S3::Bucket"].Properties.BucketEncryption EXISTS
+    }
+    "#;
+
+    assert_eq!(diff(old, new), RulePackDiff::default());
+}
+
+#[test]
+fn test_added_and_removed_rules() {
+    let old = r#"
+    rule a { Resources exists }
+    rule b { Resources exists }
+    "#;
+    let new = r#"
+    rule b { Resources exists }
+    rule c { Resources exists }
+    "#;
+
+    let diff = diff(old, new);
+    assert_eq!(diff.added_rules, vec![String::from("c")]);
+    assert_eq!(diff.removed_rules, vec![String::from("a")]);
+    assert!(diff.modified_rules.is_empty());
+}
+
+#[test]
+fn test_changed_operator_and_value() {
+    let old = r#"
+    rule volumes {
+        AWS::EC2::Volume {
+            Properties.Size <= 100
+            Properties.Encrypted == true
+        }
+    }
+    "#;
+    let new = r#"
+    rule volumes {
+        AWS::EC2::Volume {
+            Properties.Size < 500
+            Properties.Encrypted == true
+        }
+    }
+    "#;
+
+    let diff = diff(old, new);
+    assert_eq!(
+        diff.modified_rules,
+        vec![RuleDiff {
+            name: String::from("volumes"),
+            changed_clauses: vec![ClauseChange {
+                target: String::from("AWS::EC2::Volume { Properties.Size }"),
+                old: String::from("LESS THAN EQUALS 100"),
+                new: String::from("LESS THAN 500"),
+            }],
+            ..Default::default()
+        }]
+    );
+}
+
+#[test]
+fn test_added_removed_clauses_and_messages() {
+    let old = r#"
+    rule buckets when Resources exists {
+        Resources.*.Properties.BucketName exists
+        Resources.*.Properties.Tags !empty << tags are required >>
+    }
+    "#;
+    let new = r#"
+    rule buckets when Resources exists {
+        Resources.*.Properties.Tags !empty << buckets must be tagged >>
+        Resources.*.Properties.VersioningConfiguration exists or
+        Resources.*.Properties.ObjectLockEnabled == true
+    }
+    "#;
+
+    let diff = diff(old, new);
+    assert_eq!(
+        diff.modified_rules,
+        vec![RuleDiff {
+            name: String::from("buckets"),
+            added_clauses: vec![String::from(
+                "when Resources EXISTS { Resources.*.Properties.VersioningConfiguration EXISTS or Resources.*.Properties.ObjectLockEnabled EQUALS true }"
+            )],
+            removed_clauses: vec![String::from(
+                "when Resources EXISTS { Resources.*.Properties.BucketName EXISTS }"
+            )],
+            changed_messages: vec![MessageChange {
+                clause: String::from(
+                    "when Resources EXISTS { Resources.*.Properties.Tags not EMPTY }"
+                ),
+                old: Some(String::from("tags are required")),
+                new: Some(String::from("buckets must be tagged")),
+            }],
+            ..Default::default()
+        }]
+    );
+}
+
+#[test]
+fn test_changed_variables() {
+    let old = r#"
+    let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+    let allowed = ["us-east-1"]
+    rule buckets when %buckets !empty { %buckets.Properties exists }
+    "#;
+    let new = r#"
+    let buckets = Resources.*[ Type == /S3::Bucket/ ]
+    let regions = ["us-east-1"]
+    rule buckets when %buckets !empty { %buckets.Properties exists }
+    "#;
+
+    let diff = diff(old, new);
+    assert!(diff.modified_rules.is_empty());
+    assert_eq!(diff.added_variables, vec![String::from("regions")]);
+    assert_eq!(diff.removed_variables, vec![String::from("allowed")]);
+    assert_eq!(
+        diff.changed_variables,
+        vec![ClauseChange {
+            target: String::from("buckets"),
+            old: String::from(r#"Resources.*[ Type EQUALS "AWS::S3::Bucket" ]"#),
+            new: String::from("Resources.*[ Type EQUALS /S3::Bucket/ ]"),
+        }]
+    );
+}
+
+#[test]
+fn test_negation_is_reported_as_a_change() {
+    let old = r#"rule public { Resources.*.Properties.PublicAccess exists }"#;
+    let new = r#"rule public { not Resources.*.Properties.PublicAccess exists }"#;
+
+    let diff = diff(old, new);
+    assert_eq!(
+        diff.modified_rules[0].changed_clauses,
+        vec![ClauseChange {
+            target: String::from("Resources.*.Properties.PublicAccess"),
+            old: String::from("EXISTS"),
+            new: String::from("not EXISTS"),
+        }]
+    );
+}
@@ -0,0 +1,60 @@
+use std::fs;
+
+use clap::Args;
+
+use crate::commands::{Executable, SUCCESS_STATUS_CODE};
+use crate::rules::pack::{read_pack, sign_pack, SIGNATURE_EXTENSION};
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+
+const ABOUT: &str = r#"Signs a rules pack built with cfn-guard build with an Ed25519 private key, so that validate, test
+and the Lambda function given the public key with --verify-signature only evaluate rules that were
+signed with it and not modified since. The signature is written next to the pack, e.g. rules.pack.sig"#;
+const PACK_HELP: &str = "Provide the rules pack to sign";
+const KEY_HELP: &str =
+    "Provide the Ed25519 private key in PEM, e.g. from openssl genpkey -algorithm ed25519";
+const OUTPUT_HELP: &str =
+    "Write the signature to this file. Defaults to the path of the rules pack followed by .sig";
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(about=ABOUT)]
+#[clap(arg_required_else_help = true)]
+/// .
+/// The Sign command signs a rules pack for it to be verified before it is evaluated
+pub struct Sign {
+    #[arg(short, long, help=PACK_HELP, required=true)]
+    /// the path to the rules pack to sign
+    pub(crate) pack: String,
+    #[arg(short, long, help=KEY_HELP, required=true)]
+    /// the path to the private key in PEM
+    pub(crate) key: String,
+    #[arg(short, long, help=OUTPUT_HELP)]
+    /// the path the signature is written to, the pack followed by .sig when not set
+    pub(crate) output: Option<String>,
+}
+
+impl Executable for Sign {
+    /// .
+    /// signs the rules pack and writes the signature
+    ///
+    /// This function will return an error if
+    /// - the rules pack or the key could not be read
+    /// - the rules pack was not built by this version of cfn-guard
+    /// - the key is not an Ed25519 private key in PEM
+    /// - the signature could not be written
+    fn execute(&self, _writer: &mut Writer, _reader: &mut Reader) -> Result<i32> {
+        let content = fs::read(&self.pack)?;
+        // a pack this version can not load would fail to verify anyway, refuse it before signing
+        read_pack(&self.pack, &content)?;
+
+        let signature = sign_pack(&content, &fs::read_to_string(&self.key)?)?;
+        let output = match &self.output {
+            Some(output) => output.clone(),
+            None => format!("{}{SIGNATURE_EXTENSION}", self.pack),
+        };
+        fs::write(output, format!("{signature}\n"))?;
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
@@ -0,0 +1,182 @@
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+use crate::commands::validate::{build_data_file, DataFile};
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::Result;
+
+/// a resource of a deployed stack, as described by DescribeStackResources
+#[cfg_attr(not(feature = "aws"), allow(dead_code))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct StackResource {
+    pub(crate) logical_id: String,
+    pub(crate) physical_id: String,
+    pub(crate) resource_type: String,
+    pub(crate) status: String,
+    /// `NOT_CHECKED` until drift detection is run on the stack
+    pub(crate) drift_status: String,
+}
+
+/// the template a stack was deployed with as a data file. The parameter values it was deployed
+/// with are its parameters file, in the `ParameterKey`/`ParameterValue` shape of DescribeStacks,
+/// and its resources are exposed to the rules as `%STACK_RESOURCES`, keyed by their logical ids
+#[cfg_attr(not(feature = "aws"), allow(dead_code))]
+pub(crate) fn stack_data_file(
+    stack_name: &str,
+    template_body: String,
+    parameters: &[(String, String)],
+    resources: &[StackResource],
+) -> Result<DataFile> {
+    // reported as stack/<name>, e.g. stack/network
+    let mut data_file = build_data_file(template_body, format!("stack/{stack_name}"))?;
+
+    let parameters = parameters
+        .iter()
+        .map(|(key, value)| serde_json::json!({ "ParameterKey": key, "ParameterValue": value }))
+        .collect::<Vec<_>>();
+    data_file.parameters_file = Some(Rc::new(PathAwareValue::try_from(
+        serde_json::Value::Array(parameters),
+    )?));
+
+    let resources = resources
+        .iter()
+        .map(|resource| {
+            (
+                resource.logical_id.clone(),
+                serde_json::json!({
+                    "PhysicalResourceId": resource.physical_id,
+                    "ResourceType": resource.resource_type,
+                    "ResourceStatus": resource.status,
+                    "DriftStatus": resource.drift_status,
+                }),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+    data_file.stack_resources = Some(Rc::new(PathAwareValue::try_from(
+        serde_json::Value::Object(resources),
+    )?));
+
+    Ok(data_file)
+}
+
+/// the deployed template of each stack, fetched with the credentials and region of the
+/// environment
+///
+/// This function will return an error if a stack does not exist or can not be read
+#[cfg(feature = "aws")]
+pub(crate) fn stack_data_files(stacks: &[String]) -> Result<Vec<DataFile>> {
+    if stacks.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        let config = aws_config::load_from_env().await;
+        let client = aws_sdk_cloudformation::Client::new(&config);
+
+        let mut data_files = vec![];
+        for stack_name in stacks {
+            data_files.push(fetch_stack(&client, stack_name).await?);
+        }
+        Ok(data_files)
+    })
+}
+
+#[cfg(not(feature = "aws"))]
+pub(crate) fn stack_data_files(stacks: &[String]) -> Result<Vec<DataFile>> {
+    match stacks.is_empty() {
+        true => Ok(vec![]),
+        false => Err(crate::rules::errors::Error::IllegalArguments(String::from(
+            "from-stack requires cfn-guard to be built with the aws feature, e.g. cargo install cfn-guard --features aws",
+        ))),
+    }
+}
+
+#[cfg(feature = "aws")]
+async fn fetch_stack(
+    client: &aws_sdk_cloudformation::Client,
+    stack_name: &str,
+) -> Result<DataFile> {
+    use aws_sdk_cloudformation::error::DisplayErrorContext;
+    use aws_sdk_cloudformation::types::TemplateStage;
+
+    use crate::rules::errors::Error;
+
+    let unreadable = |e: &dyn std::fmt::Display| {
+        Error::IllegalArguments(format!("Unable to read the stack {stack_name}, {e}"))
+    };
+
+    // the processed template has its transforms, e.g. AWS::Serverless, expanded as deployed
+    let template = client
+        .get_template()
+        .stack_name(stack_name)
+        .template_stage(TemplateStage::Processed)
+        .send()
+        .await
+        .map_err(|e| unreadable(&DisplayErrorContext(e)))?;
+    let template_body = template
+        .template_body()
+        .ok_or_else(|| unreadable(&"it has no template"))?
+        .to_string();
+
+    let stacks = client
+        .describe_stacks()
+        .stack_name(stack_name)
+        .send()
+        .await
+        .map_err(|e| unreadable(&DisplayErrorContext(e)))?;
+    // the resolved value of SSM parameters is the one the stack was deployed with
+    let parameters = stacks
+        .stacks()
+        .iter()
+        .flat_map(|stack| stack.parameters())
+        .filter_map(|parameter| {
+            Some((
+                parameter.parameter_key()?.to_string(),
+                parameter
+                    .resolved_value()
+                    .or_else(|| parameter.parameter_value())?
+                    .to_string(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let resources = client
+        .describe_stack_resources()
+        .stack_name(stack_name)
+        .send()
+        .await
+        .map_err(|e| unreadable(&DisplayErrorContext(e)))?;
+    let resources = resources
+        .stack_resources()
+        .iter()
+        .map(|resource| StackResource {
+            logical_id: resource
+                .logical_resource_id()
+                .unwrap_or_default()
+                .to_string(),
+            physical_id: resource
+                .physical_resource_id()
+                .unwrap_or_default()
+                .to_string(),
+            resource_type: resource.resource_type().unwrap_or_default().to_string(),
+            status: resource
+                .resource_status()
+                .map_or("", |status| status.as_str())
+                .to_string(),
+            drift_status: resource
+                .drift_information()
+                .and_then(|drift| drift.stack_resource_drift_status())
+                .map_or("NOT_CHECKED", |status| status.as_str())
+                .to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    stack_data_file(stack_name, template_body, &parameters, &resources)
+}
+
+#[cfg(test)]
+#[path = "stack_tests.rs"]
+mod stack_tests;
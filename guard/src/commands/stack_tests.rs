@@ -0,0 +1,78 @@
+use super::*;
+use crate::commands::template_parameters::template_parameter_values;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::RulesFile;
+use crate::rules::Status;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+
+const TEMPLATE: &str = r#"
+Parameters:
+  Environment:
+    Type: String
+    Default: dev
+Resources:
+  logs:
+    Type: AWS::Logs::LogGroup
+    Properties:
+      Tags:
+        - Key: env
+          Value: { Ref: Environment }
+  bucket:
+    Type: AWS::S3::Bucket
+"#;
+
+fn resource(logical_id: &str, resource_type: &str, drift_status: &str) -> StackResource {
+    StackResource {
+        logical_id: logical_id.to_string(),
+        physical_id: format!("network-{logical_id}-1A2B3C"),
+        resource_type: resource_type.to_string(),
+        status: String::from("UPDATE_COMPLETE"),
+        drift_status: drift_status.to_string(),
+    }
+}
+
+fn network_stack(bucket_drift_status: &str) -> Result<DataFile> {
+    stack_data_file(
+        "network",
+        TEMPLATE.to_string(),
+        &[(String::from("Environment"), String::from("prod"))],
+        &[
+            resource("logs", "AWS::Logs::LogGroup", "IN_SYNC"),
+            resource("bucket", "AWS::S3::Bucket", bucket_drift_status),
+        ],
+    )
+}
+
+#[test]
+fn test_stack_parameters_are_deployed_values() -> Result<()> {
+    let data_file = network_stack("IN_SYNC")?;
+
+    assert_eq!("stack/network", data_file.name);
+    let values = template_parameter_values(&data_file, &IndexMap::new());
+    assert!(matches!(&values["Environment"], PathAwareValue::String((_, v)) if v == "prod"));
+
+    Ok(())
+}
+
+#[test]
+fn test_rules_read_stack_resources() -> Result<()> {
+    let rules = RulesFile::try_from(
+        r#"
+rule no_drift {
+    %STACK_RESOURCES.*.DriftStatus != "MODIFIED"
+    %STACK_RESOURCES.bucket.PhysicalResourceId == /^network-bucket-/
+}
+"#,
+    )?;
+
+    for (drift_status, expected) in [("IN_SYNC", Status::PASS), ("MODIFIED", Status::FAIL)] {
+        let data_file = network_stack(drift_status)?;
+        let mut root_scope = root_scope(&rules, Rc::new(data_file.path_value))
+            .with_stack_resources(data_file.stack_resources);
+        assert_eq!(expected, eval_rules_file(&rules, &mut root_scope, None)?);
+    }
+
+    Ok(())
+}
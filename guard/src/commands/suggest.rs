@@ -0,0 +1,290 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::fs;
+
+use crate::commands::rulegen::{numeric_range, write_generated_rules};
+use crate::commands::{Executable, GOOD, OUTPUT_FILE, SUCCESS_STATUS_CODE, TYPE};
+use crate::rules::errors::Error;
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::Result;
+use crate::utils::reader::Reader;
+use crate::utils::writer::Writer;
+use clap::Args;
+use itertools::Itertools;
+use string_builder::Builder;
+
+const ABOUT: &str = "Suggest clauses for a resource type from a compliant example template. The suggested clauses are candidates to review and edit, not a finished rule";
+const GOOD_HELP: &str = "Provide path to a compliant CloudFormation template file in JSON or YAML";
+const TYPE_HELP: &str = "The resource type to suggest clauses for, e.g. AWS::RDS::DBInstance";
+const OUTPUT_HELP: &str = "Write to output file";
+const ALLOWED_VALUE_PLACEHOLDER: &str = "<ALLOWED_VALUE>";
+
+#[derive(Debug, Clone, Eq, PartialEq, Args)]
+#[clap(arg_required_else_help = true)]
+#[clap(about=ABOUT)]
+/// .
+/// The Suggest command proposes clauses for a single resource type based on a compliant example
+pub struct Suggest {
+    /// the path to the compliant example template
+    #[arg(name="good", short=GOOD.1, long, help=GOOD_HELP)]
+    pub(crate) good: String,
+    /// the resource type the clauses are suggested for
+    #[arg(name="type", short=TYPE.1, long, help=TYPE_HELP)]
+    pub(crate) resource_type: String,
+    /// the path to the file which the suggested rule will be outputted to
+    /// default None
+    /// if set to None the rule will be outputted to the stdout
+    #[arg(short, long, visible_alias=OUTPUT_FILE.0, help=OUTPUT_HELP)]
+    pub(crate) output: Option<String>,
+}
+
+impl Executable for Suggest {
+    /// .
+    /// suggests clauses from the properties of the resources of the given type
+    ///
+    /// This function will return an error if
+    /// - the specified path does not exist
+    /// - illegal json or yaml syntax present in the template
+    /// - the template has no resources of the given type
+    fn execute(&self, writer: &mut Writer, _: &mut Reader) -> Result<i32> {
+        let content = fs::read_to_string(&self.good)?;
+        let template = PathAwareValue::try_from(crate::rules::values::read_from(&content)?)?;
+
+        let observed = observe_resources(&template, &self.resource_type);
+        if observed.resources == 0 {
+            return Err(Error::IllegalArguments(format!(
+                "no resources of type {} found in {}",
+                self.resource_type, self.good
+            )));
+        }
+
+        print_suggestions(&self.resource_type, &observed, writer)?;
+
+        Ok(SUCCESS_STATUS_CODE)
+    }
+}
+
+/// what was observed at a query across the example resources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Observed {
+    /// literal values, as they are written in a clause
+    Values(BTreeSet<String>),
+    /// a value that can not be compared against a literal, e.g. an intrinsic function
+    Present,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Observations {
+    pub(crate) resources: usize,
+    pub(crate) queries: BTreeMap<String, (Observed, usize)>,
+}
+
+impl Observations {
+    fn observe(&mut self, query: String, observed: Observed) {
+        match self.queries.get_mut(&query) {
+            None => {
+                self.queries.insert(query, (observed, 1));
+            }
+            Some((existing, count)) => {
+                *count += 1;
+                if let (Observed::Values(values), Observed::Values(more)) =
+                    (&mut *existing, &observed)
+                {
+                    values.extend(more.iter().cloned());
+                } else {
+                    *existing = Observed::Present;
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn observe_resources(template: &PathAwareValue, resource_type: &str) -> Observations {
+    let mut observations = Observations::default();
+
+    let resources = match template {
+        PathAwareValue::Map((_, map)) => match map.values.get("Resources") {
+            Some(PathAwareValue::Map((_, resources))) => resources,
+            _ => return observations,
+        },
+        _ => return observations,
+    };
+
+    for resource in resources.values.values() {
+        let resource = match resource {
+            PathAwareValue::Map((_, resource)) => resource,
+            _ => continue,
+        };
+        match resource.values.get("Type") {
+            Some(PathAwareValue::String((_, each))) if each == resource_type => {}
+            _ => continue,
+        }
+
+        observations.resources += 1;
+        if let Some(properties) = resource.values.get("Properties") {
+            // a resource can observe the same query more than once through lists
+            let mut per_resource = Observations::default();
+            observe(properties, String::from("Properties"), &mut per_resource);
+            for (query, (observed, _)) in per_resource.queries {
+                observations.observe(query, observed);
+            }
+        }
+    }
+
+    observations
+}
+
+//
+// Walks the properties of a resource down to the values that can be compared against a literal.
+// Lists are expanded with [*], and values that are not literals, such as intrinsic functions,
+// empty structures, or keys that can not be written in a query, are only observed as present
+//
+fn observe(value: &PathAwareValue, query: String, observations: &mut Observations) {
+    match value {
+        PathAwareValue::Null(_) => {}
+        PathAwareValue::Map((_, map)) => {
+            let is_intrinsic = map.values.len() == 1
+                && map
+                    .values
+                    .keys()
+                    .all(|key| key == "Ref" || key.starts_with("Fn::"));
+            if is_intrinsic
+                || map.values.is_empty()
+                || !map.values.keys().all(|key| is_query_key(key))
+            {
+                observations.observe(query, Observed::Present);
+                return;
+            }
+            for (key, each) in &map.values {
+                observe(each, format!("{query}.{key}"), observations);
+            }
+        }
+        PathAwareValue::List((_, list)) => match list.is_empty() {
+            true => observations.observe(query, Observed::Present),
+            false => {
+                for each in list {
+                    observe(each, format!("{query}[*]"), observations);
+                }
+            }
+        },
+        _ => match literal(value) {
+            Some(literal) => {
+                observations.observe(query, Observed::Values(BTreeSet::from([literal])))
+            }
+            None => observations.observe(query, Observed::Present),
+        },
+    }
+}
+
+fn is_query_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn literal(value: &PathAwareValue) -> Option<String> {
+    match value {
+        PathAwareValue::String((_, value)) => match value.contains(['"', '\n', '\\']) {
+            true => None,
+            false => Some(format!("\"{value}\"")),
+        },
+        PathAwareValue::Bool((_, value)) => Some(value.to_string()),
+        PathAwareValue::Int((_, value)) => Some(value.to_string()),
+        PathAwareValue::Float((_, value)) => Some(format!("{value:?}")),
+        _ => None,
+    }
+}
+
+// Prints one named rule with a type block for the resource type, holding a clause per observed
+// query. A query with a single value is suggested with equality, several numbers as a range or
+// an IN list, and strings always with IN along with a placeholder for the other allowed values,
+// as string properties are usually one of a set of choices. Queries without a literal value are
+// suggested with EXISTS. Queries that are only set on some of the example resources are allowed to
+// be missing, or commented out when only their presence was observed. Using an RDS instance as
+// an example, the rule printed will be:
+//     #
+//     # Clauses suggested for AWS::RDS::DBInstance from a compliant example
+//     # Keep the clauses that express the policy, and replace or remove the <ALLOWED_VALUE> placeholders
+//     #
+//     rule aws_rds_dbinstance {
+//       AWS::RDS::DBInstance {
+//         Properties.AllocatedStorage == 20
+//         Properties.Engine IN ["mysql", "<ALLOWED_VALUE>"]
+//         Properties.KmsKeyId EXISTS
+//         Properties.StorageEncrypted == true
+//       }
+//     }
+fn print_suggestions(
+    resource_type: &str,
+    observed: &Observations,
+    writer: &mut Writer,
+) -> Result<()> {
+    let mut str = Builder::default();
+
+    str.append("#\n");
+    str.append(format!(
+        "# Clauses suggested for {resource_type} from a compliant example\n"
+    ));
+    str.append(format!(
+        "# Keep the clauses that express the policy, and replace or remove the {ALLOWED_VALUE_PLACEHOLDER} placeholders\n"
+    ));
+    str.append("#\n");
+    str.append(format!(
+        "rule {} {{\n",
+        resource_type.replace("::", "_").to_lowercase()
+    ));
+    str.append(format!("  {resource_type} {{\n"));
+
+    for (query, (each, count)) in &observed.queries {
+        let clause = suggest_clause(query, each);
+        if *count == observed.resources {
+            str.append(format!("    {clause}\n"));
+            continue;
+        }
+
+        str.append(format!(
+            "    # {query} is only set on {count} of the {} example resources\n",
+            observed.resources
+        ));
+        match each {
+            Observed::Present => str.append(format!("    # {clause}\n")),
+            Observed::Values(_) => str.append(format!("    {query} !EXISTS or {clause}\n")),
+        }
+    }
+
+    str.append("  }\n");
+    str.append("}\n");
+
+    let suggested_rules = str.string().unwrap();
+
+    write_generated_rules(&suggested_rules, writer)
+}
+
+pub(crate) fn suggest_clause(query: &str, observed: &Observed) -> String {
+    let values = match observed {
+        Observed::Present => return format!("{query} EXISTS"),
+        Observed::Values(values) => values,
+    };
+
+    if values.iter().all(|value| value.starts_with('"')) {
+        let placeholder = format!("\"{ALLOWED_VALUE_PLACEHOLDER}\"");
+        return format!(
+            "{query} IN [{}]",
+            values
+                .iter()
+                .chain(std::iter::once(&placeholder))
+                .join(", ")
+        );
+    }
+
+    if values.len() == 1 {
+        return format!("{query} == {}", values.iter().next().unwrap());
+    }
+
+    match numeric_range(values) {
+        Some(range) => format!("{query} IN {range}"),
+        None => format!("{query} IN [{}]", values.iter().join(", ")),
+    }
+}
+
+#[cfg(test)]
+#[path = "suggest_tests.rs"]
+mod suggest_tests;
@@ -0,0 +1,105 @@
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use crate::commands::suggest::{observe_resources, suggest_clause, Observed};
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::values::read_from;
+use pretty_assertions::assert_eq;
+
+fn values(values: &[&str]) -> Observed {
+    Observed::Values(values.iter().map(|value| value.to_string()).collect())
+}
+
+#[test]
+fn test_observe_resources() -> crate::rules::Result<()> {
+    let template = PathAwareValue::try_from(read_from(
+        r#"
+        Resources:
+          bucket:
+            Type: AWS::S3::Bucket
+            Properties:
+              BucketName: !Sub "${AWS::StackName}-logs"
+              VersioningConfiguration:
+                Status: Enabled
+              LifecycleConfiguration:
+                Rules:
+                  - ExpirationInDays: 30
+                  - ExpirationInDays: 90
+              Tags: []
+          other:
+            Type: AWS::S3::Bucket
+            Properties:
+              VersioningConfiguration:
+                Status: Suspended
+          queue:
+            Type: AWS::SQS::Queue
+            Properties:
+              DelaySeconds: 5
+        "#,
+    )?)?;
+
+    let observed = observe_resources(&template, "AWS::S3::Bucket");
+    assert_eq!(observed.resources, 2);
+    assert_eq!(
+        observed.queries.keys().collect::<Vec<_>>(),
+        vec![
+            "Properties.BucketName",
+            "Properties.LifecycleConfiguration.Rules[*].ExpirationInDays",
+            "Properties.Tags",
+            "Properties.VersioningConfiguration.Status",
+        ]
+    );
+    assert_eq!(
+        observed.queries["Properties.BucketName"],
+        (Observed::Present, 1)
+    );
+    assert_eq!(
+        observed.queries["Properties.LifecycleConfiguration.Rules[*].ExpirationInDays"],
+        (values(&["30", "90"]), 1)
+    );
+    assert_eq!(
+        observed.queries["Properties.VersioningConfiguration.Status"],
+        (values(&["\"Enabled\"", "\"Suspended\""]), 2)
+    );
+
+    assert_eq!(
+        observe_resources(&template, "AWS::EC2::Volume").resources,
+        0
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_suggest_clause() {
+    assert_eq!(
+        suggest_clause("Properties.KmsKeyId", &Observed::Present),
+        "Properties.KmsKeyId EXISTS"
+    );
+    assert_eq!(
+        suggest_clause("Properties.MultiAZ", &values(&["true"])),
+        "Properties.MultiAZ == true"
+    );
+    assert_eq!(
+        suggest_clause("Properties.Size", &values(&["100", "20"])),
+        "Properties.Size IN r[20, 100]"
+    );
+    assert_eq!(
+        suggest_clause("Properties.Flags", &values(&["true", "false"])),
+        "Properties.Flags IN [false, true]"
+    );
+    assert_eq!(
+        suggest_clause("Properties.Engine", &values(&["\"mysql\""])),
+        "Properties.Engine IN [\"mysql\", \"<ALLOWED_VALUE>\"]"
+    );
+    assert_eq!(
+        suggest_clause(
+            "Properties.Engine",
+            &Observed::Values(BTreeSet::from([
+                String::from("\"postgres\""),
+                String::from("\"mysql\"")
+            ]))
+        ),
+        "Properties.Engine IN [\"mysql\", \"postgres\", \"<ALLOWED_VALUE>\"]"
+    );
+}
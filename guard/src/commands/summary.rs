@@ -0,0 +1,179 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::rules::eval_context::{simplified_json_from_root, EventRecord};
+use crate::rules::exprs::Severity;
+use crate::rules::messages::MessageCatalog;
+use crate::rules::{NamedStatus, RecordType, Result, Status};
+
+/// severity the findings of rules are reported with, matches the level used for SARIF results
+pub const ERROR_SEVERITY: &str = "error";
+/// severity the findings of rules annotated with `@severity(warning)` are reported with
+pub const WARNING_SEVERITY: &str = "warning";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+/// .
+/// Statistics gathered while running the `Validate` command, so embedders can build
+/// dashboards without parsing what was written out to the `Writer`
+pub struct Summary {
+    /// number of data files the rules were evaluated against
+    pub files_evaluated: usize,
+    /// number of rules that passed, counted once per data file evaluated
+    pub rules_passed: usize,
+    /// number of rules that failed, counted once per data file evaluated
+    pub rules_failed: usize,
+    /// number of warning rules that failed, counted once per data file evaluated
+    pub rules_warned: usize,
+    /// number of rules that were skipped, counted once per data file evaluated
+    pub rules_skipped: usize,
+    /// number of rules files that could not be parsed
+    pub rule_files_errored: usize,
+    /// number of times each rule failed, warning rules included, counted once per data file
+    /// evaluated and keyed by the name of the rule
+    pub failures_by_rule: BTreeMap<String, usize>,
+    /// number of findings (individual failure messages) reported, keyed by severity
+    pub findings_by_severity: BTreeMap<String, usize>,
+    /// wall clock time taken by the run
+    pub duration: Duration,
+    /// time spent evaluating each data file, across every rules file
+    pub file_durations: BTreeMap<String, Duration>,
+    /// time spent evaluating each rule, across every data file, keyed by the rules file and then
+    /// by the name of the rule
+    pub rule_durations: BTreeMap<String, BTreeMap<String, Duration>>,
+}
+
+impl Summary {
+    /// updates the counters from the root record of a single rules file evaluated against a single data file
+    pub(crate) fn record(&mut self, root_record: &EventRecord<'_>) -> Result<()> {
+        for each in &root_record.children {
+            if let Some(RecordType::RuleCheck(NamedStatus {
+                name,
+                status,
+                severity,
+                ..
+            })) = &each.container
+            {
+                if *status == Status::FAIL {
+                    *self.failures_by_rule.entry(name.to_string()).or_default() += 1;
+                }
+                match (status, severity) {
+                    (Status::PASS, _) => self.rules_passed += 1,
+                    (Status::FAIL, Severity::Error) => self.rules_failed += 1,
+                    (Status::FAIL, Severity::Warning) => self.rules_warned += 1,
+                    (Status::SKIP, _) => self.rules_skipped += 1,
+                }
+            }
+        }
+
+        // only the findings are counted, the wording of their messages does not matter
+        for failure in
+            simplified_json_from_root(root_record, &MessageCatalog::default())?.not_compliant
+        {
+            let findings = failure.get_message().len();
+            if findings > 0 {
+                let severity = match failure.severity() {
+                    Severity::Error => ERROR_SEVERITY,
+                    Severity::Warning => WARNING_SEVERITY,
+                };
+                *self
+                    .findings_by_severity
+                    .entry(severity.to_string())
+                    .or_default() += findings;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// adds up how long a single rules file took to evaluate against a single data file, and
+    /// how long each of its rules took
+    pub(crate) fn record_durations(
+        &mut self,
+        rules_file: &str,
+        data_file: &str,
+        duration: Duration,
+        rules: &[(&str, Duration)],
+    ) {
+        *self
+            .file_durations
+            .entry(data_file.to_string())
+            .or_default() += duration;
+
+        let rule_durations = self
+            .rule_durations
+            .entry(rules_file.to_string())
+            .or_default();
+        for (rule, duration) in rules {
+            *rule_durations.entry(rule.to_string()).or_default() += *duration;
+        }
+    }
+
+    /// a table of the data files and one of the rules, each sorted by the time spent evaluating
+    /// them with the slowest first, see `--timings`
+    pub(crate) fn timings_table(&self) -> String {
+        let mut files = self
+            .file_durations
+            .iter()
+            .map(|(file, duration)| (*duration, vec![file.as_str()]))
+            .collect::<Vec<_>>();
+        let mut rules = self
+            .rule_durations
+            .iter()
+            .flat_map(|(rules_file, rules)| {
+                rules.iter().map(move |(rule, duration)| {
+                    (*duration, vec![rule.as_str(), rules_file.as_str()])
+                })
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{}\n{}",
+            table(&["Data file"], &mut files),
+            table(&["Rule", "Rules file"], &mut rules)
+        )
+    }
+}
+
+/// the rows sorted by duration, slowest first, with the duration as the last column and every
+/// column padded to its widest value
+fn table(headers: &[&str], rows: &mut [(Duration, Vec<&str>)]) -> String {
+    rows.sort_by(|(left, left_row), (right, right_row)| {
+        right.cmp(left).then_with(|| left_row.cmp(right_row))
+    });
+
+    let rows = std::iter::once(
+        headers
+            .iter()
+            .map(|header| header.to_string())
+            .chain(std::iter::once(String::from("Time")))
+            .collect::<Vec<_>>(),
+    )
+    .chain(rows.iter().map(|(duration, row)| {
+        row.iter()
+            .map(|cell| cell.to_string())
+            .chain(std::iter::once(format!("{duration:.3?}")))
+            .collect()
+    }))
+    .collect::<Vec<_>>();
+
+    let widths = (0..=headers.len())
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
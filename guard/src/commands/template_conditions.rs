@@ -0,0 +1,244 @@
+use indexmap::IndexMap;
+use std::fmt::{Display, Formatter};
+
+use crate::commands::template_parameters::{parameter_overrides, template_parameter_values};
+use crate::commands::validate::DataFile;
+use crate::rules::path_value::{MapValue, PathAwareValue};
+
+const CONDITIONS: &str = "Conditions";
+const CONDITION: &str = "Condition";
+const RESOURCES: &str = "Resources";
+
+/// what became of a resource carrying a `Condition` when the conditions of its template were
+/// evaluated. Resources are only pruned when their condition evaluates to false, `value` is
+/// `None` when it could not be evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PruningDecision {
+    pub(crate) file: String,
+    pub(crate) logical_id: String,
+    pub(crate) condition: String,
+    pub(crate) value: Option<bool>,
+}
+
+impl PruningDecision {
+    pub(crate) fn is_pruned(&self) -> bool {
+        self.value == Some(false)
+    }
+}
+
+impl Display for PruningDecision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.value {
+            Some(false) => write!(
+                f,
+                "{}: pruned resource {}, condition {} is false",
+                self.file, self.logical_id, self.condition
+            ),
+            Some(true) => write!(
+                f,
+                "{}: kept resource {}, condition {} is true",
+                self.file, self.logical_id, self.condition
+            ),
+            None => write!(
+                f,
+                "{}: kept resource {}, condition {} could not be evaluated",
+                self.file, self.logical_id, self.condition
+            ),
+        }
+    }
+}
+
+/// evaluates the `Conditions` of a template against the effective values of its parameters.
+/// `Ref`s to pseudo parameters, to parameters without a value and intrinsic functions other than
+/// `Fn::Equals`, `Fn::And`, `Fn::Or`, `Fn::Not` and `Condition` cannot be evaluated, and neither
+/// can any condition depending on them
+pub(crate) fn evaluate_conditions(
+    template: &PathAwareValue,
+    values: &IndexMap<String, PathAwareValue>,
+) -> IndexMap<String, Option<bool>> {
+    let definitions = match template {
+        PathAwareValue::Map((_, map)) => match map.values.get(CONDITIONS) {
+            Some(PathAwareValue::Map((_, conditions))) => &conditions.values,
+            _ => return IndexMap::new(),
+        },
+        _ => return IndexMap::new(),
+    };
+
+    let evaluator = Conditions {
+        definitions,
+        values,
+    };
+    definitions
+        .keys()
+        .map(|name| (name.clone(), evaluator.condition(name, &mut vec![])))
+        .collect()
+}
+
+struct Conditions<'a> {
+    definitions: &'a IndexMap<String, PathAwareValue>,
+    values: &'a IndexMap<String, PathAwareValue>,
+}
+
+impl<'a> Conditions<'a> {
+    /// `visiting` holds the conditions being evaluated, a condition referring back to one of
+    /// them cannot be evaluated
+    fn condition(&self, name: &'a str, visiting: &mut Vec<&'a str>) -> Option<bool> {
+        if visiting.contains(&name) {
+            return None;
+        }
+
+        let (name, definition) = self.definitions.get_key_value(name)?;
+        visiting.push(name);
+        let value = self.expression(definition, visiting);
+        visiting.pop();
+        value
+    }
+
+    fn expression(
+        &self,
+        expression: &'a PathAwareValue,
+        visiting: &mut Vec<&'a str>,
+    ) -> Option<bool> {
+        let (function, arguments) = match expression {
+            PathAwareValue::Bool((_, value)) => return Some(*value),
+            PathAwareValue::Map((_, map)) if map.values.len() == 1 => map.values.iter().next()?,
+            _ => return None,
+        };
+
+        match (function.as_str(), arguments) {
+            (CONDITION, PathAwareValue::String((_, name))) => self.condition(name, visiting),
+
+            ("Fn::Not", PathAwareValue::List((_, arguments))) if arguments.len() == 1 => {
+                self.expression(&arguments[0], visiting).map(|value| !value)
+            }
+
+            ("Fn::And", PathAwareValue::List((_, arguments))) => {
+                let mut result = Some(true);
+                for each in arguments {
+                    match self.expression(each, visiting) {
+                        Some(false) => return Some(false),
+                        Some(true) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            }
+
+            ("Fn::Or", PathAwareValue::List((_, arguments))) => {
+                let mut result = Some(false);
+                for each in arguments {
+                    match self.expression(each, visiting) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            }
+
+            ("Fn::Equals", PathAwareValue::List((_, arguments))) if arguments.len() == 2 => {
+                Some(self.operand(&arguments[0])? == self.operand(&arguments[1])?)
+            }
+
+            _ => None,
+        }
+    }
+
+    /// the value of an `Fn::Equals` argument, compared as a string the way CloudFormation does
+    fn operand(&self, value: &PathAwareValue) -> Option<String> {
+        match value {
+            PathAwareValue::Map((_, map)) if map.values.len() == 1 => match map.values.get("Ref") {
+                Some(PathAwareValue::String((_, name))) => scalar(self.values.get(name)?),
+                _ => None,
+            },
+            other => scalar(other),
+        }
+    }
+}
+
+fn scalar(value: &PathAwareValue) -> Option<String> {
+    match value {
+        PathAwareValue::String((_, value)) => Some(value.clone()),
+        PathAwareValue::Int((_, value)) => Some(value.to_string()),
+        PathAwareValue::Float((_, value)) => Some(value.to_string()),
+        PathAwareValue::Bool((_, value)) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// removes the resources whose `Condition` evaluates to false from a template, returning a
+/// decision for each resource that carries a condition
+pub(crate) fn prune_resources(
+    template: &mut PathAwareValue,
+    values: &IndexMap<String, PathAwareValue>,
+    file: &str,
+) -> Vec<PruningDecision> {
+    let conditions = evaluate_conditions(template, values);
+    let resources = match template {
+        PathAwareValue::Map((_, map)) => match map.values.get_mut(RESOURCES) {
+            Some(PathAwareValue::Map((_, resources))) => resources,
+            _ => return vec![],
+        },
+        _ => return vec![],
+    };
+
+    let decisions = resources
+        .values
+        .iter()
+        .filter_map(|(logical_id, resource)| match resource {
+            PathAwareValue::Map((_, resource)) => match resource.values.get(CONDITION) {
+                Some(PathAwareValue::String((_, condition))) => Some(PruningDecision {
+                    file: file.to_string(),
+                    logical_id: logical_id.clone(),
+                    condition: condition.clone(),
+                    value: conditions.get(condition).copied().flatten(),
+                }),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let MapValue { keys, values } = resources;
+    values.retain(|logical_id, _| {
+        !decisions
+            .iter()
+            .any(|decision| decision.is_pruned() && &decision.logical_id == logical_id)
+    });
+    keys.retain(
+        |key| matches!(key, PathAwareValue::String((_, name)) if values.contains_key(name)),
+    );
+
+    decisions
+}
+
+/// prunes the resources of every data file whose conditions evaluate to false, using the same
+/// parameter values `--resolve-parameters` would. The input parameters are left untouched
+pub(crate) fn prune_conditional_resources(
+    data_files: Vec<DataFile>,
+    input_params: &Option<PathAwareValue>,
+) -> (Vec<DataFile>, Vec<PruningDecision>) {
+    let input_overrides = input_params
+        .as_ref()
+        .map_or_else(IndexMap::new, parameter_overrides);
+
+    let mut decisions = vec![];
+    let data_files = data_files
+        .into_iter()
+        .map(|mut data_file| {
+            let values = template_parameter_values(&data_file, &input_overrides);
+            decisions.extend(prune_resources(
+                &mut data_file.path_value,
+                &values,
+                &data_file.name,
+            ));
+            data_file
+        })
+        .collect();
+
+    (data_files, decisions)
+}
+
+#[cfg(test)]
+#[path = "template_conditions_tests.rs"]
+mod template_conditions_tests;
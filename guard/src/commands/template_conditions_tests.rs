@@ -0,0 +1,140 @@
+use super::*;
+use crate::commands::validate::build_data_file;
+use pretty_assertions::assert_eq;
+use std::convert::TryFrom;
+
+fn value(yaml: &str) -> PathAwareValue {
+    PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(yaml).unwrap()).unwrap()
+}
+
+const TEMPLATE: &str = r#"
+Parameters:
+  Environment:
+    Type: String
+    Default: dev
+  Replicas:
+    Type: Number
+    Default: "2"
+Conditions:
+  IsProd: { "Fn::Equals": [ { Ref: Environment }, prod ] }
+  IsDev: { "Fn::Not": [ { Condition: IsProd } ] }
+  IsReplicated: { "Fn::Equals": [ { Ref: Replicas }, 2 ] }
+  IsProdOrReplicated: { "Fn::Or": [ { Condition: IsProd }, { Condition: IsReplicated } ] }
+  IsProdInUsEast: { "Fn::And": [ { Condition: IsProd }, { "Fn::Equals": [ { Ref: AWS::Region }, us-east-1 ] } ] }
+  IsDevInUsEast: { "Fn::And": [ { Condition: IsDev }, { "Fn::Equals": [ { Ref: AWS::Region }, us-east-1 ] } ] }
+  Loops: { "Fn::Not": [ { Condition: Loops } ] }
+Resources:
+  bucket:
+    Type: AWS::S3::Bucket
+  prodBucket:
+    Type: AWS::S3::Bucket
+    Condition: IsProd
+  devBucket:
+    Type: AWS::S3::Bucket
+    Condition: IsDev
+  regionalBucket:
+    Type: AWS::S3::Bucket
+    Condition: IsDevInUsEast
+"#;
+
+#[test]
+fn test_evaluate_conditions() {
+    let template = value(TEMPLATE);
+    let values = effective_values(&template, r#"{"Parameters": {}}"#);
+
+    assert_eq!(
+        vec![
+            (String::from("IsProd"), Some(false)),
+            (String::from("IsDev"), Some(true)),
+            (String::from("IsReplicated"), Some(true)),
+            (String::from("IsProdOrReplicated"), Some(true)),
+            // false regardless of the region
+            (String::from("IsProdInUsEast"), Some(false)),
+            (String::from("IsDevInUsEast"), None),
+            (String::from("Loops"), None),
+        ],
+        evaluate_conditions(&template, &values)
+            .into_iter()
+            .collect::<Vec<_>>()
+    );
+
+    let values = effective_values(&template, r#"{"Parameters": {"Environment": "prod"}}"#);
+    let conditions = evaluate_conditions(&template, &values);
+    assert_eq!(Some(true), conditions["IsProd"]);
+    assert_eq!(Some(false), conditions["IsDev"]);
+    assert_eq!(None, conditions["IsProdInUsEast"]);
+}
+
+fn effective_values(
+    template: &PathAwareValue,
+    overrides: &str,
+) -> IndexMap<String, PathAwareValue> {
+    crate::commands::template_parameters::effective_parameter_values(
+        template,
+        &parameter_overrides(&value(overrides)),
+    )
+}
+
+#[test]
+fn test_prune_conditional_resources() {
+    let data_file = build_data_file(TEMPLATE.to_string(), String::from("template.yaml")).unwrap();
+    let input_params = Some(value(r#"{"Parameters": {"Environment": "prod"}}"#));
+
+    let (data_files, decisions) = prune_conditional_resources(vec![data_file], &input_params);
+
+    let decision = |logical_id: &str, condition: &str, value: Option<bool>| PruningDecision {
+        file: String::from("template.yaml"),
+        logical_id: logical_id.to_string(),
+        condition: condition.to_string(),
+        value,
+    };
+    assert_eq!(
+        vec![
+            decision("prodBucket", "IsProd", Some(true)),
+            decision("devBucket", "IsDev", Some(false)),
+            decision("regionalBucket", "IsDevInUsEast", Some(false)),
+        ],
+        decisions
+    );
+    assert_eq!(
+        "template.yaml: pruned resource devBucket, condition IsDev is false",
+        decisions[1].to_string()
+    );
+
+    match &data_files[0].path_value {
+        PathAwareValue::Map((_, map)) => match &map.values[RESOURCES] {
+            PathAwareValue::Map((_, resources)) => {
+                assert_eq!(
+                    vec!["bucket", "prodBucket"],
+                    resources.values.keys().collect::<Vec<_>>()
+                );
+                assert_eq!(2, resources.keys.len());
+            }
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_prune_conditional_resources_keeps_unknown_conditions() {
+    let data_file = build_data_file(TEMPLATE.to_string(), String::from("template.yaml")).unwrap();
+    let original = data_file.clone();
+
+    let (data_files, decisions) = prune_conditional_resources(vec![data_file], &None);
+
+    assert_eq!(
+        "template.yaml: kept resource regionalBucket, condition IsDevInUsEast could not be evaluated",
+        decisions[2].to_string()
+    );
+    // only prodBucket is pruned with the default Environment
+    assert_eq!(
+        vec!["prodBucket"],
+        decisions
+            .iter()
+            .filter(|decision| decision.is_pruned())
+            .map(|decision| decision.logical_id.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_ne!(original, data_files[0]);
+}
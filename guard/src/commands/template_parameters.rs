@@ -0,0 +1,268 @@
+use indexmap::IndexMap;
+use std::rc::Rc;
+
+use crate::commands::validate::DataFile;
+use crate::rules::path_value::{MapValue, Path, PathAwareValue};
+
+const PARAMETERS: &str = "Parameters";
+
+/// the parameter values given for a template, either as a `{"Parameters": {"Name": "value"}}`
+/// template configuration or as a list of `{"ParameterKey": "Name", "ParameterValue": "value"}`
+/// like the CLI takes. Other shapes do not provide any values
+pub(crate) fn parameter_overrides(value: &PathAwareValue) -> IndexMap<String, PathAwareValue> {
+    let mut overrides = IndexMap::new();
+    match value {
+        PathAwareValue::Map((_, map)) => {
+            if let Some(PathAwareValue::Map((_, parameters))) = map.values.get(PARAMETERS) {
+                for (name, value) in &parameters.values {
+                    overrides.insert(name.clone(), value.clone());
+                }
+            }
+        }
+
+        PathAwareValue::List((_, list)) => {
+            for each in list {
+                if let PathAwareValue::Map((_, map)) = each {
+                    if let (Some(PathAwareValue::String((_, name))), Some(value)) = (
+                        map.values.get("ParameterKey"),
+                        map.values.get("ParameterValue"),
+                    ) {
+                        overrides.insert(name.clone(), value.clone());
+                    }
+                }
+            }
+        }
+
+        _ => {}
+    }
+    overrides
+}
+
+/// the effective value of each parameter a template declares, its override when one is given,
+/// otherwise its `Default`. Parameters with neither are left out. String values of `Number`
+/// parameters are converted to numbers, and those of list parameters are split on commas
+pub(crate) fn effective_parameter_values(
+    template: &PathAwareValue,
+    overrides: &IndexMap<String, PathAwareValue>,
+) -> IndexMap<String, PathAwareValue> {
+    let mut values = IndexMap::new();
+    let parameters = match template {
+        PathAwareValue::Map((_, map)) => match map.values.get(PARAMETERS) {
+            Some(PathAwareValue::Map((_, parameters))) => parameters,
+            _ => return values,
+        },
+        _ => return values,
+    };
+
+    for (name, declaration) in &parameters.values {
+        let (parameter_type, default) = match declaration {
+            PathAwareValue::Map((_, declaration)) => (
+                match declaration.values.get("Type") {
+                    Some(PathAwareValue::String((_, parameter_type))) => parameter_type.as_str(),
+                    _ => "String",
+                },
+                declaration.values.get("Default"),
+            ),
+            _ => ("String", None),
+        };
+
+        if let Some(value) = overrides.get(name).or(default) {
+            values.insert(name.clone(), typed_value(value, parameter_type));
+        }
+    }
+    values
+}
+
+fn typed_value(value: &PathAwareValue, parameter_type: &str) -> PathAwareValue {
+    let (path, text) = match value {
+        PathAwareValue::String((path, text)) => (path, text),
+        _ => return value.clone(),
+    };
+
+    if parameter_type == "Number" {
+        if let Ok(number) = text.trim().parse::<i64>() {
+            return PathAwareValue::Int((path.clone(), number));
+        }
+        if let Ok(number) = text.trim().parse::<f64>() {
+            return PathAwareValue::Float((path.clone(), number));
+        }
+    } else if parameter_type == "CommaDelimitedList" || parameter_type.starts_with("List<") {
+        return PathAwareValue::List((
+            path.clone(),
+            text.split(',')
+                .enumerate()
+                .map(|(idx, each)| {
+                    PathAwareValue::String((path.extend_usize(idx), each.trim().to_string()))
+                })
+                .collect(),
+        ));
+    }
+    value.clone()
+}
+
+/// replaces every `{"Ref": "Name"}` outside of the `Parameters` section with the value of the
+/// parameter it refers to. Refs to pseudo parameters or parameters without a value are kept
+pub(crate) fn resolve_refs(
+    template: PathAwareValue,
+    values: &IndexMap<String, PathAwareValue>,
+) -> PathAwareValue {
+    match template {
+        PathAwareValue::Map((path, map)) => {
+            let MapValue { keys, values: map } = map;
+            let map = map
+                .into_iter()
+                .map(|(key, value)| match key == PARAMETERS {
+                    true => (key, value),
+                    false => (key, substitute(value, values)),
+                })
+                .collect();
+            PathAwareValue::Map((path, MapValue { keys, values: map }))
+        }
+        other => other,
+    }
+}
+
+fn substitute(value: PathAwareValue, values: &IndexMap<String, PathAwareValue>) -> PathAwareValue {
+    match value {
+        PathAwareValue::Map((path, map)) => {
+            if map.values.len() == 1 {
+                if let Some(PathAwareValue::String((_, name))) = map.values.get("Ref") {
+                    if let Some(value) = values.get(name) {
+                        return relocate(value, &path);
+                    }
+                }
+            }
+
+            let MapValue { keys, values: map } = map;
+            let map = map
+                .into_iter()
+                .map(|(key, value)| (key, substitute(value, values)))
+                .collect();
+            PathAwareValue::Map((path, MapValue { keys, values: map }))
+        }
+
+        PathAwareValue::List((path, list)) => PathAwareValue::List((
+            path,
+            list.into_iter()
+                .map(|each| substitute(each, values))
+                .collect(),
+        )),
+
+        other => other,
+    }
+}
+
+/// a copy of the value placed at the path of the Ref it replaces, so reports point into the
+/// template rather than at the parameter
+pub(crate) fn relocate(value: &PathAwareValue, path: &Path) -> PathAwareValue {
+    let path = path.clone();
+    match value {
+        PathAwareValue::Null(_) => PathAwareValue::Null(path),
+        PathAwareValue::String((_, v)) => PathAwareValue::String((path, v.clone())),
+        PathAwareValue::Regex((_, v)) => PathAwareValue::Regex((path, v.clone())),
+        PathAwareValue::Bool((_, v)) => PathAwareValue::Bool((path, *v)),
+        PathAwareValue::Int((_, v)) => PathAwareValue::Int((path, *v)),
+        PathAwareValue::Float((_, v)) => PathAwareValue::Float((path, *v)),
+        PathAwareValue::Char((_, v)) => PathAwareValue::Char((path, *v)),
+        PathAwareValue::RangeInt((_, v)) => PathAwareValue::RangeInt((path, v.clone())),
+        PathAwareValue::RangeFloat((_, v)) => PathAwareValue::RangeFloat((path, v.clone())),
+        PathAwareValue::RangeChar((_, v)) => PathAwareValue::RangeChar((path, v.clone())),
+        PathAwareValue::List((_, list)) => PathAwareValue::List((
+            path.clone(),
+            list.iter()
+                .enumerate()
+                .map(|(idx, each)| relocate(each, &path.extend_usize(idx)))
+                .collect(),
+        )),
+        PathAwareValue::Map((_, map)) => PathAwareValue::Map((
+            path.clone(),
+            MapValue {
+                keys: map
+                    .values
+                    .keys()
+                    .map(|key| PathAwareValue::String((path.extend_str(key), key.clone())))
+                    .collect(),
+                values: map
+                    .values
+                    .iter()
+                    .map(|(key, each)| (key.clone(), relocate(each, &path.extend_str(key))))
+                    .collect(),
+            },
+        )),
+    }
+}
+
+/// resolves the parameters of every data file that is a template. Parameter values from the
+/// input parameters are taken out of them, so they are not merged into the templates, and those
+/// from the parameters file paired with a template take precedence over them
+pub(crate) fn resolve_parameters(
+    data_files: Vec<DataFile>,
+    input_params: Option<PathAwareValue>,
+) -> (Vec<DataFile>, Option<PathAwareValue>) {
+    let (input_overrides, input_params) = match input_params {
+        Some(PathAwareValue::Map((path, mut map))) if map.values.contains_key(PARAMETERS) => {
+            let overrides = parameter_overrides(&PathAwareValue::Map((path.clone(), map.clone())));
+            map.values.shift_remove(PARAMETERS);
+            map.keys.retain(
+                |key| !matches!(key, PathAwareValue::String((_, name)) if name == PARAMETERS),
+            );
+            let rest = match map.values.is_empty() {
+                true => None,
+                false => Some(PathAwareValue::Map((path, map))),
+            };
+            (overrides, rest)
+        }
+        Some(list @ PathAwareValue::List(_)) => (parameter_overrides(&list), None),
+        other => (IndexMap::new(), other),
+    };
+
+    let data_files = data_files
+        .into_iter()
+        .map(|mut data_file| {
+            if is_template(&data_file.path_value) {
+                let values = template_parameter_values(&data_file, &input_overrides);
+                data_file.path_value = resolve_refs(data_file.path_value, &values);
+                data_file.parameter_values = Some(Rc::new(PathAwareValue::Map((
+                    Path::root(),
+                    MapValue {
+                        keys: values
+                            .keys()
+                            .map(|name| {
+                                PathAwareValue::String((
+                                    Path::root().extend_str(name),
+                                    name.clone(),
+                                ))
+                            })
+                            .collect(),
+                        values,
+                    },
+                ))));
+            }
+            data_file
+        })
+        .collect();
+
+    (data_files, input_params)
+}
+
+/// the effective parameter values of a data file, the parameters file paired with it taking
+/// precedence over the values from the input parameters
+pub(crate) fn template_parameter_values(
+    data_file: &DataFile,
+    input_overrides: &IndexMap<String, PathAwareValue>,
+) -> IndexMap<String, PathAwareValue> {
+    let mut overrides = input_overrides.clone();
+    if let Some(parameters_file) = &data_file.parameters_file {
+        overrides.extend(parameter_overrides(parameters_file));
+    }
+
+    effective_parameter_values(&data_file.path_value, &overrides)
+}
+
+fn is_template(value: &PathAwareValue) -> bool {
+    matches!(value, PathAwareValue::Map((_, map)) if map.values.contains_key(PARAMETERS))
+}
+
+#[cfg(test)]
+#[path = "template_parameters_tests.rs"]
+mod template_parameters_tests;
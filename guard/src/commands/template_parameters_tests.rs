@@ -0,0 +1,146 @@
+use super::*;
+use crate::commands::validate::build_data_file;
+use pretty_assertions::assert_eq;
+use std::convert::TryFrom;
+
+fn value(yaml: &str) -> PathAwareValue {
+    PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(yaml).unwrap()).unwrap()
+}
+
+const TEMPLATE: &str = r#"
+Parameters:
+  Environment:
+    Type: String
+    Default: dev
+  RetentionDays:
+    Type: Number
+    Default: "7"
+  Subnets:
+    Type: List<AWS::EC2::Subnet::Id>
+  KeyName:
+    Type: String
+Resources:
+  logs:
+    Type: AWS::Logs::LogGroup
+    Properties:
+      RetentionInDays: { Ref: RetentionDays }
+      Tags:
+        - Key: env
+          Value: { Ref: Environment }
+        - Key: key
+          Value: { Ref: KeyName }
+        - Key: region
+          Value: { Ref: AWS::Region }
+"#;
+
+#[test]
+fn test_parameter_overrides() {
+    let configuration = value(r#"{"Parameters": {"Environment": "prod"}}"#);
+    let cli = value(r#"[{"ParameterKey": "Environment", "ParameterValue": "prod"}]"#);
+    for each in [configuration, cli] {
+        let overrides = parameter_overrides(&each);
+        assert_eq!(vec!["Environment"], overrides.keys().collect::<Vec<_>>());
+        assert!(matches!(&overrides["Environment"], PathAwareValue::String((_, v)) if v == "prod"));
+    }
+
+    assert!(parameter_overrides(&value(r#"{"Environment": "prod"}"#)).is_empty());
+}
+
+#[test]
+fn test_effective_parameter_values() {
+    let template = value(TEMPLATE);
+    let overrides = parameter_overrides(&value(
+        r#"{"Parameters": {"Environment": "prod", "Subnets": "subnet-1, subnet-2"}}"#,
+    ));
+
+    let values = effective_parameter_values(&template, &overrides);
+    assert_eq!(
+        vec!["Environment", "RetentionDays", "Subnets"],
+        values.keys().collect::<Vec<_>>()
+    );
+    assert!(matches!(&values["Environment"], PathAwareValue::String((_, v)) if v == "prod"));
+    assert!(matches!(
+        &values["RetentionDays"],
+        PathAwareValue::Int((_, 7))
+    ));
+    match &values["Subnets"] {
+        PathAwareValue::List((_, subnets)) => assert_eq!(
+            vec!["subnet-1", "subnet-2"],
+            subnets
+                .iter()
+                .map(|each| match each {
+                    PathAwareValue::String((_, v)) => v.as_str(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        ),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_resolve_parameters() {
+    let data_file = build_data_file(TEMPLATE.to_string(), String::from("template.yaml")).unwrap();
+    let input_params = value(
+        r#"
+        Parameters:
+          Environment: prod
+        Extra:
+          Owner: platform
+        "#,
+    );
+
+    let (data_files, input_params) = resolve_parameters(vec![data_file], Some(input_params));
+
+    // the parameter values are not merged into the template, the rest of the input parameters are
+    assert_eq!(Some(value("Extra: { Owner: platform }")), input_params);
+
+    let resolved = &data_files[0].path_value;
+    let properties = value(
+        r#"
+        RetentionInDays: 7
+        Tags:
+          - Key: env
+            Value: prod
+          - Key: key
+            Value: { Ref: KeyName }
+          - Key: region
+            Value: { Ref: AWS::Region }
+        "#,
+    );
+    let selected = ["Resources", "logs", "Properties"]
+        .iter()
+        .fold(resolved, |value, key| match value {
+            PathAwareValue::Map((_, map)) => &map.values[*key],
+            _ => unreachable!(),
+        });
+    assert_eq!(&properties, selected);
+
+    // the value takes the place of the Ref it replaced
+    match selected {
+        PathAwareValue::Map((_, map)) => assert_eq!(
+            "/Resources/logs/Properties/RetentionInDays",
+            map.values["RetentionInDays"].self_path().0
+        ),
+        _ => unreachable!(),
+    }
+
+    assert_eq!(
+        Some(Rc::new(value("{ Environment: prod, RetentionDays: 7 }"))),
+        data_files[0].parameter_values
+    );
+}
+
+#[test]
+fn test_resolve_parameters_ignores_other_data() {
+    let data_file = build_data_file(
+        String::from(r#"{"Resources": {"bucket": {"Ref": "Environment"}}}"#),
+        String::from("template.json"),
+    )
+    .unwrap();
+    let original = data_file.clone();
+
+    let (data_files, input_params) = resolve_parameters(vec![data_file], None);
+    assert_eq!(vec![original], data_files);
+    assert_eq!(None, input_params);
+}
@@ -1,3 +1,4 @@
+use crate::commands::reporters::schema::{Versioned, OUTPUT_SCHEMA_VERSION, TEST_OUTPUT_SCHEMA};
 use crate::commands::reporters::test::generic::GenericReporter;
 use crate::commands::reporters::test::structured::{
     ContextAwareRule, Err, StructuredTestReporter, TestResult,
@@ -20,12 +21,18 @@ use validate::validate_path;
 use crate::commands::files::{
     alphabetical, get_files_with_filter, last_modified, read_file_content, regular_ordering,
 };
-use crate::commands::validate::{OutputFormatType, OUTPUT_FORMAT_HELP};
+use crate::commands::test_manifest::{find_manifest, read_manifest, ManifestMapping};
+use crate::commands::test_mutation::handle_mutate;
+use crate::commands::test_skeleton::generate_test_skeleton;
+use crate::commands::validate::{
+    is_rule_pack, read_public_key, read_rule_packs, OutputFormatType, OUTPUT_FORMAT_HELP,
+};
 use crate::commands::{
-    validate, ALPHABETICAL, DIRECTORY, DIRECTORY_ONLY, LAST_MODIFIED, RULES_AND_TEST_FILE,
-    RULES_FILE, TEST_DATA,
+    validate, ALPHABETICAL, DIRECTORY, DIRECTORY_ONLY, INIT, LAST_MODIFIED, OUTPUT_FILE,
+    RULES_AND_TEST_FILE, RULES_FILE, TEST_DATA, VERBOSE,
 };
 use crate::rules::errors::Error;
+use crate::rules::exprs::RuleSelection;
 use crate::rules::Result;
 use crate::utils::reader::Reader;
 use crate::utils::writer::Writer;
@@ -34,12 +41,24 @@ const ABOUT: &str = r#"Built in unit testing capability to validate a Guard rule
 unit tests specified in YAML format to determine each individual rule's success
 or failure testing.
 "#;
-const RULES_HELP: &str = "Provide a rules file";
+const RULES_HELP: &str = "Provide a rules file, or a rules pack built with cfn-guard build";
 const TEST_DATA_HELP: &str = "Provide a file or dir for data files in JSON or YAML";
-const DIRECTORY_HELP: &str = "Provide the root directory for rules";
+const DIRECTORY_HELP: &str = "Provide the root directory for rules. Its guard-tests.yaml, when it has one, maps the rules files to their test files, otherwise the test files of a rules file are in the tests directory next to it";
 const ALPHABETICAL_HELP: &str = "Sort alphabetically inside a directory";
 const LAST_MODIFIED_HELP: &str = "Sort by last modified times within a directory";
 const VERBOSE_HELP: &str = "Verbose logging";
+const OUTPUT_FILE_HELP: &str = "Write the output to the given file instead of stdout";
+const TAGS_HELP: &str =
+    "Only test rules annotated with at least one of the given tags, e.g. --tags cis,s3";
+const EXCLUDE_TAGS_HELP: &str =
+    "Skip rules annotated with any of the given tags, e.g. --exclude-tags experimental";
+const VERIFY_SIGNATURE_HELP: &str = "An Ed25519 public key in PEM. The rules file must be a rules pack with a signature next to it, e.g. rules.pack.sig, \
+written by cfn-guard sign with the matching private key, and must not have been modified since";
+const INIT_HELP: &str =
+    "Generate a test data skeleton for the rules file, with a PASS and FAIL case per rule";
+const MUTATE_HELP: &str = "Mutate the rules file, flipping operators, dropping clauses and widening IN lists, and report the mutants the test data does not catch. \
+The test data must pass against the rules file as written";
+const OUTPUT_SCHEMA_HELP: &str = "Print the JSON Schema of the json and yaml output, whose documents carry the schema_version they follow, instead of running tests";
 
 #[derive(Debug, Clone, Eq, PartialEq, Args)]
 #[clap(about=ABOUT)]
@@ -94,6 +113,36 @@ pub struct Test {
     /// if junit, json or yaml are chosen, will conflict with verbose logging if set to true
     #[arg(short, long, help=OUTPUT_FORMAT_HELP, value_enum, default_value_t=OutputFormatType::SingleLineSummary)]
     pub(crate) output_format: OutputFormatType,
+    /// path of a file the output is written to instead of stdout, errors are still written to stderr
+    /// default is None
+    #[arg(name=OUTPUT_FILE.0, short=OUTPUT_FILE.1, long, help=OUTPUT_FILE_HELP)]
+    pub(crate) output_file: Option<String>,
+    /// only test rules annotated with at least one of these tags, all rules when empty
+    #[arg(long, help=TAGS_HELP, value_delimiter=',')]
+    pub(crate) tags: Vec<String>,
+    /// skip rules annotated with any of these tags
+    #[arg(long, help=EXCLUDE_TAGS_HELP, value_delimiter=',')]
+    pub(crate) exclude_tags: Vec<String>,
+    /// path of the Ed25519 public key in PEM the rules pack must be signed for, with cfn-guard
+    /// sign. The rules file must be a rules pack when set
+    /// default is None
+    #[arg(long, help=VERIFY_SIGNATURE_HELP, requires=RULES_FILE.0, conflicts_with_all=[DIRECTORY.0, INIT.0])]
+    pub(crate) verify_signature: Option<String>,
+    /// Generate a skeleton of test cases for the rules file instead of running tests
+    /// requires rules, conflicts with test_data, directory and verbose
+    /// default is false
+    #[arg(short=INIT.1, long=INIT.0, help=INIT_HELP, requires=RULES_FILE.0, conflicts_with_all=[TEST_DATA.0, DIRECTORY.0, VERBOSE.0])]
+    pub(crate) init: bool,
+    /// Test mutants of the rules file, one change each, and report the ones the test data does
+    /// not catch
+    /// requires rules and test_data, conflicts with directory, init and verbose
+    /// default is false
+    #[arg(long, help=MUTATE_HELP, requires=RULES_FILE.0, conflicts_with_all=[DIRECTORY.0, INIT.0, VERBOSE.0])]
+    pub(crate) mutate: bool,
+    /// Print the JSON Schema of the json and yaml output instead of running tests
+    /// default is false
+    #[arg(long, help=OUTPUT_SCHEMA_HELP, conflicts_with_all=[RULES_FILE.0, TEST_DATA.0, DIRECTORY.0, INIT.0])]
+    pub(crate) output_schema: bool,
 }
 
 #[derive(Debug)]
@@ -122,7 +171,26 @@ impl Executable for Test {
     /// - parse errors occur in the rule file
     /// - illegal json or yaml syntax present in any of the data input files
     fn execute(&self, writer: &mut Writer, _: &mut Reader) -> Result<i32> {
+        if self.output_schema {
+            writeln!(writer, "{}", TEST_OUTPUT_SCHEMA.trim_end())?;
+            return Ok(SUCCESS_STATUS_CODE);
+        }
+
+        if self.init {
+            return match &self.rules {
+                Some(file) => handle_init(file, writer),
+                None => Err(Error::IllegalArguments(String::from(
+                    "a rules file must be provided to generate a test skeleton",
+                ))),
+            };
+        }
+
         let mut exit_code = SUCCESS_STATUS_CODE;
+        let selection = RuleSelection {
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            ..Default::default()
+        };
         let cmp = if self.alphabetical {
             alphabetical
         } else if self.last_modified {
@@ -139,20 +207,49 @@ impl Executable for Test {
             )));
         }
 
+        if matches!(
+            self.output_format,
+            OutputFormatType::Csv | OutputFormatType::Tsv
+        ) {
+            return Err(Error::IllegalArguments(String::from(
+                "Cannot provide an output_type of CSV or TSV, these reporters are unsupported.",
+            )));
+        }
+
         if let Some(dir) = &self.directory {
             validate_path(dir)?;
+            if let Some(manifest) = find_manifest(dir) {
+                let mappings = read_manifest(&manifest)?;
+                return match self.output_format {
+                    OutputFormatType::SingleLineSummary => {
+                        handle_plaintext_manifest(&mappings, writer, self.verbose, &selection)
+                    }
+                    OutputFormatType::JSON | OutputFormatType::YAML | OutputFormatType::Junit => {
+                        handle_structured_manifest_report(
+                            &mappings,
+                            writer,
+                            self.output_format,
+                            &selection,
+                        )
+                    }
+                    OutputFormatType::Sarif => unreachable!(),
+                    OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
+                };
+            }
+
             let walk = walkdir::WalkDir::new(dir);
             let ordered_directory = OrderedTestDirectory::from(walk);
 
             match self.output_format {
                 OutputFormatType::SingleLineSummary => {
-                    handle_plaintext_directory(ordered_directory, writer, self.verbose)
+                    handle_plaintext_directory(ordered_directory, writer, self.verbose, &selection)
                 }
                 OutputFormatType::JSON | OutputFormatType::YAML | OutputFormatType::Junit => {
                     let test_exit_code = handle_structured_directory_report(
                         ordered_directory,
                         writer,
                         self.output_format,
+                        &selection,
                     )?;
                     exit_code = if exit_code == SUCCESS_STATUS_CODE {
                         test_exit_code
@@ -163,6 +260,7 @@ impl Executable for Test {
                     Ok(exit_code)
                 }
                 OutputFormatType::Sarif => unreachable!(),
+                OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
             }
         } else {
             let file = self.rules.as_ref().unwrap();
@@ -187,6 +285,37 @@ impl Executable for Test {
             })?;
 
             let path = PathBuf::from(file);
+            if self.mutate {
+                if is_rule_pack(&path) || self.verify_signature.is_some() {
+                    return Err(Error::IllegalArguments(format!(
+                        "{file} cannot be mutated, only rules files are mutated with --mutate"
+                    )));
+                }
+                if !matches!(self.output_format, OutputFormatType::SingleLineSummary) {
+                    return Err(Error::IllegalArguments(String::from(
+                        "Cannot provide an output_type other than single-line-summary with --mutate",
+                    )));
+                }
+                return handle_mutate(&path, &data_test_files, writer, &selection);
+            }
+
+            let public_key = read_public_key(&self.verify_signature)?;
+            if is_rule_pack(&path) {
+                return handle_rule_pack(
+                    &path,
+                    public_key.as_deref(),
+                    writer,
+                    &data_test_files,
+                    self.output_format,
+                    self.verbose,
+                    &selection,
+                );
+            }
+            if public_key.is_some() {
+                return Err(Error::IllegalArguments(format!(
+                    "{file} is not a rules pack, only signed rules packs are tested with --verify-signature"
+                )));
+            }
 
             let rule_file = File::open(&path)?;
             if !rule_file.metadata()?.is_file() {
@@ -202,8 +331,10 @@ impl Executable for Test {
                     writer,
                     &data_test_files,
                     self.verbose,
+                    &selection,
                 ),
                 OutputFormatType::Sarif => unreachable!(),
+                OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
                 OutputFormatType::YAML | OutputFormatType::JSON | OutputFormatType::Junit => {
                     handle_structured_single_report(
                         rule_file,
@@ -211,6 +342,7 @@ impl Executable for Test {
                         writer,
                         &data_test_files,
                         self.output_format,
+                        &selection,
                     )
                 }
             }
@@ -222,6 +354,7 @@ fn handle_plaintext_directory(
     directory: OrderedTestDirectory,
     writer: &mut Writer,
     verbose: bool,
+    selection: &RuleSelection,
 ) -> Result<i32> {
     let mut exit_code = SUCCESS_STATUS_CODE;
 
@@ -263,6 +396,7 @@ fn handle_plaintext_directory(
                         test_data: &data_test_files,
                         rules,
                         verbose,
+                        selection,
                         writer,
                     };
 
@@ -289,6 +423,7 @@ fn handle_plaintext_single_file(
     writer: &mut Writer,
     data_test_files: &[PathBuf],
     verbose: bool,
+    selection: &RuleSelection,
 ) -> Result<i32> {
     match read_file_content(rule_file) {
         Err(e) => {
@@ -309,6 +444,7 @@ fn handle_plaintext_single_file(
                         writer,
                         verbose,
                         rules,
+                        selection,
                     };
 
                     reporter.report()
@@ -318,6 +454,82 @@ fn handle_plaintext_single_file(
         }
     }
 }
+/// tests every rules file of the rules pack against the test data, as if each was given on its own
+fn handle_rule_pack(
+    path: &Path,
+    public_key: Option<&str>,
+    writer: &mut Writer,
+    data_test_files: &[PathBuf],
+    output: OutputFormatType,
+    verbose: bool,
+    selection: &RuleSelection,
+) -> Result<i32> {
+    let mut exit_code = SUCCESS_STATUS_CODE;
+    let packed = read_rule_packs(&[path.to_path_buf()], public_key)?;
+
+    if output == OutputFormatType::SingleLineSummary {
+        for file in packed {
+            writeln!(writer, "Testing Guard File {}", file.name)?;
+            let mut reporter = GenericReporter {
+                test_data: data_test_files,
+                writer,
+                verbose,
+                rules: file.rules,
+                selection,
+            };
+            exit_code = get_exit_code(exit_code, reporter.report()?);
+            writeln!(writer, "---")?;
+        }
+
+        return Ok(exit_code);
+    }
+
+    let mut test_results = vec![];
+    for file in packed {
+        let mut reporter = StructuredTestReporter {
+            data_test_files,
+            output,
+            selection,
+            rules: ContextAwareRule {
+                rule: file.rules,
+                name: file.name,
+            },
+        };
+
+        let test = reporter.evaluate()?;
+        exit_code = get_exit_code(exit_code, test.get_exit_code());
+        test_results.push(test);
+    }
+
+    match output {
+        OutputFormatType::YAML => serde_yaml::to_writer(writer, &versioned(&test_results))?,
+        OutputFormatType::JSON => serde_json::to_writer_pretty(writer, &versioned(&test_results))?,
+        OutputFormatType::Junit => JunitReport::from(&test_results).serialize(writer)?,
+        // NOTE: safe since output type is checked prior to calling this function
+        OutputFormatType::SingleLineSummary
+        | OutputFormatType::Sarif
+        | OutputFormatType::Csv
+        | OutputFormatType::Tsv => unreachable!(),
+    }
+
+    Ok(exit_code)
+}
+
+fn handle_init(file: &str, writer: &mut Writer) -> Result<i32> {
+    validate_path(file)?;
+
+    let content = get_rule_content(Path::new(file))?;
+    let span = crate::rules::parser::Span::new_extra(&content, file);
+    let specs = match crate::rules::parser::rules_file(span)? {
+        Some(rules) => generate_test_skeleton(&rules),
+        None => vec![],
+    };
+
+    serde_yaml::to_writer(writer, &specs)?;
+
+    Ok(SUCCESS_STATUS_CODE)
+}
+
 fn get_rule_content(path: &Path) -> Result<String> {
     let rule_file = File::open(path)?;
     read_file_content(rule_file)
@@ -329,14 +541,49 @@ pub(crate) fn handle_structured_single_report(
     writer: &mut Writer,
     data_test_files: &[PathBuf],
     output: OutputFormatType,
+    selection: &RuleSelection,
 ) -> Result<i32> {
-    let mut exit_code = SUCCESS_STATUS_CODE;
+    let result = match structured_test_result(rule_file, path, data_test_files, output, selection)?
+    {
+        Some(result) => result,
+        None => return Ok(SUCCESS_STATUS_CODE),
+    };
+    let exit_code = match &result {
+        TestResult::Ok(_) => get_exit_code(SUCCESS_STATUS_CODE, result.get_exit_code()),
+        TestResult::Err(_) => SUCCESS_STATUS_CODE,
+    };
+
+    match output {
+        OutputFormatType::YAML => {
+            serde_yaml::to_writer(writer, &Versioned::new(OUTPUT_SCHEMA_VERSION, &result))?
+        }
+        OutputFormatType::JSON => {
+            serde_json::to_writer_pretty(writer, &Versioned::new(OUTPUT_SCHEMA_VERSION, &result))?
+        }
+        OutputFormatType::Junit => JunitReport::from(&vec![result]).serialize(writer)?,
+        OutputFormatType::SingleLineSummary => unreachable!(),
+        OutputFormatType::Sarif => unreachable!(),
+        OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
+    }
+
+    Ok(exit_code)
+}
+
+/// the result of testing the rules file against the test files, None when the file has no rules
+fn structured_test_result(
+    rule_file: File,
+    path: &Path,
+    data_test_files: &[PathBuf],
+    output: OutputFormatType,
+    selection: &RuleSelection,
+) -> Result<Option<TestResult>> {
     let now = Instant::now();
 
     let result = match read_file_content(rule_file) {
         Err(e) => TestResult::Err(Err {
             rule_file: path.to_str().unwrap_or("").to_string(),
             error: e.to_string(),
+            location: None,
             time: now.elapsed().as_millis(),
         }),
 
@@ -346,35 +593,128 @@ pub(crate) fn handle_structured_single_report(
                 Err(e) => TestResult::Err(Err {
                     rule_file: path.to_str().unwrap_or("").to_string(),
                     error: e.to_string(),
+                    location: e.parse_error_location().cloned(),
                     time: now.elapsed().as_millis(),
                 }),
                 Ok(Some(rule)) => {
                     let mut reporter = StructuredTestReporter {
                         data_test_files,
                         output,
+                        selection,
                         rules: ContextAwareRule {
                             rule,
                             name: path.to_str().unwrap_or("").to_string(),
                         },
                     };
 
-                    let test = reporter.evaluate()?;
-                    let test_code = test.get_exit_code();
-                    exit_code = get_exit_code(exit_code, test_code);
-
-                    test
+                    reporter.evaluate()?
                 }
-                Ok(None) => return Ok(exit_code),
+                Ok(None) => return Ok(None),
             }
         }
     };
 
+    Ok(Some(result))
+}
+
+/// tests the rules files of each mapping of the test manifest against the test files of the
+/// mapping, reporting them under the name of the mapping
+fn handle_plaintext_manifest(
+    mappings: &[ManifestMapping],
+    writer: &mut Writer,
+    verbose: bool,
+    selection: &RuleSelection,
+) -> Result<i32> {
+    let mut exit_code = SUCCESS_STATUS_CODE;
+
+    for mapping in mappings {
+        writeln!(
+            writer,
+            "Testing mapping {}, {} rules file(s) against {} test file(s)",
+            mapping.name,
+            mapping.rules_files.len(),
+            mapping.test_files.len()
+        )?;
+
+        for path in &mapping.rules_files {
+            writeln!(writer, "Testing Guard File {}", path.display())?;
+            let test_code = handle_plaintext_single_file(
+                File::open(path)?,
+                path,
+                writer,
+                &mapping.test_files,
+                verbose,
+                selection,
+            )?;
+            exit_code = get_exit_code(exit_code, test_code);
+            writeln!(writer, "---")?;
+        }
+    }
+
+    Ok(exit_code)
+}
+
+/// the results of the rules files of a mapping of the test manifest
+#[derive(Debug, Serialize)]
+struct MappingTestResult<'mapping> {
+    mapping: &'mapping str,
+    results: Vec<TestResult>,
+}
+
+/// tests the rules files of each mapping of the test manifest against the test files of the
+/// mapping. JSON and YAML group the results by mapping, JUnit names each test suite after its
+/// mapping and rules file
+fn handle_structured_manifest_report(
+    mappings: &[ManifestMapping],
+    writer: &mut Writer,
+    output: OutputFormatType,
+    selection: &RuleSelection,
+) -> Result<i32> {
+    let mut exit_code = SUCCESS_STATUS_CODE;
+    let mut mapping_results = vec![];
+
+    for mapping in mappings {
+        let mut results = vec![];
+        for path in &mapping.rules_files {
+            if let Some(result) = structured_test_result(
+                File::open(path)?,
+                path,
+                &mapping.test_files,
+                output,
+                selection,
+            )? {
+                exit_code = get_exit_code(exit_code, result.get_exit_code());
+                results.push(result);
+            }
+        }
+
+        mapping_results.push(MappingTestResult {
+            mapping: &mapping.name,
+            results,
+        });
+    }
+
     match output {
-        OutputFormatType::YAML => serde_yaml::to_writer(writer, &result)?,
-        OutputFormatType::JSON => serde_json::to_writer_pretty(writer, &result)?,
-        OutputFormatType::Junit => JunitReport::from(&vec![result]).serialize(writer)?,
-        OutputFormatType::SingleLineSummary => unreachable!(),
-        OutputFormatType::Sarif => unreachable!(),
+        OutputFormatType::YAML => serde_yaml::to_writer(writer, &versioned(&mapping_results))?,
+        OutputFormatType::JSON => {
+            serde_json::to_writer_pretty(writer, &versioned(&mapping_results))?
+        }
+        OutputFormatType::Junit => JunitReport::from(
+            &mapping_results
+                .into_iter()
+                .flat_map(|MappingTestResult { mapping, results }| {
+                    results
+                        .into_iter()
+                        .map(move |result| result.in_mapping(mapping))
+                })
+                .collect::<Vec<_>>(),
+        )
+        .serialize(writer)?,
+        // NOTE: safe since output type is checked prior to calling this function
+        OutputFormatType::SingleLineSummary
+        | OutputFormatType::Sarif
+        | OutputFormatType::Csv
+        | OutputFormatType::Tsv => unreachable!(),
     }
 
     Ok(exit_code)
@@ -384,6 +724,7 @@ fn handle_structured_directory_report(
     directory: OrderedTestDirectory,
     writer: &mut Writer,
     output: OutputFormatType,
+    selection: &RuleSelection,
 ) -> Result<i32> {
     let mut test_results = vec![];
     let mut exit_code = SUCCESS_STATUS_CODE;
@@ -404,6 +745,7 @@ fn handle_structured_directory_report(
                     test_results.push(TestResult::Err(Err {
                         rule_file: path.to_str().unwrap().to_string(),
                         error: e.to_string(),
+                        location: None,
                         time: now.elapsed().as_millis(),
                     }));
                     continue;
@@ -418,6 +760,7 @@ fn handle_structured_directory_report(
                     test_results.push(TestResult::Err(Err {
                         rule_file: path.to_str().unwrap().to_string(),
                         error: e.to_string(),
+                        location: e.parse_error_location().cloned(),
                         time: now.elapsed().as_millis(),
                     }))
                 }
@@ -427,6 +770,7 @@ fn handle_structured_directory_report(
                     let mut reporter = StructuredTestReporter {
                         data_test_files: &data_test_files,
                         output,
+                        selection,
                         rules: ContextAwareRule {
                             rule: rules,
                             name: path.to_str().unwrap().to_string(),
@@ -445,17 +789,26 @@ fn handle_structured_directory_report(
     }
 
     match output {
-        OutputFormatType::YAML => serde_yaml::to_writer(writer, &test_results)?,
-        OutputFormatType::JSON => serde_json::to_writer_pretty(writer, &test_results)?,
+        OutputFormatType::YAML => serde_yaml::to_writer(writer, &versioned(&test_results))?,
+        OutputFormatType::JSON => serde_json::to_writer_pretty(writer, &versioned(&test_results))?,
         OutputFormatType::Junit => JunitReport::from(&test_results).serialize(writer)?,
         // NOTE: safe since output type is checked prior to calling this function
         OutputFormatType::Sarif => unreachable!(),
+        OutputFormatType::Csv | OutputFormatType::Tsv => unreachable!(),
         OutputFormatType::SingleLineSummary => unreachable!(),
     }
 
     Ok(exit_code)
 }
 
+/// the documents of the json and yaml output, each with the version of the schema it follows
+fn versioned<T>(documents: &[T]) -> Vec<Versioned<&T>> {
+    documents
+        .iter()
+        .map(|document| Versioned::new(OUTPUT_SCHEMA_VERSION, document))
+        .collect()
+}
+
 fn get_exit_code(exit_code: i32, test_code: i32) -> i32 {
     match exit_code {
         SUCCESS_STATUS_CODE => test_code,
@@ -479,8 +832,13 @@ pub struct TestExpectations {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TestSpec {
     pub name: Option<String>,
+    /// patches the `base` input of the test file when it has one, see [`read_test_specs`]
+    #[serde(default)]
     pub input: serde_yaml::Value,
     pub expectations: TestExpectations,
+    /// the values of each parameter, the test case is run once for every combination of them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub matrix: BTreeMap<String, Vec<serde_yaml::Value>>,
 }
 
 struct OrderedTestDirectory(BTreeMap<String, Vec<GuardFile>>);
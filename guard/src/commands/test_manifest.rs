@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::commands::validate::argument_files;
+use crate::commands::{DATA_FILE_SUPPORTED_EXTENSIONS, RULE_FILE_SUPPORTED_EXTENSIONS};
+use crate::rules::errors::Error;
+use crate::rules::Result;
+
+/// the name of the manifest `test --dir` looks for at the root of the directory, mapping rules
+/// files to the test files they are tested against instead of the `tests` subdirectory
+/// convention
+pub(crate) const TEST_MANIFEST: &str = "guard-tests.yaml";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestManifest {
+    tests: Vec<TestMapping>,
+}
+
+/// rules files and the test files each of them is tested against, as paths, directories or glob
+/// patterns relative to the manifest
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestMapping {
+    #[serde(default)]
+    name: Option<String>,
+    rules: Vec<String>,
+    data: Vec<String>,
+}
+
+/// a mapping of the manifest, with the files its paths point to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ManifestMapping {
+    /// the name of the mapping, its rules paths when it has none
+    pub(crate) name: String,
+    pub(crate) rules_files: Vec<PathBuf>,
+    pub(crate) test_files: Vec<PathBuf>,
+}
+
+/// the manifest at the root of the directory, when there is one
+pub(crate) fn find_manifest(dir: &str) -> Option<PathBuf> {
+    let manifest = Path::new(dir).join(TEST_MANIFEST);
+    manifest.is_file().then_some(manifest)
+}
+
+/// reads the mappings of the manifest, the rules files of each are tested against every one of
+/// its test files
+///
+/// This function will return an error if
+/// - the manifest is not valid YAML or JSON, or not a test manifest
+/// - a path of a mapping does not exist, or a mapping has no rules or test files
+pub(crate) fn read_manifest(manifest: &Path) -> Result<Vec<ManifestMapping>> {
+    let content = std::fs::read_to_string(manifest)?;
+    let TestManifest { tests } = serde_yaml::from_str(&content).map_err(|e| {
+        Error::ParseError(format!(
+            "Unable to read the test manifest {}, {e}",
+            manifest.display()
+        ))
+    })?;
+
+    let root = manifest.parent().unwrap_or_else(|| Path::new(""));
+    let relative = |paths: &[String]| {
+        paths
+            .iter()
+            .map(|path| root.join(path).display().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let mut mappings = vec![];
+    for TestMapping { name, rules, data } in tests {
+        let name = name.unwrap_or_else(|| rules.join(", "));
+        let rules_files = argument_files(&relative(&rules), &RULE_FILE_SUPPORTED_EXTENSIONS)?;
+        let test_files = argument_files(&relative(&data), &DATA_FILE_SUPPORTED_EXTENSIONS)?;
+        if rules_files.is_empty() || test_files.is_empty() {
+            return Err(Error::IllegalArguments(format!(
+                "the mapping {name} of the test manifest {} has no {}",
+                manifest.display(),
+                match rules_files.is_empty() {
+                    true => "rules files",
+                    false => "test files",
+                }
+            )));
+        }
+
+        mappings.push(ManifestMapping {
+            name,
+            rules_files,
+            test_files,
+        });
+    }
+
+    Ok(mappings)
+}
+
+#[cfg(test)]
+#[path = "test_manifest_tests.rs"]
+mod test_manifest_tests;
@@ -0,0 +1,75 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+const MANIFEST_DIR: &str = "resources/test-command/manifest";
+
+fn paths(paths: &[&str]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .map(|path| Path::new(MANIFEST_DIR).join(path))
+        .collect()
+}
+
+#[test]
+fn test_find_manifest() {
+    assert_eq!(
+        Some(Path::new(MANIFEST_DIR).join(TEST_MANIFEST)),
+        find_manifest(MANIFEST_DIR)
+    );
+    assert_eq!(None, find_manifest("resources/test-command/dir"));
+}
+
+#[test]
+fn test_read_manifest() {
+    let mappings = read_manifest(&Path::new(MANIFEST_DIR).join(TEST_MANIFEST)).unwrap();
+
+    assert_eq!(
+        vec![
+            ManifestMapping {
+                name: String::from("logging"),
+                rules_files: paths(&["rules/s3_bucket_logging_enabled.guard"]),
+                test_files: paths(&["data/logging.yaml"]),
+            },
+            ManifestMapping {
+                name: String::from("encryption"),
+                rules_files: paths(&["rules/s3_bucket_server_side_encryption_enabled.guard"]),
+                test_files: paths(&["data/encryption.json"]),
+            },
+            ManifestMapping {
+                name: String::from("rules"),
+                rules_files: paths(&[
+                    "rules/s3_bucket_logging_enabled.guard",
+                    "rules/s3_bucket_server_side_encryption_enabled.guard"
+                ]),
+                test_files: paths(&["data/shared/empty.yaml"]),
+            },
+        ],
+        mappings
+    );
+}
+
+#[test]
+fn test_read_manifest_errors() {
+    let dir = std::env::temp_dir().join("cfn-guard-test-manifest");
+    std::fs::create_dir_all(&dir).unwrap();
+    let manifest = dir.join(TEST_MANIFEST);
+
+    let mut errors = vec![];
+    for content in [
+        "tests:\n  - rules: [missing.guard]\n    data: [tests]\n",
+        "tests:\n  - rules: [.]\n    data: [.]\n    expected: PASS\n",
+        "tests:\n  - name: empty\n    rules: [.]\n    data: [.]\n",
+    ] {
+        std::fs::write(&manifest, content).unwrap();
+        errors.push(read_manifest(&manifest).unwrap_err().to_string());
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+
+    assert!(errors[0].contains("missing.guard"), "{}", errors[0]);
+    assert!(
+        errors[1].contains("Unable to read the test manifest"),
+        "{}",
+        errors[1]
+    );
+    assert!(errors[2].contains("the mapping empty"), "{}", errors[2]);
+}
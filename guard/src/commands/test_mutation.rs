@@ -0,0 +1,450 @@
+use std::convert::TryFrom;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::commands::files::read_file_content;
+use crate::commands::reporters::test::{get_by_rules, get_status_result};
+use crate::commands::test::TestExpectations;
+use crate::commands::test_spec::read_test_specs;
+use crate::commands::{SUCCESS_STATUS_CODE, TEST_ERROR_STATUS_CODE, TEST_FAILURE_STATUS_CODE};
+use crate::rules::display::{
+    clause_source, guard_clause_source, rule_clause_source, when_clause_source,
+};
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::{
+    Block, Conjunctions, GuardAccessClause, GuardClause, GuardNamedRuleClause, LetValue,
+    RuleClause, RuleSelection, RulesFile, WhenConditions, WhenGuardClause,
+};
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::values::CmpOperator;
+use crate::rules::{Result, Status};
+use crate::utils::writer::Writer;
+
+/// added to IN lists of strings when no test input has a string the list does not already hold
+const SENTINEL: &str = "cfn-guard-mutant";
+
+/// a copy of the rules with a single change to one of its clauses
+#[derive(Debug, Clone)]
+pub(crate) struct Mutant<'loc> {
+    pub(crate) rule_name: String,
+    /// the change, e.g. flipped `Status == "Enabled"` to `Status != "Enabled"` at line 3
+    pub(crate) description: String,
+    pub(crate) rules: RulesFile<'loc>,
+}
+
+/// every mutant of the rules, one per
+/// - clause, named rule or `when` condition, with its operator or `not` flipped
+/// - clause in a list of `or` clauses, or in a block of more than one clause, dropped
+/// - literal IN list, widened with the values of the test inputs it does not hold yet, or with
+///   a value no test input has when there are none
+///
+/// Clauses in the filters of queries are left as they are
+pub(crate) fn mutants<'loc>(
+    rules: &RulesFile<'loc>,
+    input_values: &[PathAwareValue],
+) -> Vec<Mutant<'loc>> {
+    let mut counter = Mutator::new(usize::MAX, input_values);
+    counter.rules_file(&mut rules.clone());
+
+    (0..counter.seen)
+        .filter_map(|target| {
+            let mut mutated = rules.clone();
+            let mut mutator = Mutator::new(target, input_values);
+            mutator.rules_file(&mut mutated);
+            mutator.applied.map(|description| Mutant {
+                rule_name: mutator.applied_in,
+                description,
+                rules: mutated,
+            })
+        })
+        .collect()
+}
+
+/// walks the clauses of the rules in the same order every time, counting the places a mutation
+/// can be made and applying the one at the target
+struct Mutator<'values> {
+    target: usize,
+    seen: usize,
+    rule_name: String,
+    applied: Option<String>,
+    applied_in: String,
+    input_values: &'values [PathAwareValue],
+}
+
+impl<'values> Mutator<'values> {
+    fn new(target: usize, input_values: &'values [PathAwareValue]) -> Self {
+        Mutator {
+            target,
+            seen: 0,
+            rule_name: String::new(),
+            applied: None,
+            applied_in: String::new(),
+            input_values,
+        }
+    }
+
+    /// whether the next place a mutation can be made is the target
+    fn at_target(&mut self) -> bool {
+        let at_target = self.seen == self.target;
+        self.seen += 1;
+        at_target
+    }
+
+    fn apply(&mut self, description: String) {
+        self.applied = Some(description);
+        self.applied_in = self.rule_name.clone();
+    }
+
+    fn rules_file(&mut self, rules: &mut RulesFile<'_>) {
+        let rules = rules.guard_rules.iter_mut().chain(
+            rules
+                .parameterized_rules
+                .iter_mut()
+                .map(|parameterized| &mut parameterized.rule),
+        );
+        for rule in rules {
+            self.rule_name = rule.rule_name.clone();
+            if let Some(conditions) = &mut rule.conditions {
+                self.when_conditions(conditions);
+            }
+            self.conjunctions(
+                &mut rule.block.conjunctions,
+                rule_clause_source,
+                Self::rule_clause,
+            );
+        }
+    }
+
+    fn conjunctions<T>(
+        &mut self,
+        conjunctions: &mut Conjunctions<T>,
+        render: fn(&T) -> String,
+        visit: fn(&mut Self, &mut T),
+    ) {
+        for index in 0..conjunctions.len() {
+            let disjunctions = conjunctions[index].len();
+            for position in 0..disjunctions {
+                if (disjunctions > 1 || conjunctions.len() > 1) && self.at_target() {
+                    let dropped = render(&conjunctions[index][position]);
+                    if disjunctions > 1 {
+                        conjunctions[index].remove(position);
+                    } else {
+                        conjunctions.remove(index);
+                    }
+                    self.apply(format!("dropped `{dropped}`"));
+                    return;
+                }
+            }
+        }
+
+        for disjunctions in conjunctions.iter_mut() {
+            for clause in disjunctions.iter_mut() {
+                visit(self, clause);
+            }
+        }
+    }
+
+    fn when_conditions(&mut self, conditions: &mut WhenConditions<'_>) {
+        self.conjunctions(conditions, when_clause_source, Self::when_clause);
+    }
+
+    fn block(&mut self, block: &mut Block<'_, GuardClause<'_>>) {
+        self.conjunctions(
+            &mut block.conjunctions,
+            guard_clause_source,
+            Self::guard_clause,
+        );
+    }
+
+    fn rule_clause(&mut self, clause: &mut RuleClause<'_>) {
+        match clause {
+            RuleClause::Clause(clause) => self.guard_clause(clause),
+            RuleClause::WhenBlock(conditions, block, otherwise) => {
+                self.when_conditions(conditions);
+                self.block(block);
+                if let Some(otherwise) = otherwise {
+                    self.block(otherwise);
+                }
+            }
+            RuleClause::TypeBlock(type_block) => {
+                if let Some(conditions) = &mut type_block.conditions {
+                    self.when_conditions(conditions);
+                }
+                self.block(&mut type_block.block);
+            }
+        }
+    }
+
+    fn guard_clause(&mut self, clause: &mut GuardClause<'_>) {
+        match clause {
+            GuardClause::Clause(clause) => self.access_clause(clause),
+            GuardClause::NamedRule(named) => self.named_rule(named),
+            GuardClause::ParameterizedNamedRule(parameterized) => {
+                self.named_rule(&mut parameterized.named_rule)
+            }
+            GuardClause::BlockClause(block_clause) => self.block(&mut block_clause.block),
+            GuardClause::WhenBlock(conditions, block, otherwise) => {
+                self.when_conditions(conditions);
+                self.block(block);
+                if let Some(otherwise) = otherwise {
+                    self.block(otherwise);
+                }
+            }
+        }
+    }
+
+    fn when_clause(&mut self, clause: &mut WhenGuardClause<'_>) {
+        match clause {
+            WhenGuardClause::Clause(clause) => self.access_clause(clause),
+            WhenGuardClause::NamedRule(named) => self.named_rule(named),
+            WhenGuardClause::ParameterizedNamedRule(parameterized) => {
+                self.named_rule(&mut parameterized.named_rule)
+            }
+        }
+    }
+
+    fn named_rule(&mut self, named: &mut GuardNamedRuleClause<'_>) {
+        if self.at_target() {
+            named.negation = !named.negation;
+            self.apply(format!(
+                "flipped `{}` to `{}{}` at line {}",
+                match named.negation {
+                    true => named.dependent_rule.clone(),
+                    false => format!("not {}", named.dependent_rule),
+                },
+                match named.negation {
+                    true => "not ",
+                    false => "",
+                },
+                named.dependent_rule,
+                named.location.line
+            ));
+        }
+    }
+
+    fn access_clause(&mut self, clause: &mut GuardAccessClause<'_>) {
+        let line = clause.access_clause.location.line;
+        if self.at_target() {
+            let before = clause_source(clause);
+            clause.access_clause.comparator.1 = !clause.access_clause.comparator.1;
+            self.apply(format!(
+                "flipped `{before}` to `{}` at line {line}",
+                clause_source(clause)
+            ));
+            return;
+        }
+
+        let widened = match &clause.access_clause {
+            access if access.comparator != (CmpOperator::In, false) => None,
+            access => match &access.compare_with {
+                Some(LetValue::Value(PathAwareValue::List((path, list)))) => {
+                    widened_list(list, self.input_values)
+                        .map(|list| LetValue::Value(PathAwareValue::List((path.clone(), list))))
+                }
+                _ => None,
+            },
+        };
+        if let Some(widened) = widened {
+            if self.at_target() {
+                let before = clause_source(clause);
+                clause.access_clause.compare_with = Some(widened);
+                self.apply(format!(
+                    "widened `{before}` to `{}` at line {line}",
+                    clause_source(clause)
+                ));
+            }
+        }
+    }
+}
+
+/// the list with the values of the test inputs of the same type it does not hold yet, None for
+/// lists that are empty or not of strings, numbers, chars or bools
+fn widened_list(
+    list: &[PathAwareValue],
+    input_values: &[PathAwareValue],
+) -> Option<Vec<PathAwareValue>> {
+    let first = list.first()?;
+    if !matches!(
+        first,
+        PathAwareValue::String(_)
+            | PathAwareValue::Int(_)
+            | PathAwareValue::Float(_)
+            | PathAwareValue::Char(_)
+            | PathAwareValue::Bool(_)
+    ) {
+        return None;
+    }
+
+    let mut widened = list.to_vec();
+    for value in input_values {
+        if std::mem::discriminant(value) == std::mem::discriminant(first)
+            && !widened.contains(value)
+        {
+            widened.push(value.clone());
+        }
+    }
+
+    if widened.len() == list.len() {
+        match first {
+            PathAwareValue::String((path, _)) => {
+                widened.push(PathAwareValue::String((path.clone(), SENTINEL.to_string())))
+            }
+            _ => return None,
+        }
+    }
+
+    Some(widened)
+}
+
+/// the scalar values of the input, each once
+pub(crate) fn scalar_values(value: &PathAwareValue, values: &mut Vec<PathAwareValue>) {
+    match value {
+        PathAwareValue::List((_, list)) => {
+            for each in list {
+                scalar_values(each, values);
+            }
+        }
+        PathAwareValue::Map((_, map)) => {
+            for each in map.values.values() {
+                scalar_values(each, values);
+            }
+        }
+        PathAwareValue::String(_)
+        | PathAwareValue::Int(_)
+        | PathAwareValue::Float(_)
+        | PathAwareValue::Char(_)
+        | PathAwareValue::Bool(_) => {
+            if !values.contains(value) {
+                values.push(value.clone());
+            }
+        }
+        _ => {}
+    }
+}
+
+struct TestInput {
+    path_value: Rc<PathAwareValue>,
+    expectations: TestExpectations,
+}
+
+/// how many expectations of the test cases the rules do not meet
+fn failed_expectations(
+    rules: &RulesFile<'_>,
+    inputs: &[TestInput],
+    selection: &RuleSelection,
+) -> Result<usize> {
+    let mut failed = 0;
+    for input in inputs {
+        let mut root_scope =
+            root_scope(rules, Rc::clone(&input.path_value)).with_selection(selection);
+        eval_rules_file(rules, &mut root_scope, None)?;
+        let top = root_scope.reset_recorder().extract();
+
+        for (rule_name, records) in get_by_rules(&top) {
+            if let Some(expected) = input.expectations.rules.get(rule_name) {
+                if get_status_result(Status::try_from(expected.as_str())?, records)
+                    .0
+                    .is_none()
+                {
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    Ok(failed)
+}
+
+/// runs the test cases against every mutant of the rules file and reports the ones they do not
+/// tell apart from the rules, the mutants that survive. The test cases must pass against the
+/// rules as written
+///
+/// This function will return an error if
+/// - the rules file or a test file cannot be read
+/// - the rules file fails to parse
+/// - a test file is not a list of test cases
+pub(crate) fn handle_mutate(
+    path: &Path,
+    data_test_files: &[PathBuf],
+    writer: &mut Writer,
+    selection: &RuleSelection,
+) -> Result<i32> {
+    let content = read_file_content(std::fs::File::open(path)?)?;
+    let name = path.to_str().unwrap_or("");
+    let span = crate::rules::parser::Span::new_extra(&content, name);
+    let rules = match crate::rules::parser::rules_file(span)? {
+        Some(rules) => rules,
+        None => return Ok(SUCCESS_STATUS_CODE),
+    };
+
+    let mut inputs = vec![];
+    let mut input_values = vec![];
+    for file in data_test_files {
+        let data = read_file_content(std::fs::File::open(file)?)?;
+        for spec in read_test_specs(&data, file)? {
+            let path_value = PathAwareValue::try_from(spec.input)?;
+            scalar_values(&path_value, &mut input_values);
+            inputs.push(TestInput {
+                path_value: Rc::new(path_value),
+                expectations: spec.expectations,
+            });
+        }
+    }
+
+    let failed = failed_expectations(&rules, &inputs, selection)?;
+    if failed > 0 {
+        writeln!(
+            writer,
+            "{failed} expectation(s) of the test cases fail against {name} as written, fix them before mutating the rules"
+        )?;
+        return Ok(TEST_ERROR_STATUS_CODE);
+    }
+
+    writeln!(
+        writer,
+        "Mutating {name} against {} test case(s)",
+        inputs.len()
+    )?;
+    let mutants = mutants(&rules, &input_values);
+    let mut survived = 0;
+    for mutant in &mutants {
+        // a mutant the evaluator fails on is told apart from the rules as much as a failed test
+        let killed = failed_expectations(&mutant.rules, &inputs, selection)
+            .map_or(true, |failed| failed > 0);
+        if !killed {
+            survived += 1;
+        }
+        writeln!(
+            writer,
+            "{} {}: {}",
+            match killed {
+                true => "KILLED  ",
+                false => "SURVIVED",
+            },
+            mutant.rule_name,
+            mutant.description
+        )?;
+    }
+
+    let killed = mutants.len() - survived;
+    writeln!(
+        writer,
+        "{killed} of {} mutant(s) killed, mutation score {}%",
+        mutants.len(),
+        match mutants.is_empty() {
+            true => 100,
+            false => killed * 100 / mutants.len(),
+        }
+    )?;
+
+    Ok(match survived {
+        0 => SUCCESS_STATUS_CODE,
+        _ => TEST_FAILURE_STATUS_CODE,
+    })
+}
+
+#[cfg(test)]
+#[path = "test_mutation_tests.rs"]
+mod test_mutation_tests;
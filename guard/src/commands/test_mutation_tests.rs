@@ -0,0 +1,115 @@
+use super::*;
+use crate::rules::parser::{rules_file, Span};
+use crate::rules::path_value::Path;
+use pretty_assertions::assert_eq;
+
+const RULES: &str = r#"
+rule s3_encrypted when Type == "AWS::S3::Bucket" {
+    Properties.BucketEncryption exists
+    Properties.Algorithm in ["AES256"] or Properties.KmsKeyId exists
+}
+
+rule s3_compliant {
+    s3_encrypted
+}
+"#;
+
+fn string(value: &str) -> PathAwareValue {
+    PathAwareValue::String((Path::root(), value.to_string()))
+}
+
+fn descriptions(mutants: &[Mutant<'_>]) -> Vec<String> {
+    mutants
+        .iter()
+        .map(|mutant| format!("{}: {}", mutant.rule_name, mutant.description))
+        .collect()
+}
+
+#[test]
+fn test_mutants() {
+    let rules = rules_file(Span::new_extra(RULES, "")).unwrap().unwrap();
+    let mutants = mutants(&rules, &[string("aws:kms"), string("AES256")]);
+    let descriptions = descriptions(&mutants);
+
+    assert_eq!(9, mutants.len(), "{descriptions:#?}");
+    assert!(descriptions[0].starts_with("s3_encrypted: flipped `Type == "));
+    assert!(
+        descriptions[1].starts_with("s3_encrypted: dropped `Properties.BucketEncryption exists`")
+    );
+    assert!(descriptions[2].starts_with("s3_encrypted: dropped `Properties.Algorithm in"));
+    assert!(descriptions[3].starts_with("s3_encrypted: dropped `Properties.KmsKeyId exists`"));
+    assert_eq!(
+        "s3_encrypted: flipped `Properties.BucketEncryption exists` to `Properties.BucketEncryption not exists` at line 3",
+        descriptions[4]
+    );
+    assert!(descriptions[6].starts_with("s3_encrypted: widened `Properties.Algorithm in"));
+    assert!(descriptions[6].contains("aws:kms"), "{}", descriptions[6]);
+    assert_eq!(
+        "s3_compliant: flipped `s3_encrypted` to `not s3_encrypted` at line 8",
+        descriptions[8]
+    );
+
+    // every mutant is a single change to its own copy of the rules
+    assert_ne!(rules, mutants[0].rules);
+    assert_eq!(
+        rules,
+        rules_file(Span::new_extra(RULES, "")).unwrap().unwrap()
+    );
+    assert_eq!(1, mutants[1].rules.guard_rules[0].block.conjunctions.len());
+    assert_eq!(
+        1,
+        mutants[2].rules.guard_rules[0].block.conjunctions[1].len()
+    );
+}
+
+#[test]
+fn test_widened_list() {
+    let inputs = [
+        string("AES256"),
+        string("aws:kms"),
+        PathAwareValue::Int((Path::root(), 1)),
+    ];
+
+    assert_eq!(
+        Some(vec![string("AES256"), string("aws:kms")]),
+        widened_list(&[string("AES256")], &inputs)
+    );
+    assert_eq!(
+        Some(vec![string("AES256"), string("aws:kms"), string(SENTINEL)]),
+        widened_list(&[string("AES256"), string("aws:kms")], &inputs)
+    );
+    assert_eq!(
+        None,
+        widened_list(&[PathAwareValue::Int((Path::root(), 1))], &inputs)
+    );
+    assert_eq!(None, widened_list(&[], &inputs));
+}
+
+#[test]
+fn test_scalar_values() {
+    let value = PathAwareValue::try_from(
+        serde_yaml::from_str::<serde_yaml::Value>(
+            r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      Algorithms: [AES256, AES256, 1]
+"#,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let mut values = vec![];
+    scalar_values(&value, &mut values);
+
+    assert_eq!(
+        vec![
+            string("AWS::S3::Bucket"),
+            string("AES256"),
+            PathAwareValue::Int((Path::root(), 1))
+        ],
+        values
+    );
+}
@@ -0,0 +1,551 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_yaml::{Mapping, Value};
+
+use crate::commands::test::{TestExpectations, TestSpec};
+use crate::rules::exprs::{
+    AccessQuery, Block, GuardAccessClause, GuardClause, LetExpr, LetValue, QueryPart, RuleClause,
+    RulesFile, WhenConditions, WhenGuardClause,
+};
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::values::CmpOperator;
+
+//
+// Generates a skeleton of unit tests for a rules file, with one PASS and one FAIL case per rule.
+// The inputs are synthesized from the resource types and property paths that each rule references,
+// the clauses are satisfied with the value they compare against where one is available, else with
+// a placeholder value. The FAIL case is the PASS case with the first clause of the rule broken,
+// the generated inputs are only a starting point and are meant to be reviewed by the rule author.
+//
+
+const PLACEHOLDER: &str = "REPLACE_ME";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index,
+}
+
+#[derive(Debug, Clone)]
+struct Check {
+    resource_type: Option<String>,
+    path: Vec<Segment>,
+    comparator: (CmpOperator, bool),
+    compare_with: Option<PathAwareValue>,
+    condition: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Assignment {
+    Set(Value),
+    Omit,
+}
+
+#[derive(Clone, Default)]
+struct Context<'rules, 'loc> {
+    resource_type: Option<String>,
+    prefix: Vec<Segment>,
+    variables: HashMap<&'rules str, &'rules AccessQuery<'loc>>,
+}
+
+impl<'rules, 'loc> Context<'rules, 'loc> {
+    fn with_assignments(&self, assignments: &'rules [LetExpr<'loc>]) -> Self {
+        let mut context = self.clone();
+        for each in assignments {
+            if let LetValue::AccessClause(query) = &each.value {
+                context.variables.insert(each.var.as_str(), query);
+            }
+        }
+        context
+    }
+
+    //
+    // resolves a query into the resource type it selects, if it selects resources out of a
+    // CloudFormation template, and the path relative to it. Returns None for queries that can
+    // not be turned into a path, e.g. ones referencing a variable assigned to a literal value
+    //
+    fn resolve(&self, query: &[QueryPart<'loc>]) -> Option<(Option<String>, Vec<Segment>)> {
+        let mut parts = Vec::with_capacity(query.len());
+        let mut iter = query.iter().peekable();
+        if let Some(var) = iter.peek().and_then(|part| part.variable()) {
+            let assigned = self.variables.get(var)?;
+            parts.extend(assigned.query.iter().cloned());
+            iter.next();
+            if let Some(QueryPart::AllIndices(_)) = iter.peek() {
+                iter.next();
+            }
+        }
+        parts.extend(iter.cloned());
+
+        let (resource_type, parts) = match parts.as_slice() {
+            [QueryPart::Key(resources), QueryPart::AllValues(_), QueryPart::Filter(_, conjunctions), rest @ ..]
+                if resources == "Resources" =>
+            {
+                match type_from_filter(conjunctions) {
+                    Some(resource_type) => (Some(resource_type), rest),
+                    None => return None,
+                }
+            }
+            _ => (self.resource_type.clone(), parts.as_slice()),
+        };
+
+        let mut path = if resource_type == self.resource_type {
+            self.prefix.clone()
+        } else {
+            vec![]
+        };
+        for part in parts {
+            match part {
+                QueryPart::Key(key) if key.starts_with('%') => return None,
+                QueryPart::Key(key) => path.push(Segment::Key(key.clone())),
+                QueryPart::AllValues(_) => path.push(Segment::Key(String::from("Item"))),
+                QueryPart::AllIndices(_) | QueryPart::Index(_) | QueryPart::Slice(..) => {
+                    path.push(Segment::Index)
+                }
+                QueryPart::Filter(..) | QueryPart::MapKeyFilter(..) | QueryPart::This => {}
+                // the referenced resource is not part of the path the query starts from
+                QueryPart::Resolve => return None,
+                // the depth of the key is not known
+                QueryPart::Descendants(_) => return None,
+            }
+        }
+
+        Some((resource_type, path))
+    }
+}
+
+fn type_from_filter(conjunctions: &[Vec<GuardClause<'_>>]) -> Option<String> {
+    conjunctions.iter().flatten().find_map(|clause| match clause {
+        GuardClause::Clause(GuardAccessClause {
+            access_clause,
+            negation: false,
+        }) if access_clause.comparator == (CmpOperator::Eq, false)
+            && matches!(access_clause.query.query.as_slice(), [QueryPart::Key(key)] if key == "Type") =>
+        {
+            match &access_clause.compare_with {
+                Some(LetValue::Value(PathAwareValue::String((_, type_name)))) => {
+                    Some(type_name.clone())
+                }
+                Some(LetValue::Value(PathAwareValue::Regex((_, regex)))) => {
+                    let type_name = regex.trim_start_matches('^').trim_end_matches('$');
+                    type_name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == ':')
+                        .then(|| type_name.to_string())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+fn collect_when_conditions(
+    conditions: &WhenConditions<'_>,
+    context: &Context<'_, '_>,
+    checks: &mut Vec<Check>,
+) {
+    for disjunctions in conditions {
+        if let Some(WhenGuardClause::Clause(clause)) = disjunctions.first() {
+            collect_access_clause(clause, context, true, checks);
+        }
+    }
+}
+
+fn collect_access_clause(
+    clause: &GuardAccessClause<'_>,
+    context: &Context<'_, '_>,
+    condition: bool,
+    checks: &mut Vec<Check>,
+) {
+    let access_clause = &clause.access_clause;
+    if let Some((resource_type, path)) = context.resolve(&access_clause.query.query) {
+        let compare_with = match &access_clause.compare_with {
+            Some(LetValue::Value(value)) => Some(value.clone()),
+            _ => None,
+        };
+        checks.push(Check {
+            resource_type,
+            path,
+            comparator: (
+                access_clause.comparator.0,
+                access_clause.comparator.1 ^ clause.negation,
+            ),
+            compare_with,
+            condition,
+        });
+    }
+}
+
+fn collect_block<'rules, 'loc>(
+    block: &'rules Block<'loc, GuardClause<'loc>>,
+    context: &Context<'rules, 'loc>,
+    checks: &mut Vec<Check>,
+) {
+    let context = context.with_assignments(&block.assignments);
+    for disjunctions in &block.conjunctions {
+        if let Some(clause) = disjunctions.first() {
+            collect_guard_clause(clause, &context, checks);
+        }
+    }
+}
+
+fn collect_guard_clause<'rules, 'loc>(
+    clause: &'rules GuardClause<'loc>,
+    context: &Context<'rules, 'loc>,
+    checks: &mut Vec<Check>,
+) {
+    match clause {
+        GuardClause::Clause(clause) => collect_access_clause(clause, context, false, checks),
+        // the data a negated block wants is whatever its clauses do not describe
+        GuardClause::BlockClause(block_clause) if block_clause.negation => {}
+        GuardClause::BlockClause(block_clause) => {
+            if let Some((resource_type, prefix)) = context.resolve(&block_clause.query.query) {
+                let context = Context {
+                    resource_type,
+                    prefix,
+                    variables: context.variables.clone(),
+                };
+                collect_block(&block_clause.block, &context, checks);
+            }
+        }
+        GuardClause::WhenBlock(conditions, block, otherwise) => {
+            collect_when_conditions(conditions, context, checks);
+            collect_block(block, context, checks);
+            if let Some(otherwise) = otherwise {
+                collect_block(otherwise, context, checks);
+            }
+        }
+        GuardClause::NamedRule(_) | GuardClause::ParameterizedNamedRule(_) => {}
+    }
+}
+
+fn collect_rule_clause<'rules, 'loc>(
+    clause: &'rules RuleClause<'loc>,
+    context: &Context<'rules, 'loc>,
+    checks: &mut Vec<Check>,
+) {
+    match clause {
+        RuleClause::Clause(clause) => collect_guard_clause(clause, context, checks),
+        RuleClause::WhenBlock(conditions, block, otherwise) => {
+            collect_when_conditions(conditions, context, checks);
+            collect_block(block, context, checks);
+            if let Some(otherwise) = otherwise {
+                collect_block(otherwise, context, checks);
+            }
+        }
+        RuleClause::TypeBlock(type_block) => {
+            let context = Context {
+                resource_type: Some(type_block.type_name.clone()),
+                prefix: vec![],
+                variables: context.variables.clone(),
+            };
+            if let Some(conditions) = &type_block.conditions {
+                collect_when_conditions(conditions, &context, checks);
+            }
+            collect_block(&type_block.block, &context, checks);
+        }
+    }
+}
+
+fn literal(value: &PathAwareValue) -> Option<Value> {
+    match value {
+        PathAwareValue::Null(_) => Some(Value::Null),
+        PathAwareValue::String((_, s)) => Some(Value::from(s.as_str())),
+        PathAwareValue::Bool((_, b)) => Some(Value::from(*b)),
+        PathAwareValue::Int((_, i)) => Some(Value::from(*i)),
+        PathAwareValue::Float((_, f)) => Some(Value::from(*f)),
+        PathAwareValue::Char((_, c)) => Some(Value::from(c.to_string())),
+        PathAwareValue::List((_, list)) => list
+            .iter()
+            .map(literal)
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Sequence),
+        PathAwareValue::Map((_, map)) => map
+            .values
+            .iter()
+            .map(|(key, value)| literal(value).map(|value| (Value::from(key.as_str()), value)))
+            .collect::<Option<Mapping>>()
+            .map(Value::Mapping),
+        PathAwareValue::RangeInt((_, range)) => {
+            Some(Value::from(range.lower + (range.upper - range.lower) / 2))
+        }
+        PathAwareValue::RangeFloat((_, range)) => {
+            Some(Value::from(range.lower + (range.upper - range.lower) / 2.0))
+        }
+        PathAwareValue::Regex(_) | PathAwareValue::RangeChar(_) => None,
+    }
+}
+
+fn placeholder() -> Value {
+    Value::from(PLACEHOLDER)
+}
+
+//
+// a string matching the glob, the wildcards filled in with as little as possible
+//
+fn glob_example(glob: &str) -> String {
+    let mut example = String::with_capacity(glob.len());
+    let mut chars = glob.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {}
+            '?' => example.push('x'),
+            '\\' => example.push(chars.next().unwrap_or('\\')),
+            c => example.push(c),
+        }
+    }
+    example
+}
+
+fn offset(value: Option<&PathAwareValue>, by: i64) -> Value {
+    match value {
+        Some(PathAwareValue::Int((_, i))) => Value::from(i + by),
+        Some(PathAwareValue::Float((_, f))) => Value::from(f + by as f64),
+        _ => placeholder(),
+    }
+}
+
+//
+// value that satisfies the check, Omit when the check is satisfied by the property not being set
+//
+fn satisfying(check: &Check) -> Assignment {
+    let compare_with = check.compare_with.as_ref();
+    let value = match check.comparator {
+        (CmpOperator::Exists, true) => return Assignment::Omit,
+        (CmpOperator::Exists, false) => placeholder(),
+        (CmpOperator::Empty, false) => Value::Sequence(vec![]),
+        (CmpOperator::Empty, true) => Value::Sequence(vec![placeholder()]),
+        (CmpOperator::Eq | CmpOperator::UnorderedEq, false) => {
+            compare_with.and_then(literal).unwrap_or_else(placeholder)
+        }
+        (CmpOperator::Eq | CmpOperator::UnorderedEq, true) => match compare_with {
+            Some(PathAwareValue::Bool((_, b))) => Value::from(!b),
+            _ => placeholder(),
+        },
+        (CmpOperator::In, false) => match compare_with {
+            Some(PathAwareValue::List((_, list))) => list.iter().find_map(literal),
+            Some(value) => literal(value),
+            None => None,
+        }
+        .unwrap_or_else(placeholder),
+        (CmpOperator::In, true) => placeholder(),
+        (CmpOperator::Like, false) => match compare_with {
+            Some(PathAwareValue::String((_, glob))) => Value::from(glob_example(glob)),
+            _ => placeholder(),
+        },
+        (CmpOperator::Gt, false) | (CmpOperator::Le, true) => offset(compare_with, 1),
+        (CmpOperator::Lt, false) | (CmpOperator::Ge, true) => offset(compare_with, -1),
+        (CmpOperator::Ge, false)
+        | (CmpOperator::Le, false)
+        | (CmpOperator::Gt, true)
+        | (CmpOperator::Lt, true) => offset(compare_with, 0),
+        (CmpOperator::IsString, true) => Value::from(0),
+        (_, true) => placeholder(),
+        (CmpOperator::IsString, false) => placeholder(),
+        (CmpOperator::IsInt, false) => Value::from(0),
+        (CmpOperator::IsFloat, false) => Value::from(0.5),
+        (CmpOperator::IsBool, false) => Value::from(true),
+        (CmpOperator::IsList, false) => Value::Sequence(vec![]),
+        (CmpOperator::IsMap, false) => Value::Mapping(Mapping::new()),
+        (CmpOperator::IsNull, false) => Value::Null,
+    };
+    Assignment::Set(value)
+}
+
+//
+// value that violates the check, used to break the first clause of a rule for the FAIL case
+//
+fn violating(check: &Check) -> Assignment {
+    match satisfying(check) {
+        Assignment::Omit => Assignment::Set(placeholder()),
+        Assignment::Set(Value::Sequence(list)) if list.is_empty() => {
+            Assignment::Set(Value::Sequence(vec![placeholder()]))
+        }
+        Assignment::Set(_) => Assignment::Omit,
+    }
+}
+
+fn logical_id(resource_type: &str) -> String {
+    resource_type
+        .trim_start_matches("AWS::")
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+fn assign(target: &mut Value, path: &[Segment], assignment: &Assignment) {
+    let (first, rest) = match path.split_first() {
+        Some(split) => split,
+        None => {
+            if let Assignment::Set(value) = assignment {
+                *target = value.clone();
+            }
+            return;
+        }
+    };
+
+    let next = match first {
+        Segment::Key(key) => {
+            if !target.is_mapping() {
+                *target = Value::Mapping(Mapping::new());
+            }
+            let mapping = target.as_mapping_mut().unwrap();
+            if rest.is_empty() && *assignment == Assignment::Omit {
+                mapping.remove(key.as_str());
+                return;
+            }
+            mapping
+                .entry(Value::from(key.as_str()))
+                .or_insert(Value::Null)
+        }
+        Segment::Index => {
+            if !target.is_sequence() {
+                *target = Value::Sequence(vec![]);
+            }
+            let sequence = target.as_sequence_mut().unwrap();
+            if rest.is_empty() && *assignment == Assignment::Omit {
+                sequence.clear();
+                return;
+            }
+            if sequence.is_empty() {
+                sequence.push(Value::Null);
+            }
+            &mut sequence[0]
+        }
+    };
+
+    assign(next, rest, assignment);
+}
+
+fn build_input<'c>(assignments: impl IntoIterator<Item = (&'c Check, &'c Assignment)>) -> Value {
+    let mut root = Value::Mapping(Mapping::new());
+
+    for (check, assignment) in assignments {
+        match &check.resource_type {
+            Some(resource_type) => {
+                let id = logical_id(resource_type);
+                let resources = root
+                    .as_mapping_mut()
+                    .unwrap()
+                    .entry(Value::from("Resources"))
+                    .or_insert_with(|| Value::Mapping(Mapping::new()))
+                    .as_mapping_mut()
+                    .unwrap();
+
+                //
+                // an empty path refers to the resources of the type themselves, either they
+                // are expected to be present or absent from the template
+                //
+                if check.path.is_empty() {
+                    match assignment {
+                        Assignment::Set(Value::Sequence(list)) if list.is_empty() => {
+                            resources.remove(id.as_str());
+                            continue;
+                        }
+                        Assignment::Omit => {
+                            resources.remove(id.as_str());
+                            continue;
+                        }
+                        Assignment::Set(_) => {}
+                    }
+                }
+
+                let resource = resources.entry(Value::from(id)).or_insert_with(|| {
+                    let mut resource = Mapping::new();
+                    resource.insert(Value::from("Type"), Value::from(resource_type.as_str()));
+                    Value::Mapping(resource)
+                });
+                if !check.path.is_empty() {
+                    assign(resource, &check.path, assignment);
+                }
+            }
+            None => assign(&mut root, &check.path, assignment),
+        }
+    }
+
+    //
+    // type blocks can not be resolved against a template without any resources, a resource with
+    // no properties is added so that rules with type blocks are skipped instead
+    //
+    if let Some(resources) = root.get_mut("Resources").and_then(Value::as_mapping_mut) {
+        if resources.is_empty() {
+            let mut resource = Mapping::new();
+            resource.insert(
+                Value::from("Type"),
+                Value::from("AWS::CloudFormation::WaitConditionHandle"),
+            );
+            resources.insert(Value::from("WaitConditionHandle"), Value::Mapping(resource));
+        }
+    }
+
+    root
+}
+
+fn test_spec(rule_name: &str, name: &str, input: Value, expected: &str) -> TestSpec {
+    TestSpec {
+        name: Some(format!("{rule_name} {name}, {expected}")),
+        input,
+        expectations: TestExpectations {
+            rules: HashMap::from([(rule_name.to_string(), expected.to_string())]),
+        },
+        matrix: BTreeMap::new(),
+    }
+}
+
+pub(crate) fn generate_test_skeleton(rules_file: &RulesFile<'_>) -> Vec<TestSpec> {
+    let global = Context::default().with_assignments(&rules_file.assignments);
+    let mut specs = Vec::with_capacity(rules_file.guard_rules.len() * 2);
+
+    // private rules have no results of their own to expect
+    for rule in rules_file
+        .guard_rules
+        .iter()
+        .filter(|rule| !rule.annotations.private)
+    {
+        let context = global.with_assignments(&rule.block.assignments);
+        let mut checks = vec![];
+        if let Some(conditions) = &rule.conditions {
+            collect_when_conditions(conditions, &context, &mut checks);
+        }
+        for disjunctions in &rule.block.conjunctions {
+            if let Some(clause) = disjunctions.first() {
+                collect_rule_clause(clause, &context, &mut checks);
+            }
+        }
+
+        let assignments = checks.iter().map(satisfying).collect::<Vec<_>>();
+        specs.push(test_spec(
+            &rule.rule_name,
+            "compliant input",
+            build_input(checks.iter().zip(&assignments)),
+            "PASS",
+        ));
+
+        //
+        // the broken clause is applied last, so that it is not overwritten by the clauses
+        // that follow it and access properties underneath it
+        //
+        if let Some(index) = checks.iter().position(|check| !check.condition) {
+            let broken = violating(&checks[index]);
+            let others = checks
+                .iter()
+                .zip(&assignments)
+                .enumerate()
+                .filter(|(each, _)| *each != index)
+                .map(|(_, pair)| pair);
+            specs.push(test_spec(
+                &rule.rule_name,
+                "non-compliant input",
+                build_input(others.chain([(&checks[index], &broken)])),
+                "FAIL",
+            ));
+        }
+    }
+
+    specs
+}
+
+#[cfg(test)]
+#[path = "test_skeleton_tests.rs"]
+mod test_skeleton_tests;
@@ -0,0 +1,120 @@
+use super::*;
+use crate::rules::parser::{rules_file, Span};
+use pretty_assertions::assert_eq;
+
+fn skeleton(rules: &str) -> Vec<TestSpec> {
+    let rules = rules_file(Span::new_extra(rules, "")).unwrap().unwrap();
+    generate_test_skeleton(&rules)
+}
+
+#[test]
+fn test_skeleton_for_variable_with_type_filter() {
+    let specs = skeleton(
+        r#"
+        let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+        rule S3_BUCKET_VERSIONING_ENABLED when %buckets !empty {
+            %buckets.Properties.VersioningConfiguration.Status == "Enabled"
+        }
+        "#,
+    );
+
+    let expected: Value = serde_yaml::from_str(
+        r#"
+        Resources:
+          S3Bucket:
+            Type: AWS::S3::Bucket
+            Properties:
+              VersioningConfiguration:
+                Status: Enabled
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(2, specs.len());
+    assert_eq!(
+        Some("S3_BUCKET_VERSIONING_ENABLED compliant input, PASS"),
+        specs[0].name.as_deref()
+    );
+    assert_eq!(expected, specs[0].input);
+    assert_eq!(
+        Some(&String::from("PASS")),
+        specs[0]
+            .expectations
+            .rules
+            .get("S3_BUCKET_VERSIONING_ENABLED")
+    );
+
+    let expected: Value = serde_yaml::from_str(
+        r#"
+        Resources:
+          S3Bucket:
+            Type: AWS::S3::Bucket
+            Properties:
+              VersioningConfiguration: {}
+        "#,
+    )
+    .unwrap();
+    assert_eq!(expected, specs[1].input);
+    assert_eq!(
+        Some(&String::from("FAIL")),
+        specs[1]
+            .expectations
+            .rules
+            .get("S3_BUCKET_VERSIONING_ENABLED")
+    );
+}
+
+#[test]
+fn test_skeleton_for_type_block_and_builtins() {
+    let specs = skeleton(
+        r#"
+        rule volumes_encrypted {
+            AWS::EC2::Volume {
+                Properties.Encrypted == true
+                Properties.Size IN r[50, 150]
+                Properties.Tags[*].Key exists
+            }
+        }
+        forbid_resource_type(/AWS::IAM::User/)
+        "#,
+    );
+
+    let expected: Value = serde_yaml::from_str(
+        r#"
+        Resources:
+          EC2Volume:
+            Type: AWS::EC2::Volume
+            Properties:
+              Encrypted: true
+              Size: 100
+              Tags:
+                - Key: REPLACE_ME
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(4, specs.len());
+    assert_eq!(expected, specs[0].input);
+    assert!(specs[1].input["Resources"]["EC2Volume"]["Properties"]
+        .get("Encrypted")
+        .is_none());
+
+    let expected: Value = serde_yaml::from_str(
+        r#"
+        Resources:
+          WaitConditionHandle:
+            Type: AWS::CloudFormation::WaitConditionHandle
+        "#,
+    )
+    .unwrap();
+    assert_eq!(expected, specs[2].input);
+    let expected: Value = serde_yaml::from_str(
+        r#"
+        Resources:
+          IAMUser:
+            Type: AWS::IAM::User
+        "#,
+    )
+    .unwrap();
+    assert_eq!(expected, specs[3].input);
+}
@@ -0,0 +1,295 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::commands::test::{TestExpectations, TestSpec};
+use crate::rules::errors::Error;
+use crate::rules::Result;
+
+/// the YAML merge key, `<<: *fixture` copies the keys of the fixture the mapping does not set
+const MERGE_KEY: &str = "<<";
+
+/// a test file written as a mapping rather than a list of test cases, sharing an input between
+/// its test cases
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestSuite {
+    /// not evaluated, a place to declare the YAML anchors the test cases refer to
+    #[serde(default, rename = "fixtures")]
+    _fixtures: IgnoredAny,
+    /// the input every test case patches with its own
+    #[serde(default)]
+    base: Option<Value>,
+    cases: Vec<TestSpec>,
+}
+
+/// reads the test cases of a test file, in YAML or JSON. The file is either a list of test cases,
+/// or a mapping of
+/// - `fixtures`, ignored, to declare YAML anchors in
+/// - `base`, an input each test case patches with its `input` as a JSON merge patch (RFC 7396)
+/// - `cases`, the test cases
+///
+/// A test case with a `matrix` is expanded into one test case for every combination of the
+/// values of its parameters, see [`expand_matrix`]
+///
+/// This function will return an error if the file is not valid YAML or JSON, or not test cases
+pub(crate) fn read_test_specs(data: &str, path: &Path) -> Result<Vec<TestSpec>> {
+    let parse_error = |e: String| {
+        Error::ParseError(format!(
+            "Unable to process data in file {}, Error {}",
+            path.display(),
+            e
+        ))
+    };
+
+    let value = match serde_yaml::from_str::<Value>(data) {
+        Ok(value) => value,
+        Err(_) => serde_json::from_str::<Value>(data).map_err(|e| parse_error(e.to_string()))?,
+    };
+
+    let (base, cases) = match apply_merge_keys(value) {
+        value @ Value::Mapping(_) => {
+            let suite = serde_yaml::from_value::<TestSuite>(value)
+                .map_err(|e| parse_error(e.to_string()))?;
+            (suite.base, suite.cases)
+        }
+        value => (
+            None,
+            serde_yaml::from_value::<Vec<TestSpec>>(value)
+                .map_err(|e| parse_error(e.to_string()))?,
+        ),
+    };
+
+    let mut specs = vec![];
+    for mut spec in cases {
+        if let Some(base) = &base {
+            spec.input = merge_patch(base.clone(), spec.input);
+        }
+        specs.extend(expand_matrix(spec).map_err(parse_error)?);
+    }
+
+    Ok(specs)
+}
+
+/// one test case for every combination of the values of the parameters of the matrix, each
+/// `${parameter}` in the input, the name and the expected statuses replaced by its value. A
+/// string that is only `${parameter}` is replaced by the value itself, lists and mappings
+/// included, and `${parameter.key}` refers to a key of a mapping value. Test cases without a
+/// name are named after the values of their parameters
+///
+/// Other `${...}`, e.g. in the `Fn::Sub` of a template, are left as they are
+fn expand_matrix(spec: TestSpec) -> std::result::Result<Vec<TestSpec>, String> {
+    if spec.matrix.is_empty() {
+        return Ok(vec![spec]);
+    }
+
+    let mut combinations = vec![BTreeMap::new()];
+    for (parameter, values) in &spec.matrix {
+        if values.is_empty() {
+            return Err(format!(
+                "the matrix parameter {parameter} of test case {} has no values",
+                spec.name.as_deref().unwrap_or_default()
+            ));
+        }
+
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination| {
+                values.iter().map(move |value| {
+                    let mut combination = combination.clone();
+                    combination.insert(parameter.as_str(), value);
+                    combination
+                })
+            })
+            .collect();
+    }
+
+    Ok(combinations
+        .into_iter()
+        .map(|parameters| {
+            let values = parameters
+                .iter()
+                .map(|(parameter, value)| format!("{parameter}={}", scalar(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let name = match spec.name.as_deref() {
+                Some(name) => match substitute_str(name, &parameters) {
+                    substituted if substituted != name => substituted,
+                    _ => format!("{name} [{values}]"),
+                },
+                None => format!("[{values}]"),
+            };
+
+            TestSpec {
+                name: Some(name),
+                input: substitute(spec.input.clone(), &parameters),
+                expectations: TestExpectations {
+                    rules: spec
+                        .expectations
+                        .rules
+                        .iter()
+                        .map(|(rule, status)| (rule.clone(), substitute_str(status, &parameters)))
+                        .collect(),
+                },
+                matrix: BTreeMap::new(),
+            }
+        })
+        .collect())
+}
+
+fn substitute(value: Value, parameters: &BTreeMap<&str, &Value>) -> Value {
+    match value {
+        Value::String(string) => match placeholder_value(&string, parameters) {
+            Some(value) => value,
+            None => Value::String(substitute_str(&string, parameters)),
+        },
+        Value::Sequence(values) => Value::Sequence(
+            values
+                .into_iter()
+                .map(|value| substitute(value, parameters))
+                .collect(),
+        ),
+        Value::Mapping(mapping) => Value::Mapping(
+            mapping
+                .into_iter()
+                .map(|(key, value)| (key, substitute(value, parameters)))
+                .collect(),
+        ),
+        Value::Tagged(mut tagged) => {
+            tagged.value = substitute(tagged.value, parameters);
+            Value::Tagged(tagged)
+        }
+        value => value,
+    }
+}
+
+/// the value of the parameter when the string is only `${parameter}`
+fn placeholder_value(string: &str, parameters: &BTreeMap<&str, &Value>) -> Option<Value> {
+    let reference = string.strip_prefix("${")?.strip_suffix('}')?;
+    lookup(reference, parameters).cloned()
+}
+
+fn substitute_str(string: &str, parameters: &BTreeMap<&str, &Value>) -> String {
+    let mut substituted = String::with_capacity(string.len());
+    let mut rest = string;
+    while let Some(start) = rest.find("${") {
+        let (before, from) = rest.split_at(start);
+        substituted.push_str(before);
+        match from.find('}') {
+            Some(end) => {
+                match lookup(&from[2..end], parameters) {
+                    Some(value) => substituted.push_str(&scalar(value)),
+                    None => substituted.push_str(&from[..=end]),
+                }
+                rest = &from[end + 1..];
+            }
+            None => {
+                substituted.push_str(from);
+                rest = "";
+            }
+        }
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+/// the value of `parameter` or `parameter.key.key`
+fn lookup<'value>(
+    reference: &str,
+    parameters: &BTreeMap<&str, &'value Value>,
+) -> Option<&'value Value> {
+    let mut keys = reference.split('.');
+    let value = *parameters.get(keys.next()?)?;
+    keys.try_fold(value, |value, key| value.get(key))
+}
+
+/// the value as it is written in a string, mappings and lists as JSON
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::Null => String::from("null"),
+        Value::Bool(bool) => bool.to_string(),
+        Value::Number(number) => number.to_string(),
+        value => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
+
+/// the target patched as a JSON merge patch (RFC 7396), the mappings of the patch are merged
+/// into the ones of the target key by key, a null removes the key, anything else replaces the
+/// value. A null patch, a test case without an input, leaves the target as it is
+pub(crate) fn merge_patch(target: Value, patch: Value) -> Value {
+    match patch {
+        Value::Null => target,
+        Value::Mapping(patch) => {
+            let mut target = match target {
+                Value::Mapping(target) => target,
+                _ => Mapping::new(),
+            };
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                    continue;
+                }
+                match target.get_mut(&key) {
+                    Some(existing) => {
+                        let merged = merge_patch(std::mem::replace(existing, Value::Null), value);
+                        *existing = merged;
+                    }
+                    None => {
+                        target.insert(key, merge_patch(Value::Null, value));
+                    }
+                }
+            }
+            Value::Mapping(target)
+        }
+        patch => patch,
+    }
+}
+
+/// copies the keys of the mappings of `<<` into the mapping holding it, the keys it sets itself
+/// take precedence
+pub(crate) fn apply_merge_keys(value: Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut merged = Mapping::new();
+            let mut inherited = vec![];
+            for (key, value) in mapping {
+                let value = apply_merge_keys(value);
+                match key.as_str() {
+                    Some(MERGE_KEY) => match value {
+                        Value::Sequence(values) => inherited.extend(values),
+                        value => inherited.push(value),
+                    },
+                    _ => {
+                        merged.insert(key, value);
+                    }
+                }
+            }
+            for value in inherited {
+                if let Value::Mapping(mapping) = value {
+                    for (key, value) in mapping {
+                        if !merged.contains_key(&key) {
+                            merged.insert(key, value);
+                        }
+                    }
+                }
+            }
+            Value::Mapping(merged)
+        }
+        Value::Sequence(values) => {
+            Value::Sequence(values.into_iter().map(apply_merge_keys).collect())
+        }
+        Value::Tagged(mut tagged) => {
+            tagged.value = apply_merge_keys(tagged.value);
+            Value::Tagged(tagged)
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+#[path = "test_spec_tests.rs"]
+mod test_spec_tests;
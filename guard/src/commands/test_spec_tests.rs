@@ -0,0 +1,240 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+fn yaml(content: &str) -> Value {
+    serde_yaml::from_str(content).unwrap()
+}
+
+fn read(content: &str) -> Vec<TestSpec> {
+    read_test_specs(content, Path::new("tests/s3_tests.yaml")).unwrap()
+}
+
+#[test]
+fn test_read_test_specs_list() {
+    let specs = read(
+        r#"
+- name: versioned
+  input:
+    Resources: {}
+  expectations:
+    rules:
+      s3_versioned: SKIP
+"#,
+    );
+
+    assert_eq!(1, specs.len());
+    assert_eq!(Some(String::from("versioned")), specs[0].name);
+    assert_eq!(yaml("Resources: {}"), specs[0].input);
+}
+
+#[test]
+fn test_read_test_specs_base_and_fixtures() {
+    let specs = read(
+        r#"
+fixtures:
+  bucket: &bucket
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketName: logs
+base:
+  Resources:
+    Bucket: *bucket
+cases:
+  - name: versioned
+    input:
+      Resources:
+        Bucket:
+          Properties:
+            VersioningConfiguration:
+              Status: Enabled
+    expectations:
+      rules:
+        s3_versioned: PASS
+  - name: no bucket name
+    input:
+      Resources:
+        Bucket:
+          Properties:
+            BucketName: null
+        Other:
+          <<: *bucket
+          Type: AWS::S3::AccessPoint
+    expectations:
+      rules:
+        s3_versioned: FAIL
+  - name: base only
+    expectations:
+      rules:
+        s3_versioned: FAIL
+"#,
+    );
+
+    assert_eq!(3, specs.len());
+    assert_eq!(
+        yaml(
+            r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketName: logs
+      VersioningConfiguration:
+        Status: Enabled
+"#
+        ),
+        specs[0].input
+    );
+    assert_eq!(
+        yaml(
+            r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties: {}
+  Other:
+    Type: AWS::S3::AccessPoint
+    Properties:
+      BucketName: logs
+"#
+        ),
+        specs[1].input
+    );
+    assert_eq!(
+        yaml(
+            r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketName: logs
+"#
+        ),
+        specs[2].input
+    );
+}
+
+#[test]
+fn test_read_test_specs_matrix() {
+    let specs = read(
+        r#"
+base:
+  Resources:
+    Bucket:
+      Type: AWS::S3::Bucket
+      Properties:
+        VersioningConfiguration: ${versioning}
+        BucketName: !Sub "${AWS::StackName}-${suffix}"
+cases:
+  - name: versioning ${versioning.Status}
+    matrix:
+      versioning:
+        - Status: Enabled
+          expected: PASS
+        - Status: Suspended
+          expected: FAIL
+      suffix: [logs, data]
+    expectations:
+      rules:
+        s3_versioned: ${versioning.expected}
+"#,
+    );
+
+    assert_eq!(
+        vec![
+            "versioning Enabled",
+            "versioning Suspended",
+            "versioning Enabled",
+            "versioning Suspended"
+        ],
+        specs
+            .iter()
+            .map(|spec| spec.name.as_deref().unwrap())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["PASS", "FAIL", "PASS", "FAIL"],
+        specs
+            .iter()
+            .map(|spec| spec.expectations.rules["s3_versioned"].as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        yaml(
+            r#"
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      VersioningConfiguration:
+        Status: Suspended
+        expected: FAIL
+      BucketName: !Sub "${AWS::StackName}-data"
+"#
+        ),
+        specs[3].input
+    );
+}
+
+#[test]
+fn test_read_test_specs_matrix_names() {
+    let specs = read(
+        r#"
+- name: encryption
+  matrix:
+    algorithm: [AES256, "aws:kms"]
+  input:
+    Algorithm: ${algorithm}
+  expectations:
+    rules:
+      s3_encrypted: PASS
+- matrix:
+    count: [1]
+  input: {}
+  expectations:
+    rules:
+      s3_encrypted: SKIP
+"#,
+    );
+
+    assert_eq!(
+        vec![
+            "encryption [algorithm=AES256]",
+            "encryption [algorithm=aws:kms]",
+            "[count=1]"
+        ],
+        specs
+            .iter()
+            .map(|spec| spec.name.as_deref().unwrap())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_read_test_specs_errors() {
+    for content in [
+        "cases: [",
+        "base: {}\ncase: []\n",
+        "- name: empty\n  matrix:\n    algorithm: []\n  input: {}\n  expectations:\n    rules: {}\n",
+    ] {
+        let error = read_test_specs(content, Path::new("tests/s3_tests.yaml")).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Unable to process data in file tests/s3_tests.yaml"),
+            "{error}"
+        );
+    }
+}
+
+#[test]
+fn test_merge_patch() {
+    assert_eq!(
+        yaml("{a: {b: 2, d: 4}, e: [3]}"),
+        merge_patch(
+            yaml("{a: {b: 1, c: 3}, e: [1, 2]}"),
+            yaml("{a: {b: 2, c: null, d: 4}, e: [3]}")
+        )
+    );
+    assert_eq!(yaml("{a: 1}"), merge_patch(yaml("{a: 1}"), Value::Null));
+    assert_eq!(yaml("[1]"), merge_patch(yaml("{a: 1}"), yaml("[1]")));
+}
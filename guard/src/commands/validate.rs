@@ -1,44 +1,87 @@
 use std::cmp;
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::fmt::Debug;
 use std::fs::File;
 use std::io::{BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
 
 use clap::{Args, ValueEnum};
 use colored::*;
 use enumflags2::BitFlags;
 use serde::{Deserialize, Serialize};
 
-use crate::commands::files::{alphabetical, iterate_over, last_modified, walk_dir};
-use crate::commands::reporters::validate::structured::StructuredEvaluator;
+use crate::commands::adapters::adapt_data_files;
+use crate::commands::baseline::Baseline;
+use crate::commands::config::{find_config, GuardConfig, CONFIG_FILES};
+use crate::commands::files::{
+    alphabetical, is_glob, iterate_over, last_modified, read_file_content, walk_dir, walk_glob,
+    WalkOptions,
+};
+use crate::commands::json_refs::resolve_json_refs;
+use crate::commands::metrics::{export, MetricsSink};
+use crate::commands::progress::Progress;
+use crate::commands::remote::{is_remote, Downloads, Header};
+use crate::commands::reporters::schema::{
+    OUTPUT_SCHEMA_VERSION, OUTPUT_SCHEMA_VERSION_V2, VALIDATE_OUTPUT_SCHEMA,
+    VALIDATE_OUTPUT_SCHEMA_V2,
+};
+use crate::commands::reporters::validate::structured::{ConsoleOutput, StructuredEvaluator};
 use crate::commands::reporters::validate::summary_table::{self, SummaryType};
 use crate::commands::reporters::validate::tf::TfAware;
 use crate::commands::reporters::validate::{cfn, generic_summary};
+use crate::commands::stack::stack_data_files;
+use crate::commands::summary::Summary;
+use crate::commands::template_conditions::prune_conditional_resources;
+use crate::commands::template_parameters::resolve_parameters;
 use crate::commands::tracker::StatusContext;
 use crate::commands::{
     Executable, ALPHABETICAL, DATA_FILE_SUPPORTED_EXTENSIONS, ERROR_STATUS_CODE,
-    FAILURE_STATUS_CODE, LAST_MODIFIED, PAYLOAD, PRINT_JSON, REQUIRED_FLAGS, RULES,
-    RULE_FILE_SUPPORTED_EXTENSIONS, SHOW_SUMMARY, STRUCTURED, SUCCESS_STATUS_CODE, TYPE, VERBOSE,
+    FAILURE_STATUS_CODE, FAIL_ON, LAST_MODIFIED, OUTPUT_FILE, PAYLOAD, PRINT_JSON, REQUIRED_FLAGS,
+    RULES, RULE_FILE_SUPPORTED_EXTENSIONS, RULE_PACK_EXTENSION, SHOW_SUMMARY, STRUCTURED,
+    SUCCESS_STATUS_CODE, TYPE, VERBOSE,
 };
 use crate::rules::errors::{Error, InternalError};
 use crate::rules::eval::eval_rules_file;
 use crate::rules::eval_context::{root_scope, EventRecord};
-use crate::rules::exprs::RulesFile;
+use crate::rules::exprs::{RuleSelection, RulesFile};
+use crate::rules::functions::custom::FunctionRegistry;
+use crate::rules::limits::InputLimits;
+use crate::rules::messages::MessageCatalog;
+use crate::rules::pack::{read_pack, verify_pack, PackedRulesFile, SIGNATURE_EXTENSION};
+use crate::rules::parser::{syntax_errors_after, Span};
 use crate::rules::path_value::traversal::Traversal;
-use crate::rules::path_value::PathAwareValue;
+use crate::rules::path_value::{MapValue, PathAwareValue};
+use crate::rules::values::read_from_with_max_depth;
 use crate::rules::{Result, Status};
 use crate::utils::reader::Reader;
-use crate::utils::writer::Writer;
+use crate::utils::writer::{WriteBuffer, Writer};
 use wasm_bindgen::prelude::*;
 
+/// the value of `data` that reads the template from stdin
+pub(crate) const STDIN_DATA: &str = "-";
+
+/// the id of the `global-rules` argument
+const GLOBAL_RULES: &str = "global-rules";
+
 #[derive(Eq, Clone, Debug, PartialEq)]
 pub(crate) struct DataFile {
     pub(crate) content: String,
     pub(crate) path_value: PathAwareValue,
     pub(crate) name: String,
+    /// the parameters file found next to the data file, exposed to the rules as `%PARAMETERS_FILE`
+    pub(crate) parameters_file: Option<Rc<PathAwareValue>>,
+    /// the effective values of the parameters of a template, exposed as `%PARAMETER_VALUES`
+    /// when they are resolved
+    pub(crate) parameter_values: Option<Rc<PathAwareValue>>,
+    /// the `--vars` file shared by every data file, exposed as `%VARS`
+    pub(crate) vars: Option<Rc<PathAwareValue>>,
+    /// the resources of the deployed stack the data file was fetched from with `--from-stack`,
+    /// exposed as `%STACK_RESOURCES`
+    pub(crate) stack_resources: Option<Rc<PathAwareValue>>,
 }
 
 #[derive(Copy, Eq, Clone, Debug, PartialEq)]
@@ -50,7 +93,7 @@ pub(crate) enum Type {
 impl From<&str> for Type {
     fn from(value: &str) -> Self {
         match value {
-            "CFNTemplate" => Type::CFNTemplate,
+            "cfn" | "CFNTemplate" | "changeset" | "changeset-before" => Type::CFNTemplate,
             _ => Type::Generic,
         }
     }
@@ -66,6 +109,8 @@ pub enum OutputFormatType {
     YAML,
     Junit,
     Sarif,
+    Csv,
+    Tsv,
 }
 
 #[wasm_bindgen]
@@ -79,6 +124,81 @@ pub enum ShowSummaryType {
     None,
 }
 
+/// .
+/// Controls which outcomes of `validate` result in a non-zero exit code
+#[wasm_bindgen]
+#[derive(Copy, Eq, Clone, Debug, PartialEq, ValueEnum, Serialize, Default, Deserialize)]
+pub enum FailOn {
+    /// violated rules and rules that could not be evaluated
+    #[default]
+    Violations,
+    /// only rules that could not be evaluated
+    Errors,
+    /// violated rules, rules that could not be evaluated and failing `@severity(warning)` rules
+    Warnings,
+    /// never, the exit code is always 0 unless the command is not able to run
+    None,
+}
+
+/// How the results of `validate` are organized in the console, json and yaml output
+#[wasm_bindgen]
+#[derive(Copy, Eq, Clone, Debug, PartialEq, ValueEnum, Serialize, Default, Deserialize)]
+pub enum GroupBy {
+    /// a section for each data file, with the results of every rule against it
+    #[default]
+    File,
+    /// a group for each rule, with its failures across every data file
+    Rule,
+    /// a group for each type of resource the rules failed on, e.g. AWS::S3::Bucket
+    ResourceType,
+    /// a group for each severity of the failed rules, error or warning
+    Severity,
+}
+
+/// The layout of the structured json and yaml output of `validate`, each version keeping the
+/// fields and their meaning so consumers pinned to one keep working
+#[wasm_bindgen]
+#[derive(Copy, Eq, Clone, Debug, PartialEq, ValueEnum, Serialize, Default, Deserialize)]
+pub enum OutputVersion {
+    /// schema 1.0, the failures of each data file nested the way the rules were evaluated
+    #[default]
+    V1,
+    /// schema 2.0, the failures of each data file flattened to violations with their rule,
+    /// severity, resource, path, line and column, the baselined and suppressed ones always listed
+    V2,
+}
+
+impl OutputVersion {
+    /// the `schema_version` of each record of the output
+    pub(crate) fn schema_version(self) -> &'static str {
+        match self {
+            OutputVersion::V1 => OUTPUT_SCHEMA_VERSION,
+            OutputVersion::V2 => OUTPUT_SCHEMA_VERSION_V2,
+        }
+    }
+
+    /// the JSON Schema the output follows
+    pub(crate) fn schema(self) -> &'static str {
+        match self {
+            OutputVersion::V1 => VALIDATE_OUTPUT_SCHEMA,
+            OutputVersion::V2 => VALIDATE_OUTPUT_SCHEMA_V2,
+        }
+    }
+}
+
+impl FailOn {
+    /// the exit code of the run, given the one the evaluation resulted in and its summary
+    pub(crate) fn exit_code(self, exit_code: i32, summary: &Summary) -> i32 {
+        match (self, exit_code) {
+            (FailOn::None, _) | (FailOn::Errors, FAILURE_STATUS_CODE) => SUCCESS_STATUS_CODE,
+            (FailOn::Warnings, SUCCESS_STATUS_CODE) if summary.rules_warned > 0 => {
+                FAILURE_STATUS_CODE
+            }
+            _ => exit_code,
+        }
+    }
+}
+
 impl From<&str> for ShowSummaryType {
     fn from(value: &str) -> Self {
         match value {
@@ -96,6 +216,141 @@ impl OutputFormatType {
     pub(crate) fn is_structured(&self) -> bool {
         !matches!(self, Self::SingleLineSummary)
     }
+
+    /// report formats meant to be consumed by CI systems, these can be produced without the
+    /// `structured` flag alongside the regular console output
+    pub(crate) fn is_report(&self) -> bool {
+        matches!(self, Self::Junit | Self::Sarif | Self::Csv | Self::Tsv)
+    }
+}
+
+/// an output format, optionally paired with the path of the file it is written to, parsed from
+/// `format` or `format=path`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct OutputTarget {
+    pub(crate) format: OutputFormatType,
+    pub(crate) path: Option<String>,
+}
+
+impl FromStr for OutputTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        let (format, path) = match value.split_once('=') {
+            Some((_, "")) => return Err(format!("missing file path for output `{value}`")),
+            Some((format, path)) => (format, Some(path.to_string())),
+            None => (value, None),
+        };
+
+        Ok(OutputTarget {
+            format: OutputFormatType::from_str(format, true)?,
+            path,
+        })
+    }
+}
+
+/// selects the resources of a template to evaluate, parsed from `Attribute==glob` to match one of
+/// the attributes of a resource, e.g. `Type==AWS::EC2::*`, or from a glob on its logical id
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ResourceSelector {
+    pub(crate) attribute: Option<String>,
+    pub(crate) pattern: String,
+}
+
+impl FromStr for ResourceSelector {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        let (attribute, pattern) = match value.split_once("==") {
+            Some((attribute, pattern)) => (Some(attribute.trim()), pattern.trim()),
+            None => (None, value.trim()),
+        };
+
+        if pattern.is_empty() || attribute.map_or(false, str::is_empty) {
+            return Err(format!(
+                "invalid resource selector `{value}`, expected Attribute==pattern or a logical id pattern"
+            ));
+        }
+
+        Ok(ResourceSelector {
+            attribute: attribute.map(String::from),
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
+impl ResourceSelector {
+    fn matches(&self, logical_id: &str, resource: &PathAwareValue) -> bool {
+        match (&self.attribute, resource) {
+            (None, _) => glob_match(&self.pattern, logical_id),
+            (Some(attribute), PathAwareValue::Map((_, map))) => matches!(
+                map.values.get(attribute),
+                Some(PathAwareValue::String((_, value))) if glob_match(&self.pattern, value)
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// matches text against a pattern where `*` matches any sequence of characters and `?` any single one
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text) = (
+        pattern.chars().collect::<Vec<_>>(),
+        text.chars().collect::<Vec<_>>(),
+    );
+    let (mut p, mut t) = (0, 0);
+    // position of the last `*` seen in the pattern, and of the text it was matched at
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// drops the resources of a template that no selector matches
+fn retain_resources(value: &mut PathAwareValue, selectors: &[ResourceSelector]) {
+    let resources = match value {
+        PathAwareValue::Map((_, map)) => match map.values.get_mut("Resources") {
+            Some(PathAwareValue::Map((_, resources))) => resources,
+            _ => return,
+        },
+        _ => return,
+    };
+
+    let MapValue { keys, values } = resources;
+    values.retain(|logical_id, resource| {
+        selectors
+            .iter()
+            .any(|selector| selector.matches(logical_id, resource))
+    });
+    keys.retain(
+        |key| matches!(key, PathAwareValue::String((_, name)) if values.contains_key(name)),
+    );
+}
+
+impl From<OutputFormatType> for OutputTarget {
+    fn from(format: OutputFormatType) -> Self {
+        OutputTarget { format, path: None }
+    }
 }
 
 impl From<&str> for OutputFormatType {
@@ -105,6 +360,8 @@ impl From<&str> for OutputFormatType {
             "json" => OutputFormatType::JSON,
             "junit" => OutputFormatType::Junit,
             "sarif" => OutputFormatType::Sarif,
+            "csv" => OutputFormatType::Csv,
+            "tsv" => OutputFormatType::Tsv,
             _ => OutputFormatType::YAML,
         }
     }
@@ -141,9 +398,8 @@ pub(crate) trait Reporter: Debug {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Args)]
-#[clap(group=clap::ArgGroup::new(REQUIRED_FLAGS).args([RULES.0, PAYLOAD.0]).required(true))]
+#[clap(group=clap::ArgGroup::new(REQUIRED_FLAGS).args([RULES.0, GLOBAL_RULES, PAYLOAD.0]))]
 #[clap(about=ABOUT)]
-#[clap(arg_required_else_help = true)]
 /// .
 /// The Validate command evaluates rules against data files to determine success or failure
 pub struct Validate {
@@ -151,21 +407,118 @@ pub struct Validate {
     /// a list of paths that point to rule files, or a directory containing rule files on a local machine. Only files that end with .guard or .ruleset will be evaluated
     /// conflicts with payload
     pub(crate) rules: Vec<String>,
+    #[arg(name=GLOBAL_RULES, long, help=GLOBAL_RULES_HELP, num_args=0.., conflicts_with=PAYLOAD.0)]
+    /// a list of paths that point to rule files, or directories containing rule files, evaluated
+    /// once against every data file together after the rules are evaluated against each of them
+    /// conflicts with payload
+    pub(crate) global_rules: Vec<String>,
     #[arg(short, long, help=DATA_HELP, num_args=0.., conflicts_with=PAYLOAD.0)]
     /// a list of paths that point to data files, or a directory containing data files  for the rules to be evaluated against. Only JSON, or YAML files will be used
     /// conflicts with payload
     pub(crate) data: Vec<String>,
+    #[arg(long, help=FROM_STACK_HELP, conflicts_with=PAYLOAD.0)]
+    /// names of deployed CloudFormation stacks whose templates are evaluated along with the data
+    /// files, fetched with the credentials of the environment. Requires the aws feature
+    /// conflicts with payload
+    pub(crate) from_stack: Vec<String>,
     #[arg(short, long, help=INPUT_PARAMETERS_HELP, num_args=0..)]
     /// a list of paths that point to data files, or a directory containing data files to be merged with the data argument and then the  rules will be evaluated against them. Only JSON, or YAML files will be used
     pub(crate) input_params: Vec<String>,
-    #[arg(name=TYPE.0, short, long, help=TEMPLATE_TYPE_HELP, value_parser=TEMPLATE_TYPE)]
-    #[deprecated(since = "3.0.0", note = "this field does not get evaluated")]
+    #[arg(long, help=VARS_HELP)]
+    /// a JSON or YAML file of variables the rules read as `%VARS`, not merged into the data
+    /// default is None
+    pub(crate) vars: Option<String>,
+    #[arg(long, help=BASELINE_HELP)]
+    /// a JSON file of the violations accepted as they are, the failures made up only of these
+    /// are reported as baselined and do not affect the exit code
+    /// can only be used together with the `structured` attribute
+    /// default is None
+    pub(crate) baseline: Option<String>,
+    #[arg(long, help=UPDATE_BASELINE_HELP)]
+    /// regenerate the baseline file from the violations of this run
+    /// default is false
+    pub(crate) update_baseline: bool,
+    #[arg(long, help=PARAMETERS_FILE_SUFFIX_HELP, default_value=DEFAULT_PARAMETERS_FILE_SUFFIX)]
+    /// the suffix of the parameters file paired with a data file, an empty suffix turns the pairing off
+    /// default is .params.json
+    pub(crate) parameters_file_suffix: String,
+    #[arg(long, help=RESOLVE_PARAMETERS_HELP)]
+    /// resolve `Ref`s to the parameters of a template to their values, the `Default` of a parameter
+    /// unless the input parameters or the parameters file provide one
+    /// default is false
+    pub(crate) resolve_parameters: bool,
+    #[arg(long, help=EVALUATE_CONDITIONS_HELP)]
+    /// evaluate the `Conditions` of a template against its parameter values and leave out the
+    /// resources whose `Condition` is false, the decisions are printed with `verbose`
+    /// default is false
+    pub(crate) evaluate_conditions: bool,
+    #[arg(long, help=RESOLVE_JSON_REFS_HELP)]
+    /// replace the `$ref`s of JSON documents, e.g. OpenAPI specifications, pointing within the
+    /// document with the values they point to
+    /// default is false
+    pub(crate) resolve_json_refs: bool,
+    #[arg(name=TYPE.0, short, long, help=TEMPLATE_TYPE_HELP)]
+    /// the type of the data files, which selects how they are adapted before the rules are
+    /// evaluated against them: cfn, k8s, tf, generic or the name of a registered input adapter
+    /// default is None, which detects the type of each data file
     pub(crate) template_type: Option<String>,
-    #[arg(short, long, help=OUTPUT_FORMAT_HELP, value_enum, default_value_t=OutputFormatType::SingleLineSummary)]
-    /// Specify the format in which the output should be displayed
+    #[arg(name="output-format", short, long="output-format", help=VALIDATE_OUTPUT_FORMAT_HELP)]
+    /// Specify the formats in which the output should be displayed, as `format` or `format=path`
     /// default is single-line-summary
-    /// if junit is used, `structured` attributed must be set to true
-    pub(crate) output_format: OutputFormatType,
+    /// at most one format is written to stdout, the ones paired with a path are written to that file
+    /// if junit, sarif, csv or tsv is used without the `structured` attribute, the console output
+    /// is written to stderr and the report to stdout, or to `output_file` when set
+    pub(crate) outputs: Vec<OutputTarget>,
+    #[arg(name=OUTPUT_FILE.0, short=OUTPUT_FILE.1, long, help=OUTPUT_FILE_HELP)]
+    /// path of a file the structured, junit, sarif, csv or tsv output is written to instead of stdout
+    /// can only be used together with the `structured` attribute, or when `output_format` is one of
+    /// the report formats
+    /// default is None
+    pub(crate) output_file: Option<String>,
+    #[arg(name="fail-on", short=FAIL_ON.1, long, help=FAIL_ON_HELP, value_enum, default_value_t=FailOn::Violations)]
+    /// Controls which outcomes result in a non-zero exit code, violations (default), errors, warnings or none
+    pub(crate) fail_on: FailOn,
+    #[arg(long, help=GROUP_BY_HELP, value_enum, default_value_t=GroupBy::File, conflicts_with_all=vec![VERBOSE.0, PRINT_JSON.0])]
+    /// how the console, json and yaml output are organized, by data file (default), by rule, by
+    /// resource type or by severity. Grouped output lists the failures of each group, the groups
+    /// with the most failures first
+    pub(crate) group_by: GroupBy,
+    #[arg(long, help=MAX_FINDINGS_PER_RULE_HELP, conflicts_with_all=vec![VERBOSE.0, PRINT_JSON.0])]
+    /// how many failures of each rule the console output lists, the rest are counted
+    /// default is unlimited
+    pub(crate) max_findings_per_rule: Option<usize>,
+    #[arg(long, help=TOP_OFFENDERS_HELP, conflicts_with_all=vec![VERBOSE.0, PRINT_JSON.0])]
+    /// how many of the violations repeated most across the data files, the same rule failing on
+    /// paths of the same shape, the console output summarizes after the failures
+    /// default is None, no summary
+    pub(crate) top_offenders: Option<usize>,
+    #[arg(long, help=TAGS_HELP, value_delimiter=',')]
+    /// only evaluate rules annotated with at least one of these tags, all rules when empty
+    pub(crate) tags: Vec<String>,
+    #[arg(long, help=EXCLUDE_TAGS_HELP, value_delimiter=',')]
+    /// skip rules annotated with any of these tags
+    pub(crate) exclude_tags: Vec<String>,
+    #[arg(name="resource-selector", long, help=RESOURCE_SELECTOR_HELP)]
+    /// only evaluate the resources matching any of these selectors, all resources when empty
+    pub(crate) resource_selectors: Vec<ResourceSelector>,
+    #[arg(name="header", long="header", help=HEADER_HELP)]
+    /// headers sent along with the requests for rules and data files given as https:// URLs
+    pub(crate) headers: Vec<Header>,
+    #[arg(long, help=RULE_NAMES_HELP, value_delimiter=',')]
+    /// only evaluate the named rules with these names, all rules when empty
+    pub(crate) rule_names: Vec<String>,
+    #[arg(long, help=SKIP_RULES_HELP, value_delimiter=',')]
+    /// skip the named rules with these names
+    pub(crate) skip_rules: Vec<String>,
+    #[arg(long, help=CONTINUE_ON_RULE_ERROR_HELP)]
+    /// evaluate the rules files that parsed when others failed to parse, instead of none of them
+    /// default is false
+    pub(crate) continue_on_rule_error: bool,
+    #[arg(long, help=STRICT_TYPES_HELP)]
+    /// report the comparisons between values of different types as not comparable, instead of
+    /// comparing them
+    /// default is false
+    pub(crate) strict_types: bool,
     #[arg(short=SHOW_SUMMARY.1, long, help=SHOW_SUMMARY_HELP, value_enum, default_values_t=vec![ShowSummaryType::Fail], value_delimiter=',')]
     /// Controls if the summary table needs to be displayed. --show-summary fail (default) or --show-summary pass,fail (only show rules that did pass/fail) or --show-summary none (to turn it off) or --show-summary all (to show all the rules that pass, fail or skip)
     /// default is failed
@@ -177,6 +530,28 @@ pub struct Validate {
     #[arg(name="last-modified", short=LAST_MODIFIED.1, long, help=LAST_MODIFIED_HELP, conflicts_with=ALPHABETICAL.0)]
     /// Validate files in a directory ordered by last modified times, conflicts with `alphabetical` field
     pub(crate) last_modified: bool,
+    #[arg(long, help=MAX_DEPTH_HELP)]
+    /// how deep below the directories passed as rules or data to look for files, 1 only looks at
+    /// the files directly in them
+    /// default is unlimited
+    pub(crate) max_depth: Option<usize>,
+    #[arg(long, help=FOLLOW_SYMLINKS_HELP)]
+    /// descend into symlinked directories when looking for rules and data files
+    /// default is false
+    pub(crate) follow_symlinks: bool,
+    #[arg(long, help=MAX_FILE_SIZE_HELP)]
+    /// the size of the largest data file read, in bytes, a larger one fails the run
+    /// default is unlimited
+    pub(crate) max_file_size: Option<usize>,
+    #[arg(long, help=MAX_NESTING_DEPTH_HELP)]
+    /// how deeply the mappings and lists of a data file may nest, a deeper one fails the run
+    /// default is unlimited
+    pub(crate) max_nesting_depth: Option<usize>,
+    #[arg(long, help=MAX_QUERY_RESULTS_HELP)]
+    /// the most values a single query of the rules may resolve to, a query resolving to more
+    /// fails the run
+    /// default is unlimited
+    pub(crate) max_query_results: Option<usize>,
     #[arg(short, long, help=VERBOSE_HELP)]
     /// Output verbose logging, conflicts with `structured` field
     /// default is false
@@ -186,6 +561,41 @@ pub struct Validate {
     /// conflicts with the `structured` attribute
     /// default is false
     pub(crate) print_json: bool,
+    #[arg(long, help=PROGRESS_HELP)]
+    /// draw a progress bar of the evaluations done on stderr, when stderr is a terminal
+    /// default is false
+    pub(crate) progress: bool,
+    #[arg(long, help=TIMINGS_HELP)]
+    /// write the time spent evaluating each data file and each rule to stderr after the run,
+    /// slowest first
+    /// default is false
+    pub(crate) timings: bool,
+    #[arg(long, value_enum, help=METRICS_HELP)]
+    /// export the counts of rules evaluated, the failures of each rule and the latency of the
+    /// run with the protocol after the run
+    /// default is None, no metrics are exported
+    pub(crate) metrics: Option<MetricsSink>,
+    #[arg(long, requires="metrics", help=METRICS_ENDPOINT_HELP)]
+    /// the host:port of the StatsD server, or the URL of the OTLP/HTTP receiver, the metrics are
+    /// sent to
+    /// default is None, which is 127.0.0.1:8125 for statsd and http://localhost:4318/v1/metrics
+    /// for otlp
+    pub(crate) metrics_endpoint: Option<String>,
+    #[arg(long, help=MESSAGE_CATALOG_HELP)]
+    /// path of a YAML or JSON file mapping message IDs to the wording of the failure messages of
+    /// the reports, to customize or translate them
+    /// default is None, which keeps the English wording
+    pub(crate) message_catalog: Option<String>,
+    #[arg(long, help=CONFIG_HELP)]
+    /// path of a cfn-guard.toml or .guardrc file with the defaults of the project, filled in for
+    /// the flags that are not passed. Only read by the command line
+    /// default is None, which looks the file up in the current directory and its parents
+    pub(crate) config: Option<String>,
+    #[arg(long, help=VERIFY_SIGNATURE_HELP, conflicts_with=PAYLOAD.0)]
+    /// path of the Ed25519 public key in PEM the rules packs must be signed for, with cfn-guard
+    /// sign. Only rules packs are accepted as rules when set
+    /// default is None
+    pub(crate) verify_signature: Option<String>,
     #[arg(short=PAYLOAD.1, long, help=PAYLOAD_HELP)]
     /// Tells the command that rules, and data will be passed via a reader, as a json payload.
     /// Conflicts with both rules, and data
@@ -197,6 +607,22 @@ pub struct Validate {
     /// to "single-line-summary", show-summary when set to anything other than "none"
     /// default is false
     pub(crate) structured: bool,
+    #[arg(long, help=OUTPUT_SCHEMA_HELP)]
+    /// Print the JSON Schema of the structured json and yaml output instead of evaluating rules
+    /// default is false
+    pub(crate) output_schema: bool,
+    #[arg(long, help=OUTPUT_VERSION_HELP, value_enum, default_value_t=OutputVersion::V1)]
+    /// the layout of the structured json and yaml output, and of the schema printed with
+    /// `output-schema`, v1 (default) or v2
+    pub(crate) output_version: OutputVersion,
+    #[arg(skip)]
+    /// the custom functions the rules may call, only registered by library users with
+    /// ValidateBuilder::functions
+    pub(crate) functions: Option<Arc<FunctionRegistry>>,
+    #[arg(skip)]
+    /// the wording of the failure messages, read from `message_catalog` or given to
+    /// ValidateBuilder::message_catalog
+    pub(crate) messages: MessageCatalog,
 }
 
 impl Validate {
@@ -208,29 +634,277 @@ impl Validate {
             return Err(Error::IllegalArguments(String::from(
                 "Cannot provide a summary-type other than `none` when the `structured` flag is present",
             )));
-        } else if self.structured
-            && matches!(self.output_format, OutputFormatType::SingleLineSummary)
+        }
+
+        if self
+            .outputs
+            .iter()
+            .filter(|output| output.path.is_none())
+            .count()
+            > 1
+        {
+            return Err(Error::IllegalArguments(String::from(
+                "only one output-format can be written to stdout, use format=path to write the others to a file",
+            )));
+        }
+
+        if let Some(output) = self
+            .outputs
+            .iter()
+            .find(|output| output.path.is_some() && !output.format.is_structured())
+        {
+            return Err(Error::IllegalArguments(format!(
+                "{} cannot be written to a file, only json, yaml, junit, sarif, csv or tsv can",
+                output.format.to_possible_value().unwrap().get_name()
+            )));
+        }
+
+        if self.max_depth == Some(0) {
+            return Err(Error::IllegalArguments(String::from(
+                "max-depth must be at least 1, a depth of 0 would not look inside any directory",
+            )));
+        }
+
+        if let Some(limit) = [
+            ("max-file-size", self.max_file_size),
+            ("max-nesting-depth", self.max_nesting_depth),
+            ("max-query-results", self.max_query_results),
+        ]
+        .iter()
+        .find_map(|(limit, value)| (*value == Some(0)).then_some(limit))
         {
+            return Err(Error::IllegalArguments(format!(
+                "{limit} must be at least 1, a limit of 0 would reject every data file"
+            )));
+        }
+
+        if self.data.iter().filter(|data| *data == STDIN_DATA).count() > 1 {
+            return Err(Error::IllegalArguments(format!(
+                "`{STDIN_DATA}` can only be passed once to data, stdin can only be read once"
+            )));
+        }
+
+        if self.structured && matches!(self.output_format(), OutputFormatType::SingleLineSummary) {
             return Err(Error::IllegalArguments(String::from(
                 "single-line-summary is not able to be used when the `structured` flag is present",
             )));
         }
 
-        if matches!(self.output_format, OutputFormatType::Junit) && !self.structured {
+        if self.rules.is_empty() && self.global_rules.is_empty() && !self.payload {
+            return Err(Error::IllegalArguments(format!(
+                "either rules or payload must be passed, or the rules set in {}",
+                CONFIG_FILES.join(" or ")
+            )));
+        }
+
+        if self.update_baseline && self.baseline.is_none() {
             return Err(Error::IllegalArguments(String::from(
-                "the structured flag must be set when output is set to junit",
+                "update-baseline requires the baseline file to write to",
             )));
         }
 
-        if matches!(self.output_format, OutputFormatType::Sarif) && !self.structured {
+        if self.baseline.is_some() && !self.structured {
             return Err(Error::IllegalArguments(String::from(
-                "the structured flag must be set when output is set to sarif",
+                "baseline can only be used with the `structured` flag",
+            )));
+        }
+
+        if self.output_file.is_some() && !self.structured && !self.output_format().is_report() {
+            return Err(Error::IllegalArguments(String::from(
+                "output-file can only be used with the `structured` flag, or when output is set to junit, sarif, csv or tsv",
             )));
         }
 
         Ok(())
     }
 
+    /// the rules selected through `tags`, `exclude_tags`, `rule_names` and `skip_rules`
+    pub(crate) fn rule_selection(&self) -> RuleSelection {
+        RuleSelection {
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            names: self.rule_names.clone(),
+            skip_names: self.skip_rules.clone(),
+        }
+    }
+
+    /// parses every rules file, reporting the ones that failed together, and adds the rules files
+    /// of the rules packs and the global rules. Unless `continue_on_rule_error` is set no rules
+    /// are evaluated when any of them failed
+    fn parse_rule_files<'r>(
+        &self,
+        rule_info: &'r [RuleFileInfo],
+        packed: &'r [PackedRulesFile<'r>],
+        global_info: &'r [RuleFileInfo],
+        writer: &mut Writer,
+        summary: &mut Summary,
+    ) -> Result<ParsedRules<'r>> {
+        let mut parsed = parse_rule_files(rule_info);
+        parsed.add_packed(packed);
+        parsed.add_global(global_info);
+        if parsed.errors.is_empty() {
+            return Ok(parsed);
+        }
+
+        for rule_file_error in &parsed.errors {
+            for error in rule_file_error.all_errors() {
+                // with json output, editors read where the rules file failed to parse from stderr
+                match (self.output_format(), error.parse_error_location()) {
+                    (OutputFormatType::JSON, Some(location)) => {
+                        writer.write_err(serde_json::to_string(location)?)?
+                    }
+                    _ => writer.write_err(format!(
+                        "Parsing error handling rule file = {}, Error = {error}\n---",
+                        rule_file_error.file_name.underline(),
+                    ))?,
+                }
+            }
+        }
+
+        let failed = format!(
+            "{} of {} rules files failed to parse",
+            parsed.errors.len(),
+            rule_info.len() + global_info.len()
+        );
+        match self.continue_on_rule_error {
+            true => {
+                writer.write_err(format!("{failed}, evaluating the rules files that parsed"))?
+            }
+            false => {
+                writer.write_err(format!(
+                    "{failed}, no rules were evaluated. Use --continue-on-rule-error to evaluate the rules files that parsed"
+                ))?;
+                parsed.rules.clear();
+                parsed.global.clear();
+            }
+        }
+        summary.rule_files_errored += parsed.errors.len();
+
+        Ok(parsed)
+    }
+
+    /// the command with the settings of the config file of the project filled in for the flags
+    /// that were not passed, the file given with `config` or else the one found from the current
+    /// directory
+    ///
+    /// This function will return an error if the config file is not valid
+    pub(crate) fn with_config(&self) -> Result<Validate> {
+        let path = match &self.config {
+            Some(path) => PathBuf::from(path),
+            None => match find_config(&std::env::current_dir()?) {
+                Some(path) => path,
+                None => return Ok(self.clone()),
+            },
+        };
+        let config = GuardConfig::read(&path)?.validate;
+
+        let mut validate = self.clone();
+        if !validate.payload && validate.rules.is_empty() {
+            validate.rules = config.rules;
+        }
+        if !validate.payload && validate.data.is_empty() && validate.from_stack.is_empty() {
+            validate.data = config.data;
+        }
+        if validate.outputs.is_empty() {
+            validate.outputs = config
+                .output_format
+                .values()
+                .iter()
+                .map(|output| output.parse().map_err(Error::IllegalArguments))
+                .collect::<Result<Vec<_>>>()?;
+        }
+        // the baseline only applies to structured output, other runs leave it out
+        if validate.structured && validate.baseline.is_none() {
+            validate.baseline = config.baseline;
+        }
+        if validate.tags.is_empty() {
+            validate.tags = config.tags;
+        }
+        if validate.exclude_tags.is_empty() {
+            validate.exclude_tags = config.exclude_tags;
+        }
+
+        Ok(validate)
+    }
+
+    /// walks a `--rules`, `--data` or `--input-parameters` argument, a URL is downloaded first and
+    /// walked as a single file
+    fn walk(
+        &self,
+        downloads: &mut Downloads,
+        file_or_dir: &str,
+        cmp: fn(&walkdir::DirEntry, &walkdir::DirEntry) -> cmp::Ordering,
+        options: WalkOptions,
+    ) -> Result<(Box<dyn Iterator<Item = walkdir::DirEntry>>, bool)> {
+        match is_remote(file_or_dir) {
+            true => {
+                let path = downloads.download(file_or_dir, &self.headers)?;
+                Ok((Box::new(walk_dir(path, cmp, options)), true))
+            }
+            false => walk_argument(file_or_dir, cmp, options),
+        }
+    }
+
+    /// drops the resources that do not match any of the `resource_selectors` from every data file
+    fn select_resources(&self, mut data_files: Vec<DataFile>) -> Vec<DataFile> {
+        if !self.resource_selectors.is_empty() {
+            for file in &mut data_files {
+                retain_resources(&mut file.path_value, &self.resource_selectors);
+            }
+        }
+
+        data_files
+    }
+
+    /// hands the `vars` file to every data file, where the rules read it as `%VARS`
+    fn attach_vars(&self, mut data_files: Vec<DataFile>) -> Result<Vec<DataFile>> {
+        if let Some(path) = &self.vars {
+            let content = read_file_content(File::open(path)?)?;
+            let vars = Rc::new(
+                build_limited_data_file(content, path.clone(), &self.input_limits())?.path_value,
+            );
+            for file in &mut data_files {
+                file.vars = Some(Rc::clone(&vars));
+            }
+        }
+
+        Ok(data_files)
+    }
+
+    /// the bounds on the data files read and the values the queries of the rules resolve to
+    pub(crate) fn input_limits(&self) -> InputLimits {
+        InputLimits {
+            max_file_size: self.max_file_size,
+            max_nesting_depth: self.max_nesting_depth,
+            max_query_results: self.max_query_results,
+        }
+    }
+
+    /// whether rules to evaluate against the data files were passed, as opposed to a payload
+    fn has_rules(&self) -> bool {
+        !self.rules.is_empty() || !self.global_rules.is_empty()
+    }
+
+    /// the format written to stdout, or to `output_file` when set
+    pub(crate) fn output_format(&self) -> OutputFormatType {
+        self.outputs
+            .iter()
+            .find(|output| output.path.is_none())
+            .map_or(OutputFormatType::SingleLineSummary, |output| output.format)
+    }
+
+    /// the formats written to a file of their own
+    pub(crate) fn report_files(&self) -> impl Iterator<Item = (OutputFormatType, &str)> {
+        self.outputs
+            .iter()
+            .filter_map(|output| output.path.as_deref().map(|path| (output.format, path)))
+    }
+
+    /// whether any report is produced alongside the regular console output
+    fn has_reports(&self) -> bool {
+        self.output_format().is_report() || self.report_files().next().is_some()
+    }
+
     fn get_comparator(&self) -> fn(&walkdir::DirEntry, &walkdir::DirEntry) -> cmp::Ordering {
         match self.last_modified {
             true => last_modified,
@@ -249,8 +923,50 @@ impl Executable for Validate {
     /// - parse errors occur in the rule file
     /// - illegal json or yaml syntax present in any of the data/input parameter files
     /// - both rules is empty, and payload is false
-    #[allow(deprecated)]
     fn execute(&self, writer: &mut Writer, reader: &mut Reader) -> Result<i32> {
+        if self.output_schema {
+            writeln!(writer, "{}", self.output_version.schema().trim_end())?;
+            return Ok(SUCCESS_STATUS_CODE);
+        }
+
+        let (exit_code, summary) = self.execute_with_summary(writer, reader)?;
+        if self.timings {
+            writer.write_info(summary.timings_table().trim_end().to_string())?;
+        }
+        if let Some(sink) = self.metrics {
+            // the metrics are a side channel, the run keeps the outcome of its rules
+            if let Err(e) = export(sink, self.metrics_endpoint.as_deref(), &summary) {
+                writer.write_err(format!("unable to export metrics, {e}"))?;
+            }
+        }
+
+        Ok(exit_code)
+    }
+}
+
+impl Validate {
+    /// .
+    /// evaluates the given rules, against the given data files, and returns a `Summary` of the
+    /// run alongside the exit code.
+    ///
+    /// This function will return an error under the same conditions as `execute`
+    pub fn execute_with_summary(
+        &self,
+        writer: &mut Writer,
+        reader: &mut Reader,
+    ) -> Result<(i32, Summary)> {
+        if let Some(path) = &self.message_catalog {
+            // the catalog is read once, then the run goes on as if it had been given to the builder
+            let validate = Validate {
+                message_catalog: None,
+                messages: MessageCatalog::read(path)?,
+                ..self.clone()
+            };
+            return validate.execute_with_summary(writer, reader);
+        }
+
+        let now = Instant::now();
+        let mut summary = Summary::default();
         let summary_type = self
             .show_summary
             .iter()
@@ -270,29 +986,68 @@ impl Executable for Validate {
         self.validate_construct(&summary_type)?;
 
         let cmp = self.get_comparator();
+        let walk_options = WalkOptions {
+            max_depth: self.max_depth,
+            follow_symlinks: self.follow_symlinks,
+        };
+        let limits = self.input_limits();
+        // the remote files are deleted once the run is over
+        let mut downloads = Downloads::default();
 
         let data_files = match self.data.is_empty() {
             false => {
                 let mut streams = Vec::new();
 
                 for file_or_dir in &self.data {
-                    validate_path(file_or_dir)?;
-                    let base = resolve_path(file_or_dir)?;
-                    for file in walk_dir(base, cmp) {
+                    if file_or_dir == STDIN_DATA {
+                        let mut content = String::new();
+                        reader.read_to_string(&mut content)?;
+
+                        streams.push(build_limited_data_file(
+                            content,
+                            "STDIN".to_string(),
+                            &limits,
+                        )?);
+                        continue;
+                    }
+
+                    let remote = is_remote(file_or_dir);
+                    let (entries, single_file) =
+                        self.walk(&mut downloads, file_or_dir, cmp, walk_options)?;
+                    for file in entries {
                         if file.path().is_file() {
-                            let name = file
-                                .path()
-                                // path output occasionally includes double slashes '//'
-                                // without calling canonicalize()
-                                .canonicalize()?
-                                .to_str()
-                                .map_or("".to_string(), String::from);
-                            if has_a_supported_extension(&name, &DATA_FILE_SUPPORTED_EXTENSIONS) {
+                            let name = match remote {
+                                // a downloaded file is reported by its URL
+                                true => file_or_dir.to_string(),
+                                false => file
+                                    .path()
+                                    // path output occasionally includes double slashes '//'
+                                    // without calling canonicalize()
+                                    .canonicalize()?
+                                    .to_str()
+                                    .map_or("".to_string(), String::from),
+                            };
+                            if (remote
+                                || has_a_supported_extension(
+                                    &name,
+                                    &DATA_FILE_SUPPORTED_EXTENSIONS,
+                                ))
+                                && (single_file
+                                    || !is_parameters_file(&name, &self.parameters_file_suffix))
+                            {
+                                // checked before reading, a file too large is never held in memory
+                                limits.check_file_size(&name, file.metadata()?.len() as usize)?;
                                 let mut content = String::new();
                                 let mut reader = BufReader::new(File::open(file.path())?);
                                 reader.read_to_string(&mut content)?;
 
-                                let data_file = build_data_file(content, name)?;
+                                let mut data_file =
+                                    build_limited_data_file(content, name, &limits)?;
+                                data_file.parameters_file = read_parameters_file(
+                                    &data_file.name,
+                                    &self.parameters_file_suffix,
+                                    &limits,
+                                )?;
                                 streams.push(data_file);
                             }
                         }
@@ -301,11 +1056,11 @@ impl Executable for Validate {
                 streams
             }
             true => {
-                if !self.rules.is_empty() {
+                if self.has_rules() && self.from_stack.is_empty() {
                     let mut content = String::new();
                     reader.read_to_string(&mut content)?;
 
-                    let data_file = build_data_file(content, "STDIN".to_string())?;
+                    let data_file = build_limited_data_file(content, "STDIN".to_string(), &limits)?;
 
                     vec![data_file]
                 } else {
@@ -313,16 +1068,21 @@ impl Executable for Validate {
                 } // expect Payload, since rules aren't specified
             }
         };
+        let data_files = data_files
+            .into_iter()
+            .chain(stack_data_files(&self.from_stack)?)
+            .collect();
+        let data_files = adapt_data_files(data_files, self.template_type.as_deref())?;
+        let data_files = self.select_resources(data_files);
 
         let extra_data = match self.input_params.is_empty() {
             false => {
                 let mut primary_path_value: Option<PathAwareValue> = None;
 
                 for file_or_dir in &self.input_params {
-                    validate_path(file_or_dir)?;
-                    let base = resolve_path(file_or_dir)?;
+                    let (entries, _) = self.walk(&mut downloads, file_or_dir, cmp, walk_options)?;
 
-                    for file in walk_dir(base, cmp) {
+                    for file in entries {
                         if file.path().is_file() {
                             let name = file
                                 .file_name()
@@ -334,7 +1094,8 @@ impl Executable for Validate {
                                 let mut reader = BufReader::new(File::open(file.path())?);
                                 reader.read_to_string(&mut content)?;
 
-                                let DataFile { path_value, .. } = build_data_file(content, name)?;
+                                let DataFile { path_value, .. } =
+                                    build_limited_data_file(content, name, &limits)?;
 
                                 primary_path_value = match primary_path_value {
                                     Some(current) => Some(current.merge(path_value)?),
@@ -348,8 +1109,36 @@ impl Executable for Validate {
             }
             true => None,
         };
-
-        let mut exit_code = SUCCESS_STATUS_CODE;
+        let data_files = match self.evaluate_conditions {
+            true => {
+                let (data_files, decisions) = prune_conditional_resources(data_files, &extra_data);
+                if self.verbose {
+                    for decision in decisions {
+                        match self.output_format().is_report() {
+                            true => writer.write_err(decision.to_string())?,
+                            false => writeln!(writer, "{decision}")?,
+                        }
+                    }
+                }
+                data_files
+            }
+            false => data_files,
+        };
+        let (data_files, extra_data) = match self.resolve_parameters {
+            true => resolve_parameters(data_files, extra_data),
+            false => (data_files, extra_data),
+        };
+        let data_files = match self.resolve_json_refs {
+            true => data_files
+                .into_iter()
+                .map(|data_file| DataFile {
+                    path_value: resolve_json_refs(data_file.path_value),
+                    ..data_file
+                })
+                .collect(),
+            false => data_files,
+        };
+        let data_files = self.attach_vars(data_files)?;
 
         let data_type = self
             .template_type
@@ -362,79 +1151,107 @@ impl Executable for Validate {
             alphabetical
         };
 
-        if !self.rules.is_empty() {
+        let exit_code = if self.has_rules() {
             let mut rules = Vec::new();
 
             for file_or_dir in &self.rules {
-                validate_path(file_or_dir)?;
-                let base = resolve_path(file_or_dir)?;
-
-                if base.is_file() {
-                    rules.push(base.clone())
-                } else {
-                    for entry in walk_dir(base, cmp) {
-                        if entry.path().is_file()
-                            && entry
-                                .path()
-                                .file_name()
-                                .and_then(|s| s.to_str())
-                                .map_or(false, |s| {
+                let (entries, single_file) =
+                    self.walk(&mut downloads, file_or_dir, cmp, walk_options)?;
+
+                for entry in entries {
+                    if entry.path().is_file()
+                        && (single_file
+                            || entry.path().file_name().and_then(|s| s.to_str()).map_or(
+                                false,
+                                |s| {
                                     has_a_supported_extension(s, &RULE_FILE_SUPPORTED_EXTENSIONS)
-                                })
-                        {
-                            rules.push(entry.path().to_path_buf());
-                        }
+                                        || s.ends_with(RULE_PACK_EXTENSION)
+                                },
+                            ))
+                    {
+                        rules.push(entry.path().to_path_buf());
                     }
                 }
             }
 
-            exit_code = match self.structured {
-                true => {
-                    let rule_info = get_rule_info(&rules, writer)?;
-                    let mut evaluator = StructuredEvaluator {
-                        rule_info: &rule_info,
-                        input_params: extra_data,
-                        data: data_files,
-                        output: self.output_format,
-                        writer,
-                        exit_code,
-                    };
-                    evaluator.evaluate()?
-                }
+            summary.files_evaluated = data_files.len();
 
-                false => {
-                    for each_file_content in iterate_over(&rules, |content, file| {
-                        Ok(RuleFileInfo {
-                            content,
-                            file_name: get_file_name(file, file),
-                        })
-                    }) {
-                        match each_file_content {
+            let (packs, rules): (Vec<_>, Vec<_>) =
+                rules.into_iter().partition(|rule| is_rule_pack(rule));
+            let public_key = read_public_key(&self.verify_signature)?;
+            if let (Some(_), Some(rule)) = (&public_key, rules.first()) {
+                return Err(Error::IllegalArguments(format!(
+                    "{} is not a rules pack, only signed rules packs are evaluated with --verify-signature",
+                    rule.display()
+                )));
+            }
+            let packed = read_rule_packs(&packs, public_key.as_deref())?;
+            let rule_info = match (self.structured, self.has_reports()) {
+                (false, false) => iterate_over(&rules, |content, file| {
+                    Ok(RuleFileInfo {
+                        content,
+                        file_name: get_file_name(file, file),
+                    })
+                })
+                .try_fold(
+                    vec![],
+                    |mut rule_info, rule| -> Result<Vec<RuleFileInfo>> {
+                        match rule {
                             Err(e) => {
                                 writer.write_err(format!("Unable read content from file {e}"))?
                             }
-                            Ok(rule) => {
-                                let status = evaluate_rule(
-                                    data_type,
-                                    self.output_format,
-                                    &extra_data,
-                                    &data_files,
-                                    rule,
-                                    self.verbose,
-                                    self.print_json,
-                                    summary_type,
-                                    writer,
-                                )?;
-
-                                if status != SUCCESS_STATUS_CODE {
-                                    exit_code = status
-                                }
-                            }
+                            Ok(rule) => rule_info.push(rule),
                         }
-                    }
-                    exit_code
-                }
+                        Ok(rule_info)
+                    },
+                )?,
+                _ => get_rule_info(&rules, writer)?,
+            };
+            let global_info = get_rule_info(
+                &argument_files(&self.global_rules, &RULE_FILE_SUPPORTED_EXTENSIONS)?,
+                writer,
+            )?;
+            let parsed =
+                self.parse_rule_files(&rule_info, &packed, &global_info, writer, &mut summary)?;
+            let mut progress = Progress::new(
+                parsed.rules.len() * data_files.len() + parsed.global.len(),
+                self.progress && !writer.is_quiet(),
+            );
+
+            let exit_code = match (self.structured, self.has_reports()) {
+                (true, _) => self.evaluate_structured(
+                    &parsed,
+                    extra_data,
+                    data_files,
+                    writer,
+                    &mut summary,
+                    &mut progress,
+                    None,
+                )?,
+
+                (false, false) if !self.grouped_console() => self.evaluate_each(
+                    data_type,
+                    &parsed,
+                    &extra_data,
+                    &data_files,
+                    summary_type,
+                    writer,
+                    &mut summary,
+                    &mut progress,
+                )?,
+
+                (false, _) => self.evaluate_with_report(
+                    &parsed,
+                    extra_data,
+                    data_files,
+                    summary_type,
+                    writer,
+                    &mut summary,
+                    &mut progress,
+                )?,
             };
+            progress.finish();
+            exit_code
         } else if self.payload {
             let mut context = String::new();
             reader.read_to_string(&mut context)?;
@@ -443,73 +1260,339 @@ impl Executable for Validate {
             let data_collection = payload.list_of_data.iter().enumerate().try_fold(
                 vec![],
                 |mut data_collection, (i, data)| -> Result<Vec<DataFile>> {
-                    let content = data.to_string();
-                    let name = format!("DATA_STDIN[{}]", i + 1);
-                    let data_file = build_data_file(content, name)?;
+                    let content = data.content().to_string();
+                    let name = data.name_or(|| format!("DATA_STDIN[{}]", i + 1));
+                    let data_file = build_limited_data_file(content, name, &limits)?;
 
                     data_collection.push(data_file);
 
                     Ok(data_collection)
                 },
             )?;
+            let data_collection = adapt_data_files(data_collection, self.template_type.as_deref())?;
+            let data_collection = self.attach_vars(self.select_resources(data_collection))?;
 
             let rule_info = payload
                 .list_of_rules
                 .iter()
                 .enumerate()
                 .map(|(i, rules)| RuleFileInfo {
-                    content: rules.to_string(),
-                    file_name: format!("RULES_STDIN[{}]", i + 1),
+                    content: rules.content().to_string(),
+                    file_name: rules.name_or(|| format!("RULES_STDIN[{}]", i + 1)),
                 })
                 .collect::<Vec<_>>();
 
-            exit_code = match self.structured {
-                true => {
-                    let mut evaluator = StructuredEvaluator {
-                        rule_info: &rule_info,
-                        input_params: extra_data,
-                        data: data_collection,
-                        output: self.output_format,
-                        writer,
-                        exit_code,
-                    };
-                    evaluator.evaluate()?
-                }
-                false => {
-                    for rule in rule_info {
-                        let status = evaluate_rule(
-                            data_type,
-                            self.output_format,
-                            &None,
-                            &data_collection,
-                            rule,
-                            self.verbose,
-                            self.print_json,
-                            summary_type,
-                            writer,
-                        )?;
-
-                        if status != SUCCESS_STATUS_CODE {
-                            exit_code = status;
-                        }
-                    }
-                    exit_code
-                }
+            summary.files_evaluated = data_collection.len();
+            let parsed = self.parse_rule_files(&rule_info, &[], &[], writer, &mut summary)?;
+            let mut progress = Progress::new(
+                parsed.rules.len() * data_collection.len(),
+                self.progress && !writer.is_quiet(),
+            );
+
+            let exit_code = match (self.structured, self.has_reports()) {
+                (true, _) => self.evaluate_structured(
+                    &parsed,
+                    extra_data,
+                    data_collection,
+                    writer,
+                    &mut summary,
+                    &mut progress,
+                    None,
+                )?,
+                (false, false) if !self.grouped_console() => self.evaluate_each(
+                    data_type,
+                    &parsed,
+                    &None,
+                    &data_collection,
+                    summary_type,
+                    writer,
+                    &mut summary,
+                    &mut progress,
+                )?,
+                (false, _) => self.evaluate_with_report(
+                    &parsed,
+                    extra_data,
+                    data_collection,
+                    summary_type,
+                    writer,
+                    &mut summary,
+                    &mut progress,
+                )?,
             };
+            progress.finish();
+            exit_code
         } else {
             unreachable!()
+        };
+
+        summary.duration = now.elapsed();
+
+        Ok((self.fail_on.exit_code(exit_code, &summary), summary))
+    }
+
+    /// evaluates the rules once and writes every requested report, the one meant for stdout to
+    /// `output_file` when set, or to the writer otherwise, and the rest to the file they are paired with.
+    /// The console output, grouped with `group_by` or of each evaluation, is written to `console`
+    /// when given
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_structured(
+        &self,
+        parsed: &ParsedRules<'_>,
+        input_params: Option<PathAwareValue>,
+        data: Vec<DataFile>,
+        writer: &mut Writer,
+        summary: &mut Summary,
+        progress: &mut Progress,
+        console: Option<ConsoleOutput<'_>>,
+    ) -> Result<i32> {
+        let output_format = self.output_format();
+        let to_stdout = output_format.is_structured() && self.output_file.is_none();
+
+        let mut file_writers = vec![];
+        if let (true, Some(path)) = (output_format.is_structured(), &self.output_file) {
+            file_writers.push((output_format, report_file_writer(path)?));
+        }
+        for (format, path) in self.report_files() {
+            file_writers.push((format, report_file_writer(path)?));
+        }
+
+        let mut outputs = file_writers
+            .iter_mut()
+            .map(|(format, file_writer)| (*format, file_writer))
+            .collect::<Vec<_>>();
+        if to_stdout {
+            outputs.insert(0, (output_format, &mut *writer));
+        }
+        let mut each_console = None;
+        match console {
+            Some(ConsoleOutput::Grouped(console)) => {
+                outputs.push((OutputFormatType::SingleLineSummary, console))
+            }
+            Some(ConsoleOutput::Each(console, console_writer)) => {
+                each_console = Some((console, console_writer))
+            }
+            None => {}
+        }
+
+        // the baseline is regenerated from the violations of this run when updating it
+        let mut baseline = match (&self.baseline, self.update_baseline) {
+            (Some(_), true) => Some(Baseline::default()),
+            (Some(path), false) => Some(Baseline::read(path)?),
+            (None, _) => None,
+        };
+
+        let result = StructuredEvaluator {
+            rules: &parsed.rules,
+            global_rules: &parsed.global,
+            rule_errors: &parsed.errors,
+            input_params,
+            data,
+            outputs,
+            selection: self.rule_selection(),
+            exit_code: parsed.exit_code(),
+            summary,
+            baseline: baseline.as_mut(),
+            update_baseline: self.update_baseline,
+            strict_types: self.strict_types,
+            limits: self.input_limits(),
+            functions: self.functions.clone(),
+            messages: &self.messages,
+            progress,
+            group_by: self.group_by,
+            max_findings_per_rule: self.max_findings_per_rule,
+            top_offenders: self.top_offenders,
+            output_version: self.output_version,
+            console: each_console,
+        }
+        .evaluate();
+
+        if let (Some(path), Some(baseline), true, Ok(_)) =
+            (&self.baseline, &baseline, self.update_baseline, &result)
+        {
+            baseline.write(path)?;
+        }
+
+        // errors are still reported through the writer even when the output goes to a file
+        for (_, file_writer) in file_writers {
+            let errors = file_writer.err_to_stripped()?;
+            if !errors.is_empty() {
+                writer.write_err(errors.trim_end().to_string())?;
+            }
+        }
+
+        result
+    }
+
+    /// evaluates the rules once, printing the regular console output and writing the reports the
+    /// same way the `structured` flag would from the same records. The console output goes to
+    /// stderr when a report is written to stdout
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_with_report(
+        &self,
+        parsed: &ParsedRules<'_>,
+        input_params: Option<PathAwareValue>,
+        data: Vec<DataFile>,
+        summary_type: BitFlags<SummaryType>,
+        writer: &mut Writer,
+        summary: &mut Summary,
+        progress: &mut Progress,
+    ) -> Result<i32> {
+        let mut console = Writer::new_with_err(WriteBuffer::Vec(vec![]), WriteBuffer::Vec(vec![]))?;
+
+        // the failures are grouped across every data file, which the structured evaluation sees
+        // at once, writing the console output along with the reports
+        if self.grouped_console() {
+            let grouped_console = match self.output_format() {
+                OutputFormatType::SingleLineSummary => Some(ConsoleOutput::Grouped(&mut console)),
+                _ => None,
+            };
+            let exit_code = self.evaluate_structured(
+                parsed,
+                input_params,
+                data,
+                writer,
+                summary,
+                progress,
+                grouped_console,
+            )?;
+            self.write_console(console, writer)?;
+            return Ok(exit_code);
+        }
+
+        // the console output of each evaluation is written from the records of the reports
+        let exit_code = with_console_reporter(summary_type, &self.messages, |reporter| {
+            let each_console = Console {
+                reporter,
+                output: OutputFormatType::SingleLineSummary,
+                verbose: self.verbose,
+                print_json: self.print_json,
+            };
+            self.evaluate_structured(
+                parsed,
+                input_params,
+                data,
+                writer,
+                summary,
+                progress,
+                Some(ConsoleOutput::Each(each_console, &mut console)),
+            )
+        })?;
+        self.write_console(console, writer)?;
+
+        Ok(exit_code)
+    }
+
+    /// whether the console output lists the failures found across every data file at once,
+    /// grouped with `group_by`, instead of writing the evaluation of each data file
+    fn grouped_console(&self) -> bool {
+        self.group_by != GroupBy::File
+            || self.max_findings_per_rule.is_some()
+            || self.top_offenders.is_some()
+    }
+
+    /// writes the console output to stderr when a report is written to stdout, to stdout otherwise
+    fn write_console(&self, console: Writer, writer: &mut Writer) -> Result<()> {
+        let console = console.into_string()?;
+        if self.output_format().is_report() {
+            if !console.is_empty() {
+                writer.write_info(console.trim_end().to_string())?;
+            }
+        } else {
+            write!(writer, "{console}")?;
+        }
+
+        Ok(())
+    }
+
+    /// evaluates each rules file printing the regular console output
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_each(
+        &self,
+        data_type: Type,
+        parsed: &ParsedRules<'_>,
+        input_params: &Option<PathAwareValue>,
+        data: &Vec<DataFile>,
+        summary_type: BitFlags<SummaryType>,
+        writer: &mut Writer,
+        summary: &mut Summary,
+        progress: &mut Progress,
+    ) -> Result<i32> {
+        let mut exit_code = parsed.exit_code();
+
+        let global = parsed.global_data(data)?;
+        for ((rule, file_name), data) in parsed
+            .rules
+            .iter()
+            .map(|rules| (rules, data))
+            .chain(parsed.global.iter().map(|rules| (rules, &global)))
+        {
+            let status = evaluate_rule(
+                data_type,
+                self.output_format(),
+                input_params,
+                data,
+                rule,
+                file_name,
+                self.verbose,
+                self.print_json,
+                self.strict_types,
+                self.input_limits(),
+                &self.functions,
+                &self.messages,
+                summary_type,
+                &self.rule_selection(),
+                writer,
+                summary,
+                progress,
+            )?;
+
+            // a failure does not hide the rules files that failed to parse
+            if exit_code == SUCCESS_STATUS_CODE {
+                exit_code = status;
+            }
         }
 
         Ok(exit_code)
     }
 }
 
+fn report_file_writer(path: &str) -> Result<Writer> {
+    Writer::new_with_err(
+        WriteBuffer::File(File::create(path)?),
+        WriteBuffer::Vec(vec![]),
+    )
+}
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct Payload {
     #[serde(rename = "rules")]
-    list_of_rules: Vec<String>,
+    list_of_rules: Vec<PayloadDocument>,
     #[serde(rename = "data")]
-    list_of_data: Vec<String>,
+    list_of_data: Vec<PayloadDocument>,
+}
+
+/// a document of the payload, either its content alone or an object naming it
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum PayloadDocument {
+    Content(String),
+    Named { name: String, content: String },
+}
+
+impl PayloadDocument {
+    fn content(&self) -> &str {
+        match self {
+            PayloadDocument::Content(content) | PayloadDocument::Named { content, .. } => content,
+        }
+    }
+
+    /// the name of the document, `default` for the ones passed without a name
+    fn name_or(&self, default: impl FnOnce() -> String) -> String {
+        match self {
+            PayloadDocument::Content(_) => default(),
+            PayloadDocument::Named { name, .. } => name.clone(),
+        }
+    }
 }
 
 const ABOUT: &str = r#"Evaluates rules against the data files to determine success or failure.
@@ -523,29 +1606,97 @@ or rules files.
 "#;
 
 // const SHOW_SUMMARY_VALUE_TYPE: [&str; 5] = ["none", "all", "pass", "fail", "skip"];
-const TEMPLATE_TYPE: [&str; 1] = ["CFNTemplate"];
 const RULES_HELP: &str = "Provide a rules file or a directory of rules files. Supports passing multiple values by using this option repeatedly.\
                           \nExample:\n --rules rule1.guard --rules ./rules-dir1 --rules rule2.guard\
-                          \nFor directory arguments such as `rules-dir1` above, scanning is only supported for files with following extensions: .guard, .ruleset";
+                          \nFor directory arguments such as `rules-dir1` above, scanning is only supported for files with following extensions: .guard, .ruleset, and .pack for rules packs built with cfn-guard build\
+                          \nGlob patterns such as 'rules/**/s3-*.guard' are supported as well, * does not match across directories while ** does. \
+                          Paths matching a pattern in a .guardignore file, which follows gitignore semantics, are skipped when scanning";
 const DATA_HELP: &str = "Provide a data file or directory of data files in JSON or YAML. Supports passing multiple values by using this option repeatedly.\
                           \nExample:\n --data template1.yaml --data ./data-dir1 --data template2.yaml\
-                          \nFor directory arguments such as `data-dir1` above, scanning is only supported for files with following extensions: .yaml, .yml, .json, .jsn, .template";
+                          \nPass - to read a single template from stdin, e.g. cat template.yaml | cfn-guard validate -r rules/ -d -\
+                          \nFor directory arguments such as `data-dir1` above, scanning is only supported for files with following extensions: .yaml, .yml, .json, .jsn, .template\
+                          \nGlob patterns such as 'stacks/**/prod-*.yaml' are supported as well, * does not match across directories while ** does. \
+                          Paths matching a pattern in a .guardignore file, which follows gitignore semantics, are skipped when scanning";
+const FROM_STACK_HELP: &str = "The name of a deployed CloudFormation stack whose template is evaluated along with the data files, as deployed with its transforms expanded, using the credentials and region of the environment. \
+The parameter values it was deployed with are its parameters file, and its resources with their physical ids, statuses and drift statuses are exposed as %STACK_RESOURCES. Can be repeated. Requires cfn-guard to be built with the aws feature";
+const GLOBAL_RULES_HELP: &str = "Provide a rules file or a directory of rules files evaluated once against all the data files together, after the rules are evaluated against each of them. \
+The rules query the data files as Files, a list of each data file's Name and Content, and are reported under the GLOBAL data file. Supports passing multiple values by using this option repeatedly";
 const INPUT_PARAMETERS_HELP: &str = "Provide a parameter file or directory of parameter files in JSON or YAML that specifies any additional parameters to use along with data files to be used as a combined context. \
                            All the parameter files passed as input get merged and this combined context is again merged with each file passed as an argument for `data`. Due to this, every file is \
                            expected to contain mutually exclusive properties, without any overlap. Supports passing multiple values by using this option repeatedly.\
                           \nExample:\n --input-parameters param1.yaml --input-parameters ./param-dir1 --input-parameters param2.yaml\
                           \nFor directory arguments such as `param-dir1` above, scanning is only supported for files with following extensions: .yaml, .yml, .json, .jsn, .template";
-const TEMPLATE_TYPE_HELP: &str =
-    "Specify the type of data file used for improved messaging - ex: CFNTemplate";
+const VARS_HELP: &str =
+    "A JSON or YAML file of variables exposed to every rule as %VARS, e.g. %VARS.allowed_regions. \
+Unlike the input parameters, the variables are not merged into the data files";
+const BASELINE_HELP: &str = "A JSON file of the violations accepted as they are, e.g. the ones an existing codebase had when the rules were introduced. \
+Rules failing only on these are reported as baselined and do not affect the exit code. Can only be used with the structured flag";
+const UPDATE_BASELINE_HELP: &str =
+    "Regenerate the baseline file from the violations of this run, fixed violations are dropped from it";
+const PARAMETERS_FILE_SUFFIX_HELP: &str = "The suffix of the parameters file paired with each data file, e.g. stack.yaml is paired with stack.params.json next to it. \
+The parameters file is exposed to the rules as %PARAMETERS_FILE, and not evaluated on its own when scanning directories. Pass an empty suffix to turn the pairing off";
+pub(crate) const DEFAULT_PARAMETERS_FILE_SUFFIX: &str = ".params.json";
+const TEMPLATE_TYPE_HELP: &str = "The type of the data files, which selects how they are adapted before the rules are evaluated against them [possible values: cfn, k8s, tf, changeset, changeset-before, generic]. \
+cfn extracts the template from the TemplateBody of get-template output, k8s evaluates every document of a multi-document file and every item of a List on its own, tf and generic evaluate the files as they are. \
+changeset evaluates the resources a change set described with --include-property-values adds or modifies as they will be once it is executed, changeset-before the ones it modifies or removes as they are before. \
+Detected for each data file when not passed";
 pub(crate) const OUTPUT_FORMAT_HELP: &str =
     "Specify the format in which the output should be displayed";
 const SHOW_SUMMARY_HELP: &str = "Controls if the summary table needs to be displayed. --show-summary fail (default) or --show-summary pass,fail (only show rules that did pass/fail) or --show-summary none (to turn it off) or --show-summary all (to show all the rules that pass, fail or skip)";
+const RESOLVE_PARAMETERS_HELP: &str = "Resolve Refs to the parameters of a template to their values, so rules see the value instead of {\"Ref\": \"Name\"}. \
+A parameter takes its value from the parameters file paired with the template, then from the Parameters of the input parameters, then from its Default. \
+The values are also exposed as %PARAMETER_VALUES";
+const EVALUATE_CONDITIONS_HELP: &str = "Evaluate the Conditions of a template against its parameter values and leave out the resources whose Condition is false. \
+Parameters take their values the same way as with --resolve-parameters. Conditions that depend on pseudo parameters or on parameters without a value keep their resources. \
+The pruned resources are listed with --verbose";
+const RESOLVE_JSON_REFS_HELP: &str = "Replace the $refs of the data files pointing within the same document, e.g. {\"$ref\": \"#/components/schemas/Pet\"} in an OpenAPI specification, with the values they point to. \
+Refs to other documents, to values that do not exist, and refs that lead back to themselves are left as they are";
 const ALPHABETICAL_HELP: &str = "Validate files in a directory ordered alphabetically";
 const LAST_MODIFIED_HELP: &str = "Validate files in a directory ordered by last modified times";
+const MAX_DEPTH_HELP: &str = "How deep below the rules and data directories to look for files, 1 only looks at the files directly in them. Unlimited by default";
+const MAX_FILE_SIZE_HELP: &str = "The size in bytes of the largest data file to read, a larger one fails the run. Unlimited by default";
+const MAX_NESTING_DEPTH_HELP: &str = "How deeply the mappings and lists of a data file may nest, a deeper one fails the run. Unlimited by default";
+const MAX_QUERY_RESULTS_HELP: &str = "The most values a single query of the rules may resolve to, a query resolving to more fails the run. Unlimited by default";
+const FOLLOW_SYMLINKS_HELP: &str = "Descend into symlinked directories when looking for rules and data files. Links back to a parent directory are skipped, and a file reachable through several links is evaluated once";
 const VERBOSE_HELP: &str = "Verbose logging";
 const PRINT_JSON_HELP: &str = "Print the parse tree in a json format. This can be used to get more details on how the clauses were evaluated";
+const PROGRESS_HELP: &str =
+    "Draw a progress bar of the data files evaluated so far on stderr, when stderr is a terminal";
+const METRICS_HELP: &str = "Export the number of data files and rules evaluated, the failures of each rule and the time the run and each rule took once the run is over, as StatsD datagrams or to an OpenTelemetry collector over OTLP/HTTP. A metrics endpoint that cannot be reached is reported on stderr without failing the run";
+const METRICS_ENDPOINT_HELP: &str = "The host:port of the StatsD server, 127.0.0.1:8125 by default, or the URL of the OTLP/HTTP receiver, http://localhost:4318/v1/metrics by default, the metrics are sent to";
+const TIMINGS_HELP: &str = "Write a table of the time spent evaluating each data file and each rule to stderr after the run, slowest first, to find the rules that slow the run down";
+const MESSAGE_CATALOG_HELP: &str = "A YAML or JSON file mapping message IDs, e.g. property-missing, to the wording of the failure messages of the reports, with the values of the message in placeholders, e.g. {property}. \
+Messages left out keep their English wording. The IDs are part of the json and yaml output";
 const PAYLOAD_HELP: &str = "Provide rules and data in the following JSON format via STDIN,\n{\"rules\":[\"<rules 1>\", \"<rules 2>\", ...], \"data\":[\"<data 1>\", \"<data 2>\", ...]}, where,\n- \"rules\" takes a list of string \
-                version of rules files as its value and\n- \"data\" takes a list of string version of data files as it value.\nWhen --payload is specified --rules and --data cannot be specified.";
+                version of rules files as its value and\n- \"data\" takes a list of string version of data files as it value.\nEither can also be given as an object naming it, {\"name\": \"<name>\", \"content\": \"<rules or data>\"}, the name is used in the reports instead of RULES_STDIN[n] or DATA_STDIN[n].\nWhen --payload is specified --rules and --data cannot be specified.";
+const VALIDATE_OUTPUT_FORMAT_HELP: &str = "Specify the format in which the output should be displayed [possible values: single-line-summary, json, yaml, junit, sarif, csv, tsv]. \
+Supports passing multiple values by using this option repeatedly, pairing a format with a file as format=path writes that report to the file, e.g. -o junit=report.xml -o json=report.json. Defaults to single-line-summary";
+const GROUP_BY_HELP: &str = "How the console, json and yaml output are organized: file (default) has a section for each data file, rule, resource-type and severity list the failures of each rule, type of resource or severity across every data file, the groups with the most failures first. Junit, sarif, csv and tsv reports are not affected";
+const MAX_FINDINGS_PER_RULE_HELP: &str = "How many failures of each rule the console output lists across every data file, the rest are counted, to keep the output of scans over many templates readable. The console output lists the failures of each data file a line each, as with --group-by. Unlimited by default";
+const TOP_OFFENDERS_HELP: &str = "Summarize the given number of violations repeated most across the data files after the console output, the same rule failing on the same property of any resource, with the number of failures and the data files they are in";
+const FAIL_ON_HELP: &str = "Controls which outcomes result in a non-zero exit code: violations (default) exits with 19 when rules are violated and 5 when rules could not be evaluated, \
+errors only exits with 5, warnings also exits with 19 when rules annotated with @severity(warning) fail, none always exits with 0";
+const CONFIG_HELP: &str = "A cfn-guard.toml or .guardrc file with the default rules, data, output-format, baseline, tags and exclude-tags of the project, which the flags passed override. Looked up in the current directory and its parents up to the root of the git repository when not passed";
+const VERIFY_SIGNATURE_HELP: &str = "An Ed25519 public key in PEM. Every rules pack must have a signature next to it, e.g. rules.pack.sig, written by cfn-guard sign with the matching private key, \
+and must not have been modified since. Rules files that are not rules packs are refused";
+const HEADER_HELP: &str = "A header sent along with the requests for rules and data files given as https:// URLs, e.g. --header 'Authorization: Bearer <token>'. Can be repeated. URLs require cfn-guard to be built with the remote feature";
+const TAGS_HELP: &str = "Only evaluate rules annotated with at least one of the given tags, e.g. --tags cis,s3. Rules referenced by a selected rule are still evaluated";
+const EXCLUDE_TAGS_HELP: &str =
+    "Skip rules annotated with any of the given tags, e.g. --exclude-tags experimental";
+const RESOURCE_SELECTOR_HELP: &str = "Only evaluate the resources of a template matching the selector, either Attribute==pattern to match an attribute of the resource, \
+e.g. --resource-selector 'Type==AWS::EC2::*', or a pattern matching the logical id of the resource, e.g. --resource-selector 'MyBucket*'. \
+Patterns support * and ? wildcards. Supports passing multiple values by using this option repeatedly, a resource is evaluated when it matches any of them";
+const RULE_NAMES_HELP: &str = "Only evaluate the named rules with the given names across all rules files, e.g. --rule-names s3_encryption,s3_versioning. \
+Rules referenced by a selected rule are still evaluated";
+const CONTINUE_ON_RULE_ERROR_HELP: &str = "Evaluate the rules files that parsed when others fail to parse. By default every parse error is reported and no rules are evaluated";
+const STRICT_TYPES_HELP: &str = "Fail the clauses comparing values of different types, e.g. \"true\" == true or \"5\" == 5, with a message naming both types, \
+instead of comparing them. Sizes and durations with a unit such as \"512MiB\" still compare with numbers";
+const SKIP_RULES_HELP: &str =
+    "Skip the named rules with the given names across all rules files, e.g. --skip-rules s3_logging";
+const OUTPUT_FILE_HELP: &str = "Write the structured output to the given file instead of stdout. Can only be used with the structured flag, or when output-format is junit, sarif, csv or tsv, in which case the regular output is written to stderr";
+const OUTPUT_SCHEMA_HELP: &str = "Print the JSON Schema of the structured json and yaml output of the selected output-version, whose records carry the schema_version they follow, instead of evaluating rules";
+const OUTPUT_VERSION_HELP: &str = "The layout of the json and yaml output with the structured flag. v1 (default) keeps the failures of each data file nested as they were evaluated, \
+v2 lists them as violations with their rule, severity, resource, path, line and column, along with the baselined and suppressed ones";
 const STRUCTURED_HELP: &str = "Print out a list of structured and valid JSON/YAML. This argument conflicts with the following arguments: \nverbose \n print-json \n show-summary: all/fail/pass/skip \noutput-format: single-line-summary";
 
 #[allow(clippy::too_many_arguments)]
@@ -554,45 +1705,126 @@ fn evaluate_rule(
     output: OutputFormatType,
     extra_data: &Option<PathAwareValue>,
     data_files: &Vec<DataFile>,
-    rule: RuleFileInfo,
+    rule: &RulesFile<'_>,
+    file_name: &str,
     verbose: bool,
     print_json: bool,
+    strict_types: bool,
+    limits: InputLimits,
+    functions: &Option<Arc<FunctionRegistry>>,
+    messages: &MessageCatalog,
     summary_type: BitFlags<SummaryType>,
+    selection: &RuleSelection,
     writer: &mut Writer,
+    summary: &mut Summary,
+    progress: &mut Progress,
 ) -> Result<i32> {
-    let RuleFileInfo { content, file_name } = &rule;
-    match parse_rules(content, file_name) {
-        Err(e) => {
-            writer.write_err(format!(
-                "Parsing error handling rule file = {}, Error = {e}\n---",
-                file_name.underline(),
-            ))?;
+    let status = evaluate_against_data_input(
+        data_type,
+        output,
+        extra_data,
+        data_files,
+        rule,
+        file_name,
+        verbose,
+        print_json,
+        strict_types,
+        limits,
+        functions,
+        messages,
+        summary_type,
+        selection,
+        writer,
+        summary,
+        progress,
+    )?;
+
+    match status {
+        Status::FAIL => Ok(FAILURE_STATUS_CODE),
+        _ => Ok(SUCCESS_STATUS_CODE),
+    }
+}
 
-            return Ok(ERROR_STATUS_CODE);
+/// the entries to look for files in for a rules, data or input parameters argument, which is either a
+/// path or a glob pattern, along with whether it named a single file
+pub(crate) fn walk_argument(
+    file_or_dir: &str,
+    cmp: fn(&walkdir::DirEntry, &walkdir::DirEntry) -> cmp::Ordering,
+    options: WalkOptions,
+) -> Result<(Box<dyn Iterator<Item = walkdir::DirEntry>>, bool)> {
+    if is_glob(file_or_dir) {
+        let entries = walk_glob(file_or_dir, cmp, options)?.collect::<Vec<_>>();
+        if entries.is_empty() {
+            return Err(Error::FileNotFoundError(file_or_dir.to_string()));
         }
+        return Ok((Box::new(entries.into_iter()), false));
+    }
 
-        Ok(Some(rule)) => {
-            let status = evaluate_against_data_input(
-                data_type,
-                output,
-                extra_data,
-                data_files,
-                &rule,
-                file_name,
-                verbose,
-                print_json,
-                summary_type,
-                writer,
-            )?;
+    validate_path(file_or_dir)?;
+    let base = resolve_path(file_or_dir)?;
+    let single_file = base.is_file();
+    Ok((Box::new(walk_dir(base, cmp, options)), single_file))
+}
 
-            if status == Status::FAIL {
-                return Ok(FAILURE_STATUS_CODE);
-            }
-        }
-        Ok(None) => return Ok(SUCCESS_STATUS_CODE),
+/// the files the arguments point to, for directories and glob patterns only the ones with one of
+/// the extensions
+pub(crate) fn argument_files(arguments: &[String], extensions: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for file_or_dir in arguments {
+        let (entries, single_file) =
+            walk_argument(file_or_dir, alphabetical, WalkOptions::default())?;
+        files.extend(
+            entries
+                .filter(|entry| entry.path().is_file())
+                .filter(|entry| {
+                    single_file
+                        || entry
+                            .path()
+                            .to_str()
+                            .map_or(false, |name| has_a_supported_extension(name, extensions))
+                })
+                .map(|entry| entry.path().to_path_buf()),
+        );
     }
+    Ok(files)
+}
+
+pub(crate) fn is_rule_pack(path: &Path) -> bool {
+    path.to_str()
+        .map_or(false, |name| name.ends_with(RULE_PACK_EXTENSION))
+}
+
+/// the content of the public key passed to --verify-signature
+pub(crate) fn read_public_key(path: &Option<String>) -> Result<Option<String>> {
+    Ok(path.as_ref().map(std::fs::read_to_string).transpose()?)
+}
 
-    Ok(SUCCESS_STATUS_CODE)
+/// the rules files of the rules packs, after checking the signature written next to each pack
+/// when given the public key in PEM
+///
+/// This function will return an error if
+/// - a file is not a rules pack built by this version
+/// - a pack has no signature, or one that does not match the key and its content
+pub(crate) fn read_rule_packs(
+    packs: &[PathBuf],
+    public_key: Option<&str>,
+) -> Result<Vec<PackedRulesFile<'static>>> {
+    let mut files = vec![];
+    for pack in packs {
+        let name = pack.display().to_string();
+        let content = std::fs::read(pack)?;
+        if let Some(public_key) = public_key {
+            let signature_path = format!("{name}{SIGNATURE_EXTENSION}");
+            let signature = std::fs::read_to_string(&signature_path).map_err(|_| {
+                Error::SignatureError(format!(
+                    "{name} has no signature {signature_path}, sign it with cfn-guard sign"
+                ))
+            })?;
+            verify_pack(&name, &content, &signature, public_key)?;
+        }
+        files.extend(read_pack(&name, &content)?);
+    }
+    Ok(files)
 }
 
 pub(crate) fn validate_path(base: &str) -> Result<()> {
@@ -696,15 +1928,83 @@ fn evaluate_against_data_input<'r>(
     rules_file_name: &'r str,
     verbose: bool,
     print_json: bool,
+    strict_types: bool,
+    limits: InputLimits,
+    functions: &Option<Arc<FunctionRegistry>>,
+    messages: &MessageCatalog,
     summary_table: BitFlags<SummaryType>,
-    mut write_output: &mut Writer,
+    selection: &RuleSelection,
+    write_output: &mut Writer,
+    summary: &mut Summary,
+    progress: &mut Progress,
 ) -> Result<Status> {
-    let mut overall = Status::PASS;
-    let generic: Box<dyn Reporter> =
-        Box::new(generic_summary::GenericSummary::new(summary_table)) as Box<dyn Reporter>;
-    let tf: Box<dyn Reporter> = Box::new(TfAware::new_with(generic.as_ref())) as Box<dyn Reporter>;
+    with_console_reporter(summary_table, messages, |reporter| {
+        let console = Console {
+            reporter,
+            output,
+            verbose,
+            print_json,
+        };
+        let mut overall = Status::PASS;
+        for file in data_files {
+            let each = match &extra_data {
+                Some(data) => data.clone().merge(file.path_value.clone())?,
+                None => file.path_value.clone(),
+            };
+            let mut root_scope = root_scope(rules, Rc::new(each.clone()))
+                .with_selection(selection)
+                .with_parameters_file(file.parameters_file.clone())
+                .with_parameter_values(file.parameter_values.clone())
+                .with_vars(file.vars.clone())
+                .with_stack_resources(file.stack_resources.clone())
+                .with_strict_types(strict_types)
+                .with_limits(limits)
+                .with_functions(functions.clone());
+            let start = Instant::now();
+            let status = eval_rules_file(rules, &mut root_scope, Some(&file.name))?;
+            summary.record_durations(
+                rules_file_name,
+                &file.name,
+                start.elapsed(),
+                root_scope.durations(),
+            );
+
+            let root_record = root_scope.reset_recorder().extract();
+            summary.record(&root_record)?;
+            progress.tick(&file.name);
+
+            console.write(
+                write_output,
+                status,
+                &root_record,
+                rules_file_name,
+                file,
+                &each,
+            )?;
+
+            if status == Status::FAIL {
+                overall = Status::FAIL
+            }
+        }
+        Ok(overall)
+    })
+}
+
+/// runs `f` with the reporter writing the regular console output of an evaluation, wrapped in the
+/// summary table when asked for one
+fn with_console_reporter<T>(
+    summary_table: BitFlags<SummaryType>,
+    messages: &MessageCatalog,
+    f: impl FnOnce(&dyn Reporter) -> T,
+) -> T {
+    let generic: Box<dyn Reporter> = Box::new(generic_summary::GenericSummary::new(
+        summary_table,
+        messages,
+    )) as Box<dyn Reporter>;
+    let tf: Box<dyn Reporter> =
+        Box::new(TfAware::new_with(generic.as_ref(), messages)) as Box<dyn Reporter>;
     let cfn: Box<dyn Reporter> =
-        Box::new(cfn::CfnAware::new_with(tf.as_ref())) as Box<dyn Reporter>;
+        Box::new(cfn::CfnAware::new_with(tf.as_ref(), messages)) as Box<dyn Reporter>;
 
     let reporter: Box<dyn Reporter> = if summary_table.is_empty() {
         cfn
@@ -715,66 +2015,86 @@ fn evaluate_against_data_input<'r>(
         )) as Box<dyn Reporter>
     };
 
-    for file in data_files {
-        let each = match &extra_data {
-            Some(data) => data.clone().merge(file.path_value.clone())?,
-            None => file.path_value.clone(),
-        };
-        let traversal = Traversal::from(&each);
-        let mut root_scope = root_scope(rules, Rc::new(each.clone()));
-        let status = eval_rules_file(rules, &mut root_scope, Some(&file.name))?;
+    f(reporter.as_ref())
+}
 
-        let root_record = root_scope.reset_recorder().extract();
+/// the regular console output of an evaluation, along with its tree with `verbose` and its
+/// records with `print_json`
+pub(crate) struct Console<'report> {
+    pub(crate) reporter: &'report dyn Reporter,
+    pub(crate) output: OutputFormatType,
+    pub(crate) verbose: bool,
+    pub(crate) print_json: bool,
+}
 
-        reporter.report_eval(
-            &mut write_output,
+impl Console<'_> {
+    /// writes the console output of the rules file evaluated against `data`, the data file merged
+    /// with the input parameters
+    pub(crate) fn write(
+        &self,
+        writer: &mut Writer,
+        status: Status,
+        root_record: &EventRecord<'_>,
+        rules_file_name: &str,
+        data_file: &DataFile,
+        data: &PathAwareValue,
+    ) -> Result<()> {
+        let traversal = Traversal::from(data);
+        self.reporter.report_eval(
+            writer,
             status,
-            &root_record,
+            root_record,
             rules_file_name,
-            &file.name,
-            &file.content,
+            &data_file.name,
+            &data_file.content,
             &traversal,
-            output,
+            self.output,
         )?;
 
-        if verbose {
-            print_verbose_tree(&root_record, write_output);
+        if self.verbose {
+            print_verbose_tree(root_record, writer);
         }
 
-        if print_json {
-            writeln!(
-                write_output,
-                "{}",
-                serde_json::to_string_pretty(&root_record)?
-            )
-            .expect("Unable to write to the output");
+        if self.print_json {
+            writeln!(writer, "{}", serde_json::to_string_pretty(root_record)?)
+                .expect("Unable to write to the output");
         }
 
-        if status == Status::FAIL {
-            overall = Status::FAIL
-        }
+        Ok(())
     }
-    Ok(overall)
 }
 
-fn build_data_file(content: String, name: String) -> Result<DataFile> {
+pub(crate) fn build_data_file(content: String, name: String) -> Result<DataFile> {
+    build_limited_data_file(content, name, &InputLimits::default())
+}
+
+/// a data file, failing when it is larger or nests deeper than the limits allow
+pub(crate) fn build_limited_data_file(
+    content: String,
+    name: String,
+    limits: &InputLimits,
+) -> Result<DataFile> {
+    limits.check_file_size(&name, content.len())?;
     if content.trim().is_empty() {
         return Err(Error::ParseError(format!(
             "Unable to parse a template from data file: {name} is empty"
         )));
     }
 
-    let path_value = match crate::rules::values::read_from(&content) {
+    let path_value = match read_from_with_max_depth(&content, limits.max_nesting_depth) {
         Ok(value) => PathAwareValue::try_from(value)?,
+        Err(Error::LimitExceeded(e)) => {
+            return Err(Error::LimitExceeded(format!("data file {name} {e}")))
+        }
         Err(e) => {
             if matches!(e, Error::InternalError(InternalError::InvalidKeyType(..))) {
                 return Err(Error::ParseError(e.to_string()));
             }
 
-            let str_len: usize = cmp::min(content.len(), 100);
+            // cut at a character boundary, the content need not be ASCII
+            let beginning = content.chars().take(100).collect::<String>();
             return Err(Error::ParseError(format!(
-                "Error encountered while parsing data file: {name}, data beginning with \n{}\n ...",
-                &content[..str_len]
+                "Error encountered while parsing data file: {name}, data beginning with \n{beginning}\n ..."
             )));
         }
     };
@@ -783,10 +2103,81 @@ fn build_data_file(content: String, name: String) -> Result<DataFile> {
         name,
         path_value,
         content,
+        parameters_file: None,
+        parameter_values: None,
+        vars: None,
+        stack_resources: None,
     })
 }
 
-fn has_a_supported_extension(name: &str, extensions: &[&str]) -> bool {
+/// the name of the data file the `global_rules` are evaluated against
+pub(crate) const GLOBAL_DATA: &str = "GLOBAL";
+
+/// every data file as a single document, `{"Files": [{"Name": ..., "Content": ...}]}`, for the
+/// `global_rules` to assert over all of them at once
+pub(crate) fn global_data_file(data_files: &[DataFile]) -> Result<DataFile> {
+    let files = data_files
+        .iter()
+        .map(|file| {
+            let (_, content): (String, serde_json::Value) = (&file.path_value).try_into()?;
+            Ok(serde_json::json!({ "Name": file.name, "Content": content }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let document = serde_json::json!({ "Files": files });
+
+    Ok(DataFile {
+        content: serde_json::to_string_pretty(&document)?,
+        path_value: PathAwareValue::try_from(document)?,
+        name: GLOBAL_DATA.to_string(),
+        parameters_file: None,
+        parameter_values: None,
+        vars: data_files.first().and_then(|file| file.vars.clone()),
+        stack_resources: None,
+    })
+}
+
+/// the parameters file paired with a data file, `stack.yaml` is paired with `stack.params.json`
+/// when the suffix is `.params.json`
+fn parameters_file_for(data_file: &str, suffix: &str) -> Option<PathBuf> {
+    if suffix.is_empty() || data_file.ends_with(suffix) {
+        return None;
+    }
+
+    let stem = DATA_FILE_SUPPORTED_EXTENSIONS
+        .iter()
+        .find_map(|extension| data_file.strip_suffix(extension))?;
+    let parameters_file = PathBuf::from(format!("{stem}{suffix}"));
+    parameters_file.is_file().then_some(parameters_file)
+}
+
+/// whether a data file is the parameters file of a template next to it, these are only
+/// evaluated as part of the template when scanning directories
+fn is_parameters_file(data_file: &str, suffix: &str) -> bool {
+    match data_file.strip_suffix(suffix) {
+        Some(stem) if !suffix.is_empty() => DATA_FILE_SUPPORTED_EXTENSIONS
+            .iter()
+            .any(|extension| Path::new(&format!("{stem}{extension}")).is_file()),
+        _ => false,
+    }
+}
+
+fn read_parameters_file(
+    data_file: &str,
+    suffix: &str,
+    limits: &InputLimits,
+) -> Result<Option<Rc<PathAwareValue>>> {
+    match parameters_file_for(data_file, suffix) {
+        Some(path) => {
+            let content = read_file_content(File::open(&path)?)?;
+            let DataFile { path_value, .. } =
+                build_limited_data_file(content, path.display().to_string(), limits)?;
+            Ok(Some(Rc::new(path_value)))
+        }
+        None => Ok(None),
+    }
+}
+
+pub(crate) fn has_a_supported_extension(name: &str, extensions: &[&str]) -> bool {
     extensions.iter().any(|extension| name.ends_with(extension))
 }
 
@@ -804,7 +2195,7 @@ fn get_file_name(file: &Path, base: &Path) -> String {
     }
 }
 
-fn get_rule_info(rules: &[PathBuf], writer: &mut Writer) -> Result<Vec<RuleFileInfo>> {
+pub(crate) fn get_rule_info(rules: &[PathBuf], writer: &mut Writer) -> Result<Vec<RuleFileInfo>> {
     iterate_over(rules, |content, file| {
         Ok(RuleFileInfo {
             content,
@@ -827,6 +2218,92 @@ pub(crate) struct RuleFileInfo {
     pub(crate) file_name: String,
 }
 
+/// a rules file that failed to parse
+#[derive(Debug)]
+pub(crate) struct RuleFileError<'r> {
+    pub(crate) file_name: &'r str,
+    pub(crate) error: Error,
+    /// the syntax errors further down the file, found by resuming parsing after `error`
+    pub(crate) following_errors: Vec<Error>,
+}
+
+impl<'r> RuleFileError<'r> {
+    /// the first error along with the ones following it
+    pub(crate) fn all_errors(&self) -> impl Iterator<Item = &Error> {
+        std::iter::once(&self.error).chain(self.following_errors.iter())
+    }
+}
+
+/// the rules files that parsed, paired with their name, and the ones that did not
+#[derive(Debug, Default)]
+pub(crate) struct ParsedRules<'r> {
+    pub(crate) rules: Vec<(RulesFile<'r>, &'r str)>,
+    /// the rules files evaluated once against every data file together
+    pub(crate) global: Vec<(RulesFile<'r>, &'r str)>,
+    pub(crate) errors: Vec<RuleFileError<'r>>,
+}
+
+impl<'r> ParsedRules<'r> {
+    /// adds the rules files of rules packs, parsed when the packs were built
+    pub(crate) fn add_packed(&mut self, packed: &'r [PackedRulesFile<'r>]) {
+        self.rules.extend(
+            packed
+                .iter()
+                .map(|file| (file.rules.clone(), file.name.as_str())),
+        );
+    }
+
+    /// parses the rules files of `global_rules`, their errors are reported with the others
+    pub(crate) fn add_global(&mut self, global_info: &'r [RuleFileInfo]) {
+        let ParsedRules { rules, errors, .. } = parse_rule_files(global_info);
+        self.global.extend(rules);
+        self.errors.extend(errors);
+    }
+
+    /// the data file the global rules are evaluated against, none without global rules
+    pub(crate) fn global_data(&self, data: &[DataFile]) -> Result<Vec<DataFile>> {
+        match self.global.is_empty() {
+            true => Ok(vec![]),
+            false => Ok(vec![global_data_file(data)?]),
+        }
+    }
+
+    /// the exit code of the run before any rule is evaluated
+    fn exit_code(&self) -> i32 {
+        match self.errors.is_empty() {
+            true => SUCCESS_STATUS_CODE,
+            false => ERROR_STATUS_CODE,
+        }
+    }
+}
+
+/// parses every rules file instead of stopping at the first one that fails, empty files are skipped
+pub(crate) fn parse_rule_files(rule_info: &[RuleFileInfo]) -> ParsedRules<'_> {
+    rule_info.iter().fold(
+        ParsedRules::default(),
+        |mut parsed, RuleFileInfo { content, file_name }| {
+            match parse_rules(content, file_name) {
+                Ok(Some(rules)) => parsed.rules.push((rules, file_name)),
+                Ok(None) => {}
+                Err(error) => {
+                    let following_errors = match error.parse_error_location() {
+                        Some(location) => {
+                            syntax_errors_after(Span::new_extra(content, file_name), location.line)
+                        }
+                        None => vec![],
+                    };
+                    parsed.errors.push(RuleFileError {
+                        file_name,
+                        error,
+                        following_errors,
+                    })
+                }
+            }
+            parsed
+        },
+    )
+}
+
 #[cfg(test)]
 #[path = "validate_tests.rs"]
 mod validate_tests;
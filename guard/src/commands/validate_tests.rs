@@ -7,15 +7,56 @@ fn test_deserialize_payload_success() {
     let serialized_payload = "{\"data\":[\"data as string 1\",\"data as string 2\"], \"rules\":[\"rules as string 1\",\"rules as string 2\"]}";
     let deserialized_payload = deserialize_payload(serialized_payload).unwrap();
     assert_eq!(
-        deserialized_payload.list_of_data,
+        deserialized_payload
+            .list_of_data
+            .iter()
+            .map(PayloadDocument::content)
+            .collect::<Vec<_>>(),
         vec!["data as string 1", "data as string 2"]
     );
     assert_eq!(
-        deserialized_payload.list_of_rules,
+        deserialized_payload
+            .list_of_rules
+            .iter()
+            .map(PayloadDocument::content)
+            .collect::<Vec<_>>(),
         vec!["rules as string 1", "rules as string 2"]
     );
 }
 
+#[test]
+fn test_deserialize_payload_named_documents() {
+    let serialized_payload = r#"{"data":[{"name":"template.yaml","content":"data as string 1"},"data as string 2"], "rules":[{"name":"s3.guard","content":"rules as string 1"}]}"#;
+    let deserialized_payload = deserialize_payload(serialized_payload).unwrap();
+    let names = |documents: &[PayloadDocument], prefix: &str| {
+        documents
+            .iter()
+            .enumerate()
+            .map(|(i, each)| each.name_or(|| format!("{prefix}[{}]", i + 1)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(&deserialized_payload.list_of_data, "DATA_STDIN"),
+        vec!["template.yaml", "DATA_STDIN[2]"]
+    );
+    assert_eq!(
+        names(&deserialized_payload.list_of_rules, "RULES_STDIN"),
+        vec!["s3.guard"]
+    );
+    assert_eq!(
+        deserialized_payload.list_of_rules[0].content(),
+        "rules as string 1"
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_deserialize_payload_named_document_without_content() {
+    let serialized_payload =
+        r#"{"data":[{"name":"template.yaml"}], "rules":["rules as string 1"]}"#;
+    deserialize_payload(serialized_payload).unwrap();
+}
+
 #[test]
 #[should_panic]
 fn test_deserialize_payload_malformed_string() {
@@ -89,3 +130,94 @@ fn test_supported_extensions() {
         &RULE_FILE_SUPPORTED_EXTENSIONS
     ));
 }
+
+#[test]
+fn test_parse_resource_selector() {
+    assert_eq!(
+        "Type==AWS::EC2::*".parse::<ResourceSelector>(),
+        Ok(ResourceSelector {
+            attribute: Some(String::from("Type")),
+            pattern: String::from("AWS::EC2::*"),
+        })
+    );
+    assert_eq!(
+        "MyBucket?".parse::<ResourceSelector>(),
+        Ok(ResourceSelector {
+            attribute: None,
+            pattern: String::from("MyBucket?"),
+        })
+    );
+    assert!("Type==".parse::<ResourceSelector>().is_err());
+    assert!("==AWS::S3::Bucket".parse::<ResourceSelector>().is_err());
+    assert!("".parse::<ResourceSelector>().is_err());
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("AWS::EC2::*", "AWS::EC2::Volume"));
+    assert!(glob_match("*Bucket*", "LoggingBucketPolicy"));
+    assert!(glob_match("Bucket?", "Bucket1"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("a*b*c", "aXbYbZc"));
+    assert!(!glob_match("AWS::EC2::*", "AWS::S3::Bucket"));
+    assert!(!glob_match("Bucket?", "Bucket"));
+    assert!(!glob_match("Bucket", "Bucket1"));
+}
+
+#[test]
+fn test_parameters_file_pairing() {
+    let dir = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/resources/validate/parameters-file"
+    );
+    let stack = format!("{dir}/stack.yaml");
+    let parameters = format!("{dir}/stack.params.json");
+
+    assert_eq!(
+        Some(PathBuf::from(&parameters)),
+        parameters_file_for(&stack, DEFAULT_PARAMETERS_FILE_SUFFIX)
+    );
+    assert_eq!(None, parameters_file_for(&stack, ""));
+    assert_eq!(
+        None,
+        parameters_file_for(&format!("{dir}/other.yaml"), DEFAULT_PARAMETERS_FILE_SUFFIX)
+    );
+    assert_eq!(
+        None,
+        parameters_file_for(&parameters, DEFAULT_PARAMETERS_FILE_SUFFIX)
+    );
+
+    assert!(is_parameters_file(
+        &parameters,
+        DEFAULT_PARAMETERS_FILE_SUFFIX
+    ));
+    assert!(!is_parameters_file(&parameters, ""));
+    assert!(!is_parameters_file(&stack, DEFAULT_PARAMETERS_FILE_SUFFIX));
+}
+
+#[test]
+fn test_build_data_file_with_limits() {
+    let limits = InputLimits {
+        max_nesting_depth: Some(2),
+        ..InputLimits::default()
+    };
+    assert!(
+        build_limited_data_file(String::from("a: [1]"), String::from("a.yaml"), &limits).is_ok()
+    );
+    match build_limited_data_file(String::from("a: [[1]]"), String::from("a.yaml"), &limits) {
+        Err(Error::LimitExceeded(message)) => {
+            assert!(message.starts_with("data file a.yaml nests"), "{message}")
+        }
+        result => panic!("expected the limit to be exceeded, got {result:?}"),
+    }
+
+    // the beginning of data that fails to parse is cut at a character boundary
+    let content = format!("a{}: [", "é".repeat(120));
+    match build_data_file(content, String::from("unicode.yaml")) {
+        Err(Error::ParseError(message)) => assert!(
+            message.contains(&format!("a{}\n", "é".repeat(99))),
+            "{message}"
+        ),
+        result => panic!("expected a parse error, got {result:?}"),
+    }
+}
@@ -8,13 +8,56 @@ pub mod commands;
 mod rules;
 pub mod utils;
 
-pub use crate::commands::helper::{validate_and_return_json as run_checks, ValidateInput};
+pub use crate::commands::adapters::{register_adapter, InputAdapter};
+pub use crate::commands::helper::{
+    parse_rules, validate_and_return_json as run_checks, CompiledRules, ValidateInput,
+};
+#[cfg(feature = "async")]
+pub use crate::commands::helper::{
+    validate_and_return_json_async as run_checks_async,
+    validate_files_and_return_json_async as run_checks_on_files_async,
+};
 use crate::commands::parse_tree::ParseTree;
+pub use crate::commands::reporters::schema::{
+    OUTPUT_SCHEMA_VERSION, OUTPUT_SCHEMA_VERSION_V2, TEST_OUTPUT_SCHEMA, VALIDATE_OUTPUT_SCHEMA,
+    VALIDATE_OUTPUT_SCHEMA_V2,
+};
 use crate::commands::rulegen::Rulegen;
+use crate::commands::rules_diff::Diff;
+use crate::commands::suggest::Suggest;
 use crate::commands::test::Test;
-use crate::commands::validate::{OutputFormatType, ShowSummaryType, Validate};
+use crate::commands::validate::{
+    FailOn, GroupBy, OutputFormatType, OutputTarget, OutputVersion, ShowSummaryType, Validate,
+    DEFAULT_PARAMETERS_FILE_SUFFIX,
+};
 use crate::commands::Executable;
+pub use crate::commands::ExitCode;
 pub use crate::rules::errors::Error;
+pub use crate::rules::functions::custom::{CustomFunction, FunctionRegistry};
+pub use crate::rules::limits::InputLimits;
+pub use crate::rules::messages::{MessageCatalog, MessageId};
+pub use crate::rules::observer::{EvaluationEvent, EvaluationObserver, EventKind};
+pub use crate::rules::Status;
+
+/// The syntax tree of rules files, for tools built on top of the rules such as linters, migrators
+/// or rule generators. [`ast::RulesFile::parse`] parses rules into it and
+/// [`ast::RulesFile::to_guard_source`] writes them back as guard rules. It serializes to the JSON
+/// and YAML printed by `cfn-guard parse-tree`, and reads back from them. [`ast::RuleBuilder`]
+/// builds rules without writing them as text, to be evaluated with
+/// [`CompiledRules::from_rules_file`].
+pub mod ast {
+    pub use crate::rules::builder::{ClauseBuilder, RuleBuilder, RulesFileBuilder};
+    pub use crate::rules::eval_context::FunctionName;
+    pub use crate::rules::exprs::{
+        AccessClause, AccessQuery, Block, BlockGuardClause, Conjunctions, Disjunctions,
+        FileLocation, FunctionExpr, GuardAccessClause, GuardClause, GuardNamedRuleClause, LetExpr,
+        LetValue, MapKeyFilterClause, OnMissing, ParameterizedNamedRuleClause, ParameterizedRule,
+        QueryPart, Rule, RuleAnnotations, RuleClause, RulesFile, Severity, TypeBlock,
+        WhenConditions, WhenGuardClause,
+    };
+    pub use crate::rules::path_value::{Location, MapValue, Path, PathAwareValue};
+    pub use crate::rules::values::{CmpOperator, RangeType};
+}
 
 #[cfg(target_arch = "wasm32")]
 use crate::utils::reader::{ReadBuffer, Reader};
@@ -22,6 +65,7 @@ use crate::utils::reader::{ReadBuffer, Reader};
 use crate::utils::writer::WriteBuffer::Vec as WBVec;
 #[cfg(target_arch = "wasm32")]
 use std::io::Cursor;
+use std::sync::Arc;
 
 use wasm_bindgen::prelude::*;
 
@@ -95,34 +139,98 @@ impl ParseTreeBuilder {
 /// A builder to help construct the `Validate` command
 pub struct ValidateBuilder {
     rules: Vec<String>,
+    global_rules: Vec<String>,
     data: Vec<String>,
+    from_stack: Vec<String>,
     input_params: Vec<String>,
+    vars: Option<String>,
+    baseline: Option<String>,
+    update_baseline: bool,
     template_type: Option<String>,
     output_format: OutputFormatType,
+    output_file: Option<String>,
+    reports: Vec<OutputTarget>,
+    fail_on: FailOn,
+    group_by: GroupBy,
+    max_findings_per_rule: Option<usize>,
+    top_offenders: Option<usize>,
+    tags: Vec<String>,
+    exclude_tags: Vec<String>,
+    rule_names: Vec<String>,
+    skip_rules: Vec<String>,
+    continue_on_rule_error: bool,
+    strict_types: bool,
+    parameters_file_suffix: String,
+    resolve_parameters: bool,
+    evaluate_conditions: bool,
+    resolve_json_refs: bool,
+    resource_selectors: Vec<String>,
+    headers: Vec<String>,
     show_summary: Vec<ShowSummaryType>,
     alphabetical: bool,
     last_modified: bool,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    max_file_size: Option<usize>,
+    max_nesting_depth: Option<usize>,
+    max_query_results: Option<usize>,
+    verify_signature: Option<String>,
     verbose: bool,
     print_json: bool,
     payload: bool,
     structured: bool,
+    output_version: OutputVersion,
+    functions: Option<Arc<FunctionRegistry>>,
+    messages: MessageCatalog,
 }
 
 impl Default for ValidateBuilder {
     fn default() -> Self {
         Self {
             rules: Default::default(),
+            global_rules: Default::default(),
             data: Default::default(),
+            from_stack: Default::default(),
             input_params: Default::default(),
+            vars: None,
+            baseline: None,
+            update_baseline: false,
             template_type: Default::default(),
             output_format: Default::default(),
+            output_file: Default::default(),
+            reports: Default::default(),
+            fail_on: Default::default(),
+            group_by: Default::default(),
+            max_findings_per_rule: None,
+            top_offenders: None,
+            tags: Default::default(),
+            exclude_tags: Default::default(),
+            rule_names: Default::default(),
+            skip_rules: Default::default(),
+            continue_on_rule_error: false,
+            strict_types: false,
+            parameters_file_suffix: String::from(DEFAULT_PARAMETERS_FILE_SUFFIX),
+            resolve_parameters: false,
+            evaluate_conditions: false,
+            resolve_json_refs: false,
+            resource_selectors: Default::default(),
+            headers: Default::default(),
             show_summary: vec![Default::default()],
             alphabetical: Default::default(),
             last_modified: false,
+            max_depth: None,
+            follow_symlinks: false,
+            max_file_size: None,
+            max_nesting_depth: None,
+            max_query_results: None,
+            verify_signature: None,
             verbose: false,
             print_json: false,
             payload: false,
             structured: false,
+            output_version: Default::default(),
+            functions: None,
+            messages: Default::default(),
         }
     }
 }
@@ -134,7 +242,6 @@ impl CommandBuilder<Validate> for ValidateBuilder {
     /// This function will return an error if
     /// - conflicting attributes have been set
     /// - both rules is empty, and payload is false
-    #[allow(deprecated)]
     fn try_build(self) -> crate::rules::Result<Validate> {
         if self.structured {
             if self.output_format == OutputFormatType::SingleLineSummary {
@@ -164,22 +271,37 @@ impl CommandBuilder<Validate> for ValidateBuilder {
                     "Cannot provide a summary-type other than `none` when the `structured` flag is present",
                 )));
             }
-        } else if matches!(
-            self.output_format,
-            OutputFormatType::Junit | OutputFormatType::Sarif
-        ) {
-            return Err(Error::IllegalArguments(format!(
-                "the structured flag must be set when output is set to {:?}",
-                self.output_format
+        } else if self.output_file.is_some() && !self.output_format.is_report() {
+            return Err(Error::IllegalArguments(String::from(
+                "output_file can only be used when structured is set to true, or when output is set to junit or sarif",
+            )));
+        }
+
+        if self
+            .reports
+            .iter()
+            .any(|report| !report.format.is_structured())
+        {
+            return Err(Error::IllegalArguments(String::from(
+                "single-line-summary cannot be written to a report file",
             )));
         }
 
-        if self.payload && (!self.rules.is_empty() || !self.data.is_empty()) {
+        if self.payload
+            && (!self.rules.is_empty()
+                || !self.global_rules.is_empty()
+                || !self.data.is_empty()
+                || !self.from_stack.is_empty())
+        {
             return Err(Error::IllegalArguments(String::from("cannot construct a validate command payload conflicts with both data and rules arguments")));
         }
 
-        if !self.payload && self.rules.is_empty() {
-            return Err(Error::IllegalArguments(String::from("cannot construct a validate command: either payload must be set to true, or rules must not be empty")));
+        if !self.payload && self.rules.is_empty() && self.global_rules.is_empty() {
+            return Err(Error::IllegalArguments(String::from("cannot construct a validate command: either payload must be set to true, or rules or global_rules must not be empty")));
+        }
+
+        if self.payload && self.verify_signature.is_some() {
+            return Err(Error::IllegalArguments(String::from("cannot construct a validate command: payload conflicts with verify_signature, only signed rules packs are evaluated")));
         }
 
         if self.last_modified && self.alphabetical {
@@ -188,34 +310,157 @@ impl CommandBuilder<Validate> for ValidateBuilder {
             )));
         }
 
+        if self.max_depth == Some(0) {
+            return Err(Error::IllegalArguments(String::from(
+                "max_depth must be at least 1, a depth of 0 would not look inside any directory",
+            )));
+        }
+
+        if self.group_by != GroupBy::File && (self.verbose || self.print_json) {
+            return Err(Error::IllegalArguments(String::from(
+                "cannot construct a validate command: group_by conflicts with verbose and print_json, which print the evaluation of each data file",
+            )));
+        }
+
+        if (self.max_findings_per_rule.is_some() || self.top_offenders.is_some())
+            && (self.verbose || self.print_json)
+        {
+            return Err(Error::IllegalArguments(String::from(
+                "cannot construct a validate command: max_findings_per_rule and top_offenders conflict with verbose and print_json, which print the evaluation of each data file",
+            )));
+        }
+
+        if let Some(limit) = [
+            ("max_file_size", self.max_file_size),
+            ("max_nesting_depth", self.max_nesting_depth),
+            ("max_query_results", self.max_query_results),
+        ]
+        .iter()
+        .find_map(|(limit, value)| (*value == Some(0)).then_some(limit))
+        {
+            return Err(Error::IllegalArguments(format!(
+                "{limit} must be at least 1, a limit of 0 would reject every data file"
+            )));
+        }
+
         let ValidateBuilder {
             rules,
+            global_rules,
             data,
+            from_stack,
             input_params,
+            vars,
+            baseline,
+            update_baseline,
             template_type,
             output_format,
+            output_file,
+            reports,
+            fail_on,
+            group_by,
+            max_findings_per_rule,
+            top_offenders,
+            tags,
+            exclude_tags,
+            rule_names,
+            skip_rules,
+            continue_on_rule_error,
+            strict_types,
+            parameters_file_suffix,
+            resolve_parameters,
+            evaluate_conditions,
+            resolve_json_refs,
+            resource_selectors,
+            headers,
             show_summary,
             alphabetical,
             last_modified,
+            max_depth,
+            follow_symlinks,
+            max_file_size,
+            max_nesting_depth,
+            max_query_results,
+            verify_signature,
             verbose,
             print_json,
             payload,
             structured,
+            output_version,
+            functions,
+            messages,
         } = self;
 
+        let resource_selectors = resource_selectors
+            .iter()
+            .map(|selector| selector.parse().map_err(Error::IllegalArguments))
+            .collect::<crate::rules::Result<Vec<_>>>()?;
+
+        let headers = headers
+            .iter()
+            .map(|header| header.parse().map_err(Error::IllegalArguments))
+            .collect::<crate::rules::Result<Vec<_>>>()?;
+
         Ok(Validate {
             rules,
+            global_rules,
             data,
+            from_stack,
             input_params,
+            vars,
+            baseline,
+            update_baseline,
             template_type,
-            output_format,
+            outputs: std::iter::once(OutputTarget::from(output_format))
+                .chain(reports)
+                .collect(),
+            output_file,
+            fail_on,
+            group_by,
+            max_findings_per_rule,
+            top_offenders,
+            tags,
+            exclude_tags,
+            rule_names,
+            skip_rules,
+            continue_on_rule_error,
+            strict_types,
+            parameters_file_suffix,
+            resolve_parameters,
+            evaluate_conditions,
+            resolve_json_refs,
+            resource_selectors,
+            headers,
             show_summary,
             alphabetical,
             last_modified,
+            max_depth,
+            follow_symlinks,
+            max_file_size,
+            max_nesting_depth,
+            max_query_results,
+            verify_signature,
             verbose,
             print_json,
+            // the progress bar and the timings table are only drawn by the command line, the
+            // durations are in the summary
+            progress: false,
+            timings: false,
+            // metrics are exported by the command line, library users export the summary returned
+            // by execute_with_summary to their own sink
+            metrics: None,
+            metrics_endpoint: None,
+            // library users set the wording of the messages with message_catalog
+            message_catalog: None,
+            messages,
+            // the config file of a project is only read by the command line
+            config: None,
             payload,
             structured,
+            // the schema of the output is exported as VALIDATE_OUTPUT_SCHEMA, only the command line
+            // prints it
+            output_schema: false,
+            output_version,
+            functions,
         })
     }
 }
@@ -230,7 +475,18 @@ impl ValidateBuilder {
         self
     }
 
+    /// a list of paths that point to rule files, or directories containing rule files, evaluated
+    /// once against every data file together, which they query as `Files`
+    /// conflicts with payload
+    #[wasm_bindgen(js_name = globalRules)]
+    pub fn global_rules(mut self, rules: Vec<String>) -> Self {
+        self.global_rules = rules;
+
+        self
+    }
+
     /// a list of paths that point to data files, or a directory containing data files  for the rules to be evaluated against. Only JSON, or YAML files will be used
+    /// `-` reads a single template from the reader
     /// conflicts with payload
     pub fn data(mut self, data: Vec<String>) -> Self {
         self.data = data;
@@ -238,6 +494,16 @@ impl ValidateBuilder {
         self
     }
 
+    /// names of deployed CloudFormation stacks whose templates are evaluated along with the data
+    /// files, fetched with the credentials and region of the environment. Requires the aws feature
+    /// conflicts with payload
+    #[wasm_bindgen(js_name = fromStack)]
+    pub fn from_stack(mut self, stacks: Vec<String>) -> Self {
+        self.from_stack = stacks;
+
+        self
+    }
+
     /// Controls if the summary table needs to be displayed. --show-summary fail (default) or --show-summary pass,fail (only show rules that did pass/fail) or --show-summary none (to turn it off) or --show-summary all (to show all the rules that pass, fail or skip)
     /// default is failed
     /// must be set to none if used together with the structured flag
@@ -257,7 +523,8 @@ impl ValidateBuilder {
 
     /// Specify the format in which the output should be displayed
     /// default is single-line-summary
-    /// if junit is used, `structured` attributed must be set to true
+    /// if junit or sarif is used without the `structured` attribute, the console output is written
+    /// to stderr and the report to stdout, or to `output_file` when set
     #[wasm_bindgen(js_name = outputFormat)]
     pub fn output_format(mut self, output: OutputFormatType) -> Self {
         self.output_format = output;
@@ -265,6 +532,226 @@ impl ValidateBuilder {
         self
     }
 
+    /// path of a file the structured, junit or sarif output is written to instead of stdout
+    /// can only be used together with the `structured` attribute, or when `output_format` is junit or sarif
+    /// default is None
+    #[wasm_bindgen(js_name = outputFile)]
+    pub fn output_file(mut self, path: Option<String>) -> Self {
+        self.output_file = path;
+
+        self
+    }
+
+    /// an additional report written to the file at `path` in the given format, evaluated in the
+    /// same run as the regular output. Can be called repeatedly, single-line-summary is not supported
+    pub fn report(mut self, format: OutputFormatType, path: String) -> Self {
+        self.reports.push(OutputTarget {
+            format,
+            path: Some(path),
+        });
+
+        self
+    }
+
+    /// Controls which outcomes result in a non-zero exit code
+    /// default is violations
+    #[wasm_bindgen(js_name = failOn)]
+    pub fn fail_on(mut self, fail_on: FailOn) -> Self {
+        self.fail_on = fail_on;
+
+        self
+    }
+
+    /// How the console, json and yaml output are organized, by data file, rule, resource type or
+    /// severity
+    /// default is file
+    #[wasm_bindgen(js_name = groupBy)]
+    pub fn group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = group_by;
+
+        self
+    }
+
+    /// how many failures of each rule the console output lists across every data file, the rest
+    /// are counted
+    /// default is None, which is unlimited
+    #[wasm_bindgen(js_name = maxFindingsPerRule)]
+    pub fn max_findings_per_rule(mut self, arg: Option<usize>) -> Self {
+        self.max_findings_per_rule = arg;
+
+        self
+    }
+
+    /// how many of the violations repeated most across the data files are summarized after the
+    /// console output, with the number of failures and the data files they are in
+    /// default is None, no summary
+    #[wasm_bindgen(js_name = topOffenders)]
+    pub fn top_offenders(mut self, arg: Option<usize>) -> Self {
+        self.top_offenders = arg;
+
+        self
+    }
+
+    /// only evaluate rules annotated with at least one of these tags
+    /// default is empty, which evaluates every rule
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+
+        self
+    }
+
+    /// skip rules annotated with any of these tags
+    #[wasm_bindgen(js_name = excludeTags)]
+    pub fn exclude_tags(mut self, exclude_tags: Vec<String>) -> Self {
+        self.exclude_tags = exclude_tags;
+
+        self
+    }
+
+    /// only evaluate the named rules with these names
+    /// default is empty, which evaluates every rule
+    #[wasm_bindgen(js_name = ruleNames)]
+    pub fn rule_names(mut self, rule_names: Vec<String>) -> Self {
+        self.rule_names = rule_names;
+
+        self
+    }
+
+    /// skip the named rules with these names
+    #[wasm_bindgen(js_name = skipRules)]
+    pub fn skip_rules(mut self, skip_rules: Vec<String>) -> Self {
+        self.skip_rules = skip_rules;
+
+        self
+    }
+
+    /// evaluate the rules files that parsed when others failed to parse, instead of none of them
+    /// default is false
+    #[wasm_bindgen(js_name = continueOnRuleError)]
+    pub fn continue_on_rule_error(mut self, arg: bool) -> Self {
+        self.continue_on_rule_error = arg;
+
+        self
+    }
+
+    /// report the comparisons between values of different types as not comparable, instead of
+    /// comparing them
+    /// default is false
+    #[wasm_bindgen(js_name = strictTypes)]
+    pub fn strict_types(mut self, arg: bool) -> Self {
+        self.strict_types = arg;
+
+        self
+    }
+
+    /// path of a JSON or YAML file of variables exposed to every rule as `%VARS`, kept apart from
+    /// the data instead of being merged into it like the input parameters
+    /// default is None
+    pub fn vars(mut self, path: Option<String>) -> Self {
+        self.vars = path;
+
+        self
+    }
+
+    /// path of a JSON file of the violations accepted as they are, the failures made up only of
+    /// these are reported as baselined and do not affect the exit code. Requires `structured`
+    /// default is None
+    pub fn baseline(mut self, path: Option<String>) -> Self {
+        self.baseline = path;
+
+        self
+    }
+
+    /// regenerate the baseline file from the violations of this run
+    /// default is false
+    #[wasm_bindgen(js_name = updateBaseline)]
+    pub fn update_baseline(mut self, arg: bool) -> Self {
+        self.update_baseline = arg;
+
+        self
+    }
+
+    /// the suffix of the parameters file paired with each data file, exposed to the rules as `%PARAMETERS_FILE`,
+    /// an empty suffix turns the pairing off
+    /// default is .params.json
+    #[wasm_bindgen(js_name = parametersFileSuffix)]
+    pub fn parameters_file_suffix(mut self, arg: String) -> Self {
+        self.parameters_file_suffix = arg;
+
+        self
+    }
+
+    /// resolve `Ref`s to the parameters of a template to their values, taken from the parameters file,
+    /// the `Parameters` of the input parameters or the `Default` of each parameter, in that order.
+    /// The values are exposed as `%PARAMETER_VALUES`
+    /// default is false
+    #[wasm_bindgen(js_name = resolveParameters)]
+    pub fn resolve_parameters(mut self, arg: bool) -> Self {
+        self.resolve_parameters = arg;
+
+        self
+    }
+
+    /// evaluate the `Conditions` of each template against its parameter values, taken the same way
+    /// as with `resolve_parameters`, and leave out the resources whose `Condition` is false
+    /// default is false
+    #[wasm_bindgen(js_name = evaluateConditions)]
+    pub fn evaluate_conditions(mut self, arg: bool) -> Self {
+        self.evaluate_conditions = arg;
+
+        self
+    }
+
+    /// replace the `$ref`s of each data file pointing within the same document, e.g. the schemas
+    /// of an OpenAPI specification, with the values they point to
+    /// default is false
+    #[wasm_bindgen(js_name = resolveJsonRefs)]
+    pub fn resolve_json_refs(mut self, arg: bool) -> Self {
+        self.resolve_json_refs = arg;
+
+        self
+    }
+
+    /// the type of the data files, which selects how they are adapted before the rules are
+    /// evaluated against them: cfn, k8s, tf, generic or the name of a registered input adapter
+    /// default is None, which detects the type of each data file
+    #[wasm_bindgen(js_name = templateType)]
+    pub fn template_type(mut self, template_type: Option<String>) -> Self {
+        self.template_type = template_type;
+
+        self
+    }
+
+    /// only evaluate the resources matching any of these selectors, either `Attribute==pattern`
+    /// or a pattern for the logical id, e.g. `Type==AWS::EC2::*`
+    /// default is empty, which evaluates every resource
+    #[wasm_bindgen(js_name = resourceSelectors)]
+    pub fn resource_selectors(mut self, resource_selectors: Vec<String>) -> Self {
+        self.resource_selectors = resource_selectors;
+
+        self
+    }
+
+    /// headers sent along with the requests for rules and data files given as https:// URLs, as
+    /// `Name: value`, e.g. `Authorization: Bearer <token>`
+    /// default is empty
+    #[wasm_bindgen(js_name = headers)]
+    pub fn headers(mut self, headers: Vec<String>) -> Self {
+        self.headers = headers;
+
+        self
+    }
+
+    /// path of the Ed25519 public key in PEM the rules packs must be signed for, with cfn-guard
+    /// sign. Only rules packs are accepted as rules when set, conflicts with payload
+    /// default is None
+    #[wasm_bindgen(js_name = verifySignature)]
+    pub fn verify_signature(mut self, path: Option<String>) -> Self {
+        self.verify_signature = path;
+
+        self
+    }
+
     /// Tells the command that rules, and data will be passed via a reader, as a json payload.
     /// Conflicts with both rules, and data
     /// default is false
@@ -288,6 +775,54 @@ impl ValidateBuilder {
         self
     }
 
+    /// how deep below the directories passed as rules or data to look for files, 1 only looks at
+    /// the files directly in them
+    /// default is None, which is unlimited
+    #[wasm_bindgen(js_name = maxDepth)]
+    pub fn max_depth(mut self, arg: Option<usize>) -> Self {
+        self.max_depth = arg;
+
+        self
+    }
+
+    /// descend into symlinked directories when looking for rules and data files. Links back to a
+    /// parent directory are skipped, and a file reachable through several links is evaluated once
+    /// default is false
+    #[wasm_bindgen(js_name = followSymlinks)]
+    pub fn follow_symlinks(mut self, arg: bool) -> Self {
+        self.follow_symlinks = arg;
+
+        self
+    }
+
+    /// the size of the largest data file read, in bytes, a larger one fails the run
+    /// default is None, which is unlimited
+    #[wasm_bindgen(js_name = maxFileSize)]
+    pub fn max_file_size(mut self, arg: Option<usize>) -> Self {
+        self.max_file_size = arg;
+
+        self
+    }
+
+    /// how deeply the mappings and lists of a data file may nest, a deeper one fails the run
+    /// default is None, which is unlimited
+    #[wasm_bindgen(js_name = maxNestingDepth)]
+    pub fn max_nesting_depth(mut self, arg: Option<usize>) -> Self {
+        self.max_nesting_depth = arg;
+
+        self
+    }
+
+    /// the most values a single query of the rules may resolve to, a query resolving to more
+    /// fails the run
+    /// default is None, which is unlimited
+    #[wasm_bindgen(js_name = maxQueryResults)]
+    pub fn max_query_results(mut self, arg: Option<usize>) -> Self {
+        self.max_query_results = arg;
+
+        self
+    }
+
     /// Output verbose logging, conflicts with `structured` field
     /// default is false
     pub fn verbose(mut self, arg: bool) -> Self {
@@ -315,6 +850,16 @@ impl ValidateBuilder {
         self
     }
 
+    /// The layout of the structured json and yaml output, v1 keeps the failures of each data file
+    /// nested as they were evaluated, v2 lists them as violations with their line and column
+    /// default is v1
+    #[wasm_bindgen(js_name = outputVersion)]
+    pub fn output_version(mut self, output_version: OutputVersion) -> Self {
+        self.output_version = output_version;
+
+        self
+    }
+
     #[cfg(target_arch = "wasm32")]
     #[wasm_bindgen(constructor)]
     pub fn new() -> ValidateBuilder {
@@ -345,6 +890,26 @@ impl ValidateBuilder {
         ))
     }
 }
+
+impl ValidateBuilder {
+    /// the custom functions the rules may call besides the built-in ones
+    /// default is no custom functions, calling a function that is not built in is an error
+    pub fn functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = Some(Arc::new(functions));
+
+        self
+    }
+
+    /// the wording of the failure messages of the reports, which the catalog may reword or
+    /// translate
+    /// default is the English wording
+    pub fn message_catalog(mut self, catalog: MessageCatalog) -> Self {
+        self.messages = catalog;
+
+        self
+    }
+}
+
 /// .
 /// A builder to help construct the `Test` command
 #[derive(Default, Debug)]
@@ -356,6 +921,12 @@ pub struct TestBuilder {
     last_modified: bool,
     verbose: bool,
     output_format: OutputFormatType,
+    output_file: Option<String>,
+    tags: Vec<String>,
+    exclude_tags: Vec<String>,
+    verify_signature: Option<String>,
+    init: bool,
+    mutate: bool,
 }
 
 impl CommandBuilder<Test> for TestBuilder {
@@ -365,6 +936,8 @@ impl CommandBuilder<Test> for TestBuilder {
     /// This function will return an error if
     /// - conflicting attributes have been set
     /// - rules, test-data, and directory is set to None
+    /// - init is set without a rules file, or along with test-data, directory or verbose
+    /// - mutate is set without a rules file and test-data, or along with init, directory or verbose
     fn try_build(self) -> crate::rules::Result<Test> {
         if self.last_modified && self.alphabetical {
             return Err(Error::IllegalArguments(String::from("unable to construct a test command: cannot have both last modified, and alphabetical arguments set to true")));
@@ -378,6 +951,29 @@ impl CommandBuilder<Test> for TestBuilder {
             return Err(Error::IllegalArguments(String::from("Cannot provide an output_type of JSON, YAML, or JUnit while the verbose flag is set")));
         }
 
+        if self.init
+            && (self.rules.is_none()
+                || self.test_data.is_some()
+                || self.directory.is_some()
+                || self.verbose)
+        {
+            return Err(Error::IllegalArguments(String::from("unable to construct a test command: init requires a rules argument, and cannot be combined with test-data, directory, or verbose arguments")));
+        }
+
+        if self.verify_signature.is_some() && (self.rules.is_none() || self.init) {
+            return Err(Error::IllegalArguments(String::from("unable to construct a test command: verify_signature requires a rules argument, and cannot be combined with init")));
+        }
+
+        if self.mutate
+            && (self.rules.is_none()
+                || self.test_data.is_none()
+                || self.init
+                || self.directory.is_some()
+                || self.verbose)
+        {
+            return Err(Error::IllegalArguments(String::from("unable to construct a test command: mutate requires rules and test-data arguments, and cannot be combined with init, directory, or verbose arguments")));
+        }
+
         let TestBuilder {
             rules,
             test_data,
@@ -386,6 +982,12 @@ impl CommandBuilder<Test> for TestBuilder {
             last_modified,
             verbose,
             output_format,
+            output_file,
+            tags,
+            exclude_tags,
+            verify_signature,
+            init,
+            mutate,
         } = self;
 
         Ok(Test {
@@ -396,6 +998,15 @@ impl CommandBuilder<Test> for TestBuilder {
             last_modified,
             verbose,
             output_format,
+            output_file,
+            tags,
+            exclude_tags,
+            verify_signature,
+            init,
+            mutate,
+            // the schema of the output is exported as TEST_OUTPUT_SCHEMA, only the command line
+            // prints it
+            output_schema: false,
         })
     }
 }
@@ -459,6 +1070,58 @@ impl TestBuilder {
 
         self
     }
+
+    /// path of a file the output is written to instead of stdout, only used when the command is
+    /// run through the cfn-guard binary
+    /// default is None
+    pub fn output_file(mut self, path: Option<String>) -> Self {
+        self.output_file = path;
+
+        self
+    }
+
+    /// only test rules annotated with at least one of these tags
+    /// default is empty, which tests every rule
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+
+        self
+    }
+
+    /// skip rules annotated with any of these tags
+    pub fn exclude_tags(mut self, exclude_tags: Vec<String>) -> Self {
+        self.exclude_tags = exclude_tags;
+
+        self
+    }
+
+    /// path of the Ed25519 public key in PEM the rules pack must be signed for, with cfn-guard
+    /// sign. Requires `rules` to be a rules pack, conflicts with `init`
+    /// default is None
+    pub fn verify_signature(mut self, path: Option<String>) -> Self {
+        self.verify_signature = path;
+
+        self
+    }
+
+    /// Generate a skeleton of test cases for the rules file instead of running tests, requires
+    /// `rules` and conflicts with `test_data`, `directory` and `verbose`
+    /// default is false
+    pub fn init(mut self, arg: bool) -> Self {
+        self.init = arg;
+
+        self
+    }
+
+    /// Mutate the rules and report the mutants the test data does not catch instead of only
+    /// running tests, requires `rules` and `test_data` and conflicts with `init`, `directory` and
+    /// `verbose`
+    /// default is false
+    pub fn mutate(mut self, arg: bool) -> Self {
+        self.mutate = arg;
+
+        self
+    }
 }
 
 #[derive(Debug, Default)]
@@ -466,14 +1129,32 @@ impl TestBuilder {
 /// A builder to help construct the `Rulegen` command
 pub struct RulegenBuilder {
     output: Option<String>,
-    template: String,
+    template: Option<String>,
+    from_config_rule: Option<String>,
 }
 
 impl CommandBuilder<Rulegen> for RulegenBuilder {
-    /// construct a rulegen command
+    /// .
+    /// attempts to construct a rulegen command
+    ///
+    /// This function will return an error if
+    /// - both template and from_config_rule are set, or neither of them are
     fn try_build(self) -> crate::rules::Result<Rulegen> {
-        let RulegenBuilder { output, template } = self;
-        Ok(Rulegen { output, template })
+        if self.template.is_some() == self.from_config_rule.is_some() {
+            return Err(Error::IllegalArguments(String::from("unable to construct a rulegen command: exactly one of template or from_config_rule must be set")));
+        }
+
+        let RulegenBuilder {
+            output,
+            template,
+            from_config_rule,
+        } = self;
+
+        Ok(Rulegen {
+            output,
+            template,
+            from_config_rule,
+        })
     }
 }
 
@@ -487,8 +1168,137 @@ impl RulegenBuilder {
     }
 
     /// path to the template which the rules will be autogenerated from
+    /// conflicts with from_config_rule
     pub fn template(mut self, template: String) -> Self {
-        self.template = template;
+        self.template = Some(template);
+
+        self
+    }
+
+    /// identifier of an AWS Config managed rule to generate a rule skeleton for,
+    /// e.g. s3-bucket-server-side-encryption-enabled
+    /// conflicts with template
+    pub fn from_config_rule(mut self, from_config_rule: Option<String>) -> Self {
+        self.from_config_rule = from_config_rule;
+
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+/// .
+/// A builder to help construct the `Suggest` command
+pub struct SuggestBuilder {
+    good: String,
+    resource_type: String,
+    output: Option<String>,
+}
+
+impl CommandBuilder<Suggest> for SuggestBuilder {
+    /// .
+    /// attempts to construct a suggest command
+    ///
+    /// This function will return an error if
+    /// - either good or resource_type is empty
+    fn try_build(self) -> crate::rules::Result<Suggest> {
+        if self.good.is_empty() || self.resource_type.is_empty() {
+            return Err(Error::IllegalArguments(String::from(
+                "unable to construct a suggest command: both good and resource_type must be set",
+            )));
+        }
+
+        let SuggestBuilder {
+            good,
+            resource_type,
+            output,
+        } = self;
+
+        Ok(Suggest {
+            good,
+            resource_type,
+            output,
+        })
+    }
+}
+
+impl SuggestBuilder {
+    /// path to a compliant example template the clauses are suggested from
+    pub fn good(mut self, good: String) -> Self {
+        self.good = good;
+
+        self
+    }
+
+    /// the resource type to suggest clauses for, e.g. AWS::RDS::DBInstance
+    pub fn resource_type(mut self, resource_type: String) -> Self {
+        self.resource_type = resource_type;
+
+        self
+    }
+
+    /// path for the output file where the suggested rule will be written to
+    /// if no path is specified output will be printed to the stdout
+    pub fn output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+
+        self
+    }
+}
+
+#[derive(Default, Debug)]
+/// .
+/// A builder to help construct the `rules diff` command
+pub struct RulesDiffBuilder {
+    old: String,
+    new: String,
+    print_json: bool,
+}
+
+impl CommandBuilder<Diff> for RulesDiffBuilder {
+    /// .
+    /// attempts to construct a rules diff command
+    ///
+    /// This function will return an error if
+    /// - either old or new is empty
+    fn try_build(self) -> crate::rules::Result<Diff> {
+        if self.old.is_empty() || self.new.is_empty() {
+            return Err(Error::IllegalArguments(String::from(
+                "unable to construct a rules diff command: both old and new rules must be set",
+            )));
+        }
+
+        let RulesDiffBuilder {
+            old,
+            new,
+            print_json,
+        } = self;
+
+        Ok(Diff {
+            old,
+            new,
+            print_json,
+        })
+    }
+}
+
+impl RulesDiffBuilder {
+    /// path to a rules file, or a directory of rules files, of the previous version of the rule pack
+    pub fn old_rules(mut self, old: String) -> Self {
+        self.old = old;
+
+        self
+    }
+
+    /// path to a rules file, or a directory of rules files, of the new version of the rule pack
+    pub fn new_rules(mut self, new: String) -> Self {
+        self.new = new;
+
+        self
+    }
+
+    /// print the differences in a json format
+    pub fn print_json(mut self, arg: bool) -> Self {
+        self.print_json = arg;
 
         self
     }
@@ -497,8 +1307,17 @@ impl RulegenBuilder {
 #[cfg(test)]
 mod cfn_guard_lib_tests {
     use crate::{
-        commands::validate::ShowSummaryType, CommandBuilder, TestBuilder, ValidateBuilder,
+        commands::{
+            summary::{ERROR_SEVERITY, WARNING_SEVERITY},
+            validate::ShowSummaryType,
+        },
+        utils::{
+            reader::{ReadBuffer, Reader},
+            writer::{WriteBuffer::Vec as WBVec, Writer},
+        },
+        CommandBuilder, RulesDiffBuilder, SuggestBuilder, TestBuilder, ValidateBuilder,
     };
+    use std::io::Cursor;
 
     #[test]
     fn validate_with_errors() {
@@ -542,22 +1361,41 @@ mod cfn_guard_lib_tests {
             .try_build();
         assert!(cmd.is_err());
 
-        // fails cause junit or sarif, but not structured
-        vec![
-            crate::commands::validate::OutputFormatType::Junit,
-            crate::commands::validate::OutputFormatType::Sarif,
-        ]
-        .into_iter()
-        .for_each(|output_format| {
-            let cmd = ValidateBuilder::default()
-                .data(vec![String::from("resources/validate/data-dir")])
-                .rules(vec![String::from("resources/validate/rules-dir")])
-                .output_format(output_format)
-                .show_summary(vec![ShowSummaryType::None])
-                .try_build();
+        // fails cause output_file, but neither structured nor junit or sarif
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from("resources/validate/data-dir")])
+            .rules(vec![String::from("resources/validate/rules-dir")])
+            .output_format(crate::commands::validate::OutputFormatType::JSON)
+            .output_file(Some(String::from("report.json")))
+            .try_build();
+        assert!(cmd.is_err());
 
-            assert!(cmd.is_err());
-        });
+        // fails cause a max_depth of 0 does not look inside any directory
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from("resources/validate/data-dir")])
+            .rules(vec![String::from("resources/validate/rules-dir")])
+            .max_depth(Some(0))
+            .try_build();
+        assert!(cmd.is_err());
+
+        // fails cause a limit of 0 would reject every data file
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from("resources/validate/data-dir")])
+            .rules(vec![String::from("resources/validate/rules-dir")])
+            .max_nesting_depth(Some(0))
+            .try_build();
+        assert!(cmd.is_err());
+
+        // fails cause single-line-summary can only be written to stdout
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from("resources/validate/data-dir")])
+            .rules(vec![String::from("resources/validate/rules-dir")])
+            .report(
+                crate::commands::validate::OutputFormatType::SingleLineSummary,
+                String::from("report.txt"),
+            )
+            .try_build();
+        assert!(cmd.is_err());
 
         // fails cause no payload, or rules
         let cmd = ValidateBuilder::default()
@@ -601,6 +1439,24 @@ mod cfn_guard_lib_tests {
             .try_build();
 
         assert!(cmd.is_err());
+
+        // fails cause the resource selector has no pattern
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from("resources/validate/data-dir")])
+            .rules(vec![String::from("resources/validate/rules-dir")])
+            .resource_selectors(vec![String::from("Type==")])
+            .try_build();
+
+        assert!(cmd.is_err());
+
+        // fails cause the header has no name
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from("resources/validate/data-dir")])
+            .rules(vec![String::from("resources/validate/rules-dir")])
+            .headers(vec![String::from("Bearer token")])
+            .try_build();
+
+        assert!(cmd.is_err());
     }
 
     #[test]
@@ -658,6 +1514,238 @@ mod cfn_guard_lib_tests {
             .try_build();
 
         assert!(cmd.is_ok());
+
+        // junit and sarif no longer require the structured flag
+        vec![
+            crate::commands::validate::OutputFormatType::Junit,
+            crate::commands::validate::OutputFormatType::Sarif,
+        ]
+        .into_iter()
+        .for_each(|output_format| {
+            let cmd = ValidateBuilder::default()
+                .data(data.clone())
+                .rules(rules.clone())
+                .output_format(output_format)
+                .output_file(Some(String::from("report.xml")))
+                .try_build();
+
+            assert!(cmd.is_ok());
+        });
+
+        let cmd = ValidateBuilder::default()
+            .data(data.clone())
+            .rules(rules.clone())
+            .report(
+                crate::commands::validate::OutputFormatType::Junit,
+                String::from("report.xml"),
+            )
+            .report(
+                crate::commands::validate::OutputFormatType::JSON,
+                String::from("report.json"),
+            )
+            .try_build()
+            .unwrap();
+
+        assert_eq!(
+            cmd.output_format(),
+            crate::commands::validate::OutputFormatType::SingleLineSummary
+        );
+        assert_eq!(cmd.report_files().count(), 2);
+    }
+
+    #[test]
+    fn exit_codes_are_stable() {
+        use crate::ExitCode;
+
+        assert_eq!(ExitCode::Success.code(), 0);
+        assert_eq!(ExitCode::TestError.code(), 1);
+        assert_eq!(ExitCode::Error.code(), 5);
+        assert_eq!(ExitCode::TestFailure.code(), 7);
+        assert_eq!(ExitCode::Failure.code(), 19);
+        assert_eq!(i32::from(ExitCode::InternalError), -1);
+    }
+
+    #[test]
+    fn validate_fail_on() {
+        use crate::commands::Executable;
+
+        let execute = |fail_on| {
+            let mut reader = Reader::new(ReadBuffer::Stdin(std::io::stdin()));
+            let mut writer = Writer::new(WBVec(vec![])).unwrap();
+            ValidateBuilder::default()
+                .data(vec![String::from(
+                    "resources/validate/data-dir/s3-server-side-encryption-template-non-compliant.yaml",
+                )])
+                .rules(vec![String::from(
+                    "resources/validate/rules-dir/s3_bucket_server_side_encryption_enabled.guard",
+                )])
+                .fail_on(fail_on)
+                .try_build()
+                .unwrap()
+                .execute(&mut writer, &mut reader)
+                .unwrap()
+        };
+
+        assert_eq!(
+            execute(crate::commands::validate::FailOn::Violations),
+            crate::commands::FAILURE_STATUS_CODE
+        );
+        assert_eq!(
+            execute(crate::commands::validate::FailOn::None),
+            crate::commands::SUCCESS_STATUS_CODE
+        );
+    }
+
+    #[test]
+    fn validate_execute_with_summary() {
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from(
+                "resources/validate/data-dir/s3-server-side-encryption-template-non-compliant.yaml",
+            )])
+            .rules(vec![String::from(
+                "resources/validate/rules-dir/s3_bucket_server_side_encryption_enabled.guard",
+            )])
+            .show_summary(vec![ShowSummaryType::None])
+            .try_build()
+            .unwrap();
+
+        let mut reader = Reader::new(ReadBuffer::Cursor(Cursor::new(vec![])));
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
+
+        let (exit_code, summary) = cmd.execute_with_summary(&mut writer, &mut reader).unwrap();
+
+        assert_eq!(exit_code, crate::commands::FAILURE_STATUS_CODE);
+        assert_eq!(summary.files_evaluated, 1);
+        assert_eq!(summary.rules_failed, 1);
+        assert_eq!(summary.rules_passed, 0);
+        assert_eq!(summary.rules_skipped, 0);
+        assert_eq!(summary.findings_by_severity.len(), 1);
+        assert!(summary.findings_by_severity[ERROR_SEVERITY] > 0);
+        assert_eq!(summary.file_durations.len(), 1);
+        assert_eq!(summary.rule_durations.len(), 1);
+        assert!(summary
+            .rule_durations
+            .values()
+            .all(|rules| rules.len() == 1));
+    }
+
+    #[test]
+    fn validate_walk_options() {
+        let files_evaluated = |max_depth: Option<usize>, follow_symlinks: bool| {
+            let cmd = ValidateBuilder::default()
+                .data(vec![String::from("resources/validate/walk")])
+                .rules(vec![String::from(
+                    "resources/validate/rules-dir/s3_bucket_public_read_prohibited.guard",
+                )])
+                .max_depth(max_depth)
+                .follow_symlinks(follow_symlinks)
+                .show_summary(vec![ShowSummaryType::None])
+                .try_build()
+                .unwrap();
+
+            let mut reader = Reader::new(ReadBuffer::Cursor(Cursor::new(vec![])));
+            let mut writer = Writer::new(WBVec(vec![])).unwrap();
+            let (_, summary) = cmd.execute_with_summary(&mut writer, &mut reader).unwrap();
+            summary.files_evaluated
+        };
+
+        assert_eq!(files_evaluated(None, false), 2);
+        assert_eq!(files_evaluated(Some(1), false), 1);
+        assert_eq!(files_evaluated(None, true), 3);
+    }
+
+    #[test]
+    fn validate_input_limits() {
+        use crate::commands::Executable;
+
+        let execute = |cmd: ValidateBuilder| {
+            let mut reader = Reader::new(ReadBuffer::Cursor(Cursor::new(vec![])));
+            let mut writer = Writer::new(WBVec(vec![])).unwrap();
+            cmd.data(vec![String::from(
+                "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            )])
+            .rules(vec![String::from(
+                "resources/validate/rules-dir/s3_bucket_public_read_prohibited.guard",
+            )])
+            .show_summary(vec![ShowSummaryType::None])
+            .try_build()
+            .unwrap()
+            .execute(&mut writer, &mut reader)
+        };
+
+        let limited = ValidateBuilder::default()
+            .max_file_size(Some(4096))
+            .max_nesting_depth(Some(7))
+            .max_query_results(Some(1));
+        assert_eq!(
+            crate::commands::FAILURE_STATUS_CODE,
+            execute(limited).unwrap()
+        );
+
+        for limited in [
+            ValidateBuilder::default().max_file_size(Some(64)),
+            ValidateBuilder::default().max_nesting_depth(Some(3)),
+        ] {
+            match execute(limited) {
+                Err(crate::Error::LimitExceeded(message)) => assert!(
+                    message.starts_with("data file ")
+                        && message
+                            .contains("s3-public-read-prohibited-template-non-compliant.yaml"),
+                    "{message}"
+                ),
+                result => panic!("expected the limit to be exceeded, got {result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn validate_summary_counts_warnings() {
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from(
+                "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            )])
+            .rules(vec![String::from("resources/validate/warning-rules.guard")])
+            .show_summary(vec![ShowSummaryType::None])
+            .try_build()
+            .unwrap();
+
+        let mut reader = Reader::new(ReadBuffer::Cursor(Cursor::new(vec![])));
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
+
+        let (exit_code, summary) = cmd.execute_with_summary(&mut writer, &mut reader).unwrap();
+
+        assert_eq!(exit_code, crate::commands::SUCCESS_STATUS_CODE);
+        assert_eq!(summary.rules_passed, 1);
+        assert_eq!(summary.rules_failed, 0);
+        assert_eq!(summary.rules_warned, 1);
+        assert!(!summary.findings_by_severity.contains_key(ERROR_SEVERITY));
+        assert!(summary.findings_by_severity[WARNING_SEVERITY] > 0);
+    }
+
+    #[test]
+    fn validate_continues_on_rule_error() {
+        let cmd = ValidateBuilder::default()
+            .data(vec![String::from(
+                "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            )])
+            .rules(vec![
+                String::from("resources/validate/unparsable-rule.guard"),
+                String::from("resources/validate/warning-rules.guard"),
+            ])
+            .continue_on_rule_error(true)
+            .show_summary(vec![ShowSummaryType::None])
+            .try_build()
+            .unwrap();
+
+        let mut reader = Reader::new(ReadBuffer::Cursor(Cursor::new(vec![])));
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
+
+        let (exit_code, summary) = cmd.execute_with_summary(&mut writer, &mut reader).unwrap();
+
+        assert_eq!(exit_code, crate::commands::ERROR_STATUS_CODE);
+        assert_eq!(summary.rule_files_errored, 1);
+        assert_eq!(summary.rules_passed, 1);
+        assert_eq!(summary.rules_warned, 1);
     }
 
     #[test]
@@ -681,7 +1769,18 @@ mod cfn_guard_lib_tests {
             .alphabetical(true)
             .try_build();
 
-        assert!(cmd.is_ok())
+        assert!(cmd.is_ok());
+
+        let cmd = TestBuilder::default()
+            .directory(Option::from(data.clone()))
+            .output_format(crate::commands::validate::OutputFormatType::Junit)
+            .output_file(Option::from(String::from("report.xml")))
+            .try_build();
+
+        assert_eq!(
+            cmd.unwrap().output_file,
+            Option::from(String::from("report.xml"))
+        )
     }
 
     #[test]
@@ -705,5 +1804,65 @@ mod cfn_guard_lib_tests {
             .try_build();
 
         assert!(cmd.is_err());
+
+        // fails cause init without rules
+        let cmd = TestBuilder::default().init(true).try_build();
+
+        assert!(cmd.is_err());
+
+        // fails cause init and test_data
+        let cmd = TestBuilder::default()
+            .rules(Option::from(rules.clone()))
+            .test_data(Option::from(data.clone()))
+            .init(true)
+            .try_build();
+
+        assert!(cmd.is_err());
+
+        // fails cause mutate without test_data
+        let cmd = TestBuilder::default()
+            .rules(Option::from(rules.clone()))
+            .mutate(true)
+            .try_build();
+
+        assert!(cmd.is_err());
+    }
+
+    #[test]
+    fn build_rules_diff_command() {
+        let cmd = RulesDiffBuilder::default()
+            .old_rules(String::from("resources/rules-diff/old"))
+            .new_rules(String::from("resources/rules-diff/new"))
+            .print_json(true)
+            .try_build();
+
+        assert!(cmd.is_ok());
+
+        // fails cause new is missing
+        let cmd = RulesDiffBuilder::default()
+            .old_rules(String::from("resources/rules-diff/old"))
+            .try_build();
+
+        assert!(cmd.is_err());
+    }
+    #[test]
+    fn build_suggest_command() {
+        let cmd = SuggestBuilder::default()
+            .good(String::from(
+                "resources/suggest/data-dir/rds-template-compliant.yaml",
+            ))
+            .resource_type(String::from("AWS::RDS::DBInstance"))
+            .try_build();
+
+        assert!(cmd.is_ok());
+
+        // fails cause resource_type is missing
+        let cmd = SuggestBuilder::default()
+            .good(String::from(
+                "resources/suggest/data-dir/rds-template-compliant.yaml",
+            ))
+            .try_build();
+
+        assert!(cmd.is_err());
     }
 }
@@ -3,7 +3,7 @@ mod commands;
 mod rules;
 mod utils;
 
-use crate::commands::{CfnGuard, Commands};
+use crate::commands::{CfnGuard, Commands, ExitCode};
 use crate::utils::reader::{ReadBuffer, Reader};
 use crate::utils::writer::{WriteBuffer::File as WBFile, WriteBuffer::Stdout, Writer};
 use clap::Parser;
@@ -26,6 +26,18 @@ fn main() -> Result<(), Error> {
             }
             None => Writer::new(Stdout(std::io::stdout())).expect("Failed to create writer."),
         },
+        Commands::Suggest(cmd) => match &cmd.output {
+            Some(path) => {
+                Writer::new(WBFile(File::create(path)?)).expect("Failed to create writer.")
+            }
+            None => Writer::new(Stdout(std::io::stdout())).expect("Failed to create writer."),
+        },
+        Commands::Test(cmd) => match &cmd.output_file {
+            Some(path) => {
+                Writer::new(WBFile(File::create(path)?)).expect("Failed to create writer.")
+            }
+            None => Writer::new(Stdout(std::io::stdout())).expect("Failed to create writer."),
+        },
         _ => Writer::new(Stdout(std::io::stdout())).expect("Failed to create writer."),
     };
 
@@ -38,7 +50,7 @@ fn main() -> Result<(), Error> {
                 .write_err(format!("Error occurred {e}"))
                 .expect("failed to write to stderr");
 
-            exit(-1)
+            exit(ExitCode::InternalError.code())
         }
     }
 }
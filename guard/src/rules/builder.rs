@@ -0,0 +1,494 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+use crate::rules::errors::Error;
+use crate::rules::exprs::{
+    AccessClause, AccessQuery, Block, Conjunctions, FileLocation, GuardAccessClause, GuardClause,
+    LetValue, OnMissing, QueryPart, Rule, RuleAnnotations, RuleClause, RulesFile, Severity,
+    TypeBlock, WhenGuardClause,
+};
+use crate::rules::parser::resources_with_type_query;
+use crate::rules::path_value::{Path, PathAwareValue};
+use crate::rules::regex_cache;
+use crate::rules::values::CmpOperator;
+use crate::rules::Result;
+
+/// what a clause compares its property with
+#[derive(Debug, Clone, PartialEq)]
+enum CompareWith {
+    Value(serde_json::Value),
+    Regex(String),
+    Property(String),
+}
+
+/// Builds a clause, e.g. `Properties.BucketName exists` or `Properties.Size in [10, 20]`, for
+/// [`RuleBuilder`]. The property is a dotted path, e.g. `Properties.Tags[*].Key`, where `*`
+/// selects every value of a struct, `[*]` every element of a list, `[0]` the first one, and a
+/// name starting with `%` a variable. Keys with a dot in them cannot be written this way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClauseBuilder {
+    property: String,
+    match_all: bool,
+    comparator: Option<CmpOperator>,
+    not: bool,
+    compare_with: Option<CompareWith>,
+    message: Option<String>,
+}
+
+impl ClauseBuilder {
+    /// a clause on the property at the dotted path
+    pub fn property(path: &str) -> Self {
+        ClauseBuilder {
+            property: path.to_string(),
+            match_all: true,
+            comparator: None,
+            not: false,
+            compare_with: None,
+            message: None,
+        }
+    }
+
+    /// the clause passes when any of the values the property selects passes, rather than all,
+    /// `some` in rules
+    pub fn some(mut self) -> Self {
+        self.match_all = false;
+
+        self
+    }
+
+    /// negates the operator, e.g. `!=` or `not exists`
+    pub fn not(mut self) -> Self {
+        self.not = true;
+
+        self
+    }
+
+    /// checks the property with an operator that takes no value, e.g. `exists`, `empty` or
+    /// `is_string`
+    pub fn unary(mut self, operator: CmpOperator) -> Self {
+        self.comparator = Some(operator);
+        self.compare_with = None;
+
+        self
+    }
+
+    /// checks that the property exists
+    pub fn exists(self) -> Self {
+        self.unary(CmpOperator::Exists)
+    }
+
+    /// compares the property with a value, e.g. `CmpOperator::In` with `json!(["aws:kms"])`
+    pub fn compare(mut self, operator: CmpOperator, value: serde_json::Value) -> Self {
+        self.comparator = Some(operator);
+        self.compare_with = Some(CompareWith::Value(value));
+
+        self
+    }
+
+    /// compares the property with another property, or a variable, at the dotted path
+    pub fn compare_property(mut self, operator: CmpOperator, path: &str) -> Self {
+        self.comparator = Some(operator);
+        self.compare_with = Some(CompareWith::Property(path.to_string()));
+
+        self
+    }
+
+    /// checks that the property matches the regular expression, `== /pattern/` in rules
+    pub fn matches(mut self, pattern: &str) -> Self {
+        self.comparator = Some(CmpOperator::Eq);
+        self.compare_with = Some(CompareWith::Regex(pattern.to_string()));
+
+        self
+    }
+
+    /// the custom message reported when the clause fails
+    pub fn message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+
+        self
+    }
+
+    /// This function will return an error if
+    /// - the property is not a valid dotted path
+    /// - no operator was given, or a value is missing for an operator that takes one
+    /// - the regular expression is invalid
+    /// - the message has `>>` in it, which ends messages in rules
+    pub(crate) fn try_build(&self) -> Result<GuardAccessClause<'static>> {
+        let comparator = self.comparator.ok_or_else(|| {
+            Error::IllegalArguments(format!(
+                "the clause on {} has no operator, e.g. exists or compare",
+                self.property
+            ))
+        })?;
+        if !comparator.is_unary() && self.compare_with.is_none() {
+            return Err(Error::IllegalArguments(format!(
+                "the clause on {} has no value to compare with",
+                self.property
+            )));
+        }
+        if let Some(message) = self.message.as_deref().filter(|m| m.contains(">>")) {
+            return Err(Error::IllegalArguments(format!(
+                "the message of the clause on {} cannot contain >>, {message}",
+                self.property
+            )));
+        }
+
+        let compare_with = match &self.compare_with {
+            None => None,
+            Some(CompareWith::Value(value)) => {
+                Some(LetValue::Value(PathAwareValue::try_from(value.clone())?))
+            }
+            Some(CompareWith::Regex(pattern)) => {
+                regex_cache::compiled(pattern)?;
+                Some(LetValue::Value(PathAwareValue::Regex((
+                    Path::root(),
+                    pattern.clone(),
+                ))))
+            }
+            Some(CompareWith::Property(path)) => Some(LetValue::AccessClause(AccessQuery {
+                query: query_parts(path)?,
+                match_all: true,
+            })),
+        };
+
+        Ok(GuardAccessClause {
+            access_clause: AccessClause {
+                query: AccessQuery {
+                    query: query_parts(&self.property)?,
+                    match_all: self.match_all,
+                },
+                comparator: (comparator, self.not),
+                compare_with,
+                custom_message: self.message.clone(),
+                location: location(),
+            },
+            negation: false,
+        })
+    }
+}
+
+/// where the clauses of built rules are, as they were not read from a file
+fn location() -> FileLocation<'static> {
+    FileLocation {
+        line: 0,
+        column: 0,
+        file_name: "",
+    }
+}
+
+/// the query parts of a dotted path, e.g. `%buckets.Properties.Tags[*].Key`
+fn query_parts(path: &str) -> Result<Vec<QueryPart<'static>>> {
+    let invalid = || {
+        Error::IllegalArguments(format!(
+            "{path} is not a dotted path to a property, e.g. Properties.Tags[*].Key"
+        ))
+    };
+
+    let mut parts = vec![];
+    for segment in path.split('.') {
+        let (key, mut indices) = match segment.find('[') {
+            Some(start) => segment.split_at(start),
+            None => (segment, ""),
+        };
+        match key {
+            "" if indices.is_empty() => return Err(invalid()),
+            "" => {}
+            "*" => parts.push(QueryPart::AllValues(None)),
+            key => parts.push(QueryPart::Key(key.to_string())),
+        }
+
+        while !indices.is_empty() {
+            let end = indices.find(']').ok_or_else(invalid)?;
+            parts.push(match &indices[1..end] {
+                "*" => QueryPart::AllIndices(None),
+                index => QueryPart::Index(index.parse().map_err(|_| invalid())?),
+            });
+            indices = &indices[end + 1..];
+            if !indices.is_empty() && !indices.starts_with('[') {
+                return Err(invalid());
+            }
+        }
+    }
+
+    // like the parser, a variable followed by more of the path is iterated over
+    if parts.len() > 1 && parts[0].is_variable() && !matches!(parts[1], QueryPart::AllIndices(_)) {
+        parts.insert(1, QueryPart::AllIndices(None));
+    }
+
+    Ok(parts)
+}
+
+/// whether the name can be written in rules as the name of a rule or part of a type name
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, char::is_alphabetic)
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TypeBlockBuilder {
+    type_name: String,
+    conditions: Vec<Vec<ClauseBuilder>>,
+    clauses: Vec<Vec<ClauseBuilder>>,
+}
+
+/// Builds a named rule from clauses, without writing it as text and parsing it, e.g.
+///
+/// ```
+/// use cfn_guard::ast::{ClauseBuilder, RuleBuilder};
+///
+/// let rule = RuleBuilder::new("s3_encrypted")
+///     .type_block("AWS::S3::Bucket")
+///     .clause(ClauseBuilder::property("Properties.BucketEncryption").exists())
+///     .try_build()
+///     .unwrap();
+/// ```
+///
+/// Clauses and conditions added after [`RuleBuilder::type_block`] belong to the type block, up
+/// to the next one. Those added before belong to the rule itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleBuilder {
+    name: String,
+    annotations: RuleAnnotations,
+    conditions: Vec<Vec<ClauseBuilder>>,
+    clauses: Vec<Vec<ClauseBuilder>>,
+    type_blocks: Vec<TypeBlockBuilder>,
+}
+
+impl RuleBuilder {
+    /// a rule with the name, which rules refer to it by
+    pub fn new(name: &str) -> Self {
+        RuleBuilder {
+            name: name.to_string(),
+            annotations: RuleAnnotations::default(),
+            conditions: vec![],
+            clauses: vec![],
+            type_blocks: vec![],
+        }
+    }
+
+    /// starts a block of clauses on the resources of the type, e.g. `AWS::S3::Bucket`
+    pub fn type_block(mut self, type_name: &str) -> Self {
+        self.type_blocks.push(TypeBlockBuilder {
+            type_name: type_name.to_string(),
+            conditions: vec![],
+            clauses: vec![],
+        });
+
+        self
+    }
+
+    /// adds a clause that must pass
+    pub fn clause(self, clause: ClauseBuilder) -> Self {
+        self.any_of(vec![clause])
+    }
+
+    /// adds clauses of which at least one must pass, joined with `or` in rules
+    pub fn any_of(mut self, clauses: Vec<ClauseBuilder>) -> Self {
+        match self.type_blocks.last_mut() {
+            Some(type_block) => type_block.clauses.push(clauses),
+            None => self.clauses.push(clauses),
+        }
+
+        self
+    }
+
+    /// adds a condition the rule, or the type block, is evaluated under, `when` in rules. The
+    /// rule is skipped when a condition fails
+    pub fn when(mut self, condition: ClauseBuilder) -> Self {
+        match self.type_blocks.last_mut() {
+            Some(type_block) => type_block.conditions.push(vec![condition]),
+            None => self.conditions.push(vec![condition]),
+        }
+
+        self
+    }
+
+    /// tags the rule, `@tags` in rules
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.annotations
+            .tags
+            .extend(tags.iter().map(|tag| tag.to_string()));
+
+        self
+    }
+
+    /// how the failures of the rule are reported, `@severity` in rules
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.annotations.severity = Some(severity);
+
+        self
+    }
+
+    /// how the rule treats clauses whose property is missing, `@on-missing` in rules
+    pub fn on_missing(mut self, on_missing: OnMissing) -> Self {
+        self.annotations.on_missing = Some(on_missing);
+
+        self
+    }
+
+    /// a helper rule only evaluated when other rules refer to it, `private rule` in rules
+    pub fn private(mut self, private: bool) -> Self {
+        self.annotations.private = private;
+
+        self
+    }
+
+    /// builds the rule
+    ///
+    /// This function will return an error if
+    /// - the name of the rule, a type name, or a tag cannot be written in rules
+    /// - the rule, or one of its type blocks, has no clause
+    /// - a clause is invalid, see [`ClauseBuilder`]
+    pub fn try_build(&self) -> Result<Rule<'static>> {
+        if !is_name(&self.name) {
+            return Err(Error::IllegalArguments(format!(
+                "{} is not a valid rule name, it must start with a letter followed by letters, digits or _",
+                self.name
+            )));
+        }
+        if let Some(tag) = self.annotations.tags.iter().find(|tag| {
+            tag.is_empty()
+                || !tag
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || "_-.:/".contains(c))
+        }) {
+            return Err(Error::IllegalArguments(format!(
+                "{tag} is not a valid tag of the rule {}",
+                self.name
+            )));
+        }
+        if self.clauses.is_empty() && self.type_blocks.is_empty() {
+            return Err(Error::IllegalArguments(format!(
+                "the rule {} has no clause",
+                self.name
+            )));
+        }
+
+        let mut conjunctions = self
+            .clauses
+            .iter()
+            .map(|disjunctions| {
+                disjunctions
+                    .iter()
+                    .map(|clause| Ok(RuleClause::Clause(GuardClause::Clause(clause.try_build()?))))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Conjunctions<_>>>()?;
+        for type_block in &self.type_blocks {
+            conjunctions.push(vec![RuleClause::TypeBlock(
+                self.type_block_clause(type_block)?,
+            )]);
+        }
+
+        Ok(Rule {
+            rule_name: self.name.clone(),
+            conditions: match self.conditions.is_empty() {
+                true => None,
+                false => Some(conditions(&self.conditions)?),
+            },
+            block: Block {
+                assignments: vec![],
+                conjunctions,
+            },
+            annotations: self.annotations.clone(),
+        })
+    }
+
+    fn type_block_clause(&self, type_block: &TypeBlockBuilder) -> Result<TypeBlock<'static>> {
+        let parts = type_block.type_name.split("::").collect::<Vec<_>>();
+        if !(2..=3).contains(&parts.len()) || !parts.iter().all(|part| is_name(part)) {
+            return Err(Error::IllegalArguments(format!(
+                "{} is not a valid type name in the rule {}, e.g. AWS::S3::Bucket",
+                type_block.type_name, self.name
+            )));
+        }
+        if type_block.clauses.is_empty() {
+            return Err(Error::IllegalArguments(format!(
+                "the type block {} of the rule {} has no clause",
+                type_block.type_name, self.name
+            )));
+        }
+
+        Ok(TypeBlock {
+            type_name: type_block.type_name.clone(),
+            conditions: match type_block.conditions.is_empty() {
+                true => None,
+                false => Some(conditions(&type_block.conditions)?),
+            },
+            block: Block {
+                assignments: vec![],
+                conjunctions: type_block
+                    .clauses
+                    .iter()
+                    .map(|disjunctions| {
+                        disjunctions
+                            .iter()
+                            .map(|clause| Ok(GuardClause::Clause(clause.try_build()?)))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Conjunctions<_>>>()?,
+            },
+            query: resources_with_type_query(
+                PathAwareValue::String((Path::root(), type_block.type_name.clone())),
+                location(),
+            ),
+        })
+    }
+}
+
+fn conditions(conditions: &[Vec<ClauseBuilder>]) -> Result<Conjunctions<WhenGuardClause<'static>>> {
+    conditions
+        .iter()
+        .map(|disjunctions| {
+            disjunctions
+                .iter()
+                .map(|clause| Ok(WhenGuardClause::Clause(clause.try_build()?)))
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect()
+}
+
+/// Builds a rules file from rules built with [`RuleBuilder`], ready to be evaluated or written
+/// as guard rules with [`RulesFile::to_guard_source`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RulesFileBuilder {
+    rules: Vec<RuleBuilder>,
+}
+
+impl RulesFileBuilder {
+    /// adds a rule to the file
+    pub fn rule(mut self, rule: RuleBuilder) -> Self {
+        self.rules.push(rule);
+
+        self
+    }
+
+    /// builds the rules file
+    ///
+    /// This function will return an error if two rules have the same name, or a rule is
+    /// invalid, see [`RuleBuilder::try_build`]
+    pub fn try_build(&self) -> Result<RulesFile<'static>> {
+        let mut names = HashSet::new();
+        let mut guard_rules = Vec::with_capacity(self.rules.len());
+        for rule in &self.rules {
+            if !names.insert(rule.name.as_str()) {
+                return Err(Error::IllegalArguments(format!(
+                    "the rule {} is added more than once",
+                    rule.name
+                )));
+            }
+            guard_rules.push(rule.try_build()?);
+        }
+
+        Ok(RulesFile {
+            assignments: vec![],
+            guard_rules,
+            parameterized_rules: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "builder_tests.rs"]
+mod builder_tests;
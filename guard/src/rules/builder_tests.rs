@@ -0,0 +1,155 @@
+use super::*;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn s3_encrypted() -> RuleBuilder {
+    RuleBuilder::new("s3_encrypted")
+        .tags(&["s3"])
+        .type_block("AWS::S3::Bucket")
+        .when(ClauseBuilder::property("Properties").exists())
+        .clause(ClauseBuilder::property("Properties.BucketEncryption").exists())
+        .any_of(vec![
+            ClauseBuilder::property("Properties.Size").compare(CmpOperator::In, json!([10, 20])),
+            ClauseBuilder::property("Properties.KmsKeyId")
+                .exists()
+                .message("a key is required"),
+        ])
+        .clause(ClauseBuilder::property("Properties.Tags[*].Key").matches("^App"))
+}
+
+#[test]
+fn test_rules_file_to_guard_source() {
+    let rules = RulesFileBuilder::default()
+        .rule(s3_encrypted())
+        .rule(
+            RuleBuilder::new("s3_versioned").clause(
+                ClauseBuilder::property("Resources.*.Properties.VersioningConfiguration.Status")
+                    .some()
+                    .compare(CmpOperator::Eq, json!("Enabled")),
+            ),
+        )
+        .try_build()
+        .unwrap();
+    let source = rules.to_guard_source();
+
+    for line in [
+        "@tags(s3)",
+        "rule s3_encrypted {",
+        "AWS::S3::Bucket when Properties exists {",
+        "Properties.BucketEncryption exists",
+        "Properties.Size in [10,20] or Properties.KmsKeyId exists <<a key is required>>",
+        "Properties.Tags[*].Key == /^App/",
+        "rule s3_versioned {",
+        "some Resources.*.Properties.VersioningConfiguration.Status == \"Enabled\"",
+    ] {
+        assert!(source.contains(line), "{line} not in {source}");
+    }
+
+    let reparsed = RulesFile::parse(&source, "").unwrap();
+    assert_eq!(source, reparsed.to_guard_source());
+}
+
+#[test]
+fn test_negated_and_property_clauses() {
+    let rule = RuleBuilder::new("no_public_access")
+        .clause(
+            ClauseBuilder::property("Properties.AccessControl")
+                .not()
+                .compare(CmpOperator::Eq, json!("PublicRead")),
+        )
+        .clause(ClauseBuilder::property("Properties.Name").not().exists())
+        .clause(
+            ClauseBuilder::property("Properties.BucketName")
+                .compare_property(CmpOperator::Eq, "%name"),
+        )
+        .try_build()
+        .unwrap();
+    let source = rule.to_guard_source();
+
+    assert!(
+        source.contains("Properties.AccessControl != \"PublicRead\""),
+        "{source}"
+    );
+    assert!(source.contains("Properties.Name not exists"), "{source}");
+    assert!(
+        source.contains("Properties.BucketName == %name"),
+        "{source}"
+    );
+}
+
+#[test]
+fn test_query_parts() {
+    assert_eq!(
+        vec![
+            QueryPart::Key(String::from("%buckets")),
+            QueryPart::AllIndices(None),
+            QueryPart::Key(String::from("Properties")),
+            QueryPart::Key(String::from("Tags")),
+            QueryPart::AllIndices(None),
+            QueryPart::Key(String::from("Key")),
+        ],
+        query_parts("%buckets.Properties.Tags[*].Key").unwrap()
+    );
+    assert_eq!(
+        vec![
+            QueryPart::Key(String::from("Resources")),
+            QueryPart::AllValues(None),
+            QueryPart::Key(String::from("Values")),
+            QueryPart::Index(0),
+            QueryPart::Index(1),
+        ],
+        query_parts("Resources.*.Values[0][1]").unwrap()
+    );
+
+    for invalid in ["", "Properties..Name", "Tags[*", "Tags[a]", "Tags[0]Key"] {
+        assert!(query_parts(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_invalid_rules() {
+    let exists = || ClauseBuilder::property("Properties").exists();
+    let invalid = [
+        RuleBuilder::new("1st_rule").clause(exists()),
+        RuleBuilder::new("rule name").clause(exists()),
+        RuleBuilder::new("empty"),
+        RuleBuilder::new("no_operator").clause(ClauseBuilder::property("Properties")),
+        RuleBuilder::new("no_value").clause(
+            ClauseBuilder::property("Properties")
+                .exists()
+                .unary(CmpOperator::Eq),
+        ),
+        RuleBuilder::new("bad_message").clause(exists().message("ends >> early")),
+        RuleBuilder::new("bad_tag")
+            .tags(&["a tag"])
+            .clause(exists()),
+        RuleBuilder::new("bad_type")
+            .type_block("S3Bucket")
+            .clause(exists()),
+        RuleBuilder::new("empty_type").type_block("AWS::S3::Bucket"),
+    ];
+
+    for rule in invalid {
+        assert!(
+            matches!(rule.try_build(), Err(Error::IllegalArguments(_))),
+            "{rule:?}"
+        );
+    }
+}
+
+#[test]
+fn test_invalid_regex() {
+    let rule = RuleBuilder::new("bad_regex").clause(ClauseBuilder::property("Name").matches("("));
+
+    assert!(rule.try_build().is_err());
+}
+
+#[test]
+fn test_duplicate_rules() {
+    let result = RulesFileBuilder::default()
+        .rule(s3_encrypted())
+        .rule(s3_encrypted())
+        .try_build();
+
+    assert!(matches!(result, Err(Error::IllegalArguments(_))));
+}
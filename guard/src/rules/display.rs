@@ -1,8 +1,13 @@
-use crate::rules::eval_context::EventRecord;
-use crate::rules::exprs::SliceDisplay;
+use crate::rules::eval_context::{EventRecord, FunctionName};
+use crate::rules::exprs::{
+    Block, Conjunctions, FunctionExpr, GuardAccessClause, GuardClause, GuardNamedRuleClause,
+    LetExpr, LetValue, ParameterizedNamedRuleClause, QueryPart, Rule, RuleClause, RulesFile,
+    SliceDisplay, WhenGuardClause,
+};
+use crate::rules::parser::DEFAULT_RULE_NAME;
 use crate::rules::path_value::PathAwareValue;
 use crate::rules::values::{CmpOperator, RangeType, LOWER_INCLUSIVE, UPPER_INCLUSIVE};
-use crate::rules::{BlockCheck, ClauseCheck, QueryResult, RecordType, Status};
+use crate::rules::{BlockCheck, ClauseCheck, GuardClauseCheck, QueryResult, RecordType, Status};
 use std::fmt::{Display, Formatter};
 use std::rc::Rc;
 
@@ -10,6 +15,544 @@ pub(crate) fn display_comparison((cmp, not): (CmpOperator, bool)) -> String {
     format!("{} {}", if not { "not" } else { "" }, cmp)
 }
 
+//
+// Renders clauses back into guard rule syntax, e.g. `Properties.Tags[*].Key == /^App/`,
+// keeping variables as written (`%buckets.BucketName`) and filters spelled out in full.
+//
+pub(crate) fn clause_source(clause: &GuardAccessClause<'_>) -> String {
+    let access = &clause.access_clause;
+    let query = query_source(&access.query.query);
+    let comparator = comparator_source(access.comparator);
+    let text = match &access.compare_with {
+        Some(value) => format!("{query} {comparator} {}", let_value_source(value)),
+        None => format!("{query} {comparator}"),
+    };
+    match clause.negation {
+        true => format!("not {text}"),
+        false => text,
+    }
+}
+
+pub(crate) fn query_source(parts: &[QueryPart<'_>]) -> String {
+    let mut text = String::new();
+    let mut previous_was_variable = false;
+    for part in parts {
+        let segment = match part {
+            // variables always get an implicit [*] when parsed, which is not part of what was written
+            QueryPart::AllIndices(None) if previous_was_variable => String::new(),
+            QueryPart::AllIndices(_) => String::from("[*]"),
+            QueryPart::Index(idx) => format!("[{idx}]"),
+            QueryPart::Slice(start, end) => slice_source(*start, *end),
+            QueryPart::Filter(name, clauses) => format!(
+                "[ {}{} ]",
+                name.as_ref()
+                    .map_or(String::new(), |name| format!("{name} | ")),
+                conjunctions_source(clauses, guard_clause_source)
+            ),
+            QueryPart::MapKeyFilter(name, clause) => format!(
+                "[ {}keys {} {} ]",
+                name.as_ref()
+                    .map_or(String::new(), |name| format!("{name} | ")),
+                comparator_source(clause.comparator),
+                let_value_source(&clause.compare_with)
+            ),
+            QueryPart::Key(key) if !text.is_empty() => format!(".{}", key_source(key)),
+            QueryPart::Key(key) => key_source(key),
+            QueryPart::AllValues(_) if !text.is_empty() => String::from(".*"),
+            QueryPart::AllValues(_) => String::from("*"),
+            QueryPart::This => String::from("this"),
+            QueryPart::Resolve => String::from(".resolve()"),
+            QueryPart::Descendants(key) => format!(".**.{}", key_source(key)),
+        };
+        previous_was_variable = part.is_variable();
+        text.push_str(&segment);
+    }
+    text
+}
+
+/// a slice as it is written in a query, e.g. `[0:2]` or `[-2:]`
+pub(crate) fn slice_source(start: Option<i32>, end: Option<i32>) -> String {
+    let bound = |index: Option<i32>| index.map_or(String::new(), |index| index.to_string());
+    format!("[{}:{}]", bound(start), bound(end))
+}
+
+/// a key as it is written in a query, keys that are not plain names, e.g. `aws:cdk:path`, `my key`
+/// or `0` for a struct key, are quoted. Variables are kept as they are
+pub(crate) fn key_source(key: &str) -> String {
+    let name = key.strip_prefix('%').unwrap_or(key);
+    let mut chars = name.chars();
+    let plain = chars.next().map_or(false, char::is_alphabetic)
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    match plain {
+        true => key.to_string(),
+        false => format!("\"{}\"", key.replace('"', "\\\"")),
+    }
+}
+
+fn comparator_source((cmp, not): (CmpOperator, bool)) -> String {
+    let operator = match cmp {
+        CmpOperator::Eq if not => return String::from("!="),
+        CmpOperator::Eq => "==",
+        CmpOperator::UnorderedEq if not => return String::from("!~"),
+        CmpOperator::UnorderedEq => "=~",
+        CmpOperator::In => "in",
+        CmpOperator::Like => "like",
+        CmpOperator::Gt => ">",
+        CmpOperator::Lt => "<",
+        CmpOperator::Ge => ">=",
+        CmpOperator::Le => "<=",
+        CmpOperator::Exists => "exists",
+        CmpOperator::Empty => "empty",
+        CmpOperator::IsString => "is_string",
+        CmpOperator::IsBool => "is_bool",
+        CmpOperator::IsInt => "is_int",
+        CmpOperator::IsList => "is_list",
+        CmpOperator::IsMap => "is_struct",
+        CmpOperator::IsNull => "is_null",
+        CmpOperator::IsFloat => "is_float",
+    };
+    match not {
+        true => format!("not {operator}"),
+        false => operator.to_string(),
+    }
+}
+
+pub(crate) fn let_value_source(value: &LetValue<'_>) -> String {
+    match value {
+        LetValue::Value(value) => value_source(value),
+        LetValue::AccessClause(access) => query_source(&access.query),
+        LetValue::FunctionCall(function) if function.name == FunctionName::Interpolate => {
+            interpolated_source(function)
+        }
+        LetValue::FunctionCall(function) => format!(
+            "{}({})",
+            function.name,
+            function
+                .parameters
+                .iter()
+                .map(let_value_source)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// an interpolated string as it is written in rules, e.g. `"arn:aws:s3:::${%bucket_prefix}"`
+pub(crate) fn interpolated_source(function: &FunctionExpr<'_>) -> String {
+    let text = function
+        .parameters
+        .iter()
+        .map(|part| match part {
+            LetValue::Value(PathAwareValue::String((_, text))) => text.replace('"', "\\\""),
+            LetValue::AccessClause(access) => format!("${{{}}}", query_source(&access.query)),
+            part => let_value_source(part),
+        })
+        .collect::<String>();
+    format!("\"{text}\"")
+}
+
+/// a literal as it is written in rules, e.g. `[/^prod/,"dev"]`, `r[1,10]` or `null`
+pub(crate) fn value_source(value: &PathAwareValue) -> String {
+    match value {
+        PathAwareValue::Null(_) => String::from("null"),
+        PathAwareValue::String((_, value)) => format!("\"{}\"", value.replace('"', "\\\"")),
+        PathAwareValue::Regex((_, regex)) => format!("/{}/", regex.replace('/', "\\/")),
+        // {:?} keeps the fraction of whole numbers, 10.0 would otherwise be read back as an int
+        PathAwareValue::Float((_, value)) => format!("{value:?}"),
+        PathAwareValue::List((_, list)) => format!(
+            "[{}]",
+            list.iter().map(value_source).collect::<Vec<_>>().join(",")
+        ),
+        PathAwareValue::Map((_, map)) => format!(
+            "{{{}}}",
+            map.values
+                .iter()
+                .map(|(key, value)| format!(
+                    "\"{}\":{}",
+                    key.replace('"', "\\\""),
+                    value_source(value)
+                ))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        PathAwareValue::RangeInt((_, range)) => range_source(range, range.lower, range.upper),
+        PathAwareValue::RangeChar((_, range)) => range_source(range, range.lower, range.upper),
+        PathAwareValue::RangeFloat((_, range)) => range_source(
+            range,
+            format!("{:?}", range.lower),
+            format!("{:?}", range.upper),
+        ),
+        value => ValueOnlyDisplay(Rc::new(value.clone())).to_string(),
+    }
+}
+
+fn range_source<T: PartialOrd>(
+    range: &RangeType<T>,
+    lower: impl Display,
+    upper: impl Display,
+) -> String {
+    format!(
+        "r{}{lower},{upper}{}",
+        if range.inclusive & LOWER_INCLUSIVE != 0 {
+            "["
+        } else {
+            "("
+        },
+        if range.inclusive & UPPER_INCLUSIVE != 0 {
+            "]"
+        } else {
+            ")"
+        },
+    )
+}
+
+pub(crate) fn conjunctions_source<T>(
+    conjunctions: &Conjunctions<T>,
+    render: fn(&T) -> String,
+) -> String {
+    conjunctions
+        .iter()
+        .map(|disjunctions| {
+            disjunctions
+                .iter()
+                .map(render)
+                .collect::<Vec<_>>()
+                .join(" or ")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn block_source(block: &Block<'_, GuardClause<'_>>) -> String {
+    block
+        .assignments
+        .iter()
+        .map(|each| format!("let {} = {}", each.var, let_value_source(&each.value)))
+        .chain(std::iter::once(conjunctions_source(
+            &block.conjunctions,
+            guard_clause_source,
+        )))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn named_rule_source(named: &GuardNamedRuleClause<'_>) -> String {
+    format!(
+        "{}{}",
+        if named.negation { "not " } else { "" },
+        named.dependent_rule
+    )
+}
+
+fn parameterized_rule_source(parameterized: &ParameterizedNamedRuleClause<'_>) -> String {
+    format!(
+        "{}({})",
+        named_rule_source(&parameterized.named_rule),
+        parameterized
+            .parameters
+            .iter()
+            .map(let_value_source)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+pub(crate) fn when_clause_source(clause: &WhenGuardClause<'_>) -> String {
+    match clause {
+        WhenGuardClause::Clause(clause) => clause_source(clause),
+        WhenGuardClause::NamedRule(named) => named_rule_source(named),
+        WhenGuardClause::ParameterizedNamedRule(parameterized) => {
+            parameterized_rule_source(parameterized)
+        }
+    }
+}
+
+pub(crate) fn guard_clause_source(clause: &GuardClause<'_>) -> String {
+    match clause {
+        GuardClause::Clause(clause) => clause_source(clause),
+        GuardClause::NamedRule(named) => named_rule_source(named),
+        GuardClause::ParameterizedNamedRule(parameterized) => {
+            parameterized_rule_source(parameterized)
+        }
+        GuardClause::BlockClause(block_clause) => format!(
+            "{}{} {{ {} }}",
+            if block_clause.negation { "not " } else { "" },
+            query_source(&block_clause.query.query),
+            block_source(&block_clause.block)
+        ),
+        GuardClause::WhenBlock(conditions, block, otherwise) => {
+            when_block_source(conditions, block, otherwise.as_ref())
+        }
+    }
+}
+
+pub(crate) fn rule_clause_source(clause: &RuleClause<'_>) -> String {
+    match clause {
+        RuleClause::Clause(clause) => guard_clause_source(clause),
+        RuleClause::WhenBlock(conditions, block, otherwise) => {
+            when_block_source(conditions, block, otherwise.as_ref())
+        }
+        RuleClause::TypeBlock(type_block) => format!(
+            "{}{} {{ {} }}",
+            type_block.type_name,
+            type_block
+                .conditions
+                .as_ref()
+                .map_or(String::new(), |conditions| format!(
+                    " when {}",
+                    conjunctions_source(conditions, when_clause_source)
+                )),
+            block_source(&type_block.block)
+        ),
+    }
+}
+
+fn when_block_source(
+    conditions: &Conjunctions<WhenGuardClause<'_>>,
+    block: &Block<'_, GuardClause<'_>>,
+    otherwise: Option<&Block<'_, GuardClause<'_>>>,
+) -> String {
+    let source = format!(
+        "when {} {{ {} }}",
+        conjunctions_source(conditions, when_clause_source),
+        block_source(block)
+    );
+    match otherwise {
+        Some(otherwise) => format!("{source} else {{ {} }}", block_source(otherwise)),
+        None => source,
+    }
+}
+
+//
+// Pretty prints rules back into guard rules, one conjunction per line and the clauses of blocks
+// indented by four spaces. Unlike the renderers above, which put a clause on a single line for
+// messages and documentation, nothing of the rules is left out: `some`, custom messages,
+// annotations and `!empty` blocks are all written, so the source parses back into the same rules.
+// Comments and the layout of the original file are not kept.
+//
+const INDENT: &str = "    ";
+
+pub(crate) fn rules_file_source(rules: &RulesFile<'_>) -> String {
+    let mut sections = vec![];
+    if !rules.assignments.is_empty() {
+        sections.push(assignments_text(&rules.assignments, 0));
+    }
+    for rule in &rules.guard_rules {
+        // the clauses outside of any rule are gathered in a rule named default, named after the
+        // file too when it has a name, e.g. s3.guard/default
+        let is_default = rule.rule_name == DEFAULT_RULE_NAME
+            || rule.rule_name.ends_with(&format!("/{DEFAULT_RULE_NAME}"));
+        match is_default {
+            true => sections.push(conjunctions_text(
+                &rule.block.conjunctions,
+                0,
+                rule_clause_text,
+            )),
+            false => sections.push(rule_source(rule, None)),
+        }
+    }
+    for parameterized in &rules.parameterized_rules {
+        sections.push(rule_source(
+            &parameterized.rule,
+            Some(&parameterized.parameter_names),
+        ));
+    }
+    sections.join("\n")
+}
+
+pub(crate) fn rule_source(
+    rule: &Rule<'_>,
+    parameter_names: Option<&indexmap::IndexSet<String>>,
+) -> String {
+    let mut text = String::new();
+    let annotations = &rule.annotations;
+    if let Some(on_missing) = annotations.on_missing {
+        text.push_str(&format!("@on-missing({on_missing})\n"));
+    }
+    if let Some(severity) = annotations.severity {
+        text.push_str(&format!("@severity({severity})\n"));
+    }
+    if !annotations.tags.is_empty() {
+        text.push_str(&format!("@tags({})\n", annotations.tags.join(", ")));
+    }
+
+    if annotations.private {
+        text.push_str("private ");
+    }
+    text.push_str("rule ");
+    text.push_str(&rule.rule_name);
+    if let Some(names) = parameter_names {
+        text.push_str(&format!(
+            "({})",
+            names.iter().cloned().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    if let Some(conditions) = &rule.conditions {
+        text.push_str(&format!(" when {}", when_text(conditions)));
+    }
+    text.push_str(&format!(
+        " {{\n{}}}\n",
+        block_text(&rule.block, 1, rule_clause_text)
+    ));
+    text
+}
+
+/// the clause as guard rules, nested lines indented one level deeper than `indent`, the first
+/// line is left for the caller to indent
+pub(crate) fn guard_clause_text(clause: &GuardClause<'_>, indent: usize) -> String {
+    match clause {
+        GuardClause::Clause(clause) => access_clause_text(clause),
+        GuardClause::NamedRule(named) => {
+            with_message(named_rule_source(named), named.custom_message.as_deref())
+        }
+        GuardClause::ParameterizedNamedRule(parameterized) => with_message(
+            parameterized_rule_source(parameterized),
+            parameterized.named_rule.custom_message.as_deref(),
+        ),
+        GuardClause::BlockClause(block_clause) => format!(
+            "{}{}{}{} {{\n{}{}}}",
+            if block_clause.negation { "not " } else { "" },
+            if block_clause.query.match_all {
+                ""
+            } else {
+                "some "
+            },
+            query_source(&block_clause.query.query),
+            if block_clause.not_empty {
+                " !empty"
+            } else {
+                ""
+            },
+            block_text(&block_clause.block, indent + 1, guard_clause_text),
+            INDENT.repeat(indent)
+        ),
+        GuardClause::WhenBlock(conditions, block, otherwise) => {
+            when_block_text(conditions, block, otherwise.as_ref(), indent)
+        }
+    }
+}
+
+fn rule_clause_text(clause: &RuleClause<'_>, indent: usize) -> String {
+    match clause {
+        RuleClause::Clause(clause) => guard_clause_text(clause, indent),
+        RuleClause::WhenBlock(conditions, block, otherwise) => {
+            when_block_text(conditions, block, otherwise.as_ref(), indent)
+        }
+        RuleClause::TypeBlock(type_block) => format!(
+            "{}{} {{\n{}{}}}",
+            type_block.type_name,
+            type_block
+                .conditions
+                .as_ref()
+                .map_or(String::new(), |conditions| format!(
+                    " when {}",
+                    when_text(conditions)
+                )),
+            block_text(&type_block.block, indent + 1, guard_clause_text),
+            INDENT.repeat(indent)
+        ),
+    }
+}
+
+fn when_block_text(
+    conditions: &Conjunctions<WhenGuardClause<'_>>,
+    block: &Block<'_, GuardClause<'_>>,
+    otherwise: Option<&Block<'_, GuardClause<'_>>>,
+    indent: usize,
+) -> String {
+    let text = format!(
+        "when {} {{\n{}{}}}",
+        when_text(conditions),
+        block_text(block, indent + 1, guard_clause_text),
+        INDENT.repeat(indent)
+    );
+    match otherwise {
+        Some(otherwise) => format!(
+            "{text} else {{\n{}{}}}",
+            block_text(otherwise, indent + 1, guard_clause_text),
+            INDENT.repeat(indent)
+        ),
+        None => text,
+    }
+}
+
+fn when_text(conditions: &Conjunctions<WhenGuardClause<'_>>) -> String {
+    conjunctions_source(conditions, |clause| match clause {
+        WhenGuardClause::Clause(clause) => access_clause_text(clause),
+        WhenGuardClause::NamedRule(named) => {
+            with_message(named_rule_source(named), named.custom_message.as_deref())
+        }
+        WhenGuardClause::ParameterizedNamedRule(parameterized) => with_message(
+            parameterized_rule_source(parameterized),
+            parameterized.named_rule.custom_message.as_deref(),
+        ),
+    })
+}
+
+fn access_clause_text(clause: &GuardAccessClause<'_>) -> String {
+    let access = &clause.access_clause;
+    let mut text = format!(
+        "{}{}{} {}",
+        if clause.negation { "not " } else { "" },
+        if access.query.match_all { "" } else { "some " },
+        query_source(&access.query.query),
+        comparator_source(access.comparator)
+    );
+    if let Some(value) = &access.compare_with {
+        text.push(' ');
+        text.push_str(&let_value_source(value));
+    }
+    with_message(text, access.custom_message.as_deref())
+}
+
+fn with_message(text: String, message: Option<&str>) -> String {
+    match message {
+        Some(message) => format!("{text} <<{message}>>"),
+        None => text,
+    }
+}
+
+fn block_text<T>(block: &Block<'_, T>, indent: usize, render: fn(&T, usize) -> String) -> String {
+    let mut text = assignments_text(&block.assignments, indent);
+    text.push_str(&conjunctions_text(&block.conjunctions, indent, render));
+    text
+}
+
+fn assignments_text(assignments: &[LetExpr<'_>], indent: usize) -> String {
+    assignments
+        .iter()
+        .map(|each| {
+            format!(
+                "{}let {} = {}\n",
+                INDENT.repeat(indent),
+                each.var,
+                let_value_source(&each.value)
+            )
+        })
+        .collect()
+}
+
+fn conjunctions_text<T>(
+    conjunctions: &Conjunctions<T>,
+    indent: usize,
+    render: fn(&T, usize) -> String,
+) -> String {
+    conjunctions
+        .iter()
+        .map(|disjunctions| {
+            format!(
+                "{}{}\n",
+                INDENT.repeat(indent),
+                disjunctions
+                    .iter()
+                    .map(|each| render(each, indent))
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            )
+        })
+        .collect()
+}
+
 fn write_range<T: Display + PartialOrd>(
     formatter: &mut Formatter<'_>,
     range: &RangeType<T>,
@@ -301,7 +844,10 @@ impl<'value> std::fmt::Display for RecordType<'value> {
             //
             // has as many child events for each ClauseValueCheck
             //
-            RecordType::GuardClauseBlockCheck(BlockCheck { status, .. }) => {
+            RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                block: BlockCheck { status, .. },
+                ..
+            }) => {
                 f.write_fmt(format_args!("GuardClauseBlock(Status = {})", status))?;
             }
 
@@ -1,6 +1,8 @@
 use std::convert::Infallible;
 use std::fmt::{Debug, Formatter};
 use std::string::FromUtf8Error;
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use wasm_bindgen::JsValue;
 
@@ -19,6 +21,11 @@ pub enum Error {
     IoError(#[from] std::io::Error),
     #[error("Parser Error when parsing `{0}`")]
     ParseError(String),
+    #[error("Parser Error when parsing `{message}`")]
+    RulesParseError {
+        message: String,
+        location: Box<ParseErrorLocation>,
+    },
     #[error("Regex expression parse error for rules file {0}")]
     RegexError(#[from] Box<fancy_regex::Error>),
     #[error(
@@ -47,12 +54,45 @@ pub enum Error {
     Errors(#[from] Errors),
     #[error("{0}")]
     IllegalArguments(String),
+    #[error("Signature verification failed, {0}")]
+    SignatureError(String),
+    #[error("Input limit exceeded, {0}")]
+    LimitExceeded(String),
     #[error("Error occurred while attempting to write junit report")]
     XMLError(#[from] quick_xml::Error),
     #[error("{0}")]
     InternalError(#[from] InternalError),
 }
 
+impl Error {
+    /// where in a rules file the error occurred, for errors raised while parsing rules files
+    pub fn parse_error_location(&self) -> Option<&ParseErrorLocation> {
+        match self {
+            Error::RulesParseError { location, .. } => Some(location.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// where a rules file failed to parse and what the parser expected there. It is serialized by the
+/// json and yaml outputs so that editors can point at the offending token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseErrorLocation {
+    pub file: String,
+    /// 1-based line of the offending token
+    pub line: u32,
+    /// 1-based column of the offending token, counted in characters
+    pub column: usize,
+    /// what the parser expected to find at the offending token
+    pub expected: Vec<String>,
+    /// what the parsers handling the offending token were parsing, outermost first and separated
+    /// by `/`
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub context: String,
+    /// the rest of the line starting at the offending token
+    pub fragment: String,
+}
+
 #[derive(Debug, Error)]
 pub enum InternalError {
     #[error("non string type detected for key in a map at {0}, cfn-guard only supports keys that are string types")]
@@ -106,11 +146,15 @@ impl<'a> From<nom::Err<(Span<'a>, nom::error::ErrorKind)>> for Error {
 
 impl<'a> From<nom::Err<ParserError<'a>>> for Error {
     fn from(err: nom::Err<ParserError<'a>>) -> Self {
-        let msg = match err {
-            nom::Err::Failure(e) | nom::Err::Error(e) => format!("Parsing Error {e}"),
-            nom::Err::Incomplete(_) => "More bytes required for parsing".to_string(),
-        };
-        Error::ParseError(msg)
+        match err {
+            nom::Err::Failure(e) | nom::Err::Error(e) => Error::RulesParseError {
+                message: format!("Parsing Error {e}"),
+                location: Box::new(e.location()),
+            },
+            nom::Err::Incomplete(_) => {
+                Error::ParseError("More bytes required for parsing".to_string())
+            }
+        }
     }
 }
 
@@ -2,8 +2,14 @@ use super::exprs::*;
 use super::*;
 use crate::rules::eval::operators::Comparator;
 use crate::rules::eval_context::{block_scope, resolve_function, ValueScope};
+use crate::rules::functions::custom::FunctionRegistry;
 use crate::rules::path_value::compare_eq;
+use crate::rules::profile::Frame;
+use crate::rules::suppressions::Suppression;
+use crate::rules::this_key::{ThisKeys, THIS_KEY};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
 
 mod operators;
 
@@ -92,6 +98,34 @@ where
     }
 }
 
+/// drops the values for missing properties when the rule is annotated with `@on-missing(skip)`,
+/// returns `None` when no value is left to check
+fn skip_missing(lhs: Vec<QueryResult>, on_missing: Option<OnMissing>) -> Option<Vec<QueryResult>> {
+    if on_missing != Some(OnMissing::Skip) || lhs.is_empty() {
+        return Some(lhs);
+    }
+
+    let resolved = lhs
+        .into_iter()
+        .filter(|each| !matches!(each, QueryResult::UnResolved(_)))
+        .collect::<Vec<_>>();
+    match resolved.is_empty() {
+        true => None,
+        false => Some(resolved),
+    }
+}
+
+/// explains why a missing property failed, only when the rule chose it with `@on-missing(fail)`
+fn missing_property_message(on_missing: Option<OnMissing>) -> Option<String> {
+    match on_missing {
+        Some(OnMissing::Fail) => Some(format!(
+            "rule is annotated with @on-missing({}), missing properties fail the check",
+            OnMissing::Fail
+        )),
+        _ => None,
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn record_unary_clause<'eval, 'value, 'loc: 'value, O>(
     operation: O,
@@ -115,6 +149,9 @@ where
             Ok(result) => {
                 if !result {
                     check.status = Status::FAIL;
+                    if let QueryResult::UnResolved(_) = value {
+                        check.message = missing_property_message(eval_context.on_missing());
+                    }
                     eval_context.end_record(
                         &context,
                         RecordType::ClauseValueCheck(ClauseCheck::Unary(UnaryValueCheck {
@@ -297,6 +334,16 @@ fn unary_operation<'r, 'l: 'r, 'loc: 'l>(
         });
     }
 
+    //
+    // A filter or variable that selected nothing does not exist, so negated exists checks like
+    // not Resources[ Type == 'AWS::IAM::Policy' ] EXISTS pass instead of being skipped
+    //
+    if empty_on_expr && lhs.is_empty() && cmp.0 == CmpOperator::Exists && cmp.1 != inverse {
+        eval_context.start_record(&context)?;
+        eval_context.end_record(&context, RecordType::ClauseValueCheck(ClauseCheck::Success))?;
+        return Ok(EvaluationResult::EmptyQueryResult(Status::PASS));
+    }
+
     //
     // This only happens when the query has filters in them
     //
@@ -304,6 +351,17 @@ fn unary_operation<'r, 'l: 'r, 'loc: 'l>(
         return Ok(EvaluationResult::EmptyQueryResult(Status::SKIP));
     }
 
+    //
+    // exists and empty check for the presence of the property, so they are never skipped
+    //
+    let lhs = match cmp.0 {
+        CmpOperator::Exists | CmpOperator::Empty => lhs,
+        _ => match skip_missing(lhs, eval_context.on_missing()) {
+            Some(lhs) => lhs,
+            None => return Ok(EvaluationResult::EmptyQueryResult(Status::SKIP)),
+        },
+    };
+
     use CmpOperator::*;
     let mut operation: Box<dyn FnMut(&QueryResult) -> Result<bool>> = match cmp {
         (CmpOperator::Exists, not_exists) => box_create_func!(
@@ -678,7 +736,7 @@ fn report_at_least_one<'r, 'value: 'r, 'loc: 'value>(
     eval_context: &'r mut dyn EvalContext<'value, 'loc>,
 ) -> Result<Vec<(QueryResult, Status)>> {
     let mut statues = Vec::with_capacity(rhs_comparisons.len());
-    let mut by_lhs_value = HashMap::new();
+    let mut by_lhs_value = indexmap::IndexMap::new();
     for each in &rhs_comparisons {
         match each {
             ComparisonResult::Comparable(ComparisonWithRhs {
@@ -770,8 +828,19 @@ fn binary_operation<'value, 'loc: 'value>(
     custom_message: Option<String>,
     eval_context: &mut dyn EvalContext<'value, 'loc>,
 ) -> Result<EvaluationResult> {
-    let lhs = eval_context.query(lhs_query)?;
-    let results = cmp.compare(&lhs, rhs)?;
+    let on_missing = eval_context.on_missing();
+    let lhs = match skip_missing(eval_context.query(lhs_query)?, on_missing) {
+        Some(lhs) => lhs,
+        None => return Ok(EvaluationResult::EmptyQueryResult(Status::SKIP)),
+    };
+    let mismatched = match eval_context.strict_types() {
+        true => operators::mismatched_types(&lhs, rhs),
+        false => vec![],
+    };
+    let results = match mismatched.is_empty() {
+        true => cmp.compare(&lhs, rhs)?,
+        false => operators::EvalResult::Result(mismatched),
+    };
     match results {
         operators::EvalResult::Skip => Ok(EvaluationResult::EmptyQueryResult(Status::SKIP)),
         operators::EvalResult::Result(results) => {
@@ -785,7 +854,7 @@ fn binary_operation<'value, 'loc: 'value>(
                             RecordType::ClauseValueCheck(ClauseCheck::Comparison(
                                 ComparisonClauseCheck {
                                     status: Status::FAIL,
-                                    message: None,
+                                    message: missing_property_message(on_missing),
                                     custom_message: custom_message.clone(),
                                     comparison: cmp,
                                     from: QueryResult::UnResolved(ur.clone()),
@@ -1015,6 +1084,18 @@ pub(super) fn real_binary_operation<'value, 'loc: 'value>(
                         rhs,
                     )?,
 
+                    (CmpOperator::UnorderedEq, is_not) => each_lhs_compare(
+                        not_compare(crate::rules::path_value::compare_unordered_eq, is_not),
+                        Rc::clone(l),
+                        rhs,
+                    )?,
+
+                    (CmpOperator::Like, is_not) => each_lhs_compare(
+                        not_compare(crate::rules::path_value::compare_like, is_not),
+                        Rc::clone(l),
+                        rhs,
+                    )?,
+
                     (CmpOperator::Ge, is_not) => each_lhs_compare(
                         not_compare(crate::rules::path_value::compare_ge, is_not),
                         Rc::clone(l),
@@ -1074,10 +1155,28 @@ pub(super) fn real_binary_operation<'value, 'loc: 'value>(
     Ok(EvaluationResult::QueryValueResult(statues))
 }
 
-#[allow(clippy::never_loop)]
 pub(in crate::rules) fn eval_guard_access_clause<'value, 'loc: 'value>(
     gac: &'value GuardAccessClause<'loc>,
     resolver: &mut dyn EvalContext<'value, 'loc>,
+) -> Result<Status> {
+    let this_keys = match &gac.access_clause.custom_message {
+        Some(message) if message.contains(THIS_KEY) => {
+            Some(ThisKeys::new(&gac.access_clause.query.query, resolver)?)
+        }
+        _ => None,
+    };
+    let previous = resolver.set_this_keys(this_keys);
+    resolver.enter_frame(Frame::Clause(gac));
+    let status = check_guard_access_clause(gac, resolver);
+    resolver.exit_frame();
+    resolver.set_this_keys(previous);
+    status
+}
+
+#[allow(clippy::never_loop)]
+fn check_guard_access_clause<'value, 'loc: 'value>(
+    gac: &'value GuardAccessClause<'loc>,
+    resolver: &mut dyn EvalContext<'value, 'loc>,
 ) -> Result<Status> {
     let all = gac.access_clause.query.match_all;
     let blk_context = format!("GuardAccessClause#block{}", gac);
@@ -1103,10 +1202,15 @@ pub(in crate::rules) fn eval_guard_access_clause<'value, 'loc: 'value>(
                     Err(e) => {
                         resolver.end_record(
                             &blk_context,
-                            RecordType::GuardClauseBlockCheck(BlockCheck {
-                                status: Status::FAIL,
-                                at_least_one_matches: !all,
-                                message: Some(format!("Error {e} when handling clause, bailing")),
+                            RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                                block: BlockCheck {
+                                    status: Status::FAIL,
+                                    at_least_one_matches: !all,
+                                    message: Some(format!(
+                                        "Error {e} when handling clause, bailing"
+                                    )),
+                                },
+                                clause: gac,
                             }),
                         )?;
                         return Err(e);
@@ -1119,10 +1223,15 @@ pub(in crate::rules) fn eval_guard_access_clause<'value, 'loc: 'value>(
                     Err(e) => {
                         resolver.end_record(
                             &blk_context,
-                            RecordType::GuardClauseBlockCheck(BlockCheck {
-                                status: Status::FAIL,
-                                at_least_one_matches: !all,
-                                message: Some(format!("Error {e} when handling clause, bailing")),
+                            RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                                block: BlockCheck {
+                                    status: Status::FAIL,
+                                    at_least_one_matches: !all,
+                                    message: Some(format!(
+                                        "Error {e} when handling clause, bailing"
+                                    )),
+                                },
+                                clause: gac,
                             }),
                         )?;
                         return Err(e);
@@ -1132,13 +1241,16 @@ pub(in crate::rules) fn eval_guard_access_clause<'value, 'loc: 'value>(
             None => {
                 resolver.end_record(
                     &blk_context,
-                    RecordType::GuardClauseBlockCheck(BlockCheck {
-                        status: Status::FAIL,
-                        at_least_one_matches: !all,
-                        message: Some(
-                            "Error not RHS for binary clause when handling clause, bailing"
-                                .to_string(),
-                        ),
+                    RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                        block: BlockCheck {
+                            status: Status::FAIL,
+                            at_least_one_matches: !all,
+                            message: Some(
+                                "Error not RHS for binary clause when handling clause, bailing"
+                                    .to_string(),
+                            ),
+                        },
+                        clause: gac,
                     }),
                 )?;
                 return Err(Error::NotComparable(format!(
@@ -1162,10 +1274,13 @@ pub(in crate::rules) fn eval_guard_access_clause<'value, 'loc: 'value>(
             EvaluationResult::EmptyQueryResult(status) => {
                 resolver.end_record(
                     &blk_context,
-                    RecordType::GuardClauseBlockCheck(BlockCheck {
-                        status,
-                        message: None,
-                        at_least_one_matches: all,
+                    RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                        block: BlockCheck {
+                            status,
+                            message: None,
+                            at_least_one_matches: all,
+                        },
+                        clause: gac,
                     }),
                 )?;
                 Ok(status)
@@ -1199,10 +1314,13 @@ pub(in crate::rules) fn eval_guard_access_clause<'value, 'loc: 'value>(
                 };
                 resolver.end_record(
                     &blk_context,
-                    RecordType::GuardClauseBlockCheck(BlockCheck {
-                        message: None,
-                        status: outcome,
-                        at_least_one_matches: !all,
+                    RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                        block: BlockCheck {
+                            message: None,
+                            status: outcome,
+                            at_least_one_matches: !all,
+                        },
+                        clause: gac,
                     }),
                 )?;
                 Ok(outcome)
@@ -1212,10 +1330,13 @@ pub(in crate::rules) fn eval_guard_access_clause<'value, 'loc: 'value>(
         Err(e) => {
             resolver.end_record(
                 &blk_context,
-                RecordType::GuardClauseBlockCheck(BlockCheck {
-                    status: Status::FAIL,
-                    at_least_one_matches: !all,
-                    message: Some(format!("Error {} when handling clause, bailing", e)),
+                RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                    block: BlockCheck {
+                        status: Status::FAIL,
+                        at_least_one_matches: !all,
+                        message: Some(format!("Error {} when handling clause, bailing", e)),
+                    },
+                    clause: gac,
                 }),
             )?;
 
@@ -1303,6 +1424,16 @@ where
 pub(in crate::rules) fn eval_guard_block_clause<'value, 'loc: 'value>(
     block_clause: &'value BlockGuardClause<'loc>,
     resolver: &mut dyn EvalContext<'value, 'loc>,
+) -> Result<Status> {
+    resolver.enter_frame(Frame::Block(block_clause));
+    let status = check_guard_block_clause(block_clause, resolver);
+    resolver.exit_frame();
+    status
+}
+
+fn check_guard_block_clause<'value, 'loc: 'value>(
+    block_clause: &'value BlockGuardClause<'loc>,
+    resolver: &mut dyn EvalContext<'value, 'loc>,
 ) -> Result<Status> {
     let context = format!("BlockGuardClause#{}", block_clause.location);
     let match_all = block_clause.query.match_all;
@@ -1322,7 +1453,10 @@ pub(in crate::rules) fn eval_guard_block_clause<'value, 'loc: 'value>(
         }
     };
     if block_values.is_empty() {
-        let status = if block_clause.not_empty {
+        let status = if block_clause.negation {
+            // nothing was selected, so nothing can satisfy the negated block
+            Status::PASS
+        } else if block_clause.not_empty {
             Status::FAIL
         } else {
             Status::SKIP
@@ -1337,10 +1471,13 @@ pub(in crate::rules) fn eval_guard_block_clause<'value, 'loc: 'value>(
         )?;
         return Ok(status);
     }
+    let on_missing = resolver.on_missing();
     let mut fails = 0;
     let mut passes = 0;
     for each in block_values {
         match each {
+            QueryResult::UnResolved(_) if on_missing == Some(OnMissing::Skip) => {}
+
             QueryResult::UnResolved(ur) => {
                 fails += 1;
                 let guard_cxt = format!("GuardBlockAccessClause#{}", block_clause.location);
@@ -1349,9 +1486,11 @@ pub(in crate::rules) fn eval_guard_block_clause<'value, 'loc: 'value>(
                     &guard_cxt,
                     RecordType::ClauseValueCheck(ClauseCheck::MissingBlockValue(ValueCheck {
                         message: Some(format!(
-                            "Query {} did not resolve to correct value, reason {}",
+                            "Query {} did not resolve to correct value, reason {}{}",
                             SliceDisplay(&block_clause.query.query),
-                            ur.reason.as_ref().map_or("", |s| s)
+                            ur.reason.as_ref().map_or("", |s| s),
+                            missing_property_message(on_missing)
+                                .map_or(String::new(), |msg| format!(", {msg}"))
                         )),
                         status: Status::FAIL,
                         custom_message: None,
@@ -1414,6 +1553,11 @@ pub(in crate::rules) fn eval_guard_block_clause<'value, 'loc: 'value>(
     } else {
         Status::SKIP
     };
+    let status = match (block_clause.negation, status) {
+        (true, Status::PASS) => Status::FAIL,
+        (true, Status::FAIL) => Status::PASS,
+        (_, status) => status,
+    };
     resolver.end_record(
         &context,
         RecordType::BlockGuardCheck(BlockCheck {
@@ -1425,17 +1569,27 @@ pub(in crate::rules) fn eval_guard_block_clause<'value, 'loc: 'value>(
     Ok(status)
 }
 
+/// evaluates the block when the conditions pass, the else block when they fail, and skips
+/// both when the conditions are skipped
 fn eval_when_condition_block<'value, 'loc: 'value>(
     context: String,
     conditions: &'value WhenConditions<'loc>,
     block: &'value Block<'loc, GuardClause<'loc>>,
+    otherwise: Option<&'value Block<'loc, GuardClause<'loc>>>,
     resolver: &mut dyn EvalContext<'value, 'loc>,
 ) -> Result<Status> {
     resolver.start_record(&context)?;
     let when_context = format!("{}/When", context);
     resolver.start_record(&when_context)?;
-    let block = match eval_conjunction_clauses(conditions, resolver, eval_when_clause) {
-        Ok(status) => {
+    let block = match (
+        eval_conjunction_clauses(conditions, resolver, eval_when_clause),
+        otherwise,
+    ) {
+        (Ok(Status::FAIL), Some(otherwise)) => {
+            resolver.end_record(&when_context, RecordType::WhenCondition(Status::FAIL))?;
+            otherwise
+        }
+        (Ok(status), _) => {
             if status != Status::PASS {
                 resolver.end_record(&when_context, RecordType::WhenCondition(status))?;
                 resolver.end_record(
@@ -1452,7 +1606,7 @@ fn eval_when_condition_block<'value, 'loc: 'value>(
             block
         }
 
-        Err(e) => {
+        (Err(e), _) => {
             resolver.end_record(&when_context, RecordType::WhenCondition(Status::FAIL))?;
             resolver.end_record(
                 &context,
@@ -1525,10 +1679,54 @@ impl<'eval, 'value, 'loc: 'value> EvalContext<'value, 'loc>
         self.parent.root()
     }
 
+    fn document_root(&mut self) -> Rc<PathAwareValue> {
+        self.parent.document_root()
+    }
+
     fn rule_status(&mut self, rule_name: &'value str) -> Result<Status> {
         self.parent.rule_status(rule_name)
     }
 
+    fn on_missing(&self) -> Option<OnMissing> {
+        self.parent.on_missing()
+    }
+
+    fn set_on_missing(&mut self, on_missing: Option<OnMissing>) -> Option<OnMissing> {
+        self.parent.set_on_missing(on_missing)
+    }
+
+    fn functions(&self) -> Option<Arc<FunctionRegistry>> {
+        self.parent.functions()
+    }
+
+    fn set_current_rule(&mut self, rule_name: Option<&'value str>) -> Option<&'value str> {
+        self.parent.set_current_rule(rule_name)
+    }
+
+    fn set_this_keys(&mut self, this_keys: Option<ThisKeys>) -> Option<ThisKeys> {
+        self.parent.set_this_keys(this_keys)
+    }
+
+    fn enter_frame(&mut self, frame: Frame<'value, 'loc>) {
+        self.parent.enter_frame(frame)
+    }
+
+    fn exit_frame(&mut self) {
+        self.parent.exit_frame()
+    }
+
+    fn count_resolution(&mut self) {
+        self.parent.count_resolution()
+    }
+
+    fn without_suppressed(&mut self, results: Vec<QueryResult>) -> Vec<QueryResult> {
+        self.parent.without_suppressed(results)
+    }
+
+    fn suppressed(&self, rule_name: &str) -> Vec<Suppression> {
+        self.parent.suppressed(rule_name)
+    }
+
     fn resolve_variable(&mut self, variable_name: &'value str) -> Result<Vec<QueryResult>> {
         match self.resolved_parameters.get(variable_name) {
             Some(res) => Ok(res.clone()),
@@ -1557,9 +1755,8 @@ impl<'eval, 'value, 'loc: 'value> RecordTracer<'value>
             RecordType::RuleCheck(ns) => {
                 if ns.name == self.call_rule.named_rule.dependent_rule {
                     RecordType::RuleCheck(NamedStatus {
-                        name: ns.name,
-                        status: ns.status,
                         message: self.call_rule.named_rule.custom_message.clone(),
+                        ..ns
                     })
                 } else {
                     RecordType::RuleCheck(ns)
@@ -1625,10 +1822,11 @@ pub(in crate::rules) fn eval_guard_clause<'value, 'loc: 'value>(
         GuardClause::Clause(gac) => eval_guard_access_clause(gac, resolver),
         GuardClause::NamedRule(gnc) => eval_guard_named_clause(gnc, resolver),
         GuardClause::BlockClause(bc) => eval_guard_block_clause(bc, resolver),
-        GuardClause::WhenBlock(conditions, block) => eval_when_condition_block(
+        GuardClause::WhenBlock(conditions, block, otherwise) => eval_when_condition_block(
             "GuardConditionClause".to_string(),
             conditions,
             block,
+            otherwise.as_ref(),
             resolver,
         ),
         GuardClause::ParameterizedNamedRule(prc) => eval_parameterized_rule_call(prc, resolver),
@@ -1649,6 +1847,16 @@ pub(in crate::rules) fn eval_when_clause<'value, 'loc: 'value>(
 pub(in crate::rules) fn eval_type_block_clause<'value, 'loc: 'value>(
     type_block: &'value TypeBlock<'loc>,
     resolver: &mut dyn EvalContext<'value, 'loc>,
+) -> Result<Status> {
+    resolver.enter_frame(Frame::TypeBlock(&type_block.type_name));
+    let status = check_type_block_clause(type_block, resolver);
+    resolver.exit_frame();
+    status
+}
+
+fn check_type_block_clause<'value, 'loc: 'value>(
+    type_block: &'value TypeBlock<'loc>,
+    resolver: &mut dyn EvalContext<'value, 'loc>,
 ) -> Result<Status> {
     let context = format!("TypeBlock#{}", type_block.type_name);
     resolver.start_record(&context)?;
@@ -1828,15 +2036,36 @@ pub(in crate::rules) fn eval_rule_clause<'value, 'loc: 'value>(
     match rule_clause {
         RuleClause::Clause(gc) => eval_guard_clause(gc, resolver),
         RuleClause::TypeBlock(tb) => eval_type_block_clause(tb, resolver),
-        RuleClause::WhenBlock(conditions, block) => {
-            eval_when_condition_block("RuleClause".to_string(), conditions, block, resolver)
-        }
+        RuleClause::WhenBlock(conditions, block, otherwise) => eval_when_condition_block(
+            "RuleClause".to_string(),
+            conditions,
+            block,
+            otherwise.as_ref(),
+            resolver,
+        ),
     }
 }
 
 pub(in crate::rules) fn eval_rule<'value, 'loc: 'value>(
     rule: &'value Rule<'loc>,
     resolver: &mut dyn EvalContext<'value, 'loc>,
+) -> Result<Status> {
+    //
+    // annotations only apply to the clauses of this rule, named rules it depends on use their own
+    //
+    let previous = resolver.set_on_missing(rule.annotations.on_missing);
+    let previous_rule = resolver.set_current_rule(Some(&rule.rule_name));
+    resolver.enter_frame(Frame::Rule(&rule.rule_name));
+    let status = eval_rule_clauses(rule, resolver);
+    resolver.exit_frame();
+    resolver.set_current_rule(previous_rule);
+    resolver.set_on_missing(previous);
+    status
+}
+
+fn eval_rule_clauses<'value, 'loc: 'value>(
+    rule: &'value Rule<'loc>,
+    resolver: &mut dyn EvalContext<'value, 'loc>,
 ) -> Result<Status> {
     let context = rule.rule_name.to_string();
     resolver.start_record(&context)?;
@@ -1852,6 +2081,8 @@ pub(in crate::rules) fn eval_rule<'value, 'loc: 'value>(
                         RecordType::RuleCheck(NamedStatus {
                             status: Status::SKIP,
                             name: &rule.rule_name,
+                            severity: rule.annotations.severity(),
+                            suppressed: resolver.suppressed(&rule.rule_name),
                             ..Default::default()
                         }),
                     )?;
@@ -1868,6 +2099,7 @@ pub(in crate::rules) fn eval_rule<'value, 'loc: 'value>(
                     RecordType::RuleCheck(NamedStatus {
                         status: Status::FAIL,
                         name: &rule.rule_name,
+                        severity: rule.annotations.severity(),
                         ..Default::default()
                     }),
                 )?;
@@ -1885,6 +2117,8 @@ pub(in crate::rules) fn eval_rule<'value, 'loc: 'value>(
                 RecordType::RuleCheck(NamedStatus {
                     status,
                     name: &rule.rule_name,
+                    severity: rule.annotations.severity(),
+                    suppressed: resolver.suppressed(&rule.rule_name),
                     ..Default::default()
                 }),
             )?;
@@ -1897,6 +2131,7 @@ pub(in crate::rules) fn eval_rule<'value, 'loc: 'value>(
                 RecordType::RuleCheck(NamedStatus {
                     status: Status::FAIL,
                     name: &rule.rule_name,
+                    severity: rule.annotations.severity(),
                     ..Default::default()
                 }),
             )?;
@@ -1921,15 +2156,24 @@ pub(crate) fn eval_rules_file<'value, 'loc: 'value>(
     resolver.start_record(&context)?;
     let mut fails = 0;
     let mut passes = 0;
+    let mut warnings = 0;
     for each_rule in &rule.guard_rules {
-        match eval_rule(each_rule, resolver) {
+        // private rules are only evaluated when a rule refers to them, and reported in it
+        if each_rule.annotations.private || !resolver.is_rule_selected(each_rule) {
+            continue;
+        }
+        let start = Instant::now();
+        let result = eval_rule(each_rule, resolver);
+        resolver.record_duration(&each_rule.rule_name, start.elapsed());
+        match result {
             Ok(status) => match status {
                 Status::PASS => {
                     passes += 1;
                 }
-                Status::FAIL => {
-                    fails += 1;
-                }
+                Status::FAIL => match each_rule.annotations.severity() {
+                    Severity::Error => fails += 1,
+                    Severity::Warning => warnings += 1,
+                },
                 Status::SKIP => {}
             },
 
@@ -1939,6 +2183,7 @@ pub(crate) fn eval_rules_file<'value, 'loc: 'value>(
                     RecordType::RuleCheck(NamedStatus {
                         status: Status::FAIL,
                         name: &each_rule.rule_name,
+                        severity: each_rule.annotations.severity(),
                         ..Default::default()
                     }),
                 )?;
@@ -1947,9 +2192,10 @@ pub(crate) fn eval_rules_file<'value, 'loc: 'value>(
         }
     }
 
+    // failing warnings do not fail the file, it passes as long as no other rule failed
     let overall = if fails > 0 {
         Status::FAIL
-    } else if passes > 0 {
+    } else if passes > 0 || warnings > 0 {
         Status::PASS
     } else {
         Status::SKIP
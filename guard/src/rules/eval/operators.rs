@@ -1,7 +1,9 @@
 use std::rc::Rc;
 
+use crate::rules::display::ValueOnlyDisplay;
 use crate::rules::errors::Error;
 use crate::rules::path_value::*;
+use crate::rules::values::parse_quantity;
 use crate::rules::{CmpOperator, QueryResult, UnResolved};
 
 #[derive(Clone, Debug)]
@@ -110,7 +112,9 @@ struct CommonOperator {
     comparator: fn(&PathAwareValue, &PathAwareValue) -> crate::rules::Result<bool>,
 }
 
-struct EqOperation {}
+struct EqOperation {
+    comparator: fn(&PathAwareValue, &PathAwareValue) -> crate::rules::Result<bool>,
+}
 struct InOperation {}
 
 fn selected<U, R>(query_results: &[QueryResult], mut c: U, mut r: R) -> Vec<Rc<PathAwareValue>>
@@ -239,6 +243,51 @@ fn not_comparable(lhs: Rc<PathAwareValue>, rhs: Rc<PathAwareValue>) -> ValueEval
     }))
 }
 
+/// the values of different types the comparison would compare, as values that are not
+/// comparable, for `--strict-types` to report them instead of letting them fail, or pass when the
+/// comparison is negated. Lists are compared element by element, and maps with maps only
+pub(crate) fn mismatched_types(lhs: &[QueryResult], rhs: &[QueryResult]) -> Vec<ValueEvalResult> {
+    let lhs_flattened = flattened(lhs, |_| {});
+    let rhs_flattened = flattened(rhs, |_| {});
+    let mut mismatched = vec![];
+    for each_lhs in &lhs_flattened {
+        for each_rhs in &rhs_flattened {
+            if !strictly_comparable(each_lhs, each_rhs) {
+                mismatched.push(ValueEvalResult::ComparisonResult(
+                    ComparisonResult::NotComparable(NotComparable {
+                        reason: format!(
+                            "{} {} is not comparable with {} {} with --strict-types",
+                            each_lhs.type_info(),
+                            ValueOnlyDisplay(Rc::clone(each_lhs)),
+                            each_rhs.type_info(),
+                            ValueOnlyDisplay(Rc::clone(each_rhs)),
+                        ),
+                        pair: LhsRhsPair::new(Rc::clone(each_lhs), Rc::clone(each_rhs)),
+                    }),
+                ));
+            }
+        }
+    }
+    mismatched
+}
+
+fn strictly_comparable(lhs: &PathAwareValue, rhs: &PathAwareValue) -> bool {
+    match (lhs, rhs) {
+        (PathAwareValue::List(_), _) | (_, PathAwareValue::List(_)) => true,
+        (PathAwareValue::String(_), PathAwareValue::Regex(_))
+        | (PathAwareValue::Regex(_), PathAwareValue::String(_))
+        | (PathAwareValue::Int(_), PathAwareValue::RangeInt(_))
+        | (PathAwareValue::Float(_), PathAwareValue::RangeFloat(_))
+        | (PathAwareValue::Char(_), PathAwareValue::RangeChar(_)) => true,
+        // sizes and durations with a unit compare with numbers, "5" does not
+        (PathAwareValue::String((_, s)), PathAwareValue::Int(_) | PathAwareValue::Float(_))
+        | (PathAwareValue::Int(_) | PathAwareValue::Float(_), PathAwareValue::String((_, s))) => {
+            parse_quantity(s).is_some()
+        }
+        (lhs, rhs) => std::mem::discriminant(lhs) == std::mem::discriminant(rhs),
+    }
+}
+
 fn success(lhs: Rc<PathAwareValue>, rhs: Rc<PathAwareValue>) -> ValueEvalResult {
     ValueEvalResult::ComparisonResult(ComparisonResult::Success(Compare::Value(LhsRhsPair {
         lhs,
@@ -459,7 +508,7 @@ impl Comparator for EqOperation {
         let mut results = Vec::with_capacity(lhs.len());
         match (is_literal(lhs), is_literal(rhs)) {
             (Some(ref l), Some(ref r)) => {
-                results.push(match_value(Rc::clone(l), Rc::clone(r), compare_eq));
+                results.push(match_value(Rc::clone(l), Rc::clone(r), self.comparator));
             }
 
             (Some(l), None) => {
@@ -476,7 +525,7 @@ impl Comparator for EqOperation {
                 match &*l {
                     PathAwareValue::List(_) => {
                         for each in rhs {
-                            results.push(match_value(Rc::clone(&l), each, compare_eq));
+                            results.push(match_value(Rc::clone(&l), each, self.comparator));
                         }
                     }
 
@@ -488,7 +537,7 @@ impl Comparator for EqOperation {
                                         results.push(match_value(
                                             Rc::new(single_value.clone()),
                                             Rc::new(each_rhs.clone()),
-                                            compare_eq,
+                                            self.comparator,
                                         ));
                                     }
                                 }
@@ -497,7 +546,7 @@ impl Comparator for EqOperation {
                                     results.push(match_value(
                                         Rc::new(single_value.clone()),
                                         Rc::new(rest_rhs.clone()),
-                                        compare_eq,
+                                        self.comparator,
                                     ));
                                 }
                             }
@@ -519,10 +568,10 @@ impl Comparator for EqOperation {
                                 results.push(match_value(
                                     each,
                                     Rc::new(rhsl[0].clone()),
-                                    compare_eq,
+                                    self.comparator,
                                 ))
                             } else {
-                                results.push(match_value(each, Rc::clone(&r), compare_eq));
+                                results.push(match_value(each, Rc::clone(&r), self.comparator));
                             }
                         }
                     }
@@ -534,14 +583,14 @@ impl Comparator for EqOperation {
                                     results.push(match_value(
                                         Rc::new(each_lhs.clone()),
                                         Rc::new(single_value.clone()),
-                                        compare_eq,
+                                        self.comparator,
                                     ));
                                 }
                             } else {
                                 results.push(match_value(
                                     each.clone(),
                                     Rc::clone(&r.clone()),
-                                    compare_eq,
+                                    self.comparator,
                                 ));
                             }
                         }
@@ -608,8 +657,19 @@ impl Comparator for crate::rules::CmpOperator {
         }
 
         match self {
-            CmpOperator::Eq => EqOperation {}.compare(lhs, rhs),
+            CmpOperator::Eq => EqOperation {
+                comparator: compare_eq,
+            }
+            .compare(lhs, rhs),
+            CmpOperator::UnorderedEq => EqOperation {
+                comparator: compare_unordered_eq,
+            }
+            .compare(lhs, rhs),
             CmpOperator::In => InOperation {}.compare(lhs, rhs),
+            CmpOperator::Like => CommonOperator {
+                comparator: compare_like,
+            }
+            .compare(lhs, rhs),
             CmpOperator::Lt => CommonOperator {
                 comparator: compare_lt,
             }
@@ -663,8 +723,11 @@ impl Comparator for (crate::rules::CmpOperator, bool) {
                                     match c {
                                         Compare::QueryIn(qin) => {
                                             let reverse_diff = if rhs.len() >= lhs.len()
-                                                && matches!(self.0, crate::rules::CmpOperator::Eq)
-                                            {
+                                                && matches!(
+                                                    self.0,
+                                                    crate::rules::CmpOperator::Eq
+                                                        | crate::rules::CmpOperator::UnorderedEq
+                                                ) {
                                                 reverse_diff(qin.diff, &qin.rhs)
                                             } else {
                                                 reverse_diff(qin.diff, &qin.lhs)
@@ -1,33 +1,70 @@
+use crate::rules::display::{clause_source, slice_source, ValueOnlyDisplay};
 use crate::rules::errors::Error;
 use crate::rules::exprs::{
-    AccessQuery, Block, Conjunctions, FunctionExpr, GuardClause, LetExpr, LetValue,
-    ParameterizedRule, QueryPart, Rule, RulesFile, SliceDisplay,
+    list_position, slice_range, AccessQuery, Block, Conjunctions, FunctionExpr, GuardClause,
+    LetExpr, LetValue, OnMissing, ParameterizedRule, QueryPart, Rule, RuleSelection, RulesFile,
+    Severity, SliceDisplay,
+};
+use crate::rules::functions::collections::{
+    count, difference, distinct, intersection, keys, sorted, subset_of, union, values,
 };
-use crate::rules::functions::collections::count;
 use crate::rules::functions::converters::{
     parse_bool, parse_char, parse_float, parse_int, parse_str,
 };
+use crate::rules::functions::custom::{call_custom, FunctionRegistry};
 use crate::rules::functions::strings::{
-    join, json_parse, regex_replace, substring, to_lower, to_upper, url_decode,
+    env, interpolate, join, json_parse, matches_ci, parse_arn, regex_replace, substring, to_lower,
+    to_upper, url_decode,
 };
+use crate::rules::functions::tags::required_tags;
+use crate::rules::limits::InputLimits;
+use crate::rules::messages::{MessageCatalog, MessageId};
+use crate::rules::observer::{EvaluationEvent, EvaluationObserver};
 use crate::rules::path_value::{Location, MapValue, PathAwareValue};
+use crate::rules::profile::{Frame, Profiler};
+use crate::rules::suppressions::{Suppression, Suppressions};
+use crate::rules::this_key::{this_value, ThisKeys, THIS};
 use crate::rules::values::CmpOperator;
 use crate::rules::Result;
 use crate::rules::Status::SKIP;
 use crate::rules::{
-    BlockCheck, ClauseCheck, ComparisonClauseCheck, EvalContext, InComparisonCheck, NamedStatus,
-    QueryResult, RecordTracer, RecordType, Status, TypeBlockCheck, UnResolved, UnaryValueCheck,
-    ValueCheck,
+    BlockCheck, ClauseCheck, ComparisonClauseCheck, EvalContext, GuardClauseCheck,
+    InComparisonCheck, NamedStatus, QueryResult, RecordTracer, RecordType, Status, TypeBlockCheck,
+    UnResolved, UnaryValueCheck, ValueCheck,
 };
 use cruet::case::{camel, class, kebab, pascal, snake, title, train};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::convert::TryFrom;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 use std::vec::Vec;
 
 use super::functions::date_time::{now, parse_epoch};
+use super::template_metrics::{template_metrics, TEMPLATE_METRICS};
+
+/// name of the reserved variable the parameters file paired with the data file is exposed under,
+/// e.g. `%PARAMETERS_FILE.Parameters.Environment`. It resolves to no values when the data file has
+/// no parameters file. A `let` with the same name in the rules file takes precedence
+pub(crate) const PARAMETERS_FILE: &str = "PARAMETERS_FILE";
+
+/// name of the reserved variable the effective parameter values of a template are exposed under
+/// with `--resolve-parameters`, e.g. `%PARAMETER_VALUES.Environment`. It resolves to no values
+/// otherwise. A `let` with the same name in the rules file takes precedence
+pub(crate) const PARAMETER_VALUES: &str = "PARAMETER_VALUES";
+
+/// name of the reserved variable the `--vars` file is exposed under, e.g. `%VARS.allowed_regions`.
+/// It resolves to no values when no vars file is given. A `let` with the same name in the rules
+/// file takes precedence
+pub(crate) const VARS: &str = "VARS";
+
+/// name of the reserved variable the resources of a deployed stack are exposed under when it is
+/// validated with `--from-stack`, keyed by their logical ids, e.g.
+/// `%STACK_RESOURCES.*.DriftStatus`. It resolves to no values for other data files. A `let` with
+/// the same name in the rules file takes precedence
+pub(crate) const STACK_RESOURCES: &str = "STACK_RESOURCES";
 
 pub(crate) struct Scope<'value, 'loc: 'value> {
     root: Rc<PathAwareValue>,
@@ -50,9 +87,83 @@ pub(crate) struct RootScope<'value, 'loc: 'value> {
     rules_status: HashMap<&'value str, Status>,
     parameterized_rules: HashMap<&'value str, &'value ParameterizedRule<'loc>>,
     recorder: RecordTracker<'value>,
+    on_missing: Option<OnMissing>,
+    /// whether comparisons between values of different types are reported, see `--strict-types`
+    strict_types: bool,
+    /// the most values a query may resolve to, see `--max-query-results`
+    limits: InputLimits,
+    selection: RuleSelection,
+    parameters_file: Option<Rc<PathAwareValue>>,
+    parameter_values: Option<Rc<PathAwareValue>>,
+    vars: Option<Rc<PathAwareValue>>,
+    stack_resources: Option<Rc<PathAwareValue>>,
+    /// the resources of the data file opting out of rules through their metadata
+    suppressions: Suppressions,
+    current_rule: Option<&'value str>,
+    /// the resources each rule was suppressed for so far
+    suppressed: HashMap<&'value str, BTreeSet<Suppression>>,
+    /// what `%THIS.key` stands for in the custom message of the clause being evaluated
+    this_keys: Option<ThisKeys>,
+    /// how long each rule of the rules file took to evaluate, in the order they were evaluated
+    durations: Vec<(&'value str, Duration)>,
+    /// records the frames evaluated when the evaluation is profiled
+    profiler: Option<Profiler>,
+    /// receives the events of the evaluation as they are recorded
+    observer: Option<Box<dyn EvaluationObserver + 'value>>,
+    /// the custom functions the rules can call
+    functions: Option<Arc<FunctionRegistry>>,
 }
 
 impl<'value, 'loc: 'value> RootScope<'value, 'loc> {
+    pub(crate) fn with_selection(mut self, selection: &RuleSelection) -> Self {
+        self.selection = selection.clone();
+        self
+    }
+
+    pub(crate) fn with_parameters_file(
+        mut self,
+        parameters_file: Option<Rc<PathAwareValue>>,
+    ) -> Self {
+        self.parameters_file = parameters_file;
+        self
+    }
+
+    pub(crate) fn with_parameter_values(
+        mut self,
+        parameter_values: Option<Rc<PathAwareValue>>,
+    ) -> Self {
+        self.parameter_values = parameter_values;
+        self
+    }
+
+    pub(crate) fn with_vars(mut self, vars: Option<Rc<PathAwareValue>>) -> Self {
+        self.vars = vars;
+        self
+    }
+
+    pub(crate) fn with_functions(mut self, functions: Option<Arc<FunctionRegistry>>) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    pub(crate) fn with_strict_types(mut self, strict_types: bool) -> Self {
+        self.strict_types = strict_types;
+        self
+    }
+
+    pub(crate) fn with_limits(mut self, limits: InputLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub(crate) fn with_stack_resources(
+        mut self,
+        stack_resources: Option<Rc<PathAwareValue>>,
+    ) -> Self {
+        self.stack_resources = stack_resources;
+        self
+    }
+
     #[cfg(test)]
     pub fn reset_root(self, new_root: Rc<PathAwareValue>) -> RootScope<'value, 'loc> {
         root_scope_with(
@@ -65,6 +176,28 @@ impl<'value, 'loc: 'value> RootScope<'value, 'loc> {
         )
     }
 
+    /// how long each rule of the rules file took to evaluate, including the rules it depends on
+    pub(crate) fn durations(&self) -> &[(&'value str, Duration)] {
+        &self.durations
+    }
+
+    /// records the time spent in each rule, block, clause and variable while evaluating
+    pub(crate) fn with_profiler(mut self) -> Self {
+        self.profiler = Some(Profiler::default());
+        self
+    }
+
+    /// what was recorded since the profiler was set
+    pub(crate) fn take_profiler(&mut self) -> Option<Profiler> {
+        self.profiler.take()
+    }
+
+    /// reports the events of the evaluation to the observer as they are recorded
+    pub(crate) fn with_observer(mut self, observer: Box<dyn EvaluationObserver + 'value>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
     pub(crate) fn reset_recorder(&mut self) -> RecordTracker<'value> {
         std::mem::replace(
             &mut self.recorder,
@@ -122,9 +255,8 @@ fn retrieve_index(
     elements: &Vec<PathAwareValue>,
     query: &[QueryPart<'_>],
 ) -> QueryResult {
-    let check = if index >= 0 { index } else { -index } as usize;
-    if check < elements.len() {
-        QueryResult::Resolved(Rc::new(elements[check].clone()))
+    if let Some(position) = list_position(index, elements.len()) {
+        QueryResult::Resolved(Rc::new(elements[position].clone()))
     } else {
         QueryResult::UnResolved(
             UnResolved {
@@ -332,7 +464,11 @@ fn query_retrieval<'value, 'loc: 'value>(
     current: Rc<PathAwareValue>,
     resolver: &mut dyn EvalContext<'value, 'loc>,
 ) -> Result<Vec<QueryResult>> {
-    query_retrieval_with_converter(query_index, query, current, resolver, None)
+    let results = query_retrieval_with_converter(query_index, query, current, resolver, None)?;
+    resolver
+        .limits()
+        .check_query_results(SliceDisplay(query), results.len())?;
+    Ok(results)
 }
 
 fn query_retrieval_with_converter<'value, 'loc: 'value>(
@@ -342,6 +478,7 @@ fn query_retrieval_with_converter<'value, 'loc: 'value>(
     resolver: &mut dyn EvalContext<'value, 'loc>,
     converter: Option<&dyn Fn(&str) -> String>,
 ) -> Result<Vec<QueryResult>> {
+    resolver.count_resolution();
     if query_index >= query.len() {
         return Ok(vec![QueryResult::Resolved(Rc::clone(&current))]);
     }
@@ -390,8 +527,10 @@ fn query_retrieval_with_converter<'value, 'loc: 'value>(
             query_retrieval_with_converter(query_index + 1, query, current, resolver, converter)
         }
 
+        // numeric keys index into lists, on a struct they are looked up like any other key,
+        // e.g. Metadata."0"
         QueryPart::Key(key) => match key.parse::<i32>() {
-            Ok(idx) => match &*current {
+            Ok(idx) if !matches!(&*current, PathAwareValue::Map(_)) => match &*current {
                 PathAwareValue::List((_, list)) => map_resolved(
                     &current,
                     retrieve_index(Rc::clone(&current), idx, list, query),
@@ -417,7 +556,7 @@ fn query_retrieval_with_converter<'value, 'loc: 'value>(
                 ),
             },
 
-            Err(_) => {
+            _ => {
                 if let PathAwareValue::Map((path, map)) = &*current {
                     if query[query_index].is_variable() {
                         let var = query[query_index].variable().unwrap();
@@ -426,14 +565,13 @@ fn query_retrieval_with_converter<'value, 'loc: 'value>(
                             match &query[query_index+1] {
                                     QueryPart::AllIndices(_) | QueryPart::Key(_) => keys,
                                     QueryPart::Index(index) => {
-                                        let check = if *index >= 0 { *index } else { -*index } as usize;
-                                        if check < keys.len() {
-                                            vec![keys[check].clone()]
+                                        if let Some(position) = list_position(*index, keys.len()) {
+                                            vec![keys[position].clone()]
                                         } else {
                                             return to_unresolved_result(
                                                 current,
                                                 format!("Index {} on the set of values returned for variable {} on the join, is out of bounds. Length {}, Values = {:?}",
-                                                        check, var, keys.len(), keys),
+                                                        index, var, keys.len(), keys),
                                                 &query[query_index..]
                                             )
                                         }
@@ -607,6 +745,29 @@ fn query_retrieval_with_converter<'value, 'loc: 'value>(
             ),
         },
 
+        QueryPart::Slice(start, end) => match &*current {
+            PathAwareValue::List((_, list)) => accumulate(
+                Rc::clone(&current),
+                query_index,
+                query,
+                &list[slice_range(*start, *end, list.len())],
+                resolver,
+                converter,
+            ),
+
+            _ => to_unresolved_result(
+                Rc::clone(&current),
+                format!(
+                    "Attempting to retrieve the slice {} but type is not an array at path {}, \
+                    type {}",
+                    slice_source(*start, *end),
+                    current.self_path(),
+                    current.type_info()
+                ),
+                &query[query_index..],
+            ),
+        },
+
         QueryPart::AllIndices(name) => {
             match &*current {
                 PathAwareValue::List((_, elements)) => accumulate(
@@ -723,16 +884,17 @@ fn query_retrieval_with_converter<'value, 'loc: 'value>(
 
         QueryPart::Filter(name, conjunctions) => match &*current {
             PathAwareValue::Map((_path, map)) => match &query[query_index - 1] {
-                QueryPart::AllValues(_name) | QueryPart::AllIndices(_name) => {
-                    check_and_delegate(conjunctions, &None)(
-                        query_index + 1,
-                        query,
-                        Rc::clone(&current),
-                        Rc::clone(&current),
-                        resolver,
-                        converter,
-                    )
-                }
+                QueryPart::AllValues(_name)
+                | QueryPart::AllIndices(_name)
+                | QueryPart::Index(_)
+                | QueryPart::Slice(..) => check_and_delegate(conjunctions, &None)(
+                    query_index + 1,
+                    query,
+                    Rc::clone(&current),
+                    Rc::clone(&current),
+                    resolver,
+                    converter,
+                ),
 
                 QueryPart::Key(_) => {
                     if !map.is_empty() {
@@ -921,6 +1083,121 @@ fn query_retrieval_with_converter<'value, 'loc: 'value>(
                 &query[query_index..],
             ),
         },
+
+        QueryPart::Resolve => {
+            let root = resolver.document_root();
+            match referenced_resource(&current, &root) {
+                Some(resource) => query_retrieval_with_converter(
+                    query_index + 1,
+                    query,
+                    Rc::new(resource.clone()),
+                    resolver,
+                    converter,
+                ),
+
+                None => to_unresolved_result(
+                    Rc::clone(&current),
+                    format!(
+                        "Could not resolve the value at path {} to a resource in the Resources \
+                        section, Type = {}",
+                        current.self_path(),
+                        current.type_info()
+                    ),
+                    &query[query_index..],
+                ),
+            }
+        }
+
+        QueryPart::Descendants(key) => {
+            let mut found = vec![];
+            descendants(&current, key, &mut found);
+            if found.is_empty() {
+                return to_unresolved_result(
+                    Rc::clone(&current),
+                    format!(
+                        "Could not find key {} at any depth inside path {}",
+                        key,
+                        current.self_path()
+                    ),
+                    &query[query_index..],
+                );
+            }
+
+            let mut acc = Vec::with_capacity(found.len());
+            for each in found {
+                acc.extend(query_retrieval_with_converter(
+                    query_index + 1,
+                    query,
+                    Rc::new(each.clone()),
+                    resolver,
+                    converter,
+                )?);
+            }
+            Ok(acc)
+        }
+    }
+}
+
+//
+// the values of `key` in the structs at any depth inside `value`, `value` included, in the order
+// they appear. Values found are searched too, a key can be nested in itself
+//
+fn descendants<'a>(value: &'a PathAwareValue, key: &str, found: &mut Vec<&'a PathAwareValue>) {
+    match value {
+        PathAwareValue::Map((_, map)) => {
+            for (each_key, each) in map.values.iter() {
+                if each_key == key {
+                    found.push(each);
+                }
+                descendants(each, key, found);
+            }
+        }
+        PathAwareValue::List((_, elements)) => {
+            for each in elements {
+                descendants(each, key, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+//
+// the resource a value refers to, the value being a `Ref` or `Fn::GetAtt` to it, or
+// its logical id
+//
+fn referenced_resource<'a>(
+    value: &PathAwareValue,
+    root: &'a PathAwareValue,
+) -> Option<&'a PathAwareValue> {
+    let logical_id = match value {
+        PathAwareValue::String((_, logical_id)) => logical_id.as_str(),
+        PathAwareValue::Map((_, map)) if map.values.len() == 1 => {
+            match map.values.iter().next()? {
+                (function, PathAwareValue::String((_, logical_id))) if function == "Ref" => {
+                    logical_id.as_str()
+                }
+                // the short form !GetAtt resource.attribute
+                (function, PathAwareValue::String((_, attribute))) if function == "Fn::GetAtt" => {
+                    attribute.split_once('.')?.0
+                }
+                (function, PathAwareValue::List((_, arguments))) if function == "Fn::GetAtt" => {
+                    match arguments.first()? {
+                        PathAwareValue::String((_, logical_id)) => logical_id.as_str(),
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    match root {
+        PathAwareValue::Map((_, map)) => match map.values.get("Resources")? {
+            PathAwareValue::Map((_, resources)) => resources.values.get(logical_id),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
@@ -960,6 +1237,14 @@ pub(crate) fn root_scope_with<'value, 'loc: 'value>(
     root: Rc<PathAwareValue>,
 ) -> RootScope<'value, 'loc> {
     RootScope {
+        suppressions: Suppressions::from_template(&root),
+        current_rule: None,
+        suppressed: HashMap::new(),
+        this_keys: None,
+        durations: vec![],
+        profiler: None,
+        observer: None,
+        functions: None,
         scope: Scope {
             root,
             literals,
@@ -975,6 +1260,14 @@ pub(crate) fn root_scope_with<'value, 'loc: 'value>(
             final_event: None,
             events: vec![],
         },
+        on_missing: None,
+        strict_types: false,
+        limits: InputLimits::default(),
+        selection: RuleSelection::default(),
+        parameters_file: None,
+        parameter_values: None,
+        vars: None,
+        stack_resources: None,
     }
 }
 
@@ -1063,7 +1356,8 @@ impl<'value> RecordTracer<'value> for RecordTracker<'value> {
 impl<'value, 'loc: 'value> EvalContext<'value, 'loc> for RootScope<'value, 'loc> {
     fn query(&mut self, query: &'value [QueryPart<'loc>]) -> Result<Vec<QueryResult>> {
         let root = self.root();
-        query_retrieval(0, query, root, self)
+        let results = query_retrieval(0, query, root, self)?;
+        Ok(self.without_suppressed(results))
     }
 
     fn find_parameterized_rule(
@@ -1115,6 +1409,89 @@ impl<'value, 'loc: 'value> EvalContext<'value, 'loc> for RootScope<'value, 'loc>
         Ok(status)
     }
 
+    fn on_missing(&self) -> Option<OnMissing> {
+        self.on_missing
+    }
+
+    fn set_on_missing(&mut self, on_missing: Option<OnMissing>) -> Option<OnMissing> {
+        std::mem::replace(&mut self.on_missing, on_missing)
+    }
+
+    fn strict_types(&self) -> bool {
+        self.strict_types
+    }
+
+    fn functions(&self) -> Option<Arc<FunctionRegistry>> {
+        self.functions.clone()
+    }
+
+    fn limits(&self) -> InputLimits {
+        self.limits
+    }
+
+    fn set_current_rule(&mut self, rule_name: Option<&'value str>) -> Option<&'value str> {
+        std::mem::replace(&mut self.current_rule, rule_name)
+    }
+
+    fn set_this_keys(&mut self, this_keys: Option<ThisKeys>) -> Option<ThisKeys> {
+        std::mem::replace(&mut self.this_keys, this_keys)
+    }
+
+    fn without_suppressed(&mut self, results: Vec<QueryResult>) -> Vec<QueryResult> {
+        let rule_name = match self.current_rule {
+            Some(rule_name) if !self.suppressions.is_empty() => rule_name,
+            _ => return results,
+        };
+
+        results
+            .into_iter()
+            .filter(
+                |result| match self.suppressions.covering(rule_name, result) {
+                    Some(suppression) => {
+                        self.suppressed
+                            .entry(rule_name)
+                            .or_default()
+                            .insert(suppression.clone());
+                        false
+                    }
+                    None => true,
+                },
+            )
+            .collect()
+    }
+
+    fn suppressed(&self, rule_name: &str) -> Vec<Suppression> {
+        self.suppressed
+            .get(rule_name)
+            .map_or(vec![], |suppressed| suppressed.iter().cloned().collect())
+    }
+
+    fn is_rule_selected(&self, rule: &Rule<'_>) -> bool {
+        self.selection.is_selected(rule)
+    }
+
+    fn record_duration(&mut self, rule_name: &'value str, duration: Duration) {
+        self.durations.push((rule_name, duration));
+    }
+
+    fn enter_frame(&mut self, frame: Frame<'value, 'loc>) {
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.enter(frame);
+        }
+    }
+
+    fn exit_frame(&mut self) {
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.exit();
+        }
+    }
+
+    fn count_resolution(&mut self) {
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.count_resolution();
+        }
+    }
+
     fn resolve_variable(&mut self, variable_name: &'value str) -> Result<Vec<QueryResult>> {
         if let Some(val) = self.scope.literals.get(variable_name) {
             return Ok(vec![QueryResult::Literal(Rc::clone(val))]);
@@ -1138,6 +1515,41 @@ impl<'value, 'loc: 'value> EvalContext<'value, 'loc> for RootScope<'value, 'loc>
 
         let query = match self.scope.variable_queries.get(variable_name) {
             Some(val) => val,
+            None if variable_name == TEMPLATE_METRICS => {
+                let metrics = Rc::new(template_metrics(&self.scope.root)?);
+                self.scope
+                    .literals
+                    .insert(TEMPLATE_METRICS, Rc::clone(&metrics));
+                return Ok(vec![QueryResult::Literal(metrics)]);
+            }
+            None if variable_name == PARAMETERS_FILE => {
+                return Ok(self
+                    .parameters_file
+                    .iter()
+                    .map(|value| QueryResult::Literal(Rc::clone(value)))
+                    .collect());
+            }
+            None if variable_name == PARAMETER_VALUES => {
+                return Ok(self
+                    .parameter_values
+                    .iter()
+                    .map(|value| QueryResult::Literal(Rc::clone(value)))
+                    .collect());
+            }
+            None if variable_name == VARS => {
+                return Ok(self
+                    .vars
+                    .iter()
+                    .map(|value| QueryResult::Literal(Rc::clone(value)))
+                    .collect());
+            }
+            None if variable_name == STACK_RESOURCES => {
+                return Ok(self
+                    .stack_resources
+                    .iter()
+                    .map(|value| QueryResult::Literal(Rc::clone(value)))
+                    .collect());
+            }
             None => {
                 return Err(Error::MissingValue(format!(
                     "Could not resolve variable by name {} across scopes",
@@ -1148,7 +1560,10 @@ impl<'value, 'loc: 'value> EvalContext<'value, 'loc> for RootScope<'value, 'loc>
 
         let match_all = query.match_all;
 
-        let result = query_retrieval(0, &query.query, self.root(), self)?;
+        self.enter_frame(Frame::Variable(variable_name));
+        let result = query_retrieval(0, &query.query, self.root(), self);
+        self.exit_frame();
+        let result = result?;
         let result = if !match_all {
             result
                 .into_iter()
@@ -1179,11 +1594,18 @@ impl<'value, 'loc: 'value> EvalContext<'value, 'loc> for RootScope<'value, 'loc>
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
 #[serde(rename_all = "snake_case")]
-pub(crate) enum FunctionName {
+pub enum FunctionName {
     Count,
+    Difference,
+    Distinct,
+    Env,
+    Intersection,
     Join,
     JsonParse,
+    Keys,
+    MatchesCi,
     Now,
+    ParseArn,
     ParseBoolean,
     ParseChar,
     ParseEpoch,
@@ -1191,22 +1613,47 @@ pub(crate) enum FunctionName {
     ParseInt,
     ParseString,
     RegexReplace,
+    RequiredTags,
+    Sorted,
+    SubsetOf,
     Substring,
     ToLower,
     ToUpper,
+    Union,
     UrlDecode,
+    Values,
+    /// a string assigned with `let` writing the values of the variables in it, e.g.
+    /// `"arn:aws:s3:::${%bucket_prefix}"`, never called by name
+    Interpolate,
+    /// a function registered by the application embedding Guard
+    Custom(String),
 }
 
 impl FunctionName {
     pub fn get_expected_number_of_args(&self) -> usize {
         match self {
-            FunctionName::Join => 2,
+            // custom functions are looked up when the rules are evaluated, their arguments are
+            // checked when they are called
+            FunctionName::Custom(_) => 0,
+            FunctionName::Join
+            | FunctionName::Env
+            | FunctionName::Intersection
+            | FunctionName::Union
+            | FunctionName::Difference
+            | FunctionName::SubsetOf
+            | FunctionName::RequiredTags
+            | FunctionName::MatchesCi => 2,
             FunctionName::Substring | FunctionName::RegexReplace => 3,
             FunctionName::Count
+            | FunctionName::Distinct
+            | FunctionName::Sorted
+            | FunctionName::Keys
+            | FunctionName::Values
             | FunctionName::JsonParse
             | FunctionName::ToUpper
             | FunctionName::ToLower
             | FunctionName::UrlDecode
+            | FunctionName::ParseArn
             | FunctionName::ParseString
             | FunctionName::ParseBoolean
             | FunctionName::ParseFloat
@@ -1214,6 +1661,8 @@ impl FunctionName {
             | FunctionName::ParseEpoch
             | FunctionName::ParseChar => 1,
             FunctionName::Now => 0,
+            // interpolated strings have as many parts as they are written with
+            FunctionName::Interpolate => 0,
         }
     }
 }
@@ -1222,9 +1671,16 @@ impl std::fmt::Display for FunctionName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let name = match self {
             FunctionName::Count => "count",
+            FunctionName::Difference => "difference",
+            FunctionName::Distinct => "distinct",
+            FunctionName::Env => "env",
+            FunctionName::Intersection => "intersection",
             FunctionName::Join => "join",
             FunctionName::JsonParse => "json_parse",
+            FunctionName::Keys => "keys",
+            FunctionName::MatchesCi => "matches_ci",
             FunctionName::Now => "now",
+            FunctionName::ParseArn => "parse_arn",
             FunctionName::ParseBoolean => "parse_boolean",
             FunctionName::ParseChar => "parse_char",
             FunctionName::ParseEpoch => "parse_epoch",
@@ -1232,45 +1688,83 @@ impl std::fmt::Display for FunctionName {
             FunctionName::ParseInt => "parse_int",
             FunctionName::ParseString => "parse_string",
             FunctionName::RegexReplace => "regex_replace",
+            FunctionName::RequiredTags => "required_tags",
+            FunctionName::Sorted => "sorted",
+            FunctionName::SubsetOf => "subset_of",
             FunctionName::Substring => "substring",
             FunctionName::ToLower => "to_lower",
             FunctionName::ToUpper => "to_upper",
+            FunctionName::Union => "union",
             FunctionName::UrlDecode => "url_decode",
+            FunctionName::Values => "values",
+            FunctionName::Interpolate => "interpolate",
+            FunctionName::Custom(name) => name,
         };
         write!(f, "{}", name)
     }
 }
 
+impl FunctionName {
+    pub(crate) fn built_in(name: &str) -> Option<FunctionName> {
+        match name {
+            "count" => Some(FunctionName::Count),
+            "difference" => Some(FunctionName::Difference),
+            "distinct" => Some(FunctionName::Distinct),
+            "env" => Some(FunctionName::Env),
+            "intersection" => Some(FunctionName::Intersection),
+            "join" => Some(FunctionName::Join),
+            "json_parse" => Some(FunctionName::JsonParse),
+            "keys" => Some(FunctionName::Keys),
+            "matches_ci" => Some(FunctionName::MatchesCi),
+            "now" => Some(FunctionName::Now),
+            "parse_arn" => Some(FunctionName::ParseArn),
+            "parse_boolean" => Some(FunctionName::ParseBoolean),
+            "parse_char" => Some(FunctionName::ParseChar),
+            "parse_epoch" => Some(FunctionName::ParseEpoch),
+            "parse_float" => Some(FunctionName::ParseFloat),
+            "parse_int" => Some(FunctionName::ParseInt),
+            "parse_string" => Some(FunctionName::ParseString),
+            "regex_replace" => Some(FunctionName::RegexReplace),
+            "required_tags" => Some(FunctionName::RequiredTags),
+            "sorted" => Some(FunctionName::Sorted),
+            "subset_of" => Some(FunctionName::SubsetOf),
+            "substring" => Some(FunctionName::Substring),
+            "to_lower" => Some(FunctionName::ToLower),
+            "to_upper" => Some(FunctionName::ToUpper),
+            "union" => Some(FunctionName::Union),
+            "url_decode" => Some(FunctionName::UrlDecode),
+            "values" => Some(FunctionName::Values),
+            _ => None,
+        }
+    }
+}
+
 impl TryFrom<&str> for FunctionName {
     type Error = Error;
 
     fn try_from(name: &str) -> std::result::Result<Self, Self::Error> {
-        match name {
-            "count" => Ok(FunctionName::Count),
-            "join" => Ok(FunctionName::Join),
-            "json_parse" => Ok(FunctionName::JsonParse),
-            "now" => Ok(FunctionName::Now),
-            "parse_boolean" => Ok(FunctionName::ParseBoolean),
-            "parse_char" => Ok(FunctionName::ParseChar),
-            "parse_epoch" => Ok(FunctionName::ParseEpoch),
-            "parse_float" => Ok(FunctionName::ParseFloat),
-            "parse_int" => Ok(FunctionName::ParseInt),
-            "parse_string" => Ok(FunctionName::ParseString),
-            "regex_replace" => Ok(FunctionName::RegexReplace),
-            "substring" => Ok(FunctionName::Substring),
-            "to_lower" => Ok(FunctionName::ToLower),
-            "to_upper" => Ok(FunctionName::ToUpper),
-            "url_decode" => Ok(FunctionName::UrlDecode),
-            _ => Err(Error::ParseError(format!(
-                "No function with the name '{name}' exists.",
-            ))),
+        match FunctionName::built_in(name) {
+            Some(function) => Ok(function),
+            // any other name is looked up in the functions the rules are evaluated with
+            None => Ok(FunctionName::Custom(name.to_string())),
         }
     }
 }
 
 struct CountFunction;
+struct IntersectionFunction;
+struct UnionFunction;
+struct DifferenceFunction;
+struct SubsetOfFunction;
+struct DistinctFunction;
+struct SortedFunction;
+struct KeysFunction;
+struct ValuesFunction;
+struct RequiredTagsFunction;
+struct EnvFunction;
 struct JsonParseFunction;
 struct RegexReplaceFunction;
+struct MatchesCiFunction;
 struct SubstringFunction;
 struct ToUpperFunction;
 struct ToLowerFunction;
@@ -1281,8 +1775,10 @@ struct ParseFloatFunction;
 struct ParseStringFunction;
 struct ParseBooleanFunction;
 struct ParseCharFunction;
+struct ParseArnFunction;
 struct ParseEpochFunction;
 struct NowFunction;
+struct InterpolateFunction;
 
 trait Callable {
     fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>>;
@@ -1292,8 +1788,19 @@ impl Callable for FunctionName {
     fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
         match self {
             FunctionName::Count => CountFunction.call(args),
+            FunctionName::Intersection => IntersectionFunction.call(args),
+            FunctionName::Union => UnionFunction.call(args),
+            FunctionName::Difference => DifferenceFunction.call(args),
+            FunctionName::SubsetOf => SubsetOfFunction.call(args),
+            FunctionName::Distinct => DistinctFunction.call(args),
+            FunctionName::Sorted => SortedFunction.call(args),
+            FunctionName::Keys => KeysFunction.call(args),
+            FunctionName::Values => ValuesFunction.call(args),
+            FunctionName::RequiredTags => RequiredTagsFunction.call(args),
+            FunctionName::Env => EnvFunction.call(args),
             FunctionName::JsonParse => JsonParseFunction.call(args),
             FunctionName::RegexReplace => RegexReplaceFunction.call(args),
+            FunctionName::MatchesCi => MatchesCiFunction.call(args),
             FunctionName::Substring => SubstringFunction.call(args),
             FunctionName::ToUpper => ToUpperFunction.call(args),
             FunctionName::ToLower => ToLowerFunction.call(args),
@@ -1304,8 +1811,12 @@ impl Callable for FunctionName {
             FunctionName::ParseString => ParseStringFunction.call(args),
             FunctionName::ParseBoolean => ParseBooleanFunction.call(args),
             FunctionName::ParseChar => ParseCharFunction.call(args),
+            FunctionName::ParseArn => ParseArnFunction.call(args),
             FunctionName::ParseEpoch => ParseEpochFunction.call(args),
             FunctionName::Now => NowFunction.call(args),
+            FunctionName::Interpolate => InterpolateFunction.call(args),
+            // resolve_function calls custom functions with the registry of the evaluation
+            FunctionName::Custom(name) => call_custom(None, name, args),
         }
     }
 }
@@ -1322,12 +1833,72 @@ impl Callable for NowFunction {
     }
 }
 
+impl Callable for InterpolateFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        interpolate(args)
+    }
+}
+
 impl Callable for CountFunction {
     fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
         Ok(vec![Some(count(&args[0]))])
     }
 }
 
+impl Callable for IntersectionFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(intersection(&args[0], &args[1]))
+    }
+}
+
+impl Callable for UnionFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(union(&args[0], &args[1]))
+    }
+}
+
+impl Callable for DifferenceFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(difference(&args[0], &args[1]))
+    }
+}
+
+impl Callable for SubsetOfFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(vec![Some(subset_of(&args[0], &args[1]))])
+    }
+}
+
+impl Callable for DistinctFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(distinct(&args[0]))
+    }
+}
+
+impl Callable for SortedFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(vec![Some(sorted(&args[0])?)])
+    }
+}
+
+impl Callable for KeysFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(keys(&args[0]))
+    }
+}
+
+impl Callable for ValuesFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(values(&args[0]))
+    }
+}
+
+impl Callable for RequiredTagsFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        Ok(required_tags(&args[0], &args[1]))
+    }
+}
+
 impl Callable for JsonParseFunction {
     fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
         json_parse(&args[0])
@@ -1366,6 +1937,25 @@ impl Callable for RegexReplaceFunction {
     }
 }
 
+impl Callable for MatchesCiFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        let pattern = match args[1].first() {
+            Some(QueryResult::Resolved(r) | QueryResult::Literal(r)) => match &**r {
+                PathAwareValue::String((_, s)) | PathAwareValue::Regex((_, s)) => Some(s),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match pattern {
+            Some(pattern) => matches_ci(&args[0], pattern),
+            None => Err(Error::ParseError(String::from(
+                "matches_ci function requires the second argument to be a string or a regex",
+            ))),
+        }
+    }
+}
+
 impl Callable for SubstringFunction {
     fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
         let substring_err_msg = |index| {
@@ -1470,19 +2060,61 @@ impl Callable for ParseCharFunction {
     }
 }
 
+impl Callable for EnvFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        match env_variable_name(&args[0]) {
+            Some(name) => env(name, &args[1]),
+            None => Err(Error::ParseError(String::from(
+                "env function requires the first argument to be the name of an environment variable as a string",
+            ))),
+        }
+    }
+}
+
+fn env_variable_name(arg: &[QueryResult]) -> Option<&str> {
+    match arg.first()? {
+        QueryResult::Literal(r) | QueryResult::Resolved(r) => match &**r {
+            PathAwareValue::String((_, name)) => Some(name),
+            _ => None,
+        },
+        QueryResult::UnResolved(_) => None,
+    }
+}
+
+impl Callable for ParseArnFunction {
+    fn call(&self, args: &[Vec<QueryResult>]) -> Result<Vec<Option<PathAwareValue>>> {
+        parse_arn(&args[0])
+    }
+}
+
 impl<'value, 'loc: 'value> RecordTracer<'value> for RootScope<'value, 'loc> {
     fn start_record(&mut self, context: &str) -> Result<()> {
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_start(context);
+        }
         self.recorder.start_record(context)
     }
 
     fn end_record(&mut self, context: &str, record: RecordType<'value>) -> Result<()> {
+        let record = match &self.this_keys {
+            Some(this_keys) => this_keys.render_record(record),
+            None => record,
+        };
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_end(&EvaluationEvent::from_record(context, &record));
+        }
         self.recorder.end_record(context, record)
     }
 }
 
 impl<'value, 'loc: 'value, 'eval> EvalContext<'value, 'loc> for ValueScope<'value, 'eval, 'loc> {
     fn query(&mut self, query: &'value [QueryPart<'loc>]) -> Result<Vec<QueryResult>> {
-        query_retrieval(0, query, self.root(), self.parent)
+        let results = match query.first().and_then(|part| part.variable()) {
+            // `%THIS` is resolved by this scope, the element being evaluated
+            Some(THIS) => query_retrieval(0, query, self.root(), self)?,
+            _ => query_retrieval(0, query, self.root(), self.parent)?,
+        };
+        Ok(self.parent.without_suppressed(results))
     }
 
     fn find_parameterized_rule(
@@ -1496,12 +2128,73 @@ impl<'value, 'loc: 'value, 'eval> EvalContext<'value, 'loc> for ValueScope<'valu
         Rc::clone(&self.root)
     }
 
+    fn document_root(&mut self) -> Rc<PathAwareValue> {
+        self.parent.document_root()
+    }
+
     fn rule_status(&mut self, rule_name: &'value str) -> Result<Status> {
         self.parent.rule_status(rule_name)
     }
 
+    fn on_missing(&self) -> Option<OnMissing> {
+        self.parent.on_missing()
+    }
+
+    fn set_on_missing(&mut self, on_missing: Option<OnMissing>) -> Option<OnMissing> {
+        self.parent.set_on_missing(on_missing)
+    }
+
+    fn strict_types(&self) -> bool {
+        self.parent.strict_types()
+    }
+
+    fn functions(&self) -> Option<Arc<FunctionRegistry>> {
+        self.parent.functions()
+    }
+
+    fn limits(&self) -> InputLimits {
+        self.parent.limits()
+    }
+
+    fn set_current_rule(&mut self, rule_name: Option<&'value str>) -> Option<&'value str> {
+        self.parent.set_current_rule(rule_name)
+    }
+
+    fn set_this_keys(&mut self, this_keys: Option<ThisKeys>) -> Option<ThisKeys> {
+        self.parent.set_this_keys(this_keys)
+    }
+
+    fn enter_frame(&mut self, frame: Frame<'value, 'loc>) {
+        self.parent.enter_frame(frame)
+    }
+
+    fn exit_frame(&mut self) {
+        self.parent.exit_frame()
+    }
+
+    fn count_resolution(&mut self) {
+        self.parent.count_resolution()
+    }
+
+    fn without_suppressed(&mut self, results: Vec<QueryResult>) -> Vec<QueryResult> {
+        self.parent.without_suppressed(results)
+    }
+
+    fn suppressed(&self, rule_name: &str) -> Vec<Suppression> {
+        self.parent.suppressed(rule_name)
+    }
+
     fn resolve_variable(&mut self, variable_name: &'value str) -> Result<Vec<QueryResult>> {
-        self.parent.resolve_variable(variable_name)
+        match self.parent.resolve_variable(variable_name) {
+            Err(Error::MissingValue(_)) if variable_name == THIS => {
+                let document = self.parent.document_root();
+                Ok(this_value(&self.root, &document)
+                    .map(|this| QueryResult::Literal(Rc::new(this)))
+                    .into_iter()
+                    .collect())
+            }
+            result => result,
+        }
     }
 
     fn add_variable_capture_key(
@@ -1525,7 +2218,8 @@ impl<'value, 'loc: 'value, 'eval> RecordTracer<'value> for ValueScope<'value, 'e
 
 impl<'value, 'loc: 'value, 'eval> EvalContext<'value, 'loc> for BlockScope<'value, 'loc, 'eval> {
     fn query(&mut self, query: &'value [QueryPart<'loc>]) -> Result<Vec<QueryResult>> {
-        query_retrieval(0, query, self.root(), self)
+        let results = query_retrieval(0, query, self.root(), self)?;
+        Ok(self.without_suppressed(results))
     }
 
     fn find_parameterized_rule(
@@ -1539,10 +2233,62 @@ impl<'value, 'loc: 'value, 'eval> EvalContext<'value, 'loc> for BlockScope<'valu
         Rc::clone(&self.scope.root)
     }
 
+    fn document_root(&mut self) -> Rc<PathAwareValue> {
+        self.parent.document_root()
+    }
+
     fn rule_status(&mut self, rule_name: &'value str) -> Result<Status> {
         self.parent.rule_status(rule_name)
     }
 
+    fn on_missing(&self) -> Option<OnMissing> {
+        self.parent.on_missing()
+    }
+
+    fn set_on_missing(&mut self, on_missing: Option<OnMissing>) -> Option<OnMissing> {
+        self.parent.set_on_missing(on_missing)
+    }
+
+    fn strict_types(&self) -> bool {
+        self.parent.strict_types()
+    }
+
+    fn functions(&self) -> Option<Arc<FunctionRegistry>> {
+        self.parent.functions()
+    }
+
+    fn limits(&self) -> InputLimits {
+        self.parent.limits()
+    }
+
+    fn set_current_rule(&mut self, rule_name: Option<&'value str>) -> Option<&'value str> {
+        self.parent.set_current_rule(rule_name)
+    }
+
+    fn set_this_keys(&mut self, this_keys: Option<ThisKeys>) -> Option<ThisKeys> {
+        self.parent.set_this_keys(this_keys)
+    }
+
+    fn enter_frame(&mut self, frame: Frame<'value, 'loc>) {
+        self.parent.enter_frame(frame)
+    }
+
+    fn exit_frame(&mut self) {
+        self.parent.exit_frame()
+    }
+
+    fn count_resolution(&mut self) {
+        self.parent.count_resolution()
+    }
+
+    fn without_suppressed(&mut self, results: Vec<QueryResult>) -> Vec<QueryResult> {
+        self.parent.without_suppressed(results)
+    }
+
+    fn suppressed(&self, rule_name: &str) -> Vec<Suppression> {
+        self.parent.suppressed(rule_name)
+    }
+
     fn resolve_variable(&mut self, variable_name: &'value str) -> Result<Vec<QueryResult>> {
         if let Some(val) = self.scope.literals.get(variable_name) {
             return Ok(vec![QueryResult::Literal(Rc::clone(val))]);
@@ -1571,7 +2317,10 @@ impl<'value, 'loc: 'value, 'eval> EvalContext<'value, 'loc> for BlockScope<'valu
 
         let match_all = query.match_all;
 
-        let result = query_retrieval(0, &query.query, self.root(), self)?;
+        self.enter_frame(Frame::Variable(variable_name));
+        let result = query_retrieval(0, &query.query, self.root(), self);
+        self.exit_frame();
+        let result = result?;
         let result = if !match_all {
             result
                 .into_iter()
@@ -1610,11 +2359,13 @@ impl<'value, 'loc: 'value, 'eval> RecordTracer<'value> for BlockScope<'value, 'l
 pub(crate) struct Messages {
     pub(crate) custom_message: Option<String>,
     pub(crate) error_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) message_id: Option<MessageId>,
     #[serde(skip_serializing)]
     pub(crate) location: Option<Location>,
 }
 
-pub(crate) type Metadata = HashMap<String, String>;
+pub(crate) type Metadata = BTreeMap<String, String>;
 
 #[derive(Clone, Debug, Serialize, Default)]
 pub(crate) struct FileReport<'value> {
@@ -1625,6 +2376,23 @@ pub(crate) struct FileReport<'value> {
     pub(crate) not_compliant: Vec<ClauseReport<'value>>,
     pub(crate) not_applicable: BTreeSet<String>,
     pub(crate) compliant: BTreeSet<String>,
+    /// failures whose violations are all in the baseline validate was given, these do not fail
+    /// the file
+    #[serde(
+        with = "serde_yaml::with::singleton_map_recursive",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub(crate) baselined: Vec<ClauseReport<'value>>,
+    /// the rules resources of the data file opted out of through their metadata
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) suppressed: Vec<SuppressedReport<'value>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct SuppressedReport<'value> {
+    pub(crate) rule: &'value str,
+    #[serde(flatten)]
+    pub(crate) suppression: Suppression,
 }
 
 impl<'value> FileReport<'value> {
@@ -1637,6 +2405,8 @@ impl<'value> FileReport<'value> {
         self.not_compliant.extend(report.not_compliant);
         self.compliant.extend(report.compliant);
         self.not_applicable.extend(report.not_applicable);
+        self.baselined.extend(report.baselined);
+        self.suppressed.extend(report.suppressed);
     }
 }
 
@@ -1646,6 +2416,8 @@ pub(crate) struct RuleReport<'value> {
     pub(crate) metadata: Metadata,
     pub(crate) messages: Messages,
     pub(crate) checks: Vec<ClauseReport<'value>>,
+    #[serde(skip_serializing_if = "Severity::is_error")]
+    pub(crate) severity: Severity,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -1681,6 +2453,8 @@ impl ValueComparisons for UnaryCheck {
 pub(crate) struct UnaryReport {
     pub(crate) check: UnaryCheck,
     pub(crate) context: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) query: Option<String>,
     pub(crate) messages: Messages,
 }
 
@@ -1725,6 +2499,8 @@ impl ValueComparisons for BinaryCheck {
 #[derive(Clone, Debug, Serialize)]
 pub(crate) struct BinaryReport {
     pub(crate) context: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) query: Option<String>,
     pub(crate) messages: Messages,
     pub(crate) check: BinaryCheck,
 }
@@ -1806,6 +2582,24 @@ impl<'value> ClauseReport<'value> {
         }
     }
 
+    /// the severity of a failed rule, every other failure is an error
+    pub(crate) fn severity(&self) -> Severity {
+        match self {
+            Self::Rule(RuleReport { severity, .. }) => *severity,
+            _ => Severity::Error,
+        }
+    }
+
+    /// attaches the query of the guard clause that produced this failure
+    fn with_query(mut self, query: &str) -> Self {
+        if let Self::Clause(GuardClauseReport::Unary(UnaryReport { query: slot, .. }))
+        | Self::Clause(GuardClauseReport::Binary(BinaryReport { query: slot, .. })) = &mut self
+        {
+            slot.get_or_insert_with(|| query.to_string());
+        }
+        self
+    }
+
     pub fn get_message(&self) -> Vec<Messages> {
         match self {
             ClauseReport::Rule(rule) => rule.checks.iter().fold(vec![], |mut messages, report| {
@@ -1923,6 +2717,13 @@ pub(crate) fn cmp_str(cmp: (CmpOperator, bool)) -> &'static str {
                     "EQUAL"
                 }
             }
+            CmpOperator::UnorderedEq => {
+                if not {
+                    "NOT UNORDERED EQUAL"
+                } else {
+                    "UNORDERED EQUAL"
+                }
+            }
             CmpOperator::Le => {
                 if not {
                     "NOT LESS THAN EQUAL"
@@ -1958,6 +2759,13 @@ pub(crate) fn cmp_str(cmp: (CmpOperator, bool)) -> &'static str {
                     "IN"
                 }
             }
+            CmpOperator::Like => {
+                if not {
+                    "NOT LIKE"
+                } else {
+                    "LIKE"
+                }
+            }
             _ => unreachable!(),
         }
     }
@@ -1965,6 +2773,7 @@ pub(crate) fn cmp_str(cmp: (CmpOperator, bool)) -> &'static str {
 
 fn report_all_failed_clauses_for_rules<'value>(
     checks: &[EventRecord<'value>],
+    catalog: &MessageCatalog,
 ) -> Vec<ClauseReport<'value>> {
     let mut clauses = Vec::with_capacity(checks.len());
     for current in checks {
@@ -1973,14 +2782,16 @@ fn report_all_failed_clauses_for_rules<'value>(
                 name,
                 status: Status::FAIL,
                 message,
+                severity,
+                ..
             })) => {
                 clauses.push(ClauseReport::Rule(RuleReport {
                     name,
-                    checks: report_all_failed_clauses_for_rules(&current.children),
+                    severity: *severity,
+                    checks: report_all_failed_clauses_for_rules(&current.children, catalog),
                     messages: Messages {
                         custom_message: message.clone(),
-                        error_message: None,
-                        location: None,
+                        ..Default::default()
                     },
                     ..Default::default()
                 }));
@@ -1994,16 +2805,18 @@ fn report_all_failed_clauses_for_rules<'value>(
                     clauses.push(ClauseReport::Block(GuardBlockReport {
                         context: current.context.clone(),
                         messages: Messages {
-                            error_message: Some(String::from(
-                                "query for block clause did not retrieve any value",
-                            )),
+                            error_message: Some(catalog.format(MessageId::BlockQueryEmpty, &[])),
+                            message_id: Some(MessageId::BlockQueryEmpty),
                             custom_message: None,
                             location: None,
                         },
                         unresolved: None,
                     }));
                 } else {
-                    clauses.extend(report_all_failed_clauses_for_rules(&current.children));
+                    clauses.extend(report_all_failed_clauses_for_rules(
+                        &current.children,
+                        catalog,
+                    ));
                 }
             }
 
@@ -2012,15 +2825,27 @@ fn report_all_failed_clauses_for_rules<'value>(
                 ..
             })) => {
                 clauses.push(ClauseReport::Disjunctions(DisjunctionsReport {
-                    checks: report_all_failed_clauses_for_rules(&current.children),
+                    checks: report_all_failed_clauses_for_rules(&current.children, catalog),
                 }));
             }
 
-            Some(RecordType::GuardClauseBlockCheck(BlockCheck {
-                status: Status::FAIL,
-                ..
-            }))
-            | Some(RecordType::TypeBlock(Status::FAIL))
+            Some(RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                block:
+                    BlockCheck {
+                        status: Status::FAIL,
+                        ..
+                    },
+                clause,
+            })) => {
+                let query = clause_source(clause);
+                clauses.extend(
+                    report_all_failed_clauses_for_rules(&current.children, catalog)
+                        .into_iter()
+                        .map(|report| report.with_query(&query)),
+                );
+            }
+
+            Some(RecordType::TypeBlock(Status::FAIL))
             | Some(RecordType::TypeCheck(TypeBlockCheck {
                 block:
                     BlockCheck {
@@ -2033,7 +2858,10 @@ fn report_all_failed_clauses_for_rules<'value>(
                 status: Status::FAIL,
                 ..
             })) => {
-                clauses.extend(report_all_failed_clauses_for_rules(&current.children));
+                clauses.extend(report_all_failed_clauses_for_rules(
+                    &current.children,
+                    catalog,
+                ));
             }
 
             Some(RecordType::ClauseValueCheck(clause)) => match clause {
@@ -2042,17 +2870,19 @@ fn report_all_failed_clauses_for_rules<'value>(
                         .as_ref()
                         .map_or("".to_string(), |s| s.replace('\n', ";"));
 
-                    let error_message = format!(
-                        "Check was not compliant as variable in context [{}] was not empty",
-                        current.context
+                    let error_message = catalog.format(
+                        MessageId::VariableNotEmpty,
+                        &[("context", &current.context)],
                     );
                     clauses.push(ClauseReport::Clause(GuardClauseReport::Unary(
                         UnaryReport {
                             context: current.context.clone(),
+                            query: None,
                             check: UnaryCheck::UnResolvedContext(current.context.to_string()),
                             messages: Messages {
                                 custom_message: Some(custom_message),
                                 error_message: Some(error_message),
+                                message_id: Some(MessageId::VariableNotEmpty),
                                 location: None,
                             },
                         },
@@ -2062,20 +2892,21 @@ fn report_all_failed_clauses_for_rules<'value>(
                 ClauseCheck::Success => {}
 
                 ClauseCheck::DependentRule(missing) => {
-                    let message = missing.custom_message.as_ref().map_or("", String::as_str);
-                    let error_message = format!(
-                            "Check was not compliant as dependent rule [{rule}] did not PASS. Context [{cxt}]",
-                            rule=missing.rule,
-                            cxt=current.context,
-                        );
+                    let custom_message = missing.custom_message.as_ref().map_or("", String::as_str);
+                    let error_message = catalog.format(
+                        MessageId::DependentRuleFailed,
+                        &[("rule", &missing.rule), ("context", &current.context)],
+                    );
                     clauses.push(ClauseReport::Clause(GuardClauseReport::Unary(
                         UnaryReport {
                             messages: Messages {
-                                custom_message: Some(message.to_string()),
+                                custom_message: Some(custom_message.to_string()),
                                 error_message: Some(error_message),
+                                message_id: Some(MessageId::DependentRuleFailed),
                                 location: None,
                             },
                             context: current.context.clone(),
+                            query: None,
                             check: UnaryCheck::UnResolvedContext(missing.rule.to_string()),
                         },
                     )));
@@ -2088,17 +2919,17 @@ fn report_all_failed_clauses_for_rules<'value>(
                         }
                         _ => unreachable!(),
                     };
-                    let message = missing.custom_message.as_ref().map_or("", String::as_str);
-                    let error_message = format!(
-                            "Check was not compliant as property [{}] is missing. Value traversed to [{}]",
-                            property,
-                            far
-                        );
+                    let custom_message = missing.custom_message.as_ref().map_or("", String::as_str);
+                    let error_message = catalog.format(
+                        MessageId::BlockPropertyMissing,
+                        &[("property", &property), ("traversed_to", &far)],
+                    );
                     clauses.push(ClauseReport::Block(GuardBlockReport {
                         context: current.context.clone(),
                         messages: Messages {
-                            custom_message: Some(message.to_string()),
+                            custom_message: Some(custom_message.to_string()),
                             error_message: Some(error_message),
+                            message_id: Some(MessageId::BlockPropertyMissing),
                             location: None,
                         },
                         unresolved: Some(ur.clone()),
@@ -2189,52 +3020,58 @@ fn report_all_failed_clauses_for_rules<'value>(
                     let error_message = message
                         .as_ref()
                         .map_or("".to_string(), |s| format!("Error = [{}]", s));
+                    let operator = cmp_str((*cmp, *not));
 
-                    let (message, check) = match from {
-                            QueryResult::Literal(_) => unreachable!(),
-                            QueryResult::Resolved(res) => {
-                                (
-                                    format!(
-                                        "Check was not compliant as property [{prop}] {cmp_msg}.{err}",
-                                        prop=res.self_path(),
-                                        cmp_msg=cmp_msg,
-                                        err=error_message
-                                    ),
-                                    UnaryCheck::Resolved(UnaryComparison {
-                                        comparison: (*cmp, *not),
-                                        value: res.clone(),
-                                    })
-                                )
-
-                            },
+                    let (message_id, message, check) = match from {
+                        QueryResult::Literal(_) => unreachable!(),
+                        QueryResult::Resolved(res) => (
+                            MessageId::UnaryCheckFailed,
+                            catalog.format(
+                                MessageId::UnaryCheckFailed,
+                                &[
+                                    ("property", &res.self_path()),
+                                    ("check", &cmp_msg),
+                                    ("operator", &operator),
+                                    ("error", &error_message),
+                                ],
+                            ),
+                            UnaryCheck::Resolved(UnaryComparison {
+                                comparison: (*cmp, *not),
+                                value: res.clone(),
+                            }),
+                        ),
 
-                            QueryResult::UnResolved(unres) => {
-                                (
-                                    format!(
-                                        "Check was not compliant as property [{remain}] is missing. Value traversed to [{tr}].{err}",
-                                        remain=unres.remaining_query,
-                                        tr=unres.traversed_to,
-                                        err=error_message
-                                    ),
-                                    UnaryCheck::UnResolved(ValueUnResolved{
-                                        value: unres.clone(),
-                                        comparison: (*cmp, *not),
-                                    })
-                                )
-                            }
-                        };
+                        QueryResult::UnResolved(unres) => (
+                            MessageId::PropertyMissing,
+                            catalog.format(
+                                MessageId::PropertyMissing,
+                                &[
+                                    ("property", &unres.remaining_query),
+                                    ("traversed_to", &unres.traversed_to),
+                                    ("operator", &operator),
+                                    ("error", &error_message),
+                                ],
+                            ),
+                            UnaryCheck::UnResolved(ValueUnResolved {
+                                value: unres.clone(),
+                                comparison: (*cmp, *not),
+                            }),
+                        ),
+                    };
 
                     clauses.push(ClauseReport::Clause(GuardClauseReport::Unary(
                         UnaryReport {
                             messages: Messages {
                                 custom_message: Some(custom_message),
                                 error_message: Some(message),
+                                message_id: Some(message_id),
                                 location: Some(
                                     from.unresolved_traversed_to()
                                         .map_or(Location::default(), |val| val.self_path().1),
                                 ),
                             },
                             context: current.context.clone(),
+                            query: None,
                             check,
                         },
                     )));
@@ -2255,22 +3092,28 @@ fn report_all_failed_clauses_for_rules<'value>(
                     let error_message = message
                         .as_ref()
                         .map_or("".to_string(), |s| format!(" Error = [{}]", s));
+                    let operator = cmp_str((*cmp, *not));
 
                     match from {
                         QueryResult::Literal(_) => unreachable!(),
                         QueryResult::UnResolved(to_unres) => {
-                            let message = format!(
-                                    "Check was not compliant as property [{remain}] to compare from is missing. Value traversed to [{to}].{err}",
-                                    remain=to_unres.remaining_query,
-                                    to=to_unres.traversed_to,
-                                    err=error_message
-                                );
+                            let message = catalog.format(
+                                MessageId::CompareFromMissing,
+                                &[
+                                    ("property", &to_unres.remaining_query),
+                                    ("traversed_to", &to_unres.traversed_to),
+                                    ("operator", &operator),
+                                    ("error", &error_message),
+                                ],
+                            );
                             clauses.push(ClauseReport::Clause(GuardClauseReport::Binary(
                                 BinaryReport {
                                     context: current.context.to_string(),
+                                    query: None,
                                     messages: Messages {
                                         custom_message: Some(custom_message),
                                         error_message: Some(message),
+                                        message_id: Some(MessageId::CompareFromMissing),
                                         location: Some(to_unres.traversed_to.self_path().1),
                                     },
                                     check: BinaryCheck::UnResolved(ValueUnResolved {
@@ -2286,21 +3129,75 @@ fn report_all_failed_clauses_for_rules<'value>(
                                 match to {
                                     QueryResult::Literal(_) => unreachable!(),
                                     QueryResult::Resolved(to_res) => {
-                                        let message = format!(
-                                                "Check was not compliant as property value [{from}] {op_msg} value [{to}].{err}",
-                                                from=res,
-                                                to=to_res,
-                                                op_msg=match cmp {
-                                                    CmpOperator::Eq => if *not { "equal to" } else { "not equal to" },
-                                                    CmpOperator::Le => if *not { "less than equal to" } else { "not less than equal to" },
-                                                    CmpOperator::Lt => if *not { "less than" } else { "not less than" },
-                                                    CmpOperator::Ge => if *not { "greater than equal to" } else { "not greater than equal" },
-                                                    CmpOperator::Gt => if *not { "greater than" } else { "not greater than" },
-                                                    CmpOperator::In => if *not { "in" } else { "not in" },
-                                                    _ => unreachable!()
-                                                },
-                                                err=error_message
-                                            );
+                                        let check = match cmp {
+                                            CmpOperator::Eq => {
+                                                if *not {
+                                                    "equal to"
+                                                } else {
+                                                    "not equal to"
+                                                }
+                                            }
+                                            CmpOperator::UnorderedEq => {
+                                                if *not {
+                                                    "equal in any order to"
+                                                } else {
+                                                    "not equal in any order to"
+                                                }
+                                            }
+                                            CmpOperator::Le => {
+                                                if *not {
+                                                    "less than equal to"
+                                                } else {
+                                                    "not less than equal to"
+                                                }
+                                            }
+                                            CmpOperator::Lt => {
+                                                if *not {
+                                                    "less than"
+                                                } else {
+                                                    "not less than"
+                                                }
+                                            }
+                                            CmpOperator::Ge => {
+                                                if *not {
+                                                    "greater than equal to"
+                                                } else {
+                                                    "not greater than equal"
+                                                }
+                                            }
+                                            CmpOperator::Gt => {
+                                                if *not {
+                                                    "greater than"
+                                                } else {
+                                                    "not greater than"
+                                                }
+                                            }
+                                            CmpOperator::In => {
+                                                if *not {
+                                                    "in"
+                                                } else {
+                                                    "not in"
+                                                }
+                                            }
+                                            CmpOperator::Like => {
+                                                if *not {
+                                                    "like"
+                                                } else {
+                                                    "not like"
+                                                }
+                                            }
+                                            _ => unreachable!(),
+                                        };
+                                        let message = catalog.format(
+                                            MessageId::ComparisonFailed,
+                                            &[
+                                                ("from", res),
+                                                ("to", to_res),
+                                                ("check", &check),
+                                                ("operator", &operator),
+                                                ("error", &error_message),
+                                            ],
+                                        );
                                         clauses.push(ClauseReport::Clause(
                                             GuardClauseReport::Binary(BinaryReport {
                                                 check: BinaryCheck::Resolved(BinaryComparison {
@@ -2309,9 +3206,11 @@ fn report_all_failed_clauses_for_rules<'value>(
                                                     comparison: (*cmp, *not),
                                                 }),
                                                 context: current.context.to_string(),
+                                                query: None,
                                                 messages: Messages {
                                                     location: Some(to_res.clone().self_path().1),
                                                     error_message: Some(message),
+                                                    message_id: Some(MessageId::ComparisonFailed),
                                                     custom_message: Some(custom_message),
                                                 },
                                             }),
@@ -2319,18 +3218,23 @@ fn report_all_failed_clauses_for_rules<'value>(
                                     }
 
                                     QueryResult::UnResolved(to_unres) => {
-                                        let message = format!(
-                                                "Check was not compliant as property [{remain}] to compare to is missing. Value traversed to [{to}].{err}",
-                                                remain=to_unres.remaining_query,
-                                                to=to_unres.traversed_to,
-                                                err=error_message
-                                            );
+                                        let message = catalog.format(
+                                            MessageId::CompareToMissing,
+                                            &[
+                                                ("property", &to_unres.remaining_query),
+                                                ("traversed_to", &to_unres.traversed_to),
+                                                ("operator", &operator),
+                                                ("error", &error_message),
+                                            ],
+                                        );
                                         clauses.push(ClauseReport::Clause(
                                             GuardClauseReport::Binary(BinaryReport {
                                                 context: current.context.to_string(),
+                                                query: None,
                                                 messages: Messages {
                                                     custom_message: Some(custom_message),
                                                     error_message: Some(message),
+                                                    message_id: Some(MessageId::CompareToMissing),
                                                     location: Some(
                                                         to_unres.traversed_to.self_path().1,
                                                     ),
@@ -2356,17 +3260,21 @@ fn report_all_failed_clauses_for_rules<'value>(
                     comparison,
                     ..
                 }) => {
-                    let error_message = format!(
-                        "Check was not compliant as property [{}] was not present in [{}]",
-                        from.resolved().unwrap().self_path(),
-                        SliceDisplay(to)
+                    let error_message = catalog.format(
+                        MessageId::NotInList,
+                        &[
+                            ("property", &from.resolved().unwrap().self_path()),
+                            ("values", &SliceDisplay(to)),
+                        ],
                     );
                     clauses.push(ClauseReport::Clause(GuardClauseReport::Binary(
                         BinaryReport {
                             context: current.context.to_string(),
+                            query: None,
                             messages: Messages {
                                 custom_message: custom_message.clone(),
                                 error_message: Some(error_message),
+                                message_id: Some(MessageId::NotInList),
                                 location: Some(from.resolved().unwrap().self_path().1),
                             },
                             check: BinaryCheck::InResolved(InComparison {
@@ -2400,17 +3308,29 @@ fn report_all_failed_clauses_for_rules<'value>(
     clauses
 }
 
+/// the report of the evaluation recorded under the root, with its failure messages worded by the
+/// catalog
 pub(crate) fn simplified_json_from_root<'value>(
     root: &EventRecord<'value>,
+    catalog: &MessageCatalog,
 ) -> Result<FileReport<'value>> {
     Ok(match &root.container {
         Some(RecordType::FileCheck(NamedStatus { name, status, .. })) => {
             let mut pass: BTreeSet<String> = BTreeSet::new();
             let mut skip: BTreeSet<String> = BTreeSet::new();
+            let mut suppressed = vec![];
             for each in &root.children {
-                if let Some(RecordType::RuleCheck(NamedStatus { status, name, .. })) =
-                    &each.container
+                if let Some(RecordType::RuleCheck(NamedStatus {
+                    status,
+                    name,
+                    suppressed: suppressions,
+                    ..
+                })) = &each.container
                 {
+                    suppressed.extend(suppressions.iter().map(|suppression| SuppressedReport {
+                        rule: name,
+                        suppression: suppression.clone(),
+                    }));
                     match *status {
                         Status::PASS => {
                             pass.insert(name.to_string());
@@ -2425,9 +3345,10 @@ pub(crate) fn simplified_json_from_root<'value>(
             FileReport {
                 status: *status,
                 name,
-                not_compliant: report_all_failed_clauses_for_rules(&root.children),
+                not_compliant: report_all_failed_clauses_for_rules(&root.children, catalog),
                 not_applicable: skip,
                 compliant: pass,
+                suppressed,
                 ..Default::default()
             }
         }
@@ -2435,6 +3356,21 @@ pub(crate) fn simplified_json_from_root<'value>(
     })
 }
 
+/// there is nothing to write in an interpolated string for a variable without a value
+fn check_interpolated_variable(query: &[QueryPart<'_>], values: &[QueryResult]) -> Result<()> {
+    match values.is_empty()
+        || values
+            .iter()
+            .any(|value| matches!(value, QueryResult::UnResolved(_)))
+    {
+        true => Err(Error::MissingValue(format!(
+            "Could not interpolate {} into a string, it did not resolve to a value",
+            SliceDisplay(query)
+        ))),
+        false => Ok(()),
+    }
+}
+
 pub(crate) fn resolve_function<'value, 'eval, 'loc: 'value>(
     name: &FunctionName,
     parameters: &'value [LetValue<'loc>],
@@ -2450,6 +3386,9 @@ pub(crate) fn resolve_function<'value, 'eval, 'loc: 'value>(
                     }
                     LetValue::AccessClause(clause) => {
                         let resolved_query = resolver.query(&clause.query)?;
+                        if *name == FunctionName::Interpolate {
+                            check_interpolated_variable(&clause.query, &resolved_query)?;
+                        }
                         args.push(resolved_query);
                     }
                     LetValue::FunctionCall(FunctionExpr {
@@ -2463,13 +3402,37 @@ pub(crate) fn resolve_function<'value, 'eval, 'loc: 'value>(
                 Ok(args)
             })?;
 
-    Ok(name
-        .call(&args)?
+    let values = match name {
+        FunctionName::Custom(custom) => {
+            call_custom(resolver.functions().as_deref(), custom, &args)?
+        }
+        _ => name.call(&args)?,
+    };
+    let values = values
         .into_iter()
         .flatten()
         .map(Rc::new)
-        .map(QueryResult::Resolved)
-        .collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // what was read from the environment shows up in the verbose output for auditing
+    if let (FunctionName::Env, Some(variable)) = (name, env_variable_name(&args[0])) {
+        let source = match std::env::var_os(variable) {
+            Some(_) => "",
+            None => " (not set, default used)",
+        };
+        let context = format!(
+            "env({variable}){source} = [{}]",
+            values
+                .iter()
+                .map(|value| ValueOnlyDisplay(Rc::clone(value)).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        resolver.start_record(&context)?;
+        resolver.end_record(&context, RecordType::ClauseValueCheck(ClauseCheck::Success))?;
+    }
+
+    Ok(values.into_iter().map(QueryResult::Resolved).collect())
 }
 
 #[cfg(test)]
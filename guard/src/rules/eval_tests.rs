@@ -5,7 +5,11 @@ use pretty_assertions::{assert_eq, assert_ne};
 use std::collections::HashMap;
 
 use crate::rules::eval_context::eval_context_tests::BasicQueryTesting;
-use crate::rules::eval_context::{root_scope, EventRecord, RecordTracker};
+use crate::rules::eval_context::{
+    root_scope, simplified_json_from_root, BinaryReport, ClauseReport, EventRecord,
+    GuardClauseReport, RecordTracker, UnaryReport,
+};
+use crate::rules::messages::MessageCatalog;
 
 use super::*;
 
@@ -990,8 +994,11 @@ fn block_guard_pass() -> Result<()> {
                         if idx == 0 {
                             assert!(matches!(
                                 inner,
-                                RecordType::GuardClauseBlockCheck(BlockCheck {
-                                    status: Status::FAIL,
+                                RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                                    block: BlockCheck {
+                                        status: Status::FAIL,
+                                        ..
+                                    },
                                     ..
                                 })
                             ),);
@@ -1016,8 +1023,11 @@ fn block_guard_pass() -> Result<()> {
                         } else {
                             assert!(matches!(
                                 inner,
-                                RecordType::GuardClauseBlockCheck(BlockCheck {
-                                    status: Status::PASS,
+                                RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                                    block: BlockCheck {
+                                        status: Status::PASS,
+                                        ..
+                                    },
                                     ..
                                 })
                             ),);
@@ -1294,16 +1304,22 @@ fn variable_projections_failures() -> Result<()> {
             let gbc = &each_rule_clause.children[0];
             assert!(matches!(
                 gbc.container,
-                Some(RecordType::GuardClauseBlockCheck(BlockCheck {
-                    status: Status::PASS,
+                Some(RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                    block: BlockCheck {
+                        status: Status::PASS,
+                        ..
+                    },
                     ..
                 }))
             ),);
         } else if idx == 2 {
             assert!(matches!(
                 each_rule_clause.container,
-                Some(RecordType::GuardClauseBlockCheck(BlockCheck {
-                    status: Status::FAIL,
+                Some(RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                    block: BlockCheck {
+                        status: Status::FAIL,
+                        ..
+                    },
                     ..
                 }))
             ),);
@@ -1324,8 +1340,11 @@ fn variable_projections_failures() -> Result<()> {
         } else {
             assert!(matches!(
                 each_rule_clause.container,
-                Some(RecordType::GuardClauseBlockCheck(BlockCheck {
-                    status: Status::PASS,
+                Some(RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                    block: BlockCheck {
+                        status: Status::PASS,
+                        ..
+                    },
                     ..
                 }))
             ),);
@@ -1792,10 +1811,14 @@ fn test_multiple_valued_clause_reporting() -> Result<()> {
 
         fn end_record(&mut self, _context: &str, record: RecordType<'value>) -> Result<()> {
             match record {
-                RecordType::GuardClauseBlockCheck(BlockCheck {
-                    message,
-                    status,
-                    at_least_one_matches,
+                RecordType::GuardClauseBlockCheck(GuardClauseCheck {
+                    block:
+                        BlockCheck {
+                            message,
+                            status,
+                            at_least_one_matches,
+                        },
+                    ..
                 }) => {
                     assert_eq!(message, None);
                     assert_eq!(status, Status::FAIL);
@@ -4044,3 +4067,1367 @@ fn status_combinator() {
     assert_eq!(pass.and(fail), Status::FAIL);
     assert_eq!(fail.and(pass), Status::FAIL);
 }
+
+#[test]
+fn test_on_missing_annotation() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    @on-missing(skip)
+    rule versioning_if_present {
+        Resources.*.Properties.VersioningConfiguration.Status == 'Enabled'
+    }
+
+    @on-missing(skip)
+    rule lifecycle_if_present {
+        Resources.*.Properties.LifecycleConfiguration.Rules is_list
+        Resources.*.Properties.LifecycleConfiguration { Rules !empty }
+    }
+
+    @on-missing(skip)
+    rule name_exists {
+        Resources.*.Properties.BucketName exists
+    }
+
+    @on-missing(fail)
+    rule versioning_required {
+        Resources.*.Properties.VersioningConfiguration.Status == 'Enabled'
+    }
+
+    rule versioning_default {
+        Resources.*.Properties.VersioningConfiguration.Status == 'Enabled'
+    }
+
+    @on-missing(skip)
+    rule depends_on_default {
+        versioning_default
+    }
+    "#,
+    )?;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(
+        r#"
+    Resources:
+      versioned:
+        Type: AWS::S3::Bucket
+        Properties:
+          VersioningConfiguration:
+            Status: Enabled
+      plain:
+        Type: AWS::S3::Bucket
+        Properties: {}
+    "#,
+    )?)?;
+    let mut root = root_scope(&rules_file, Rc::new(value));
+    eval_rules_file(&rules_file, &mut root, None)?;
+    let messages = serde_json::to_string(&root.reset_recorder().extract())?;
+
+    assert_eq!(root.rule_status("versioning_if_present")?, Status::PASS);
+    // every value is missing, nothing is left to check
+    assert_eq!(root.rule_status("lifecycle_if_present")?, Status::SKIP);
+    // exists checks for the property itself, so it is never skipped
+    assert_eq!(root.rule_status("name_exists")?, Status::FAIL);
+    assert_eq!(root.rule_status("versioning_required")?, Status::FAIL);
+    assert_eq!(root.rule_status("versioning_default")?, Status::FAIL);
+    // the annotation does not carry over to the rules it references
+    assert_eq!(root.rule_status("depends_on_default")?, Status::FAIL);
+
+    // only the rule that explicitly chose to fail explains why
+    assert_eq!(
+        messages
+            .matches("rule is annotated with @on-missing(fail), missing properties fail the check")
+            .count(),
+        1
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rule_selection_by_tags() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule encrypted @tags(s3, cis-2.1.1) {
+        Resources.*.Properties.BucketEncryption exists
+    }
+
+    @tags(s3, experimental)
+    rule versioned {
+        Resources.*.Properties.VersioningConfiguration exists
+    }
+
+    rule named @tags(naming) {
+        versioned
+        Resources.*.Properties.BucketName exists
+    }
+    "#,
+    )?;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(
+        r#"
+    Resources:
+      bucket:
+        Type: AWS::S3::Bucket
+        Properties:
+          BucketName: logs
+          BucketEncryption: {}
+    "#,
+    )?)?;
+    let value = Rc::new(value);
+
+    let evaluated = |selection: RuleSelection| -> Result<(Status, Vec<String>)> {
+        let mut root = root_scope(&rules_file, Rc::clone(&value)).with_selection(&selection);
+        let status = eval_rules_file(&rules_file, &mut root, None)?;
+        let top = root.reset_recorder().extract();
+        Ok((
+            status,
+            top.children.into_iter().map(|rule| rule.context).collect(),
+        ))
+    };
+
+    let (status, rules) = evaluated(RuleSelection::default())?;
+    assert_eq!(status, Status::FAIL);
+    assert_eq!(rules, vec!["encrypted", "versioned", "named"]);
+
+    let (status, rules) = evaluated(RuleSelection {
+        tags: vec![String::from("cis-2.1.1")],
+        ..Default::default()
+    })?;
+    assert_eq!(status, Status::PASS);
+    assert_eq!(rules, vec!["encrypted"]);
+
+    let (status, rules) = evaluated(RuleSelection {
+        tags: vec![String::from("s3")],
+        exclude_tags: vec![String::from("experimental")],
+        ..Default::default()
+    })?;
+    assert_eq!(status, Status::PASS);
+    assert_eq!(rules, vec!["encrypted"]);
+
+    // excluded rules are still evaluated when a selected rule refers to them
+    let (status, rules) = evaluated(RuleSelection {
+        exclude_tags: vec![String::from("experimental")],
+        ..Default::default()
+    })?;
+    assert_eq!(status, Status::FAIL);
+    assert_eq!(rules, vec!["encrypted", "named"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_failing_warnings_do_not_fail_the_file() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule encrypted {
+        Resources.*.Properties.BucketEncryption exists
+    }
+
+    @severity(warning)
+    rule versioned {
+        Resources.*.Properties.VersioningConfiguration exists
+    }
+    "#,
+    )?;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(
+        r#"
+    Resources:
+      bucket:
+        Type: AWS::S3::Bucket
+        Properties:
+          BucketEncryption: {}
+    "#,
+    )?)?;
+
+    let mut root = root_scope(&rules_file, Rc::new(value));
+    let status = eval_rules_file(&rules_file, &mut root, None)?;
+    assert_eq!(status, Status::PASS);
+
+    let top = root.reset_recorder().extract();
+    let checks = top
+        .children
+        .iter()
+        .filter_map(|rule| match &rule.container {
+            Some(RecordType::RuleCheck(NamedStatus {
+                name,
+                status,
+                severity,
+                ..
+            })) => Some((*name, *status, *severity)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        checks,
+        vec![
+            ("encrypted", Status::PASS, Severity::Error),
+            ("versioned", Status::FAIL, Severity::Warning),
+        ]
+    );
+
+    let report = simplified_json_from_root(&top, &MessageCatalog::default())?;
+    assert_eq!(report.not_compliant.len(), 1);
+    assert_eq!(report.not_compliant[0].severity(), Severity::Warning);
+
+    Ok(())
+}
+
+#[test]
+fn test_rules_suppressed_in_resource_metadata() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+
+    rule s3_encryption {
+        %buckets.Properties.BucketEncryption exists
+    }
+
+    rule s3_versioning {
+        AWS::S3::Bucket {
+            Properties.VersioningConfiguration exists
+        }
+    }
+    "#,
+    )?;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(
+        r#"
+    Resources:
+      legacy:
+        Type: AWS::S3::Bucket
+        Metadata:
+          guard:
+            SuppressedRules:
+              - s3_encryption: "ticket-123"
+              - s3_versioning
+        Properties: {}
+      logs:
+        Type: AWS::S3::Bucket
+        Properties:
+          BucketEncryption: {}
+    "#,
+    )?)?;
+
+    let mut root = root_scope(&rules_file, Rc::new(value));
+    let status = eval_rules_file(&rules_file, &mut root, None)?;
+    assert_eq!(status, Status::FAIL);
+
+    let top = root.reset_recorder().extract();
+    let checks = top
+        .children
+        .iter()
+        .filter_map(|rule| match &rule.container {
+            Some(RecordType::RuleCheck(NamedStatus {
+                name,
+                status,
+                suppressed,
+                ..
+            })) => Some((*name, *status, suppressed.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        checks,
+        vec![
+            (
+                "s3_encryption",
+                Status::PASS,
+                vec![Suppression {
+                    resource: String::from("legacy"),
+                    justification: Some(String::from("ticket-123")),
+                }]
+            ),
+            (
+                "s3_versioning",
+                Status::FAIL,
+                vec![Suppression {
+                    resource: String::from("legacy"),
+                    justification: None,
+                }]
+            ),
+        ]
+    );
+
+    let report = simplified_json_from_root(&top, &MessageCatalog::default())?;
+    assert_eq!(report.suppressed.len(), 2);
+    assert_eq!(report.suppressed[0].rule, "s3_encryption");
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_clauses_report_the_query_as_written() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+    rule tagged when %buckets !empty {
+        %buckets.Properties.BucketEncryption exists
+        %buckets.Properties.Tags[ Key == /^App/ ].Value != 'none'
+        Resources[ name | Type == 'AWS::S3::Bucket' ].Properties.Tags[0].Key in ['App', 'Team']
+    }
+    "#,
+    )?;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(
+        r#"
+    Resources:
+      bucket:
+        Type: AWS::S3::Bucket
+        Properties:
+          Tags:
+            - Key: Application
+              Value: none
+    "#,
+    )?)?;
+
+    let mut root = root_scope(&rules_file, Rc::new(value));
+    let status = eval_rules_file(&rules_file, &mut root, None)?;
+    assert_eq!(status, Status::FAIL);
+
+    let top = root.reset_recorder().extract();
+    let report = simplified_json_from_root(&top, &MessageCatalog::default())?;
+    let queries = match &report.not_compliant[..] {
+        [ClauseReport::Rule(rule)] => rule
+            .checks
+            .iter()
+            .filter_map(|check| match check {
+                ClauseReport::Clause(GuardClauseReport::Unary(UnaryReport { query, .. }))
+                | ClauseReport::Clause(GuardClauseReport::Binary(BinaryReport { query, .. })) => {
+                    query.as_deref()
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        queries,
+        vec![
+            "%buckets.Properties.BucketEncryption exists",
+            "%buckets.Properties.Tags[ Key == /^App/ ].Value != \"none\"",
+            "Resources[ name | Type == \"AWS::S3::Bucket\" ].Properties.Tags[0].Key in [\"App\",\"Team\"]",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rule_selection_by_name() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule encrypted {
+        Resources.*.Properties.BucketEncryption exists
+    }
+
+    rule versioned {
+        Resources.*.Properties.VersioningConfiguration exists
+    }
+
+    rule named {
+        versioned
+        Resources.*.Properties.BucketName exists
+    }
+    "#,
+    )?;
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+    Resources:
+      bucket:
+        Type: AWS::S3::Bucket
+        Properties:
+          BucketName: logs
+          BucketEncryption: {}
+    "#,
+    )?)?);
+
+    let evaluated = |selection: RuleSelection| -> Result<(Status, Vec<String>)> {
+        let mut root = root_scope(&rules_file, Rc::clone(&value)).with_selection(&selection);
+        let status = eval_rules_file(&rules_file, &mut root, None)?;
+        let top = root.reset_recorder().extract();
+        Ok((
+            status,
+            top.children.into_iter().map(|rule| rule.context).collect(),
+        ))
+    };
+
+    let (status, rules) = evaluated(RuleSelection {
+        names: vec![String::from("encrypted")],
+        ..Default::default()
+    })?;
+    assert_eq!(status, Status::PASS);
+    assert_eq!(rules, vec!["encrypted"]);
+
+    let (status, rules) = evaluated(RuleSelection {
+        skip_names: vec![String::from("versioned")],
+        ..Default::default()
+    })?;
+    // named still fails through the skipped rule it refers to
+    assert_eq!(status, Status::FAIL);
+    assert_eq!(rules, vec!["encrypted", "named"]);
+
+    let (status, rules) = evaluated(RuleSelection {
+        names: vec![String::from("encrypted"), String::from("versioned")],
+        skip_names: vec![String::from("versioned")],
+        ..Default::default()
+    })?;
+    assert_eq!(status, Status::PASS);
+    assert_eq!(rules, vec!["encrypted"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_exists_path_with_special_keys() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Metadata:
+          cfn.lint/config:
+            ignore_checks: [W3005]
+        Resources:
+          bucket:
+            Type: AWS::S3::Bucket
+            Properties:
+              Tags:
+                - Key: team
+                  Value: infra
+    "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule lint { exists_path("Metadata/cfn.lint~1config/ignore_checks") }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule tagged { exists_path("/Resources/bucket/Properties/Tags/0/Key") }"#)?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(
+            r#"rule encrypted { exists_path("Resources/bucket/Properties/BucketEncryption") }"#
+        )?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule unencrypted { not exists_path("Resources/bucket/Properties/BucketEncryption") }"#
+        )?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule when_lint when exists_path("Metadata/cfn.lint~1config") { Resources.bucket.Type == "AWS::S3::Bucket" }"#
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quoted_keys() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Resources:
+          bucket:
+            Type: AWS::S3::Bucket
+            Metadata:
+              aws:cdk:path: Stack/bucket/Resource
+              "0": first
+            Properties:
+              Tags:
+                - Key: cost center
+                  Value: "1234"
+              "cdk.nag":
+                rules_to_suppress: []
+    "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule cdk { Resources.bucket.Metadata."aws:cdk:path" == /^Stack/ }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule numeric_key { Resources.bucket.Metadata."0" == "first" }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule index { Resources.bucket.Properties.Tags."0".Key == "cost center" }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule dotted { Resources.bucket.Properties."cdk.nag".rules_to_suppress empty }"#
+        )?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule missing { Resources.bucket.Metadata."1" exists }"#)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_like_operator() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Resources:
+          logs:
+            Type: AWS::S3::Bucket
+            Properties:
+              BucketName: prod-logs
+              Arn: arn:aws:s3:::prod-logs
+              Tags:
+                prod-owner: infra
+                cost-center: 42
+    "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule prod { Resources.*.Properties.Arn like "arn:aws:s3:::prod-*" }"#)?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule dev { Resources.*.Properties.BucketName like "dev-*" }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule not_dev { Resources.*.Properties.BucketName not like "dev-*" }"#)?
+    );
+    // anchored, the pattern has to match the whole value
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule partial { Resources.*.Properties.BucketName like "prod" }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule single { Resources.*.Properties.BucketName like "pro?-*s" }"#)?
+    );
+    // only strings match
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule number { Resources.*.Properties.Tags.'cost-center' like "4*" }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule prod_tags { Resources.*.Properties.Tags[ keys like "prod-*" ] == "infra" }"#
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_resolve_references() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Resources:
+          instance:
+            Type: AWS::EC2::Instance
+            Properties:
+              SecurityGroupIds:
+                - Ref: sg
+                - Fn::GetAtt: [ openSg, GroupId ]
+              IamInstanceProfile:
+                Fn::GetAtt: profile.Arn
+          sg:
+            Type: AWS::EC2::SecurityGroup
+            Properties:
+              SecurityGroupIngress:
+                - CidrIp: 10.0.0.0/8
+          openSg:
+            Type: AWS::EC2::SecurityGroup
+            Properties:
+              SecurityGroupIngress:
+                - CidrIp: 0.0.0.0/0
+          profile:
+            Type: AWS::IAM::InstanceProfile
+            DependsOn: [ sg ]
+    "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    assert_eq!(
+        Status::FAIL,
+        evaluated(
+            r#"rule no_open_ingress {
+                Resources.*[ Type == 'AWS::EC2::Instance' ]
+                    .Properties.SecurityGroupIds[*].resolve()
+                    .Properties.SecurityGroupIngress[*].CidrIp != '0.0.0.0/0'
+            }"#
+        )?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule security_groups {
+                Resources.instance.Properties.SecurityGroupIds[*].resolve().Type == 'AWS::EC2::SecurityGroup'
+            }"#
+        )?
+    );
+    // the short form of Fn::GetAtt and logical ids, from within a block
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule profile {
+                Resources.instance.Properties {
+                    IamInstanceProfile.resolve().DependsOn[*].resolve().Type == 'AWS::EC2::SecurityGroup'
+                }
+            }"#
+        )?
+    );
+    // a value that does not refer to a resource does not resolve
+    assert_eq!(
+        Status::FAIL,
+        evaluated(
+            r#"rule unresolved {
+                Resources.sg.Properties.SecurityGroupIngress[*].CidrIp.resolve() exists
+            }"#
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unit_literals() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Resources:
+          queue:
+            Type: AWS::SQS::Queue
+            Properties:
+              MessageRetentionPeriod: 1209600
+              MaximumMessageSize: 262144
+          function:
+            Type: AWS::Lambda::Function
+            Properties:
+              Timeout: 900
+          pod:
+            Type: Custom::Pod
+            Properties:
+              Memory: 512MiB
+    "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule retention { Resources.queue.Properties.MessageRetentionPeriod == 14d }"#
+        )?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule message_size { Resources.queue.Properties.MaximumMessageSize <= 256KiB }"#
+        )?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule timeout { Resources.function.Properties.Timeout < 5m }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule timeout_range { Resources.function.Properties.Timeout in r[1s, 15m] }"#)?
+    );
+    // values with a unit in the data are normalized too
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule memory { Resources.pod.Properties.Memory < 1GiB }"#)?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule memory_floor { Resources.pod.Properties.Memory >= 1GB }"#)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_negated_filters() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Resources:
+          logs:
+            Type: AWS::S3::Bucket
+            Properties:
+              BucketName: logs
+              Public: false
+    "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    // a filter selecting nothing does not exist
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule no_policies { not Resources[ Type == 'AWS::IAM::Policy' ] EXISTS }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(r#"rule no_policies { Resources[ Type == 'AWS::IAM::Policy' ] NOT EXISTS }"#)?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule no_buckets { not Resources[ Type == 'AWS::S3::Bucket' ] EXISTS }"#)?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"
+            let policies = Resources[ Type == 'AWS::IAM::Policy' ]
+            rule without_policies when not %policies EXISTS {
+                Resources.*.Properties.BucketName == 'logs'
+            }
+            "#
+        )?
+    );
+
+    // negated blocks pass when their clauses fail, or when nothing is selected
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule private { not Resources[ Type == 'AWS::S3::Bucket' ] { Properties.Public == true } }"#
+        )?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule no_policies { not Resources[ Type == 'AWS::IAM::Policy' ] { Properties exists } }"#
+        )?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(
+            r#"rule not_logs { not some Resources[ Type == 'AWS::S3::Bucket' ] { Properties.BucketName == 'logs' } }"#
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_template_sections() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Metadata:
+          Owner: platform
+        Mappings:
+          RegionMap:
+            us-east-1: { ami: ami-123 }
+        Resources:
+          bucket:
+            Type: AWS::S3::Bucket
+        Outputs:
+          BucketName:
+            Value: { Ref: bucket }
+            Export: { Name: logs-bucket }
+    "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule sections {
+                METADATA.Owner == "platform"
+                MAPPINGS.RegionMap.*.ami exists
+                OUTPUTS.*.Export.Name exists
+            }"#
+        )?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(r#"rule owner_output { OUTPUTS.Owner exists }"#)?
+    );
+
+    Ok(())
+}
+
+fn eval_rules_on_data<'value, 'loc: 'value>(
+    rules_file: &'value RulesFile<'loc>,
+    data: &str,
+) -> Result<(Status, EventRecord<'value>)> {
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(data)?)?;
+    let mut root = root_scope(rules_file, Rc::new(value));
+    let status = eval_rules_file(rules_file, &mut root, None)?;
+    Ok((status, root.reset_recorder().extract()))
+}
+
+#[rstest::rstest]
+#[case("prod", Status::FAIL)]
+#[case("dev", Status::PASS)]
+fn test_guarded_clause(#[case] environment: &str, #[case] expected: Status) -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+        let environment = environment
+
+        rule encrypted_in_prod {
+            Resources.*.Properties.KmsKeyId EXISTS when %environment == "prod"
+            Resources.*.Properties.BucketName EXISTS
+        }
+        "#,
+    )?;
+
+    let data = format!(
+        r#"
+        environment: {environment}
+        Resources:
+          bucket:
+            Type: AWS::S3::Bucket
+            Properties:
+              BucketName: logs
+        "#
+    );
+    let (status, _) = eval_rules_on_data(&rules_file, &data)?;
+    assert_eq!(expected, status);
+
+    Ok(())
+}
+
+#[rstest::rstest]
+#[case("prod", "KmsKeyId: key", Status::PASS)]
+#[case("prod", "BucketEncryption: {}", Status::FAIL)]
+#[case("dev", "BucketEncryption: {}", Status::PASS)]
+#[case("dev", "KmsKeyId: key", Status::FAIL)]
+fn test_when_else_blocks(
+    #[case] environment: &str,
+    #[case] properties: &str,
+    #[case] expected: Status,
+) -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+        let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+
+        rule encryption {
+            when Parameters.Environment == "prod" {
+                %buckets.Properties.KmsKeyId EXISTS
+            } else {
+                %buckets.Properties.BucketEncryption EXISTS
+            }
+        }
+
+        rule logging {
+            %buckets {
+                when Properties.Public == true {
+                    Properties.LoggingConfiguration EXISTS
+                }
+                else {
+                    Properties.Public EXISTS
+                }
+            }
+        }
+        "#,
+    )?;
+
+    let data = format!(
+        r#"
+        Parameters:
+          Environment: {environment}
+        Resources:
+          bucket:
+            Type: AWS::S3::Bucket
+            Properties:
+              Public: false
+              {properties}
+        "#
+    );
+    let (status, top) = eval_rules_on_data(&rules_file, &data)?;
+    assert_eq!(expected, status);
+
+    // only the failures of the block that was evaluated are reported
+    let report = simplified_json_from_root(&top, &MessageCatalog::default())?;
+    assert_eq!(
+        usize::from(expected == Status::FAIL),
+        report.not_compliant.len()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_else_block_skipped_with_conditions() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+        let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+
+        rule skipped {
+            when %buckets.Properties.Environment == "prod" {
+                Resources.*.Properties.KmsKeyId EXISTS
+            } else {
+                Resources.*.Properties.BucketEncryption EXISTS
+            }
+        }
+        "#,
+    )?;
+
+    let (status, _) = eval_rules_on_data(
+        &rules_file,
+        r#"
+        Resources:
+          topic:
+            Type: AWS::SNS::Topic
+            Properties: {}
+        "#,
+    )?;
+    assert_eq!(Status::SKIP, status);
+
+    Ok(())
+}
+
+#[rstest::rstest]
+#[case("BucketEncryption: {}", Status::PASS)]
+#[case("{}", Status::FAIL)]
+fn test_private_rules(#[case] properties: &str, #[case] expected: Status) -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+        private rule buckets_encrypted {
+            Resources.*[ Type == 'AWS::S3::Bucket' ].Properties.BucketEncryption EXISTS
+        }
+
+        private rule unused {
+            Resources.*.Properties.Unused EXISTS
+        }
+
+        rule encryption {
+            buckets_encrypted
+        }
+        "#,
+    )?;
+
+    let data = format!(
+        r#"
+        Resources:
+          bucket:
+            Type: AWS::S3::Bucket
+            Properties: {properties}
+        "#
+    );
+    let (status, top) = eval_rules_on_data(&rules_file, &data)?;
+    assert_eq!(expected, status);
+
+    // only the rule referring to the private rule is reported and counted
+    let report = simplified_json_from_root(&top, &MessageCatalog::default())?;
+    let reported = report
+        .compliant
+        .iter()
+        .chain(report.not_applicable.iter())
+        .cloned()
+        .chain(report.not_compliant.iter().map(|failure| match failure {
+            ClauseReport::Rule(rule) => rule.name.to_string(),
+            _ => unreachable!(),
+        }))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![String::from("encryption")], reported);
+
+    Ok(())
+}
+
+const INTERPOLATED_LET_VALUES_DATA: &str = r#"
+Parameters:
+  Prefix: logs
+Resources:
+  policy:
+    Type: AWS::S3::BucketPolicy
+    Properties:
+      Resource: arn:aws:s3:::logs-bucket/*
+"#;
+
+#[rstest::rstest]
+#[case(
+    r#"
+    let prefix = Parameters.Prefix
+    let arn = "arn:aws:s3:::${%prefix}-bucket/*"
+    Resources.policy.Properties.Resource == %arn
+    "#,
+    Status::PASS
+)]
+#[case(
+    r#"
+    let prefix = "assets"
+    rule policy {
+        let arn = "arn:aws:s3:::${%prefix}-bucket/*"
+        Resources.policy.Properties.Resource == %arn
+    }
+    "#,
+    Status::FAIL
+)]
+fn test_interpolated_let_values(#[case] rules: &str, #[case] expected: Status) -> Result<()> {
+    let rules_file = RulesFile::try_from(rules)?;
+    let (status, _) = eval_rules_on_data(&rules_file, INTERPOLATED_LET_VALUES_DATA)?;
+    assert_eq!(expected, status);
+
+    Ok(())
+}
+
+// variables without a value cannot be written in a string
+#[rstest::rstest]
+#[case(
+    r#"
+    let arn = "arn:aws:s3:::${%undefined}"
+    Resources.policy.Properties.Resource == %arn
+    "#
+)]
+#[case(
+    r#"
+    let prefix = Parameters.Missing
+    let arn = "arn:aws:s3:::${%prefix}"
+    Resources.policy.Properties.Resource == %arn
+    "#
+)]
+fn test_interpolated_let_values_without_value(#[case] rules: &str) -> Result<()> {
+    let rules_file = RulesFile::try_from(rules)?;
+    assert!(eval_rules_on_data(&rules_file, INTERPOLATED_LET_VALUES_DATA).is_err());
+
+    Ok(())
+}
+
+const DESCENDANTS_DATA: &str = r#"
+Resources:
+  bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketEncryption:
+        ServerSideEncryptionConfiguration:
+          - ServerSideEncryptionByDefault:
+              KmsKeyId: bucket-key
+  table:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      SSESpecification:
+        KmsKeyId: table-key
+  queue:
+    Type: AWS::SQS::Queue
+    Properties: {}
+"#;
+
+#[rstest::rstest]
+#[case("Resources.*.Properties.**.KmsKeyId !EXISTS", Status::FAIL)]
+#[case("Resources.queue.Properties.**.KmsKeyId !EXISTS", Status::PASS)]
+#[case("Resources.bucket.**.KmsKeyId == 'bucket-key'", Status::PASS)]
+#[case(
+    "Resources.*[ Type != 'AWS::SQS::Queue' ].Properties.**.KmsKeyId EXISTS",
+    Status::PASS
+)]
+#[case("Resources.*.Properties.**.KmsKeyId EXISTS", Status::FAIL)]
+fn test_descendants(#[case] rules: &str, #[case] expected: Status) -> Result<()> {
+    let rules_file = RulesFile::try_from(rules)?;
+    let (status, _) = eval_rules_on_data(&rules_file, DESCENDANTS_DATA)?;
+    assert_eq!(expected, status);
+
+    Ok(())
+}
+
+#[test]
+fn test_descendants_query_paths() -> Result<()> {
+    let value =
+        PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(DESCENDANTS_DATA)?)?;
+    let mut eval = BasicQueryTesting {
+        root: Rc::new(value),
+        recorder: None,
+    };
+    let query = AccessQuery::try_from("Resources.*.Properties.**.KmsKeyId")?.query;
+    let found = eval
+        .query(&query)?
+        .into_iter()
+        .filter_map(|each| match each {
+            QueryResult::Resolved(value) => Some(value.self_path().0.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "/Resources/bucket/Properties/BucketEncryption/ServerSideEncryptionConfiguration/0/ServerSideEncryptionByDefault/KmsKeyId",
+            "/Resources/table/Properties/SSESpecification/KmsKeyId",
+        ],
+        found
+    );
+
+    Ok(())
+}
+
+#[rstest::rstest]
+#[case("Statement[-1].Effect == 'Deny'", Status::PASS)]
+#[case("Statement[-3].Action == 's3:GetObject'", Status::PASS)]
+#[case("Statement[-4] EXISTS", Status::FAIL)]
+#[case("Statement[0:2].Effect == 'Allow'", Status::PASS)]
+#[case("Statement[1:].Effect == 'Allow'", Status::FAIL)]
+#[case("Statement[:-1].Action != '*'", Status::PASS)]
+#[case("Statement[-2:][ Effect == 'Deny' ].Action == '*'", Status::PASS)]
+#[case("Statement[5:] EXISTS", Status::FAIL)]
+fn test_slices_and_negative_indices(#[case] rules: &str, #[case] expected: Status) -> Result<()> {
+    let rules_file = RulesFile::try_from(rules)?;
+    let (status, _) = eval_rules_on_data(
+        &rules_file,
+        r#"
+        Statement:
+          - Effect: Allow
+            Action: s3:GetObject
+          - Effect: Allow
+            Action: s3:PutObject
+          - Effect: Deny
+            Action: "*"
+        "#,
+    )?;
+    assert_eq!(expected, status);
+
+    Ok(())
+}
+
+#[rstest::rstest]
+#[case("10.0.0.0/16", "[22, 443]", Status::PASS)]
+#[case("10.1.0.0/16", "[22, 443]", Status::FAIL)]
+#[case("10.0.0.0/16", "[443, 22]", Status::FAIL)]
+fn test_distinct_and_sorted_functions(
+    #[case] cidr: &str,
+    #[case] ports: &str,
+    #[case] expected: Status,
+) -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+        let groups = Resources.*[ Type == 'AWS::EC2::SecurityGroup' ]
+
+        rule no_duplicate_cidrs when %groups !empty {
+            let cidrs = %groups.Properties.SecurityGroupIngress[*].CidrIp
+            let unique = count(distinct(%cidrs))
+            let total = count(%cidrs)
+            %unique == %total
+        }
+
+        rule ports_in_order when %groups !empty {
+            let ports = %groups.Properties.Ports
+            let ordered = sorted(%ports)
+            %ports == %ordered
+        }
+        "#,
+    )?;
+
+    let data = format!(
+        r#"
+        Resources:
+          sg:
+            Type: AWS::EC2::SecurityGroup
+            Properties:
+              SecurityGroupIngress:
+                - CidrIp: 10.1.0.0/16
+                - CidrIp: {cidr}
+              Ports: {ports}
+        "#
+    );
+    let (status, _) = eval_rules_on_data(&rules_file, &data)?;
+    assert_eq!(expected, status);
+
+    Ok(())
+}
+
+#[rstest::rstest]
+#[case("{ Owner: platform, CostCenter: '1234' }", Status::PASS)]
+#[case("{ Owner: platform, Team: payments }", Status::FAIL)]
+#[case("{ Owner: '' }", Status::FAIL)]
+#[case("{}", Status::FAIL)]
+fn test_keys_and_values_functions(#[case] tags: &str, #[case] expected: Status) -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+        let allowed_tag_keys = ['Owner', 'CostCenter']
+        let parameters = Resources.*[ Type == 'AWS::SSM::Parameter' ]
+
+        rule only_allowed_tag_keys when %parameters !empty {
+            let tag_keys = keys(%parameters.Properties.Tags)
+            let unknown = difference(%tag_keys, %allowed_tag_keys)
+            %unknown empty
+        }
+
+        rule no_empty_tag_values when %parameters !empty {
+            let tag_values = values(%parameters.Properties.Tags)
+            %tag_values !empty
+            %tag_values != ''
+        }
+        "#,
+    )?;
+
+    let data = format!(
+        r#"
+        Resources:
+          param:
+            Type: AWS::SSM::Parameter
+            Properties:
+              Tags: {tags}
+        "#
+    );
+    let (status, _) = eval_rules_on_data(&rules_file, &data)?;
+    assert_eq!(expected, status);
+
+    Ok(())
+}
+
+#[test]
+fn test_strict_types() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Enabled: "true"
+        Port: "443"
+        Size: 512MiB
+        Name: logs
+        "#,
+    )?)?);
+
+    for (clause, expected, expected_strict) in [
+        ("Enabled == true", Status::FAIL, Status::FAIL),
+        ("Port != 80", Status::FAIL, Status::FAIL),
+        ("Port IN [80, 443]", Status::FAIL, Status::FAIL),
+        ("Port NOT IN [80, 8080]", Status::PASS, Status::FAIL),
+        ("Size >= 256MiB", Status::PASS, Status::PASS),
+        ("Name == /^lo/", Status::PASS, Status::PASS),
+        ("Name IN ['logs', 'data']", Status::PASS, Status::PASS),
+    ] {
+        let rules_file = RulesFile::try_from(format!("rule check {{ {clause} }}").as_str())?;
+
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        assert_eq!(
+            expected,
+            eval_rules_file(&rules_file, &mut root, None)?,
+            "{clause}"
+        );
+
+        let mut root = root_scope(&rules_file, Rc::clone(&value)).with_strict_types(true);
+        assert_eq!(
+            expected_strict,
+            eval_rules_file(&rules_file, &mut root, None)?,
+            "{clause} with --strict-types"
+        );
+    }
+
+    let rules_file = RulesFile::try_from("rule check { Enabled == true }")?;
+    let mut root = root_scope(&rules_file, value).with_strict_types(true);
+    eval_rules_file(&rules_file, &mut root, None)?;
+    let record = format!("{:?}", root.reset_recorder().extract());
+    assert!(
+        record.contains(r#"String \"true\" is not comparable with bool true with --strict-types"#)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unordered_eq_operator() -> Result<()> {
+    let value = Rc::new(PathAwareValue::try_from(serde_yaml::from_str::<
+        serde_yaml::Value,
+    >(
+        r#"
+        Resources:
+          web:
+            Type: AWS::EC2::SecurityGroup
+            Properties:
+              SecurityGroupIngress:
+                - IpProtocol: tcp
+                  FromPort: 80
+                  ToPort: 80
+                - IpProtocol: tcp
+                  FromPort: 443
+                  ToPort: 443
+              Tags:
+                - Key: owner
+                  Value: infra
+                - Key: environment
+                  Value: prod
+        "#,
+    )?)?);
+
+    let evaluated = |rules: &str| -> Result<Status> {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::clone(&value));
+        eval_rules_file(&rules_file, &mut root, None)
+    };
+
+    let ingress = r#"[
+        { IpProtocol: 'tcp', FromPort: 443, ToPort: 443 },
+        { IpProtocol: 'tcp', FromPort: 80, ToPort: 80 }
+    ]"#;
+    assert_eq!(
+        Status::FAIL,
+        evaluated(&format!(
+            "rule ordered {{ Resources.web.Properties.SecurityGroupIngress == {ingress} }}"
+        ))?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(&format!(
+            "rule unordered {{ Resources.web.Properties.SecurityGroupIngress =~ {ingress} }}"
+        ))?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(&format!(
+            "rule negated {{ Resources.web.Properties.SecurityGroupIngress !~ {ingress} }}"
+        ))?
+    );
+    assert_eq!(
+        Status::PASS,
+        evaluated(
+            r#"rule tags { Resources.web.Properties.Tags =~ [{ Key: 'environment', Value: 'prod' }, { Key: 'owner', Value: 'infra' }] }"#
+        )?
+    );
+    assert_eq!(
+        Status::FAIL,
+        evaluated(
+            r#"rule missing_tag { Resources.web.Properties.Tags =~ [{ Key: 'environment', Value: 'prod' }] }"#
+        )?
+    );
+
+    Ok(())
+}
@@ -524,6 +524,40 @@ impl<'loc> Evaluate for GuardAccessClause<'loc> {
                 !all,
             )?,
 
+            //
+            // =~, !~
+            //
+            CmpOperator::UnorderedEq => compare(
+                &lhs,
+                &clause.access_clause.query.query,
+                &rhs,
+                rhs_query,
+                invert_closure(
+                    super::path_value::compare_unordered_eq,
+                    clause.access_clause.comparator.1,
+                    clause.negation,
+                ),
+                false,
+                !all,
+            )?,
+
+            //
+            // like, not like
+            //
+            CmpOperator::Like => compare(
+                &lhs,
+                &clause.access_clause.query.query,
+                &rhs,
+                rhs_query,
+                invert_closure(
+                    super::path_value::compare_like,
+                    clause.access_clause.comparator.1,
+                    clause.negation,
+                ),
+                false,
+                !all,
+            )?,
+
             //
             // >
             //
@@ -695,7 +729,7 @@ impl<'loc> Evaluate for GuardClause<'loc> {
             GuardClause::Clause(gac) => gac.evaluate(context, var_resolver),
             GuardClause::NamedRule(nr) => nr.evaluate(context, var_resolver),
             GuardClause::BlockClause(bc) => bc.evaluate(context, var_resolver),
-            GuardClause::WhenBlock(conditions, clauses) => {
+            GuardClause::WhenBlock(conditions, clauses, otherwise) => {
                 let status = loop {
                     let mut when_conditions =
                         AutoReport::new(EvaluationType::Condition, var_resolver, "");
@@ -703,14 +737,21 @@ impl<'loc> Evaluate for GuardClause<'loc> {
                         .status(conditions.evaluate(context, var_resolver)?)
                         .get_status();
                 };
-                match status {
-                    Status::PASS => {
+                match (status, otherwise) {
+                    (Status::PASS, _) => {
                         let mut auto_block =
                             AutoReport::new(EvaluationType::ConditionBlock, var_resolver, "");
                         Ok(auto_block
                             .status(clauses.evaluate(context, var_resolver)?)
                             .get_status())
                     }
+                    (Status::FAIL, Some(otherwise)) => {
+                        let mut auto_block =
+                            AutoReport::new(EvaluationType::ConditionBlock, var_resolver, "");
+                        Ok(auto_block
+                            .status(otherwise.evaluate(context, var_resolver)?)
+                            .get_status())
+                    }
                     _ => {
                         let mut skip_block =
                             AutoReport::new(EvaluationType::ConditionBlock, var_resolver, "");
@@ -967,7 +1008,7 @@ impl<'loc> Evaluate for RuleClause<'loc> {
         Ok(match self {
             RuleClause::Clause(gc) => gc.evaluate(context, var_resolver)?,
             RuleClause::TypeBlock(tb) => tb.evaluate(context, var_resolver)?,
-            RuleClause::WhenBlock(conditions, block) => {
+            RuleClause::WhenBlock(conditions, block, otherwise) => {
                 let status = {
                     let mut auto_cond =
                         AutoReport::new(EvaluationType::Condition, var_resolver, "");
@@ -976,14 +1017,21 @@ impl<'loc> Evaluate for RuleClause<'loc> {
                         .get_status()
                 };
 
-                match status {
-                    Status::PASS => {
+                match (status, otherwise) {
+                    (Status::PASS, _) => {
                         let mut auto_block =
                             AutoReport::new(EvaluationType::ConditionBlock, var_resolver, "");
                         auto_block
                             .status(block.evaluate(context, var_resolver)?)
                             .get_status()
                     }
+                    (Status::FAIL, Some(otherwise)) => {
+                        let mut auto_block =
+                            AutoReport::new(EvaluationType::ConditionBlock, var_resolver, "");
+                        auto_block
+                            .status(otherwise.evaluate(context, var_resolver)?)
+                            .get_status()
+                    }
                     _ => {
                         let mut skip_block =
                             AutoReport::new(EvaluationType::ConditionBlock, var_resolver, "");
@@ -1027,7 +1075,11 @@ impl<'loc> Evaluate for RulesFile<'loc> {
     ) -> Result<Status> {
         let mut overall = Status::PASS;
         let mut auto_report = AutoReport::new(EvaluationType::File, var_resolver, "");
-        for rule in &self.guard_rules {
+        for rule in self
+            .guard_rules
+            .iter()
+            .filter(|rule| !rule.annotations.private)
+        {
             if Status::FAIL == rule.evaluate(context, var_resolver)? {
                 overall = Status::FAIL
             }
@@ -1,6 +1,8 @@
 use crate::rules::values::*;
 
-use crate::rules::display::ValueOnlyDisplay;
+use crate::rules::display::{
+    guard_clause_text, key_source, rule_source, rules_file_source, slice_source, ValueOnlyDisplay,
+};
 use crate::rules::path_value::PathAwareValue;
 use serde::{Deserialize, Serialize};
 use std::fmt::Formatter;
@@ -10,11 +12,11 @@ use std::rc::Rc;
 use super::eval_context::FunctionName;
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct FileLocation<'loc> {
-    pub(crate) line: u32,
-    pub(crate) column: u32,
+pub struct FileLocation<'loc> {
+    pub line: u32,
+    pub column: u32,
     #[serde(skip_serializing, skip_deserializing)]
-    pub(crate) file_name: &'loc str,
+    pub file_name: &'loc str,
 }
 
 impl<'loc> std::fmt::Display for FileLocation<'loc> {
@@ -28,12 +30,81 @@ impl<'loc> std::fmt::Display for FileLocation<'loc> {
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) enum LetValue<'loc> {
-    Value(PathAwareValue),
+pub enum LetValue<'loc> {
+    Value(#[serde(with = "literal")] PathAwareValue),
     AccessClause(AccessQuery<'loc>),
     FunctionCall(FunctionExpr<'loc>),
 }
 
+/// Literals are written with their path and their value like the values of data files, as JSON,
+/// which has no regex or range. Those holding one carry the literal as written in the rules too,
+/// e.g. `literal: /^prod/`, and are read back from it, so that the rules round-trip through JSON
+/// and YAML. Binary formats, e.g. the one of rules packs, keep the value as is.
+mod literal {
+    use super::*;
+    use crate::rules::display::value_source;
+    use crate::rules::path_value::{Location, Path};
+    use serde::ser::{Error, SerializeStruct};
+    use serde::{Deserializer, Serializer};
+    use std::convert::{TryFrom, TryInto};
+
+    #[derive(Deserialize)]
+    struct Readable {
+        path: String,
+        value: serde_json::Value,
+        #[serde(default)]
+        literal: Option<String>,
+    }
+
+    pub(super) fn serialize<S>(value: &PathAwareValue, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() || !needs_literal(value) {
+            return value.serialize(serializer);
+        }
+
+        let (path, json): (String, serde_json::Value) =
+            value.try_into().map_err(S::Error::custom)?;
+        let mut readable = serializer.serialize_struct("PathAwareValue", 3)?;
+        readable.serialize_field("path", &path)?;
+        readable.serialize_field("value", &json)?;
+        readable.serialize_field("literal", &value_source(value))?;
+        readable.end()
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<PathAwareValue, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return PathAwareValue::deserialize(deserializer);
+        }
+
+        let readable = Readable::deserialize(deserializer)?;
+        let path = Path(readable.path, Location::default());
+        match readable.literal {
+            Some(literal) => PathAwareValue::try_from((literal.as_str(), path)),
+            None => PathAwareValue::try_from((&readable.value, path)),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+
+    /// whether the value is, or holds, a regex or a range, which JSON values cannot tell apart
+    /// from strings
+    fn needs_literal(value: &PathAwareValue) -> bool {
+        match value {
+            PathAwareValue::Regex(_)
+            | PathAwareValue::RangeInt(_)
+            | PathAwareValue::RangeFloat(_)
+            | PathAwareValue::RangeChar(_) => true,
+            PathAwareValue::List((_, list)) => list.iter().any(needs_literal),
+            PathAwareValue::Map((_, map)) => map.values.values().any(needs_literal),
+            _ => false,
+        }
+    }
+}
+
 ///
 /// This expression encapsulates assignment expressions inside a block expression
 /// or at the file let. An assignment can either be a direct Value object or access
@@ -41,9 +112,9 @@ pub(crate) enum LetValue<'loc> {
 /// match specific selections [crate::rules::common::walk_type]
 ///
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct LetExpr<'loc> {
-    pub(crate) var: String,
-    pub(crate) value: LetValue<'loc>,
+pub struct LetExpr<'loc> {
+    pub var: String,
+    pub value: LetValue<'loc>,
 }
 
 ///
@@ -62,14 +133,21 @@ pub(crate) struct LetExpr<'loc> {
 ///
 ///
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) enum QueryPart<'loc> {
+pub enum QueryPart<'loc> {
     This,
     Key(String),
     MapKeyFilter(Option<String>, MapKeyFilterClause<'loc>),
     AllValues(Option<String>),
     AllIndices(Option<String>),
+    /// an element of a list, negative indices counting from the end, `[-1]` being the last one
     Index(i32),
+    /// the elements of a list from a start up to an end it excludes, `[0:2]`, `[1:]` or `[:-1]`
+    Slice(Option<i32>, Option<i32>),
     Filter(Option<String>, Conjunctions<GuardClause<'loc>>),
+    /// follows a `Ref`, `Fn::GetAtt` or logical id to the resource it references, `resolve()`
+    Resolve,
+    /// the values of a key at any depth, `**.KmsKeyId`
+    Descendants(String),
 }
 
 impl<'loc> QueryPart<'loc> {
@@ -94,11 +172,36 @@ impl<'loc> QueryPart<'loc> {
     }
 }
 
+/// the position `index` refers to in a list of `len` elements, when it is in bounds
+pub(crate) fn list_position(index: i32, len: usize) -> Option<usize> {
+    let position = match index >= 0 {
+        true => index as usize,
+        false => len.checked_sub(index.unsigned_abs() as usize)?,
+    };
+    (position < len).then_some(position)
+}
+
+/// the positions `[start:end]` selects in a list of `len` elements, bounds past either end of
+/// the list are clamped to it
+pub(crate) fn slice_range(
+    start: Option<i32>,
+    end: Option<i32>,
+    len: usize,
+) -> std::ops::Range<usize> {
+    let bound = |index: i32| match index >= 0 {
+        true => (index as usize).min(len),
+        false => len.saturating_sub(index.unsigned_abs() as usize),
+    };
+    let start = start.map_or(0, bound);
+    let end = end.map_or(len, bound);
+    start..end.max(start)
+}
+
 impl<'loc> std::fmt::Display for QueryPart<'loc> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             QueryPart::Key(s) => {
-                f.write_str(s.as_str())?;
+                f.write_str(&key_source(s))?;
             }
 
             QueryPart::AllIndices(_name) => {
@@ -113,6 +216,10 @@ impl<'loc> std::fmt::Display for QueryPart<'loc> {
                 write!(f, "{}", idx)?;
             }
 
+            QueryPart::Slice(start, end) => {
+                write!(f, "{}", slice_source(*start, *end))?;
+            }
+
             QueryPart::Filter(name, _c) => {
                 f.write_fmt(format_args!(
                     "{} (filter-clauses)",
@@ -130,26 +237,34 @@ impl<'loc> std::fmt::Display for QueryPart<'loc> {
             QueryPart::This => {
                 f.write_str("_")?;
             }
+
+            QueryPart::Resolve => {
+                f.write_str("resolve()")?;
+            }
+
+            QueryPart::Descendants(key) => {
+                write!(f, "**.{}", key_source(key))?;
+            }
         }
         Ok(())
     }
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct AccessQuery<'loc> {
-    pub(crate) query: Vec<QueryPart<'loc>>,
-    pub(crate) match_all: bool,
+pub struct AccessQuery<'loc> {
+    pub query: Vec<QueryPart<'loc>>,
+    pub match_all: bool,
 }
 
 //pub(crate) type AccessQuery<'loc> = Vec<QueryPart<'loc>>;
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct AccessClause<'loc> {
-    pub(crate) query: AccessQuery<'loc>,
-    pub(crate) comparator: (CmpOperator, bool),
-    pub(crate) compare_with: Option<LetValue<'loc>>,
-    pub(crate) custom_message: Option<String>,
-    pub(crate) location: FileLocation<'loc>,
+pub struct AccessClause<'loc> {
+    pub query: AccessQuery<'loc>,
+    pub comparator: (CmpOperator, bool),
+    pub compare_with: Option<LetValue<'loc>>,
+    pub custom_message: Option<String>,
+    pub location: FileLocation<'loc>,
 }
 
 impl<'loc> Default for AccessClause<'loc> {
@@ -171,116 +286,265 @@ impl<'loc> Default for AccessClause<'loc> {
     }
 }
 
-pub(crate) type Disjunctions<T> = Vec<T>;
-pub(crate) type Conjunctions<T> = Vec<Disjunctions<T>>;
+pub type Disjunctions<T> = Vec<T>;
+pub type Conjunctions<T> = Vec<Disjunctions<T>>;
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct GuardAccessClause<'loc> {
-    pub(crate) access_clause: AccessClause<'loc>,
-    pub(crate) negation: bool,
+pub struct GuardAccessClause<'loc> {
+    pub access_clause: AccessClause<'loc>,
+    pub negation: bool,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct MapKeyFilterClause<'loc> {
-    pub(crate) comparator: (CmpOperator, bool),
-    pub(crate) compare_with: LetValue<'loc>,
+pub struct MapKeyFilterClause<'loc> {
+    pub comparator: (CmpOperator, bool),
+    pub compare_with: LetValue<'loc>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct GuardNamedRuleClause<'loc> {
-    pub(crate) dependent_rule: String,
-    pub(crate) negation: bool,
-    pub(crate) custom_message: Option<String>,
-    pub(crate) location: FileLocation<'loc>,
+pub struct GuardNamedRuleClause<'loc> {
+    pub dependent_rule: String,
+    pub negation: bool,
+    pub custom_message: Option<String>,
+    pub location: FileLocation<'loc>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct BlockGuardClause<'loc> {
-    pub(crate) query: AccessQuery<'loc>,
-    pub(crate) block: Block<'loc, GuardClause<'loc>>,
-    pub(crate) location: FileLocation<'loc>,
-    pub(crate) not_empty: bool,
+pub struct BlockGuardClause<'loc> {
+    pub query: AccessQuery<'loc>,
+    pub block: Block<'loc, GuardClause<'loc>>,
+    pub location: FileLocation<'loc>,
+    pub not_empty: bool,
+    /// `not` in front of the block, the block passes when its clauses fail and when the query
+    /// selects nothing
+    pub negation: bool,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct WhenGuardBlockClause<'loc> {
-    pub(crate) conditions: WhenConditions<'loc>,
-    pub(crate) block: Block<'loc, GuardClause<'loc>>,
+pub struct WhenGuardBlockClause<'loc> {
+    pub conditions: WhenConditions<'loc>,
+    pub block: Block<'loc, GuardClause<'loc>>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct ParameterizedNamedRuleClause<'loc> {
-    pub(crate) parameters: Vec<LetValue<'loc>>,
-    pub(crate) named_rule: GuardNamedRuleClause<'loc>,
+pub struct ParameterizedNamedRuleClause<'loc> {
+    pub parameters: Vec<LetValue<'loc>>,
+    pub named_rule: GuardNamedRuleClause<'loc>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct FunctionExpr<'loc> {
-    pub(crate) parameters: Vec<LetValue<'loc>>,
-    pub(crate) name: FunctionName,
-    pub(crate) location: FileLocation<'loc>,
+pub struct FunctionExpr<'loc> {
+    pub parameters: Vec<LetValue<'loc>>,
+    pub name: FunctionName,
+    pub location: FileLocation<'loc>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) enum GuardClause<'loc> {
+pub enum GuardClause<'loc> {
     Clause(GuardAccessClause<'loc>),
     NamedRule(GuardNamedRuleClause<'loc>),
     ParameterizedNamedRule(ParameterizedNamedRuleClause<'loc>),
     BlockClause(BlockGuardClause<'loc>),
-    WhenBlock(WhenConditions<'loc>, Block<'loc, GuardClause<'loc>>),
+    /// the block evaluated when the conditions hold, and the `else` block evaluated when they
+    /// fail
+    WhenBlock(
+        WhenConditions<'loc>,
+        Block<'loc, GuardClause<'loc>>,
+        Option<Block<'loc, GuardClause<'loc>>>,
+    ),
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) enum WhenGuardClause<'loc> {
+pub enum WhenGuardClause<'loc> {
     Clause(GuardAccessClause<'loc>),
     NamedRule(GuardNamedRuleClause<'loc>),
     ParameterizedNamedRule(ParameterizedNamedRuleClause<'loc>),
 }
 
-pub(crate) type WhenConditions<'loc> = Conjunctions<WhenGuardClause<'loc>>;
+pub type WhenConditions<'loc> = Conjunctions<WhenGuardClause<'loc>>;
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash)]
-pub(crate) struct Block<'loc, T> {
-    pub(crate) assignments: Vec<LetExpr<'loc>>,
-    pub(crate) conjunctions: Conjunctions<T>,
+pub struct Block<'loc, T> {
+    pub assignments: Vec<LetExpr<'loc>>,
+    pub conjunctions: Conjunctions<T>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct TypeBlock<'loc> {
-    pub(crate) type_name: String,
-    pub(crate) conditions: Option<WhenConditions<'loc>>,
-    pub(crate) block: Block<'loc, GuardClause<'loc>>, // only contains access clauses
-    pub(crate) query: Vec<QueryPart<'loc>>,
+pub struct TypeBlock<'loc> {
+    pub type_name: String,
+    pub conditions: Option<WhenConditions<'loc>>,
+    pub block: Block<'loc, GuardClause<'loc>>, // only contains access clauses
+    pub query: Vec<QueryPart<'loc>>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
-pub(crate) enum RuleClause<'loc> {
+pub enum RuleClause<'loc> {
     Clause(GuardClause<'loc>),
-    WhenBlock(WhenConditions<'loc>, Block<'loc, GuardClause<'loc>>),
+    /// the block evaluated when the conditions hold, and the `else` block evaluated when they
+    /// fail
+    WhenBlock(
+        WhenConditions<'loc>,
+        Block<'loc, GuardClause<'loc>>,
+        Option<Block<'loc, GuardClause<'loc>>>,
+    ),
     TypeBlock(TypeBlock<'loc>),
 }
 
+/// how a rule treats clauses whose target property is missing, set with `@on-missing(fail|skip)`
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum OnMissing {
+    /// the clause fails for the missing property, this is the default
+    Fail,
+    /// the missing property is ignored, the clause is skipped when no value is left to check
+    Skip,
+}
+
+impl std::fmt::Display for OnMissing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnMissing::Fail => f.write_str("fail"),
+            OnMissing::Skip => f.write_str("skip"),
+        }
+    }
+}
+
+/// how the failures of a rule are reported, set with `@severity(error|warning)`
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// failures are violations, this is the default
+    #[default]
+    Error,
+    /// failures are advisory, they are reported as warnings and do not fail the data file
+    Warning,
+}
+
+impl Severity {
+    pub(crate) fn is_error(&self) -> bool {
+        *self == Severity::Error
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// annotations placed around the rule name, e.g. `@on-missing(skip)` or `@tags(s3, cis-2.1.1)`
+#[derive(Eq, PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleAnnotations {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub on_missing: Option<OnMissing>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub severity: Option<Severity>,
+    /// `private rule`, a helper rule only evaluated when other rules refer to it, left out of the
+    /// reports and the summary counts
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub private: bool,
+}
+
+impl RuleAnnotations {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.on_missing.is_none()
+            && self.tags.is_empty()
+            && self.severity.is_none()
+            && !self.private
+    }
+
+    pub(crate) fn severity(&self) -> Severity {
+        self.severity.unwrap_or_default()
+    }
+}
+
+/// which rules in a rules file get evaluated based on their name and `@tags`, rules excluded here
+/// are still evaluated when a selected rule refers to them by name
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub(crate) struct RuleSelection {
+    pub(crate) tags: Vec<String>,
+    pub(crate) exclude_tags: Vec<String>,
+    pub(crate) names: Vec<String>,
+    pub(crate) skip_names: Vec<String>,
+}
+
+impl RuleSelection {
+    pub(crate) fn is_selected(&self, rule: &Rule<'_>) -> bool {
+        let tags = &rule.annotations.tags;
+        (self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag)))
+            && !self.exclude_tags.iter().any(|tag| tags.contains(tag))
+            && (self.names.is_empty() || self.names.contains(&rule.rule_name))
+            && !self.skip_names.contains(&rule.rule_name)
+    }
+}
+
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct Rule<'loc> {
-    pub(crate) rule_name: String,
-    pub(crate) conditions: Option<WhenConditions<'loc>>,
-    pub(crate) block: Block<'loc, RuleClause<'loc>>,
+pub struct Rule<'loc> {
+    pub rule_name: String,
+    pub conditions: Option<WhenConditions<'loc>>,
+    pub block: Block<'loc, RuleClause<'loc>>,
+    #[serde(skip_serializing_if = "RuleAnnotations::is_empty", default)]
+    pub annotations: RuleAnnotations,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct ParameterizedRule<'loc> {
-    pub(crate) parameter_names: indexmap::IndexSet<String>,
-    pub(crate) rule: Rule<'loc>,
+pub struct ParameterizedRule<'loc> {
+    pub parameter_names: indexmap::IndexSet<String>,
+    pub rule: Rule<'loc>,
 }
 
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+/// The rules of a rules file, as parsed. Serialized as is into rules packs, printed as YAML with
+/// `singleton_map_recursive` so that enums are maps rather than tags. Serialized to JSON or YAML,
+/// it reads back into the same rules, file locations aside as they lose their file name.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RulesFile<'loc> {
-    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
-    pub(crate) assignments: Vec<LetExpr<'loc>>,
-    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
-    pub(crate) guard_rules: Vec<Rule<'loc>>,
-    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
-    pub(crate) parameterized_rules: Vec<ParameterizedRule<'loc>>,
+    pub assignments: Vec<LetExpr<'loc>>,
+    /// the named rules, along with the rule named `default` holding the clauses written outside
+    /// of any rule
+    pub guard_rules: Vec<Rule<'loc>>,
+    pub parameterized_rules: Vec<ParameterizedRule<'loc>>,
+}
+
+impl<'loc> RulesFile<'loc> {
+    /// parses the rules, the file name is the one the file locations of the clauses refer to
+    ///
+    /// This function will return an error if the rules fail to parse
+    pub fn parse(content: &'loc str, file_name: &'loc str) -> crate::rules::Result<Self> {
+        let span = crate::rules::parser::Span::new_extra(content, file_name);
+        Ok(crate::rules::parser::rules_file(span)?.unwrap_or_default())
+    }
+
+    /// the rules written back as guard rules, which parse into the same rules. Comments and the
+    /// layout of the original file are not kept
+    pub fn to_guard_source(&self) -> String {
+        rules_file_source(self)
+    }
+}
+
+impl<'loc> Rule<'loc> {
+    /// the rule written back as guard rules
+    pub fn to_guard_source(&self) -> String {
+        rule_source(self, None)
+    }
+}
+
+impl<'loc> ParameterizedRule<'loc> {
+    /// the rule written back as guard rules, along with its parameters
+    pub fn to_guard_source(&self) -> String {
+        rule_source(&self.rule, Some(&self.parameter_names))
+    }
+}
+
+impl<'loc> GuardClause<'loc> {
+    /// the clause written back as guard rules, the clauses of its block, if any, on the lines
+    /// that follow indented by four spaces
+    pub fn to_guard_source(&self) -> String {
+        guard_clause_text(self, 0)
+    }
 }
 
 pub(crate) struct SliceDisplay<'a, T: 'a>(pub(crate) &'a [T]);
@@ -316,6 +580,9 @@ impl<'loc> std::fmt::Display for GuardClause<'loc> {
 #[allow(clippy::needless_range_loop)]
 impl<'loc> std::fmt::Display for BlockGuardClause<'loc> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.negation {
+            write!(f, "not ")?;
+        }
         write!(f, "{} {{ ", self.query)?;
         for each in &self.block.conjunctions {
             let len = each.len();
@@ -366,6 +633,9 @@ impl<'loc> std::fmt::Display for AccessQuery<'loc> {
 
 impl<'loc> std::fmt::Display for FunctionExpr<'loc> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.name == FunctionName::Interpolate {
+            return write!(f, "{}", crate::rules::display::interpolated_source(self));
+        }
         let params = self
             .parameters
             .iter()
@@ -391,3 +661,7 @@ pub(crate) fn display_comparator(cmp: (CmpOperator, bool)) -> String {
     let (op, not) = cmp;
     format!("{}{} ", if not { "not " } else { "" }, op)
 }
+
+#[cfg(test)]
+#[path = "exprs_tests.rs"]
+mod exprs_tests;
@@ -0,0 +1,130 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+const RULES: &str = r#"
+let buckets = Resources.*[ Type == /S3::Bucket/ ]
+let sizes = [r[1,10], r(0.5,2.0]]
+
+@tags(s3, cis-2.1.1)
+@severity(warning)
+rule s3_encrypted when %buckets !empty {
+    some %buckets.Properties.BucketEncryption exists <<encryption is required>>
+    %buckets.Properties.Size in r[1,10] or %buckets.Properties.KmsKeyId exists
+    %buckets.Properties.Tags[*] {
+        Key in [/^App/, "Owner"]
+    }
+}
+
+rule s3_tagged(tags) {
+    %buckets.Properties.Tags[*] {
+        Key in %tags
+    }
+}
+
+AWS::S3::Bucket when Properties.Name exists {
+    Properties.Name != null
+}
+"#;
+
+const SOURCE: &str = r#"let buckets = Resources.*[ Type == /S3::Bucket/ ]
+let sizes = [r[1,10],r(0.5,2.0]]
+
+AWS::S3::Bucket when Properties.Name exists {
+    Properties.Name != null
+}
+
+@severity(warning)
+@tags(s3, cis-2.1.1)
+rule s3_encrypted when %buckets not empty {
+    some %buckets.Properties.BucketEncryption exists <<encryption is required>>
+    %buckets.Properties.Size in r[1,10] or %buckets.Properties.KmsKeyId exists
+    %buckets.Properties.Tags[*] {
+        Key in [/^App/,"Owner"]
+    }
+}
+
+rule s3_tagged(tags) {
+    %buckets.Properties.Tags[*] {
+        Key in %tags
+    }
+}
+"#;
+
+#[test]
+fn test_to_guard_source() {
+    let rules = RulesFile::parse(RULES, "").unwrap();
+    assert_eq!(SOURCE, rules.to_guard_source());
+
+    let reparsed = RulesFile::parse(SOURCE, "").unwrap();
+    assert_eq!(SOURCE, reparsed.to_guard_source());
+}
+
+#[test]
+fn test_guard_clause_to_guard_source() {
+    let rules = RulesFile::parse(RULES, "").unwrap();
+    let clause = match &rules.parameterized_rules[0].rule.block.conjunctions[0][0] {
+        RuleClause::Clause(clause) => clause,
+        clause => panic!("expecting a clause, found {clause:?}"),
+    };
+
+    assert_eq!(
+        "%buckets.Properties.Tags[*] {\n    Key in %tags\n}",
+        clause.to_guard_source()
+    );
+    assert_eq!(
+        "rule s3_tagged(tags) {\n    %buckets.Properties.Tags[*] {\n        Key in %tags\n    }\n}\n",
+        rules.parameterized_rules[0].to_guard_source()
+    );
+}
+
+#[test]
+fn test_json_round_trip() {
+    let rules = RulesFile::parse(RULES, "").unwrap();
+    let json = serde_json::to_string(&rules).unwrap();
+    assert!(json.contains(r#""literal":"/S3::Bucket/""#), "{json}");
+
+    let read: RulesFile = serde_json::from_str(&json).unwrap();
+    assert_eq!(rules, read);
+}
+
+#[test]
+fn test_yaml_round_trip() {
+    let rules = RulesFile::parse(RULES, "").unwrap();
+    let mut yaml = vec![];
+    serde_yaml::with::singleton_map_recursive::serialize(
+        &rules,
+        &mut serde_yaml::Serializer::new(&mut yaml),
+    )
+    .unwrap();
+
+    let read: RulesFile = serde_yaml::with::singleton_map_recursive::deserialize(
+        serde_yaml::Deserializer::from_slice(&yaml),
+    )
+    .unwrap();
+    assert_eq!(rules, read);
+}
+
+#[test]
+fn test_parse_empty_rules() {
+    assert_eq!(
+        RulesFile::default(),
+        RulesFile::parse("# nothing\n", "").unwrap()
+    );
+}
+
+#[test]
+fn test_list_positions() {
+    assert_eq!(Some(0), list_position(0, 3));
+    assert_eq!(Some(2), list_position(-1, 3));
+    assert_eq!(Some(0), list_position(-3, 3));
+    assert_eq!(None, list_position(3, 3));
+    assert_eq!(None, list_position(-4, 3));
+    assert_eq!(None, list_position(-1, 0));
+
+    assert_eq!(0..2, slice_range(Some(0), Some(2), 3));
+    assert_eq!(1..3, slice_range(Some(1), None, 3));
+    assert_eq!(0..2, slice_range(None, Some(-1), 3));
+    assert_eq!(1..3, slice_range(Some(-2), Some(10), 3));
+    assert_eq!(2..2, slice_range(Some(2), Some(1), 3));
+    assert_eq!(0..0, slice_range(None, None, 0));
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use crate::rules::errors::Error;
+use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::RulesFile;
+use crate::rules::parser::{access, Span};
+use crate::rules::path_value::{Path, PathAwareValue};
+use crate::rules::values::read_from;
+use crate::rules::{EvalContext, QueryResult};
+
+lazy_static! {
+    /// the documents read by `from`, so each file is only read once however many rules load
+    /// values from it
+    static ref DOCUMENTS: Mutex<HashMap<String, PathAwareValue>> = Mutex::new(HashMap::new());
+}
+
+/// the values the query resolves to in the JSON or YAML file, as a list, for
+/// `from("approved-amis.json", "amis[*].id")` to stand for the values when the rules are parsed.
+/// Relative paths are resolved from the current directory
+///
+/// This function will return an error if
+/// - the file cannot be read, or is not JSON or YAML
+/// - the query is not a valid query
+/// - the query does not resolve to any value
+pub(crate) fn values_from(file: &str, query: &str) -> crate::rules::Result<PathAwareValue> {
+    let document = document(file)?;
+
+    let span = Span::new_extra(query, file);
+    let access_query = match access(span) {
+        Ok((rest, access_query)) if rest.fragment().trim().is_empty() => access_query,
+        _ => {
+            return Err(Error::ParseError(format!(
+                "{query} is not a valid query to load values from {file} with"
+            )))
+        }
+    };
+
+    let rules = RulesFile {
+        assignments: vec![],
+        guard_rules: vec![],
+        parameterized_rules: vec![],
+    };
+    let mut scope = root_scope(&rules, Rc::new(document));
+    let values = scope
+        .query(&access_query.query)?
+        .into_iter()
+        .filter_map(|result| match result {
+            QueryResult::Literal(value) | QueryResult::Resolved(value) => {
+                Some(value.as_ref().clone())
+            }
+            QueryResult::UnResolved(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    if values.is_empty() {
+        return Err(Error::ParseError(format!(
+            "{query} does not resolve to any value in {file}"
+        )));
+    }
+
+    Ok(PathAwareValue::List((Path::root(), values)))
+}
+
+fn document(file: &str) -> crate::rules::Result<PathAwareValue> {
+    let mut documents = DOCUMENTS
+        .lock()
+        .map_err(|_| Error::IllegalArguments(String::from("loaded documents are unavailable")))?;
+    if let Some(document) = documents.get(file) {
+        return Ok(document.clone());
+    }
+
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| Error::IllegalArguments(format!("Unable to load values from {file}, {e}")))?;
+    let document = PathAwareValue::try_from(read_from(&content)?)?;
+    documents.insert(file.to_string(), document.clone());
+
+    Ok(document)
+}
+
+#[cfg(test)]
+#[path = "external_tests.rs"]
+mod external_tests;
@@ -0,0 +1,63 @@
+use super::*;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::Status;
+use pretty_assertions::assert_eq;
+
+const APPROVED_AMIS: &str = "resources/from/approved-amis.json";
+
+#[test]
+fn test_values_from() -> crate::rules::Result<()> {
+    let values = values_from(APPROVED_AMIS, "amis[*].id")?;
+    let ids = match &values {
+        PathAwareValue::List((_, ids)) => ids
+            .iter()
+            .map(|id| match id {
+                PathAwareValue::String((_, id)) => id.as_str(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>(),
+        _ => unreachable!(),
+    };
+    assert_eq!(vec!["ami-0abc", "ami-0def"], ids);
+
+    Ok(())
+}
+
+#[test]
+fn test_values_from_errors() {
+    assert!(matches!(
+        values_from("resources/from/missing.json", "amis[*].id"),
+        Err(Error::IllegalArguments(_))
+    ));
+    assert!(matches!(
+        values_from(APPROVED_AMIS, "images[*].id"),
+        Err(Error::ParseError(_))
+    ));
+}
+
+#[test]
+fn test_in_values_from() -> crate::rules::Result<()> {
+    let rules = format!(
+        r#"let approved = from("{APPROVED_AMIS}", "amis[*].id")
+
+        rule approved_amis {{
+            Resources.*[ Type == 'AWS::EC2::Instance' ].Properties.ImageId IN from("{APPROVED_AMIS}", "amis[*].id")
+        }}
+
+        rule approved_amis_variable {{
+            Resources.*[ Type == 'AWS::EC2::Instance' ].Properties.ImageId IN %approved
+        }}"#
+    );
+    let rules_file = RulesFile::try_from(rules.as_str())?;
+
+    for (image_id, expected) in [("ami-0def", Status::PASS), ("ami-0bad", Status::FAIL)] {
+        let template = format!(
+            r#"{{"Resources": {{"web": {{"Type": "AWS::EC2::Instance", "Properties": {{"ImageId": "{image_id}"}}}}}}}}"#
+        );
+        let value = Rc::new(PathAwareValue::try_from(read_from(&template)?)?);
+        let mut root = root_scope(&rules_file, value);
+        assert_eq!(expected, eval_rules_file(&rules_file, &mut root, None)?);
+    }
+
+    Ok(())
+}
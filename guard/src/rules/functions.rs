@@ -1,4 +1,6 @@
 pub(crate) mod collections;
 pub mod converters;
+pub(crate) mod custom;
 pub mod date_time;
 pub(crate) mod strings;
+pub(crate) mod tags;
@@ -1,5 +1,7 @@
+use std::cmp::Ordering;
+
 use crate::rules::{
-    path_value::{Path, PathAwareValue},
+    path_value::{compare_values, MapValue, Path, PathAwareValue},
     QueryResult,
 };
 
@@ -22,6 +24,143 @@ pub(crate) fn count(args: &[QueryResult]) -> PathAwareValue {
     }
 }
 
+/// the values a query resolved to, with the elements of lists taken on their own so that a
+/// variable holding a list literal like `[22, 443]` can be passed as is
+pub(crate) fn elements(args: &[QueryResult]) -> Vec<&PathAwareValue> {
+    args.iter()
+        .filter_map(|query| match query {
+            QueryResult::Literal(val) | QueryResult::Resolved(val) => Some(val.as_ref()),
+            QueryResult::UnResolved(_) => None,
+        })
+        .flat_map(|value| match value {
+            PathAwareValue::List((_, list)) => list.iter().collect(),
+            _ => vec![value],
+        })
+        .collect()
+}
+
+/// the values of the first set, without duplicates, for which `keep` holds given whether the
+/// second set contains them
+fn select(
+    first: &[QueryResult],
+    second: &[QueryResult],
+    keep: bool,
+) -> Vec<Option<PathAwareValue>> {
+    let second = elements(second);
+    let mut selected: Vec<&PathAwareValue> = vec![];
+    for value in elements(first) {
+        if second.contains(&value) == keep && !selected.contains(&value) {
+            selected.push(value);
+        }
+    }
+
+    selected.into_iter().cloned().map(Some).collect()
+}
+
+pub(crate) fn intersection(
+    first: &[QueryResult],
+    second: &[QueryResult],
+) -> Vec<Option<PathAwareValue>> {
+    select(first, second, true)
+}
+
+pub(crate) fn difference(
+    first: &[QueryResult],
+    second: &[QueryResult],
+) -> Vec<Option<PathAwareValue>> {
+    select(first, second, false)
+}
+
+pub(crate) fn union(first: &[QueryResult], second: &[QueryResult]) -> Vec<Option<PathAwareValue>> {
+    let mut union: Vec<&PathAwareValue> = vec![];
+    for value in elements(first).into_iter().chain(elements(second)) {
+        if !union.contains(&value) {
+            union.push(value);
+        }
+    }
+
+    union.into_iter().cloned().map(Some).collect()
+}
+
+/// whether every value of the first set is in the second, which holds for an empty first set
+pub(crate) fn subset_of(first: &[QueryResult], second: &[QueryResult]) -> PathAwareValue {
+    let values = elements(first);
+    let second = elements(second);
+    let path = values
+        .first()
+        .map_or_else(Path::root, |value| value.self_path().clone());
+
+    PathAwareValue::Bool((path, values.iter().all(|value| second.contains(value))))
+}
+
+/// the values without their duplicates, in the order they were first found
+pub(crate) fn distinct(args: &[QueryResult]) -> Vec<Option<PathAwareValue>> {
+    let mut distinct: Vec<&PathAwareValue> = vec![];
+    for value in elements(args) {
+        if !distinct.contains(&value) {
+            distinct.push(value);
+        }
+    }
+
+    distinct.into_iter().cloned().map(Some).collect()
+}
+
+/// the values in ascending order, as a single list to compare with the list they came from.
+/// Strings, numbers and characters are sorted, other values are not comparable
+pub(crate) fn sorted(args: &[QueryResult]) -> crate::rules::Result<PathAwareValue> {
+    let path = args
+        .iter()
+        .find_map(|query| match query {
+            QueryResult::Literal(val) | QueryResult::Resolved(val) => Some(val.self_path().clone()),
+            QueryResult::UnResolved(_) => None,
+        })
+        .unwrap_or_else(Path::root);
+
+    let mut error = None;
+    let mut values = elements(args);
+    values.sort_by(|first, second| {
+        compare_values(first, second).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            Ordering::Equal
+        })
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(PathAwareValue::List((
+            path,
+            values.into_iter().cloned().collect(),
+        ))),
+    }
+}
+
+/// the structs among the values, taken the same way as by [elements], others are left out
+fn structs(args: &[QueryResult]) -> Vec<&MapValue> {
+    elements(args)
+        .into_iter()
+        .filter_map(|value| match value {
+            PathAwareValue::Map((_, map)) => Some(map),
+            _ => None,
+        })
+        .collect()
+}
+
+/// the keys of the structs among the values, as strings with the paths of the keys
+pub(crate) fn keys(args: &[QueryResult]) -> Vec<Option<PathAwareValue>> {
+    structs(args)
+        .into_iter()
+        .flat_map(|map| map.keys.iter().cloned().map(Some))
+        .collect()
+}
+
+/// the values of the structs among the values
+pub(crate) fn values(args: &[QueryResult]) -> Vec<Option<PathAwareValue>> {
+    structs(args)
+        .into_iter()
+        .flat_map(|map| map.values.values().cloned().map(Some))
+        .collect()
+}
+
 #[cfg(test)]
 #[path = "collections_tests.rs"]
 mod collections_tests;
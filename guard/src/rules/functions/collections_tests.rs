@@ -68,3 +68,198 @@ fn test_count_function() -> crate::rules::Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_set_functions() -> crate::rules::Result<()> {
+    let value_str = r#"
+    Resources:
+      sg:
+        Type: AWS::EC2::SecurityGroup
+        Properties:
+          Ports: [22, 443, 8080]
+          Approved: [22, 80, 443]
+    "#;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(value_str)?)?;
+
+    let mut eval = BasicQueryTesting {
+        root: Rc::new(value),
+        recorder: None,
+    };
+    let query = AccessQuery::try_from(r#"Resources.sg.Properties.Ports[*]"#)?;
+    let ports = eval.query(&query.query)?;
+    // a list resolved as a single value is taken element by element
+    let query = AccessQuery::try_from(r#"Resources.sg.Properties.Approved"#)?;
+    let approved = eval.query(&query.query)?;
+
+    let ints = |values: Vec<Option<PathAwareValue>>| {
+        values
+            .into_iter()
+            .map(|value| match value {
+                Some(PathAwareValue::Int((_, i))) => i,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(vec![22, 443], ints(intersection(&ports, &approved)));
+    assert_eq!(vec![8080], ints(difference(&ports, &approved)));
+    assert_eq!(vec![80], ints(difference(&approved, &ports)));
+    assert_eq!(vec![22, 443, 8080, 80], ints(union(&ports, &approved)));
+    assert_eq!(vec![22, 443, 8080], ints(union(&ports, &ports)));
+
+    let common = intersection(&ports, &approved);
+    assert_eq!(
+        "/Resources/sg/Properties/Ports/0",
+        common[0].as_ref().unwrap().self_path().0
+    );
+
+    match subset_of(&ports, &approved) {
+        PathAwareValue::Bool((_, subset)) => assert!(!subset),
+        _ => unreachable!(),
+    }
+    match subset_of(&[], &ports) {
+        PathAwareValue::Bool((_, subset)) => assert!(subset),
+        _ => unreachable!(),
+    }
+
+    let common = common
+        .into_iter()
+        .flatten()
+        .map(|value| QueryResult::Resolved(Rc::new(value)))
+        .collect::<Vec<_>>();
+    match subset_of(&common, &approved) {
+        PathAwareValue::Bool((_, subset)) => assert!(subset),
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_distinct_and_sorted() -> crate::rules::Result<()> {
+    let value_str = r#"
+    Resources:
+      sg:
+        Type: AWS::EC2::SecurityGroup
+        Properties:
+          SecurityGroupIngress:
+            - CidrIp: 10.0.0.0/16
+            - CidrIp: 10.1.0.0/16
+            - CidrIp: 10.0.0.0/16
+          Ports: [443, 22, 8080]
+          Mixed: [22, "ssh", {}]
+    "#;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(value_str)?)?;
+
+    let mut eval = BasicQueryTesting {
+        root: Rc::new(value),
+        recorder: None,
+    };
+    let query = AccessQuery::try_from(r#"Resources.sg.Properties.SecurityGroupIngress[*].CidrIp"#)?;
+    let cidrs = eval.query(&query.query)?;
+
+    let distinct = distinct(&cidrs);
+    assert_eq!(
+        vec!["10.0.0.0/16", "10.1.0.0/16"],
+        distinct
+            .iter()
+            .map(|value| match value {
+                Some(PathAwareValue::String((_, cidr))) => cidr.as_str(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "/Resources/sg/Properties/SecurityGroupIngress/1/CidrIp",
+        distinct[1].as_ref().unwrap().self_path().0
+    );
+
+    let query = AccessQuery::try_from(r#"Resources.sg.Properties.Ports"#)?;
+    let ports = eval.query(&query.query)?;
+    match sorted(&ports)? {
+        PathAwareValue::List((path, list)) => {
+            assert_eq!("/Resources/sg/Properties/Ports", path.0);
+            assert_eq!(
+                vec![22, 443, 8080],
+                list.iter()
+                    .map(|value| match value {
+                        PathAwareValue::Int((_, port)) => *port,
+                        _ => unreachable!(),
+                    })
+                    .collect::<Vec<_>>()
+            );
+        }
+        _ => unreachable!(),
+    }
+
+    let query = AccessQuery::try_from(r#"Resources.sg.Properties.Mixed"#)?;
+    let mixed = eval.query(&query.query)?;
+    assert!(sorted(&mixed).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_keys_and_values() -> crate::rules::Result<()> {
+    let value_str = r#"
+    Resources:
+      param:
+        Type: AWS::SSM::Parameter
+        Properties:
+          Tags:
+            Owner: platform
+            CostCenter: "1234"
+      function:
+        Type: AWS::Serverless::Function
+        Properties:
+          Tags:
+            Owner: payments
+          Runtime: python3.12
+    "#;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(value_str)?)?;
+
+    let mut eval = BasicQueryTesting {
+        root: Rc::new(value),
+        recorder: None,
+    };
+    let query = AccessQuery::try_from(r#"Resources.*.Properties.Tags"#)?;
+    let tags = eval.query(&query.query)?;
+
+    let keys = keys(&tags);
+    assert_eq!(
+        vec!["Owner", "CostCenter", "Owner"],
+        keys.iter()
+            .map(|value| match value {
+                Some(PathAwareValue::String((_, key))) => key.as_str(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "/Resources/param/Properties/Tags/CostCenter",
+        keys[1].as_ref().unwrap().self_path().0
+    );
+
+    let values = values(&tags);
+    assert_eq!(
+        vec!["platform", "1234", "payments"],
+        values
+            .iter()
+            .map(|value| match value {
+                Some(PathAwareValue::String((_, value))) => value.as_str(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "/Resources/function/Properties/Tags/Owner",
+        values[2].as_ref().unwrap().self_path().0
+    );
+
+    let query = AccessQuery::try_from(r#"Resources.*.Properties.Runtime"#)?;
+    let runtimes = eval.query(&query.query)?;
+    assert!(keys(&runtimes).is_empty());
+    assert!(values(&runtimes).is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::rules::errors::Error;
+use crate::rules::eval_context::FunctionName;
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::QueryResult;
+
+/// A function rules files can call in `let` assignments alongside the built-in ones, for
+/// applications embedding Guard to look values up in their own systems, e.g. an internal registry
+/// of approved AMIs. Functions are registered with a [`FunctionRegistry`].
+pub trait CustomFunction: Send + Sync {
+    /// the number of arguments the function is called with, checked when the function is called
+    fn number_of_args(&self) -> usize;
+
+    /// called with the values each argument resolved to, returns the values the assignment
+    /// resolves to
+    fn call(&self, args: &[Vec<serde_json::Value>]) -> Result<Vec<serde_json::Value>, Error>;
+}
+
+/// The custom functions the rules evaluated with the registry can call by name. Names are looked
+/// up when the rules are evaluated, so the same rules can be evaluated with different registries,
+/// e.g. with `ValidateBuilder::functions` or `CompiledRules::with_functions`.
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, Arc<dyn CustomFunction>>,
+}
+
+impl FunctionRegistry {
+    pub fn new() -> FunctionRegistry {
+        FunctionRegistry::default()
+    }
+
+    /// Makes the function callable by the given name, replacing the function registered by that
+    /// name before.
+    ///
+    /// This function will return an error if the name is not a valid function name, or the name
+    /// of a built-in function
+    pub fn register(
+        &mut self,
+        name: &str,
+        function: impl CustomFunction + 'static,
+    ) -> crate::rules::Result<()> {
+        let mut chars = name.chars();
+        let valid = chars.next().map_or(false, char::is_alphabetic)
+            && chars.all(|c| c.is_alphanumeric() || c == '_');
+        if !valid {
+            return Err(Error::IllegalArguments(format!(
+                "'{name}' is not a valid function name, it must start with a letter followed by letters, digits or '_'"
+            )));
+        }
+
+        if FunctionName::built_in(name).is_some() {
+            return Err(Error::IllegalArguments(format!(
+                "'{name}' is a built-in function and cannot be replaced"
+            )));
+        }
+
+        self.functions.insert(name.to_string(), Arc::new(function));
+
+        Ok(())
+    }
+
+    /// the names of the registered functions, in no particular order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str)
+    }
+}
+
+impl Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut names = self.names().collect::<Vec<_>>();
+        names.sort_unstable();
+        f.debug_struct("FunctionRegistry")
+            .field("functions", &names)
+            .finish()
+    }
+}
+
+/// registries are the same when they hold the same functions under the same names
+impl PartialEq for FunctionRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.functions.len() == other.functions.len()
+            && self.functions.iter().all(|(name, function)| {
+                other.functions.get(name).map_or(false, |other| {
+                    std::ptr::eq(
+                        Arc::as_ptr(function) as *const (),
+                        Arc::as_ptr(other) as *const (),
+                    )
+                })
+            })
+    }
+}
+
+impl Eq for FunctionRegistry {}
+
+/// calls the custom function of the registry with the values of the arguments as JSON, unresolved
+/// queries have no value to pass
+pub(crate) fn call_custom(
+    functions: Option<&FunctionRegistry>,
+    name: &str,
+    args: &[Vec<QueryResult>],
+) -> crate::rules::Result<Vec<Option<PathAwareValue>>> {
+    let function = functions
+        .and_then(|functions| functions.functions.get(name))
+        .ok_or_else(|| {
+            Error::IllegalArguments(format!("No function with the name '{name}' exists."))
+        })?;
+
+    if args.len() != function.number_of_args() {
+        return Err(Error::IllegalArguments(format!(
+            "function: {name} requires: {} parameters to be passed, but received: {}",
+            function.number_of_args(),
+            args.len()
+        )));
+    }
+
+    let args = args
+        .iter()
+        .map(|arg| {
+            arg.iter()
+                .filter_map(|result| match result {
+                    QueryResult::Literal(value) | QueryResult::Resolved(value) => Some(value),
+                    QueryResult::UnResolved(_) => None,
+                })
+                .map(|value| {
+                    let (_, value): (String, serde_json::Value) = value.as_ref().try_into()?;
+                    Ok(value)
+                })
+                .collect::<crate::rules::Result<Vec<_>>>()
+        })
+        .collect::<crate::rules::Result<Vec<_>>>()?;
+
+    function
+        .call(&args)?
+        .into_iter()
+        .map(|value| PathAwareValue::try_from(value).map(Some))
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "custom_tests.rs"]
+mod custom_tests;
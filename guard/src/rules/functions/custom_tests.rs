@@ -0,0 +1,117 @@
+use pretty_assertions::assert_eq;
+use std::rc::Rc;
+
+use super::*;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::RulesFile;
+use crate::rules::Status;
+
+struct ApprovedImages;
+
+impl CustomFunction for ApprovedImages {
+    fn number_of_args(&self) -> usize {
+        1
+    }
+
+    fn call(&self, args: &[Vec<serde_json::Value>]) -> Result<Vec<serde_json::Value>, Error> {
+        Ok(args[0]
+            .iter()
+            .map(|image| serde_json::Value::Bool(image == "ami-approved"))
+            .collect())
+    }
+}
+
+fn approved_images() -> Option<Arc<FunctionRegistry>> {
+    let mut functions = FunctionRegistry::new();
+    functions
+        .register("approved_images", ApprovedImages)
+        .unwrap();
+    Some(Arc::new(functions))
+}
+
+fn instance(image: &str) -> crate::rules::Result<PathAwareValue> {
+    PathAwareValue::try_from(serde_json::json!({
+        "Resources": { "instance": { "Properties": { "ImageId": image } } }
+    }))
+}
+
+#[test]
+fn test_custom_function_in_rules() -> crate::rules::Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule approved_images {
+        let approved = approved_images(Resources.*.Properties.ImageId)
+        %approved == true
+    }
+    "#,
+    )?;
+
+    for (image, expected) in [("ami-approved", Status::PASS), ("ami-other", Status::FAIL)] {
+        let mut root =
+            root_scope(&rules_file, Rc::new(instance(image)?)).with_functions(approved_images());
+        assert_eq!(expected, eval_rules_file(&rules_file, &mut root, None)?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_function_number_of_args() -> crate::rules::Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule approved_images {
+        let approved = approved_images(Resources.*, Parameters.*)
+        %approved == true
+    }
+    "#,
+    )?;
+
+    let mut root = root_scope(&rules_file, Rc::new(instance("ami-approved")?))
+        .with_functions(approved_images());
+    assert!(matches!(
+        eval_rules_file(&rules_file, &mut root, None),
+        Err(Error::IllegalArguments(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_function_not_registered() -> crate::rules::Result<()> {
+    // unknown names parse, the registry of the evaluation decides whether they exist
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule approved_images {
+        let approved = approved_images(Resources.*.Properties.ImageId)
+        %approved == true
+    }
+    "#,
+    )?;
+
+    let mut root = root_scope(&rules_file, Rc::new(instance("ami-approved")?));
+    assert!(matches!(
+        eval_rules_file(&rules_file, &mut root, None),
+        Err(Error::IllegalArguments(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_register_function_invalid_names() {
+    let mut functions = FunctionRegistry::new();
+    assert!(matches!(
+        functions.register("count", ApprovedImages),
+        Err(Error::IllegalArguments(_))
+    ));
+    assert!(matches!(
+        functions.register("approved-images", ApprovedImages),
+        Err(Error::IllegalArguments(_))
+    ));
+    assert!(matches!(
+        functions.register("", ApprovedImages),
+        Err(Error::IllegalArguments(_))
+    ));
+    assert_eq!(0, functions.names().count());
+}
@@ -1,8 +1,8 @@
-use crate::rules::path_value::{Path, PathAwareValue};
+use crate::rules::path_value::{MapValue, Path, PathAwareValue};
 use crate::rules::QueryResult;
 
 use crate::rules::errors::Error;
-use fancy_regex::Regex;
+use crate::rules::regex_cache;
 use nom::Slice;
 use std::convert::TryFrom;
 
@@ -35,6 +35,73 @@ pub(crate) fn url_decode(
     Ok(aggr)
 }
 
+/// the value of the environment variable `name` as a string, or the `default` values when it is
+/// not set
+pub(crate) fn env(
+    name: &str,
+    default: &[QueryResult],
+) -> crate::rules::Result<Vec<Option<PathAwareValue>>> {
+    match std::env::var(name) {
+        Ok(value) => Ok(vec![Some(PathAwareValue::String((Path::root(), value)))]),
+        Err(_) => Ok(default
+            .iter()
+            .map(|entry| match entry {
+                QueryResult::Literal(v) | QueryResult::Resolved(v) => Some((**v).clone()),
+                QueryResult::UnResolved(_) => None,
+            })
+            .collect()),
+    }
+}
+
+/// the parts of an ARN, `arn:partition:service:region:account:resource`, as a struct with the
+/// keys partition, service, region, account and resource. Values that are not ARNs yield None
+pub(crate) fn parse_arn(args: &[QueryResult]) -> crate::rules::Result<Vec<Option<PathAwareValue>>> {
+    let mut aggr = Vec::with_capacity(args.len());
+    for entry in args.iter() {
+        match entry {
+            QueryResult::Literal(v) | QueryResult::Resolved(v) => {
+                if let PathAwareValue::String((path, val)) = &**v {
+                    aggr.push(arn_parts(path, val));
+                } else {
+                    aggr.push(None);
+                }
+            }
+            _ => aggr.push(None),
+        }
+    }
+    Ok(aggr)
+}
+
+const ARN_PARTS: [&str; 5] = ["partition", "service", "region", "account", "resource"];
+
+fn arn_parts(path: &Path, arn: &str) -> Option<PathAwareValue> {
+    let mut parts = arn.splitn(6, ':');
+    if parts.next()? != "arn" {
+        return None;
+    }
+
+    let parts = parts.collect::<Vec<_>>();
+    // region and account are empty for global resources, e.g. arn:aws:s3:::bucket
+    if parts.len() != ARN_PARTS.len() || parts[0].is_empty() || parts[1].is_empty() {
+        return None;
+    }
+
+    let mut keys = Vec::with_capacity(ARN_PARTS.len());
+    let mut values = indexmap::IndexMap::with_capacity(ARN_PARTS.len());
+    for (key, part) in ARN_PARTS.iter().zip(parts) {
+        let sub_path = path.extend_str(key);
+        keys.push(PathAwareValue::String((sub_path.clone(), key.to_string())));
+        values.insert(
+            key.to_string(),
+            PathAwareValue::String((sub_path, part.to_string())),
+        );
+    }
+    Some(PathAwareValue::Map((
+        path.clone(),
+        MapValue { keys, values },
+    )))
+}
+
 pub(crate) fn json_parse(
     args: &[QueryResult],
 ) -> crate::rules::Result<Vec<Option<PathAwareValue>>> {
@@ -65,7 +132,7 @@ pub(crate) fn regex_replace(
         match entry {
             QueryResult::Literal(v) | QueryResult::Resolved(v) => {
                 if let PathAwareValue::String((path, val)) = &**v {
-                    let regex = Regex::try_from(extract_expr).map_err(Box::new)?;
+                    let regex = regex_cache::compiled(extract_expr)?;
                     let mut replaced = String::with_capacity(replace_expr.len() * 2);
                     for cap in regex.captures_iter(val) {
                         cap.map_err(Box::new)?.expand(replace_expr, &mut replaced);
@@ -83,6 +150,32 @@ pub(crate) fn regex_replace(
     Ok(aggr)
 }
 
+/// whether each string matches the pattern, ignoring case, as a bool. Values that are not
+/// strings yield None
+pub(crate) fn matches_ci(
+    args: &[QueryResult],
+    pattern: &str,
+) -> crate::rules::Result<Vec<Option<PathAwareValue>>> {
+    let regex = regex_cache::compiled(&format!("(?i){pattern}"))?;
+    let mut aggr = Vec::with_capacity(args.len());
+    for entry in args.iter() {
+        match entry {
+            QueryResult::Literal(v) | QueryResult::Resolved(v) => {
+                if let PathAwareValue::String((path, val)) = &**v {
+                    let matched = regex.is_match(val).map_err(Box::new)?;
+                    aggr.push(Some(PathAwareValue::Bool((path.clone(), matched))));
+                } else {
+                    aggr.push(None);
+                }
+            }
+            _ => {
+                aggr.push(None);
+            }
+        }
+    }
+    Ok(aggr)
+}
+
 pub(crate) fn substring(
     args: &[QueryResult],
     from: usize,
@@ -196,6 +289,54 @@ pub(crate) fn join(args: &[QueryResult], delimiter: &str) -> crate::rules::Resul
     }
 }
 
+/// the strings the parts of an interpolated string write one after the other, one for each
+/// combination of the values of the variables in it
+pub(crate) fn interpolate(
+    parts: &[Vec<QueryResult>],
+) -> crate::rules::Result<Vec<Option<PathAwareValue>>> {
+    let mut path = None;
+    let mut strings = vec![String::new()];
+    for values in parts {
+        let mut texts = Vec::with_capacity(values.len());
+        for value in values {
+            let value = match value {
+                QueryResult::Literal(value) | QueryResult::Resolved(value) => value,
+                QueryResult::UnResolved(ur) => {
+                    return Err(Error::IncompatibleError(format!(
+                        "Interpolating unresolved values is not allowed {}, unsatisfied part {}",
+                        ur.traversed_to, ur.remaining_query
+                    )));
+                }
+            };
+            path.get_or_insert_with(|| value.self_path().clone());
+            texts.push(match &**value {
+                PathAwareValue::String((_, text)) => text.clone(),
+                PathAwareValue::Char((_, c)) => c.to_string(),
+                PathAwareValue::Int((_, i)) => i.to_string(),
+                PathAwareValue::Float((_, f)) => f.to_string(),
+                PathAwareValue::Bool((_, b)) => b.to_string(),
+                _ => {
+                    return Err(Error::IncompatibleError(format!(
+                        "Interpolating non scalar values {}",
+                        value
+                    )));
+                }
+            });
+        }
+
+        strings = strings
+            .iter()
+            .flat_map(|string| texts.iter().map(move |text| format!("{string}{text}")))
+            .collect();
+    }
+
+    let path = path.unwrap_or_else(Path::root);
+    Ok(strings
+        .into_iter()
+        .map(|string| Some(PathAwareValue::String((path.clone(), string))))
+        .collect())
+}
+
 #[cfg(test)]
 #[path = "strings_tests.rs"]
 mod strings_tests;
@@ -96,6 +96,34 @@ fn test_regex_replace() -> crate::rules::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_matches_ci() -> crate::rules::Result<()> {
+    let value_str = r#"
+    Names: [PROD-logs, prod-data, dev-prod, 42]
+    "#;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(value_str)?)?;
+
+    let mut eval = BasicQueryTesting {
+        root: Rc::new(value),
+        recorder: None,
+    };
+    let query = AccessQuery::try_from(r#"Names[*]"#)?;
+    let results = eval.query(&query.query)?;
+
+    let matched = matches_ci(&results, "^prod-")?
+        .into_iter()
+        .map(|value| match value {
+            Some(PathAwareValue::Bool((_, matched))) => Some(matched),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(vec![Some(true), Some(true), Some(false), None], matched);
+
+    assert!(matches_ci(&results, "(prod").is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_substring() -> crate::rules::Result<()> {
     let value_str = r#"
@@ -136,3 +164,121 @@ fn test_substring() -> crate::rules::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_parse_arn() -> crate::rules::Result<()> {
+    let value_str = r#"
+    Arns:
+      - arn:aws:newservice:us-west-2:123456789012:Table/extracted
+      - arn:aws-us-gov:s3:::bucket/key:with:colons
+      - not-an-arn
+      - arn:aws:iam
+      - 42
+    "#;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(value_str)?)?;
+
+    let mut eval = BasicQueryTesting {
+        root: Rc::new(value),
+        recorder: None,
+    };
+    let query = AccessQuery::try_from(r#"Arns[*]"#)?;
+    let results = eval.query(&query.query)?;
+
+    let parsed = parse_arn(&results)?;
+    assert_eq!(parsed.len(), 5);
+
+    let parts = |value: &Option<PathAwareValue>| match value {
+        Some(PathAwareValue::Map((_, map))) => map
+            .values
+            .iter()
+            .map(|(key, value)| match value {
+                PathAwareValue::String((_, part)) => (key.clone(), part.clone()),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>(),
+        _ => unreachable!(),
+    };
+    let expected = |parts: [&str; 5]| {
+        ["partition", "service", "region", "account", "resource"]
+            .iter()
+            .zip(parts)
+            .map(|(key, part)| (key.to_string(), part.to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        expected([
+            "aws",
+            "newservice",
+            "us-west-2",
+            "123456789012",
+            "Table/extracted"
+        ]),
+        parts(&parsed[0])
+    );
+    assert_eq!(
+        expected(["aws-us-gov", "s3", "", "", "bucket/key:with:colons"]),
+        parts(&parsed[1])
+    );
+    assert!(parsed[2..].iter().all(Option::is_none));
+
+    Ok(())
+}
+
+#[test]
+fn test_env() -> crate::rules::Result<()> {
+    let default = vec![QueryResult::Literal(Rc::new(PathAwareValue::String((
+        Path::root(),
+        String::from("us-west-2"),
+    ))))];
+
+    std::env::set_var("CFN_GUARD_TEST_ENV_REGION", "eu-west-1");
+    match &env("CFN_GUARD_TEST_ENV_REGION", &default)?[..] {
+        [Some(PathAwareValue::String((_, value)))] => assert_eq!("eu-west-1", value),
+        unexpected => panic!("unexpected {unexpected:?}"),
+    }
+
+    match &env("CFN_GUARD_TEST_ENV_UNSET", &default)?[..] {
+        [Some(PathAwareValue::String((_, value)))] => assert_eq!("us-west-2", value),
+        unexpected => panic!("unexpected {unexpected:?}"),
+    }
+    assert!(env("CFN_GUARD_TEST_ENV_UNSET", &[])?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_interpolate() -> crate::rules::Result<()> {
+    let string = |text: &str| {
+        QueryResult::Literal(Rc::new(PathAwareValue::String((
+            Path::root(),
+            text.to_string(),
+        ))))
+    };
+    let parts = vec![
+        vec![string("arn:aws:s3:::")],
+        vec![string("logs"), string("assets")],
+        vec![string("-")],
+        vec![QueryResult::Resolved(Rc::new(PathAwareValue::Int((
+            Path::root(),
+            1,
+        ))))],
+    ];
+
+    let interpolated = interpolate(&parts)?
+        .into_iter()
+        .map(|value| match value {
+            Some(PathAwareValue::String((_, text))) => text,
+            unexpected => panic!("unexpected {unexpected:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec!["arn:aws:s3:::logs-1", "arn:aws:s3:::assets-1"],
+        interpolated
+    );
+
+    let list = PathAwareValue::List((Path::root(), vec![]));
+    assert!(interpolate(&[vec![QueryResult::Literal(Rc::new(list))]]).is_err());
+
+    Ok(())
+}
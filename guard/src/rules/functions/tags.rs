@@ -0,0 +1,84 @@
+use crate::rules::functions::collections::elements;
+use crate::rules::{path_value::PathAwareValue, QueryResult};
+
+/// the required tag names each resource is missing, one value per missing tag with the path of
+/// the tags of the resource, or of the resource when it has none. Both encodings of tags are
+/// understood, a list of `Key`/`Value` structs as most resource types use, and a struct of tag
+/// names to values as `AWS::SSM::Parameter` or `AWS::Serverless::Function` use. Resources are
+/// either the resources of a template, whose `Properties.Tags` are looked at, or the tags of
+/// resources themselves
+pub(crate) fn required_tags(
+    required: &[QueryResult],
+    resources: &[QueryResult],
+) -> Vec<Option<PathAwareValue>> {
+    let required = elements(required)
+        .into_iter()
+        .filter_map(|name| match name {
+            PathAwareValue::String((_, name)) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut missing = vec![];
+    for resource in resources {
+        let resource = match resource {
+            QueryResult::Literal(value) | QueryResult::Resolved(value) => value.as_ref(),
+            QueryResult::UnResolved(_) => continue,
+        };
+
+        let (at, names) = match tags_of(resource) {
+            Some(tags) => (tags, tag_names(tags)),
+            None => (resource, vec![]),
+        };
+        for name in &required {
+            if !names.contains(name) {
+                missing.push(Some(PathAwareValue::String((
+                    at.self_path().clone(),
+                    name.to_string(),
+                ))));
+            }
+        }
+    }
+
+    missing
+}
+
+/// the tags of a resource of a template, or the value itself when it is tags already
+fn tags_of(value: &PathAwareValue) -> Option<&PathAwareValue> {
+    match value {
+        PathAwareValue::Map((_, resource))
+            if resource.values.contains_key("Type")
+                || resource.values.contains_key("Properties") =>
+        {
+            match resource.values.get("Properties") {
+                Some(PathAwareValue::Map((_, properties))) => properties.values.get("Tags"),
+                _ => None,
+            }
+        }
+        PathAwareValue::List(_) | PathAwareValue::Map(_) => Some(value),
+        _ => None,
+    }
+}
+
+fn tag_names(tags: &PathAwareValue) -> Vec<&str> {
+    match tags {
+        PathAwareValue::List((_, tags)) => tags
+            .iter()
+            .filter_map(|tag| match tag {
+                PathAwareValue::Map((_, tag)) => {
+                    match tag.values.get("Key").or_else(|| tag.values.get("key")) {
+                        Some(PathAwareValue::String((_, key))) => Some(key.as_str()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect(),
+        PathAwareValue::Map((_, tags)) => tags.values.keys().map(String::as_str).collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+#[path = "tags_tests.rs"]
+mod tags_tests;
@@ -0,0 +1,78 @@
+use super::*;
+use crate::rules::eval_context::eval_context_tests::BasicQueryTesting;
+use crate::rules::exprs::AccessQuery;
+use crate::rules::EvalContext;
+use pretty_assertions::assert_eq;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+#[test]
+fn test_required_tags() -> crate::rules::Result<()> {
+    let value_str = r#"
+    Required: [Owner, CostCenter]
+    Resources:
+      bucket:
+        Type: AWS::S3::Bucket
+        Properties:
+          Tags:
+            - Key: Owner
+              Value: infra
+            - Key: CostCenter
+              Value: "42"
+      queue:
+        Type: AWS::SQS::Queue
+        Properties:
+          Tags:
+            - Key: Owner
+              Value: infra
+      parameter:
+        Type: AWS::SSM::Parameter
+        Properties:
+          Tags:
+            CostCenter: "42"
+      topic:
+        Type: AWS::SNS::Topic
+    "#;
+    let value = PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(value_str)?)?;
+
+    let mut eval = BasicQueryTesting {
+        root: Rc::new(value),
+        recorder: None,
+    };
+    let query = AccessQuery::try_from(r#"Required"#)?;
+    let required = eval.query(&query.query)?;
+
+    let mut missing = |query: &str| -> crate::rules::Result<Vec<(String, String)>> {
+        let query = AccessQuery::try_from(query)?;
+        let resources = eval.query(&query.query)?;
+        Ok(required_tags(&required, &resources)
+            .into_iter()
+            .map(|value| match value {
+                Some(PathAwareValue::String((path, name))) => (path.0, name),
+                _ => unreachable!(),
+            })
+            .collect())
+    };
+
+    let expected = vec![
+        (
+            String::from("/Resources/queue/Properties/Tags"),
+            String::from("CostCenter"),
+        ),
+        (
+            String::from("/Resources/parameter/Properties/Tags"),
+            String::from("Owner"),
+        ),
+        (String::from("/Resources/topic"), String::from("Owner")),
+        (String::from("/Resources/topic"), String::from("CostCenter")),
+    ];
+    assert_eq!(expected, missing("Resources.*")?);
+    // the tags of the resources can be passed instead
+    assert_eq!(
+        expected[..2].to_vec(),
+        missing("Resources.*.Properties.Tags")?
+    );
+    assert_eq!(Vec::<(String, String)>::new(), missing("Resources.bucket")?);
+
+    Ok(())
+}
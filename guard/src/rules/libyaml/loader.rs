@@ -21,6 +21,8 @@ pub struct Loader {
     documents: Vec<MarkedValue>,
     last_container_index: Vec<usize>,
     func_support_index: Vec<(usize, (String, Location))>,
+    /// how deeply mappings and lists may nest, unlimited when None
+    max_depth: Option<usize>,
 }
 
 impl Loader {
@@ -28,37 +30,72 @@ impl Loader {
         Loader::default()
     }
 
+    /// a loader failing on the first mapping or list nested deeper than `max_depth`, before the
+    /// values of a hostile document are all kept in memory
+    pub(crate) fn with_max_depth(max_depth: Option<usize>) -> Loader {
+        Loader {
+            max_depth,
+            ..Loader::default()
+        }
+    }
+
     pub(crate) fn load(&mut self, content: String) -> rules::Result<MarkedValue> {
         let mut parser = Parser::new(Cow::Borrowed(content.as_bytes()));
 
         loop {
             let (event, location) = parser.next()?;
-            {
-                match event {
-                    Event::StreamStart | Event::StreamEnd | Event::DocumentStart => {}
-                    Event::DocumentEnd => {
-                        self.documents.push(self.stack.pop().unwrap());
-                        self.stack.clear();
-                        self.last_container_index.clear();
-                        return Ok(self.documents.pop().unwrap());
-                    }
-                    Event::MappingStart(..) => self.handle_mapping_start(location),
-                    Event::MappingEnd => self.handle_mapping_end()?,
-                    Event::SequenceStart(sequence_start) => {
-                        self.handle_sequence_start(sequence_start, location)
-                    }
-                    Event::SequenceEnd => self.handle_sequence_end(),
-                    Event::Scalar(scalar) => self.handle_scalar_event(scalar, location),
-                    Event::Alias(_) => {
-                        return Err(Error::ParseError(String::from(
-                            "Guard does not currently support aliases",
-                        )))
-                    }
-                };
-            };
+            match event {
+                Event::StreamStart | Event::StreamEnd | Event::DocumentStart => {}
+                Event::DocumentEnd => {
+                    self.end_document();
+                    return Ok(self.documents.pop().unwrap());
+                }
+                event => self.handle_event(event, location)?,
+            }
+        }
+    }
+
+    /// every document of a multi-document stream, where `load` only reads the first one
+    pub(crate) fn load_all(&mut self, content: String) -> rules::Result<Vec<MarkedValue>> {
+        let mut parser = Parser::new(Cow::Borrowed(content.as_bytes()));
+
+        loop {
+            let (event, location) = parser.next()?;
+            match event {
+                Event::StreamStart | Event::DocumentStart => {}
+                Event::StreamEnd => return Ok(std::mem::take(&mut self.documents)),
+                Event::DocumentEnd => self.end_document(),
+                event => self.handle_event(event, location)?,
+            }
         }
     }
 
+    fn end_document(&mut self) {
+        self.documents.push(self.stack.pop().unwrap());
+        self.stack.clear();
+        self.last_container_index.clear();
+    }
+
+    fn handle_event(&mut self, event: Event<'_>, location: Location) -> rules::Result<()> {
+        match event {
+            Event::MappingStart(..) => self.handle_mapping_start(location)?,
+            Event::MappingEnd => self.handle_mapping_end()?,
+            Event::SequenceStart(sequence_start) => {
+                self.handle_sequence_start(sequence_start, location)?
+            }
+            Event::SequenceEnd => self.handle_sequence_end(),
+            Event::Scalar(scalar) => self.handle_scalar_event(scalar, location),
+            Event::Alias(_) => {
+                return Err(Error::ParseError(String::from(
+                    "Guard does not currently support aliases",
+                )))
+            }
+            Event::StreamStart | Event::StreamEnd | Event::DocumentStart | Event::DocumentEnd => {}
+        };
+
+        Ok(())
+    }
+
     fn handle_scalar_event(&mut self, event: Scalar, location: Location) {
         let Scalar {
             tag, value, style, ..
@@ -128,7 +165,12 @@ impl Loader {
         }
     }
 
-    fn handle_sequence_start(&mut self, event: SequenceStart, location: Location) {
+    fn handle_sequence_start(
+        &mut self,
+        event: SequenceStart,
+        location: Location,
+    ) -> rules::Result<()> {
+        self.check_depth(&location)?;
         if let Some(tag) = &event.tag {
             let handle = tag.get_handle();
             let suffix = tag.get_suffix(handle.len());
@@ -143,6 +185,8 @@ impl Loader {
         }
         self.stack.push(MarkedValue::List(vec![], location));
         self.last_container_index.push(self.stack.len() - 1);
+
+        Ok(())
     }
 
     fn handle_mapping_end(&mut self) -> crate::rules::Result<()> {
@@ -170,10 +214,25 @@ impl Loader {
         Ok(())
     }
 
-    fn handle_mapping_start(&mut self, location: Location) {
+    fn handle_mapping_start(&mut self, location: Location) -> rules::Result<()> {
+        self.check_depth(&location)?;
         self.stack
             .push(MarkedValue::Map(indexmap::IndexMap::new(), location));
         self.last_container_index.push(self.stack.len() - 1);
+
+        Ok(())
+    }
+
+    /// fails when the mapping or list starting at the location nests deeper than `max_depth`
+    fn check_depth(&self, location: &Location) -> rules::Result<()> {
+        match self.max_depth {
+            Some(max_depth) if self.last_container_index.len() >= max_depth => {
+                Err(Error::LimitExceeded(format!(
+                    "nests mappings and lists deeper than the maximum nesting depth of {max_depth} at {location}"
+                )))
+            }
+            _ => Ok(()),
+        }
     }
 }
 
@@ -337,3 +337,32 @@ fn test_handle_null() {
 
     assert!(matches!(val, MarkedValue::String(..)));
 }
+
+#[test]
+fn yaml_loader_max_depth() {
+    let docs = "a: {b: [1, {c: 2}]}";
+
+    assert!(Loader::with_max_depth(Some(4))
+        .load(String::from(docs))
+        .is_ok());
+    assert!(Loader::with_max_depth(None)
+        .load(String::from(docs))
+        .is_ok());
+
+    match Loader::with_max_depth(Some(3)).load(String::from(docs)) {
+        Err(Error::LimitExceeded(message)) => assert!(
+            message.starts_with(
+                "nests mappings and lists deeper than the maximum nesting depth of 3 at "
+            ),
+            "{message}"
+        ),
+        result => panic!("expected the limit to be exceeded, got {result:?}"),
+    }
+
+    // a deeply nested flow sequence fails as soon as the limit is reached
+    let docs = "[".repeat(100_000);
+    assert!(matches!(
+        Loader::with_max_depth(Some(64)).load(docs),
+        Err(Error::LimitExceeded(..))
+    ));
+}
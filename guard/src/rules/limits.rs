@@ -0,0 +1,76 @@
+use std::fmt::Display;
+
+use crate::rules::errors::Error;
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::Result;
+
+/// bounds on the data files Guard reads and on the values its queries resolve to, so that a
+/// hostile or malformed data file cannot make an application embedding Guard, e.g. a lambda or
+/// a service, spend unbounded memory or time on it. Every limit is off by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputLimits {
+    /// the size of the largest data file read, in bytes
+    pub max_file_size: Option<usize>,
+    /// how many mappings and lists of a data file may nest in one another
+    pub max_nesting_depth: Option<usize>,
+    /// the most values a single query may resolve to
+    pub max_query_results: Option<usize>,
+}
+
+impl InputLimits {
+    /// This function will return an error if the size of the data file, in bytes, is larger than
+    /// `max_file_size`
+    pub(crate) fn check_file_size(&self, name: &str, size: usize) -> Result<()> {
+        match self.max_file_size {
+            Some(max_file_size) if size > max_file_size => Err(Error::LimitExceeded(format!(
+                "data file {name} is {size} bytes, larger than the maximum file size of {max_file_size} bytes"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// This function will return an error if the mappings and lists of the value nest deeper
+    /// than `max_nesting_depth`
+    pub(crate) fn check_nesting_depth(&self, name: &str, value: &PathAwareValue) -> Result<()> {
+        let max_nesting_depth = match self.max_nesting_depth {
+            Some(max_nesting_depth) => max_nesting_depth,
+            None => return Ok(()),
+        };
+
+        // walked without recursing, the value may be as deep as the parser allowed
+        let mut remaining = vec![(value, 0)];
+        while let Some((value, depth)) = remaining.pop() {
+            let children = match value {
+                PathAwareValue::Map((_, map)) => map.values.values().collect::<Vec<_>>(),
+                PathAwareValue::List((_, list)) => list.iter().collect(),
+                _ => continue,
+            };
+            if depth >= max_nesting_depth {
+                return Err(Error::LimitExceeded(format!(
+                    "data file {name} nests mappings and lists deeper than the maximum nesting depth of {max_nesting_depth} at {}",
+                    value.self_path().0
+                )));
+            }
+            remaining.extend(children.into_iter().map(|child| (child, depth + 1)));
+        }
+
+        Ok(())
+    }
+
+    /// This function will return an error if a query resolved to more than `max_query_results`
+    /// values
+    pub(crate) fn check_query_results(&self, query: impl Display, results: usize) -> Result<()> {
+        match self.max_query_results {
+            Some(max_query_results) if results > max_query_results => {
+                Err(Error::LimitExceeded(format!(
+                    "query {query} resolved to {results} values, more than the maximum of {max_query_results}"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "limits_tests.rs"]
+mod limits_tests;
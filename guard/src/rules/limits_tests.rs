@@ -0,0 +1,77 @@
+use super::*;
+use crate::rules::path_value::Path;
+use pretty_assertions::assert_eq;
+use std::convert::TryFrom;
+
+fn value(content: &str) -> PathAwareValue {
+    PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(content).unwrap()).unwrap()
+}
+
+#[test]
+fn test_no_limits() {
+    let limits = InputLimits::default();
+
+    assert!(limits.check_file_size("template.yaml", usize::MAX).is_ok());
+    assert!(limits
+        .check_nesting_depth("template.yaml", &value("a: [[[[1]]]]"))
+        .is_ok());
+    assert!(limits
+        .check_query_results("Resources.*", usize::MAX)
+        .is_ok());
+}
+
+#[test]
+fn test_check_file_size() {
+    let limits = InputLimits {
+        max_file_size: Some(10),
+        ..InputLimits::default()
+    };
+
+    assert!(limits.check_file_size("template.yaml", 10).is_ok());
+    match limits.check_file_size("template.yaml", 11) {
+        Err(Error::LimitExceeded(message)) => assert_eq!(
+            "data file template.yaml is 11 bytes, larger than the maximum file size of 10 bytes",
+            message
+        ),
+        result => panic!("expected the limit to be exceeded, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_check_nesting_depth() {
+    let limits = InputLimits {
+        max_nesting_depth: Some(3),
+        ..InputLimits::default()
+    };
+
+    assert!(limits
+        .check_nesting_depth("template.yaml", &value("a: {b: [1, 2]}"))
+        .is_ok());
+    assert!(limits
+        .check_nesting_depth("template.yaml", &PathAwareValue::Int((Path::root(), 1)))
+        .is_ok());
+    match limits.check_nesting_depth("template.yaml", &value("a: {b: [1, {c: 2}]}")) {
+        Err(Error::LimitExceeded(message)) => assert_eq!(
+            "data file template.yaml nests mappings and lists deeper than the maximum nesting depth of 3 at /a/b/1",
+            message
+        ),
+        result => panic!("expected the limit to be exceeded, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_check_query_results() {
+    let limits = InputLimits {
+        max_query_results: Some(2),
+        ..InputLimits::default()
+    };
+
+    assert!(limits.check_query_results("Resources.*", 2).is_ok());
+    match limits.check_query_results("Resources.*", 3) {
+        Err(Error::LimitExceeded(message)) => assert_eq!(
+            "query Resources.* resolved to 3 values, more than the maximum of 2",
+            message
+        ),
+        result => panic!("expected the limit to be exceeded, got {result:?}"),
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rules::errors::Error;
+
+/// identifies a failure message of the reports whatever its wording, included as the
+/// `message_id` of the structured output so that tools reading it can tell failures apart without
+/// matching on the English text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MessageId {
+    VariableNotEmpty,
+    DependentRuleFailed,
+    BlockPropertyMissing,
+    BlockQueryEmpty,
+    UnaryCheckFailed,
+    PropertyMissing,
+    CompareFromMissing,
+    CompareToMissing,
+    ComparisonFailed,
+    NotInList,
+}
+
+impl MessageId {
+    /// the English wording of the message, with the placeholders its values are put in
+    fn default_template(self) -> &'static str {
+        match self {
+            MessageId::VariableNotEmpty => {
+                "Check was not compliant as variable in context [{context}] was not empty"
+            }
+            MessageId::DependentRuleFailed => {
+                "Check was not compliant as dependent rule [{rule}] did not PASS. Context [{context}]"
+            }
+            MessageId::BlockPropertyMissing => {
+                "Check was not compliant as property [{property}] is missing. Value traversed to [{traversed_to}]"
+            }
+            MessageId::BlockQueryEmpty => "query for block clause did not retrieve any value",
+            MessageId::UnaryCheckFailed => {
+                "Check was not compliant as property [{property}] {check}.{error}"
+            }
+            MessageId::PropertyMissing => {
+                "Check was not compliant as property [{property}] is missing. Value traversed to [{traversed_to}].{error}"
+            }
+            MessageId::CompareFromMissing => {
+                "Check was not compliant as property [{property}] to compare from is missing. Value traversed to [{traversed_to}].{error}"
+            }
+            MessageId::CompareToMissing => {
+                "Check was not compliant as property [{property}] to compare to is missing. Value traversed to [{traversed_to}].{error}"
+            }
+            MessageId::ComparisonFailed => {
+                "Check was not compliant as property value [{from}] {check} value [{to}].{error}"
+            }
+            MessageId::NotInList => {
+                "Check was not compliant as property [{property}] was not present in [{values}]"
+            }
+        }
+    }
+
+    /// the placeholders the message has values for, a template may use any of them
+    fn placeholders(self) -> &'static [&'static str] {
+        match self {
+            MessageId::VariableNotEmpty => &["context"],
+            MessageId::DependentRuleFailed => &["rule", "context"],
+            MessageId::BlockPropertyMissing => &["property", "traversed_to"],
+            MessageId::BlockQueryEmpty => &[],
+            MessageId::UnaryCheckFailed => &["property", "check", "operator", "error"],
+            MessageId::PropertyMissing
+            | MessageId::CompareFromMissing
+            | MessageId::CompareToMissing => &["property", "traversed_to", "operator", "error"],
+            MessageId::ComparisonFailed => &["from", "to", "check", "operator", "error"],
+            MessageId::NotInList => &["property", "values"],
+        }
+    }
+}
+
+/// The wording of the failure messages, the English one by default. Organizations reword or
+/// translate them with a YAML or JSON file mapping message IDs to templates, e.g.
+/// `property-missing: "Propriété [{property}] manquante"`, the messages it leaves out keep their
+/// English wording. The reports of validate use the catalog of `--message-catalog`, or the one
+/// given to `ValidateBuilder::message_catalog`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageCatalog {
+    templates: HashMap<MessageId, String>,
+}
+
+impl MessageCatalog {
+    /// reads the templates of a catalog from the content of a YAML or JSON file
+    ///
+    /// This function will return an error if
+    /// - the content is not a mapping of message IDs to strings
+    /// - a template has a placeholder its message has no value for
+    pub fn from_content(content: &str, name: &str) -> crate::rules::Result<Self> {
+        let templates: HashMap<MessageId, String> = serde_yaml::from_str(content).map_err(|e| {
+            Error::ParseError(format!("Unable to read the message catalog {name}, {e}"))
+        })?;
+
+        for (id, template) in &templates {
+            if let Some(placeholder) = placeholders(template)
+                .into_iter()
+                .find(|placeholder| !id.placeholders().contains(placeholder))
+            {
+                return Err(Error::ParseError(format!(
+                    "Unable to read the message catalog {name}, the message {} has no value for the placeholder {{{placeholder}}}",
+                    serde_yaml::to_string(id)?.trim_end()
+                )));
+            }
+        }
+
+        Ok(MessageCatalog { templates })
+    }
+
+    /// reads the catalog from a YAML or JSON file
+    ///
+    /// This function will return an error if the file cannot be read, or is not a catalog
+    pub fn read(path: &str) -> crate::rules::Result<Self> {
+        Self::from_content(&std::fs::read_to_string(path)?, path)
+    }
+
+    /// the message with its placeholders replaced by the values given
+    pub(crate) fn format(&self, id: MessageId, values: &[(&str, &dyn Display)]) -> String {
+        let template = self
+            .templates
+            .get(&id)
+            .map_or(id.default_template(), String::as_str);
+
+        let mut message = String::with_capacity(template.len());
+        let mut remaining = template;
+        while let Some(start) = remaining.find('{') {
+            message.push_str(&remaining[..start]);
+            remaining = &remaining[start..];
+            let value = remaining.find('}').and_then(|end| {
+                values
+                    .iter()
+                    .find(|(placeholder, _)| *placeholder == &remaining[1..end])
+                    .map(|(_, value)| (end, value))
+            });
+            match value {
+                Some((end, value)) => {
+                    message.push_str(&value.to_string());
+                    remaining = &remaining[end + 1..];
+                }
+                None => {
+                    message.push('{');
+                    remaining = &remaining[1..];
+                }
+            }
+        }
+        message.push_str(remaining);
+
+        message
+    }
+}
+
+/// the names between braces in the template
+fn placeholders(template: &str) -> Vec<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(placeholder, _)| placeholder))
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "messages_tests.rs"]
+mod messages_tests;
@@ -0,0 +1,79 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_default_wording() {
+    let catalog = MessageCatalog::default();
+
+    assert_eq!(
+        "Check was not compliant as property [Properties.Encrypted] is missing. Value traversed to [/Resources/Volume]. Error = [missing]",
+        catalog.format(
+            MessageId::PropertyMissing,
+            &[
+                ("property", &"Properties.Encrypted"),
+                ("traversed_to", &"/Resources/Volume"),
+                ("operator", &"EXISTS"),
+                ("error", &" Error = [missing]"),
+            ]
+        )
+    );
+    assert_eq!(
+        "query for block clause did not retrieve any value",
+        catalog.format(MessageId::BlockQueryEmpty, &[])
+    );
+}
+
+#[test]
+fn test_catalog_overrides() {
+    let catalog = MessageCatalog::from_content(
+        r#"
+property-missing: "La propriété {property} manque ({operator}), {unused"
+not-in-list: "{property} {{values}}"
+"#,
+        "messages.yaml",
+    )
+    .unwrap();
+
+    assert_eq!(
+        "La propriété Properties.Encrypted manque (EXISTS), {unused",
+        catalog.format(
+            MessageId::PropertyMissing,
+            &[
+                ("property", &"Properties.Encrypted"),
+                ("traversed_to", &"/Resources/Volume"),
+                ("operator", &"EXISTS"),
+                ("error", &""),
+            ]
+        )
+    );
+    assert_eq!(
+        "Tier {[gold, silver]}",
+        catalog.format(
+            MessageId::NotInList,
+            &[("property", &"Tier"), ("values", &"[gold, silver]")]
+        )
+    );
+    // the messages the catalog leaves out keep their wording
+    assert_eq!(
+        "Check was not compliant as dependent rule [s3_exists] did not PASS. Context [s3]",
+        catalog.format(
+            MessageId::DependentRuleFailed,
+            &[("rule", &"s3_exists"), ("context", &"s3")]
+        )
+    );
+}
+
+#[test]
+fn test_catalog_errors() {
+    for (content, expected) in [
+        ("property-gone: text", "unknown variant `property-gone`"),
+        (
+            "not-in-list: \"{property} is not {expected}\"",
+            "the message not-in-list has no value for the placeholder {expected}",
+        ),
+        ("- property-missing", "Unable to read the message catalog"),
+    ] {
+        let error = MessageCatalog::from_content(content, "messages.yaml").unwrap_err();
+        assert!(error.to_string().contains(expected), "{error}");
+    }
+}
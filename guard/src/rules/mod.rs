@@ -1,20 +1,38 @@
 #![allow(deprecated)]
+pub(crate) mod builder;
 pub(crate) mod display;
 pub(crate) mod errors;
 pub(crate) mod eval;
 pub(crate) mod eval_context;
 pub(crate) mod evaluate;
 pub(crate) mod exprs;
+pub(crate) mod external;
 pub(crate) mod functions;
 mod libyaml;
+pub(crate) mod limits;
+pub(crate) mod messages;
+pub(crate) mod observer;
+pub(crate) mod pack;
 pub(crate) mod parser;
 pub(crate) mod path_value;
+pub(crate) mod profile;
+pub(crate) mod regex_cache;
+pub(crate) mod suppressions;
+pub(crate) mod template_metrics;
+pub(crate) mod this_key;
 pub(crate) mod values;
 
 use errors::Error;
 
-use crate::rules::exprs::{ParameterizedRule, QueryPart};
+use crate::rules::exprs::{
+    GuardAccessClause, OnMissing, ParameterizedRule, QueryPart, Rule, Severity,
+};
+use crate::rules::functions::custom::FunctionRegistry;
+use crate::rules::limits::InputLimits;
 use crate::rules::path_value::PathAwareValue;
+use crate::rules::profile::Frame;
+use crate::rules::suppressions::Suppression;
+use crate::rules::this_key::ThisKeys;
 use crate::rules::values::CmpOperator;
 use colored::*;
 use lazy_static::lazy_static;
@@ -23,6 +41,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub(crate) type Result<R> = std::result::Result<R, Error>;
 
@@ -85,9 +105,10 @@ lazy_static! {
     };
 }
 
-#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize, Default)]
+/// the outcome of the evaluation of a rule, a clause or a block
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Serialize, Deserialize, Default)]
 #[allow(clippy::upper_case_acronyms)]
-pub(crate) enum Status {
+pub enum Status {
     PASS,
     FAIL,
     #[default]
@@ -120,7 +141,7 @@ impl TryFrom<&str> for Status {
     }
 }
 impl Status {
-    fn and(&self, status: Status) -> Status {
+    pub(crate) fn and(&self, status: Status) -> Status {
         match self {
             Status::FAIL => Status::FAIL,
             Status::PASS => match status {
@@ -258,11 +279,26 @@ pub(crate) struct BlockCheck {
     pub(crate) message: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct GuardClauseCheck<'value> {
+    #[serde(flatten)]
+    pub(crate) block: BlockCheck,
+    // kept out of the serialized record, reports render it as the clause's query
+    #[serde(skip)]
+    pub(crate) clause: &'value GuardAccessClause<'value>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub(crate) struct NamedStatus<'value> {
     pub(crate) name: &'value str,
     pub(crate) status: Status,
     pub(crate) message: Option<String>,
+    /// the `@severity` of the rule for rule checks, a failing warning does not fail the file
+    #[serde(skip_serializing_if = "Severity::is_error")]
+    pub(crate) severity: Severity,
+    /// the resources that opted out of the rule through their metadata, for rule checks
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) suppressed: Vec<Suppression>,
 }
 
 impl<'value> Default for NamedStatus<'value> {
@@ -271,6 +307,8 @@ impl<'value> Default for NamedStatus<'value> {
             name: "",
             status: Status::PASS,
             message: None,
+            severity: Severity::Error,
+            suppressed: vec![],
         }
     }
 }
@@ -346,7 +384,7 @@ pub(crate) enum RecordType<'value> {
     //
     // has as many child events for each ClauseValueCheck
     //
-    GuardClauseBlockCheck(BlockCheck),
+    GuardClauseBlockCheck(GuardClauseCheck<'value>),
 
     //
     // one per value check, unary or binary
@@ -367,6 +405,11 @@ pub(crate) trait EvalContext<'value, 'loc: 'value>: RecordTracer<'value> {
         rule_name: &str,
     ) -> Result<&'value ParameterizedRule<'loc>>;
     fn root(&mut self) -> Rc<PathAwareValue>;
+    /// the data file being evaluated, unlike `root` it is never narrowed down to the value a
+    /// block or filter is evaluated against
+    fn document_root(&mut self) -> Rc<PathAwareValue> {
+        self.root()
+    }
     fn rule_status(&mut self, rule_name: &'value str) -> Result<Status>;
     fn resolve_variable(&mut self, variable_name: &'value str) -> Result<Vec<QueryResult>>;
     fn add_variable_capture_key(
@@ -377,6 +420,59 @@ pub(crate) trait EvalContext<'value, 'loc: 'value>: RecordTracer<'value> {
     fn add_variable_capture_index(&mut self, _: &str, _: Rc<PathAwareValue>) -> Result<()> {
         Ok(())
     }
+    /// how clauses treat missing properties in the rule being evaluated, `None` unless the rule
+    /// is annotated with `@on-missing`
+    fn on_missing(&self) -> Option<OnMissing> {
+        None
+    }
+    /// sets the missing property behavior for the rule about to be evaluated, returns the previous one
+    fn set_on_missing(&mut self, _: Option<OnMissing>) -> Option<OnMissing> {
+        None
+    }
+    /// whether comparisons between values of different types are reported as not comparable
+    /// instead of being compared, see `--strict-types`
+    fn strict_types(&self) -> bool {
+        false
+    }
+    /// the bounds on the values a query may resolve to, see `--max-query-results`
+    fn limits(&self) -> InputLimits {
+        InputLimits::default()
+    }
+    /// the custom functions the rules can call, registered by the application embedding Guard
+    fn functions(&self) -> Option<Arc<FunctionRegistry>> {
+        None
+    }
+    /// sets the rule about to be evaluated, returns the previous one
+    fn set_current_rule(&mut self, _: Option<&'value str>) -> Option<&'value str> {
+        None
+    }
+    /// sets what `%THIS.key` stands for in the custom message of the clause about to be
+    /// evaluated, returns the previous one
+    fn set_this_keys(&mut self, _: Option<ThisKeys>) -> Option<ThisKeys> {
+        None
+    }
+    /// leaves out the query results found in the resources whose metadata suppress the rule
+    /// being evaluated
+    fn without_suppressed(&mut self, results: Vec<QueryResult>) -> Vec<QueryResult> {
+        results
+    }
+    /// the resources that suppressed the rule so far
+    fn suppressed(&self, _: &str) -> Vec<Suppression> {
+        vec![]
+    }
+    /// whether a rule of the rules file is evaluated, see `--tags` and `--exclude-tags`
+    fn is_rule_selected(&self, _: &Rule<'_>) -> bool {
+        true
+    }
+    /// records how long a rule of the rules file took to evaluate, see `--timings`
+    fn record_duration(&mut self, _: &'value str, _: Duration) {}
+    /// starts timing a rule, block, clause or variable when the evaluation is profiled, see
+    /// `profile`
+    fn enter_frame(&mut self, _: Frame<'value, 'loc>) {}
+    /// stops timing the frame entered last
+    fn exit_frame(&mut self) {}
+    /// counts a value stepped through while resolving a query, towards the frame being timed
+    fn count_resolution(&mut self) {}
 }
 
 pub(crate) trait EvaluationContext {
@@ -0,0 +1,101 @@
+use crate::rules::{ClauseCheck, RecordType, Status};
+
+/// what an [`EvaluationEvent`] is the evaluation of, named after the records of the verbose
+/// output of validate, e.g. `RuleCheck` for a rule and `ClauseValueCheck` for a clause checked
+/// against one value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    FileCheck,
+    RuleCheck,
+    RuleCondition,
+    TypeCheck,
+    TypeCondition,
+    TypeBlock,
+    Filter,
+    WhenCheck,
+    WhenCondition,
+    Disjunction,
+    BlockGuardCheck,
+    GuardClauseBlockCheck,
+    ClauseValueCheck,
+}
+
+/// the end of the evaluation of a rules file, a rule, a clause or one of the blocks they are
+/// made of, as reported to an [`EvaluationObserver`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvaluationEvent<'event> {
+    /// the same context the evaluation was started with, e.g. `s3_encrypted` for a rule
+    pub context: &'event str,
+    pub kind: EventKind,
+    /// the name of the rules file or of the rule, for `FileCheck` and `RuleCheck` events
+    pub name: Option<&'event str>,
+    pub status: Status,
+}
+
+/// Receives the events of the evaluation of rules while it happens, e.g. to report progress,
+/// collect metrics or build a UI, without parsing the verbose output afterwards. Events nest:
+/// every [`EvaluationObserver::on_start`] is matched by the [`EvaluationObserver::on_end`] of the
+/// same context, after those of the evaluations it is made of, e.g. a rule ends after its
+/// clauses. See [`crate::CompiledRules::validate_with_observer`]
+pub trait EvaluationObserver {
+    /// the evaluation of the context started
+    fn on_start(&mut self, _context: &str) {}
+
+    /// the evaluation of the context ended
+    fn on_end(&mut self, _event: &EvaluationEvent<'_>) {}
+}
+
+impl<O: EvaluationObserver + ?Sized> EvaluationObserver for &mut O {
+    fn on_start(&mut self, context: &str) {
+        (**self).on_start(context)
+    }
+
+    fn on_end(&mut self, event: &EvaluationEvent<'_>) {
+        (**self).on_end(event)
+    }
+}
+
+impl<'event> EvaluationEvent<'event> {
+    pub(crate) fn from_record(context: &'event str, record: &'event RecordType<'_>) -> Self {
+        let (kind, name, status) = match record {
+            RecordType::FileCheck(named) => (EventKind::FileCheck, Some(named.name), named.status),
+            RecordType::RuleCheck(named) => (EventKind::RuleCheck, Some(named.name), named.status),
+            RecordType::RuleCondition(status) => (EventKind::RuleCondition, None, *status),
+            RecordType::TypeCheck(check) => (EventKind::TypeCheck, None, check.block.status),
+            RecordType::TypeCondition(status) => (EventKind::TypeCondition, None, *status),
+            RecordType::TypeBlock(status) => (EventKind::TypeBlock, None, *status),
+            RecordType::Filter(status) => (EventKind::Filter, None, *status),
+            RecordType::WhenCheck(check) => (EventKind::WhenCheck, None, check.status),
+            RecordType::WhenCondition(status) => (EventKind::WhenCondition, None, *status),
+            RecordType::Disjunction(check) => (EventKind::Disjunction, None, check.status),
+            RecordType::BlockGuardCheck(check) => (EventKind::BlockGuardCheck, None, check.status),
+            RecordType::GuardClauseBlockCheck(check) => {
+                (EventKind::GuardClauseBlockCheck, None, check.block.status)
+            }
+            RecordType::ClauseValueCheck(check) => (
+                EventKind::ClauseValueCheck,
+                None,
+                match check {
+                    ClauseCheck::Success => Status::PASS,
+                    ClauseCheck::NoValueForEmptyCheck(_) => Status::FAIL,
+                    ClauseCheck::Comparison(check) => check.status,
+                    ClauseCheck::InComparison(check) => check.status,
+                    ClauseCheck::Unary(check) => check.value.status,
+                    ClauseCheck::DependentRule(check) => check.status,
+                    ClauseCheck::MissingBlockValue(check) => check.status,
+                },
+            ),
+        };
+
+        EvaluationEvent {
+            context,
+            kind,
+            name,
+            status,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "observer_tests.rs"]
+mod observer_tests;
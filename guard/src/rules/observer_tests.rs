@@ -0,0 +1,87 @@
+use super::*;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::RulesFile;
+use crate::rules::path_value::PathAwareValue;
+use pretty_assertions::assert_eq;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct Recorder {
+    depth: usize,
+    max_depth: usize,
+    started: Vec<String>,
+    ended: Vec<(String, EventKind, Option<String>, Status)>,
+}
+
+impl EvaluationObserver for Recorder {
+    fn on_start(&mut self, context: &str) {
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        self.started.push(context.to_string());
+    }
+
+    fn on_end(&mut self, event: &EvaluationEvent<'_>) {
+        self.depth -= 1;
+        self.ended.push((
+            event.context.to_string(),
+            event.kind,
+            event.name.map(String::from),
+            event.status,
+        ));
+    }
+}
+
+#[test]
+fn test_observer_events() -> crate::rules::Result<()> {
+    let rules = RulesFile::try_from(
+        r#"
+rule s3_encrypted {
+    Resources.*.Properties.BucketEncryption exists
+}
+
+rule s3_versioned {
+    Resources.*.Properties.VersioningConfiguration exists
+}
+"#,
+    )?;
+    let value = PathAwareValue::try_from(serde_json::json!({
+        "Resources": { "bucket": { "Properties": { "BucketEncryption": {} } } }
+    }))?;
+
+    let mut recorder = Recorder::default();
+    let mut root = root_scope(&rules, Rc::new(value)).with_observer(Box::new(&mut recorder));
+    let status = eval_rules_file(&rules, &mut root, Some("template.json"))?;
+    drop(root);
+
+    assert_eq!(Status::FAIL, status);
+    assert_eq!(0, recorder.depth);
+    assert!(recorder.max_depth > 2);
+    assert_eq!(recorder.started.len(), recorder.ended.len());
+
+    let rules = recorder
+        .ended
+        .iter()
+        .filter(|(_, kind, ..)| *kind == EventKind::RuleCheck)
+        .map(|(_, _, name, status)| (name.as_deref().unwrap(), *status))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("s3_encrypted", Status::PASS),
+            ("s3_versioned", Status::FAIL)
+        ],
+        rules
+    );
+
+    let (_, kind, name, status) = recorder.ended.last().unwrap();
+    assert_eq!(EventKind::FileCheck, *kind);
+    assert_eq!(Some("template.json"), name.as_deref());
+    assert_eq!(Status::FAIL, *status);
+
+    assert!(recorder.ended.iter().any(
+        |(_, kind, _, status)| *kind == EventKind::ClauseValueCheck && *status == Status::FAIL
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::convert::TryFrom;
+use std::io::Write;
+
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::rules::errors::Error;
+use crate::rules::exprs::RulesFile;
+use crate::rules::Result;
+
+/// the first line of a rules pack, followed by the version of cfn-guard that built it
+const HEADER: &str = "cfn-guard rules pack ";
+
+/// the version of cfn-guard, the only one that can read the packs it builds as the parsed rules
+/// change between releases
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// a rules file of a pack, parsed when the pack was built
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PackedRulesFile<'loc> {
+    pub(crate) name: String,
+    pub(crate) rules: RulesFile<'loc>,
+}
+
+/// writes the rules files as a rules pack, a header line with the version followed by the parsed
+/// rules as CBOR. A self describing format is needed as the parsed rules skip empty fields
+pub(crate) fn write_pack(files: &[PackedRulesFile<'_>], writer: &mut dyn Write) -> Result<()> {
+    writeln!(writer, "{HEADER}{VERSION}")?;
+    ciborium::ser::into_writer(files, writer)
+        .map_err(|e| Error::IncompatibleError(format!("Unable to write the rules pack, {e}")))
+}
+
+/// reads the rules files of a rules pack
+///
+/// This function will return an error if the content is not a rules pack, or one built by
+/// another version of cfn-guard
+pub(crate) fn read_pack(name: &str, content: &[u8]) -> Result<Vec<PackedRulesFile<'static>>> {
+    let (header, rules) = match content.iter().position(|byte| *byte == b'\n') {
+        Some(end) => (&content[..end], &content[end + 1..]),
+        None => (content, &content[content.len()..]),
+    };
+
+    let version = std::str::from_utf8(header)
+        .ok()
+        .and_then(|header| header.strip_prefix(HEADER))
+        .ok_or_else(|| {
+            Error::ParseError(format!(
+                "{name} is not a rules pack, build one with cfn-guard build"
+            ))
+        })?;
+    if version != VERSION {
+        return Err(Error::IncompatibleError(format!(
+            "{name} was built by cfn-guard {version}, rebuild it with cfn-guard {VERSION}"
+        )));
+    }
+
+    ciborium::de::from_reader(rules)
+        .map_err(|e| Error::ParseError(format!("Unable to read the rules pack {name}, {e}")))
+}
+
+/// appended to the path of a rules pack for the path of its signature, written by cfn-guard sign
+pub(crate) const SIGNATURE_EXTENSION: &str = ".sig";
+
+/// signs the content of a rules pack with an Ed25519 private key in PKCS#8 PEM, the signature is
+/// returned as hex to be written next to the pack
+///
+/// This function will return an error if the key is not an Ed25519 private key in PEM
+pub(crate) fn sign_pack(content: &[u8], private_key_pem: &str) -> Result<String> {
+    let key = SigningKey::from_pkcs8_pem(private_key_pem).map_err(|e| {
+        Error::IllegalArguments(format!(
+            "The private key is not an Ed25519 private key in PEM, {e}"
+        ))
+    })?;
+
+    Ok(key
+        .sign(content)
+        .to_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// checks the signature written by [`sign_pack`] against the content of a rules pack, with the
+/// Ed25519 public key in PEM matching the private key the pack was signed with
+///
+/// This function will return an error if
+/// - the key is not an Ed25519 public key in PEM
+/// - the signature is not one written by cfn-guard sign
+/// - the pack was not signed with the private key of the public key, or was modified since
+pub(crate) fn verify_pack(
+    name: &str,
+    content: &[u8],
+    signature: &str,
+    public_key_pem: &str,
+) -> Result<()> {
+    let key = VerifyingKey::from_public_key_pem(public_key_pem).map_err(|e| {
+        Error::IllegalArguments(format!(
+            "The public key is not an Ed25519 public key in PEM, {e}"
+        ))
+    })?;
+
+    let signature = decode_hex(signature.trim())
+        .and_then(|bytes| <[u8; Signature::BYTE_SIZE]>::try_from(bytes.as_slice()).ok())
+        .map(|bytes| Signature::from_bytes(&bytes))
+        .ok_or_else(|| {
+            Error::SignatureError(format!(
+                "the signature of {name} is not one written by cfn-guard sign"
+            ))
+        })?;
+
+    key.verify(content, &signature).map_err(|_| {
+        Error::SignatureError(format!(
+            "{name} was not signed with the private key of the public key, or was modified since"
+        ))
+    })
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "pack_tests.rs"]
+mod pack_tests;
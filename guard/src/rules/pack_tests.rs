@@ -0,0 +1,108 @@
+use std::convert::TryFrom;
+
+use super::*;
+use pretty_assertions::assert_eq;
+
+const RULES: &str = r#"
+let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]
+let allowed = { "sse": ["aws:kms", "AES256"], "versioning": true }
+
+rule s3_encrypted when %buckets !empty {
+    %buckets.Properties.BucketEncryption.ServerSideEncryptionConfiguration[*] {
+        ServerSideEncryptionByDefault.SSEAlgorithm in %allowed.sse
+        ServerSideEncryptionByDefault.KMSMasterKeyID == /^arn:aws:kms:/
+    }
+    %buckets.Properties.ObjectLockConfiguration.Rule.DefaultRetention.Days in r[1, 365]
+}
+
+rule tagged(tags) {
+    %tags[*].Key exists
+}
+
+rule buckets_tagged {
+    tagged(%buckets.Properties.Tags)
+}
+"#;
+
+fn packed(content: &str) -> Result<PackedRulesFile<'_>> {
+    Ok(PackedRulesFile {
+        name: String::from("s3.guard"),
+        rules: RulesFile::try_from(content)?,
+    })
+}
+
+#[test]
+fn test_read_written_pack() -> Result<()> {
+    let files = vec![packed(RULES)?, packed("rule empty { this exists }")?];
+    let mut pack = vec![];
+    write_pack(&files, &mut pack)?;
+
+    assert!(pack.starts_with(format!("{HEADER}{VERSION}\n").as_bytes()));
+    assert_eq!(files, read_pack("rules.pack", &pack)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_read_other_content() {
+    let error = read_pack("s3.guard", RULES.as_bytes()).unwrap_err();
+    assert!(matches!(error, Error::ParseError(_)));
+    assert!(error
+        .to_string()
+        .contains("s3.guard is not a rules pack, build one with cfn-guard build"));
+
+    let error = read_pack("rules.pack", format!("{HEADER}1.0.0\n").as_bytes()).unwrap_err();
+    assert!(matches!(error, Error::IncompatibleError(_)));
+    assert!(error.to_string().contains(&format!(
+        "rules.pack was built by cfn-guard 1.0.0, rebuild it with cfn-guard {VERSION}"
+    )));
+}
+
+const PRIVATE_KEY: &str = include_str!("../../resources/sign/private.pem");
+const PUBLIC_KEY: &str = include_str!("../../resources/sign/public.pem");
+const OTHER_PUBLIC_KEY: &str = include_str!("../../resources/sign/other-public.pem");
+
+#[test]
+fn test_verify_signed_pack() -> Result<()> {
+    let mut pack = vec![];
+    write_pack(&[packed(RULES)?], &mut pack)?;
+
+    let signature = sign_pack(&pack, PRIVATE_KEY)?;
+    assert_eq!(2 * Signature::BYTE_SIZE, signature.len());
+    verify_pack("rules.pack", &pack, &format!("{signature}\n"), PUBLIC_KEY)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_verify_modified_or_unsigned_pack() -> Result<()> {
+    let mut pack = vec![];
+    write_pack(&[packed(RULES)?], &mut pack)?;
+    let signature = sign_pack(&pack, PRIVATE_KEY)?;
+
+    let error = verify_pack("rules.pack", &pack, &signature, OTHER_PUBLIC_KEY).unwrap_err();
+    assert!(matches!(error, Error::SignatureError(_)));
+
+    let last = pack.len() - 1;
+    pack[last] ^= 1;
+    let error = verify_pack("rules.pack", &pack, &signature, PUBLIC_KEY).unwrap_err();
+    assert!(error.to_string().contains(
+        "rules.pack was not signed with the private key of the public key, or was modified since"
+    ));
+
+    let error = verify_pack("rules.pack", &pack, "not hex", PUBLIC_KEY).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("the signature of rules.pack is not one written by cfn-guard sign"));
+
+    Ok(())
+}
+
+#[test]
+fn test_keys_must_be_ed25519_pem() {
+    let error = sign_pack(b"", PUBLIC_KEY).unwrap_err();
+    assert!(matches!(error, Error::IllegalArguments(_)));
+
+    let error = verify_pack("rules.pack", b"", "", PRIVATE_KEY).unwrap_err();
+    assert!(matches!(error, Error::IllegalArguments(_)));
+}
@@ -1,4 +1,3 @@
-use fancy_regex::Regex;
 use std::convert::TryFrom;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
@@ -11,10 +10,10 @@ use nom::character::complete::{anychar, digit1, one_of};
 use nom::character::complete::{char, multispace0, multispace1, space0};
 use nom::combinator::{all_consuming, cut, peek};
 use nom::combinator::{map, value};
-use nom::combinator::{map_res, opt};
+use nom::combinator::{map_res, opt, recognize};
 use nom::error::context;
 use nom::error::ErrorKind;
-use nom::multi::{fold_many1, separated_list, separated_nonempty_list};
+use nom::multi::{fold_many0, fold_many1, separated_list, separated_nonempty_list};
 use nom::multi::{many0, many1};
 use nom::number::complete::double;
 use nom::sequence::{delimited, preceded};
@@ -23,14 +22,20 @@ use nom::sequence::{separated_pair, tuple};
 use nom::{FindSubstring, InputTake, Slice};
 use nom_locate::LocatedSpan;
 
-use crate::rules::errors::Error;
+use crate::rules::errors::{Error, ParseErrorLocation};
 use crate::rules::eval_context::FunctionName;
 use crate::rules::exprs::*;
+use crate::rules::external::values_from;
 use crate::rules::path_value::{Path, PathAwareValue};
+use crate::rules::regex_cache;
 use crate::rules::values::*;
 
 pub(crate) type Span<'a> = LocatedSpan<&'a str, &'a str>;
-const DEFAULT_RULE_NAME: &str = "default";
+pub(crate) const DEFAULT_RULE_NAME: &str = "default";
+
+/// how deep brackets, braces and parentheses can be nested in a rules file. The parser recurses
+/// for each level, input nested deeper would overflow the stack instead of failing to parse
+pub(crate) const MAX_NESTING_DEPTH: usize = 64;
 
 pub(crate) fn from_str2(in_str: &str) -> Span {
     Span::new_extra(in_str, "")
@@ -73,6 +78,49 @@ impl<'a> nom::error::ParseError<Span<'a>> for ParserError<'a> {
     }
 }
 
+impl<'a> ParserError<'a> {
+    pub(crate) fn location(&self) -> ParseErrorLocation {
+        // contexts added with `context("expecting ...", ...)` say what the parser was looking for,
+        // the kind of the innermost parser that failed is all there is otherwise
+        let mut expected = self
+            .context
+            .split('/')
+            .filter_map(|each| each.strip_prefix("expecting "))
+            .map(String::from)
+            .collect::<Vec<_>>();
+        if expected.is_empty() {
+            expected.push(expected_token(self.kind));
+        }
+
+        ParseErrorLocation {
+            file: self.span.extra.to_string(),
+            line: self.span.location_line(),
+            column: self.span.get_utf8_column(),
+            expected,
+            context: self.context.clone(),
+            fragment: self
+                .span
+                .fragment()
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_string(),
+        }
+    }
+}
+
+fn expected_token(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Eof => String::from("end of file"),
+        ErrorKind::Tag => String::from("keyword or operator"),
+        ErrorKind::Char | ErrorKind::OneOf => String::from("character"),
+        ErrorKind::Digit => String::from("number"),
+        ErrorKind::Alpha | ErrorKind::AlphaNumeric => String::from("name"),
+        ErrorKind::Space | ErrorKind::MultiSpace => String::from("whitespace"),
+        other => other.description().to_lowercase(),
+    }
+}
+
 impl<'a> std::fmt::Display for ParserError<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let message = format!(
@@ -162,7 +210,7 @@ pub(in crate::rules) fn parse_int_value(input: Span) -> IResult<Span, Value> {
     alt((positive, negative))(input)
 }
 
-fn parse_string_inner(ch: char) -> impl Fn(Span) -> IResult<Span, Value> {
+fn parse_string_inner(ch: char) -> impl Fn(Span) -> IResult<Span, String> {
     move |input: Span| {
         let mut completed = String::new();
         let (input, _begin) = char(ch)(input)?;
@@ -187,13 +235,13 @@ fn parse_string_inner(ch: char) -> impl Fn(Span) -> IResult<Span, Value> {
             }
             completed.push_str(frag);
             let (remainder, _end) = cut(char(ch))(remainder)?;
-            return Ok((remainder, Value::String(completed)));
+            return Ok((remainder, completed));
         }
     }
 }
 
 pub(crate) fn parse_string(input: Span) -> IResult<Span, Value> {
-    alt((parse_string_inner('\''), parse_string_inner('\"')))(input)
+    map(string_literal, Value::String)(input)
     //    map(
     //        alt((
     //            delimited(
@@ -209,6 +257,23 @@ pub(crate) fn parse_string(input: Span) -> IResult<Span, Value> {
     //    )(input)
 }
 
+//
+// a quoted string, for the places that need the string itself rather than a value
+//
+fn string_literal(input: Span) -> IResult<Span, String> {
+    alt((parse_string_inner('\''), parse_string_inner('\"')))(input)
+}
+
+//
+// an index into a list, e.g. the 0 of [0], which must fit in an i32
+//
+fn parse_index(input: Span) -> IResult<Span, i32> {
+    map_res(parse_int_value, |value| match value {
+        Value::Int(i) => i32::try_from(i).map_err(|_| ()),
+        _ => Err(()),
+    })(input)
+}
+
 fn parse_bool(input: Span) -> IResult<Span, Value> {
     let true_parser = value(Value::Bool(true), alt((tag("true"), tag("True"))));
     let false_parser = value(Value::Bool(false), alt((tag("false"), tag("False"))));
@@ -230,8 +295,33 @@ fn parse_float(input: Span) -> IResult<Span, Value> {
     }))
 }
 
+//
+//  quantity = digit1 ["." digit1] unit, e.g. 5GiB, 1.5h, 30d
+//
+// normalized to bytes or seconds, see UNITS
+//
+fn parse_quantity(input: Span) -> IResult<Span, Value> {
+    let (remainder, (number, unit)) = pair(
+        recognize(pair(digit1, opt(preceded(char('.'), digit1)))),
+        alpha1,
+    )(input)?;
+    match (
+        number.fragment().parse::<f64>(),
+        unit_factor(unit.fragment()),
+    ) {
+        (Ok(number), Some(factor)) => Ok((remainder, quantity_value(number * factor))),
+        _ => Err(nom::Err::Error(ParserError {
+            context: format!("Unknown unit {}", unit.fragment()),
+            kind: ErrorKind::Alpha,
+            span: input,
+        })),
+    }
+}
+
 fn parse_regex_inner(input: Span) -> IResult<Span, Value> {
     let mut regex = String::new();
+    // where the escaped slashes are in the regex, each is one character further in the input
+    let mut escapes = vec![];
     let parser = is_not("/");
     let mut span = input;
     loop {
@@ -243,6 +333,7 @@ fn parse_regex_inner(input: Span) -> IResult<Span, Value> {
         //
         if !fragment.is_empty() && fragment.ends_with('\\') {
             regex.push_str(&fragment[0..fragment.len() - 1]);
+            escapes.push(regex.len());
             regex.push('/');
 
             if remainder.is_empty() {
@@ -258,19 +349,59 @@ fn parse_regex_inner(input: Span) -> IResult<Span, Value> {
 
         regex.push_str(fragment);
 
-        return match Regex::try_from(regex.as_str()) {
+        //
+        // compiled now so that an invalid pattern is reported where it is written, rather than
+        // when the first value is matched against it, and is already compiled by then
+        //
+        return match regex_cache::compiled(&regex) {
             Ok(_) => Ok((remainder, Value::Regex(regex))),
-            Err(e) => Err(nom::Err::Error(ParserError {
-                context: format!("Could not parse regular expression: {}", e),
-                kind: ErrorKind::RegexpMatch,
-                span: input,
-            })),
+            Err(e) => {
+                let (offset, message) = match e {
+                    Error::RegexError(e) => match *e {
+                        fancy_regex::Error::ParseError(position, _) => (
+                            position + escapes.iter().filter(|escape| **escape <= position).count(),
+                            e.to_string(),
+                        ),
+                        _ => (0, e.to_string()),
+                    },
+                    e => (0, e.to_string()),
+                };
+                Err(nom::Err::Failure(ParserError {
+                    context: format!("expecting a valid regular expression, {message}"),
+                    kind: ErrorKind::RegexpMatch,
+                    span: input.slice(offset.min(input.fragment().len())..),
+                }))
+            }
         };
     }
 }
 
+/// a regex literal, `/pattern/`, optionally followed by the flags `i` to ignore case and `m` for
+/// `^` and `$` to match at line boundaries, which are kept as the inline flags `(?im)` of the
+/// pattern
 fn parse_regex(input: Span) -> IResult<Span, Value> {
-    delimited(char('/'), parse_regex_inner, char('/'))(input)
+    let (remainder, regex) = delimited(char('/'), parse_regex_inner, char('/'))(input)?;
+    let (remainder, flags) = take_while(|c: char| c.is_ascii_alphanumeric())(remainder)?;
+    if flags.fragment().is_empty() {
+        return Ok((remainder, regex));
+    }
+
+    if let Some(unknown) = flags.fragment().find(|c| c != 'i' && c != 'm') {
+        return Err(nom::Err::Failure(ParserError {
+            context: "expecting the regular expression flags i or m".to_string(),
+            kind: ErrorKind::RegexpMatch,
+            span: flags.slice(unknown..),
+        }));
+    }
+
+    let inline = ['i', 'm']
+        .iter()
+        .filter(|flag| flags.fragment().contains(**flag))
+        .collect::<String>();
+    match regex {
+        Value::Regex(pattern) => Ok((remainder, Value::Regex(format!("(?{inline}){pattern}")))),
+        regex => Ok((remainder, regex)),
+    }
 }
 
 fn parse_char(input: Span) -> IResult<Span, Value> {
@@ -280,7 +411,7 @@ fn parse_char(input: Span) -> IResult<Span, Value> {
 fn range_value(input: Span) -> IResult<Span, Value> {
     delimited(
         space0,
-        alt((parse_float, parse_int_value, parse_char)),
+        alt((parse_quantity, parse_float, parse_int_value, parse_char)),
         space0,
     )(input)
 }
@@ -334,9 +465,10 @@ fn parse_scalar_value(input: Span) -> IResult<Span, Value> {
     //
     // IMP: order does matter
     // parse_float is before parse_int. the later can parse only the whole part of the float
-    // to match.
+    // to match. parse_quantity is before both, they would stop short of the unit
     alt((
         parse_string,
+        parse_quantity,
         parse_float,
         parse_int_value,
         parse_bool,
@@ -365,13 +497,7 @@ fn key_part(input: Span) -> IResult<Span, String> {
             take_while1(|c: char| c.is_alphanumeric() || c == '-' || c == '_'),
             |s: Span| (*s.fragment()).to_string(),
         ),
-        map(parse_string, |v| {
-            if let Value::String(s) = v {
-                s
-            } else {
-                unreachable!()
-            }
-        }),
+        string_literal,
     ))(input)
 }
 
@@ -412,6 +538,10 @@ pub(crate) fn parse_value(input: Span) -> IResult<Span, Value> {
     )(input)
 }
 
+fn path_aware_value(input: Span) -> IResult<Span, PathAwareValue> {
+    map_res(parse_value, PathAwareValue::try_from)(input)
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 //                                                                                                //
 //                                                                                                //
@@ -567,6 +697,10 @@ fn in_keyword(input: Span) -> IResult<Span, CmpOperator> {
     value(CmpOperator::In, alt((tag("in"), tag("IN"))))(input)
 }
 
+fn like_keyword(input: Span) -> IResult<Span, CmpOperator> {
+    value(CmpOperator::Like, alt((tag("like"), tag("LIKE"))))(input)
+}
+
 fn not(input: Span) -> IResult<Span, ()> {
     match alt((preceded(tag("not"), space1), preceded(tag("NOT"), space1)))(input) {
         Ok((remainder, _not)) => Ok((remainder, ())),
@@ -584,6 +718,8 @@ fn eq(input: Span) -> IResult<Span, (CmpOperator, bool)> {
     alt((
         value((CmpOperator::Eq, false), tag("==")),
         value((CmpOperator::Eq, true), tag("!=")),
+        value((CmpOperator::UnorderedEq, false), tag("=~")),
+        value((CmpOperator::UnorderedEq, true), tag("!~")),
     ))(input)
 }
 
@@ -601,7 +737,8 @@ fn empty(input: Span) -> IResult<Span, CmpOperator> {
 
 fn other_operations(input: Span) -> IResult<Span, (CmpOperator, bool)> {
     let (input, not) = opt(not)(input)?;
-    let (input, operation) = alt((in_keyword, exists, empty, is_type_operations))(input)?;
+    let (input, operation) =
+        alt((in_keyword, like_keyword, exists, empty, is_type_operations))(input)?;
     Ok((input, (operation, not.is_some())))
 }
 
@@ -723,21 +860,51 @@ fn dotted_property(input: Span) -> IResult<Span, QueryPart> {
         preceded(
             char('.'),
             alt((
-                map(parse_int_value, |idx| {
-                    let idx = match idx {
-                        Value::Int(i) => i as i32,
-                        _ => unreachable!(),
-                    };
-                    QueryPart::Index(idx)
-                }),
+                map(parse_index, QueryPart::Index),
+                resolve_call,
                 map(property_name, QueryPart::Key),
                 map(var_name_access_inclusive, QueryPart::Key),
+                descendants,
                 value(QueryPart::AllValues(None), char('*')),
             )), // end alt
         ), // end preceded for char '.'
     )(input)
 }
 
+//
+//  descendants = "**" "." property_name
+//
+fn descendants(input: Span) -> IResult<Span, QueryPart> {
+    map(
+        preceded(
+            tag("**"),
+            cut(context(
+                "expecting . and a property name after **",
+                preceded(char('.'), property_name),
+            )),
+        ),
+        QueryPart::Descendants,
+    )(input)
+}
+
+//
+//  resolve_call = "resolve" "(" ")"
+//
+// a property named resolve is still looked up as one, only the call follows references
+//
+fn resolve_call(input: Span) -> IResult<Span, QueryPart> {
+    value(
+        QueryPart::Resolve,
+        tuple((
+            tag("resolve"),
+            zero_or_more_ws_or_comment,
+            char('('),
+            zero_or_more_ws_or_comment,
+            char(')'),
+        )),
+    )(input)
+}
+
 fn open_array(input: Span) -> IResult<Span, ()> {
     value((), preceded(zero_or_more_ws_or_comment, char('[')))(input)
 }
@@ -759,29 +926,35 @@ fn all_indices(input: Span) -> IResult<Span, QueryPart> {
     Ok((input, query_part))
 }
 
+//
+//  array_slice = "[" [index] ":" [index] "]"
+//
+fn array_slice(input: Span) -> IResult<Span, QueryPart> {
+    map(
+        delimited(
+            open_array,
+            separated_pair(
+                opt(preceded(zero_or_more_ws_or_comment, parse_index)),
+                preceded(zero_or_more_ws_or_comment, char(':')),
+                opt(preceded(zero_or_more_ws_or_comment, parse_index)),
+            ),
+            cut(close_array),
+        ),
+        |(start, end)| QueryPart::Slice(start, end),
+    )(input)
+}
+
 fn array_index(input: Span) -> IResult<Span, QueryPart> {
     map(
-        delimited(open_array, parse_int_value, cut(close_array)),
-        |idx| {
-            let idx = match idx {
-                Value::Int(i) => i as i32,
-                _ => unreachable!(),
-            };
-            QueryPart::Index(idx)
-        },
+        delimited(open_array, parse_index, cut(close_array)),
+        QueryPart::Index,
     )(input)
 }
 
 fn map_key_lookup(input: Span) -> IResult<Span, QueryPart> {
     let (input, _open) = open_array(input)?;
     let (input, query_part) = alt((
-        map(parse_string, |idx| {
-            let idx = match idx {
-                Value::String(i) => i,
-                _ => unreachable!(),
-            };
-            QueryPart::Key(idx)
-        }),
+        map(string_literal, QueryPart::Key),
         map(
             delimited(
                 zero_or_more_ws_or_comment,
@@ -805,14 +978,14 @@ fn map_keys_match(input: Span) -> IResult<Span, QueryPart> {
             eq,
             value((CmpOperator::In, false), in_keyword),
             map(tuple((not, in_keyword)), |_m| (CmpOperator::In, true)),
+            value((CmpOperator::Like, false), like_keyword),
+            map(tuple((not, like_keyword)), |_m| (CmpOperator::Like, true)),
         )),
     ))(input)?;
     let (input, with) = cut(preceded(
         zero_or_more_ws_or_comment,
         alt((
-            map(parse_value, |value| {
-                LetValue::Value(PathAwareValue::try_from(value).unwrap())
-            }),
+            map(path_aware_value, LetValue::Value),
             map(
                 preceded(zero_or_more_ws_or_comment, access),
                 LetValue::AccessClause,
@@ -835,6 +1008,7 @@ fn map_keys_match(input: Span) -> IResult<Span, QueryPart> {
 fn predicate_or_index(input: Span) -> IResult<Span, QueryPart> {
     alt((
         all_indices,
+        array_slice,
         array_index,
         map_key_lookup,
         map_keys_match,
@@ -865,13 +1039,7 @@ fn dotted_access(input: Span) -> IResult<Span, Vec<QueryPart>> {
 }
 
 fn property_name(input: Span) -> IResult<Span, String> {
-    alt((
-        var_name,
-        map(parse_string, |v| match v {
-            Value::String(value) => value,
-            _ => unreachable!(),
-        }),
-    ))(input)
+    alt((var_name, string_literal))(input)
 }
 
 fn some_keyword(input: Span) -> IResult<Span, bool> {
@@ -895,6 +1063,19 @@ fn this_keyword(input: Span) -> IResult<Span, QueryPart> {
     )(input)
 }
 
+//
+// OUTPUTS, MAPPINGS and METADATA start a query at the section of a CloudFormation template they
+// name, e.g. OUTPUTS.Owner exists. Quoted, they are looked up as keys like any other name
+//
+fn section_keyword(input: Span) -> IResult<Span, QueryPart> {
+    map_res(var_name, |name| match name.as_str() {
+        "OUTPUTS" => Ok(QueryPart::Key(String::from("Outputs"))),
+        "MAPPINGS" => Ok(QueryPart::Key(String::from("Mappings"))),
+        "METADATA" => Ok(QueryPart::Key(String::from("Metadata"))),
+        _ => Err(()),
+    })(input)
+}
+
 //
 //   access     =   (var_name / var_name_access) [dotted_access]
 //
@@ -904,6 +1085,7 @@ pub(crate) fn access(input: Span) -> IResult<Span, AccessQuery> {
             opt(some_keyword),
             alt((
                 this_keyword,
+                section_keyword,
                 map(
                     alt((var_name_access_inclusive, property_name)),
                     QueryPart::Key,
@@ -965,6 +1147,32 @@ where
         (query, value)
     })(rest)?;
 
+    if cmp.0 == CmpOperator::Like {
+        let (rest, (glob, custom_message)) = context(
+            "expecting a quoted glob pattern after like, e.g. like \"arn:aws:s3:::prod-*\"",
+            cut(tuple((
+                preceded(zero_or_more_ws_or_comment, string_literal),
+                preceded(zero_or_more_ws_or_comment, opt(custom_message)),
+            ))),
+        )(rest)?;
+        return Ok((
+            rest,
+            mapper(GuardAccessClause {
+                access_clause: AccessClause {
+                    query,
+                    comparator: cmp,
+                    compare_with: Some(LetValue::Value(PathAwareValue::String((
+                        Path::root(),
+                        glob,
+                    )))),
+                    custom_message: custom_message.map(String::from),
+                    location,
+                },
+                negation: not.is_some(),
+            }),
+        ));
+    }
+
     if !does_comparator_have_rhs(&cmp.0) {
         let (rest, custom_message) = map(
             preceded(zero_or_more_ws_or_comment, opt(custom_message)),
@@ -991,9 +1199,15 @@ where
                         // Order does matter here as true/false and other values can be interpreted as access
                         //
                         map(tuple((
-                            parse_value, preceded(zero_or_more_ws_or_comment, opt(custom_message)))),
+                            path_aware_value, preceded(zero_or_more_ws_or_comment, opt(custom_message)))),
                             move |(rhs, msg)| {
-                                (Some(LetValue::Value(PathAwareValue::try_from(rhs).unwrap())), msg.map(String::from).or(None))
+                                (Some(LetValue::Value(rhs)), msg.map(String::from).or(None))
+                            }),
+                        map(tuple((
+                            preceded(zero_or_more_ws_or_comment, from_expr),
+                            preceded(zero_or_more_ws_or_comment, opt(custom_message)))),
+                            |(rhs, msg)| {
+                                (Some(LetValue::Value(rhs)), msg.map(String::from).or(None))
                             }),
                        map(tuple((
                             preceded(zero_or_more_ws_or_comment, function_expr),
@@ -1025,6 +1239,105 @@ where
     }
 }
 
+//
+//  exists_path_clause         = [not_keyword] "exists_path" *SP "(" *SP string *SP ")" *(LWSP/comment) [custom_message]
+//
+//  the path is a JSON-pointer like literal, segments are separated by "/", "~1" stands for a "/"
+//  and "~0" for a "~" inside a segment. Segments are never interpreted as variables or wildcards
+//
+fn exists_path_clause_with_map<'loc, M, T: 'loc>(
+    input: Span<'loc>,
+    mapper: M,
+) -> IResult<Span<'loc>, T>
+where
+    M: Fn(GuardAccessClause<'loc>) -> T,
+{
+    let location = FileLocation {
+        file_name: input.extra,
+        line: input.location_line(),
+        column: input.get_utf8_column() as u32,
+    };
+
+    let (rest, not) = preceded(zero_or_more_ws_or_comment, opt(not))(input)?;
+    let (rest, _) = terminated(tag("exists_path"), tuple((space0, char('('))))(rest)?;
+    let path_start = rest;
+    let (rest, path) = context(
+        "expecting a quoted path like \"Resources/MyBucket/Properties\"",
+        cut(delimited(space0, string_literal, space0)),
+    )(rest)?;
+    let (rest, _) = context("expecting ) to close exists_path", cut(char(')')))(rest)?;
+    let (rest, custom_message) = map(
+        preceded(zero_or_more_ws_or_comment, opt(custom_message)),
+        |msg| msg.map(String::from),
+    )(rest)?;
+
+    let query = path_literal_query(&path).map_err(|e| {
+        nom::Err::Failure(ParserError {
+            context: e,
+            span: path_start,
+            kind: ErrorKind::Escaped,
+        })
+    })?;
+
+    Ok((
+        rest,
+        mapper(GuardAccessClause {
+            access_clause: AccessClause {
+                query: AccessQuery {
+                    query,
+                    match_all: true,
+                },
+                comparator: (CmpOperator::Exists, false),
+                compare_with: None,
+                custom_message,
+                location,
+            },
+            negation: not.is_some(),
+        }),
+    ))
+}
+
+/// splits the literal path of an exists_path clause into its keys, unescaping ~1 and ~0
+pub(crate) fn path_literal_query<'loc>(path: &str) -> Result<Vec<QueryPart<'loc>>, String> {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    if path.is_empty() {
+        return Err(String::from("exists_path requires a non-empty path"));
+    }
+
+    let mut query = Vec::new();
+    for segment in path.split('/') {
+        if segment.is_empty() {
+            return Err(format!("empty segment in path {path}"));
+        }
+
+        let mut key = String::with_capacity(segment.len());
+        let mut chars = segment.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '~' => match chars.next() {
+                    Some('0') => key.push('~'),
+                    Some('1') => key.push('/'),
+                    _ => {
+                        return Err(format!(
+                            "invalid escape in segment {segment}, use ~0 for ~ and ~1 for /"
+                        ))
+                    }
+                },
+                _ => key.push(ch),
+            }
+        }
+
+        // keys starting with % are looked up as variables during evaluation
+        if key.starts_with('%') {
+            return Err(format!(
+                "segment {segment} starts with %, which is reserved for variables"
+            ));
+        }
+        query.push(QueryPart::Key(key));
+    }
+    Ok(query)
+}
+
 fn clause_with<A>(input: Span, access: A) -> IResult<Span, GuardClause>
 where
     A: Fn(Span) -> IResult<Span, AccessQuery>,
@@ -1039,6 +1352,7 @@ pub(crate) fn block_clause(input: Span) -> IResult<Span, GuardClause> {
         column: input.get_utf8_column() as u32,
     };
 
+    let (input, negation) = opt(not)(input)?;
     let (input, query) = access(input)?;
     let (input, not_empty) = opt(value(
         true,
@@ -1055,10 +1369,40 @@ pub(crate) fn block_clause(input: Span) -> IResult<Span, GuardClause> {
             },
             location,
             not_empty: not_empty.map_or(false, std::convert::identity),
+            negation: negation.is_some(),
         }),
     ))
 }
 
+//
+//  from_expr                  = "from" *SP "(" *SP string *SP "," *SP string *SP ")"
+//
+// loads the values the query, the second string, resolves to in the file named by the first
+// string when the rules are parsed
+//
+fn from_expr(input: Span) -> IResult<Span, PathAwareValue> {
+    let (rest, (file, query)) = preceded(
+        tuple((tag("from"), space0, char('('))),
+        cut(terminated(
+            separated_pair(
+                delimited(multispace0, string_literal, multispace0),
+                char(','),
+                delimited(multispace0, string_literal, multispace0),
+            ),
+            char(')'),
+        )),
+    )(input)?;
+
+    match values_from(&file, &query) {
+        Ok(values) => Ok((rest, values)),
+        Err(e) => Err(nom::Err::Failure(ParserError {
+            context: e.to_string(),
+            span: input,
+            kind: ErrorKind::Verify,
+        })),
+    }
+}
+
 fn function_expr(input: Span) -> IResult<Span, FunctionExpr> {
     let location = FileLocation {
         file_name: input.extra,
@@ -1075,7 +1419,10 @@ fn function_expr(input: Span) -> IResult<Span, FunctionExpr> {
         })
     })?;
 
-    if parameters.len() != name.get_expected_number_of_args() {
+    // custom functions check their arguments when they are called
+    if !matches!(name, FunctionName::Custom(_))
+        && parameters.len() != name.get_expected_number_of_args()
+    {
         return Err(nom::Err::Error(ParserError {
             context: format!(
                 "function: {name} requires: {} parameters to be passed, but received: {}",
@@ -1101,9 +1448,8 @@ pub(crate) fn let_value(input: Span) -> IResult<Span, LetValue> {
     preceded(
         zero_or_more_ws_or_comment,
         alt((
-            map(parse_value, |val| {
-                LetValue::Value(PathAwareValue::try_from(val).unwrap())
-            }),
+            map(path_aware_value, LetValue::Value),
+            map(from_expr, LetValue::Value),
             map(function_expr, LetValue::FunctionCall),
             map(access, LetValue::AccessClause),
         )),
@@ -1167,28 +1513,91 @@ pub(crate) fn parameterized_rule_call_clause(
 //
 fn clause(input: Span) -> IResult<Span, GuardClause> {
     alt((
-        when_block(single_clauses, clause, |conds, (assigns, cls)| {
-            GuardClause::WhenBlock(
-                conds,
-                Block {
-                    assignments: assigns,
-                    conjunctions: cls,
-                },
-            )
-        }),
+        when_block(single_clauses, clause, GuardClause::WhenBlock),
         block_clause,
+        |i| exists_path_clause_with_map(i, GuardClause::Clause),
         map(
             parameterized_rule_call_clause,
             GuardClause::ParameterizedNamedRule,
         ),
-        |i| clause_with(i, access),
+        guarded_clause,
     ))(input)
 }
 
+//
+//  guarded_clause             = clause [*SP "when" 1*(LWSP/comment) condition]
+//
+// a clause only evaluated when the condition on the same line holds, reads as a when block of
+// the clause. A "when" on the lines following the clause, or conditions followed by a block,
+// start a when block instead
+//
+fn guarded_clause(input: Span) -> IResult<Span, GuardClause> {
+    let (rest, guarded) = clause_with(input, access)?;
+
+    let consumed = &input.fragment()[..rest.location_offset() - input.location_offset()];
+    if consumed[consumed.trim_end().len()..].contains('\n') {
+        return Ok((rest, guarded));
+    }
+
+    let condition = preceded(
+        tuple((space0, when, one_or_more_ws_or_comment)),
+        single_condition,
+    )(rest);
+    match condition {
+        Ok((after, condition))
+            if peek(preceded(zero_or_more_ws_or_comment, char('{')))(after).is_err() =>
+        {
+            Ok((
+                after,
+                GuardClause::WhenBlock(
+                    vec![vec![condition]],
+                    Block {
+                        assignments: vec![],
+                        conjunctions: vec![vec![guarded]],
+                    },
+                    None,
+                ),
+            ))
+        }
+        Ok(_) | Err(nom::Err::Error(_)) => Ok((rest, guarded)),
+        Err(e) => Err(e),
+    }
+}
+
 fn single_clause(input: Span) -> IResult<Span, WhenGuardClause> {
     clause_with_map(input, access, WhenGuardClause::Clause)
 }
 
+fn single_condition(input: Span) -> IResult<Span, WhenGuardClause> {
+    //
+    // Order does matter here. Both rule_clause and access clause have the same syntax
+    // for the first part e.g
+    //
+    // s3_encrypted_bucket  or configuration.containers.*.port == 80
+    //
+    // the first part is a rule clause and the second part is access clause. Consider
+    // this example
+    //
+    // s3_encrypted_bucket or bucket_encryption EXISTS
+    //
+    // The first part if rule clause and second part is access. if we use the rule_clause
+    // to be first it would interpret bucket_encryption as the rule_clause. Now to prevent that
+    // we are using the alt form to first parse to see if it is clause and then try rules_clause
+    //
+    alt((
+        single_clause,
+        |i| exists_path_clause_with_map(i, WhenGuardClause::Clause),
+        map(
+            parameterized_rule_call_clause,
+            WhenGuardClause::ParameterizedNamedRule,
+        ),
+        map_res(rule_clause, |g| match g {
+            GuardClause::NamedRule(nr) => Ok(WhenGuardClause::NamedRule(nr)),
+            _ => Err(()),
+        }),
+    ))(input)
+}
+
 //
 //  rule_clause   =   (var_name (LWSP/comment)) /
 //                    (var_name [1*SP << anychar >>] (LWSP/comment)
@@ -1337,32 +1746,7 @@ where
 fn single_clauses(input: Span) -> IResult<Span, Conjunctions<WhenGuardClause>> {
     cnf_clauses(
         input,
-        //
-        // Order does matter here. Both rule_clause and access clause have the same syntax
-        // for the first part e.g
-        //
-        // s3_encrypted_bucket  or configuration.containers.*.port == 80
-        //
-        // the first part is a rule clause and the second part is access clause. Consider
-        // this example
-        //
-        // s3_encrypted_bucket or bucket_encryption EXISTS
-        //
-        // The first part if rule clause and second part is access. if we use the rule_clause
-        // to be first it would interpret bucket_encryption as the rule_clause. Now to prevent that
-        // we are using the alt form to first parse to see if it is clause and then try rules_clause
-        //
-        alt((
-            single_clause,
-            map(
-                parameterized_rule_call_clause,
-                WhenGuardClause::ParameterizedNamedRule,
-            ),
-            map(rule_clause, |g| match g {
-                GuardClause::NamedRule(nr) => WhenGuardClause::NamedRule(nr),
-                _ => unreachable!(),
-            }),
-        )),
+        single_condition,
         //
         // Mapping the GuardClause
         //
@@ -1419,19 +1803,106 @@ fn let_assignment_expr(input: Span) -> IResult<Span, String> {
     Ok((input, var_name))
 }
 
+///
+/// The start of a variable interpolated in a string assigned with `let`, e.g.
+/// `let arn_prefix = "arn:aws:s3:::${%bucket_prefix}"`
+///
+const INTERPOLATION_START: &str = "${%";
+
+///
+/// A string with `${%name}` in it becomes a call writing its text and the values of the variables
+/// one after the other. Other strings, `${AWS::Region}` of `Fn::Sub` included, are kept as is
+///
+fn interpolated_string(
+    input: Span,
+    value: PathAwareValue,
+) -> Result<LetValue, nom::Err<ParserError>> {
+    let (path, text) = match value {
+        PathAwareValue::String((path, text)) if text.contains(INTERPOLATION_START) => (path, text),
+        value => return Ok(LetValue::Value(value)),
+    };
+
+    let mut parameters = vec![];
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find(INTERPOLATION_START) {
+        let interpolated = &rest[start + INTERPOLATION_START.len()..];
+        let name = interpolated
+            .find('}')
+            .map(|end| &interpolated[..end])
+            .filter(|name| {
+                name.starts_with(|c: char| c.is_alphabetic())
+                    && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            })
+            .ok_or_else(|| {
+                nom::Err::Failure(ParserError {
+                    context: format!(
+                        "expecting a variable name followed by }} after {INTERPOLATION_START} in \"{text}\""
+                    ),
+                    span: input,
+                    kind: ErrorKind::Char,
+                })
+            })?;
+
+        if start > 0 {
+            parameters.push(LetValue::Value(PathAwareValue::String((
+                path.clone(),
+                rest[..start].to_string(),
+            ))));
+        }
+        parameters.push(LetValue::AccessClause(AccessQuery {
+            query: vec![QueryPart::Key(format!("%{name}"))],
+            match_all: true,
+        }));
+        rest = &interpolated[name.len() + 1..];
+    }
+    if !rest.is_empty() {
+        parameters.push(LetValue::Value(PathAwareValue::String((
+            path,
+            rest.to_string(),
+        ))));
+    }
+
+    Ok(LetValue::FunctionCall(FunctionExpr {
+        parameters,
+        name: FunctionName::Interpolate,
+        location: FileLocation {
+            file_name: input.extra,
+            line: input.location_line(),
+            column: input.get_column() as u32,
+        },
+    }))
+}
+
 fn assignment(input: Span) -> IResult<Span, LetExpr> {
     let (input, var_name) = let_assignment_expr(input)?;
 
-    match parse_value(input) {
-        Ok((input, value)) => Ok((
-            input,
+    match path_aware_value(input) {
+        Ok((rest, value)) => Ok((
+            rest,
             LetExpr {
                 var: var_name,
-                value: LetValue::Value(PathAwareValue::try_from(value).unwrap()),
+                value: interpolated_string(input, value)?,
             },
         )),
 
         Err(nom::Err::Error(_)) => {
+            //
+            // values loaded from another file are resolved now, like a value object
+            //
+            match preceded(zero_or_more_ws_or_comment, from_expr)(input) {
+                Ok((input, value)) => {
+                    return Ok((
+                        input,
+                        LetExpr {
+                            var: var_name,
+                            value: LetValue::Value(value),
+                        },
+                    ))
+                }
+                Err(nom::Err::Error(_)) => {}
+                Err(e) => return Err(e),
+            }
+
             //
             // if we did not succeed in parsing a value object, then
             // if must be an access pattern, or function call  else it is a failure
@@ -1468,6 +1939,19 @@ fn when(input: Span) -> IResult<Span, ()> {
     value((), alt((tag("when"), tag("WHEN"))))(input)
 }
 
+//
+// else keyword, only when a block follows it, as a key named else can still be queried
+//
+fn else_keyword(input: Span) -> IResult<Span, ()> {
+    value(
+        (),
+        terminated(
+            alt((tag("else"), tag("ELSE"))),
+            peek(preceded(zero_or_more_ws_or_comment, char('{'))),
+        ),
+    )(input)
+}
+
 #[allow(clippy::redundant_closure)]
 fn when_conditions<'loc, P>(
     condition_parser: P,
@@ -1495,6 +1979,11 @@ where
     }
 }
 
+enum BlockItem<'loc, T> {
+    Assignment(LetExpr<'loc>),
+    Clauses(Disjunctions<T>),
+}
+
 #[allow(clippy::redundant_closure)]
 fn block<'loc, T, P>(
     clause_parser: P,
@@ -1506,35 +1995,27 @@ where
     move |input: Span| {
         let (input, _start_block) = preceded(zero_or_more_ws_or_comment, char('{'))(input)?;
 
-        let mut conjunctions: Conjunctions<T> = Conjunctions::new();
-        let (input, results) = fold_many1(
+        let (input, (assignments, conjunctions)) = fold_many1(
             alt((
-                map(preceded(zero_or_more_ws_or_comment, assignment), |s| {
-                    (Some(s), None)
-                }),
+                map(
+                    preceded(zero_or_more_ws_or_comment, assignment),
+                    BlockItem::Assignment,
+                ),
                 map(
                     |i: Span| disjunction_clauses(i, |i: Span| clause_parser(i), true),
-                    |c: Disjunctions<T>| (None, Some(c)),
+                    BlockItem::Clauses,
                 ),
             )),
-            Vec::new(),
-            |mut acc, pair| {
-                acc.push(pair);
-                acc
+            (Vec::new(), Conjunctions::new()),
+            |(mut assignments, mut conjunctions), item| {
+                match item {
+                    BlockItem::Assignment(let_expr) => assignments.push(let_expr),
+                    BlockItem::Clauses(disjunctions) => conjunctions.push(disjunctions),
+                }
+                (assignments, conjunctions)
             },
         )(input)?;
 
-        let mut assignments = vec![];
-        for each in results {
-            match each {
-                (Some(let_expr), None) => {
-                    assignments.push(let_expr);
-                }
-                (None, Some(v)) => conjunctions.push(v),
-                (_, _) => unreachable!(),
-            }
-        }
-
         let (input, _end_block) = cut(preceded(zero_or_more_ws_or_comment, char('}')))(input)?;
 
         Ok((input, (assignments, conjunctions)))
@@ -1616,35 +2097,50 @@ fn type_block(input: Span) -> IResult<Span, TypeBlock> {
                 assignments,
                 conjunctions: clauses,
             },
-            query: vec![
-                QueryPart::Key("Resources".to_string()),
-                QueryPart::AllValues(None),
-                QueryPart::Filter(
-                    None,
-                    Conjunctions::from([Disjunctions::from([GuardClause::Clause(
-                        GuardAccessClause {
-                            negation: false,
-                            access_clause: AccessClause {
-                                query: AccessQuery {
-                                    query: vec![QueryPart::Key("Type".to_string())],
-                                    match_all: true,
-                                },
-                                custom_message: None,
-                                location,
-                                compare_with: Some(LetValue::Value(PathAwareValue::String((
-                                    Path::root(),
-                                    name.type_name,
-                                )))),
-                                comparator: (CmpOperator::Eq, false),
-                            },
-                        },
-                    )])]),
-                ),
-            ],
+            query: resources_with_type_query(
+                PathAwareValue::String((Path::root(), name.type_name)),
+                location,
+            ),
         },
     ))
 }
 
+//
+// Resources.*[ Type == <type> ], where type is either a string or a regex
+//
+pub(crate) fn resources_with_type_query(
+    type_value: PathAwareValue,
+    location: FileLocation,
+) -> Vec<QueryPart> {
+    vec![
+        QueryPart::Key("Resources".to_string()),
+        QueryPart::AllValues(None),
+        QueryPart::Filter(
+            None,
+            Conjunctions::from([Disjunctions::from([GuardClause::Clause(
+                GuardAccessClause {
+                    negation: false,
+                    access_clause: AccessClause {
+                        query: AccessQuery {
+                            query: vec![QueryPart::Key("Type".to_string())],
+                            match_all: true,
+                        },
+                        custom_message: None,
+                        location,
+                        compare_with: Some(LetValue::Value(type_value)),
+                        comparator: (CmpOperator::Eq, false),
+                    },
+                },
+            )])]),
+        ),
+    ]
+}
+
+//
+//  when_block                 = "when" 1*(LWSP/comment) conditions block [*(LWSP/comment) "else" block]
+//
+// the else block is evaluated when the conditions fail, instead of repeating them negated
+//
 #[allow(clippy::redundant_closure)]
 fn when_block<'loc, C, B, M, T, R>(
     conditions: C,
@@ -1656,15 +2152,26 @@ where
     B: Fn(Span<'loc>) -> IResult<Span<'loc>, T>,
     T: Clone + 'loc,
     R: 'loc,
-    M: Fn(Conjunctions<WhenGuardClause<'loc>>, (Vec<LetExpr<'loc>>, Conjunctions<T>)) -> R,
+    M: Fn(Conjunctions<WhenGuardClause<'loc>>, Block<'loc, T>, Option<Block<'loc, T>>) -> R,
 {
     move |input: Span| {
+        let to_block = |(assignments, conjunctions): (Vec<LetExpr<'loc>>, Conjunctions<T>)| Block {
+            assignments,
+            conjunctions,
+        };
         map(
             preceded(
                 zero_or_more_ws_or_comment,
-                pair(when_conditions(|p| conditions(p)), block(|p| block_fn(p))),
+                tuple((
+                    when_conditions(|p| conditions(p)),
+                    map(block(|p| block_fn(p)), to_block),
+                    opt(preceded(
+                        preceded(zero_or_more_ws_or_comment, else_keyword),
+                        cut(map(block(|p| block_fn(p)), to_block)),
+                    )),
+                )),
             ),
-            |(w, b)| mapper(w, b),
+            |(w, b, e)| mapper(w, b, e),
         )(input)
     }
 }
@@ -1675,23 +2182,10 @@ fn rule_block_clause(input: Span) -> IResult<Span, RuleClause> {
             preceded(zero_or_more_ws_or_comment, type_block),
             RuleClause::TypeBlock,
         ),
-        map(
-            preceded(
-                zero_or_more_ws_or_comment,
-                pair(
-                    when_conditions(single_clauses),
-                    block(alt((clause, rule_clause))),
-                ),
-            ),
-            |(conditions, block)| {
-                RuleClause::WhenBlock(
-                    conditions,
-                    Block {
-                        assignments: block.0,
-                        conjunctions: block.1,
-                    },
-                )
-            },
+        when_block(
+            single_clauses,
+            alt((clause, rule_clause)),
+            RuleClause::WhenBlock,
         ),
         map(
             preceded(zero_or_more_ws_or_comment, alt((clause, rule_clause))),
@@ -1700,10 +2194,312 @@ fn rule_block_clause(input: Span) -> IResult<Span, RuleClause> {
     ))(input)
 }
 
+//
+// Policy builtins, shorthands for the most common governance policies that expand into a
+// complete named rule with a default message
+//
+//  ABNF        =  forbid-resource-type / forbid-property
+//  forbid-resource-type = "forbid_resource_type" "(" (regex / string) ")"
+//  forbid-property      = "forbid_property" "(" type-name "," quoted-access ")"
+//
+fn policy_builtin(input: Span) -> IResult<Span, Rule> {
+    preceded(
+        zero_or_more_ws_or_comment,
+        alt((forbid_resource_type, forbid_property)),
+    )(input)
+}
+
+#[allow(clippy::redundant_closure)]
+fn builtin_arguments<'loc, P, R>(parser: P) -> impl Fn(Span<'loc>) -> IResult<Span<'loc>, R>
+where
+    P: Fn(Span<'loc>) -> IResult<Span<'loc>, R>,
+{
+    move |input: Span| {
+        let (input, _open) = preceded(space0, char('('))(input)?;
+        cut(terminated(
+            delimited(multispace0, |s| parser(s), multispace0),
+            char(')'),
+        ))(input)
+    }
+}
+
+fn builtin_rule_name(builtin: &str, parts: &[&str]) -> String {
+    let mut rule_name = String::from(builtin);
+    for part in parts {
+        for word in part.split(|c: char| !c.is_ascii_alphanumeric()) {
+            if !word.is_empty() {
+                rule_name.push('_');
+                rule_name.push_str(word);
+            }
+        }
+    }
+    rule_name
+}
+
+//
+// forbid_resource_type(/AWS::IAM::User/) expands to
+//
+// rule forbid_resource_type_regex_AWS_IAM_User {
+//     Resources.*[ Type == /AWS::IAM::User/ ] EMPTY
+//     << resources with type matching /AWS::IAM::User/ are forbidden >>
+// }
+//
+// and forbid_resource_type("AWS::IAM::User") to a rule named forbid_resource_type_exact_AWS_IAM_User,
+// so both can be told apart in reports
+//
+fn forbid_resource_type(input: Span) -> IResult<Span, Rule> {
+    let location = FileLocation {
+        file_name: input.extra,
+        line: input.location_line(),
+        column: input.get_utf8_column() as u32,
+    };
+    let (input, _builtin) = tag("forbid_resource_type")(input)?;
+    let arguments = input;
+    let (input, type_value) = builtin_arguments(map_res(
+        alt((parse_regex, parse_string)),
+        PathAwareValue::try_from,
+    ))(input)?;
+
+    let (rule_name, message) = match &type_value {
+        PathAwareValue::Regex((_, regex)) => (
+            builtin_rule_name("forbid_resource_type", &["regex", regex]),
+            format!("resources with type matching /{regex}/ are forbidden"),
+        ),
+        PathAwareValue::String((_, type_name)) => (
+            builtin_rule_name("forbid_resource_type", &["exact", type_name]),
+            format!("resources of type {type_name} are forbidden"),
+        ),
+        _ => {
+            return Err(nom::Err::Failure(ParserError {
+                context: String::from("expecting a quoted resource type or a /regex/"),
+                span: arguments,
+                kind: ErrorKind::Verify,
+            }))
+        }
+    };
+
+    let clause = GuardClause::Clause(GuardAccessClause {
+        negation: false,
+        access_clause: AccessClause {
+            query: AccessQuery {
+                query: resources_with_type_query(type_value, location.clone()),
+                match_all: true,
+            },
+            comparator: (CmpOperator::Empty, false),
+            compare_with: None,
+            custom_message: Some(message),
+            location,
+        },
+    });
+
+    Ok((
+        input,
+        Rule {
+            rule_name,
+            conditions: None,
+            annotations: RuleAnnotations::default(),
+            block: Block {
+                assignments: vec![],
+                conjunctions: vec![vec![RuleClause::Clause(clause)]],
+            },
+        },
+    ))
+}
+
+//
+// forbid_property(AWS::Lambda::Function, "Code.ZipFile") expands to
+//
+// rule forbid_property_AWS_Lambda_Function_Code_ZipFile {
+//     AWS::Lambda::Function {
+//         Properties.Code.ZipFile NOT EXISTS
+//         << property Code.ZipFile is forbidden on AWS::Lambda::Function resources >>
+//     }
+// }
+//
+fn forbid_property(input: Span) -> IResult<Span, Rule> {
+    let location = FileLocation {
+        file_name: input.extra,
+        line: input.location_line(),
+        column: input.get_utf8_column() as u32,
+    };
+    let (input, _builtin) = tag("forbid_property")(input)?;
+    let (input, (name, (property, property_query))) = builtin_arguments(separated_pair(
+        terminated(type_name, multispace0),
+        char(','),
+        preceded(
+            multispace0,
+            alt((
+                delimited(char('"'), consumed_access, char('"')),
+                delimited(char('\''), consumed_access, char('\'')),
+            )),
+        ),
+    ))(input)?;
+
+    let rule_name = builtin_rule_name("forbid_property", &[&name.type_name, property]);
+    let message = format!(
+        "property {property} is forbidden on {type_name} resources",
+        type_name = name.type_name
+    );
+
+    let mut query = vec![QueryPart::Key("Properties".to_string())];
+    query.extend(property_query.query);
+
+    let clause = GuardClause::Clause(GuardAccessClause {
+        negation: false,
+        access_clause: AccessClause {
+            query: AccessQuery {
+                query,
+                match_all: property_query.match_all,
+            },
+            comparator: (CmpOperator::Exists, true),
+            compare_with: None,
+            custom_message: Some(message),
+            location: location.clone(),
+        },
+    });
+
+    Ok((
+        input,
+        Rule {
+            rule_name,
+            conditions: None,
+            annotations: RuleAnnotations::default(),
+            block: Block {
+                assignments: vec![],
+                conjunctions: vec![vec![RuleClause::TypeBlock(TypeBlock {
+                    type_name: name.type_name.clone(),
+                    conditions: None,
+                    block: Block {
+                        assignments: vec![],
+                        conjunctions: vec![vec![clause]],
+                    },
+                    query: resources_with_type_query(
+                        PathAwareValue::String((Path::root(), name.type_name)),
+                        location,
+                    ),
+                })]],
+            },
+        },
+    ))
+}
+
+//
+// access query along with the text it was parsed from
+//
+fn consumed_access(input: Span) -> IResult<Span, (&str, AccessQuery)> {
+    let (remaining, query) = access(input)?;
+    let consumed = &input.fragment()[..input.fragment().len() - remaining.fragment().len()];
+    Ok((remaining, (consumed, query)))
+}
+
+//
+// @on-missing(fail|skip)
+//
+fn on_missing_annotation(input: Span) -> IResult<Span, OnMissing> {
+    preceded(
+        tag("@on-missing"),
+        cut(delimited(
+            preceded(space0, char('(')),
+            delimited(
+                space0,
+                alt((
+                    value(OnMissing::Fail, tag("fail")),
+                    value(OnMissing::Skip, tag("skip")),
+                )),
+                space0,
+            ),
+            char(')'),
+        )),
+    )(input)
+}
+
+fn tags_annotation(input: Span) -> IResult<Span, Vec<String>> {
+    preceded(
+        tag("@tags"),
+        cut(delimited(
+            preceded(space0, char('(')),
+            separated_nonempty_list(
+                char(','),
+                delimited(
+                    multispace0,
+                    map(
+                        take_while1(|c: char| c.is_alphanumeric() || "_-.:/".contains(c)),
+                        |tag: Span| tag.fragment().to_string(),
+                    ),
+                    multispace0,
+                ),
+            ),
+            char(')'),
+        )),
+    )(input)
+}
+
+fn severity_annotation(input: Span) -> IResult<Span, Severity> {
+    preceded(
+        tag("@severity"),
+        cut(delimited(
+            preceded(space0, char('(')),
+            delimited(
+                space0,
+                alt((
+                    value(Severity::Error, tag("error")),
+                    value(Severity::Warning, tag("warning")),
+                )),
+                space0,
+            ),
+            char(')'),
+        )),
+    )(input)
+}
+
+enum RuleAnnotation {
+    OnMissing(OnMissing),
+    Tags(Vec<String>),
+    Severity(Severity),
+}
+
+//
+// annotations before the rule keyword, or right after the rule name, e.g.
+//
+// @on-missing(skip)
+// @severity(warning)
+// rule s3_encryption @tags(s3, encryption) { ... }
+//
+fn rule_annotations(input: Span) -> IResult<Span, RuleAnnotations> {
+    fold_many0(
+        preceded(
+            zero_or_more_ws_or_comment,
+            alt((
+                map(on_missing_annotation, RuleAnnotation::OnMissing),
+                map(tags_annotation, RuleAnnotation::Tags),
+                map(severity_annotation, RuleAnnotation::Severity),
+            )),
+        ),
+        RuleAnnotations::default(),
+        |mut annotations, annotation| {
+            match annotation {
+                RuleAnnotation::OnMissing(on_missing) => annotations.on_missing = Some(on_missing),
+                RuleAnnotation::Tags(tags) => annotations.tags.extend(tags),
+                RuleAnnotation::Severity(severity) => annotations.severity = Some(severity),
+            }
+            annotations
+        },
+    )(input)
+}
+
 //
 // rule block
 //
 fn rule_block(input: Span) -> IResult<Span, Rule> {
+    let (input, mut annotations) = rule_annotations(input)?;
+    //
+    // private rules are helpers for other rules, they are not reported on their own
+    //
+    let (input, private) = opt(preceded(
+        zero_or_more_ws_or_comment,
+        terminated(tag("private"), one_or_more_ws_or_comment),
+    ))(input)?;
+    annotations.private = private.is_some();
     //
     // rule is followed by space
     //
@@ -1711,6 +2507,10 @@ fn rule_block(input: Span) -> IResult<Span, Rule> {
     let (input, _space) = one_or_more_ws_or_comment(input)?;
 
     let (input, rule_name) = cut(var_name)(input)?;
+    let (input, trailing) = rule_annotations(input)?;
+    annotations.on_missing = trailing.on_missing.or(annotations.on_missing);
+    annotations.tags.extend(trailing.tags);
+    annotations.severity = trailing.severity.or(annotations.severity);
     let (input, conditions) = opt(when_conditions(single_clauses))(input)?;
     let (input, (assignments, conjunctions)) = cut(block(rule_block_clause))(input)?;
 
@@ -1723,6 +2523,7 @@ fn rule_block(input: Span) -> IResult<Span, Rule> {
                 assignments,
                 conjunctions,
             },
+            annotations,
         },
     ))
 }
@@ -1748,6 +2549,7 @@ fn parameter_names(input: Span) -> IResult<Span, indexmap::IndexSet<String>> {
 // Parameterized Rule
 //
 fn parameterized_rule_block(input: Span) -> IResult<Span, ParameterizedRule> {
+    let (input, annotations) = rule_annotations(input)?;
     //
     // rule is followed by space
     //
@@ -1772,6 +2574,7 @@ fn parameterized_rule_block(input: Span) -> IResult<Span, ParameterizedRule> {
                     conjunctions,
                 },
                 conditions: None,
+                annotations,
             },
         },
     ))
@@ -1807,7 +2610,11 @@ where
 enum Exprs<'loc> {
     Assignment(LetExpr<'loc>),
     DefaultTypeBlock(Disjunctions<TypeBlock<'loc>>),
-    DefaultWhenBlock(WhenConditions<'loc>, Block<'loc, GuardClause<'loc>>),
+    DefaultWhenBlock(
+        WhenConditions<'loc>,
+        Block<'loc, GuardClause<'loc>>,
+        Option<Block<'loc, GuardClause<'loc>>>,
+    ),
     DefaultClause(Disjunctions<GuardClause<'loc>>),
     Rule(Rule<'loc>),
     ParameterizedRule(ParameterizedRule<'loc>),
@@ -1826,6 +2633,7 @@ pub(crate) fn get_rule_name<'b>(rule_file_name: &str, rule_name: &'b str) -> &'b
 // Rules File
 //
 pub(crate) fn rules_file(input: Span) -> Result<Option<RulesFile>, Error> {
+    check_nesting(input)?;
     let input = match zero_or_more_ws_or_comment(input) {
         Ok(input) => {
             if input.0.is_empty() {
@@ -1838,22 +2646,7 @@ pub(crate) fn rules_file(input: Span) -> Result<Option<RulesFile>, Error> {
     };
 
     let exprs = all_consuming(fold_many1(
-        remove_whitespace_comments(alt((
-            map(assignment, Exprs::Assignment),
-            map(parameterized_rule_block, Exprs::ParameterizedRule),
-            map(rule_block, Exprs::Rule),
-            map(type_block_clauses, Exprs::DefaultTypeBlock),
-            when_block(single_clauses, alt((clause, rule_clause)), |c, b| {
-                Exprs::DefaultWhenBlock(
-                    c,
-                    Block {
-                        assignments: b.0,
-                        conjunctions: b.1,
-                    },
-                )
-            }),
-            map(default_clauses, Exprs::DefaultClause),
-        ))),
+        remove_whitespace_comments(top_level_expr),
         Vec::new(),
         |mut acc, expr| {
             acc.push(expr);
@@ -1862,6 +2655,80 @@ pub(crate) fn rules_file(input: Span) -> Result<Option<RulesFile>, Error> {
     ))(input)?
     .1;
 
+    Ok(Some(assemble_rules_file(input, exprs)))
+}
+
+///
+/// Fails on the first bracket, brace or parenthesis nested more than [MAX_NESTING_DEPTH] levels
+/// deep, before the parser recurses into it. Those in strings, regular expressions, custom
+/// messages and comments are not counted
+///
+fn check_nesting(input: Span) -> Result<(), Error> {
+    let text = *input.fragment();
+    let mut depth = 0_usize;
+    let mut chars = text.char_indices().peekable();
+    while let Some((offset, ch)) = chars.next() {
+        let closing = match ch {
+            '[' | '{' | '(' => {
+                depth += 1;
+                if depth > MAX_NESTING_DEPTH {
+                    return Err(Error::from(nom::Err::Failure(ParserError {
+                        context: format!(
+                            "expecting at most {MAX_NESTING_DEPTH} levels of nested brackets, braces or parentheses"
+                        ),
+                        span: input.slice(offset..),
+                        kind: ErrorKind::TooLarge,
+                    })));
+                }
+                continue;
+            }
+            ']' | '}' | ')' => {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+            '"' | '\'' | '/' => ch,
+            '#' => '\n',
+            '<' if matches!(chars.peek(), Some((_, '<'))) => '>',
+            _ => continue,
+        };
+
+        // skips to the end of the string, regex, comment or message, escaped characters included
+        while let Some((_, next)) = chars.next() {
+            match next {
+                '\\' if closing != '\n' && closing != '>' => {
+                    chars.next();
+                }
+                '>' if closing == '>' => {
+                    if matches!(chars.peek(), Some((_, '>'))) {
+                        chars.next();
+                        break;
+                    }
+                }
+                next if next == closing => break,
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+fn top_level_expr(input: Span) -> IResult<Span, Exprs> {
+    alt((
+        map(assignment, Exprs::Assignment),
+        map(parameterized_rule_block, Exprs::ParameterizedRule),
+        map(rule_block, Exprs::Rule),
+        map(policy_builtin, Exprs::Rule),
+        map(type_block_clauses, Exprs::DefaultTypeBlock),
+        when_block(
+            single_clauses,
+            alt((clause, rule_clause)),
+            Exprs::DefaultWhenBlock,
+        ),
+        map(default_clauses, Exprs::DefaultClause),
+    ))(input)
+}
+
+fn assemble_rules_file<'a>(input: Span<'a>, exprs: Vec<Exprs<'a>>) -> RulesFile<'a> {
     let mut global_assignments = Vec::with_capacity(exprs.len());
     let mut default_rule_clauses = Vec::with_capacity(exprs.len());
     let mut named_rules = Vec::with_capacity(exprs.len());
@@ -1884,8 +2751,8 @@ pub(crate) fn rules_file(input: Span) -> Result<Option<RulesFile>, Error> {
                     .map(RuleClause::TypeBlock)
                     .collect(),
             ),
-            Exprs::DefaultWhenBlock(w, b) => {
-                default_rule_clauses.push(vec![RuleClause::WhenBlock(w, b)])
+            Exprs::DefaultWhenBlock(w, b, e) => {
+                default_rule_clauses.push(vec![RuleClause::WhenBlock(w, b, e)])
             }
         }
     }
@@ -1903,6 +2770,7 @@ pub(crate) fn rules_file(input: Span) -> Result<Option<RulesFile>, Error> {
 
         let default_rule = Rule {
             conditions: None,
+            annotations: RuleAnnotations::default(),
             rule_name: default_rule_name,
             block: Block {
                 assignments: vec![],
@@ -1912,11 +2780,91 @@ pub(crate) fn rules_file(input: Span) -> Result<Option<RulesFile>, Error> {
         named_rules.insert(0, default_rule);
     }
 
-    Ok(Some(RulesFile {
+    RulesFile {
         assignments: global_assignments,
         guard_rules: named_rules,
         parameterized_rules,
-    }))
+    }
+}
+
+///
+/// Parses a rules file like [rules_file], but instead of stopping at the first syntax error it
+/// skips the malformed rule, assignment or default clause and carries on with the rest of the
+/// file. Parsing resumes at the next line that starts in the first column with something other
+/// than a closing brace or a comment, which is where rules and assignments begin in a formatted
+/// rules file. A malformed clause makes the whole rule it is in fail.
+///
+/// Returns the rules that parsed, `None` when none did, along with every syntax error found
+///
+pub(crate) fn rules_file_recovering(input: Span) -> (Option<RulesFile>, Vec<Error>) {
+    if let Err(e) = check_nesting(input) {
+        return (None, vec![e]);
+    }
+
+    let mut rest = match zero_or_more_ws_or_comment(input) {
+        Ok((rest, _)) => rest,
+        Err(_) => input,
+    };
+
+    let mut exprs = vec![];
+    let mut errors = vec![];
+    while !rest.fragment().is_empty() {
+        match remove_whitespace_comments(top_level_expr)(rest) {
+            Ok((remaining, expr)) if remaining.location_offset() > rest.location_offset() => {
+                exprs.push(expr);
+                rest = remaining;
+            }
+            Ok(_) => {
+                errors.push(Error::from(nom::Err::Error(ParserError {
+                    context: String::from("expecting a rule, an assignment or a clause"),
+                    span: rest,
+                    kind: ErrorKind::Many1,
+                })));
+                rest = next_top_level_line(rest);
+            }
+            Err(e) => {
+                errors.push(Error::from(e));
+                rest = next_top_level_line(rest);
+            }
+        }
+    }
+
+    match exprs.is_empty() {
+        true => (None, errors),
+        false => (Some(assemble_rules_file(input, exprs)), errors),
+    }
+}
+
+///
+/// The syntax errors of a rules file found by [rules_file_recovering] in the rules,
+/// assignments and clauses starting after the given line, to report the errors that follow the
+/// one [rules_file] stopped at
+///
+pub(crate) fn syntax_errors_after(input: Span, line: u32) -> Vec<Error> {
+    let text = input.fragment();
+    let start = match line {
+        0 | 1 => 0,
+        _ => text
+            .match_indices('\n')
+            .nth(line as usize - 2)
+            .map_or(text.len(), |(offset, _)| offset + 1),
+    };
+    rules_file_recovering(next_top_level_line(input.slice(start..))).1
+}
+
+/// the rest of the input from the first line after the current one that starts in the first
+/// column with something other than whitespace, a closing brace or a comment
+fn next_top_level_line(input: Span) -> Span {
+    let text = input.fragment();
+    let mut offset = text.find('\n').map_or(text.len(), |end| end + 1);
+    while offset < text.len() {
+        let line = &text[offset..];
+        match line.chars().next() {
+            Some(c) if !c.is_whitespace() && c != '}' && c != '#' => break,
+            _ => offset += line.find('\n').map_or(line.len(), |end| end + 1),
+        }
+    }
+    input.slice(offset..)
 }
 
 //
@@ -2022,7 +2970,7 @@ impl<'a> TryFrom<&'a str> for RulesFile<'a> {
 
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
         let span = from_str2(value);
-        Ok(rules_file(span)?.unwrap())
+        rules_file(span)?.ok_or_else(|| Error::ParseError(String::from("no rules to parse")))
     }
 }
 
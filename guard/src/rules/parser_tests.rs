@@ -1,3 +1,4 @@
+use crate::rules::display::let_value_source;
 use crate::rules::path_value::PathAwareValue;
 use crate::rules::values::WithinRange;
 use crate::rules::{EvaluationContext, EvaluationType, Status};
@@ -140,6 +141,46 @@ fn test_parse_float(#[case] s: &str, #[case] expected: Value) {
     assert_eq!(parse_float(from_str2(s)), Ok((cmp, expected)));
 }
 
+#[rstest::rstest]
+#[case("5GB", Value::Int(5_000_000_000))]
+#[case("512MiB", Value::Int(536_870_912))]
+#[case("1.5KiB", Value::Int(1536))]
+#[case("30d", Value::Int(2_592_000))]
+#[case("15m", Value::Int(900))]
+#[case("1500ms", Value::Float(1.5))]
+#[case("2w", Value::Int(1_209_600))]
+fn test_parse_quantity(#[case] s: &str, #[case] expected: Value) {
+    let cmp = unsafe { Span::new_from_raw_offset(s.len(), 1, "", "") };
+    assert_eq!(parse_quantity(from_str2(s)), Ok((cmp, expected.clone())));
+    assert_eq!(parse_value(from_str2(s)), Ok((cmp, expected)));
+}
+
+#[rstest::rstest]
+#[case("5gb")]
+#[case("5 GB")]
+#[case("12e+2")]
+#[case("GB")]
+fn test_parse_quantity_error(#[case] s: &str) {
+    assert!(matches!(
+        parse_quantity(from_str2(s)),
+        Err(nom::Err::Error(_))
+    ));
+}
+
+#[test]
+fn test_parse_quantity_range() {
+    let (rest, value) = parse_value(from_str2("r[1GiB, 2GiB)")).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        Value::RangeInt(RangeType {
+            lower: 1_073_741_824,
+            upper: 2_147_483_648,
+            inclusive: LOWER_INCLUSIVE,
+        }),
+        value
+    );
+}
+
 #[test]
 fn test_parse_float_error() {
     let s = "error";
@@ -165,23 +206,15 @@ fn test_parse_regex() {
 
     let improperly_escaped_regular_expression =
         "/arn:[\\w+=/,.@-]+:[\\w+=/,.@-]+:[\\w+=/,.@-]*:[0-9]*:[\\w+=,.@-]+(/[\\w+=,.@-]+)*/";
-    let _cmp = unsafe {
-        Span::new_from_raw_offset(
-            11,
-            1,
-            ",.@-]+:[\\w+=/,.@-]+:[\\w+=/,.@-]*:[0-9]*:[\\w+=,.@-]+(/[\\w+=,.@-]+)*/",
-            "",
-        )
-    };
     assert_eq!(
         parse_regex(from_str2(improperly_escaped_regular_expression)),
-        Err(nom::Err::Error(ParserError {
-                context: "Could not parse regular expression: Parsing error at position 9: Invalid character class".to_string(),
+        Err(nom::Err::Failure(ParserError {
+                context: "expecting a valid regular expression, Parsing error at position 9: Invalid character class".to_string(),
                 kind: ErrorKind::RegexpMatch,
                 span: unsafe { Span::new_from_raw_offset(
+                    10,
                     1,
-                    1,
-                    "arn:[\\w+=/,.@-]+:[\\w+=/,.@-]+:[\\w+=/,.@-]*:[0-9]*:[\\w+=,.@-]+(/[\\w+=,.@-]+)*/",
+                    "/,.@-]+:[\\w+=/,.@-]+:[\\w+=/,.@-]*:[0-9]*:[\\w+=,.@-]+(/[\\w+=,.@-]+)*/",
                     ""
                 ) },
             }))
@@ -202,6 +235,40 @@ fn test_parse_regex() {
     );
 }
 
+#[test]
+fn test_parse_regex_flags() {
+    let s = "/^prod-/i";
+    let cmp = unsafe { Span::new_from_raw_offset(s.len(), 1, "", "") };
+    assert_eq!(
+        parse_regex(from_str2(s)),
+        Ok((cmp, Value::Regex("(?i)^prod-".to_string())))
+    );
+
+    let s = "/^prod-$/mim ]";
+    let cmp = unsafe { Span::new_from_raw_offset(s.len() - 2, 1, " ]", "") };
+    assert_eq!(
+        parse_regex(from_str2(s)),
+        Ok((cmp, Value::Regex("(?im)^prod-$".to_string())))
+    );
+
+    let s = "/^prod-/ix";
+    assert_eq!(
+        parse_regex(from_str2(s)),
+        Err(nom::Err::Failure(ParserError {
+            context: "expecting the regular expression flags i or m".to_string(),
+            kind: ErrorKind::RegexpMatch,
+            span: unsafe { Span::new_from_raw_offset(8, 1, "ix", "") },
+        }))
+    );
+
+    let (_, regex) = parse_regex(from_str2("/^prod-/i")).unwrap();
+    let regex = PathAwareValue::try_from(regex).unwrap();
+    assert_eq!(
+        PathAwareValue::String((Path::root(), "PROD-logs".to_string())),
+        regex
+    );
+}
+
 #[test]
 fn test_parse_scalar() {
     let s = "1234";
@@ -2755,6 +2822,7 @@ fn test_rule_block() {
     let expectations = [Ok((
         unsafe { Span::new_from_raw_offset(examples[0].len(), 24, "", "") },
         Rule {
+            annotations: RuleAnnotations::default(),
             rule_name: String::from("example_rule"),
             conditions: Some(Conjunctions::from([Disjunctions::from([
                 WhenGuardClause::Clause(GuardAccessClause {
@@ -3164,6 +3232,7 @@ fn test_try_from_rule_block() -> Result<(), Error> {
     "#;
     let rule_statement = Rule::try_from(rule)?;
     let expected = Rule {
+        annotations: RuleAnnotations::default(),
         rule_name: String::from("s3_secure_exception"),
         conditions: None,
         block: Block {
@@ -3421,6 +3490,7 @@ fn test_rules_file_default_rules() -> Result<(), Error> {
     AWS::ApiGateway::Method Properties.ResourceId == "ApiGatewayBadBot.RootResourceId" <<Should be root resource id>> or  AWS::ApiGateway::Method Properties.ResourceId == "ApiGatewayBadBotResource"
     "#;
     let default_rule = Rule {
+        annotations: RuleAnnotations::default(),
         rule_name: String::from("default"),
         conditions: None,
         block: Block {
@@ -3751,6 +3821,7 @@ fn parameterized_rule_parse_test() -> Result<(), Error> {
     let expected = ParameterizedRule {
         parameter_names: parameters,
         rule: Rule {
+            annotations: RuleAnnotations::default(),
             rule_name: "policy_checks".to_string(),
             conditions: None,
             block: Block {
@@ -3794,6 +3865,7 @@ fn parameterized_rule_parse_test() -> Result<(), Error> {
                             ])]),
                         },
                         not_empty: false,
+                        negation: false,
                     }),
                 )])]),
             },
@@ -3960,6 +4032,7 @@ fn test_block_properties() -> Result<(), Error> {
             ],
         },
         not_empty: false,
+        negation: false,
     });
     assert_eq!(block_clause, expected);
     Ok(())
@@ -4096,12 +4169,14 @@ fn parameterized_rule_block() -> Result<(), Error> {
     let expected = ParameterizedRule {
         parameter_names,
         rule: Rule {
+            annotations: RuleAnnotations::default(),
             rule_name: "iam_disallowed_attributes_check".to_string(),
             block: Block {
                 assignments: vec![],
                 conjunctions: Conjunctions::from([Disjunctions::from([RuleClause::Clause(
                     GuardClause::BlockClause(BlockGuardClause {
                         not_empty: false,
+                        negation: false,
                         query: AccessQuery {
                             match_all: true,
                             query: vec![QueryPart::Key("%iam_statements".to_string())],
@@ -4475,7 +4550,7 @@ fn parameterized_clause_in_when_condition() -> Result<(), Error> {
                         assert!(matches!(&prc.parameters[1], LetValue::Value(_)));
                     }
 
-                    GuardClause::WhenBlock(conds, _) => {
+                    GuardClause::WhenBlock(conds, _, _) => {
                         assert_eq!(conds.len(), 2);
                         match &conds[0][0] {
                             WhenGuardClause::ParameterizedNamedRule(prc) => {
@@ -4604,6 +4679,20 @@ fn test_parse_regex_inner_when_regex_is_not_valid() {
     assert_eq!(expected_invalid, parse_regex_inner(invalid_cmp));
 }
 
+#[test]
+fn test_parse_regex_error_points_at_the_invalid_part() {
+    // the escaped slash is one character longer in the rules than in the pattern
+    let rules = "rule r { Properties.Arn == /arn\\/(a|b/ }";
+    match rules_file(from_str2(rules)).unwrap_err() {
+        Error::RulesParseError { location, .. } => {
+            assert_eq!(1, location.line);
+            assert_eq!(38, location.column);
+            assert!(location.context.contains("valid regular expression"));
+        }
+        error => panic!("{error}"),
+    }
+}
+
 #[test]
 fn test_parse_regex_inner_when_regex_is_valid() {
     let valid = "\\w+/";
@@ -4694,6 +4783,57 @@ fn test_parse_assignment_with_function_call2() {
     }
 }
 
+#[test]
+fn test_parse_assignment_with_interpolation() {
+    let input = r#"let arn_prefix = "arn:aws:s3:::${%bucket_prefix}-${%environment}/*""#;
+    let (rest, assigned) = assignment(from_str2(input)).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!("arn_prefix", assigned.var);
+
+    match &assigned.value {
+        LetValue::FunctionCall(function) => {
+            assert_eq!(FunctionName::Interpolate, function.name);
+            assert_eq!(
+                vec![
+                    "\"arn:aws:s3:::\"",
+                    "%bucket_prefix",
+                    "\"-\"",
+                    "%environment",
+                    "\"/*\""
+                ],
+                function
+                    .parameters
+                    .iter()
+                    .map(let_value_source)
+                    .collect::<Vec<_>>()
+            );
+        }
+        _ => unreachable!(),
+    }
+    assert_eq!(
+        r#""arn:aws:s3:::${%bucket_prefix}-${%environment}/*""#,
+        let_value_source(&assigned.value)
+    );
+
+    // the placeholders of Fn::Sub are kept as written
+    let (_, assigned) = assignment(from_str2(r#"let region = "${AWS::Region}""#)).unwrap();
+    assert!(matches!(
+        assigned.value,
+        LetValue::Value(PathAwareValue::String((_, ref text))) if text == "${AWS::Region}"
+    ));
+
+    for input in [
+        r#"let arn = "arn:${%bucket_prefix""#,
+        r#"let arn = "arn:${%bucket.name}""#,
+        r#"let arn = "arn:${%}""#,
+    ] {
+        assert!(
+            matches!(assignment(from_str2(input)), Err(nom::Err::Failure(_))),
+            "{input}"
+        );
+    }
+}
+
 #[test]
 fn test_get_rule_name() {
     let rule_clause_name1 = "harry";
@@ -4709,3 +4849,764 @@ fn test_get_rule_name() {
         rule_clause_name1
     );
 }
+
+#[test]
+fn test_policy_builtins() -> Result<(), Error> {
+    let s = r#"
+    forbid_resource_type(/AWS::IAM::User/)
+    forbid_resource_type( "AWS::IAM::AccessKey" )
+    forbid_property(AWS::Lambda::Function, "Code.ZipFile")
+    forbid_resource_type("AWS::IAM::User")
+    "#;
+    let rules_file = rules_file(from_str2(s))?.unwrap();
+    assert_eq!(rules_file.guard_rules.len(), 4);
+
+    let rule_names = rules_file
+        .guard_rules
+        .iter()
+        .map(|rule| rule.rule_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rule_names,
+        vec![
+            "forbid_resource_type_regex_AWS_IAM_User",
+            "forbid_resource_type_exact_AWS_IAM_AccessKey",
+            "forbid_property_AWS_Lambda_Function_Code_ZipFile",
+            "forbid_resource_type_exact_AWS_IAM_User",
+        ]
+    );
+
+    match &rules_file.guard_rules[0].block.conjunctions[0][0] {
+        RuleClause::Clause(GuardClause::Clause(clause)) => {
+            assert_eq!(clause.access_clause.comparator, (CmpOperator::Empty, false));
+            assert_eq!(
+                clause.access_clause.custom_message.as_deref(),
+                Some("resources with type matching /AWS::IAM::User/ are forbidden")
+            );
+            assert_eq!(clause.access_clause.query.query.len(), 3);
+        }
+        clause => unreachable!("unexpected clause {:?}", clause),
+    }
+
+    match &rules_file.guard_rules[2].block.conjunctions[0][0] {
+        RuleClause::TypeBlock(type_block) => {
+            assert_eq!(type_block.type_name, "AWS::Lambda::Function");
+            match &type_block.block.conjunctions[0][0] {
+                GuardClause::Clause(clause) => {
+                    assert_eq!(clause.access_clause.comparator, (CmpOperator::Exists, true));
+                    assert_eq!(
+                        clause.access_clause.query.query,
+                        vec![
+                            QueryPart::Key("Properties".to_string()),
+                            QueryPart::Key("Code".to_string()),
+                            QueryPart::Key("ZipFile".to_string()),
+                        ]
+                    );
+                    assert_eq!(
+                        clause.access_clause.custom_message.as_deref(),
+                        Some(
+                            "property Code.ZipFile is forbidden on AWS::Lambda::Function resources"
+                        )
+                    );
+                }
+                clause => unreachable!("unexpected clause {:?}", clause),
+            }
+        }
+        clause => unreachable!("unexpected clause {:?}", clause),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_policy_builtins_malformed() {
+    assert!(rules_file(from_str2("forbid_resource_type(/AWS::IAM::User/")).is_err());
+    assert!(rules_file(from_str2("forbid_property(AWS::Lambda::Function)")).is_err());
+    assert!(rules_file(from_str2("forbid_property(AWS::Lambda::Function, Code)")).is_err());
+}
+
+#[test]
+fn test_rule_annotations() -> Result<(), Error> {
+    let s = r#"
+    @on-missing(skip)
+    rule optional { Resources.*.Properties.Tags !empty }
+
+    # comments are allowed between annotations and the rule
+    @on-missing( fail )
+    # required
+    rule required { Resources.*.Properties.Tags !empty }
+
+    rule unannotated { Resources.*.Properties.Tags !empty }
+
+    @on-missing(skip)
+    rule parameterized(tags) { %tags !empty }
+    "#;
+    let rules_file = rules_file(from_str2(s))?.unwrap();
+    let annotations = rules_file
+        .guard_rules
+        .iter()
+        .map(|rule| rule.annotations.on_missing)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        annotations,
+        vec![Some(OnMissing::Skip), Some(OnMissing::Fail), None]
+    );
+    assert_eq!(
+        rules_file.parameterized_rules[0]
+            .rule
+            .annotations
+            .on_missing,
+        Some(OnMissing::Skip)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_rule_annotations_malformed() {
+    assert!(rules_file(from_str2("@on-missing(ignore) rule r { Resources exists }")).is_err());
+    assert!(rules_file(from_str2("@on-missing rule r { Resources exists }")).is_err());
+    assert!(rules_file(from_str2("@on-missing(skip)")).is_err());
+}
+
+#[test]
+fn test_rule_tags() -> Result<(), Error> {
+    let s = r#"
+    rule s3_encryption @tags(s3, encryption, cis-2.1.1) {
+        Resources.*.Properties.BucketEncryption exists
+    }
+
+    @tags(experimental)
+    @on-missing(skip)
+    rule versioning @tags( s3 ) when Resources exists {
+        Resources.*.Properties.VersioningConfiguration exists
+    }
+
+    rule untagged { Resources exists }
+    "#;
+    let rules_file = rules_file(from_str2(s))?.unwrap();
+    let tags = rules_file
+        .guard_rules
+        .iter()
+        .map(|rule| rule.annotations.tags.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tags,
+        vec![
+            vec!["s3", "encryption", "cis-2.1.1"],
+            vec!["experimental", "s3"],
+            vec![],
+        ]
+    );
+    assert_eq!(
+        rules_file.guard_rules[1].annotations.on_missing,
+        Some(OnMissing::Skip)
+    );
+    assert!(rules_file.guard_rules[1].conditions.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_rule_tags_malformed() {
+    assert!(rules_file(from_str2("rule r @tags() { Resources exists }")).is_err());
+    assert!(rules_file(from_str2("rule r @tags(s3 { Resources exists }")).is_err());
+    assert!(rules_file(from_str2("@tags rule r { Resources exists }")).is_err());
+}
+
+#[test]
+fn test_rule_severity() -> Result<(), Error> {
+    let s = r#"
+    @severity(warning)
+    rule versioning { Resources.*.Properties.VersioningConfiguration exists }
+
+    rule logging @severity( error ) @tags(s3) { Resources.*.Properties.LoggingConfiguration exists }
+
+    rule encryption { Resources.*.Properties.BucketEncryption exists }
+    "#;
+    let rules = rules_file(from_str2(s))?.unwrap();
+    let severities = rules
+        .guard_rules
+        .iter()
+        .map(|rule| rule.annotations.severity)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        severities,
+        vec![Some(Severity::Warning), Some(Severity::Error), None]
+    );
+    assert_eq!(rules.guard_rules[1].annotations.tags, vec!["s3"]);
+
+    assert!(rules_file(from_str2("@severity(info) rule r { Resources exists }")).is_err());
+    assert!(rules_file(from_str2("rule r @severity() { Resources exists }")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_exists_path_clause() {
+    let examples = [
+        r#"exists_path("Resources/MyBucket/Properties/BucketEncryption")"#,
+        r#"not exists_path('/Metadata/cfn.lint~1ignore/0')"#,
+        r#"exists_path( "a~0b" ) <<tilde keys>>"#,
+    ];
+    let expectations = [
+        (
+            vec!["Resources", "MyBucket", "Properties", "BucketEncryption"],
+            false,
+            None,
+        ),
+        (vec!["Metadata", "cfn.lint/ignore", "0"], true, None),
+        (vec!["a~b"], false, Some(String::from("tilde keys"))),
+    ];
+
+    for (each, (keys, negation, message)) in examples.iter().zip(expectations) {
+        let (rest, clause) = clause(from_str2(each)).unwrap();
+        assert!(rest.is_empty());
+        match clause {
+            GuardClause::Clause(GuardAccessClause {
+                access_clause,
+                negation: clause_negation,
+            }) => {
+                assert_eq!(
+                    keys.iter()
+                        .map(|key| QueryPart::Key(key.to_string()))
+                        .collect::<Vec<_>>(),
+                    access_clause.query.query
+                );
+                assert_eq!((CmpOperator::Exists, false), access_clause.comparator);
+                assert_eq!(None, access_clause.compare_with);
+                assert_eq!(message, access_clause.custom_message);
+                assert_eq!(negation, clause_negation);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_exists_path_clause_failures() {
+    let examples = [
+        r#"exists_path("")"#,
+        r#"exists_path("Resources//Properties")"#,
+        r#"exists_path("Resources/a~2b")"#,
+        r#"exists_path("Resources/%var")"#,
+        r#"exists_path(Resources.MyBucket)"#,
+        r#"exists_path("Resources""#,
+    ];
+
+    for each in examples {
+        assert!(
+            matches!(clause(from_str2(each)), Err(nom::Err::Failure(_))),
+            "{}",
+            each
+        );
+    }
+}
+
+#[test]
+fn test_quoted_keys_in_queries() {
+    let examples = [
+        (
+            r#"Resources.bucket.Metadata."aws:cdk:path""#,
+            vec!["Resources", "bucket", "Metadata", "aws:cdk:path"],
+        ),
+        (
+            r#"Properties.'my key'.value"#,
+            vec!["Properties", "my key", "value"],
+        ),
+        (r#""cdk.json".context"#, vec!["cdk.json", "context"]),
+        (r#"Metadata."0""#, vec!["Metadata", "0"]),
+        (
+            r#"Tags."say \"hi\"".Value"#,
+            vec!["Tags", r#"say "hi""#, "Value"],
+        ),
+    ];
+
+    for (each, keys) in examples {
+        let (rest, query) = access(from_str2(each)).unwrap();
+        assert!(rest.is_empty(), "{}", each);
+        assert_eq!(
+            keys.iter()
+                .map(|key| QueryPart::Key(key.to_string()))
+                .collect::<Vec<_>>(),
+            query.query
+        );
+
+        // rendering the query quotes the keys again, so it parses back to the same query
+        let rendered = crate::rules::display::query_source(&query.query);
+        let (_, reparsed) = access(from_str2(&rendered)).unwrap();
+        assert_eq!(query.query, reparsed.query, "{}", rendered);
+    }
+}
+
+#[test]
+fn test_like_clause() {
+    let examples = [
+        r#"Properties.BucketName like "prod-*""#,
+        r#"Properties.BucketName not like 'dev-??' <<no dev buckets>>"#,
+        r#"Properties.Arn LIKE "arn:aws:s3:::*\*""#,
+    ];
+    let expectations = [
+        ("prod-*", false, None),
+        ("dev-??", true, Some(String::from("no dev buckets"))),
+        ("arn:aws:s3:::*\\*", false, None),
+    ];
+
+    for (each, (glob, not, message)) in examples.iter().zip(expectations) {
+        let (rest, clause) = clause(from_str2(each)).unwrap();
+        assert!(rest.is_empty());
+        match clause {
+            GuardClause::Clause(GuardAccessClause { access_clause, .. }) => {
+                assert_eq!((CmpOperator::Like, not), access_clause.comparator);
+                assert!(matches!(
+                    access_clause.compare_with,
+                    Some(LetValue::Value(PathAwareValue::String((_, ref value)))) if value == glob
+                ));
+                assert_eq!(message, access_clause.custom_message);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_like_clause_failures() {
+    let examples = [
+        r#"Properties.BucketName like prod-*"#,
+        r#"Properties.BucketName like /prod-.*/"#,
+        r#"Properties.BucketName like ["prod-*"]"#,
+        r#"Properties.BucketName like %prefix"#,
+    ];
+
+    for each in examples {
+        assert!(
+            matches!(clause(from_str2(each)), Err(nom::Err::Failure(_))),
+            "{}",
+            each
+        );
+    }
+}
+
+#[test]
+fn test_access_with_template_sections() {
+    for (query, section) in [
+        ("OUTPUTS.Owner", "Outputs"),
+        ("MAPPINGS.RegionMap", "Mappings"),
+        ("METADATA.Owner", "Metadata"),
+    ] {
+        let (rest, query) = access(from_str2(query)).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(QueryPart::Key(String::from(section)), query.query[0]);
+    }
+
+    // only the whole names are shorthands, and quoted they are keys
+    for query in ["OUTPUTS_v2.Owner", "\"OUTPUTS\".Owner"] {
+        let (_, parsed) = access(from_str2(query)).unwrap();
+        assert!(!matches!(&parsed.query[0], QueryPart::Key(key) if key == "Outputs"));
+    }
+}
+
+#[test]
+fn test_guarded_clause() {
+    let (rest, guarded) = clause(from_str2(
+        r#"Properties.KmsKeyId EXISTS when %environment == "prod""#,
+    ))
+    .unwrap();
+    assert!(rest.is_empty());
+    match guarded {
+        GuardClause::WhenBlock(conditions, block, None) => {
+            assert!(matches!(
+                conditions.as_slice(),
+                [disjunctions] if matches!(disjunctions.as_slice(), [WhenGuardClause::Clause(_)])
+            ));
+            assert!(block.assignments.is_empty());
+            assert!(matches!(
+                block.conjunctions.as_slice(),
+                [disjunctions] if matches!(disjunctions.as_slice(), [GuardClause::Clause(_)])
+            ));
+        }
+        _ => unreachable!(),
+    }
+
+    // a when on the following line, or conditions followed by a block, is a when block
+    for input in [
+        "Properties.KmsKeyId EXISTS\n    when %environment == \"prod\" { Properties.Tags EXISTS }",
+        "Properties.KmsKeyId EXISTS when %environment == \"prod\" { Properties.Tags EXISTS }",
+    ] {
+        let (rest, unguarded) = clause(from_str2(input)).unwrap();
+        assert!(matches!(unguarded, GuardClause::Clause(_)));
+        assert!(rest.fragment().starts_with("when"));
+    }
+}
+
+#[test]
+fn test_when_else_block() {
+    let (rest, parsed) = clause(from_str2(
+        "when %environment == \"prod\" { Properties.KmsKeyId EXISTS } else { Properties.BucketEncryption EXISTS }",
+    ))
+    .unwrap();
+    assert!(rest.is_empty());
+    match parsed {
+        GuardClause::WhenBlock(_, block, Some(otherwise)) => {
+            assert_eq!(1, block.conjunctions.len());
+            assert_eq!(1, otherwise.conjunctions.len());
+        }
+        _ => unreachable!(),
+    }
+
+    // else can be on its own line, in rules and at the top level of the file
+    let rules = rules_file(from_str2(
+        r#"
+        when %environment == "prod" {
+            Properties.KmsKeyId EXISTS
+        }
+        else {
+            Properties.BucketEncryption EXISTS
+        }
+
+        rule encryption {
+            when %environment == "prod" { Properties.KmsKeyId EXISTS } ELSE { Properties.BucketEncryption EXISTS }
+        }
+        "#,
+    ))
+    .unwrap()
+    .unwrap();
+    assert_eq!(2, rules.guard_rules.len());
+    assert!(rules
+        .guard_rules
+        .iter()
+        .flat_map(|rule| rule.block.conjunctions.iter().flatten())
+        .all(|clause| matches!(clause, RuleClause::WhenBlock(_, _, Some(_)))));
+
+    // a key named else is still queried when it does not follow a when block
+    let (rest, parsed) = clause(from_str2("else { Foo EXISTS }")).unwrap();
+    assert!(rest.is_empty());
+    assert!(matches!(parsed, GuardClause::BlockClause(_)));
+    let (_, parsed) = clause(from_str2("when %x == 1 { Foo EXISTS } elsewhere EXISTS")).unwrap();
+    assert!(matches!(parsed, GuardClause::WhenBlock(_, _, None)));
+}
+
+#[test]
+fn test_private_rule() {
+    let rules = rules_file(from_str2(
+        r#"
+        @severity(warning)
+        private rule buckets_encrypted {
+            Resources.*.Properties.BucketEncryption EXISTS
+        }
+
+        rule encryption {
+            buckets_encrypted
+        }
+        "#,
+    ))
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        vec![true, false],
+        rules
+            .guard_rules
+            .iter()
+            .map(|rule| rule.annotations.private)
+            .collect::<Vec<_>>()
+    );
+    assert!(
+        crate::rules::display::rule_source(&rules.guard_rules[0], None)
+            .starts_with("@severity(warning)\nprivate rule buckets_encrypted")
+    );
+
+    // a rule can still be named private
+    let rules = rules_file(from_str2("rule private { Foo EXISTS }"))
+        .unwrap()
+        .unwrap();
+    assert_eq!("private", rules.guard_rules[0].rule_name);
+    assert!(!rules.guard_rules[0].annotations.private);
+}
+
+#[test]
+fn test_access_with_resolve() {
+    let (rest, query) = access(from_str2(
+        "Properties.SecurityGroupIds[*].resolve().Properties.GroupName",
+    ))
+    .unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        vec![
+            QueryPart::Key(String::from("Properties")),
+            QueryPart::Key(String::from("SecurityGroupIds")),
+            QueryPart::AllIndices(None),
+            QueryPart::Resolve,
+            QueryPart::Key(String::from("Properties")),
+            QueryPart::Key(String::from("GroupName")),
+        ],
+        query.query
+    );
+
+    // without the call it is a plain property
+    let (rest, query) = access(from_str2("Properties.resolve.Enabled")).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(QueryPart::Key(String::from("resolve")), query.query[1]);
+
+    let (rest, query) = access(from_str2("Properties.Role.resolve( ).Type")).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(QueryPart::Resolve, query.query[2]);
+}
+
+#[test]
+fn test_access_with_descendants() {
+    let (rest, query) = access(from_str2("Resources.*.Properties.**.KmsKeyId")).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        vec![
+            QueryPart::Key(String::from("Resources")),
+            QueryPart::AllValues(None),
+            QueryPart::Key(String::from("Properties")),
+            QueryPart::Descendants(String::from("KmsKeyId")),
+        ],
+        query.query
+    );
+    assert_eq!(
+        "Resources.*.Properties.**.KmsKeyId",
+        crate::rules::display::query_source(&query.query)
+    );
+
+    let (rest, query) = access(from_str2("Properties.**.'aws:kms'.Enabled")).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        QueryPart::Descendants(String::from("aws:kms")),
+        query.query[1]
+    );
+
+    // ** is always followed by the key searched for
+    assert!(matches!(
+        access(from_str2("Properties.**")),
+        Err(nom::Err::Failure(_))
+    ));
+}
+
+#[test]
+fn test_access_with_slices() {
+    for (query, expected) in [
+        ("Statement[-1]", QueryPart::Index(-1)),
+        ("Statement[0:2]", QueryPart::Slice(Some(0), Some(2))),
+        ("Statement[1:]", QueryPart::Slice(Some(1), None)),
+        ("Statement[:-1]", QueryPart::Slice(None, Some(-1))),
+        ("Statement[:]", QueryPart::Slice(None, None)),
+    ] {
+        let (rest, parsed) = access(from_str2(query)).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(expected, parsed.query[1]);
+        assert_eq!(query, crate::rules::display::query_source(&parsed.query));
+    }
+
+    // whitespace is allowed around the bounds and the colon
+    for (query, expected) in [
+        ("Statement[ 0 : 2 ]", QueryPart::Slice(Some(0), Some(2))),
+        ("Statement[1 :]", QueryPart::Slice(Some(1), None)),
+        ("Statement[: -1]", QueryPart::Slice(None, Some(-1))),
+    ] {
+        let (rest, parsed) = access(from_str2(query)).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(expected, parsed.query[1]);
+    }
+
+    assert!(matches!(
+        access(from_str2("Statement[0:2")),
+        Err(nom::Err::Failure(_))
+    ));
+}
+
+#[test]
+fn test_parse_error_location() {
+    let content = "rule encrypted {\n    Properties.Encrypted == {\"Fn::Sub\":/{\"a\"}}\n}\n";
+    let err = rules_file(Span::new_extra(content, "encrypted.guard")).unwrap_err();
+
+    // the message is the same as before the location was added
+    assert!(err
+        .to_string()
+        .starts_with("Parser Error when parsing `Parsing Error Error parsing file encrypted.guard at line 2 at column 28"));
+
+    let location = err.parse_error_location().unwrap();
+    assert_eq!("encrypted.guard", location.file);
+    assert_eq!((2, 28), (location.line, location.column));
+    assert_eq!(
+        vec![String::from(
+            r#"either a property access "engine.core" or value like "string" or ["this", "that"]"#
+        )],
+        location.expected
+    );
+    assert_eq!(r#" {"Fn::Sub":/{"a"}}"#, location.fragment);
+}
+
+#[test]
+fn test_rules_file_recovering() {
+    let content = r#"let encrypted = true
+
+rule first_broken {
+    Properties.Encrypted == {"Fn::Sub":/{"a"}}
+}
+
+# a comment between the rules
+rule valid {
+    Properties.Encrypted == %encrypted
+}
+
+rule second_broken {
+    Properties.Size == 10
+    Properties.KmsKeyId == {"Fn::Sub":/{"a"}}
+}
+"#;
+    let (rules, errors) = rules_file_recovering(Span::new_extra(content, "broken.guard"));
+
+    let rules = rules.unwrap();
+    assert_eq!(1, rules.assignments.len());
+    assert_eq!(
+        vec!["valid"],
+        rules
+            .guard_rules
+            .iter()
+            .map(|rule| rule.rule_name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![(4, 28), (14, 27)],
+        errors
+            .iter()
+            .map(|e| {
+                let location = e.parse_error_location().unwrap();
+                (location.line, location.column)
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // the errors following the first one rules_file stops at
+    let first = rules_file(Span::new_extra(content, "broken.guard")).unwrap_err();
+    let after = syntax_errors_after(
+        Span::new_extra(content, "broken.guard"),
+        first.parse_error_location().unwrap().line,
+    );
+    assert_eq!(1, after.len());
+    assert_eq!(14, after[0].parse_error_location().unwrap().line);
+}
+
+#[test]
+fn test_rules_file_recovering_without_errors() {
+    let content = "rule valid { Resources exists }\n";
+    let (rules, errors) = rules_file_recovering(Span::new_extra(content, ""));
+    assert!(errors.is_empty());
+    assert_eq!(1, rules.unwrap().guard_rules.len());
+
+    let (rules, errors) = rules_file_recovering(Span::new_extra("}}\n", ""));
+    assert!(rules.is_none());
+    assert_eq!(1, errors.len());
+}
+
+#[test]
+fn test_negated_block_clause() {
+    let (_rest, clause) = block_clause(from_str2(
+        "not Resources[ Type == 'AWS::S3::Bucket' ] { Properties exists }",
+    ))
+    .unwrap();
+    match clause {
+        GuardClause::BlockClause(block_clause) => {
+            assert!(block_clause.negation);
+            assert!(!block_clause.not_empty);
+        }
+        _ => unreachable!(),
+    }
+
+    let (_rest, clause) = block_clause(from_str2("Resources.* { Properties exists }")).unwrap();
+    assert!(matches!(
+        clause,
+        GuardClause::BlockClause(BlockGuardClause {
+            negation: false,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_unordered_eq_clause() {
+    let examples = [
+        r#"Properties.Tags =~ [{ Key: 'owner', Value: 'infra' }]"#,
+        r#"Properties.Tags !~ %expected_tags"#,
+    ];
+    let expectations = [
+        (CmpOperator::UnorderedEq, false),
+        (CmpOperator::UnorderedEq, true),
+    ];
+
+    for (each, comparator) in examples.iter().zip(expectations) {
+        let (rest, clause) = clause(from_str2(each)).unwrap();
+        assert!(rest.is_empty());
+        match clause {
+            GuardClause::Clause(GuardAccessClause { access_clause, .. }) => {
+                assert_eq!(comparator, access_clause.comparator);
+                assert!(access_clause.compare_with.is_some());
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_deeply_nested_input_is_a_parse_error() {
+    for open in ["[", "{", "("] {
+        let rules = format!(
+            "rule deep {{ Properties.Tags == {} }}",
+            open.repeat(100_000)
+        );
+        let error = rules_file(from_str2(&rules)).unwrap_err();
+        assert!(
+            error.to_string().contains("levels of nested brackets"),
+            "{error}"
+        );
+    }
+
+    let (rules, errors) = rules_file_recovering(from_str2(&"[".repeat(100_000)));
+    assert!(rules.is_none());
+    assert_eq!(1, errors.len());
+}
+
+#[test]
+fn test_nesting_in_strings_regex_messages_and_comments_is_not_counted() {
+    let deep = "[".repeat(MAX_NESTING_DEPTH + 1);
+    let escaped = "\\[".repeat(MAX_NESTING_DEPTH + 1);
+    let rules = format!(
+        r#"
+        # {deep}
+        rule nested {{
+            Properties.Name == "{deep}"
+            Properties.Pattern == /{escaped}\//
+            Properties.Tags[0].Key exists << {deep} >>
+        }}
+        "#
+    );
+
+    let rules = rules_file(from_str2(&rules)).unwrap().unwrap();
+    assert_eq!(1, rules.guard_rules.len());
+
+    assert!(check_nesting(from_str2(&"[".repeat(MAX_NESTING_DEPTH))).is_ok());
+    assert!(check_nesting(from_str2(&"[".repeat(MAX_NESTING_DEPTH + 1))).is_err());
+    assert!(check_nesting(from_str2(&"[]".repeat(MAX_NESTING_DEPTH + 1))).is_ok());
+}
+
+#[test]
+fn test_malformed_input_is_an_error() {
+    for each in [
+        "",
+        "rule r { Properties.Tags[99999999999] exists }",
+        "rule r { Properties.Tags.99999999999 exists }",
+        "forbid_resource_type(",
+        "rule r { exists_path(\"/a~2\") }",
+        "let x = from('missing.yaml', 'Resources')",
+        "rule r { Properties.Name == 'unterminated\\",
+        "rule r { Properties.Name == /unterminated\\",
+        "rule r when { }",
+        "let",
+        "rule r { %x[ keys == ] }",
+    ] {
+        assert!(RulesFile::try_from(each).is_err(), "{each}");
+    }
+}
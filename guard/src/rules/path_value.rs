@@ -18,8 +18,8 @@ use super::{Evaluate, EvaluationContext, Status};
 // Local mod
 //
 use super::values::*;
-use crate::rules::exprs::LetValue;
-use fancy_regex::Regex;
+use crate::rules::exprs::{list_position, LetValue};
+use crate::rules::regex_cache;
 use serde::ser::{SerializeMap, SerializeStruct};
 use std::hash::{Hash, Hasher};
 
@@ -27,7 +27,7 @@ use std::hash::{Hash, Hasher};
 // crate level
 //
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
-pub(crate) struct Location {
+pub struct Location {
     pub(crate) line: usize,
     pub(crate) col: usize,
 }
@@ -45,8 +45,9 @@ impl std::fmt::Display for Location {
     }
 }
 
+/// where a value is in the data file it was read from, e.g. `/Resources/MyBucket/Type`
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub(crate) struct Path(pub(crate) String, pub(crate) Location);
+pub struct Path(pub(crate) String, pub(crate) Location);
 
 impl Path {
     #[cfg(test)]
@@ -136,7 +137,7 @@ impl Path {
 }
 
 #[derive(Debug, Clone, Deserialize)]
-pub(crate) struct MapValue {
+pub struct MapValue {
     pub(crate) keys: Vec<PathAwareValue>,
     pub(crate) values: indexmap::IndexMap<String, PathAwareValue>,
 }
@@ -146,6 +147,14 @@ impl Serialize for MapValue {
     where
         S: Serializer,
     {
+        // binary formats, e.g. the one of rules packs, are read back, so they keep the keys
+        if !serializer.is_human_readable() {
+            let mut map_value = serializer.serialize_struct("MapValue", 2)?;
+            map_value.serialize_field("keys", &self.keys)?;
+            map_value.serialize_field("values", &self.values)?;
+            return map_value.end();
+        }
+
         let mut map = serializer.serialize_map(Some(self.values.len()))?;
         for (key, value) in self.values.iter() {
             map.serialize_entry(key, value)?;
@@ -168,8 +177,10 @@ impl MapValue {
     }
 }
 
+/// a value of a data file along with its path, or a literal of the rules, e.g. `/^prod/` or
+/// `r[1,10]`
 #[derive(Debug, Clone, Deserialize)]
-pub(crate) enum PathAwareValue {
+pub enum PathAwareValue {
     Null(Path),
     String((Path, String)),
     Regex((Path, String)),
@@ -251,19 +262,9 @@ impl PartialEq for PathAwareValue {
 
             (PathAwareValue::Bool((_, b1)), PathAwareValue::Bool((_, b2))) => b1 == b2,
 
-            (PathAwareValue::String((_, s)), PathAwareValue::Regex((_, r))) => {
-                if let Ok(regex) = Regex::new(r.as_str()) {
-                    regex.is_match(s.as_str()).unwrap() // given that we have already validated the regular expression
-                } else {
-                    false
-                }
-            }
-            (PathAwareValue::Regex((_, r)), PathAwareValue::String((_, s))) => {
-                if let Ok(regex) = Regex::new(r.as_str()) {
-                    regex.is_match(s.as_str()).unwrap() // given that we have already validated the regular expression
-                } else {
-                    false
-                }
+            (PathAwareValue::String((_, s)), PathAwareValue::Regex((_, r)))
+            | (PathAwareValue::Regex((_, r)), PathAwareValue::String((_, s))) => {
+                regex_cache::is_match(r, s)
             }
             (PathAwareValue::Regex((_, r)), PathAwareValue::Regex((_, s))) => r == s,
 
@@ -636,9 +637,8 @@ impl QueryResolver for PathAwareValue {
                                     match query[1] {
                                         QueryPart::AllIndices(_) | QueryPart::Key(_) => keys,
                                         QueryPart::Index(index) => {
-                                            let check = if index >= 0 { index } else { -index } as usize;
-                                            if check < keys.len() {
-                                                vec![keys[check]]
+                                            if let Some(position) = list_position(index, keys.len()) {
+                                                vec![keys[position]]
                                             } else {
                                                 self.map_some_or_error_all(all, query)?
                                             }
@@ -857,6 +857,64 @@ impl QueryResolver for PathAwareValue {
                     _ => self.map_some_or_error_all(all, query),
                 }
             }
+
+            QueryPart::Resolve => Err(Error::IncompatibleError(format!(
+                "resolve() is not supported by this evaluator, query {}",
+                SliceDisplay(query)
+            ))),
+
+            QueryPart::Slice(..) => Err(Error::IncompatibleError(format!(
+                "slices are not supported by this evaluator, query {}",
+                SliceDisplay(query)
+            ))),
+
+            QueryPart::Descendants(_) => Err(Error::IncompatibleError(format!(
+                "** is not supported by this evaluator, query {}",
+                SliceDisplay(query)
+            ))),
+        }
+    }
+}
+
+/// [`PathAwareValue`] as it is deserialized, for the binary formats that are read back
+#[derive(Serialize)]
+#[serde(rename = "PathAwareValue")]
+enum BinaryPathAwareValue<'v> {
+    Null(&'v Path),
+    String((&'v Path, &'v String)),
+    Regex((&'v Path, &'v String)),
+    Bool((&'v Path, &'v bool)),
+    Int((&'v Path, &'v i64)),
+    Float((&'v Path, &'v f64)),
+    Char((&'v Path, &'v char)),
+    List((&'v Path, &'v Vec<PathAwareValue>)),
+    Map((&'v Path, &'v MapValue)),
+    RangeInt((&'v Path, &'v RangeType<i64>)),
+    RangeFloat((&'v Path, &'v RangeType<f64>)),
+    RangeChar((&'v Path, &'v RangeType<char>)),
+}
+
+impl<'v> From<&'v PathAwareValue> for BinaryPathAwareValue<'v> {
+    fn from(value: &'v PathAwareValue) -> Self {
+        match value {
+            PathAwareValue::Null(path) => BinaryPathAwareValue::Null(path),
+            PathAwareValue::String((path, value)) => BinaryPathAwareValue::String((path, value)),
+            PathAwareValue::Regex((path, value)) => BinaryPathAwareValue::Regex((path, value)),
+            PathAwareValue::Bool((path, value)) => BinaryPathAwareValue::Bool((path, value)),
+            PathAwareValue::Int((path, value)) => BinaryPathAwareValue::Int((path, value)),
+            PathAwareValue::Float((path, value)) => BinaryPathAwareValue::Float((path, value)),
+            PathAwareValue::Char((path, value)) => BinaryPathAwareValue::Char((path, value)),
+            PathAwareValue::List((path, value)) => BinaryPathAwareValue::List((path, value)),
+            PathAwareValue::Map((path, value)) => BinaryPathAwareValue::Map((path, value)),
+            PathAwareValue::RangeInt((path, value)) => {
+                BinaryPathAwareValue::RangeInt((path, value))
+            }
+            PathAwareValue::RangeFloat((path, value)) => {
+                BinaryPathAwareValue::RangeFloat((path, value))
+            }
+            PathAwareValue::RangeChar((path, value)) => {
+                BinaryPathAwareValue::RangeChar((path, value))
+            }
         }
     }
 }
@@ -866,6 +924,12 @@ impl Serialize for PathAwareValue {
     where
         S: Serializer,
     {
+        // the path and value pair below can not be read back, binary formats, e.g. the one of
+        // rules packs, are
+        if !serializer.is_human_readable() {
+            return BinaryPathAwareValue::from(self).serialize(serializer);
+        }
+
         let result: crate::rules::Result<(String, serde_json::Value)> = self.try_into();
         match result {
             Ok((path, value)) => {
@@ -1005,9 +1069,8 @@ impl PathAwareValue {
         list: &'v Vec<PathAwareValue>,
         query: &[QueryPart<'_>],
     ) -> Result<&'v PathAwareValue, Error> {
-        let check = if index >= 0 { index } else { -index } as usize;
-        if check < list.len() {
-            Ok(&list[check])
+        if let Some(position) = list_position(index, list.len()) {
+            Ok(&list[position])
         } else {
             Err(Error::
                 RetrievalError(
@@ -1044,7 +1107,10 @@ impl PathAwareValue {
     }
 }
 
-fn compare_values(first: &PathAwareValue, other: &PathAwareValue) -> Result<Ordering, Error> {
+pub(crate) fn compare_values(
+    first: &PathAwareValue,
+    other: &PathAwareValue,
+) -> Result<Ordering, Error> {
     match (first, other) {
         //
         // scalar values
@@ -1059,6 +1125,23 @@ fn compare_values(first: &PathAwareValue, other: &PathAwareValue) -> Result<Orde
             )),
         },
         (PathAwareValue::Char((_, f)), PathAwareValue::Char((_, s))) => Ok(f.cmp(s)),
+        //
+        // sizes and durations with a unit, e.g. "512MiB", compare with numbers in bytes or
+        // seconds, like the literals 512MiB or 30d do
+        //
+        (PathAwareValue::String(_), PathAwareValue::Int(_) | PathAwareValue::Float(_))
+        | (PathAwareValue::Int(_) | PathAwareValue::Float(_), PathAwareValue::String(_)) => {
+            match (as_quantity(first), as_quantity(other)) {
+                (Some(f), Some(s)) => f.partial_cmp(&s).ok_or_else(|| {
+                    Error::NotComparable("Float values are not comparable".to_owned())
+                }),
+                _ => Err(Error::NotComparable(format!(
+                    "PathAwareValues are not comparable {}, {}",
+                    first.type_info(),
+                    other.type_info()
+                ))),
+            }
+        }
         (_, _) => Err(Error::NotComparable(format!(
             "PathAwareValues are not comparable {}, {}",
             first.type_info(),
@@ -1067,14 +1150,21 @@ fn compare_values(first: &PathAwareValue, other: &PathAwareValue) -> Result<Orde
     }
 }
 
+fn as_quantity(value: &PathAwareValue) -> Option<f64> {
+    match value {
+        PathAwareValue::String((_, s)) => parse_quantity(s),
+        PathAwareValue::Int((_, i)) => Some(*i as f64),
+        PathAwareValue::Float((_, f)) => Some(*f),
+        _ => None,
+    }
+}
+
 #[allow(clippy::never_loop)]
 pub(crate) fn compare_eq(first: &PathAwareValue, second: &PathAwareValue) -> Result<bool, Error> {
     let (reg, s) = match (first, second) {
-        (PathAwareValue::String((_, s)), PathAwareValue::Regex((_, r))) => {
-            (Regex::try_from(r.as_str()).map_err(Box::new)?, s.as_str())
-        }
-        (PathAwareValue::Regex((_, r)), PathAwareValue::String((_, s))) => {
-            (Regex::try_from(r.as_str()).map_err(Box::new)?, s.as_str())
+        (PathAwareValue::String((_, s)), PathAwareValue::Regex((_, r)))
+        | (PathAwareValue::Regex((_, r)), PathAwareValue::String((_, s))) => {
+            (regex_cache::compiled(r)?, s.as_str())
         }
 
         (PathAwareValue::String((_, s1)), PathAwareValue::String((_, s2))) => return Ok(s1 == s2),
@@ -1151,6 +1241,101 @@ pub(crate) fn compare_eq(first: &PathAwareValue, second: &PathAwareValue) -> Res
     }
 }
 
+/// compares values like `compare_eq`, except that lists are equal when they hold the same
+/// elements in any order, at every level. Used by `=~` for sets of ingress rules or tags where
+/// the order they are written in does not matter
+pub(crate) fn compare_unordered_eq(
+    first: &PathAwareValue,
+    second: &PathAwareValue,
+) -> Result<bool, Error> {
+    match (first, second) {
+        (PathAwareValue::List((_, list)), PathAwareValue::List((_, list2))) => {
+            if list.len() != list2.len() {
+                return Ok(false);
+            }
+
+            // each element is matched with an element of the other list not matched yet, so
+            // duplicates have to appear as many times in both lists
+            let mut matched = vec![false; list2.len()];
+            for left in list {
+                let found = (0..list2.len()).find(|&index| {
+                    !matched[index] && compare_unordered_eq(left, &list2[index]).unwrap_or(false)
+                });
+                match found {
+                    Some(index) => matched[index] = true,
+                    None => return Ok(false),
+                }
+            }
+            Ok(true)
+        }
+
+        (PathAwareValue::Map((_, map)), PathAwareValue::Map((_, map2))) => {
+            if map.values.len() != map2.values.len() {
+                return Ok(false);
+            }
+
+            for (key, value) in map.values.iter() {
+                match map2.values.get(key) {
+                    Some(value2) if compare_unordered_eq(value, value2).unwrap_or(false) => {}
+                    _ => return Ok(false),
+                }
+            }
+            Ok(true)
+        }
+
+        _ => compare_eq(first, second),
+    }
+}
+
+/// translates a glob pattern into a regular expression matching the whole of a string. `*`
+/// matches any sequence of characters, `?` any single one and `\` takes the character after it
+/// literally, everything else matches itself
+pub(crate) fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^(?s:");
+    let mut chars = glob.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '\\' => push_literal(&mut regex, chars.next().unwrap_or('\\')),
+            c => push_literal(&mut regex, c),
+        }
+    }
+    regex.push_str(")$");
+    regex
+}
+
+fn push_literal(regex: &mut String, c: char) {
+    if "\\.+*?()|[]{}^$".contains(c) {
+        regex.push('\\');
+    }
+    regex.push(c);
+}
+
+/// matches a string against a glob pattern, see `glob_to_regex`. Only strings can be matched
+pub(crate) fn compare_like(first: &PathAwareValue, second: &PathAwareValue) -> Result<bool, Error> {
+    match (first, second) {
+        (PathAwareValue::String((_, s)), PathAwareValue::String((_, glob))) => {
+            regex_cache::compiled(&glob_to_regex(glob))
+                .and_then(|regex| regex.is_match(s).map_err(|e| Error::from(Box::new(e))))
+                .map_err(|e| {
+                    Error::NotComparable(format!("Unable to match {s} against glob {glob}, {e}"))
+                })
+        }
+
+        (PathAwareValue::String(_), _) => Err(Error::NotComparable(format!(
+            "like expects a glob pattern string, e.g. like \"arn:aws:s3:::prod-*\", found {}",
+            second.type_info()
+        ))),
+
+        _ => Err(Error::NotComparable(format!(
+            "like only matches strings, found {} at {}",
+            first.type_info(),
+            first.self_path()
+        ))),
+    }
+}
+
 pub(crate) fn compare_lt(first: &PathAwareValue, other: &PathAwareValue) -> Result<bool, Error> {
     match compare_values(first, other) {
         Ok(o) => match o {
@@ -407,3 +407,110 @@ fn merge_values_test() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_compare_like() -> Result<(), Error> {
+    let string = |s: &str| PathAwareValue::String((Path::root(), s.to_string()));
+
+    assert_eq!(
+        "^(?s:arn:aws:s3:::prod-.*)$",
+        glob_to_regex("arn:aws:s3:::prod-*")
+    );
+    assert_eq!("^(?s:a\\.b.\\*)$", glob_to_regex("a.b?\\*"));
+
+    let matches = |value: &str, glob: &str| compare_like(&string(value), &string(glob));
+    assert!(matches("arn:aws:s3:::prod-logs", "arn:aws:s3:::prod-*")?);
+    assert!(matches("arn:aws:s3:::prod-", "arn:aws:s3:::prod-*")?);
+    // the whole value has to match
+    assert!(!matches("arn:aws:s3:::prod-logs/key", "prod-*")?);
+    assert!(matches("t3.micro", "t?.*")?);
+    assert!(!matches("t3micro", "t3.*")?);
+    assert!(matches("a*b", "a\\*b")?);
+    assert!(!matches("axb", "a\\*b")?);
+    assert!(matches("(a|b)+", "(a|b)+")?);
+
+    assert!(matches!(
+        compare_like(&PathAwareValue::Int((Path::root(), 10)), &string("1*")),
+        Err(Error::NotComparable(_))
+    ));
+    assert!(matches!(
+        compare_like(&string("10"), &PathAwareValue::Int((Path::root(), 10))),
+        Err(Error::NotComparable(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_compare_quantities() -> Result<(), Error> {
+    let string = |s: &str| PathAwareValue::String((Path::root(), s.to_string()));
+    let int = |i: i64| PathAwareValue::Int((Path::root(), i));
+
+    assert_eq!(Some(536_870_912.0), parse_quantity("512MiB"));
+    assert_eq!(Some(0.25), parse_quantity("250ms"));
+    assert_eq!(None, parse_quantity("512"));
+    assert_eq!(None, parse_quantity("512 MiB"));
+    assert_eq!(None, parse_quantity("512mib"));
+    assert_eq!(None, parse_quantity(".5h"));
+
+    assert!(compare_eq(&string("1KiB"), &int(1024))?);
+    assert!(compare_lt(&string("512MiB"), &int(1_000_000_000))?);
+    assert!(compare_ge(&int(2_592_000), &string("30d"))?);
+    assert!(compare_gt(
+        &string("1.5s"),
+        &PathAwareValue::Float((Path::root(), 1.25))
+    )?);
+
+    // strings without a unit are not numbers
+    assert!(matches!(
+        compare_lt(&string("512"), &int(1024)),
+        Err(Error::NotComparable(_))
+    ));
+    assert!(matches!(
+        compare_eq(&string("lots"), &int(1024)),
+        Err(Error::NotComparable(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_compare_unordered_eq() -> Result<(), Error> {
+    let value = |yaml: &str| -> Result<PathAwareValue, Error> {
+        PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(yaml)?)
+    };
+    let unordered_eq = |first: &str, second: &str| -> Result<(bool, bool), Error> {
+        let (first, second) = (value(first)?, value(second)?);
+        Ok((
+            compare_unordered_eq(&first, &second)?,
+            compare_eq(&first, &second)?,
+        ))
+    };
+
+    assert_eq!((true, true), unordered_eq("[1, 2, 3]", "[1, 2, 3]")?);
+    assert_eq!((true, false), unordered_eq("[1, 2, 3]", "[3, 1, 2]")?);
+    assert_eq!((false, false), unordered_eq("[1, 2]", "[1, 2, 3]")?);
+    // duplicates have to appear as many times in both lists
+    assert_eq!((false, false), unordered_eq("[1, 1, 2]", "[1, 2, 2]")?);
+    assert_eq!((true, false), unordered_eq("[1, 1, 2]", "[1, 2, 1]")?);
+    assert_eq!((true, false), unordered_eq("[a, 1, true]", "[true, a, 1]")?);
+    // lists within maps within lists
+    assert_eq!(
+        (true, false),
+        unordered_eq(
+            "[{FromPort: 443, CidrIps: [10.0.0.0/8, 172.16.0.0/12]}, {FromPort: 80, CidrIps: []}]",
+            "[{CidrIps: [], FromPort: 80}, {FromPort: 443, CidrIps: [172.16.0.0/12, 10.0.0.0/8]}]"
+        )?
+    );
+    assert_eq!(
+        (false, false),
+        unordered_eq("{FromPort: 443, ToPort: 443}", "{FromPort: 443}")?
+    );
+    assert_eq!((true, true), unordered_eq("prod", "prod")?);
+    assert!(matches!(
+        compare_unordered_eq(&value("[1]")?, &value("1")?),
+        Err(Error::NotComparable(_))
+    ));
+
+    Ok(())
+}
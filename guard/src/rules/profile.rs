@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::rules::display::{clause_source, query_source};
+use crate::rules::exprs::{BlockGuardClause, GuardAccessClause};
+
+/// a part of the rules the evaluator spends time in while profiled
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Frame<'value, 'loc> {
+    Rule(&'value str),
+    /// the query of a `let`, resolved the first time the variable is used
+    Variable(&'value str),
+    TypeBlock(&'value str),
+    Block(&'value BlockGuardClause<'loc>),
+    Clause(&'value GuardAccessClause<'loc>),
+}
+
+impl<'value, 'loc> Frame<'value, 'loc> {
+    /// the frame as it is written in the rules file, with the line of blocks and clauses to tell
+    /// the ones written the same way apart
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Frame::Rule(name) => format!("rule {name}"),
+            Frame::Variable(name) => format!("let {name}"),
+            Frame::TypeBlock(type_name) => format!("{type_name} {{}}"),
+            Frame::Block(block) => format!(
+                "{} {{}} at line {}",
+                query_source(&block.query.query),
+                block.location.line
+            ),
+            Frame::Clause(clause) => format!(
+                "{} at line {}",
+                clause_source(clause),
+                clause.access_clause.location.line
+            ),
+        }
+    }
+}
+
+/// what was measured for a frame, across every time it was evaluated
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FrameStats {
+    /// how many times the frame was evaluated
+    pub(crate) calls: usize,
+    /// time spent in the frame, including the frames evaluated within it
+    pub(crate) total: Duration,
+    /// time spent in the frame itself, leaving out the frames evaluated within it
+    pub(crate) own: Duration,
+    /// how many values the queries of the frame itself stepped through
+    pub(crate) resolutions: usize,
+}
+
+impl FrameStats {
+    fn add(&mut self, other: FrameStats) {
+        self.calls += other.calls;
+        self.total += other.total;
+        self.own += other.own;
+        self.resolutions += other.resolutions;
+    }
+}
+
+#[derive(Debug)]
+struct OpenFrame {
+    label: String,
+    start: Instant,
+    /// time spent in the frames evaluated within it so far
+    children: Duration,
+    resolutions: usize,
+}
+
+/// records the time spent in each rule, block, clause and variable, and the values their queries
+/// resolved, keyed by the stack of frames they were evaluated in
+#[derive(Debug, Default)]
+pub(crate) struct Profiler {
+    open: Vec<OpenFrame>,
+    stacks: BTreeMap<Vec<String>, FrameStats>,
+}
+
+impl Profiler {
+    pub(crate) fn enter(&mut self, frame: Frame<'_, '_>) {
+        self.open.push(OpenFrame {
+            label: frame.label(),
+            start: Instant::now(),
+            children: Duration::ZERO,
+            resolutions: 0,
+        });
+    }
+
+    pub(crate) fn exit(&mut self) {
+        let frame = match self.open.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        let total = frame.start.elapsed();
+        if let Some(parent) = self.open.last_mut() {
+            parent.children += total;
+        }
+
+        let stack = self
+            .open
+            .iter()
+            .map(|open| open.label.clone())
+            .chain(std::iter::once(frame.label))
+            .collect();
+        self.stacks.entry(stack).or_default().add(FrameStats {
+            calls: 1,
+            total,
+            own: total.saturating_sub(frame.children),
+            resolutions: frame.resolutions,
+        });
+    }
+
+    /// counts a value stepped through by a query of the frame being evaluated
+    pub(crate) fn count_resolution(&mut self) {
+        if let Some(frame) = self.open.last_mut() {
+            frame.resolutions += 1;
+        }
+    }
+
+    /// adds what the other profiler recorded while evaluating the rules file, under a frame for
+    /// the rules file
+    pub(crate) fn merge(&mut self, rules_file: &str, other: Profiler) {
+        for (stack, stats) in other.stacks {
+            let stack = std::iter::once(rules_file.to_string())
+                .chain(stack)
+                .collect();
+            self.stacks.entry(stack).or_default().add(stats);
+        }
+    }
+
+    /// the stats of each stack of frames, outermost frame first
+    pub(crate) fn stacks(&self) -> &BTreeMap<Vec<String>, FrameStats> {
+        &self.stacks
+    }
+
+    /// the stats of each frame, keyed by the outermost frame of its stacks and the frame, added
+    /// up across the stacks it was evaluated in
+    pub(crate) fn frames(&self) -> BTreeMap<(&str, &str), FrameStats> {
+        let mut frames = BTreeMap::<(&str, &str), FrameStats>::new();
+        for (stack, stats) in &self.stacks {
+            if let (Some(outermost), Some(frame)) = (stack.first(), stack.last()) {
+                frames
+                    .entry((outermost.as_str(), frame.as_str()))
+                    .or_default()
+                    .add(*stats);
+            }
+        }
+        frames
+    }
+}
+
+#[cfg(test)]
+#[path = "profile_tests.rs"]
+mod profile_tests;
@@ -0,0 +1,80 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+fn stack(frames: &[&str]) -> Vec<String> {
+    frames.iter().map(|frame| frame.to_string()).collect()
+}
+
+#[test]
+fn test_labels() {
+    assert_eq!("rule s3_encrypted", Frame::Rule("s3_encrypted").label());
+    assert_eq!("let buckets", Frame::Variable("buckets").label());
+    assert_eq!(
+        "AWS::S3::Bucket {}",
+        Frame::TypeBlock("AWS::S3::Bucket").label()
+    );
+}
+
+#[test]
+fn test_nested_frames() {
+    let mut profiler = Profiler::default();
+    profiler.enter(Frame::Rule("s3_encrypted"));
+    profiler.count_resolution();
+    profiler.enter(Frame::Variable("buckets"));
+    profiler.count_resolution();
+    profiler.count_resolution();
+    profiler.exit();
+    profiler.exit();
+    profiler.enter(Frame::Rule("s3_encrypted"));
+    profiler.exit();
+    // nothing is open anymore
+    profiler.exit();
+
+    let stacks = profiler.stacks();
+    assert_eq!(2, stacks.len());
+
+    let rule = stacks[&stack(&["rule s3_encrypted"])];
+    assert_eq!(2, rule.calls);
+    assert_eq!(1, rule.resolutions);
+    assert!(rule.own <= rule.total);
+
+    let variable = stacks[&stack(&["rule s3_encrypted", "let buckets"])];
+    assert_eq!(1, variable.calls);
+    assert_eq!(2, variable.resolutions);
+    assert_eq!(variable.own, variable.total);
+    assert!(variable.total <= rule.total);
+}
+
+#[test]
+fn test_merge() {
+    let profiled = || {
+        let mut profiler = Profiler::default();
+        profiler.enter(Frame::Rule("s3_encrypted"));
+        profiler.enter(Frame::Variable("buckets"));
+        profiler.count_resolution();
+        profiler.exit();
+        profiler.exit();
+        profiler
+    };
+
+    let mut profiler = Profiler::default();
+    profiler.merge("s3.guard", profiled());
+    profiler.merge("s3.guard", profiled());
+    profiler.merge("iam.guard", profiled());
+
+    assert_eq!(
+        vec![
+            stack(&["iam.guard", "rule s3_encrypted"]),
+            stack(&["iam.guard", "rule s3_encrypted", "let buckets"]),
+            stack(&["s3.guard", "rule s3_encrypted"]),
+            stack(&["s3.guard", "rule s3_encrypted", "let buckets"]),
+        ],
+        profiler.stacks().keys().cloned().collect::<Vec<_>>()
+    );
+
+    let frames = profiler.frames();
+    assert_eq!(4, frames.len());
+    assert_eq!(2, frames[&("s3.guard", "let buckets")].calls);
+    assert_eq!(2, frames[&("s3.guard", "let buckets")].resolutions);
+    assert_eq!(1, frames[&("iam.guard", "rule s3_encrypted")].calls);
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+
+use crate::rules::errors::Error;
+
+/// the most patterns kept compiled, patterns built from data, e.g. the first argument of
+/// regex_replace, can make the cache grow with every document evaluated otherwise
+const MAX_CACHED_PATTERNS: usize = 1024;
+
+lazy_static! {
+    /// the regular expressions compiled so far, every value a clause is evaluated against is
+    /// matched with the same few patterns of the rules
+    static ref COMPILED: Mutex<HashMap<String, Arc<Regex>>> = Mutex::new(HashMap::new());
+}
+
+/// the pattern compiled, once however many times it is matched
+///
+/// This function will return an error if the pattern is not a valid regular expression
+pub(crate) fn compiled(pattern: &str) -> Result<Arc<Regex>, Error> {
+    if let Some(regex) = cache().get(pattern) {
+        return Ok(Arc::clone(regex));
+    }
+
+    let regex = Arc::new(Regex::new(pattern).map_err(Box::new)?);
+    let mut cache = cache();
+    if cache.len() >= MAX_CACHED_PATTERNS {
+        cache.clear();
+    }
+    cache.insert(pattern.to_string(), Arc::clone(&regex));
+
+    Ok(regex)
+}
+
+/// whether the pattern matches the value, false for patterns that are not valid regular
+/// expressions or that give up on the value, e.g. by backtracking too much, for the places that
+/// cannot report errors
+pub(crate) fn is_match(pattern: &str, value: &str) -> bool {
+    compiled(pattern)
+        .and_then(|regex| regex.is_match(value).map_err(|e| Error::from(Box::new(e))))
+        .unwrap_or(false)
+}
+
+fn cache() -> std::sync::MutexGuard<'static, HashMap<String, Arc<Regex>>> {
+    // a panic while the lock was held cannot leave the cache half updated, it is still usable
+    COMPILED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+#[path = "regex_cache_tests.rs"]
+mod regex_cache_tests;
@@ -0,0 +1,28 @@
+use super::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_compiled_once() {
+    let first = compiled("^arn:aws:s3:::[a-z0-9.-]+$").unwrap();
+    let second = compiled("^arn:aws:s3:::[a-z0-9.-]+$").unwrap();
+
+    assert!(Arc::ptr_eq(&first, &second));
+    assert!(first.is_match("arn:aws:s3:::logs").unwrap());
+}
+
+#[test]
+fn test_compiled_invalid_pattern() {
+    match compiled("(a|b") {
+        Err(Error::RegexError(e)) => {
+            assert!(matches!(*e, fancy_regex::Error::ParseError(4, _)), "{e}")
+        }
+        result => panic!("expected a regex error, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_is_match() {
+    assert_eq!(true, is_match("^aws:kms", "aws:kms:dsse"));
+    assert_eq!(false, is_match("^aws:kms", "AES256"));
+    assert_eq!(false, is_match("(a|b", "a"));
+}
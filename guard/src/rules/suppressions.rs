@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::rules::path_value::PathAwareValue;
+use crate::rules::QueryResult;
+
+/// a resource of a template opting out of a rule through its metadata, e.g.
+///
+/// ```yaml
+/// Metadata:
+///   guard:
+///     SuppressedRules:
+///       - s3_encryption: "ticket-123"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct Suppression {
+    pub(crate) resource: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) justification: Option<String>,
+}
+
+/// the resources of a template suppressing each rule, with the path of the resource
+#[derive(Debug, Default)]
+pub(crate) struct Suppressions {
+    by_rule: HashMap<String, Vec<(String, Suppression)>>,
+}
+
+impl Suppressions {
+    /// reads `Metadata.guard.SuppressedRules` of every resource of the template. A rule is named
+    /// on its own, or mapped to the justification for suppressing it
+    pub(crate) fn from_template(root: &PathAwareValue) -> Suppressions {
+        let mut suppressions = Suppressions::default();
+        let resources = match map_entry(root, "Resources") {
+            Some(PathAwareValue::Map((_, resources))) => resources,
+            _ => return suppressions,
+        };
+
+        for (logical_id, resource) in &resources.values {
+            let rules = match map_entry(resource, "Metadata")
+                .and_then(|metadata| map_entry(metadata, "guard"))
+                .and_then(|guard| map_entry(guard, "SuppressedRules"))
+            {
+                Some(PathAwareValue::List((_, rules))) => rules,
+                _ => continue,
+            };
+
+            for rule in rules {
+                let suppressed = match rule {
+                    PathAwareValue::String((_, name)) => vec![(name.as_str(), None)],
+                    PathAwareValue::Map((_, map)) => map
+                        .values
+                        .iter()
+                        .map(|(name, justification)| {
+                            let justification = match justification {
+                                PathAwareValue::String((_, text)) => Some(text.to_string()),
+                                _ => None,
+                            };
+                            (name.as_str(), justification)
+                        })
+                        .collect(),
+                    _ => continue,
+                };
+
+                for (name, justification) in suppressed {
+                    suppressions
+                        .by_rule
+                        .entry(name.to_string())
+                        .or_default()
+                        .push((
+                            resource.self_path().0.clone(),
+                            Suppression {
+                                resource: logical_id.to_string(),
+                                justification,
+                            },
+                        ));
+                }
+            }
+        }
+
+        suppressions
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.by_rule.is_empty()
+    }
+
+    /// the suppression of the rule by the resource the query result was found in, if any
+    pub(crate) fn covering(&self, rule: &str, result: &QueryResult) -> Option<&Suppression> {
+        let path = match result {
+            QueryResult::Resolved(value) => &value.self_path().0,
+            QueryResult::UnResolved(unresolved) => &unresolved.traversed_to.self_path().0,
+            QueryResult::Literal(_) => return None,
+        };
+
+        self.by_rule
+            .get(rule)?
+            .iter()
+            .find_map(
+                |(prefix, suppression)| match path.strip_prefix(prefix.as_str()) {
+                    Some(rest) if rest.is_empty() || rest.starts_with('/') => Some(suppression),
+                    _ => None,
+                },
+            )
+    }
+}
+
+fn map_entry<'value>(value: &'value PathAwareValue, key: &str) -> Option<&'value PathAwareValue> {
+    match value {
+        PathAwareValue::Map((_, map)) => map.values.get(key),
+        _ => None,
+    }
+}
@@ -0,0 +1,56 @@
+use std::convert::{TryFrom, TryInto};
+
+use crate::rules::path_value::{Path, PathAwareValue};
+use crate::rules::Result;
+
+/// name of the reserved variable the metrics are exposed under, e.g. `%template_metrics.resource_count`.
+/// A `let` with the same name in the rules file takes precedence
+pub(crate) const TEMPLATE_METRICS: &str = "template_metrics";
+
+const RESOURCES: &str = "Resources";
+const PARAMETERS: &str = "Parameters";
+
+/// computes size and complexity metrics for the document being evaluated, so that platform
+/// limits (number of resources per stack, template body size, ...) can be checked with
+/// regular clauses
+///
+/// - `resource_count`: number of entries in the top level `Resources` section
+/// - `parameter_count`: number of entries in the top level `Parameters` section
+/// - `byte_size`: size in bytes of the document serialized as compact JSON, this is independent
+///   of the formatting (or YAML vs JSON) of the file that was loaded
+/// - `max_depth`: maximum nesting depth of maps and lists, a scalar document has a depth of 0
+pub(crate) fn template_metrics(root: &PathAwareValue) -> Result<PathAwareValue> {
+    let (_, json): (String, serde_json::Value) = root.try_into()?;
+    let byte_size = serde_json::to_vec(&json)?.len();
+
+    let metrics = serde_json::json!({
+        "resource_count": section_len(root, RESOURCES),
+        "parameter_count": section_len(root, PARAMETERS),
+        "byte_size": byte_size,
+        "max_depth": max_depth(root),
+    });
+
+    PathAwareValue::try_from((&metrics, Path::root().extend_str(TEMPLATE_METRICS)))
+}
+
+fn section_len(root: &PathAwareValue, section: &str) -> usize {
+    match root {
+        PathAwareValue::Map((_, map)) => match map.values.get(section) {
+            Some(PathAwareValue::Map((_, section))) => section.values.len(),
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+fn max_depth(value: &PathAwareValue) -> usize {
+    match value {
+        PathAwareValue::Map((_, map)) => 1 + map.values.values().map(max_depth).max().unwrap_or(0),
+        PathAwareValue::List((_, list)) => 1 + list.iter().map(max_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+#[path = "template_metrics_tests.rs"]
+mod template_metrics_tests;
@@ -0,0 +1,114 @@
+use super::*;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::RulesFile;
+use crate::rules::Status;
+use pretty_assertions::assert_eq;
+use std::rc::Rc;
+
+const TEMPLATE: &str = r#"
+Parameters:
+  BucketName:
+    Type: String
+Resources:
+  bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketName:
+        Ref: BucketName
+      Tags:
+        - Key: team
+          Value: guard
+  topic:
+    Type: AWS::SNS::Topic
+"#;
+
+fn template() -> Result<PathAwareValue> {
+    PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(TEMPLATE)?)
+}
+
+fn metric(metrics: &PathAwareValue, name: &str) -> i64 {
+    match metrics {
+        PathAwareValue::Map((_, map)) => match map.values.get(name) {
+            Some(PathAwareValue::Int((_, value))) => *value,
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_template_metrics() -> Result<()> {
+    let value = template()?;
+    let metrics = template_metrics(&value)?;
+
+    assert_eq!(metric(&metrics, "resource_count"), 2);
+    assert_eq!(metric(&metrics, "parameter_count"), 1);
+    // Resources -> bucket -> Properties -> Tags -> [0] -> Key
+    assert_eq!(metric(&metrics, "max_depth"), 6);
+
+    let (_, json): (String, serde_json::Value) = (&value).try_into()?;
+    assert_eq!(
+        metric(&metrics, "byte_size"),
+        serde_json::to_string(&json)?.len() as i64
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_template_metrics_missing_sections() -> Result<()> {
+    let metrics = template_metrics(&PathAwareValue::try_from("{}")?)?;
+
+    assert_eq!(metric(&metrics, "resource_count"), 0);
+    assert_eq!(metric(&metrics, "parameter_count"), 0);
+    assert_eq!(metric(&metrics, "max_depth"), 1);
+    assert_eq!(metric(&metrics, "byte_size"), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_template_metrics_in_rules() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule stack_limits {
+        %template_metrics.resource_count <= 2
+        %template_metrics.parameter_count < 10
+        %template_metrics.max_depth <= 6
+        %template_metrics.byte_size < 51200
+    }
+    "#,
+    )?;
+    let value = Rc::new(template()?);
+    let mut root = root_scope(&rules_file, Rc::clone(&value));
+    assert_eq!(eval_rules_file(&rules_file, &mut root, None)?, Status::PASS);
+
+    let rules_file = RulesFile::try_from(
+        r#"
+    rule stack_limits {
+        %template_metrics.resource_count <= 1
+    }
+    "#,
+    )?;
+    let mut root = root_scope(&rules_file, Rc::clone(&value));
+    assert_eq!(eval_rules_file(&rules_file, &mut root, None)?, Status::FAIL);
+
+    Ok(())
+}
+
+#[test]
+fn test_template_metrics_shadowed_by_let() -> Result<()> {
+    let rules_file = RulesFile::try_from(
+        r#"
+    let template_metrics = { "resource_count": 500 }
+    rule stack_limits {
+        %template_metrics.resource_count == 500
+    }
+    "#,
+    )?;
+    let mut root = root_scope(&rules_file, Rc::new(template()?));
+    assert_eq!(eval_rules_file(&rules_file, &mut root, None)?, Status::PASS);
+
+    Ok(())
+}
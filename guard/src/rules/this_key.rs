@@ -0,0 +1,202 @@
+use crate::rules::exprs::QueryPart;
+use crate::rules::path_value::{MapValue, PathAwareValue};
+use crate::rules::{ClauseCheck, EvalContext, QueryResult, RecordType, Result};
+
+/// name of the reserved variable the element a filter or block is evaluated against is exposed
+/// under, `%THIS.key` is the map key or list index it was found at. A `let` with the same name in
+/// the rules file takes precedence
+pub(crate) const THIS: &str = "THIS";
+
+/// replaced in the custom message of a clause with the key or index of the element each failing
+/// value was found in, e.g. the logical id of the resource for `Resources.*`
+pub(crate) const THIS_KEY: &str = "%THIS.key";
+
+const KEY: &str = "key";
+
+/// the key or index the value was found at, the last part of its path
+fn last_key(path: &str) -> Option<&str> {
+    match path.rsplit_once('/') {
+        Some((_, key)) if !key.is_empty() => Some(key),
+        _ => None,
+    }
+}
+
+/// `{"key": ...}` for the value a filter or block is evaluated against, the key is an int when the
+/// value is an element of a list of the document
+pub(crate) fn this_value(
+    value: &PathAwareValue,
+    document: &PathAwareValue,
+) -> Option<PathAwareValue> {
+    let path = value.self_path();
+    let key = last_key(&path.0)?;
+    let parent = path.0.strip_suffix(key)?.trim_end_matches('/');
+
+    let in_list = parent
+        .split('/')
+        .skip(1)
+        .try_fold(document, |current, part| match current {
+            PathAwareValue::Map((_, map)) => map.values.get(part),
+            PathAwareValue::List((_, list)) => list.get(part.parse::<usize>().ok()?),
+            _ => None,
+        })
+        .map_or(false, |parent| matches!(parent, PathAwareValue::List(_)));
+
+    let key_path = path.extend_str(KEY);
+    let key = match key.parse::<i64>() {
+        Ok(index) if in_list => PathAwareValue::Int((key_path, index)),
+        _ => PathAwareValue::String((key_path, key.to_string())),
+    };
+
+    Some(PathAwareValue::Map((
+        path.clone(),
+        MapValue {
+            keys: vec![PathAwareValue::String((path.clone(), KEY.to_string()))],
+            values: std::iter::once((KEY.to_string(), key)).collect(),
+        },
+    )))
+}
+
+/// how a part of a query moves down the path of the values it resolves to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// one level down to a named key or a fixed index
+    Descend,
+    /// one level down to any key or index, `*`, `[*]` or a filter, which is the key to report
+    Capture,
+    /// the rest of the path can not be followed from the query
+    Stop,
+}
+
+/// what `%THIS.key` stands for in the custom message of the clause being evaluated, worked out by
+/// following the path of each failing value along the query of the clause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ThisKeys {
+    /// the paths the query starts from, with the key `%THIS.key` stands for at each of them
+    anchors: Vec<(String, Option<String>)>,
+    steps: Vec<Step>,
+}
+
+impl ThisKeys {
+    pub(crate) fn new<'value, 'loc: 'value>(
+        query: &'value [QueryPart<'loc>],
+        resolver: &mut dyn EvalContext<'value, 'loc>,
+    ) -> Result<ThisKeys> {
+        let (anchors, query) = match query.first().and_then(|part| part.variable()) {
+            Some(variable) => {
+                let anchors = resolver
+                    .resolve_variable(variable)?
+                    .iter()
+                    .filter_map(|value| match value {
+                        QueryResult::Resolved(value) => Some(value.self_path().0.clone()),
+                        _ => None,
+                    })
+                    .map(|path| {
+                        let key = last_key(&path).map(String::from);
+                        (path, key)
+                    })
+                    .collect();
+                (anchors, &query[1..])
+            }
+            None => {
+                let root = resolver.root();
+                let path = root.self_path().0.clone();
+                let key = last_key(&path).map(String::from);
+                (vec![(path, key)], query)
+            }
+        };
+
+        let steps = query
+            .iter()
+            .enumerate()
+            .filter_map(|(index, part)| match part {
+                QueryPart::This => None,
+                QueryPart::Key(_) if part.is_variable() => Some(Step::Stop),
+                QueryPart::Key(_) | QueryPart::Index(_) => Some(Step::Descend),
+                QueryPart::AllValues(_)
+                | QueryPart::AllIndices(_)
+                | QueryPart::Slice(..)
+                | QueryPart::MapKeyFilter(..) => Some(Step::Capture),
+                // a filter right after `*`, `[*]`, an index or a slice checks the element that was
+                // already selected
+                QueryPart::Filter(..) => match index.checked_sub(1).map(|prev| &query[prev]) {
+                    Some(QueryPart::AllValues(_))
+                    | Some(QueryPart::AllIndices(_))
+                    | Some(QueryPart::Index(_))
+                    | Some(QueryPart::Slice(..)) => None,
+                    _ => Some(Step::Capture),
+                },
+                QueryPart::Resolve | QueryPart::Descendants(_) => Some(Step::Stop),
+            })
+            .collect();
+
+        Ok(ThisKeys { anchors, steps })
+    }
+
+    /// the key of the element the value was found in, `None` when the value is not reached by
+    /// the query of the clause
+    fn key<'a>(&'a self, value: &'a QueryResult) -> Option<&'a str> {
+        let path = match value {
+            QueryResult::Resolved(value) => &value.self_path().0,
+            QueryResult::UnResolved(unresolved) => &unresolved.traversed_to.self_path().0,
+            QueryResult::Literal(_) => return None,
+        };
+
+        let (rest, key) = self
+            .anchors
+            .iter()
+            .filter_map(|(anchor, key)| match path.strip_prefix(anchor.as_str()) {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => Some((rest, key)),
+                _ => None,
+            })
+            .min_by_key(|(rest, _)| rest.len())?;
+
+        let mut key = key.as_deref();
+        for (step, part) in self.steps.iter().zip(rest.split('/').skip(1)) {
+            match step {
+                Step::Descend => {}
+                Step::Capture => key = Some(part),
+                Step::Stop => break,
+            }
+        }
+        key
+    }
+
+    fn render(&self, custom_message: &mut Option<String>, value: &QueryResult) {
+        if let (Some(message), Some(key)) = (custom_message.as_mut(), self.key(value)) {
+            *message = message.replace(THIS_KEY, key);
+        }
+    }
+
+    /// the record with `%THIS.key` in its custom message replaced by the key of the value it
+    /// checked
+    pub(crate) fn render_record<'value>(&self, record: RecordType<'value>) -> RecordType<'value> {
+        let check = match record {
+            RecordType::ClauseValueCheck(check) => check,
+            other => return other,
+        };
+
+        RecordType::ClauseValueCheck(match check {
+            ClauseCheck::Comparison(mut check) => {
+                self.render(&mut check.custom_message, &check.from);
+                ClauseCheck::Comparison(check)
+            }
+            ClauseCheck::InComparison(mut check) => {
+                self.render(&mut check.custom_message, &check.from);
+                ClauseCheck::InComparison(check)
+            }
+            ClauseCheck::Unary(mut check) => {
+                self.render(&mut check.value.custom_message, &check.value.from);
+                ClauseCheck::Unary(check)
+            }
+            ClauseCheck::MissingBlockValue(mut check) => {
+                self.render(&mut check.custom_message, &check.from);
+                ClauseCheck::MissingBlockValue(check)
+            }
+            other => other,
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "this_key_tests.rs"]
+mod this_key_tests;
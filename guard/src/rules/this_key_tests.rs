@@ -0,0 +1,116 @@
+use pretty_assertions::assert_eq;
+use std::convert::{TryFrom, TryInto};
+use std::rc::Rc;
+
+use super::*;
+use crate::rules::eval::eval_rules_file;
+use crate::rules::eval_context::root_scope;
+use crate::rules::exprs::{AccessQuery, RulesFile, SliceDisplay};
+use crate::rules::Status;
+
+const TEMPLATE: &str = r#"
+Resources:
+  ProdBucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      Tags:
+        - Key: Name
+          Value: prod
+  DevBucket:
+    Type: AWS::S3::Bucket
+  ProdQueue:
+    Type: AWS::SQS::Queue
+"#;
+
+fn template() -> PathAwareValue {
+    PathAwareValue::try_from(serde_yaml::from_str::<serde_yaml::Value>(TEMPLATE).unwrap()).unwrap()
+}
+
+#[test]
+fn test_this_key_in_filters() -> Result<()> {
+    for (rules, expected) in [
+        (
+            "rule prod { Resources[ %THIS.key == /^Prod/ ].Type == 'AWS::S3::Bucket' }",
+            Status::FAIL,
+        ),
+        (
+            "rule prod { Resources[ %THIS.key == /Bucket$/ ].Type == 'AWS::S3::Bucket' }",
+            Status::PASS,
+        ),
+        // list elements are keyed by their index
+        (
+            "rule tags { Resources.ProdBucket.Properties.Tags[ %THIS.key == 0 ].Key == 'Name' }",
+            Status::PASS,
+        ),
+        // a variable named THIS takes precedence
+        (
+            "let THIS = { \"key\": \"Other\" }\nrule other { Resources[ %THIS.key == 'Other' ] !empty }",
+            Status::PASS,
+        ),
+    ] {
+        let rules_file = RulesFile::try_from(rules)?;
+        let mut root = root_scope(&rules_file, Rc::new(template()));
+        assert_eq!(expected, eval_rules_file(&rules_file, &mut root, None)?, "{rules}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_this_keys_of_values() -> Result<()> {
+    let queries = [
+        (
+            "Resources.*.Properties.Tags",
+            vec![Some("ProdBucket"), Some("DevBucket"), Some("ProdQueue")],
+        ),
+        // the key of the last `*`, `[*]` or filter along the way
+        (
+            "Resources.*.Properties.Tags[*].Value",
+            vec![Some("0"), Some("DevBucket"), Some("ProdQueue")],
+        ),
+        (
+            "Resources[ Type == 'AWS::S3::Bucket' ].Properties",
+            vec![Some("ProdBucket"), Some("DevBucket")],
+        ),
+        (
+            "%buckets.Properties",
+            vec![Some("ProdBucket"), Some("DevBucket")],
+        ),
+        ("Resources.ProdBucket.Type", vec![None]),
+    ]
+    .iter()
+    .map(|(query, expected)| Ok((AccessQuery::try_from(*query)?, expected.clone())))
+    .collect::<Result<Vec<_>>>()?;
+
+    let rules_file = RulesFile::try_from("let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]")?;
+    let mut root = root_scope(&rules_file, Rc::new(template()));
+
+    for (query, expected) in &queries {
+        let this_keys = ThisKeys::new(&query.query, &mut root)?;
+        let values = root.query(&query.query)?;
+        let keys = values
+            .iter()
+            .map(|value| this_keys.key(value))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, &keys, "{}", SliceDisplay(&query.query));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_this_value() {
+    let template = template();
+    let bucket = match &template {
+        PathAwareValue::Map((_, map)) => match &map.values["Resources"] {
+            PathAwareValue::Map((_, resources)) => resources.values["ProdBucket"].clone(),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+
+    let this = this_value(&bucket, &template).unwrap();
+    let (_, this): (String, serde_json::Value) = (&this).try_into().unwrap();
+    assert_eq!(serde_json::json!({ "key": "ProdBucket" }), this);
+    assert!(this_value(&template, &template).is_none());
+}
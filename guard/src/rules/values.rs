@@ -21,7 +21,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize, Hash, Copy)]
 pub enum CmpOperator {
     Eq,
+    UnorderedEq,
     In,
+    Like,
     Gt,
     Lt,
     Le,
@@ -59,7 +61,9 @@ impl Display for CmpOperator {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             CmpOperator::Eq => f.write_str("EQUALS")?,
+            CmpOperator::UnorderedEq => f.write_str("UNORDERED EQUALS")?,
             CmpOperator::In => f.write_str("IN")?,
+            CmpOperator::Like => f.write_str("LIKE")?,
             CmpOperator::Gt => f.write_str("GREATER THAN")?,
             CmpOperator::Lt => f.write_str("LESS THAN")?,
             CmpOperator::Ge => f.write_str("GREATER THAN EQUALS")?,
@@ -277,6 +281,61 @@ fn is_within<T: PartialOrd>(range: &RangeType<T>, other: &T) -> bool {
     lower && upper
 }
 
+//
+//    .VolumeSize >= 5GiB
+//    .Timeout <= 15m
+//
+// sizes are normalized to bytes and durations to seconds
+//
+const UNITS: [(&str, f64); 17] = [
+    ("B", 1.0),
+    ("KB", 1e3),
+    ("MB", 1e6),
+    ("GB", 1e9),
+    ("TB", 1e12),
+    ("PB", 1e15),
+    ("KiB", 1024.0),
+    ("MiB", 1_048_576.0),
+    ("GiB", 1_073_741_824.0),
+    ("TiB", 1_099_511_627_776.0),
+    ("PiB", 1_125_899_906_842_624.0),
+    ("ms", 0.001),
+    ("s", 1.0),
+    ("m", 60.0),
+    ("h", 3_600.0),
+    ("d", 86_400.0),
+    ("w", 604_800.0),
+];
+
+pub(crate) fn unit_factor(unit: &str) -> Option<f64> {
+    UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, factor)| *factor)
+}
+
+/// the number of bytes or seconds in a size or duration written with a unit, e.g. 512MiB or 30d
+pub(crate) fn parse_quantity(text: &str) -> Option<f64> {
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = text.split_at(split);
+    if number.is_empty() || number.starts_with('.') || number.ends_with('.') {
+        return None;
+    }
+    Some(number.parse::<f64>().ok()? * unit_factor(unit)?)
+}
+
+/// a quantity in bytes or seconds as a value, an integer unless it has a fraction, e.g. 1500ms
+pub(crate) fn quantity_value(quantity: f64) -> Value {
+    let rounded = quantity.round();
+    if (quantity - rounded).abs() <= f64::EPSILON * rounded.abs().max(1.0)
+        && rounded.abs() < i64::MAX as f64
+    {
+        Value::Int(rounded as i64)
+    } else {
+        Value::Float(quantity)
+    }
+}
+
 impl<'a> TryFrom<&'a serde_yaml::Value> for Value {
     type Error = Error;
 
@@ -442,9 +501,29 @@ impl MarkedValue {
 }
 
 pub(crate) fn read_from(from_reader: &str) -> crate::rules::Result<MarkedValue> {
-    let mut loader = Loader::new();
+    read_from_with_max_depth(from_reader, None)
+}
+
+/// the first document, failing once its mappings and lists nest deeper than `max_depth`
+pub(crate) fn read_from_with_max_depth(
+    from_reader: &str,
+    max_depth: Option<usize>,
+) -> crate::rules::Result<MarkedValue> {
+    let mut loader = Loader::with_max_depth(max_depth);
     match loader.load(from_reader.to_string()) {
         Ok(doc) => Ok(doc),
+        Err(e) => match e {
+            Error::InternalError(..) | Error::LimitExceeded(..) => Err(e),
+            _ => Err(Error::ParseError(format!("{}", e))),
+        },
+    }
+}
+
+/// every document of a multi-document YAML file, a JSON file is a single document
+pub(crate) fn read_all_from(from_reader: &str) -> crate::rules::Result<Vec<MarkedValue>> {
+    let mut loader = Loader::new();
+    match loader.load_all(from_reader.to_string()) {
+        Ok(docs) => Ok(docs),
         Err(e) => match e {
             Error::InternalError(..) => Err(e),
             _ => Err(Error::ParseError(format!("{}", e))),
@@ -485,3 +485,37 @@ Resources:
     println!("{:?}", path_value);
     Ok(())
 }
+
+#[test]
+fn test_read_all_documents() -> Result<()> {
+    let documents = r#"
+---
+kind: Pod
+metadata:
+  name: app
+---
+kind: Service
+metadata:
+  name: app
+"#;
+
+    let values = super::read_all_from(documents)?;
+    assert_eq!(2, values.len());
+
+    let kinds = values
+        .into_iter()
+        .map(|value| {
+            let path_value = PathAwareValue::try_from(value)?;
+            match path_value {
+                PathAwareValue::Map((_, map)) => match map.values.get("kind") {
+                    Some(PathAwareValue::String((_, kind))) => Ok(kind.clone()),
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(vec!["Pod", "Service"], kinds);
+
+    Ok(())
+}
@@ -9,6 +9,8 @@ use std::io::{Read, Stderr, Stdout, Write};
 pub struct Writer {
     buffer: WriteBuffer,
     err: WriteBuffer,
+    /// discards everything but errors, see `--quiet`
+    quiet: bool,
 }
 
 impl Default for Writer {
@@ -16,6 +18,7 @@ impl Default for Writer {
         Self {
             buffer: WriteBuffer::Stdout(std::io::stdout()),
             err: WriteBuffer::Stderr(std::io::stderr()),
+            quiet: false,
         }
     }
 }
@@ -31,6 +34,7 @@ impl Writer {
         Ok(Self {
             buffer,
             err: WriteBuffer::Stderr(std::io::stderr()),
+            quiet: false,
         })
     }
 
@@ -41,7 +45,30 @@ impl Writer {
             )));
         }
 
-        Ok(Self { buffer, err })
+        Ok(Self {
+            buffer,
+            err,
+            quiet: false,
+        })
+    }
+
+    /// discards the output written to stdout from now on, errors and output files are still written
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// whether only errors are written, see [`Writer::set_quiet`]
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// writes a line to the error stream that is not an error, e.g. a progress or timing report,
+    /// which is discarded when the writer is quiet
+    pub fn write_info(&mut self, s: String) -> std::io::Result<()> {
+        match self.quiet {
+            true => Ok(()),
+            false => self.write_err(s),
+        }
     }
 
     pub fn write_err(&mut self, s: String) -> std::io::Result<()> {
@@ -101,6 +128,10 @@ impl Writer {
 
 impl Write for Writer {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // output files are still written, only the console is kept quiet
+        if self.quiet && !matches!(self.buffer, WriteBuffer::File(_)) {
+            return Ok(buf.len());
+        }
         self.buffer.write(String::from_utf8_lossy(buf).as_bytes())
     }
 
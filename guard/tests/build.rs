@@ -0,0 +1,147 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod build_tests {
+    use crate::assert_output_from_file_eq;
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct BuildTestRunner<'args> {
+        rules: Vec<&'args str>,
+        output: Option<&'args str>,
+    }
+
+    impl<'args> BuildTestRunner<'args> {
+        fn rules(&'args mut self, arg: &'args str) -> &'args mut BuildTestRunner {
+            self.rules.push(arg);
+            self
+        }
+
+        fn output(&'args mut self, arg: &'args str) -> &'args mut BuildTestRunner {
+            self.output = Some(arg);
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for BuildTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Build.to_string()];
+
+            for rules in &self.rules {
+                args.push(String::from("--rules"));
+                args.push(get_full_path_for_resource_file(rules));
+            }
+
+            if let Some(output) = self.output {
+                args.push(String::from("--output"));
+                args.push(output.to_string());
+            }
+
+            args
+        }
+    }
+
+    /// validates the data against the rules pack, not a resource file
+    struct ValidatePackRunner<'args> {
+        pack: &'args str,
+        data: &'args str,
+    }
+
+    impl<'args> CommandTestRunner for ValidatePackRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            vec![
+                Command::Validate.to_string(),
+                String::from("--rules"),
+                self.pack.to_string(),
+                String::from("--data"),
+                get_full_path_for_resource_file(self.data),
+            ]
+        }
+    }
+
+    fn temp_pack(name: &str) -> String {
+        std::env::temp_dir()
+            .join(name)
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_validate_against_built_pack() {
+        let pack = temp_pack("cfn-guard-build-test.pack");
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = BuildTestRunner::default()
+            .rules("resources/validate/rules-dir")
+            .output(&pack)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!("", writer.stripped().unwrap());
+
+        // the pack names its rules files as validate names the files of the directory
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidatePackRunner {
+            pack: &pack,
+            data: "resources/validate/data-dir",
+        }
+        .run(&mut writer, &mut reader);
+        std::fs::remove_file(&pack).unwrap();
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/rules_dir_against_data_dir.out",
+            writer
+        )
+    }
+
+    #[test]
+    fn test_build_with_parse_errors() {
+        let pack = temp_pack("cfn-guard-build-parse-errors-test.pack");
+
+        let mut reader = Reader::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = BuildTestRunner::default()
+            .rules("resources/validate/rules-with-syntax-errors.guard")
+            .output(&pack)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+        assert!(writer
+            .err_to_stripped()
+            .unwrap()
+            .contains("Parsing error handling rule file"));
+        assert!(!std::path::Path::new(&pack).exists());
+    }
+
+    #[test]
+    fn test_validate_against_other_file_named_pack() {
+        let pack = temp_pack("cfn-guard-not-a-pack-test.pack");
+        std::fs::write(&pack, "rule s3_exists { Resources exists }").unwrap();
+
+        let mut reader = Reader::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidatePackRunner {
+            pack: &pack,
+            data: "resources/validate/data-dir",
+        }
+        .run(&mut writer, &mut reader);
+        std::fs::remove_file(&pack).unwrap();
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(writer
+            .err_to_stripped()
+            .unwrap()
+            .contains("is not a rules pack, build one with cfn-guard build"));
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod conformance_tests {
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct ConformanceTestRunner<'args> {
+        impl_cmd: Option<&'args str>,
+        cases: Option<&'args str>,
+        export: bool,
+    }
+
+    impl<'args> ConformanceTestRunner<'args> {
+        fn impl_cmd(&'args mut self, arg: &'args str) -> &'args mut ConformanceTestRunner {
+            self.impl_cmd = Some(arg);
+            self
+        }
+
+        fn cases(&'args mut self, arg: &'args str) -> &'args mut ConformanceTestRunner {
+            self.cases = Some(arg);
+            self
+        }
+
+        fn export(&'args mut self) -> &'args mut ConformanceTestRunner {
+            self.export = true;
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for ConformanceTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Conformance.to_string()];
+
+            if let Some(impl_cmd) = self.impl_cmd {
+                args.push(String::from("--impl-cmd"));
+                args.push(impl_cmd.to_string());
+            }
+
+            if let Some(cases) = self.cases {
+                args.push(String::from("--cases"));
+                args.push(cases.to_string());
+            }
+
+            if self.export {
+                args.push(String::from("--export"));
+            }
+
+            args
+        }
+    }
+
+    #[test]
+    fn test_bundled_cases_conform() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ConformanceTestRunner::default().run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(output.starts_with("PASS binary/equals\n"));
+        assert!(!output.contains("FAIL "));
+    }
+
+    #[test]
+    fn test_export_cases() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ConformanceTestRunner::default()
+            .cases("resources/conformance/divergence-cases.yaml")
+            .export()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let exported =
+            serde_json::from_str::<serde_json::Value>(&writer.stripped().unwrap()).unwrap();
+        assert_eq!("conforming", exported["cases"][0]["name"]);
+        assert_eq!("SKIP", exported["cases"][1]["expected"]["absent"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_impl_cmd_divergence() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ConformanceTestRunner::default()
+            .impl_cmd(r#"cat > /dev/null; echo '{"present": "PASS"}'"#)
+            .cases("resources/conformance/divergence-cases.yaml")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::TEST_COMMAND_FAILURE, status_code);
+        assert_eq!(
+            indoc! {r#"
+                PASS conforming
+                FAIL diverging
+                  rule present: expected FAIL, got PASS
+                  rule absent: expected SKIP, missing from the output
+                1 of 2 cases conform
+            "#},
+            writer.stripped().unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_impl_cmd_receives_case() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ConformanceTestRunner::default()
+            .impl_cmd(r#"grep -q '"name":"conforming"' && echo '{"present": "PASS"}' || echo '{}'"#)
+            .cases("resources/conformance/divergence-cases.yaml")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::TEST_COMMAND_FAILURE, status_code);
+        assert!(writer.stripped().unwrap().starts_with("PASS conforming\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_impl_cmd_failure() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ConformanceTestRunner::default()
+            .impl_cmd("cat > /dev/null; echo not json")
+            .cases("resources/conformance/divergence-cases.yaml")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::TEST_COMMAND_FAILURE, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(
+            output.contains("FAIL conforming\n  unable to read the rule statuses from the output")
+        );
+        assert!(output.ends_with("0 of 2 cases conform\n"));
+    }
+}
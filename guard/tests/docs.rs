@@ -0,0 +1,106 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod docs_tests {
+    use crate::assert_output_from_file_eq;
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct DocsTestRunner<'args> {
+        rules: Vec<&'args str>,
+        output: Option<&'args str>,
+        format: Option<&'args str>,
+    }
+
+    impl<'args> DocsTestRunner<'args> {
+        fn rules(&'args mut self, arg: &'args str) -> &'args mut DocsTestRunner {
+            self.rules.push(arg);
+            self
+        }
+
+        fn output(&'args mut self, arg: &'args str) -> &'args mut DocsTestRunner {
+            self.output = Some(arg);
+            self
+        }
+
+        fn format(&'args mut self, arg: &'args str) -> &'args mut DocsTestRunner {
+            self.format = Some(arg);
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for DocsTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Docs.to_string()];
+
+            for rules in &self.rules {
+                args.push(String::from("--rules"));
+                args.push(get_full_path_for_resource_file(rules));
+            }
+
+            if let Some(output) = self.output {
+                args.push(String::from("--output"));
+                args.push(output.to_string());
+            }
+
+            if let Some(format) = self.format {
+                args.push(String::from("--format"));
+                args.push(format.to_string());
+            }
+
+            args
+        }
+    }
+
+    #[test]
+    fn test_markdown_docs() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = DocsTestRunner::default()
+            .rules("resources/docs/rules")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_output_from_file_eq!("resources/docs/output-dir/docs.md", writer)
+    }
+
+    #[test]
+    fn test_html_docs_to_output_file() {
+        let output = std::env::temp_dir().join("cfn-guard-docs-test.html");
+        let output = output.to_str().unwrap();
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = DocsTestRunner::default()
+            .rules("resources/docs/rules/s3.guard")
+            .format("html")
+            .output(output)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!("", writer.stripped().unwrap());
+
+        let html = std::fs::read_to_string(output).unwrap();
+        std::fs::remove_file(output).unwrap();
+        assert!(html.contains("<h3>s3_bucket_versioning_enabled</h3>\n<p>Versioning protects objects from being overwritten or deleted by mistake.</p>\n"));
+        assert!(html.contains("<li><strong>Severity:</strong> warning</li>"));
+        assert!(html.contains("<br>Message: <em>Versioning must be enabled on all S3 buckets</em>"));
+    }
+
+    #[test]
+    fn test_docs_missing_rules() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = DocsTestRunner::default()
+            .rules("resources/docs/dne")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+}
@@ -157,4 +157,361 @@ mod functional_tests {
             .unwrap();
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn test_compiled_rules_across_threads() {
+        use cfn_guard::*;
+        use std::sync::Arc;
+
+        let rule = "AWS::S3::Bucket { Properties exists }";
+        let compiled = Arc::new(
+            CompiledRules::compile(ValidateInput {
+                content: rule,
+                file_name: "functional_test.rule",
+            })
+            .unwrap(),
+        );
+
+        let handles = vec![
+            r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket" } } }"#,
+            r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket", "Properties": {} } } }"#,
+        ]
+        .into_iter()
+        .map(|data| {
+            let compiled = Arc::clone(&compiled);
+            std::thread::spawn(move || {
+                let input = || ValidateInput {
+                    content: data,
+                    file_name: "functional_test.json",
+                };
+                let expected = run_checks(
+                    input(),
+                    ValidateInput {
+                        content: rule,
+                        file_name: "functional_test.rule",
+                    },
+                    false,
+                )
+                .unwrap();
+                assert_eq!(expected, compiled.validate(input(), false).unwrap());
+            })
+        })
+        .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(CompiledRules::compile(ValidateInput {
+            content: "rule broken {",
+            file_name: "broken.rule",
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_compiled_rules_with_limits() {
+        use cfn_guard::*;
+
+        let compiled = CompiledRules::compile(ValidateInput {
+            content: "let buckets = Resources.*[ Type == 'AWS::S3::Bucket' ]\nrule s3 { %buckets.Properties exists }",
+            file_name: "functional_test.rule",
+        })
+        .unwrap();
+        let data = r#"{ "Resources": { "a": { "Type": "AWS::S3::Bucket", "Properties": {} }, "b": { "Type": "AWS::S3::Bucket", "Properties": {} } } }"#;
+        let validate = |limits: InputLimits| {
+            compiled.validate_with_limits(
+                ValidateInput {
+                    content: data,
+                    file_name: "functional_test.json",
+                },
+                false,
+                &limits,
+            )
+        };
+
+        assert_eq!(
+            compiled
+                .validate(
+                    ValidateInput {
+                        content: data,
+                        file_name: "functional_test.json",
+                    },
+                    false
+                )
+                .unwrap(),
+            validate(InputLimits {
+                max_file_size: Some(data.len()),
+                max_nesting_depth: Some(4),
+                max_query_results: Some(2),
+            })
+            .unwrap()
+        );
+
+        for limits in [
+            InputLimits {
+                max_file_size: Some(data.len() - 1),
+                ..InputLimits::default()
+            },
+            InputLimits {
+                max_nesting_depth: Some(3),
+                ..InputLimits::default()
+            },
+            InputLimits {
+                max_query_results: Some(1),
+                ..InputLimits::default()
+            },
+        ] {
+            match validate(limits) {
+                Err(Error::LimitExceeded(_)) => {}
+                result => panic!("expected {limits:?} to be exceeded, got {result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_compiled_rules_structured() {
+        use cfn_guard::commands::validate::OutputFormatType;
+        use cfn_guard::*;
+
+        let compiled = CompiledRules::compile(ValidateInput {
+            content: "rule s3 { Resources.*[ Type == 'AWS::S3::Bucket' ].Properties exists }",
+            file_name: "functional_test.rule",
+        })
+        .unwrap();
+        let validate = |data: &str, format: OutputFormatType| {
+            compiled.validate_structured(
+                ValidateInput {
+                    content: data,
+                    file_name: "functional_test.json",
+                },
+                format,
+            )
+        };
+
+        let (exit_code, report) = validate(
+            r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket" } } }"#,
+            OutputFormatType::JSON,
+        )
+        .unwrap();
+        let report = serde_json::from_str::<serde_json::Value>(&report).unwrap();
+        assert_eq!(ExitCode::Failure.code(), exit_code);
+        assert_eq!("functional_test.json", report[0]["name"]);
+        assert_eq!("FAIL", report[0]["status"]);
+
+        let (exit_code, report) = validate(
+            r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket", "Properties": {} } } }"#,
+            OutputFormatType::Junit,
+        )
+        .unwrap();
+        assert_eq!(ExitCode::Success.code(), exit_code);
+        assert!(report.contains("<testsuites"));
+
+        assert!(validate("{}", OutputFormatType::SingleLineSummary).is_err());
+    }
+
+    #[test]
+    fn test_compiled_rules_from_builder() {
+        use cfn_guard::ast::{ClauseBuilder, RuleBuilder, RulesFileBuilder};
+        use cfn_guard::*;
+
+        let rules = RulesFileBuilder::default()
+            .rule(
+                RuleBuilder::new("s3_encrypted")
+                    .type_block("AWS::S3::Bucket")
+                    .clause(ClauseBuilder::property("Properties.BucketEncryption").exists()),
+            )
+            .try_build()
+            .unwrap();
+        let compiled = CompiledRules::from_rules_file("built.guard", rules);
+        let validate = |data: &str| {
+            let result = compiled
+                .validate(
+                    ValidateInput {
+                        content: data,
+                        file_name: "functional_test.json",
+                    },
+                    false,
+                )
+                .unwrap();
+            serde_json::from_str::<serde_json::Value>(&result).unwrap()
+        };
+
+        let failure = validate(
+            r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket", "Properties": {} } } }"#,
+        );
+        assert_eq!("FAIL", failure["status"]);
+        assert_eq!("s3_encrypted", failure["not_compliant"][0]["Rule"]["name"]);
+
+        let success = validate(
+            r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket", "Properties": { "BucketEncryption": {} } } } }"#,
+        );
+        assert_eq!("PASS", success["status"]);
+        assert_eq!(serde_json::json!(["s3_encrypted"]), success["compliant"]);
+    }
+
+    #[test]
+    fn test_compiled_rules_with_observer() {
+        use cfn_guard::*;
+
+        #[derive(Default)]
+        struct Progress {
+            rules: Vec<(String, Status)>,
+        }
+
+        impl EvaluationObserver for Progress {
+            fn on_end(&mut self, event: &EvaluationEvent<'_>) {
+                if event.kind == EventKind::RuleCheck {
+                    self.rules
+                        .push((event.name.unwrap().to_string(), event.status));
+                }
+            }
+        }
+
+        let compiled = CompiledRules::compile(ValidateInput {
+            content: "rule s3 { Resources.*.Properties exists }\nrule ec2 { Resources.*.Type == 'AWS::EC2::Instance' }",
+            file_name: "functional_test.rule",
+        })
+        .unwrap();
+        let input = || ValidateInput {
+            content: r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket", "Properties": {} } } }"#,
+            file_name: "functional_test.json",
+        };
+
+        let mut progress = Progress::default();
+        let result = compiled
+            .validate_with_observer(input(), false, &mut progress)
+            .unwrap();
+
+        assert_eq!(compiled.validate(input(), false).unwrap(), result);
+        assert_eq!(
+            vec![
+                (String::from("s3"), Status::PASS),
+                (String::from("ec2"), Status::FAIL)
+            ],
+            progress.rules
+        );
+    }
+
+    #[test]
+    fn test_compiled_rules_from_pack() {
+        use cfn_guard::commands::CfnGuard;
+        use cfn_guard::utils::reader::Reader;
+        use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+        use cfn_guard::*;
+        use clap::Parser;
+
+        let rule = "AWS::S3::Bucket { Properties exists }";
+        let rules_file = std::env::temp_dir().join("cfn-guard-functional-test.guard");
+        let pack = std::env::temp_dir().join("cfn-guard-functional-test.pack");
+        std::fs::write(&rules_file, rule).unwrap();
+
+        let build = CfnGuard::parse_from([
+            "cfn-guard",
+            "build",
+            "--rules",
+            rules_file.to_str().unwrap(),
+            "--output",
+            pack.to_str().unwrap(),
+        ]);
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
+        assert_eq!(
+            0,
+            build.execute(&mut writer, &mut Reader::default()).unwrap()
+        );
+
+        let compiled =
+            CompiledRules::from_pack("functional_test.pack", &std::fs::read(&pack).unwrap())
+                .unwrap();
+        std::fs::remove_file(&rules_file).unwrap();
+        std::fs::remove_file(&pack).unwrap();
+
+        assert_eq!(1, compiled.len());
+        assert_eq!(rules_file.to_str().unwrap(), compiled[0].file_name());
+        let data = || ValidateInput {
+            content: r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket" } } }"#,
+            file_name: "functional_test.json",
+        };
+        let expected = run_checks(
+            data(),
+            ValidateInput {
+                content: rule,
+                file_name: compiled[0].file_name(),
+            },
+            false,
+        )
+        .unwrap();
+        assert_eq!(expected, compiled[0].validate(data(), false).unwrap());
+
+        assert!(CompiledRules::from_pack("functional_test.pack", rule.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_rules_from_bytes() {
+        use cfn_guard::parse_rules;
+
+        assert_eq!(
+            2,
+            parse_rules(b"rule a { Properties exists }\nrule b { a }").unwrap()
+        );
+        assert_eq!(0, parse_rules(b"# only a comment").unwrap());
+
+        for malformed in [
+            &b"rule broken {"[..],
+            &[0xff, 0xfe, b'{'],
+            "[".repeat(1_000_000).as_bytes(),
+            b"rule r { Properties.Tags[4294967296] exists }",
+        ] {
+            assert!(parse_rules(malformed).is_err());
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_run_check_async() {
+        use cfn_guard::*;
+        let data = r#"{ "Resources": { "bucket": { "Type": "AWS::S3::Bucket" } } }"#;
+        let rule = "AWS::S3::Bucket { Properties exists }";
+        let input = || {
+            (
+                ValidateInput {
+                    content: data,
+                    file_name: "functional_test.json",
+                },
+                ValidateInput {
+                    content: rule,
+                    file_name: "functional_test.rule",
+                },
+            )
+        };
+
+        let (data_input, rules_input) = input();
+        let expected = run_checks(data_input, rules_input, true).unwrap();
+        let (data_input, rules_input) = input();
+        let result = run_checks_async(data_input, rules_input, true)
+            .await
+            .unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_run_checks_on_files_async() {
+        let result = cfn_guard::run_checks_on_files_async(
+            "resources/validate/data-dir/s3-server-side-encryption-template-non-compliant.yaml",
+            "resources/validate/rules-dir/s3_bucket_server_side_encryption_enabled.guard",
+            true,
+        )
+        .await
+        .unwrap();
+        assert!(result.contains(r#""status": "FAIL""#));
+
+        let missing = cfn_guard::run_checks_on_files_async(
+            "resources/validate/data-dir/missing.yaml",
+            "resources/validate/rules-dir/s3_bucket_server_side_encryption_enabled.guard",
+            false,
+        )
+        .await;
+        assert!(matches!(missing, Err(cfn_guard::Error::IoError(_))));
+    }
 }
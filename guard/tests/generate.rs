@@ -0,0 +1,130 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod generate_tests {
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct GenerateTestRunner<'args> {
+        target: Option<&'args str>,
+        shell: Option<&'args str>,
+        output_dir: Option<&'args str>,
+    }
+
+    impl<'args> GenerateTestRunner<'args> {
+        fn target(&'args mut self, arg: &'args str) -> &'args mut GenerateTestRunner {
+            self.target = Some(arg);
+            self
+        }
+
+        fn shell(&'args mut self, arg: &'args str) -> &'args mut GenerateTestRunner {
+            self.shell = Some(arg);
+            self
+        }
+
+        fn output_dir(&'args mut self, arg: &'args str) -> &'args mut GenerateTestRunner {
+            self.output_dir = Some(arg);
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for GenerateTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Generate.to_string()];
+
+            if let Some(target) = self.target {
+                args.push(target.to_string());
+            }
+
+            if let Some(shell) = self.shell {
+                args.push(String::from("--shell"));
+                args.push(shell.to_string());
+            }
+
+            if let Some(output_dir) = self.output_dir {
+                args.push(String::from("--output-dir"));
+                args.push(output_dir.to_string());
+            }
+
+            args
+        }
+    }
+
+    #[test]
+    fn test_generate_completions() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = GenerateTestRunner::default()
+            .target("completions")
+            .shell("bash")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let completions = writer.stripped().unwrap();
+        assert!(completions.contains("_cfn-guard()"), "{completions}");
+        assert!(completions.contains("--message-catalog"), "{completions}");
+    }
+
+    #[test]
+    fn test_generate_spec() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = GenerateTestRunner::default()
+            .target("spec")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let spec: serde_json::Value = serde_json::from_str(&writer.stripped().unwrap()).unwrap();
+        assert_eq!("cfn-guard", spec["name"]);
+        let generate = spec["subcommands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|subcommand| subcommand["name"] == "generate")
+            .unwrap();
+        assert_eq!(
+            serde_json::json!(["completions", "man", "spec"]),
+            generate["args"][0]["possible_values"]
+        );
+    }
+
+    #[test]
+    fn test_generate_man_pages_to_output_dir() {
+        let output_dir = std::env::temp_dir().join("cfn-guard-generate-man-test");
+        std::fs::create_dir_all(&output_dir).unwrap();
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = GenerateTestRunner::default()
+            .target("man")
+            .output_dir(output_dir.to_str().unwrap())
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!("", writer.stripped().unwrap());
+
+        let page = std::fs::read_to_string(output_dir.join("cfn-guard-generate.1")).unwrap();
+        let top = std::fs::read_to_string(output_dir.join("cfn-guard.1")).unwrap();
+        std::fs::remove_dir_all(&output_dir).unwrap();
+        assert!(page.contains(".TH"), "{page}");
+        assert!(page.contains("completions"), "{page}");
+        assert!(top.contains("validate"), "{top}");
+    }
+
+    #[test]
+    fn test_generate_completions_without_shell() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = GenerateTestRunner::default()
+            .target("completions")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+}
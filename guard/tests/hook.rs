@@ -0,0 +1,137 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod hook_tests {
+    use cfn_guard::commands::CONFIG;
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct HookTestRunner<'args> {
+        config: Option<&'args str>,
+        files: Vec<&'args str>,
+    }
+
+    impl<'args> HookTestRunner<'args> {
+        fn config(&'args mut self, arg: &'args str) -> &'args mut HookTestRunner {
+            self.config = Some(arg);
+            self
+        }
+
+        fn files(&'args mut self, args: Vec<&'args str>) -> &'args mut HookTestRunner {
+            self.files = args;
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for HookTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Hook.to_string()];
+
+            if let Some(config) = self.config {
+                args.push(format!("-{}", CONFIG.1));
+                args.push(config.to_string());
+            }
+
+            args.extend(self.files.iter().map(|file| file.to_string()));
+
+            args
+        }
+    }
+
+    #[test]
+    fn test_hook_reports_each_file_against_its_rule_sets() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = HookTestRunner::default()
+            .config("resources/hook/cfn-guard-hook.yaml")
+            .files(vec![
+                "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+                "resources/validate/data-dir/s3-server-side-encryption-template-compliant.yaml",
+                "README.md",
+            ])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert_eq!(
+            indoc! {r#"
+                resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml: FAIL [s3]
+                  ../validate/rules-dir/s3_bucket_logging_enabled.guard/S3_BUCKET_LOGGING_ENABLED: Violation: S3 Bucket Logging needs to be configured to enable logging.
+                  ../validate/warning-rules.guard/s3_public_access_block (warning): Buckets should block public access
+                resources/validate/data-dir/s3-server-side-encryption-template-compliant.yaml: FAIL [s3]
+                  ../validate/rules-dir/s3_bucket_logging_enabled.guard/S3_BUCKET_LOGGING_ENABLED: Violation: S3 Bucket Logging needs to be configured to enable logging.
+                resources/validate/data-dir/s3-server-side-encryption-template-compliant.yaml: FAIL [encryption]
+                  ../validate/rules-dir/advanced_regex_negative_lookbehind_rule.guard/default
+                  ../validate/rules-dir/s3_bucket_logging_enabled.guard/S3_BUCKET_LOGGING_ENABLED: Violation: S3 Bucket Logging needs to be configured to enable logging.
+                2 file(s) matched the rule sets, 2 failed
+            "#},
+            writer.stripped().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hook_reports_files_that_cannot_be_parsed() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = HookTestRunner::default()
+            .config("resources/hook/cfn-guard-hook.yaml")
+            .files(vec!["resources/validate/malformed-template.yaml"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+        assert!(writer
+            .stripped()
+            .unwrap()
+            .starts_with("resources/validate/malformed-template.yaml: ERROR [s3]\n"));
+    }
+
+    #[test]
+    fn test_hook_reports_rules_files_that_cannot_be_parsed() {
+        let mut reader = Reader::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = HookTestRunner::default()
+            .config("resources/hook/unparsable-rules.yaml")
+            .files(vec!["resources/validate/blank.yaml"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+        assert!(writer.err_to_stripped().unwrap().starts_with(
+            "[unparsable] unable to parse rules file ../validate/unparsable-rule.guard"
+        ));
+    }
+
+    #[test]
+    fn test_hook_ignores_files_no_rule_set_matches() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = HookTestRunner::default()
+            .config("resources/hook/cfn-guard-hook.yaml")
+            .files(vec!["README.md"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!(
+            "No files matched the rule sets in resources/hook/cfn-guard-hook.yaml\n",
+            writer.stripped().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hook_missing_config() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = HookTestRunner::default()
+            .config("resources/hook/dne.yaml")
+            .files(vec!["README.md"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+}
@@ -19,6 +19,8 @@ mod parse_tree_tests {
         output: Option<&'args str>,
         print_json: bool,
         print_yaml: bool,
+        query: Option<&'args str>,
+        format: Option<&'args str>,
     }
 
     impl<'args> ParseTreeTestRunner<'args> {
@@ -43,6 +45,16 @@ mod parse_tree_tests {
             self.print_json = true;
             self
         }
+
+        fn query(&'args mut self, arg: &'args str) -> &'args mut ParseTreeTestRunner {
+            self.query = Some(arg);
+            self
+        }
+
+        fn format(&'args mut self, arg: &'args str) -> &'args mut ParseTreeTestRunner {
+            self.format = Some(arg);
+            self
+        }
     }
 
     impl<'args> CommandTestRunner for ParseTreeTestRunner<'args> {
@@ -61,6 +73,16 @@ mod parse_tree_tests {
                 args.push(format!("--{}", PRINT_JSON.0));
             }
 
+            if let Some(query) = self.query {
+                args.push(String::from("--query"));
+                args.push(query.to_string());
+            }
+
+            if let Some(format) = self.format {
+                args.push(String::from("--format"));
+                args.push(format.to_string());
+            }
+
             args
         }
     }
@@ -178,4 +200,57 @@ mod parse_tree_tests {
         assert_eq!(expected_status_code, status_code);
         assert_output_from_file_eq!(expected_writer_output, writer)
     }
+
+    #[test]
+    fn test_query_rule() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ParseTreeTestRunner::default()
+            .rules("validate/rules-dir/s3_bucket_logging_enabled.guard")
+            .query("S3_BUCKET_LOGGING_ENABLED")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let tree = writer.stripped().unwrap();
+        assert!(
+            tree.starts_with("rule_name: S3_BUCKET_LOGGING_ENABLED\n"),
+            "{tree}"
+        );
+        assert!(!tree.contains("guard_rules"), "{tree}");
+    }
+
+    #[test]
+    fn test_query_missing_rule() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let status_code = ParseTreeTestRunner::default()
+            .rules("validate/rules-dir/s3_bucket_logging_enabled.guard")
+            .query("S3_BUCKET_VERSIONING_ENABLED")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(writer
+            .err_to_stripped()
+            .unwrap()
+            .contains("no rule named S3_BUCKET_VERSIONING_ENABLED"));
+    }
+
+    #[test]
+    fn test_dot_output() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ParseTreeTestRunner::default()
+            .rules("validate/rules-dir/s3_bucket_logging_enabled.guard")
+            .format("dot")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let digraph = writer.stripped().unwrap();
+        assert!(digraph.starts_with("digraph rules {\n"), "{digraph}");
+        assert!(
+            digraph.contains("[label=\"rule S3_BUCKET_LOGGING_ENABLED\", shape=box, style=bold];"),
+            "{digraph}"
+        );
+        assert!(digraph.ends_with("}\n"), "{digraph}");
+    }
 }
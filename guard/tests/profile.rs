@@ -0,0 +1,139 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod profile_tests {
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct ProfileTestRunner<'args> {
+        rules: Vec<&'args str>,
+        data: Vec<&'args str>,
+        output_format: Option<&'args str>,
+        top: Option<&'args str>,
+    }
+
+    impl<'args> ProfileTestRunner<'args> {
+        fn rules(&'args mut self, arg: &'args str) -> &'args mut ProfileTestRunner {
+            self.rules.push(arg);
+            self
+        }
+
+        fn data(&'args mut self, arg: &'args str) -> &'args mut ProfileTestRunner {
+            self.data.push(arg);
+            self
+        }
+
+        fn output_format(&'args mut self, arg: &'args str) -> &'args mut ProfileTestRunner {
+            self.output_format = Some(arg);
+            self
+        }
+
+        fn top(&'args mut self, arg: &'args str) -> &'args mut ProfileTestRunner {
+            self.top = Some(arg);
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for ProfileTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Profile.to_string()];
+
+            for rules in &self.rules {
+                args.push(String::from("--rules"));
+                args.push(get_full_path_for_resource_file(rules));
+            }
+
+            for data in &self.data {
+                args.push(String::from("--data"));
+                args.push(get_full_path_for_resource_file(data));
+            }
+
+            if let Some(output_format) = self.output_format {
+                args.push(String::from("--output-format"));
+                args.push(output_format.to_string());
+            }
+
+            if let Some(top) = self.top {
+                args.push(String::from("--top"));
+                args.push(top.to_string());
+            }
+
+            args
+        }
+    }
+
+    #[test]
+    fn test_sorted_profile() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ProfileTestRunner::default()
+            .rules("resources/validate/rules-dir/s3_bucket_public_read_prohibited.guard")
+            .data("resources/validate/data-dir/s3-public-read-prohibited-template-compliant.yaml")
+            .data(
+                "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            )
+            .top("0")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(output.starts_with("Own time"));
+
+        // the rule and the variable are evaluated once for each data file
+        let calls = |frame: &str| {
+            output
+                .lines()
+                .find(|line| line.ends_with(frame))
+                .map(|line| line.split_whitespace().nth(2).unwrap().to_string())
+        };
+        assert_eq!(
+            Some(String::from("2")),
+            calls("rule S3_BUCKET_PUBLIC_READ_PROHIBITED")
+        );
+        assert_eq!(
+            Some(String::from("2")),
+            calls("let s3_bucket_public_read_prohibited")
+        );
+    }
+
+    #[test]
+    fn test_folded_profile() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ProfileTestRunner::default()
+            .rules("resources/validate/rules-dir/s3_bucket_public_read_prohibited.guard")
+            .data("resources/validate/data-dir/s3-public-read-prohibited-template-compliant.yaml")
+            .output_format("folded")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        for line in writer.stripped().unwrap().lines() {
+            let (stack, micros) = line.rsplit_once(' ').unwrap();
+            assert!(stack.contains("s3_bucket_public_read_prohibited.guard;"));
+            assert!(micros.parse::<u128>().unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn test_profile_rules_that_fail_to_evaluate() {
+        let mut reader = Reader::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ProfileTestRunner::default()
+            .rules("resources/validate/malformed-rule.guard")
+            .data("resources/validate/data-dir/s3-public-read-prohibited-template-compliant.yaml")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+        assert!(writer
+            .err_to_stripped()
+            .unwrap()
+            .contains("malformed-rule.guard"));
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod results_diff_tests {
+    use crate::assert_output_from_file_eq;
+    use cfn_guard::commands::PRINT_JSON;
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct ResultsDiffTestRunner<'args> {
+        before: &'args str,
+        after: &'args str,
+        print_json: bool,
+    }
+
+    impl<'args> CommandTestRunner for ResultsDiffTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Diff.to_string()];
+
+            args.push(String::from("--before"));
+            args.push(get_full_path_for_resource_file(self.before));
+            args.push(String::from("--after"));
+            args.push(get_full_path_for_resource_file(self.after));
+
+            if self.print_json {
+                args.push(format!("-{}", PRINT_JSON.1));
+            }
+
+            args
+        }
+    }
+
+    #[test]
+    fn test_results_diff_new_violations() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ResultsDiffTestRunner {
+            before: "resources/diff/before.json",
+            after: "resources/diff/after.json",
+            print_json: false,
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert_output_from_file_eq!("resources/diff/output-dir/diff.out", writer)
+    }
+
+    #[rstest::rstest]
+    #[case(
+        "resources/diff/after.json",
+        "resources/diff/before.json",
+        StatusCode::VALIDATION_ERROR
+    )]
+    #[case(
+        "resources/diff/before.json",
+        "resources/diff/before.json",
+        StatusCode::SUCCESS
+    )]
+    fn test_results_diff_status_code(
+        #[case] before: &str,
+        #[case] after: &str,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ResultsDiffTestRunner {
+            before,
+            after,
+            print_json: true,
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[test]
+    fn test_results_diff_not_a_report() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ResultsDiffTestRunner {
+            before: "resources/diff/before.json",
+            after: "resources/rules-diff/old/s3.guard",
+            print_json: false,
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+}
@@ -5,7 +5,7 @@ pub(crate) mod utils;
 #[cfg(test)]
 mod rulegen_tests {
     use crate::assert_output_from_file_eq;
-    use cfn_guard::commands::{OUTPUT, TEMPLATE};
+    use cfn_guard::commands::{FROM_CONFIG_RULE, OUTPUT, TEMPLATE};
     use cfn_guard::utils::reader::Reader;
     use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
     use pretty_assertions::assert_eq;
@@ -16,6 +16,7 @@ mod rulegen_tests {
     struct RulegenTestRunner<'args> {
         template: Option<&'args str>,
         output: Option<&'args str>,
+        from_config_rule: Option<&'args str>,
     }
 
     impl<'args> RulegenTestRunner<'args> {
@@ -24,6 +25,11 @@ mod rulegen_tests {
             self
         }
 
+        fn config_rule(&'args mut self, arg: Option<&'args str>) -> &'args mut RulegenTestRunner {
+            self.from_config_rule = arg;
+            self
+        }
+
         #[allow(dead_code)]
         fn output(&'args mut self, arg: Option<&'args str>) -> &'args mut RulegenTestRunner {
             self.output = arg;
@@ -45,6 +51,11 @@ mod rulegen_tests {
                 args.push(get_full_path_for_resource_file(self.output.unwrap()))
             }
 
+            if let Some(from_config_rule) = self.from_config_rule {
+                args.push(format!("--{}", FROM_CONFIG_RULE.0));
+                args.push(from_config_rule.to_string());
+            }
+
             args
         }
     }
@@ -74,4 +85,41 @@ mod rulegen_tests {
         assert_eq!(expected_status_code, status_code);
         assert_output_from_file_eq!(expected_output_file_path, writer)
     }
+
+    #[rstest::rstest]
+    #[case(
+        "s3-bucket-server-side-encryption-enabled",
+        "resources/rulegen/output-dir/test_rulegen_from_config_rule.out",
+        StatusCode::SUCCESS
+    )]
+    #[case(
+        "S3_BUCKET_SERVER_SIDE_ENCRYPTION_ENABLED",
+        "resources/rulegen/output-dir/test_rulegen_from_config_rule.out",
+        StatusCode::SUCCESS
+    )]
+    fn test_rulegen_from_config_rule(
+        #[case] config_rule_arg: &str,
+        #[case] expected_output_file_path: &str,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = RulegenTestRunner::default()
+            .config_rule(Some(config_rule_arg))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+        assert_output_from_file_eq!(expected_output_file_path, writer)
+    }
+
+    #[test]
+    fn test_rulegen_from_unknown_config_rule() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = RulegenTestRunner::default()
+            .config_rule(Some("not-a-managed-rule"))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
 }
@@ -0,0 +1,90 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod rules_diff_tests {
+    use crate::assert_output_from_file_eq;
+    use cfn_guard::commands::PRINT_JSON;
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct RulesDiffTestRunner<'args> {
+        old: &'args str,
+        new: &'args str,
+        print_json: bool,
+    }
+
+    impl<'args> RulesDiffTestRunner<'args> {
+        fn old_rules(&'args mut self, arg: &'args str) -> &'args mut RulesDiffTestRunner {
+            self.old = arg;
+            self
+        }
+
+        fn new_rules(&'args mut self, arg: &'args str) -> &'args mut RulesDiffTestRunner {
+            self.new = arg;
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for RulesDiffTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Rules.to_string(), String::from("diff")];
+
+            args.push(get_full_path_for_resource_file(self.old));
+            args.push(get_full_path_for_resource_file(self.new));
+
+            if self.print_json {
+                args.push(format!("-{}", PRINT_JSON.1));
+            }
+
+            args
+        }
+    }
+
+    #[rstest::rstest]
+    #[case(false, "resources/rules-diff/output-dir/diff.out")]
+    #[case(true, "resources/rules-diff/output-dir/diff.json")]
+    fn test_rules_diff(#[case] print_json: bool, #[case] expected_output_file_path: &str) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = RulesDiffTestRunner {
+            old: "resources/rules-diff/old",
+            new: "resources/rules-diff/new",
+            print_json,
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_output_from_file_eq!(expected_output_file_path, writer)
+    }
+
+    #[test]
+    fn test_rules_diff_no_differences() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = RulesDiffTestRunner::default()
+            .old_rules("resources/rules-diff/old/ec2.guard")
+            .new_rules("resources/rules-diff/new/ec2.guard")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!("No differences found\n", writer.stripped().unwrap());
+    }
+
+    #[test]
+    fn test_rules_diff_missing_directory() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = RulesDiffTestRunner::default()
+            .old_rules("resources/rules-diff/old")
+            .new_rules("resources/rules-diff/dne")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+}
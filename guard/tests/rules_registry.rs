@@ -0,0 +1,108 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod rules_registry_tests {
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    const REGISTRY: &str = "resources/registry/index.yaml";
+
+    struct ListTestRunner;
+
+    impl CommandTestRunner for ListTestRunner {
+        fn build_args(&self) -> Vec<String> {
+            vec![
+                Command::Rules.to_string(),
+                String::from("list"),
+                String::from("--registry"),
+                get_full_path_for_resource_file(REGISTRY),
+            ]
+        }
+    }
+
+    struct PullTestRunner<'args> {
+        pack: &'args str,
+        output_dir: String,
+    }
+
+    impl<'args> CommandTestRunner for PullTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            vec![
+                Command::Rules.to_string(),
+                String::from("pull"),
+                self.pack.to_string(),
+                String::from("--registry"),
+                get_full_path_for_resource_file(REGISTRY),
+                String::from("--output-dir"),
+                self.output_dir.clone(),
+            ]
+        }
+    }
+
+    /// pulls the pack into a directory of its own, removed once pulled
+    fn pull(pack: &str, name: &str) -> (i32, String, Vec<String>) {
+        let dir = std::env::temp_dir().join(name);
+        let mut reader = Reader::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = PullTestRunner {
+            pack,
+            output_dir: dir.to_str().unwrap().to_string(),
+        }
+        .run(&mut writer, &mut reader);
+
+        let mut files = match std::fs::read_dir(dir.join(pack.replace('@', "/"))) {
+            Ok(entries) => entries
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                .collect::<Vec<_>>(),
+            Err(_) => vec![],
+        };
+        files.sort();
+        let _ = std::fs::remove_dir_all(dir);
+
+        (status_code, writer.err_to_stripped().unwrap(), files)
+    }
+
+    #[test]
+    fn test_rules_list() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ListTestRunner.run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!(
+            "s3-baseline 1.0.0, 1.1.0\n    Versioning, encryption and public access checks for S3 buckets\ntampered 0.1.0\n",
+            writer.stripped().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rules_pull() {
+        let (status_code, _, files) = pull("s3-baseline@1.1.0", "cfn-guard-pull-test");
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!(vec!["s3-public-access.guard", "s3.guard"], files);
+    }
+
+    #[test]
+    fn test_rules_pull_unknown_version() {
+        let (status_code, err, _) = pull("s3-baseline@2.0.0", "cfn-guard-pull-version-test");
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(err.contains("the versions are 1.0.0, 1.1.0"));
+    }
+
+    #[test]
+    fn test_rules_pull_checksum_mismatch() {
+        let (status_code, err, files) = pull("tampered@0.1.0", "cfn-guard-pull-checksum-test");
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(err.contains("the registry expects"));
+        assert!(files.is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod sign_tests {
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct SignTestRunner<'args> {
+        pack: Option<&'args str>,
+        key: Option<&'args str>,
+        output: Option<&'args str>,
+    }
+
+    impl<'args> SignTestRunner<'args> {
+        fn pack(&'args mut self, arg: &'args str) -> &'args mut SignTestRunner {
+            self.pack = Some(arg);
+            self
+        }
+
+        fn key(&'args mut self, arg: &'args str) -> &'args mut SignTestRunner {
+            self.key = Some(arg);
+            self
+        }
+
+        fn output(&'args mut self, arg: &'args str) -> &'args mut SignTestRunner {
+            self.output = Some(arg);
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for SignTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Sign.to_string()];
+
+            if let Some(pack) = self.pack {
+                args.push(String::from("--pack"));
+                args.push(pack.to_string());
+            }
+
+            if let Some(key) = self.key {
+                args.push(String::from("--key"));
+                args.push(get_full_path_for_resource_file(key));
+            }
+
+            if let Some(output) = self.output {
+                args.push(String::from("--output"));
+                args.push(output.to_string());
+            }
+
+            args
+        }
+    }
+
+    /// builds the rules into the pack, not a resource file
+    struct BuildPackRunner<'args> {
+        rules: &'args str,
+        pack: &'args str,
+    }
+
+    impl<'args> CommandTestRunner for BuildPackRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            vec![
+                Command::Build.to_string(),
+                String::from("--rules"),
+                get_full_path_for_resource_file(self.rules),
+                String::from("--output"),
+                self.pack.to_string(),
+            ]
+        }
+    }
+
+    /// validates the data against the rules, checking their signatures with the public key
+    struct ValidateSignedRunner<'args> {
+        rules: String,
+        data: &'args str,
+        public_key: &'args str,
+    }
+
+    impl<'args> CommandTestRunner for ValidateSignedRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            vec![
+                Command::Validate.to_string(),
+                String::from("--rules"),
+                self.rules.clone(),
+                String::from("--data"),
+                get_full_path_for_resource_file(self.data),
+                String::from("--verify-signature"),
+                get_full_path_for_resource_file(self.public_key),
+            ]
+        }
+    }
+
+    /// a rules pack of the rules directory in a directory of its own, removed with the directory
+    fn build_pack(name: &str) -> (std::path::PathBuf, String) {
+        let dir = std::env::temp_dir().join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let pack = dir.join("rules.pack").to_str().unwrap().to_string();
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = BuildPackRunner {
+            rules: "resources/validate/rules-dir",
+            pack: &pack,
+        }
+        .run(&mut writer, &mut reader);
+        assert_eq!(StatusCode::SUCCESS, status_code);
+
+        (dir, pack)
+    }
+
+    fn validate_signed(pack: &str, public_key: &str) -> (i32, String) {
+        let mut reader = Reader::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateSignedRunner {
+            rules: pack.to_string(),
+            data: "resources/validate/data-dir",
+            public_key,
+        }
+        .run(&mut writer, &mut reader);
+
+        (status_code, writer.err_to_stripped().unwrap())
+    }
+
+    #[test]
+    fn test_validate_against_signed_pack() {
+        let (dir, pack) = build_pack("cfn-guard-sign-test");
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = SignTestRunner::default()
+            .pack(&pack)
+            .key("resources/sign/private.pem")
+            .run(&mut writer, &mut reader);
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert!(std::path::Path::new(&format!("{pack}.sig")).exists());
+
+        let (status_code, _) = validate_signed(&pack, "resources/sign/public.pem");
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+
+        let (status_code, err) = validate_signed(&pack, "resources/sign/other-public.pem");
+        std::fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(err.contains("was not signed with the private key of the public key"));
+    }
+
+    #[test]
+    fn test_validate_against_modified_or_unsigned_pack() {
+        let (dir, pack) = build_pack("cfn-guard-sign-modified-test");
+
+        let (status_code, err) = validate_signed(&pack, "resources/sign/public.pem");
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(err.contains("has no signature"));
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let signature = dir.join("signature").to_str().unwrap().to_string();
+        let status_code = SignTestRunner::default()
+            .pack(&pack)
+            .key("resources/sign/private.pem")
+            .output(&signature)
+            .run(&mut writer, &mut reader);
+        assert_eq!(StatusCode::SUCCESS, status_code);
+
+        std::fs::rename(&signature, format!("{pack}.sig")).unwrap();
+        let mut content = std::fs::read(&pack).unwrap();
+        content.push(b'\n');
+        std::fs::write(&pack, content).unwrap();
+
+        let (status_code, err) = validate_signed(&pack, "resources/sign/public.pem");
+        std::fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(err.contains("or was modified since"));
+    }
+
+    #[test]
+    fn test_validate_rules_files_with_verify_signature() {
+        let (status_code, err) = validate_signed(
+            &get_full_path_for_resource_file("resources/validate/rules-dir"),
+            "resources/sign/public.pem",
+        );
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert!(err.contains("only signed rules packs are evaluated with --verify-signature"));
+    }
+}
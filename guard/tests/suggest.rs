@@ -0,0 +1,78 @@
+// Copyright Amazon Web Services, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+pub(crate) mod utils;
+
+#[cfg(test)]
+mod suggest_tests {
+    use crate::assert_output_from_file_eq;
+    use cfn_guard::commands::{GOOD, TYPE};
+    use cfn_guard::utils::reader::Reader;
+    use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::{get_full_path_for_resource_file, Command, CommandTestRunner, StatusCode};
+
+    #[derive(Default)]
+    struct SuggestTestRunner<'args> {
+        good: Option<&'args str>,
+        resource_type: Option<&'args str>,
+    }
+
+    impl<'args> SuggestTestRunner<'args> {
+        fn good(&'args mut self, arg: &'args str) -> &'args mut SuggestTestRunner {
+            self.good = Some(arg);
+            self
+        }
+
+        fn resource_type(&'args mut self, arg: &'args str) -> &'args mut SuggestTestRunner {
+            self.resource_type = Some(arg);
+            self
+        }
+    }
+
+    impl<'args> CommandTestRunner for SuggestTestRunner<'args> {
+        fn build_args(&self) -> Vec<String> {
+            let mut args = vec![Command::Suggest.to_string()];
+
+            if let Some(good) = self.good {
+                args.push(format!("-{}", GOOD.1));
+                args.push(get_full_path_for_resource_file(good));
+            }
+
+            if let Some(resource_type) = self.resource_type {
+                args.push(format!("-{}", TYPE.1));
+                args.push(resource_type.to_string());
+            }
+
+            args
+        }
+    }
+
+    #[test]
+    fn test_suggest_from_template() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = SuggestTestRunner::default()
+            .good("resources/suggest/data-dir/rds-template-compliant.yaml")
+            .resource_type("AWS::RDS::DBInstance")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_output_from_file_eq!(
+            "resources/suggest/output-dir/test_suggest_from_template.out",
+            writer
+        )
+    }
+
+    #[test]
+    fn test_suggest_for_missing_resource_type() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = SuggestTestRunner::default()
+            .good("resources/suggest/data-dir/rds-template-compliant.yaml")
+            .resource_type("AWS::EC2::Volume")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+}
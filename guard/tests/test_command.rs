@@ -9,8 +9,8 @@ mod test_command_tests {
 
     use crate::assert_output_from_file_eq;
     use cfn_guard::commands::{
-        ALPHABETICAL, DIRECTORY, LAST_MODIFIED, OUTPUT_FORMAT, RULES_AND_TEST_FILE, RULES_FILE,
-        TEST_DATA, VERBOSE,
+        ALPHABETICAL, DIRECTORY, INIT, LAST_MODIFIED, OUTPUT_FORMAT, RULES_AND_TEST_FILE,
+        RULES_FILE, TEST_DATA, VERBOSE,
     };
     use cfn_guard::utils::reader::Reader;
     use cfn_guard::utils::writer::{WriteBuffer::Vec as WBVec, Writer};
@@ -29,6 +29,9 @@ mod test_command_tests {
         alphabetical: bool,
         last_modified: bool,
         verbose: bool,
+        init: bool,
+        mutate: bool,
+        output_schema: bool,
     }
 
     impl<'args> TestCommandTestRunner<'args> {
@@ -82,6 +85,21 @@ mod test_command_tests {
             self.output_format = Some(args);
             self
         }
+
+        fn init(&'args mut self) -> &'args mut TestCommandTestRunner {
+            self.init = true;
+            self
+        }
+
+        fn mutate(&'args mut self) -> &'args mut TestCommandTestRunner {
+            self.mutate = true;
+            self
+        }
+
+        fn output_schema(&'args mut self) -> &'args mut TestCommandTestRunner {
+            self.output_schema = true;
+            self
+        }
     }
 
     impl<'args> CommandTestRunner for TestCommandTestRunner<'args> {
@@ -125,6 +143,18 @@ mod test_command_tests {
                 args.push(String::from(output_format));
             }
 
+            if self.init {
+                args.push(format!("--{}", INIT.0));
+            }
+
+            if self.mutate {
+                args.push(String::from("--mutate"));
+            }
+
+            if self.output_schema {
+                args.push(String::from("--output-schema"));
+            }
+
             args
         }
     }
@@ -179,6 +209,88 @@ mod test_command_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_init_generates_passing_test_skeleton() {
+        let rules = "resources/validate/rules-dir/s3_bucket_server_side_encryption_enabled.guard";
+        let skeleton =
+            "resources/test-command/init/s3_bucket_server_side_encryption_enabled_tests.yaml";
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .rules(Some(rules))
+            .init()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_output_from_file_eq!(skeleton, writer);
+
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .rules(Some(rules))
+            .test_data(Some(skeleton))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+    }
+
+    #[rstest]
+    #[case(
+        "s3_bucket_encryption_tests.yaml",
+        StatusCode::SUCCESS,
+        "3 of 3 mutant(s) killed, mutation score 100%"
+    )]
+    #[case(
+        "s3_bucket_encryption_pass_only_tests.yaml",
+        StatusCode::TEST_COMMAND_FAILURE,
+        "2 of 3 mutant(s) killed, mutation score 66%"
+    )]
+    fn test_mutate(
+        #[case] test_data: &str,
+        #[case] expected_status_code: i32,
+        #[case] summary: &str,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .rules(Some(
+                "resources/test-command/mutate/s3_bucket_encryption.guard",
+            ))
+            .test_data(Some(&format!("resources/test-command/mutate/{test_data}")))
+            .mutate()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(output.contains(summary), "{output}");
+        assert_eq!(
+            expected_status_code != StatusCode::SUCCESS,
+            output.contains("SURVIVED s3_bucket_encryption: widened"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_mutate_fails_when_tests_fail_against_the_rules() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .rules(Some(
+                "resources/test-command/mutate/s3_bucket_encryption.guard",
+            ))
+            .test_data(Some(
+                "resources/test-command/mutate/s3_bucket_encryption_failing_tests.yaml",
+            ))
+            .mutate()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INCORRECT_STATUS_ERROR, status_code);
+        assert!(writer
+            .stripped()
+            .unwrap()
+            .contains("fix them before mutating the rules"));
+    }
+
     #[test]
     fn test_parse_error_when_guard_rule_has_syntax_error() {
         let mut reader = Reader::default();
@@ -200,6 +312,33 @@ mod test_command_tests {
         assert_eq!(expected_err_msg, writer.stripped().unwrap());
     }
 
+    #[test]
+    fn test_parse_error_location_in_json_output() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        TestCommandTestRunner::default()
+            .test_data(Some("resources/test-command/data-dir/test.yaml"))
+            .rules(Some("resources/test-command/rule-dir/invalid_rule.guard"))
+            .output_format("json")
+            .run(&mut writer, &mut reader);
+
+        let output =
+            serde_json::from_str::<serde_json::Value>(&writer.stripped().unwrap()).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "file": "resources/test-command/rule-dir/invalid_rule.guard",
+                "line": 8,
+                "column": 46,
+                "expected": [
+                    r#"either a property access "engine.core" or value like "string" or ["this", "that"]"#
+                ],
+                "context": r#"expecting either a property access "engine.core" or value like "string" or ["this", "that"]"#,
+                "fragment": r#" {"Fn::ImportValue":/{"Fn::Sub":"${pSecretKmsKey}"}}"#,
+            }),
+            output["location"]
+        );
+    }
+
     #[test]
     fn test_parse_error_when_file_dne() {
         let mut reader = Reader::default();
@@ -259,6 +398,78 @@ mod test_command_tests {
         );
     }
 
+    #[test]
+    fn test_with_manifest_dir() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .directory(Option::from("resources/test-command/manifest"))
+            .directory_only()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let output = writer.stripped().unwrap();
+        for mapping in [
+            "Testing mapping logging, 1 rules file(s) against 1 test file(s)",
+            "Testing mapping encryption, 1 rules file(s) against 1 test file(s)",
+            "Testing mapping rules, 2 rules file(s) against 1 test file(s)",
+        ] {
+            assert!(output.contains(mapping), "{output}");
+        }
+    }
+
+    #[test]
+    fn test_output_schema() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .output_schema()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let schema =
+            serde_json::from_str::<serde_json::Value>(&writer.stripped().unwrap()).unwrap();
+        assert_eq!(
+            cfn_guard::OUTPUT_SCHEMA_VERSION,
+            schema["$defs"]["SchemaVersion"]["const"]
+        );
+    }
+
+    #[test]
+    fn test_with_manifest_dir_structured() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .directory(Option::from("resources/test-command/manifest"))
+            .directory_only()
+            .output_format("json")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let output =
+            serde_json::from_str::<serde_json::Value>(&writer.stripped().unwrap()).unwrap();
+        let mappings = output
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|mapping| {
+                (
+                    mapping["mapping"].as_str().unwrap(),
+                    mapping["results"].as_array().unwrap().len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("logging", 1), ("encryption", 1), ("rules", 2)],
+            mappings
+        );
+        assert!(output
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|mapping| mapping["schema_version"] == cfn_guard::OUTPUT_SCHEMA_VERSION));
+    }
+
     #[rstest]
     #[case("json")]
     #[case("yaml")]
@@ -348,6 +559,33 @@ mod test_command_tests {
         assert_output_from_file_eq!("resources/test-command/output-dir/functions.out", writer);
     }
 
+    #[test]
+    fn test_data_file_with_base_and_matrix() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = TestCommandTestRunner::default()
+            .test_data(Option::from(
+                "resources/test-command/data-dir/s3_bucket_server_side_encryption_enabled_matrix.yaml",
+            ))
+            .rules(Some(
+                "resources/validate/rules-dir/s3_bucket_server_side_encryption_enabled.guard",
+            ))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let output = writer.stripped().unwrap();
+        for name in [
+            "Name: encryption [algorithm=AES256]",
+            "Name: encryption [algorithm=aws:kms]",
+            "Name: unsupported encryption DES",
+            "Name: unsupported encryption none",
+            "Name: no encryption",
+            "Name: no buckets",
+        ] {
+            assert!(output.contains(name), "{output}");
+        }
+    }
+
     #[test]
     fn test_with_failure() {
         let mut reader = Reader::default();
@@ -25,6 +25,16 @@ pub enum Command {
     Validate,
     Test,
     Rulegen,
+    Suggest,
+    Rules,
+    Hook,
+    Conformance,
+    Docs,
+    Generate,
+    Diff,
+    Profile,
+    Build,
+    Sign,
 }
 
 impl std::fmt::Display for Command {
@@ -37,6 +47,16 @@ impl std::fmt::Display for Command {
                 Command::Validate => "validate",
                 Command::Test => "test",
                 Command::Rulegen => "rulegen",
+                Command::Suggest => "suggest",
+                Command::Rules => "rules",
+                Command::Hook => "hook",
+                Command::Conformance => "conformance",
+                Command::Docs => "docs",
+                Command::Generate => "generate",
+                Command::Diff => "diff",
+                Command::Profile => "profile",
+                Command::Build => "build",
+                Command::Sign => "sign",
             }
         )
     }
@@ -54,6 +74,7 @@ impl StatusCode {
     pub const VALIDATION_ERROR: i32 = 19;
 }
 
+#[allow(dead_code)]
 pub fn read_from_resource_file(path: &str) -> String {
     let mut resource = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     resource.push(path);
@@ -90,6 +111,7 @@ pub fn sanitize_sarif_writer(writer: Writer) -> Writer {
     writer
 }
 
+#[allow(dead_code)]
 pub fn get_full_path_for_resource_file(path: &str) -> String {
     let path = if cfg!(windows) {
         path.replace('/', r#"\"#)
@@ -133,6 +155,7 @@ pub fn sanitize_path(string_to_sanitize: String) -> String {
     replace_path_with_filenames(replaced_home_directory)
 }
 
+#[allow(dead_code)]
 pub fn compare_write_buffer_with_file(
     expected_output_relative_file_path: &str,
     actual_output_writer: Writer,
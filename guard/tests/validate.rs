@@ -8,8 +8,8 @@ mod validate_tests {
     use std::io::Cursor;
 
     use cfn_guard::commands::{
-        ALPHABETICAL, DATA, INPUT_PARAMETERS, LAST_MODIFIED, OUTPUT_FORMAT, PAYLOAD, PRINT_JSON,
-        RULES, SHOW_SUMMARY, STRUCTURED, VERBOSE,
+        ALPHABETICAL, DATA, FAIL_ON, INPUT_PARAMETERS, LAST_MODIFIED, OUTPUT_FILE, OUTPUT_FORMAT,
+        PAYLOAD, PRINT_JSON, RULES, SHOW_SUMMARY, STRUCTURED, TYPE, VERBOSE,
     };
     use cfn_guard::utils::reader::ReadBuffer::Cursor as ReadCursor;
     use cfn_guard::utils::reader::Reader;
@@ -24,15 +24,42 @@ mod validate_tests {
     struct ValidateTestRunner<'args> {
         data: Vec<&'args str>,
         rules: Vec<&'args str>,
+        global_rules: Vec<&'args str>,
         show_summary: Vec<&'args str>,
         input_parameters: Vec<&'args str>,
         output_format: Option<&'args str>,
+        output_file: Option<&'args str>,
+        reports: Vec<&'args str>,
+        fail_on: Option<&'args str>,
+        tags: Vec<&'args str>,
+        exclude_tags: Vec<&'args str>,
+        rule_names: Vec<&'args str>,
+        skip_rules: Vec<&'args str>,
+        resource_selectors: Vec<&'args str>,
+        continue_on_rule_error: bool,
+        vars: Option<&'args str>,
+        baseline: Option<&'args str>,
+        config: Option<&'args str>,
+        message_catalog: Option<&'args str>,
+        template_type: Option<&'args str>,
+        update_baseline: bool,
+        parameters_file_suffix: Option<&'args str>,
+        resolve_parameters: bool,
+        evaluate_conditions: bool,
+        resolve_json_refs: bool,
         alphabetical: bool,
         last_modified: bool,
+        max_depth: Option<&'args str>,
+        follow_symlinks: bool,
+        timings: bool,
+        quiet: bool,
+        no_color: bool,
         verbose: bool,
         print_json: bool,
         payload: bool,
         structured: bool,
+        output_schema: bool,
+        output_version: Option<&'args str>,
     }
 
     impl<'args> ValidateTestRunner<'args> {
@@ -67,6 +94,105 @@ mod validate_tests {
             self
         }
 
+        fn output_file(&'args mut self, arg: &'args str) -> &'args mut ValidateTestRunner {
+            self.output_file = Some(arg);
+            self
+        }
+
+        fn report(&'args mut self, arg: &'args str) -> &'args mut ValidateTestRunner {
+            self.reports.push(arg);
+            self
+        }
+
+        fn fail_on(&'args mut self, arg: &'args str) -> &'args mut ValidateTestRunner {
+            self.fail_on = Some(arg);
+            self
+        }
+
+        fn tags(&'args mut self, args: Vec<&'args str>) -> &'args mut ValidateTestRunner {
+            self.tags = args;
+            self
+        }
+
+        fn exclude_tags(&'args mut self, args: Vec<&'args str>) -> &'args mut ValidateTestRunner {
+            self.exclude_tags = args;
+            self
+        }
+
+        fn rule_names(&'args mut self, args: Vec<&'args str>) -> &'args mut ValidateTestRunner {
+            self.rule_names = args;
+            self
+        }
+
+        fn skip_rules(&'args mut self, args: Vec<&'args str>) -> &'args mut ValidateTestRunner {
+            self.skip_rules = args;
+            self
+        }
+
+        fn resource_selectors(
+            &'args mut self,
+            args: Vec<&'args str>,
+        ) -> &'args mut ValidateTestRunner {
+            self.resource_selectors = args;
+            self
+        }
+
+        fn continue_on_rule_error(&'args mut self) -> &'args mut ValidateTestRunner {
+            self.continue_on_rule_error = true;
+            self
+        }
+
+        fn resolve_parameters(&'args mut self, arg: bool) -> &'args mut ValidateTestRunner {
+            self.resolve_parameters = arg;
+            self
+        }
+
+        fn evaluate_conditions(&'args mut self, arg: bool) -> &'args mut ValidateTestRunner {
+            self.evaluate_conditions = arg;
+            self
+        }
+
+        fn resolve_json_refs(&'args mut self, arg: bool) -> &'args mut ValidateTestRunner {
+            self.resolve_json_refs = arg;
+            self
+        }
+
+        fn vars(&'args mut self, arg: Option<&'args str>) -> &'args mut ValidateTestRunner {
+            self.vars = arg;
+            self
+        }
+
+        fn baseline(&'args mut self, arg: &'args str) -> &'args mut ValidateTestRunner {
+            self.baseline = Some(arg);
+            self
+        }
+
+        fn template_type(
+            &'args mut self,
+            arg: Option<&'args str>,
+        ) -> &'args mut ValidateTestRunner {
+            self.template_type = arg;
+            self
+        }
+
+        fn config(&'args mut self, arg: &'args str) -> &'args mut ValidateTestRunner {
+            self.config = Some(arg);
+            self
+        }
+
+        fn message_catalog(&'args mut self, arg: &'args str) -> &'args mut ValidateTestRunner {
+            self.message_catalog = Some(arg);
+            self
+        }
+
+        fn parameters_file_suffix(
+            &'args mut self,
+            arg: Option<&'args str>,
+        ) -> &'args mut ValidateTestRunner {
+            self.parameters_file_suffix = arg;
+            self
+        }
+
         fn payload(&'args mut self) -> &'args mut ValidateTestRunner {
             self.payload = true;
             self
@@ -84,6 +210,31 @@ mod validate_tests {
             self
         }
 
+        fn max_depth(&'args mut self, arg: Option<&'args str>) -> &'args mut ValidateTestRunner {
+            self.max_depth = arg;
+            self
+        }
+
+        fn follow_symlinks(&'args mut self, arg: bool) -> &'args mut ValidateTestRunner {
+            self.follow_symlinks = arg;
+            self
+        }
+
+        fn timings(&'args mut self) -> &'args mut ValidateTestRunner {
+            self.timings = true;
+            self
+        }
+
+        fn quiet(&'args mut self) -> &'args mut ValidateTestRunner {
+            self.quiet = true;
+            self
+        }
+
+        fn no_color(&'args mut self) -> &'args mut ValidateTestRunner {
+            self.no_color = true;
+            self
+        }
+
         fn verbose(&'args mut self) -> &'args mut ValidateTestRunner {
             self.verbose = true;
             self
@@ -99,6 +250,11 @@ mod validate_tests {
             self.structured = true;
             self
         }
+
+        fn output_schema(&'args mut self) -> &'args mut ValidateTestRunner {
+            self.output_schema = true;
+            self
+        }
     }
 
     impl<'args> CommandTestRunner for ValidateTestRunner<'args> {
@@ -109,7 +265,10 @@ mod validate_tests {
                 args.push(format!("-{}", DATA.1));
 
                 for data_arg in &self.data {
-                    args.push(get_path_for_resource_file(data_arg));
+                    match *data_arg {
+                        "-" => args.push(data_arg.to_string()),
+                        _ => args.push(get_path_for_resource_file(data_arg)),
+                    }
                 }
             }
 
@@ -121,6 +280,11 @@ mod validate_tests {
                 }
             }
 
+            for global_rules_arg in &self.global_rules {
+                args.push(String::from("--global-rules"));
+                args.push(get_path_for_resource_file(global_rules_arg));
+            }
+
             if !self.input_parameters.is_empty() {
                 args.push(format!("-{}", INPUT_PARAMETERS.1));
 
@@ -139,6 +303,96 @@ mod validate_tests {
                 args.push(String::from(output_format));
             }
 
+            for report in &self.reports {
+                args.push(format!("-{}", OUTPUT_FORMAT.1));
+                args.push(String::from(*report));
+            }
+
+            if let Some(output_file) = self.output_file {
+                args.push(format!("-{}", OUTPUT_FILE.1));
+                args.push(String::from(output_file));
+            }
+
+            if let Some(fail_on) = self.fail_on {
+                args.push(format!("-{}", FAIL_ON.1));
+                args.push(String::from(fail_on));
+            }
+
+            if !self.tags.is_empty() {
+                args.push(String::from("--tags"));
+                args.push(self.tags.join(","));
+            }
+
+            if !self.exclude_tags.is_empty() {
+                args.push(String::from("--exclude-tags"));
+                args.push(self.exclude_tags.join(","));
+            }
+
+            if !self.rule_names.is_empty() {
+                args.push(String::from("--rule-names"));
+                args.push(self.rule_names.join(","));
+            }
+
+            if !self.skip_rules.is_empty() {
+                args.push(String::from("--skip-rules"));
+                args.push(self.skip_rules.join(","));
+            }
+
+            for selector in &self.resource_selectors {
+                args.push(String::from("--resource-selector"));
+                args.push(selector.to_string());
+            }
+
+            if self.continue_on_rule_error {
+                args.push(String::from("--continue-on-rule-error"));
+            }
+
+            if let Some(vars) = self.vars {
+                args.push(String::from("--vars"));
+                args.push(get_path_for_resource_file(vars));
+            }
+
+            if let Some(template_type) = self.template_type {
+                args.push(format!("-{}", TYPE.1));
+                args.push(String::from(template_type));
+            }
+
+            if let Some(config) = self.config {
+                args.push(String::from("--config"));
+                args.push(get_path_for_resource_file(config));
+            }
+
+            if let Some(message_catalog) = self.message_catalog {
+                args.push(String::from("--message-catalog"));
+                args.push(get_path_for_resource_file(message_catalog));
+            }
+
+            if let Some(baseline) = self.baseline {
+                args.push(String::from("--baseline"));
+                args.push(String::from(baseline));
+            }
+
+            if self.update_baseline {
+                args.push(String::from("--update-baseline"));
+            }
+
+            if let Some(suffix) = self.parameters_file_suffix {
+                args.push(String::from("--parameters-file-suffix"));
+                args.push(suffix.to_string());
+            }
+
+            if self.resolve_parameters {
+                args.push(String::from("--resolve-parameters"));
+            }
+
+            if self.evaluate_conditions {
+                args.push(String::from("--evaluate-conditions"));
+            }
+
+            if self.resolve_json_refs {
+                args.push(String::from("--resolve-json-refs"));
+            }
+
             if self.alphabetical {
                 args.push(format!("-{}", ALPHABETICAL.1));
             }
@@ -147,6 +401,27 @@ mod validate_tests {
                 args.push(format!("-{}", LAST_MODIFIED.1));
             }
 
+            if let Some(max_depth) = self.max_depth {
+                args.push(String::from("--max-depth"));
+                args.push(max_depth.to_string());
+            }
+
+            if self.follow_symlinks {
+                args.push(String::from("--follow-symlinks"));
+            }
+
+            if self.timings {
+                args.push(String::from("--timings"));
+            }
+
+            if self.quiet {
+                args.push(String::from("--quiet"));
+            }
+
+            if self.no_color {
+                args.push(String::from("--no-color"));
+            }
+
             if self.verbose {
                 args.push(format!("-{}", VERBOSE.1));
             }
@@ -163,6 +438,15 @@ mod validate_tests {
                 args.push(format!("-{}", STRUCTURED.1));
             }
 
+            if self.output_schema {
+                args.push(String::from("--output-schema"));
+            }
+
+            if let Some(output_version) = self.output_version {
+                args.push(String::from("--output-version"));
+                args.push(String::from(output_version));
+            }
+
             args
         }
     }
@@ -201,6 +485,8 @@ mod validate_tests {
     #[case(vec!["blank.yaml"], vec!["rules-dir/s3_bucket_public_read_prohibited.guard"], StatusCode::INTERNAL_FAILURE)]
     #[case(vec!["s3-server-side-encryption-template-non-compliant-2.yaml"], vec!["comments.guard"], StatusCode::SUCCESS)]
     #[case(vec!["s3-server-side-encryption-template-non-compliant-2.yaml"], vec!["comments.guard"], StatusCode::SUCCESS)]
+    #[case(vec!["policy-builtins-template-compliant.yaml"], vec!["policy_builtins.guard"], StatusCode::SUCCESS)]
+    #[case(vec!["policy-builtins-template-non-compliant.yaml"], vec!["policy_builtins.guard"], StatusCode::VALIDATION_ERROR)]
     fn test_single_data_file_single_rules_file_status(
         #[case] data_arg: Vec<&str>,
         #[case] rules_arg: Vec<&str>,
@@ -217,308 +503,1185 @@ mod validate_tests {
     }
 
     #[rstest::rstest]
-    #[case("SSEAlgorithm: {{CRASH}}")]
-    #[case("~:")]
-    #[case("[1, 2, 3]: foo")]
-    #[case("1: foo")]
-    #[case("1.0: foo")]
-    fn test_graceful_handling_when_yaml_file_has_non_string_type_key(#[case] input: &str) {
-        let bytes = input.as_bytes();
-        let mut reader = Reader::new(ReadCursor(Cursor::new(bytes.to_vec())));
-        let mut writer = Writer::default();
-
+    #[case(
+        "violations",
+        "rules-dir/s3_bucket_public_read_prohibited.guard",
+        StatusCode::VALIDATION_ERROR
+    )]
+    #[case(
+        "warnings",
+        "rules-dir/s3_bucket_public_read_prohibited.guard",
+        StatusCode::VALIDATION_ERROR
+    )]
+    #[case(
+        "errors",
+        "rules-dir/s3_bucket_public_read_prohibited.guard",
+        StatusCode::SUCCESS
+    )]
+    #[case(
+        "none",
+        "rules-dir/s3_bucket_public_read_prohibited.guard",
+        StatusCode::SUCCESS
+    )]
+    #[case("violations", "unparsable-rule.guard", StatusCode::PARSING_ERROR)]
+    #[case("errors", "unparsable-rule.guard", StatusCode::PARSING_ERROR)]
+    #[case("none", "unparsable-rule.guard", StatusCode::SUCCESS)]
+    fn test_fail_on(#[case] fail_on: &str, #[case] rules: &str, #[case] expected_status_code: i32) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
         let status_code = ValidateTestRunner::default()
-            .rules(vec!["s3_bucket_server_side_encryption_enabled_2.guard"])
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .rules(vec![rules])
+            .output_format(Option::from("json"))
+            .structured()
+            .show_summary(vec!["none"])
+            .fail_on(fail_on)
             .run(&mut writer, &mut reader);
 
-        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+        assert_eq!(expected_status_code, status_code);
     }
 
-    #[test]
-    fn test_single_data_file_single_rules_file_compliant() {
+    #[rstest::rstest]
+    #[case(vec![], vec![], StatusCode::VALIDATION_ERROR)]
+    #[case(vec!["cis-2.1.1", "cis-2.1.3"], vec![], StatusCode::SUCCESS)]
+    #[case(vec!["s3"], vec![], StatusCode::VALIDATION_ERROR)]
+    #[case(vec!["s3"], vec!["experimental"], StatusCode::SUCCESS)]
+    #[case(vec![], vec!["experimental"], StatusCode::SUCCESS)]
+    #[case(vec!["unknown"], vec![], StatusCode::SUCCESS)]
+    fn test_rule_selection_by_tags(
+        #[case] tags: Vec<&str>,
+        #[case] exclude_tags: Vec<&str>,
+        #[case] expected_status_code: i32,
+    ) {
         let mut reader = Reader::default();
-        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
         let status_code = ValidateTestRunner::default()
             .data(vec![
-                "data-dir/s3-public-read-prohibited-template-compliant.yaml",
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
             ])
-            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
-            .show_summary(vec!["all"])
+            .rules(vec!["tagged-rules.guard"])
+            .tags(tags)
+            .exclude_tags(exclude_tags)
             .run(&mut writer, &mut reader);
 
-        let expected_output = indoc! {
-            r#"s3-public-read-prohibited-template-compliant.yaml Status = PASS
-               PASS rules
-               s3_bucket_public_read_prohibited.guard/S3_BUCKET_PUBLIC_READ_PROHIBITED    PASS
-               ---
-               "#
-        };
-
-        assert_eq!(StatusCode::SUCCESS, status_code);
-        assert_output_from_str_eq!(expected_output, writer)
+        assert_eq!(expected_status_code, status_code);
     }
 
     #[rstest::rstest]
-    #[case(
-        vec!["data-dir/s3-public-read-prohibited-template-compliant.yaml"],
-        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"],
-        "resources/validate/output-dir/test_single_data_file_single_rules_file_verbose_compliant.out",
-        StatusCode::SUCCESS
-    )]
-    #[case(
-        vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml"],
-        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"],
-        "resources/validate/output-dir/test_single_data_file_single_rules_file_verbose_non_compliant.out",
-        StatusCode::VALIDATION_ERROR
-    )]
-    #[case(
-        vec!["template_where_resources_isnt_root.json"],
-        vec!["workshop.guard"],
-        "resources/validate/output-dir/failing_template_without_resources_at_root.out",
-        StatusCode::VALIDATION_ERROR
-    )]
-    #[case(
-        vec!["failing_template_with_slash_in_key.yaml"],
-        vec!["rules-dir/s3_bucket_server_side_encryption_enabled.guard"],
-        "resources/validate/output-dir/failing_template_with_slash_in_key.out",
-        StatusCode::VALIDATION_ERROR
-    )]
-    fn test_single_data_file_single_rules_file_verbose(
-        #[case] data_arg: Vec<&str>,
-        #[case] rules_arg: Vec<&str>,
-        #[case] expected_output: &str,
-        #[case] expected_status_code: i32,
-    ) {
-        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+    // the second pod has no limits, generic data only reads the first document
+    #[case(None, StatusCode::VALIDATION_ERROR)]
+    #[case(Some("k8s"), StatusCode::VALIDATION_ERROR)]
+    #[case(Some("generic"), StatusCode::SUCCESS)]
+    fn test_template_type(#[case] template_type: Option<&str>, #[case] expected_status_code: i32) {
         let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
         let status_code = ValidateTestRunner::default()
-            .data(data_arg)
-            .rules(rules_arg)
-            .show_summary(vec!["all"])
-            .verbose()
+            .rules(vec!["k8s/container-limits.guard"])
+            .data(vec!["k8s/pods.yaml"])
+            .template_type(template_type)
             .run(&mut writer, &mut reader);
 
         assert_eq!(expected_status_code, status_code);
-        assert_output_from_file_eq!(expected_output, writer)
     }
 
     #[rstest::rstest]
-    #[case(
-        vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml"],
-        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"],
-        "resources/validate/output-dir/test_single_data_file_single_rules_file_verbose.out",
-        StatusCode::VALIDATION_ERROR
-    )]
-    #[case(
-        vec!["data-dir/advanced_regex_negative_lookbehind_non_compliant.yaml"],
-        vec!["rules-dir/advanced_regex_negative_lookbehind_rule.guard"],
-        "resources/validate/output-dir/advanced_regex_negative_lookbehind_non_compliant.out",
-        StatusCode::VALIDATION_ERROR
-    )]
-    #[case(
-        vec!["data-dir/advanced_regex_negative_lookbehind_compliant.yaml"],
-        vec!["rules-dir/advanced_regex_negative_lookbehind_rule.guard"],
-        "resources/validate/output-dir/advanced_regex_negative_lookbehind_compliant.out",
-        StatusCode::SUCCESS
-    )]
-    fn test_single_data_file_single_rules_file(
-        #[case] data_arg: Vec<&str>,
-        #[case] rules_arg: Vec<&str>,
-        #[case] expected_output: &str,
-        #[case] expected_status_code: i32,
-    ) {
+    // the config only selects s3_encryption, which the template passes
+    #[case(vec![], StatusCode::SUCCESS)]
+    #[case(vec!["experimental"], StatusCode::VALIDATION_ERROR)]
+    fn test_config_file(#[case] tags: Vec<&str>, #[case] expected_status_code: i32) {
         let mut reader = Reader::default();
-        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
         let status_code = ValidateTestRunner::default()
-            .data(data_arg)
-            .rules(rules_arg)
-            .show_summary(vec!["all"])
+            .config("config/cfn-guard.toml")
+            .tags(tags)
             .run(&mut writer, &mut reader);
 
         assert_eq!(expected_status_code, status_code);
-        assert_output_from_file_eq!(expected_output, writer)
     }
 
     #[rstest::rstest]
-    #[case(
-    vec!["data-dir/s3-server-side-encryption-template-compliant.yaml", "data-dir/s3-public-read-prohibited-template-compliant.yaml"],
-    vec!["rules-dir/s3_bucket_public_read_prohibited.guard"]
-    )]
-    #[case(
-    vec!["data-dir/s3-public-read-prohibited-template-compliant.yaml"],
-    vec!["rules-dir/s3_bucket_public_read_prohibited.guard", "rules-dir/s3_bucket_public_read_prohibited.guard"]
-    )]
-    fn test_different_combinations_of_rules_and_data(
-        #[case] data_arg: Vec<&str>,
-        #[case] rules_arg: Vec<&str>,
+    #[case(vec!["s3_public_access_block"], vec![], StatusCode::VALIDATION_ERROR)]
+    #[case(vec!["s3_encryption", "s3_versioning"], vec![], StatusCode::SUCCESS)]
+    #[case(vec![], vec!["s3_public_access_block"], StatusCode::SUCCESS)]
+    #[case(vec!["s3_encryption", "s3_public_access_block"], vec!["s3_public_access_block"], StatusCode::SUCCESS)]
+    fn test_rule_selection_by_name(
+        #[case] rule_names: Vec<&str>,
+        #[case] skip_rules: Vec<&str>,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .rules(vec!["tagged-rules.guard"])
+            .rule_names(rule_names)
+            .skip_rules(skip_rules)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[rstest::rstest]
+    #[case("violations", None, StatusCode::SUCCESS)]
+    #[case("violations", Some("junit"), StatusCode::SUCCESS)]
+    #[case("violations", Some("sarif"), StatusCode::SUCCESS)]
+    #[case("warnings", None, StatusCode::VALIDATION_ERROR)]
+    #[case("warnings", Some("json"), StatusCode::VALIDATION_ERROR)]
+    fn test_warning_rules(
+        #[case] fail_on: &str,
+        #[case] output_format: Option<&str>,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .rules(vec!["warning-rules.guard"])
+            .output_format(output_format)
+            .fail_on(fail_on)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+
+        let output = writer.stripped().unwrap();
+        match output_format {
+            None => assert!(output.contains("WARNING rules")),
+            Some("junit") => assert!(output.contains(r#"status="skip""#)),
+            Some("sarif") => assert!(output.contains(r#""level": "warning""#)),
+            _ => assert!(output.contains(r#""severity": "warning""#)),
+        }
+    }
+
+    #[test]
+    fn test_timings() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .show_summary(vec!["none"])
+            .timings()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+
+        let timings = writer.err_to_stripped().unwrap();
+        let lines = timings.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("Data file"));
+        assert!(lines[1].contains("s3-public-read-prohibited-template-non-compliant.yaml"));
+        assert!(lines[3].starts_with("Rule"));
+        assert!(lines[4].starts_with("S3_BUCKET_PUBLIC_READ_PROHIBITED"));
+    }
+
+    #[test]
+    fn test_quiet() {
+        let run = || {
+            let mut reader = Reader::default();
+            let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+            let status_code = ValidateTestRunner::default()
+                .data(vec![
+                    "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+                ])
+                .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+                .timings()
+                .quiet()
+                .run(&mut writer, &mut reader);
+            (status_code, writer)
+        };
+
+        let (status_code, writer) = run();
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert!(writer.is_quiet());
+        assert_eq!("", writer.stripped().unwrap());
+
+        // the failures are still summarized, only to a console that is kept quiet
+        let (_, mut writer) = run();
+        writer.write_info(String::from("discarded")).unwrap();
+        assert_eq!("", writer.err_to_stripped().unwrap());
+    }
+
+    #[test]
+    fn test_message_catalog_is_kept_to_its_run() {
+        let run = |message_catalog: Option<&str>| {
+            let mut reader = Reader::default();
+            let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+            let mut runner = ValidateTestRunner::default();
+            let runner = runner
+                .data(vec![
+                    "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+                ])
+                .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+                .output_format(Some("json"))
+                .structured();
+            let status_code = match message_catalog {
+                Some(message_catalog) => runner
+                    .message_catalog(message_catalog)
+                    .run(&mut writer, &mut reader),
+                None => runner.run(&mut writer, &mut reader),
+            };
+            (status_code, writer.stripped().unwrap())
+        };
+
+        let (status_code, output) = run(Some("message-catalog.yaml"));
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert!(output.contains("REWORDED"), "{output}");
+        assert!(!output.contains("Check was not compliant"), "{output}");
+
+        // a run without the catalog is back to the English wording
+        let (status_code, output) = run(None);
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert!(!output.contains("REWORDED"), "{output}");
+        assert!(output.contains("Check was not compliant"), "{output}");
+    }
+
+    #[test]
+    fn test_no_color() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .show_summary(vec!["all"])
+            .no_color()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+
+        let output = writer.into_string().unwrap();
+        assert!(output.contains("FAIL"));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[rstest::rstest]
+    #[case("csv", "data_file,rule,resource,status,message")]
+    #[case("tsv", "data_file\trule\tresource\tstatus\tmessage")]
+    fn test_delimited_output(#[case] output_format: &str, #[case] header: &str) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .output_format(Some(output_format))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+
+        let output = writer.stripped().unwrap();
+        let mut lines = output.lines();
+        assert_eq!(Some(header), lines.next());
+        assert!(lines.any(|line| line.contains("FAIL")));
+    }
+
+    #[rstest::rstest]
+    #[case(false)]
+    #[case(true)]
+    fn test_rule_file_parse_errors_are_reported_together(#[case] continue_on_rule_error: bool) {
+        let run = || {
+            let mut reader = Reader::default();
+            let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+            let mut runner = ValidateTestRunner::default();
+            let runner = runner
+                .data(vec![
+                    "s3-server-side-encryption-template-non-compliant-2.yaml",
+                ])
+                .rules(vec![
+                    "unparsable-rule.guard",
+                    "s3_bucket_server_side_encryption_enabled_2.guard",
+                    "unparsable-rule-2.guard",
+                ]);
+            let status_code = match continue_on_rule_error {
+                true => runner
+                    .continue_on_rule_error()
+                    .run(&mut writer, &mut reader),
+                false => runner.run(&mut writer, &mut reader),
+            };
+            (status_code, writer)
+        };
+
+        // a failing rule does not hide the rules files that failed to parse
+        let (status_code, writer) = run();
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+
+        let errors = writer.err_to_stripped().unwrap();
+        assert!(errors.contains("rule file = unparsable-rule.guard"));
+        assert!(errors.contains("rule file = unparsable-rule-2.guard"));
+        assert!(errors.contains("2 of 3 rules files failed to parse"));
+
+        let output = run().1.stripped().unwrap();
+        assert_eq!(
+            continue_on_rule_error,
+            output.contains("s3_bucket_server_side_encryption_enabled_2.guard")
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(false, 0)]
+    #[case(true, 1)]
+    fn test_rule_file_parse_errors_in_structured_output(
+        #[case] continue_on_rule_error: bool,
+        #[case] evaluated_rules: usize,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let mut runner = ValidateTestRunner::default();
+        let runner = runner
+            .data(vec![
+                "s3-server-side-encryption-template-non-compliant-2.yaml",
+            ])
+            .rules(vec![
+                "unparsable-rule.guard",
+                "s3_bucket_server_side_encryption_enabled_2.guard",
+            ])
+            .show_summary(vec!["none"])
+            .output_format(Some("json"))
+            .structured();
+        let status_code = match continue_on_rule_error {
+            true => runner
+                .continue_on_rule_error()
+                .run(&mut writer, &mut reader),
+            false => runner.run(&mut writer, &mut reader),
+        };
+
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+
+        let output =
+            serde_json::from_str::<serde_json::Value>(&writer.stripped().unwrap()).unwrap();
+        assert_eq!(output[0]["name"], "unparsable-rule.guard");
+        assert_eq!(output[0]["status"], "ERROR");
+        assert!(output[0]["error"].as_str().unwrap().contains("line 3"));
+        assert_eq!(output[0]["location"]["file"], "unparsable-rule.guard");
+        assert_eq!(output[0]["location"]["line"], 3);
+        assert_eq!(
+            output[1]["not_compliant"].as_array().unwrap().len(),
+            evaluated_rules
+        );
+    }
+
+    #[test]
+    fn test_rule_file_parse_error_location_with_json_output() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "s3-server-side-encryption-template-non-compliant-2.yaml",
+            ])
+            .rules(vec!["unparsable-rule.guard"])
+            .output_format(Some("json"))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+
+        let errors = writer.err_to_stripped().unwrap();
+        let location =
+            serde_json::from_str::<serde_json::Value>(errors.lines().next().unwrap()).unwrap();
+        assert_eq!(location["file"], "unparsable-rule.guard");
+        assert_eq!(location["line"], 3);
+        assert!(location["expected"].as_array().is_some());
+    }
+
+    #[test]
+    fn test_rule_file_reports_every_syntax_error() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "s3-server-side-encryption-template-non-compliant-2.yaml",
+            ])
+            .rules(vec!["rules-with-syntax-errors.guard"])
+            .output_format(Some("json"))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::PARSING_ERROR, status_code);
+
+        let errors = writer.err_to_stripped().unwrap();
+        let lines = errors
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .map(|location| location["line"].as_u64().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![2, 10], lines);
+        assert!(errors.contains("1 of 1 rules files failed to parse"));
+    }
+
+    #[rstest::rstest]
+    #[case(vec![], StatusCode::VALIDATION_ERROR)]
+    #[case(vec!["EncryptedBucket"], StatusCode::SUCCESS)]
+    #[case(vec!["Unencrypted*"], StatusCode::VALIDATION_ERROR)]
+    #[case(vec!["Type==AWS::EC2::*"], StatusCode::SUCCESS)]
+    #[case(vec!["Type==AWS::EC2::*", "*Bucket"], StatusCode::VALIDATION_ERROR)]
+    fn test_resource_selector(
+        #[case] resource_selectors: Vec<&str>,
+        #[case] expected_status_code: i32,
     ) {
         let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).unwrap();
+        let status_code = ValidateTestRunner::default()
+            .data(vec!["resource-selector-template.yaml"])
+            .rules(vec![
+                "rules-dir/s3_bucket_server_side_encryption_enabled.guard",
+            ])
+            .resource_selectors(resource_selectors)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[rstest::rstest]
+    #[case("SSEAlgorithm: {{CRASH}}")]
+    #[case("~:")]
+    #[case("[1, 2, 3]: foo")]
+    #[case("1: foo")]
+    #[case("1.0: foo")]
+    fn test_graceful_handling_when_yaml_file_has_non_string_type_key(#[case] input: &str) {
+        let bytes = input.as_bytes();
+        let mut reader = Reader::new(ReadCursor(Cursor::new(bytes.to_vec())));
         let mut writer = Writer::default();
+
         let status_code = ValidateTestRunner::default()
-            .data(data_arg)
-            .rules(rules_arg)
+            .rules(vec!["s3_bucket_server_side_encryption_enabled_2.guard"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+
+    #[test]
+    fn test_single_data_file_single_rules_file_compliant() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-compliant.yaml",
+            ])
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .show_summary(vec!["all"])
             .run(&mut writer, &mut reader);
 
+        let expected_output = indoc! {
+            r#"s3-public-read-prohibited-template-compliant.yaml Status = PASS
+               PASS rules
+               s3_bucket_public_read_prohibited.guard/S3_BUCKET_PUBLIC_READ_PROHIBITED    PASS
+               ---
+               "#
+        };
+
         assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_output_from_str_eq!(expected_output, writer)
     }
 
     #[rstest::rstest]
-    #[case(vec!["data-dir/"], vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])]
-    #[case(vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml"], vec!["rules-dir/"])]
-    #[case(vec!["data-dir/"], vec!["rules-dir/"])]
     #[case(
-        vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml", "data-dir/s3-public-read-prohibited-template-compliant.yaml"],
-        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"]
+        vec!["data-dir/s3-public-read-prohibited-template-compliant.yaml"],
+        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"],
+        "resources/validate/output-dir/test_single_data_file_single_rules_file_verbose_compliant.out",
+        StatusCode::SUCCESS
     )]
     #[case(
         vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml"],
-        vec!["rules-dir/s3_bucket_public_read_prohibited.guard", "rules-dir/s3_bucket_server_side_encryption_enabled.guard"]
+        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"],
+        "resources/validate/output-dir/test_single_data_file_single_rules_file_verbose_non_compliant.out",
+        StatusCode::VALIDATION_ERROR
     )]
     #[case(
-        vec!["data-dir/", "s3-server-side-encryption-template-non-compliant-2.yaml"],
-        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"]
+        vec!["template_where_resources_isnt_root.json"],
+        vec!["workshop.guard"],
+        "resources/validate/output-dir/failing_template_without_resources_at_root.out",
+        StatusCode::VALIDATION_ERROR
     )]
     #[case(
-        vec!["s3-server-side-encryption-template-non-compliant-2.yaml"],
-        vec!["rules-dir/", "s3_bucket_server_side_encryption_enabled_2.guard"]
+        vec!["failing_template_with_slash_in_key.yaml"],
+        vec!["rules-dir/s3_bucket_server_side_encryption_enabled.guard"],
+        "resources/validate/output-dir/failing_template_with_slash_in_key.out",
+        StatusCode::VALIDATION_ERROR
     )]
-    #[case(vec!["data-dir/"], vec!["rules-dir/", "s3_bucket_server_side_encryption_enabled_2.guard"])]
-    #[case(vec!["data-dir/", "s3-server-side-encryption-template-non-compliant-2.yaml"], vec!["rules-dir/"])]
+    fn test_single_data_file_single_rules_file_verbose(
+        #[case] data_arg: Vec<&str>,
+        #[case] rules_arg: Vec<&str>,
+        #[case] expected_output: &str,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let mut reader = Reader::default();
+        let status_code = ValidateTestRunner::default()
+            .data(data_arg)
+            .rules(rules_arg)
+            .show_summary(vec!["all"])
+            .verbose()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+        assert_output_from_file_eq!(expected_output, writer)
+    }
+
+    #[rstest::rstest]
     #[case(
-        vec!["data-dir/", "s3-server-side-encryption-template-non-compliant-2.yaml"],
-        vec!["rules-dir/", "s3_bucket_server_side_encryption_enabled_2.guard"]
+        vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml"],
+        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"],
+        "resources/validate/output-dir/test_single_data_file_single_rules_file_verbose.out",
+        StatusCode::VALIDATION_ERROR
     )]
-    fn test_combinations_of_rules_and_data_non_compliant(
+    #[case(
+        vec!["data-dir/advanced_regex_negative_lookbehind_non_compliant.yaml"],
+        vec!["rules-dir/advanced_regex_negative_lookbehind_rule.guard"],
+        "resources/validate/output-dir/advanced_regex_negative_lookbehind_non_compliant.out",
+        StatusCode::VALIDATION_ERROR
+    )]
+    #[case(
+        vec!["data-dir/advanced_regex_negative_lookbehind_compliant.yaml"],
+        vec!["rules-dir/advanced_regex_negative_lookbehind_rule.guard"],
+        "resources/validate/output-dir/advanced_regex_negative_lookbehind_compliant.out",
+        StatusCode::SUCCESS
+    )]
+    fn test_single_data_file_single_rules_file(
         #[case] data_arg: Vec<&str>,
         #[case] rules_arg: Vec<&str>,
+        #[case] expected_output: &str,
+        #[case] expected_status_code: i32,
     ) {
         let mut reader = Reader::default();
-        let mut writer = Writer::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .data(data_arg)
+            .rules(rules_arg)
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+        assert_output_from_file_eq!(expected_output, writer)
+    }
+
+    #[rstest::rstest]
+    #[case(
+    vec!["data-dir/s3-server-side-encryption-template-compliant.yaml", "data-dir/s3-public-read-prohibited-template-compliant.yaml"],
+    vec!["rules-dir/s3_bucket_public_read_prohibited.guard"]
+    )]
+    #[case(
+    vec!["data-dir/s3-public-read-prohibited-template-compliant.yaml"],
+    vec!["rules-dir/s3_bucket_public_read_prohibited.guard", "rules-dir/s3_bucket_public_read_prohibited.guard"]
+    )]
+    fn test_different_combinations_of_rules_and_data(
+        #[case] data_arg: Vec<&str>,
+        #[case] rules_arg: Vec<&str>,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::default();
+        let status_code = ValidateTestRunner::default()
+            .data(data_arg)
+            .rules(rules_arg)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+    }
+
+    #[rstest::rstest]
+    #[case(vec!["data-dir/"], vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])]
+    #[case(vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml"], vec!["rules-dir/"])]
+    #[case(vec!["data-dir/"], vec!["rules-dir/"])]
+    #[case(
+        vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml", "data-dir/s3-public-read-prohibited-template-compliant.yaml"],
+        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"]
+    )]
+    #[case(
+        vec!["data-dir/s3-public-read-prohibited-template-non-compliant.yaml"],
+        vec!["rules-dir/s3_bucket_public_read_prohibited.guard", "rules-dir/s3_bucket_server_side_encryption_enabled.guard"]
+    )]
+    #[case(
+        vec!["data-dir/", "s3-server-side-encryption-template-non-compliant-2.yaml"],
+        vec!["rules-dir/s3_bucket_public_read_prohibited.guard"]
+    )]
+    #[case(
+        vec!["s3-server-side-encryption-template-non-compliant-2.yaml"],
+        vec!["rules-dir/", "s3_bucket_server_side_encryption_enabled_2.guard"]
+    )]
+    #[case(vec!["data-dir/"], vec!["rules-dir/", "s3_bucket_server_side_encryption_enabled_2.guard"])]
+    #[case(vec!["data-dir/", "s3-server-side-encryption-template-non-compliant-2.yaml"], vec!["rules-dir/"])]
+    #[case(
+        vec!["data-dir/", "s3-server-side-encryption-template-non-compliant-2.yaml"],
+        vec!["rules-dir/", "s3_bucket_server_side_encryption_enabled_2.guard"]
+    )]
+    fn test_combinations_of_rules_and_data_non_compliant(
+        #[case] data_arg: Vec<&str>,
+        #[case] rules_arg: Vec<&str>,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::default();
+        let status_code = ValidateTestRunner::default()
+            .data(data_arg)
+            .rules(rules_arg)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+    }
+
+    #[test]
+    fn test_updated_summary_output() {
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let mut reader = Reader::default();
+        let status_code = ValidateTestRunner::default()
+            .data(vec!["data-dir"])
+            .rules(vec!["rules-dir"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/rules_dir_against_data_dir.out",
+            writer
+        )
+    }
+
+    #[rstest::rstest]
+    #[case(
+        vec!["db_resource.yaml"],
+        vec!["db_param_port_rule.guard"],
+        vec!["input-parameters-dir/db_params.yaml"],
+        StatusCode::VALIDATION_ERROR
+    )]
+    #[case(
+        vec!["db_resource.yaml"],
+        vec!["db_param_port_rule.guard"],
+        vec!["input-parameters-dir/db_params.yaml", "input-parameters-dir/db_metadata.yaml"],
+        StatusCode::SUCCESS
+    )]
+    #[case(
+        vec!["db_resource.yaml"],
+        vec!["db_param_port_rule.guard"],
+        vec!["input-parameters-dir/"],
+        StatusCode::SUCCESS
+    )]
+    #[case(
+        vec!["db_resource.yaml"],
+        vec!["db_param_port_rule.guard"],
+        vec!["input-parameters-dir/malformed-template.yaml"],
+        StatusCode::INTERNAL_FAILURE
+    )]
+    #[case(
+        vec!["db_resource.yaml"],
+        vec!["db_param_port_rule.guard"],
+        vec!["input-parameters-dir/blank-template.yaml"],
+        StatusCode::INTERNAL_FAILURE
+    )]
+    #[case(
+        vec!["db_resource.yaml"],
+        vec!["db_param_port_rule.guard"],
+        vec!["input-parameters-dir/blank-template.yaml", "input-parameters-dir/db_params.yaml"],
+        StatusCode::INTERNAL_FAILURE
+    )]
+    fn test_combinations_of_rules_data_and_input_params_files(
+        #[case] data_arg: Vec<&str>,
+        #[case] rules_arg: Vec<&str>,
+        #[case] input_params_arg: Vec<&str>,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::default();
+        let status_code = ValidateTestRunner::default()
+            .data(data_arg)
+            .rules(rules_arg)
+            .input_parameters(input_params_arg)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[test]
+    fn test_rules_with_data_from_stdin_yaml() {
+        let mut reader = utils::get_reader(
+            "resources/validate/data-dir/s3-server-side-encryption-template-compliant.yaml",
+        );
+        let mut writer = Writer::default();
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+    }
+
+    #[test]
+    fn test_rules_with_data_from_stdin_yaml_verbose() {
+        let mut reader = utils::get_reader(
+            "resources/validate/data-dir/s3-server-side-encryption-template-compliant.yaml",
+        );
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .verbose()
+            .run(&mut writer, &mut reader);
+
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/payload_verbose_success.out",
+            writer
+        );
+        assert_eq!(StatusCode::SUCCESS, status_code);
+    }
+
+    #[test]
+    fn test_rules_with_data_from_stdin_fail() {
+        let mut reader = utils::get_reader(
+            "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+        );
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .verbose()
+            .run(&mut writer, &mut reader);
+
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/payload_verbose_non_compliant.out",
+            writer
+        );
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+    }
+
+    #[rstest::rstest]
+    #[case(vec!["parameters-file"], None, StatusCode::VALIDATION_ERROR)]
+    #[case(vec!["parameters-file/stack.yaml"], None, StatusCode::VALIDATION_ERROR)]
+    #[case(vec!["parameters-file/other.yaml"], None, StatusCode::SUCCESS)]
+    #[case(vec!["parameters-file"], Some(""), StatusCode::SUCCESS)]
+    #[case(vec!["parameters-file"], Some(".missing.json"), StatusCode::SUCCESS)]
+    fn test_parameters_file_paired_with_data_file(
+        #[case] data_arg: Vec<&str>,
+        #[case] parameters_file_suffix: Option<&str>,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["parameters-file.guard"])
+            .data(data_arg)
+            .parameters_file_suffix(parameters_file_suffix)
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[rstest::rstest]
+    #[case(Some("vars/prod.yaml"), StatusCode::SUCCESS)]
+    #[case(Some("vars/dev.yaml"), StatusCode::VALIDATION_ERROR)]
+    fn test_vars_file(#[case] vars: Option<&str>, #[case] expected_status_code: i32) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["vars/allowed-bucket-names.guard"])
+            .data(vec!["vars/template.yaml"])
+            .vars(vars)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[rstest::rstest]
+    #[case("global/one-zone", StatusCode::SUCCESS)]
+    #[case("global/two-zones", StatusCode::VALIDATION_ERROR)]
+    fn test_global_rules(#[case] data_arg: &str, #[case] expected_status_code: i32) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner {
+            data: vec![data_arg],
+            global_rules: vec!["global/one-hosted-zone.guard"],
+            show_summary: vec!["all"],
+            ..Default::default()
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+        assert!(writer.stripped().unwrap().contains("GLOBAL Status = "));
+    }
+
+    #[test]
+    fn test_global_rules_structured() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner {
+            data: vec!["global/two-zones"],
+            global_rules: vec!["global/one-hosted-zone.guard"],
+            output_format: Some("json"),
+            show_summary: vec!["none"],
+            structured: true,
+            ..Default::default()
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        let reports: serde_json::Value = serde_json::from_str(&writer.stripped().unwrap()).unwrap();
+        let names = reports
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|report| report["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(3, names.len());
+        assert_eq!("GLOBAL", names[2]);
+        assert_eq!("FAIL", reports[2]["status"]);
+    }
+
+    #[test]
+    fn test_baseline() {
+        let temp_path = |name: &str| {
+            std::env::temp_dir()
+                .join(format!("cfn-guard-baseline-{}-{name}", std::process::id()))
+                .display()
+                .to_string()
+        };
+        let template = temp_path("template.yaml");
+        let baseline = temp_path("baseline.json");
+        let copy_template = |resource: &str| {
+            std::fs::copy(
+                get_full_path_for_resource_file(&format!("resources/validate/baseline/{resource}")),
+                &template,
+            )
+            .unwrap();
+        };
+        let validate = |writer: &mut Writer, update_baseline: bool| {
+            ValidateTestRunner {
+                rules: vec!["baseline/s3-encrypted.guard"],
+                data: vec![template.as_str()],
+                output_format: Some("json"),
+                show_summary: vec!["none"],
+                baseline: Some(baseline.as_str()),
+                update_baseline,
+                structured: true,
+                ..Default::default()
+            }
+            .run(writer, &mut Reader::default())
+        };
+
+        // the legacy bucket is accepted as it is
+        copy_template("legacy.yaml");
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        assert_eq!(StatusCode::SUCCESS, validate(&mut writer, true));
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&baseline).unwrap()).unwrap();
+        assert_eq!(1, written["violations"].as_array().unwrap().len());
+
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        assert_eq!(StatusCode::SUCCESS, validate(&mut writer, false));
+        let report: serde_json::Value = serde_json::from_str(&writer.stripped().unwrap()).unwrap();
+        assert_eq!(0, report[0]["not_compliant"].as_array().unwrap().len());
+        assert_eq!(1, report[0]["baselined"].as_array().unwrap().len());
+
+        // a new unencrypted bucket fails the rule again
+        copy_template("new-bucket.yaml");
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = validate(&mut writer, false);
+
+        std::fs::remove_file(&template).unwrap();
+        std::fs::remove_file(&baseline).unwrap();
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+    }
+
+    #[test]
+    fn test_baseline_without_structured_flag() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["baseline/s3-encrypted.guard"])
+            .data(vec!["baseline/legacy.yaml"])
+            .baseline("baseline.json")
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+
+    #[rstest::rstest]
+    #[case(vec!["guardignore"], vec!["generated-keep.yaml", "template.yaml"])]
+    #[case(vec!["guardignore/generated-stack.yaml"], vec!["generated-stack.yaml"])]
+    #[case(
+        vec!["guardignore/**/*.yaml"],
+        vec!["generated-keep.yaml", "template.yaml"]
+    )]
+    #[case(
+        vec!["data-dir/s3-public-read-*.yaml"],
+        vec![
+            "s3-public-read-prohibited-template-compliant.yaml",
+            "s3-public-read-prohibited-template-non-compliant.yaml"
+        ]
+    )]
+    fn test_data_discovery_with_globs_and_guardignore(
+        #[case] data_arg: Vec<&str>,
+        #[case] expected_files: Vec<&str>,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        ValidateTestRunner::default()
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .data(data_arg)
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        let evaluated = writer
+            .stripped()
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.split_once(" Status = "))
+            .map(|(file, _)| file.rsplit(['/', '\\']).next().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(expected_files, evaluated);
+    }
+
+    #[rstest::rstest]
+    #[case(None, false, vec!["deep.yaml", "top.yaml"])]
+    #[case(Some("1"), false, vec!["top.yaml"])]
+    #[case(Some("1"), true, vec!["top.yaml"])]
+    // linked/ is a link to nested/, deep.yaml is only evaluated once and the link nested/back to
+    // the walked directory is skipped
+    #[case(None, true, vec!["deep.yaml", "shared.yaml", "top.yaml"])]
+    #[case(Some("2"), true, vec!["shared.yaml", "top.yaml"])]
+    fn test_data_discovery_depth_and_symlinks(
+        #[case] max_depth: Option<&str>,
+        #[case] follow_symlinks: bool,
+        #[case] expected_files: Vec<&str>,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .data(vec!["walk"])
+            .max_depth(max_depth)
+            .follow_symlinks(follow_symlinks)
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let evaluated = writer
+            .stripped()
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.split_once(" Status = "))
+            .map(|(file, _)| file.rsplit(['/', '\\']).next().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(expected_files, evaluated);
+    }
+
+    #[test]
+    fn test_max_depth_of_zero() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .data(vec!["walk"])
+            .max_depth(Some("0"))
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+
+    #[test]
+    fn test_rules_discovery_with_globs() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["rules-dir/s3_bucket_*.guard"])
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(output.contains("s3_bucket_logging_enabled.guard/S3_BUCKET_LOGGING_ENABLED"));
+        assert!(!output.contains("advanced_regex_negative_lookbehind_rule.guard"));
+    }
+
+    #[test]
+    fn test_glob_without_matches() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["rules-dir"])
+            .data(vec!["data-dir/**/does-not-exist-*.yaml"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+
+    #[rstest::rstest]
+    // without resolving, the rules see {"Ref": "RetentionInDays"} rather than a number
+    #[case(false, vec![], StatusCode::VALIDATION_ERROR)]
+    #[case(true, vec![], StatusCode::SUCCESS)]
+    #[case(true, vec!["resolve-parameters/prod.json"], StatusCode::SUCCESS)]
+    #[case(
+        true,
+        vec!["resolve-parameters/prod-default-retention.json"],
+        StatusCode::VALIDATION_ERROR
+    )]
+    fn test_resolve_parameters(
+        #[case] resolve_parameters: bool,
+        #[case] input_params_arg: Vec<&str>,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["resolve-parameters.guard"])
+            .data(vec!["resolve-parameters/template.yaml"])
+            .input_parameters(input_params_arg)
+            .resolve_parameters(resolve_parameters)
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[rstest::rstest]
+    // the bucket only created in dev does not enable versioning
+    #[case(false, vec!["evaluate-conditions/prod.json"], StatusCode::VALIDATION_ERROR)]
+    #[case(true, vec![], StatusCode::VALIDATION_ERROR)]
+    #[case(true, vec!["evaluate-conditions/prod.json"], StatusCode::SUCCESS)]
+    fn test_evaluate_conditions(
+        #[case] evaluate_conditions: bool,
+        #[case] input_params_arg: Vec<&str>,
+        #[case] expected_status_code: i32,
+    ) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["evaluate-conditions.guard"])
+            .data(vec!["evaluate-conditions/template.yaml"])
+            .input_parameters(input_params_arg)
+            .evaluate_conditions(evaluate_conditions)
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
+    }
+
+    #[test]
+    fn test_evaluate_conditions_verbose() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
         let status_code = ValidateTestRunner::default()
-            .data(data_arg)
-            .rules(rules_arg)
+            .rules(vec!["evaluate-conditions.guard"])
+            .data(vec!["evaluate-conditions/template.yaml"])
+            .input_parameters(vec!["evaluate-conditions/prod.json"])
+            .evaluate_conditions(true)
+            .verbose()
             .run(&mut writer, &mut reader);
 
-        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(output.contains("pruned resource ScratchBucket, condition IsDev is false"));
+    }
+
+    #[rstest::rstest]
+    // the response schema only has a type once its $ref is resolved
+    #[case(false, StatusCode::VALIDATION_ERROR)]
+    #[case(true, StatusCode::SUCCESS)]
+    fn test_resolve_json_refs(#[case] resolve_json_refs: bool, #[case] expected_status_code: i32) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["resolve-json-refs.guard"])
+            .data(vec!["resolve-json-refs/openapi.json"])
+            .resolve_json_refs(resolve_json_refs)
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(expected_status_code, status_code);
     }
 
     #[test]
-    fn test_updated_summary_output() {
+    fn test_this_key_in_filters_and_messages() {
+        let mut reader = Reader::default();
         let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["this-key.guard"])
+            .data(vec!["this-key/template.yaml"])
+            .show_summary(vec!["all"])
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(output.contains("Bucket LogsBucket must be encrypted"));
+        assert!(!output.contains("Bucket ProdBucket must be encrypted"));
+        assert!(output.contains("Queue ScratchQueue must be named"));
+        assert!(output.contains("ProdQueue is a production resource and must be retained"));
+        assert!(!output.contains("%THIS.key"));
+        assert!(output.contains("prod_resources_retained    FAIL"));
+    }
+
+    #[test]
+    fn test_parameters_file_is_not_evaluated_on_its_own() {
         let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
         let status_code = ValidateTestRunner::default()
-            .data(vec!["data-dir"])
-            .rules(vec!["rules-dir"])
+            .rules(vec!["parameters-file.guard"])
+            .data(vec!["parameters-file"])
+            .show_summary(vec!["all"])
             .run(&mut writer, &mut reader);
 
         assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
-        assert_output_from_file_eq!(
-            "resources/validate/output-dir/rules_dir_against_data_dir.out",
-            writer
-        )
+        let output = writer.stripped().unwrap();
+        assert!(output.contains("environment_tag_matches_parameters    FAIL"));
+        assert!(!output.contains("stack.params.json"));
     }
 
     #[rstest::rstest]
     #[case(
-        vec!["db_resource.yaml"],
-        vec!["db_param_port_rule.guard"],
-        vec!["input-parameters-dir/db_params.yaml"],
-        StatusCode::VALIDATION_ERROR
-    )]
-    #[case(
-        vec!["db_resource.yaml"],
-        vec!["db_param_port_rule.guard"],
-        vec!["input-parameters-dir/db_params.yaml", "input-parameters-dir/db_metadata.yaml"],
-        StatusCode::SUCCESS
-    )]
-    #[case(
-        vec!["db_resource.yaml"],
-        vec!["db_param_port_rule.guard"],
-        vec!["input-parameters-dir/"],
+        "data-dir/s3-public-read-prohibited-template-compliant.yaml",
         StatusCode::SUCCESS
     )]
     #[case(
-        vec!["db_resource.yaml"],
-        vec!["db_param_port_rule.guard"],
-        vec!["input-parameters-dir/malformed-template.yaml"],
-        StatusCode::INTERNAL_FAILURE
-    )]
-    #[case(
-        vec!["db_resource.yaml"],
-        vec!["db_param_port_rule.guard"],
-        vec!["input-parameters-dir/blank-template.yaml"],
-        StatusCode::INTERNAL_FAILURE
-    )]
-    #[case(
-        vec!["db_resource.yaml"],
-        vec!["db_param_port_rule.guard"],
-        vec!["input-parameters-dir/blank-template.yaml", "input-parameters-dir/db_params.yaml"],
-        StatusCode::INTERNAL_FAILURE
+        "data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+        StatusCode::VALIDATION_ERROR
     )]
-    fn test_combinations_of_rules_data_and_input_params_files(
-        #[case] data_arg: Vec<&str>,
-        #[case] rules_arg: Vec<&str>,
-        #[case] input_params_arg: Vec<&str>,
-        #[case] expected_status_code: i32,
-    ) {
-        let mut reader = Reader::default();
-        let mut writer = Writer::default();
-        let status_code = ValidateTestRunner::default()
-            .data(data_arg)
-            .rules(rules_arg)
-            .input_parameters(input_params_arg)
-            .run(&mut writer, &mut reader);
-
-        assert_eq!(expected_status_code, status_code);
-    }
-
-    #[test]
-    fn test_rules_with_data_from_stdin_yaml() {
-        let mut reader = utils::get_reader(
-            "resources/validate/data-dir/s3-server-side-encryption-template-compliant.yaml",
-        );
-        let mut writer = Writer::default();
+    fn test_data_from_stdin_with_dash(#[case] template: &str, #[case] expected_status_code: i32) {
+        let mut reader = utils::get_reader(&format!("resources/validate/{template}"));
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
         let status_code = ValidateTestRunner::default()
             .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
+            .data(vec!["-"])
             .run(&mut writer, &mut reader);
 
-        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!(expected_status_code, status_code);
     }
 
     #[test]
-    fn test_rules_with_data_from_stdin_yaml_verbose() {
+    fn test_data_from_stdin_with_dash_alongside_files() {
         let mut reader = utils::get_reader(
-            "resources/validate/data-dir/s3-server-side-encryption-template-compliant.yaml",
+            "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
         );
         let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
         let status_code = ValidateTestRunner::default()
             .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
-            .verbose()
+            .data(vec![
+                "data-dir/s3-public-read-prohibited-template-compliant.yaml",
+                "-",
+            ])
+            .show_summary(vec!["all"])
             .run(&mut writer, &mut reader);
 
-        assert_output_from_file_eq!(
-            "resources/validate/output-dir/payload_verbose_success.out",
-            writer
-        );
-        assert_eq!(StatusCode::SUCCESS, status_code);
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        let output = writer.stripped().unwrap();
+        assert!(output.contains("s3-public-read-prohibited-template-compliant.yaml Status = PASS"));
+        assert!(output.contains("STDIN Status = FAIL"));
     }
 
     #[test]
-    fn test_rules_with_data_from_stdin_fail() {
+    fn test_data_from_stdin_with_dash_only_once() {
         let mut reader = utils::get_reader(
-            "resources/validate/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            "resources/validate/data-dir/s3-public-read-prohibited-template-compliant.yaml",
         );
         let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
         let status_code = ValidateTestRunner::default()
             .rules(vec!["rules-dir/s3_bucket_public_read_prohibited.guard"])
-            .verbose()
+            .data(vec!["-", "-"])
             .run(&mut writer, &mut reader);
 
-        assert_output_from_file_eq!(
-            "resources/validate/output-dir/payload_verbose_non_compliant.out",
-            writer
-        );
-        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
     }
 
     #[test]
@@ -595,6 +1758,37 @@ mod validate_tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_code_of_failures_on_the_first_line() {
+        let payload = r#"{"data": ["{\"Resources\":{\"NewVolume\":{\"Type\":\"AWS::EC2::Volume\",\"Properties\":{\"Size\":500,\"Encrypted\":false}}}}"], "rules" : [ "Resources.*.Properties.Encrypted == true" ]}"#;
+        let mut reader = Reader::new(ReadCursor(Cursor::new(Vec::from(payload.as_bytes()))));
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .payload()
+            .run(&mut writer, &mut reader);
+
+        let result = writer.stripped().unwrap();
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert!(result.contains("Code:"));
+        assert!(result.contains("    1.{\"Resources\""));
+    }
+
+    #[test]
+    fn test_with_payload_named_documents() {
+        let payload = r#"{"data": [{"name": "volumes.json", "content": "{\"Resources\":{\"NewVolume\":{\"Type\":\"AWS::EC2::Volume\",\"Properties\":{\"Encrypted\":false}}}}"}], "rules" : [{"name": "ebs.guard", "content": "rule ebs_encrypted { Resources.*.Properties.Encrypted == true }"}]}"#;
+        let mut reader = Reader::new(ReadCursor(Cursor::new(Vec::from(payload.as_bytes()))));
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .payload()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        let result = writer.stripped().unwrap();
+        assert!(result.contains("volumes.json Status = FAIL"));
+        assert!(result.contains("ebs.guard/ebs_encrypted"));
+        assert!(!result.contains("_STDIN["));
+    }
+
     #[rstest::rstest]
     #[case("yaml")]
     #[case("json")]
@@ -629,6 +1823,88 @@ mod validate_tests {
         );
     }
 
+    #[test]
+    fn test_output_schema() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .output_schema()
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let schema =
+            serde_json::from_str::<serde_json::Value>(&writer.stripped().unwrap()).unwrap();
+        assert_eq!(
+            cfn_guard::OUTPUT_SCHEMA_VERSION,
+            schema["$defs"]["SchemaVersion"]["const"]
+        );
+        assert_eq!("array", schema["type"]);
+    }
+
+    #[test]
+    fn test_output_schema_v2() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner {
+            output_schema: true,
+            output_version: Some("v2"),
+            ..Default::default()
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::SUCCESS, status_code);
+        let schema =
+            serde_json::from_str::<serde_json::Value>(&writer.stripped().unwrap()).unwrap();
+        assert_eq!(
+            cfn_guard::OUTPUT_SCHEMA_VERSION_V2,
+            schema["$defs"]["SchemaVersion"]["const"]
+        );
+    }
+
+    #[test]
+    fn test_structured_output_v2() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner {
+            rules: vec!["/rules-dir"],
+            data: vec!["/data-dir/s3-public-read-prohibited-template-non-compliant.yaml"],
+            output_format: Some("json"),
+            show_summary: vec!["none"],
+            structured: true,
+            output_version: Some("v2"),
+            ..Default::default()
+        }
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        let reports: serde_json::Value = serde_json::from_str(&writer.stripped().unwrap()).unwrap();
+        let report = &reports[0];
+        assert_eq!(
+            cfn_guard::OUTPUT_SCHEMA_VERSION_V2,
+            report["schema_version"]
+        );
+        assert_eq!("FAIL", report["status"]);
+        assert!(report.get("not_compliant").is_none());
+        assert_eq!(serde_json::json!([]), report["suppressed"]);
+        assert_eq!(serde_json::json!([]), report["baselined"]);
+
+        let violation = report["violations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|violation| violation["path"] == "/Resources/MyBucket/Properties")
+            .unwrap();
+        assert_eq!("MyBucket", violation["resource"]);
+        assert_eq!("error", violation["severity"]);
+        assert_eq!(
+            (14, 7),
+            (
+                violation["line"].as_u64().unwrap(),
+                violation["column"].as_u64().unwrap()
+            )
+        );
+    }
+
     #[test]
     fn test_structured_output_payload() {
         let mut reader = Reader::new(ReadCursor(Cursor::new(Vec::from(
@@ -691,18 +1967,160 @@ mod validate_tests {
     #[rstest::rstest]
     #[case("junit")]
     #[case("sarif")]
-    fn test_structured_outputs_fail_without_structured_flag(#[case] output: &str) {
+    fn test_report_outputs_without_structured_flag(#[case] output: &str) {
         let mut reader = Reader::default();
-        let mut writer = Writer::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
         let status_code = ValidateTestRunner::default()
             .rules(vec!["/rules-dir"])
             .data(vec![
                 "/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
             ])
-            .show_summary(vec!["none"])
             .output_format(Option::from(output))
             .run(&mut writer, &mut reader);
 
+        // the console summary goes to stderr, leaving only the report on stdout
+        let writer = if output == "junit" {
+            sanitize_junit_writer(writer)
+        } else {
+            sanitize_sarif_writer(writer)
+        };
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+        assert_output_from_file_eq!(
+            &format!("resources/validate/output-dir/structured.{output}"),
+            writer
+        );
+    }
+
+    #[test]
+    fn test_report_output_file_without_structured_flag() {
+        let output_file = std::env::temp_dir().join(format!(
+            "cfn-guard-validate-report-{}.xml",
+            std::process::id()
+        ));
+        let output_file = output_file.display().to_string();
+
+        let mut reader = Reader::default();
+        let mut writer =
+            Writer::new_with_err(WBVec(vec![]), WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["/rules-dir"])
+            .data(vec![
+                "/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .output_format(Option::from("junit"))
+            .output_file(&output_file)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+
+        let console = writer.err_to_stripped().unwrap();
+        assert!(console.contains("Status = FAIL"));
+        assert!(console.contains("FAILED rules"));
+
+        let report = std::fs::read(&output_file).expect("report was not written");
+        std::fs::remove_file(&output_file).unwrap();
+
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/structured.junit",
+            sanitize_junit_writer(Writer::new(WBVec(report)).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_multiple_output_formats() {
+        let report_path = |extension: &str| {
+            std::env::temp_dir()
+                .join(format!(
+                    "cfn-guard-validate-reports-{}.{extension}",
+                    std::process::id()
+                ))
+                .display()
+                .to_string()
+        };
+        let (junit, json, sarif) = (
+            report_path("xml"),
+            report_path("json"),
+            report_path("sarif"),
+        );
+        let (junit_arg, json_arg, sarif_arg) = (
+            format!("junit={junit}"),
+            format!("json={json}"),
+            format!("sarif={sarif}"),
+        );
+
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["/rules-dir"])
+            .data(vec![
+                "/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .output_format(Option::from("single-line-summary"))
+            .report(&junit_arg)
+            .report(&json_arg)
+            .report(&sarif_arg)
+            .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+
+        // the console output stays on stdout when no report is written there
+        let console = writer.stripped().unwrap();
+        assert!(console.contains("Status = FAIL"));
+        assert!(console.contains("FAILED rules"));
+
+        let read_report = |path: &str| {
+            let report = std::fs::read(path).expect("report was not written");
+            std::fs::remove_file(path).unwrap();
+            Writer::new(WBVec(report)).unwrap()
+        };
+
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/structured.junit",
+            sanitize_junit_writer(read_report(&junit))
+        );
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/structured.json",
+            read_report(&json)
+        );
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/structured.sarif",
+            sanitize_sarif_writer(read_report(&sarif))
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(vec!["json", "yaml"])]
+    #[case(vec!["single-line-summary=report.txt"])]
+    fn test_multiple_output_formats_invalid(#[case] outputs: Vec<&str>) {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner {
+            reports: outputs,
+            ..Default::default()
+        }
+        .rules(vec!["/rules-dir"])
+        .data(vec![
+            "/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+        ])
+        .run(&mut writer, &mut reader);
+
+        assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
+    }
+
+    #[test]
+    fn test_output_file_fails_with_single_line_summary() {
+        let mut reader = Reader::default();
+        let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+        let status_code = ValidateTestRunner::default()
+            .rules(vec!["/rules-dir"])
+            .data(vec![
+                "/data-dir/s3-public-read-prohibited-template-non-compliant.yaml",
+            ])
+            .output_file("report.txt")
+            .run(&mut writer, &mut reader);
+
         assert_eq!(StatusCode::INTERNAL_FAILURE, status_code);
     }
 
@@ -714,8 +2132,12 @@ mod validate_tests {
     #[case("json_parse.guard")]
     #[case("string_manipulation.guard")]
     #[case("url_decode.guard")]
+    #[case("parse_arn.guard")]
+    #[case("env.guard")]
     #[case("join.guard")]
     #[case("count.guard")]
+    #[case("set_operations.guard")]
+    #[case("required_tags.guard")]
     #[case("converters.guard")]
     #[case("complex_rules.guard")]
     fn test_validate_with_fn_expr_success(#[case] rule: &str) {
@@ -750,6 +2172,27 @@ mod validate_tests {
         );
     }
 
+    #[test]
+    fn test_validate_output_order_is_deterministic() {
+        let run = || {
+            let mut reader = Reader::default();
+            let mut writer = Writer::new(WBVec(vec![])).expect("Failed to create writer.");
+            let status_code = ValidateTestRunner::default()
+                .rules(vec!["ordering.guard"])
+                .data(vec!["ordering-template.yaml"])
+                .show_summary(vec!["all"])
+                .run(&mut writer, &mut reader);
+            assert_eq!(StatusCode::VALIDATION_ERROR, status_code);
+            writer
+        };
+
+        assert_eq!(run().stripped().unwrap(), run().stripped().unwrap());
+        assert_output_from_file_eq!(
+            "resources/validate/output-dir/ordering_show_summary_all.out",
+            run()
+        );
+    }
+
     #[test]
     fn test_validate_with_failing_complex_rule() {
         let mut reader = Reader::default();